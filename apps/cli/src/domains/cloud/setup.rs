@@ -56,6 +56,8 @@ async fn add_cloud_interactive(ctx: &Context) -> Result<()> {
 			"Dropbox".to_string(),
 			"Azure Blob Storage".to_string(),
 			"Google Cloud Storage".to_string(),
+			"SFTP server".to_string(),
+			"WebDAV server (Nextcloud, ownCloud, NAS)".to_string(),
 		],
 	)?;
 
@@ -66,6 +68,8 @@ async fn add_cloud_interactive(ctx: &Context) -> Result<()> {
 		3 => add_dropbox_interactive(ctx).await,
 		4 => add_azure_blob_interactive(ctx).await,
 		5 => add_gcs_interactive(ctx).await,
+		6 => add_sftp_interactive(ctx).await,
+		7 => add_webdav_interactive(ctx).await,
 		_ => unreachable!(),
 	}
 }
@@ -342,6 +346,93 @@ async fn add_gcs_interactive(ctx: &Context) -> Result<()> {
 	execute_add_cloud(ctx, input).await
 }
 
+async fn add_sftp_interactive(ctx: &Context) -> Result<()> {
+	let name = text("Volume name (e.g., 'My NAS')", false)?.unwrap();
+	let host = text("Host", false)?.unwrap();
+	let port = text("Port (default: 22)", true)?
+		.map(|p| p.parse::<u16>())
+		.transpose()
+		.map_err(|e| anyhow::anyhow!("Invalid port: {}", e))?;
+	let username = text("Username", false)?.unwrap();
+	let root = text("Root directory (leave empty for home directory)", true)?;
+
+	println!("\nSSH Setup:");
+	println!("  The host key must already be in ~/.ssh/known_hosts");
+	println!("  Leave the key path empty to use ssh-agent and your default identities\n");
+
+	let private_key_path = text("Path to private key", true)?;
+
+	println!("\nSummary:");
+	println!("  Provider: SFTP");
+	println!("  Name:     {}", name);
+	println!("  Host:     {}:{}", host, port.unwrap_or(22));
+	println!("  User:     {}", username);
+	if let Some(ref r) = root {
+		println!("  Root:     {}", r);
+	}
+	println!();
+
+	confirm_or_abort("Add this cloud volume?", false)?;
+
+	let input = VolumeAddCloudInput {
+		service: CloudServiceType::Sftp,
+		display_name: name.clone(),
+		config: CloudStorageConfig::Sftp {
+			host,
+			port,
+			username,
+			private_key_path,
+			root,
+		},
+	};
+
+	execute_add_cloud(ctx, input).await
+}
+
+async fn add_webdav_interactive(ctx: &Context) -> Result<()> {
+	let name = text("Volume name (e.g., 'My Nextcloud')", false)?.unwrap();
+	let endpoint = text(
+		"Server URL (e.g., https://cloud.example.com/remote.php/dav/files/me)",
+		false,
+	)?
+	.unwrap();
+	let root = text("Root folder path (leave empty for entire share)", true)?;
+
+	println!("\nCredentials will be stored securely in your system keyring");
+	println!("  Leave empty for anonymous access\n");
+
+	let username = text("Username", true)?;
+	let secret = if username.is_some() {
+		password("Password", false)?
+	} else {
+		None
+	};
+
+	println!("\nSummary:");
+	println!("  Provider: WebDAV");
+	println!("  Name:     {}", name);
+	println!("  Endpoint: {}", endpoint);
+	if let Some(ref r) = root {
+		println!("  Root:     {}", r);
+	}
+	println!();
+
+	confirm_or_abort("Add this cloud volume?", false)?;
+
+	let input = VolumeAddCloudInput {
+		service: CloudServiceType::WebDav,
+		display_name: name.clone(),
+		config: CloudStorageConfig::WebDav {
+			endpoint,
+			root,
+			username,
+			password: secret,
+		},
+	};
+
+	execute_add_cloud(ctx, input).await
+}
+
 async fn execute_add_cloud(ctx: &Context, input: VolumeAddCloudInput) -> Result<()> {
 	print!("Connecting to cloud storage... ");
 	std::io::Write::flush(&mut std::io::stdout())?;
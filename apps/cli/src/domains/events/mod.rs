@@ -371,6 +371,21 @@ fn summarize_event(event: &Event) -> String {
 			format!("Resource deleted: {} ({})", resource_type, resource_id)
 		}
 
+		// Saved search events
+		Event::SavedSearchMembershipChanged {
+			saved_search_id,
+			added,
+			removed,
+			..
+		} => {
+			format!(
+				"Saved search {} changed: {} added, {} removed",
+				saved_search_id,
+				added.len(),
+				removed.len()
+			)
+		}
+
 		// Legacy location events
 		Event::LocationAdded {
 			location_id, path, ..
@@ -462,6 +477,20 @@ fn summarize_event(event: &Event) -> String {
 		// Config events
 		Event::ConfigChanged { .. } => "Configuration changed".to_string(),
 
+		// Extension events
+		Event::ExtensionNotification {
+			extension_id,
+			title,
+			message,
+		} => match title {
+			Some(title) => format!("[{}] {}: {}", extension_id, title, message),
+			None => format!("[{}] {}", extension_id, message),
+		},
+		Event::ExtensionSuspended {
+			extension_id,
+			reason,
+		} => format!("Extension {} suspended: {}", extension_id, reason),
+
 		// Custom events
 		Event::Custom { event_type, data } => {
 			format!("Custom event: {} - {:?}", event_type, data)
@@ -191,6 +191,10 @@ async fn check_for_simple_conflicts(
 			// Sidecar paths cannot be destinations for copy operations
 			return Ok(false);
 		}
+		SdPath::Archive { .. } => {
+			// Archives are read-only, so they cannot be destinations for copy operations
+			return Ok(false);
+		}
 	};
 
 	// Resolve the actual destination file path using the same logic as the core copy job
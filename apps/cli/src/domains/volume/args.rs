@@ -32,11 +32,11 @@ pub struct VolumeAddCloudArgs {
 	#[arg(long)]
 	pub secret_access_key: Option<String>,
 
-	/// Custom endpoint (S3, Azure, GCS)
+	/// Custom endpoint (S3, Azure, GCS), or server URL (WebDAV)
 	#[arg(long)]
 	pub endpoint: Option<String>,
 
-	/// Root folder path or ID (Google Drive, OneDrive, Dropbox, GCS)
+	/// Root folder path or ID (Google Drive, OneDrive, Dropbox, GCS, SFTP, WebDAV)
 	#[arg(long)]
 	pub root: Option<String>,
 
@@ -71,6 +71,26 @@ pub struct VolumeAddCloudArgs {
 	/// Path to service account JSON file (GCS)
 	#[arg(long)]
 	pub service_account: Option<String>,
+
+	/// Server hostname (SFTP)
+	#[arg(long)]
+	pub host: Option<String>,
+
+	/// Server port (SFTP, defaults to 22)
+	#[arg(long)]
+	pub port: Option<u16>,
+
+	/// Username (SFTP, WebDAV)
+	#[arg(long)]
+	pub username: Option<String>,
+
+	/// Password (WebDAV)
+	#[arg(long)]
+	pub password: Option<String>,
+
+	/// Path to SSH private key (SFTP, defaults to ssh-agent)
+	#[arg(long)]
+	pub private_key: Option<String>,
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
@@ -84,6 +104,9 @@ pub enum CloudServiceArg {
 	BackblazeB2,
 	Wasabi,
 	DigitalOceanSpaces,
+	Sftp,
+	#[value(name = "webdav")]
+	WebDav,
 }
 
 impl From<CloudServiceArg> for CloudServiceType {
@@ -98,6 +121,8 @@ impl From<CloudServiceArg> for CloudServiceType {
 			CloudServiceArg::BackblazeB2 => CloudServiceType::BackblazeB2,
 			CloudServiceArg::Wasabi => CloudServiceType::Wasabi,
 			CloudServiceArg::DigitalOceanSpaces => CloudServiceType::DigitalOceanSpaces,
+			CloudServiceArg::Sftp => CloudServiceType::Sftp,
+			CloudServiceArg::WebDav => CloudServiceType::WebDav,
 		}
 	}
 }
@@ -234,6 +259,30 @@ impl VolumeAddCloudArgs {
 					credential,
 				}
 			}
+			CloudServiceArg::Sftp => {
+				let host = self.host.ok_or("--host is required for SFTP")?;
+				let username = self.username.ok_or("--username is required for SFTP")?;
+
+				CloudStorageConfig::Sftp {
+					host,
+					port: self.port,
+					username,
+					private_key_path: self.private_key,
+					root: self.root,
+				}
+			}
+			CloudServiceArg::WebDav => {
+				let endpoint = self
+					.endpoint
+					.ok_or("--endpoint is required for WebDAV")?;
+
+				CloudStorageConfig::WebDav {
+					endpoint,
+					root: self.root,
+					username: self.username,
+					password: self.password,
+				}
+			}
 		};
 
 		Ok(VolumeAddCloudInput {
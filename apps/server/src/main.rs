@@ -18,6 +18,8 @@ use tokio::{
 };
 use tracing::{info, warn};
 
+mod metrics;
+
 #[derive(Clone)]
 struct AppState {
 	auth: HashMap<String, SecStr>,
@@ -210,6 +212,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 	let app = Router::new()
 		.route("/health", get(health))
 		.route("/rpc", post(daemon_rpc))
+		.route("/metrics", get(metrics::metrics))
 		.route(
 			"/",
 			get(|| async { "Spacedrive Server - RPC only (no web UI)" }),
@@ -232,6 +235,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 		args.port
 	);
 	info!("RPC endpoint available at /rpc");
+	info!("Metrics endpoint available at /metrics");
 
 	// Setup graceful shutdown
 	let shutdown_signal = shutdown_signal(daemon_handle);
@@ -0,0 +1,311 @@
+//! `/metrics` endpoint - daemon state in OpenMetrics text format
+
+use crate::AppState;
+use axum::{
+	extract::State,
+	http::{header, StatusCode},
+	response::{IntoResponse, Response},
+};
+use sd_core::{
+	infra::daemon::types::{DaemonRequest, DaemonResponse},
+	ops::{
+		core::metrics::{CoreMetrics, VolumeMetrics},
+		indexing::metrics::IndexingTotalsSnapshot,
+	},
+};
+use std::fmt::{Display, Write};
+use tokio::{
+	io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+	net::TcpStream,
+};
+
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Serve a metrics snapshot collected by the daemon
+pub async fn metrics(State(state): State<AppState>) -> Response {
+	match fetch_metrics(&state.socket_addr).await {
+		Ok(metrics) => ([(header::CONTENT_TYPE, CONTENT_TYPE)], render(&metrics)).into_response(),
+		Err(e) => (StatusCode::SERVICE_UNAVAILABLE, e).into_response(),
+	}
+}
+
+async fn fetch_metrics(socket_addr: &str) -> Result<CoreMetrics, String> {
+	let mut stream = TcpStream::connect(socket_addr)
+		.await
+		.map_err(|e| format!("Daemon not available: {}", e))?;
+
+	let request_line = serde_json::to_string(&DaemonRequest::Metrics)
+		.map_err(|e| format!("Invalid request: {}", e))?;
+	stream
+		.write_all(format!("{}\n", request_line).as_bytes())
+		.await
+		.map_err(|e| format!("Write failed: {}", e))?;
+
+	let mut reader = BufReader::new(stream);
+	let mut response_line = String::new();
+	reader
+		.read_line(&mut response_line)
+		.await
+		.map_err(|e| format!("Read failed: {}", e))?;
+
+	match serde_json::from_str::<DaemonResponse>(&response_line) {
+		Ok(DaemonResponse::JsonOk(json)) => {
+			serde_json::from_value(json).map_err(|e| format!("Invalid metrics: {}", e))
+		}
+		Ok(DaemonResponse::Error(e)) => Err(e.to_string()),
+		Ok(other) => Err(format!("Unexpected daemon response: {:?}", other)),
+		Err(e) => Err(format!("Invalid response: {}", e)),
+	}
+}
+
+/// Render a snapshot in the OpenMetrics text exposition format
+pub fn render(metrics: &CoreMetrics) -> String {
+	let mut out = OpenMetrics::default();
+
+	out.family(
+		"spacedrive_jobs",
+		"gauge",
+		"Jobs known to the job manager by type and status",
+	);
+	for job in &metrics.jobs {
+		let library = job.library_id.to_string();
+		let status = job.status.to_string();
+		out.sample(
+			"spacedrive_jobs",
+			&[
+				("library", &library),
+				("type", &job.job_type),
+				("status", &status),
+			],
+			job.count,
+		);
+	}
+
+	let indexing_counters: [(&str, &str, fn(&IndexingTotalsSnapshot) -> u64); 5] = [
+		("spacedrive_indexer_runs", "Indexer jobs completed", |i| {
+			i.runs
+		}),
+		("spacedrive_indexer_files", "Files indexed", |i| i.files),
+		(
+			"spacedrive_indexer_directories",
+			"Directories indexed",
+			|i| i.dirs,
+		),
+		("spacedrive_indexer_bytes", "Bytes indexed", |i| i.bytes),
+		(
+			"spacedrive_indexer_errors",
+			"Errors reported by indexer jobs",
+			|i| i.errors,
+		),
+	];
+	for (name, help, value) in indexing_counters {
+		out.family(name, "counter", help);
+		for library in &metrics.libraries {
+			let id = library.library_id.to_string();
+			out.sample(
+				&format!("{}_total", name),
+				&[("library", &id), ("library_name", &library.name)],
+				value(&library.indexing),
+			);
+		}
+	}
+
+	out.family(
+		"spacedrive_indexer_duration_seconds",
+		"counter",
+		"Time spent in indexer jobs",
+	);
+	for library in &metrics.libraries {
+		let id = library.library_id.to_string();
+		out.sample(
+			"spacedrive_indexer_duration_seconds_total",
+			&[("library", &id), ("library_name", &library.name)],
+			library.indexing.duration_ms as f64 / 1000.0,
+		);
+	}
+
+	out.family(
+		"spacedrive_indexer_last_files_per_second",
+		"gauge",
+		"Throughput of the most recent indexer job",
+	);
+	for library in &metrics.libraries {
+		let id = library.library_id.to_string();
+		out.sample(
+			"spacedrive_indexer_last_files_per_second",
+			&[("library", &id), ("library_name", &library.name)],
+			library.indexing.last_files_per_second,
+		);
+	}
+
+	out.family(
+		"spacedrive_sync_watermark_lag_seconds",
+		"gauge",
+		"How far this device trails a sync peer",
+	);
+	for library in &metrics.libraries {
+		let id = library.library_id.to_string();
+		for peer in &library.sync_peers {
+			if let Some(lag) = peer.watermark_lag_ms {
+				let peer_id = peer.peer_id.to_string();
+				out.sample(
+					"spacedrive_sync_watermark_lag_seconds",
+					&[("library", &id), ("peer", &peer_id)],
+					lag as f64 / 1000.0,
+				);
+			}
+		}
+	}
+
+	out.family(
+		"spacedrive_sync_last_sync_timestamp_seconds",
+		"gauge",
+		"When changes were last exchanged with a sync peer",
+	);
+	for library in &metrics.libraries {
+		let id = library.library_id.to_string();
+		for peer in &library.sync_peers {
+			if let Some(last_sync) = peer.last_sync_at {
+				let peer_id = peer.peer_id.to_string();
+				out.sample(
+					"spacedrive_sync_last_sync_timestamp_seconds",
+					&[("library", &id), ("peer", &peer_id)],
+					last_sync.timestamp(),
+				);
+			}
+		}
+	}
+
+	let volume_gauges: [(&str, &str, fn(&VolumeMetrics) -> u64); 2] = [
+		(
+			"spacedrive_volume_capacity_bytes",
+			"Total capacity of a volume",
+			|v| v.total_capacity,
+		),
+		(
+			"spacedrive_volume_available_bytes",
+			"Free space on a volume",
+			|v| v.available_space,
+		),
+	];
+	for (name, help, value) in volume_gauges {
+		out.family(name, "gauge", help);
+		for volume in metrics.volumes.iter().filter(|v| v.is_mounted) {
+			let mount_point = volume.mount_point.display().to_string();
+			out.sample(
+				name,
+				&[
+					("volume", &volume.fingerprint),
+					("name", &volume.name),
+					("mount_point", &mount_point),
+				],
+				value(volume),
+			);
+		}
+	}
+
+	out.family(
+		"spacedrive_event_bus_subscribers",
+		"gauge",
+		"Subscribers on the core event bus",
+	);
+	out.sample(
+		"spacedrive_event_bus_subscribers",
+		&[],
+		metrics.connections.event_bus_subscribers,
+	);
+
+	if let Some(connections) = metrics.connections.rpc_connections {
+		out.family(
+			"spacedrive_rpc_connections",
+			"gauge",
+			"Open daemon RPC connections",
+		);
+		out.sample("spacedrive_rpc_connections", &[], connections);
+	}
+	if let Some(streams) = metrics.connections.rpc_event_streams {
+		out.family(
+			"spacedrive_rpc_event_streams",
+			"gauge",
+			"Daemon RPC connections streaming events",
+		);
+		out.sample("spacedrive_rpc_event_streams", &[], streams);
+	}
+
+	out.finish()
+}
+
+#[derive(Default)]
+struct OpenMetrics {
+	buf: String,
+}
+
+impl OpenMetrics {
+	fn family(&mut self, name: &str, kind: &str, help: &str) {
+		let _ = writeln!(self.buf, "# TYPE {} {}", name, kind);
+		let _ = writeln!(self.buf, "# HELP {} {}", name, help);
+	}
+
+	fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+		self.buf.push_str(name);
+		if !labels.is_empty() {
+			let labels = labels
+				.iter()
+				.map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+				.collect::<Vec<_>>()
+				.join(",");
+			let _ = write!(self.buf, "{{{}}}", labels);
+		}
+		let _ = writeln!(self.buf, " {}", value);
+	}
+
+	fn finish(mut self) -> String {
+		self.buf.push_str("# EOF\n");
+		self.buf
+	}
+}
+
+fn escape_label(value: &str) -> String {
+	value
+		.replace('\\', "\\\\")
+		.replace('"', "\\\"")
+		.replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sd_core::ops::core::metrics::ConnectionMetrics;
+
+	#[test]
+	fn test_render_openmetrics() {
+		let metrics = CoreMetrics {
+			collected_at: Default::default(),
+			jobs: Vec::new(),
+			libraries: Vec::new(),
+			volumes: vec![VolumeMetrics {
+				fingerprint: "abc".to_string(),
+				name: "Media \"Backup\"".to_string(),
+				mount_point: "/mnt/media".into(),
+				is_mounted: true,
+				total_capacity: 1000,
+				available_space: 250,
+			}],
+			connections: ConnectionMetrics {
+				event_bus_subscribers: 3,
+				rpc_connections: Some(2),
+				rpc_event_streams: None,
+			},
+		};
+
+		let text = render(&metrics);
+		assert!(text.contains("# TYPE spacedrive_volume_capacity_bytes gauge\n"));
+		assert!(text.contains(
+			"spacedrive_volume_available_bytes{volume=\"abc\",name=\"Media \\\"Backup\\\"\",mount_point=\"/mnt/media\"} 250\n"
+		));
+		assert!(text.contains("spacedrive_event_bus_subscribers 3\n"));
+		assert!(text.contains("spacedrive_rpc_connections 2\n"));
+		assert!(!text.contains("spacedrive_rpc_event_streams"));
+		assert!(text.ends_with("# EOF\n"));
+	}
+}
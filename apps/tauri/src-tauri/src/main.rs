@@ -67,6 +67,8 @@ fn get_default_event_subscription() -> Vec<&'static str> {
 		"ResourceChanged",
 		"ResourceChangedBatch",
 		"ResourceDeleted",
+		// Saved search events
+		"SavedSearchMembershipChanged",
 		// Legacy compatibility
 		"LocationAdded",
 		"LocationRemoved",
@@ -74,6 +76,9 @@ fn get_default_event_subscription() -> Vec<&'static str> {
 		"ThumbnailsGenerated",
 		"FileOperationCompleted",
 		"FilesModified",
+		// Extension events
+		"ExtensionNotification",
+		"ExtensionSuspended",
 	]
 }
 
@@ -4,9 +4,12 @@ use super::{default_data_dir, Preferences};
 use crate::config::migration::Migrate;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use tracing::{info, warn};
+use uuid::Uuid;
 
 /// Main application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +44,10 @@ pub struct AppConfig {
 	/// Proxy pairing configuration
 	#[serde(default)]
 	pub proxy_pairing: ProxyPairingConfig,
+
+	/// Peer file transfer configuration
+	#[serde(default)]
+	pub file_transfer: FileTransferConfig,
 }
 
 /// Configuration for core services
@@ -165,6 +172,69 @@ impl Default for ProxyPairingConfig {
 	}
 }
 
+/// Peer file transfer configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct FileTransferConfig {
+	/// Compress chunks with zstd when the other device supports it
+	#[serde(default = "default_true")]
+	pub compression: bool,
+	/// Limits on the bandwidth used by outgoing transfers
+	#[serde(default)]
+	pub bandwidth: BandwidthConfig,
+}
+
+impl Default for FileTransferConfig {
+	fn default() -> Self {
+		Self {
+			compression: true,
+			bandwidth: BandwidthConfig::default(),
+		}
+	}
+}
+
+/// Bandwidth limits for outgoing transfers, in megabits per second (None = unlimited)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct BandwidthConfig {
+	/// Limit shared by all transfers
+	#[serde(default)]
+	pub global_limit_mbps: Option<u32>,
+	/// Limit for each individual transfer
+	#[serde(default)]
+	pub per_transfer_limit_mbps: Option<u32>,
+	/// Limits shared by all transfers with a specific device
+	#[serde(default)]
+	pub device_limits_mbps: HashMap<Uuid, u32>,
+	/// Time windows with their own limits, the first active one replaces the limits above
+	#[serde(default)]
+	pub schedule: Vec<BandwidthWindow>,
+}
+
+/// Limits that apply during a recurring time window, e.g. office hours
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct BandwidthWindow {
+	/// Days the window applies on
+	pub days: BandwidthDays,
+	/// Local start time, as "HH:MM"
+	pub start: String,
+	/// Local end time, as "HH:MM"; windows ending before they start run past midnight
+	pub end: String,
+	/// Limit shared by all transfers during the window
+	#[serde(default)]
+	pub global_limit_mbps: Option<u32>,
+	/// Limit for each individual transfer during the window
+	#[serde(default)]
+	pub per_transfer_limit_mbps: Option<u32>,
+}
+
+/// Days a bandwidth window applies on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum BandwidthDays {
+	Daily,
+	Weekdays,
+	Weekends,
+}
+
 impl Default for LoggingConfig {
 	fn default() -> Self {
 		Self {
@@ -242,6 +312,7 @@ impl AppConfig {
 			services: ServiceConfig::default(),
 			logging: LoggingConfig::default(),
 			proxy_pairing: ProxyPairingConfig::default(),
+			file_transfer: FileTransferConfig::default(),
 		}
 	}
 
@@ -305,7 +376,7 @@ impl Migrate for AppConfig {
 	}
 
 	fn target_version() -> u32 {
-		5 // Added proxy pairing configuration
+		6 // Added file transfer configuration
 	}
 
 	fn migrate(&mut self) -> Result<()> {
@@ -337,9 +408,15 @@ impl Migrate for AppConfig {
 				// Migration from v4 to v5: Add proxy pairing configuration
 				self.proxy_pairing = ProxyPairingConfig::default();
 				self.version = 5;
+				self.migrate()
+			}
+			5 => {
+				// Migration from v5 to v6: Add file transfer configuration
+				self.file_transfer = FileTransferConfig::default();
+				self.version = 6;
 				Ok(())
 			}
-			5 => Ok(()), // Already at target version
+			6 => Ok(()), // Already at target version
 			v => Err(anyhow!("Unknown config version: {}", v)),
 		}
 	}
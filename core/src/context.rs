@@ -2,10 +2,10 @@
 
 use crate::{
 	config::JobLoggingConfig,
-	crypto::key_manager::KeyManager,
+	crypto::{key_manager::KeyManager, vault::VaultManager},
 	device::DeviceManager,
 	filetype::FileTypeRegistry,
-	infra::action::manager::ActionManager,
+	infra::action::{journal::UndoJournal, manager::ActionManager},
 	infra::event::EventBus,
 	infra::sync::TransactionManager,
 	library::LibraryManager,
@@ -26,9 +26,13 @@ pub struct CoreContext {
 	pub library_manager: Arc<RwLock<Option<Arc<LibraryManager>>>>,
 	pub volume_manager: Arc<VolumeManager>,
 	pub key_manager: Arc<KeyManager>,
+	// Session unlock state of encrypted vault locations
+	pub vault_manager: Arc<VaultManager>,
 	// This is wrapped in an RwLock to allow it to be set after initialization
 	pub sidecar_manager: Arc<RwLock<Option<Arc<SidecarManager>>>>,
 	pub action_manager: Arc<RwLock<Option<Arc<ActionManager>>>>,
+	// Per-session undo/redo history of reversible actions
+	pub undo_journal: Arc<UndoJournal>,
 	pub networking: Arc<RwLock<Option<Arc<NetworkingService>>>>,
 	#[cfg(feature = "wasm")]
 	pub plugin_manager: Arc<RwLock<Option<Arc<RwLock<crate::infra::extension::PluginManager>>>>>,
@@ -61,9 +65,11 @@ impl CoreContext {
 			device_manager,
 			library_manager: Arc::new(RwLock::new(library_manager)),
 			volume_manager,
+			vault_manager: Arc::new(VaultManager::new(key_manager.clone())),
 			key_manager,
 			sidecar_manager: Arc::new(RwLock::new(None)),
 			action_manager: Arc::new(RwLock::new(None)),
+			undo_journal: Arc::new(UndoJournal::new()),
 			networking: Arc::new(RwLock::new(None)),
 			#[cfg(feature = "wasm")]
 			plugin_manager: Arc::new(RwLock::new(None)),
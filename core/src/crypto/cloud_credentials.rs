@@ -285,6 +285,16 @@ pub enum CredentialData {
 
 	/// Connection string (Azure, etc.)
 	ConnectionString(String),
+
+	/// Username + password (WebDAV)
+	Basic { username: String, password: String },
+
+	/// SSH login (SFTP). The private key stays on disk and is referenced by path;
+	/// without one the ssh-agent and default identities are used.
+	SshKey {
+		username: String,
+		private_key_path: Option<String>,
+	},
 }
 
 impl CloudCredential {
@@ -339,6 +349,37 @@ impl CloudCredential {
 		}
 	}
 
+	/// Create a new username/password credential
+	pub fn new_basic(
+		service: crate::volume::CloudServiceType,
+		username: String,
+		password: String,
+	) -> Self {
+		Self {
+			service,
+			data: CredentialData::Basic { username, password },
+			created_at: chrono::Utc::now(),
+			expires_at: None,
+		}
+	}
+
+	/// Create a new SSH key credential
+	pub fn new_ssh_key(
+		service: crate::volume::CloudServiceType,
+		username: String,
+		private_key_path: Option<String>,
+	) -> Self {
+		Self {
+			service,
+			data: CredentialData::SshKey {
+				username,
+				private_key_path,
+			},
+			created_at: chrono::Utc::now(),
+			expires_at: None,
+		}
+	}
+
 	/// Check if this credential is expired
 	pub fn is_expired(&self) -> bool {
 		if let Some(expires_at) = self.expires_at {
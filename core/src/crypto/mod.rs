@@ -1,2 +1,3 @@
 pub mod cloud_credentials;
 pub mod key_manager;
+pub mod vault;
@@ -0,0 +1,210 @@
+//! On-disk format of encrypted vault objects
+//!
+//! ```text
+//! magic "SDVAULT\x01" | object id (16 bytes) | frame*
+//! frame = sealed length (u32 LE) | nonce (24 bytes) | XChaCha20-Poly1305 ciphertext
+//! ```
+//!
+//! Each frame seals up to 1 MiB of plaintext prefixed with its index (u64 LE) and a
+//! final-frame flag, so frames can't be reordered, dropped or truncated without the
+//! decryption failing.
+
+use super::VaultError;
+use sd_crypto::{
+	cloud::{OneShotDecryption, OneShotEncryption, SecretKey},
+	primitives::EncryptedBlock,
+	CryptoRng,
+};
+use std::io::{Cursor, Read, Write};
+use uuid::Uuid;
+
+const MAGIC: &[u8; 8] = b"SDVAULT\x01";
+
+/// Index and final-frame flag prepended to every frame's plaintext
+const FRAME_PREFIX_LEN: usize = 9;
+
+/// Plaintext bytes carried by each frame
+pub const FRAME_DATA_LEN: usize = EncryptedBlock::PLAIN_TEXT_SIZE - FRAME_PREFIX_LEN;
+
+/// Upper bound on a sealed frame, used to reject corrupt length fields before allocating
+const MAX_SEALED_LEN: usize = EncryptedBlock::CIPHER_TEXT_SIZE + 24;
+
+/// Encrypt everything `reader` yields into `writer`, returning the plaintext size
+pub fn encrypt_stream(
+	key: &SecretKey,
+	object_id: Uuid,
+	mut reader: impl Read,
+	mut writer: impl Write,
+) -> Result<u64, VaultError> {
+	let mut rng = CryptoRng::new()?;
+
+	writer.write_all(MAGIC)?;
+	writer.write_all(object_id.as_bytes())?;
+
+	let mut total = 0u64;
+	let mut index = 0u64;
+	let mut current = read_chunk(&mut reader)?;
+
+	loop {
+		// Look ahead so the last frame can be flagged, even when the input is empty
+		let next = if current.len() == FRAME_DATA_LEN {
+			read_chunk(&mut reader)?
+		} else {
+			Vec::new()
+		};
+		let is_last = next.is_empty();
+
+		let mut plaintext = Vec::with_capacity(FRAME_PREFIX_LEN + current.len());
+		plaintext.extend_from_slice(&index.to_le_bytes());
+		plaintext.push(u8::from(is_last));
+		plaintext.extend_from_slice(&current);
+
+		let EncryptedBlock { nonce, cipher_text } = key.encrypt(&plaintext, &mut rng)?;
+		let sealed_len = (nonce.len() + cipher_text.len()) as u32;
+		writer.write_all(&sealed_len.to_le_bytes())?;
+		writer.write_all(nonce.as_slice())?;
+		writer.write_all(&cipher_text)?;
+
+		total += current.len() as u64;
+		if is_last {
+			break;
+		}
+
+		index += 1;
+		current = next;
+	}
+
+	writer.flush()?;
+	Ok(total)
+}
+
+/// Decrypt an object written by [`encrypt_stream`] into `writer`, returning the plaintext size
+pub fn decrypt_stream(
+	key: &SecretKey,
+	object_id: Uuid,
+	mut reader: impl Read,
+	mut writer: impl Write,
+) -> Result<u64, VaultError> {
+	let mut header = [0u8; 24];
+	reader
+		.read_exact(&mut header)
+		.map_err(|_| VaultError::Corrupt("missing header".to_string()))?;
+	if &header[..8] != MAGIC {
+		return Err(VaultError::Corrupt("not a vault object".to_string()));
+	}
+	if header[8..] != *object_id.as_bytes() {
+		return Err(VaultError::Corrupt("object id mismatch".to_string()));
+	}
+
+	let mut total = 0u64;
+	let mut expected_index = 0u64;
+
+	loop {
+		let mut len_bytes = [0u8; 4];
+		reader
+			.read_exact(&mut len_bytes)
+			.map_err(|_| VaultError::Corrupt("object is truncated".to_string()))?;
+		let sealed_len = u32::from_le_bytes(len_bytes) as usize;
+		if sealed_len > MAX_SEALED_LEN {
+			return Err(VaultError::Corrupt("frame is too large".to_string()));
+		}
+
+		let mut sealed = vec![0u8; sealed_len];
+		reader
+			.read_exact(&mut sealed)
+			.map_err(|_| VaultError::Corrupt("object is truncated".to_string()))?;
+		if sealed_len < 24 + FRAME_PREFIX_LEN {
+			return Err(VaultError::Corrupt("frame is too small".to_string()));
+		}
+
+		let plaintext = key.decrypt(sealed.as_slice().into())?;
+		if plaintext.len() < FRAME_PREFIX_LEN {
+			return Err(VaultError::Corrupt("frame is too small".to_string()));
+		}
+
+		let index = u64::from_le_bytes(plaintext[..8].try_into().expect("slice of 8 bytes"));
+		if index != expected_index {
+			return Err(VaultError::Corrupt("frames are out of order".to_string()));
+		}
+
+		let data = &plaintext[FRAME_PREFIX_LEN..];
+		writer.write_all(data)?;
+		total += data.len() as u64;
+
+		if plaintext[8] == 1 {
+			break;
+		}
+		expected_index += 1;
+	}
+
+	// Anything after the final frame was appended by someone else
+	if reader.read(&mut [0u8; 1])? != 0 {
+		return Err(VaultError::Corrupt(
+			"trailing data after final frame".to_string(),
+		));
+	}
+
+	writer.flush()?;
+	Ok(total)
+}
+
+/// Encrypt an in-memory buffer
+pub fn encrypt_bytes(key: &SecretKey, object_id: Uuid, data: &[u8]) -> Result<Vec<u8>, VaultError> {
+	let mut out = Vec::with_capacity(data.len() + 64);
+	encrypt_stream(key, object_id, data, &mut out)?;
+	Ok(out)
+}
+
+/// Decrypt an in-memory buffer written by [`encrypt_bytes`]
+pub fn decrypt_bytes(key: &SecretKey, object_id: Uuid, data: &[u8]) -> Result<Vec<u8>, VaultError> {
+	let mut out = Vec::with_capacity(data.len());
+	decrypt_stream(key, object_id, Cursor::new(data), &mut out)?;
+	Ok(out)
+}
+
+fn read_chunk(reader: &mut impl Read) -> Result<Vec<u8>, VaultError> {
+	let mut chunk = Vec::with_capacity(FRAME_DATA_LEN);
+	reader.take(FRAME_DATA_LEN as u64).read_to_end(&mut chunk)?;
+	Ok(chunk)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn key() -> SecretKey {
+		SecretKey::generate(&mut CryptoRng::new().unwrap())
+	}
+
+	#[test]
+	fn test_round_trip_across_frames() {
+		let key = key();
+		let id = Uuid::new_v4();
+
+		for len in [0, 17, FRAME_DATA_LEN, FRAME_DATA_LEN * 2 + 5] {
+			let data: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+			let sealed = encrypt_bytes(&key, id, &data).unwrap();
+			assert_eq!(decrypt_bytes(&key, id, &sealed).unwrap(), data);
+		}
+	}
+
+	#[test]
+	fn test_rejects_tampering() {
+		let key = key();
+		let id = Uuid::new_v4();
+		let data = vec![7u8; FRAME_DATA_LEN + 100];
+		let sealed = encrypt_bytes(&key, id, &data).unwrap();
+
+		// Wrong key, wrong object and a flipped ciphertext bit all fail
+		assert!(decrypt_bytes(&self::key(), id, &sealed).is_err());
+		assert!(decrypt_bytes(&key, Uuid::new_v4(), &sealed).is_err());
+		let mut flipped = sealed.clone();
+		*flipped.last_mut().unwrap() ^= 1;
+		assert!(decrypt_bytes(&key, id, &flipped).is_err());
+
+		// Dropping the final frame is detected as truncation
+		let first_frame_len = u32::from_le_bytes(sealed[24..28].try_into().unwrap()) as usize;
+		let truncated = &sealed[..24 + 4 + first_frame_len];
+		assert!(decrypt_bytes(&key, id, truncated).is_err());
+	}
+}
@@ -0,0 +1,138 @@
+//! Vault key hierarchy
+//!
+//! Each library has one random vault master key. It is wrapped with a key derived from
+//! the user's vault password (Argon2id) and stored in the device's [`KeyManager`]. Every
+//! vault object is sealed with its own key, derived from the master key and the object's
+//! id, so no two files share a key. An object's thumbnail is sealed with a separate key
+//! derived in its own context, so it never reuses the key of the contents it depicts.
+//!
+//! [`KeyManager`]: crate::crypto::key_manager::KeyManager
+
+use super::VaultError;
+use argon2::Argon2;
+use sd_crypto::{
+	cloud::{OneShotDecryption, OneShotEncryption, SecretKey},
+	CryptoRng, Protected,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const FILE_KEY_CONTEXT: &str = "spacedrive 2024 vault object key";
+const MANIFEST_KEY_CONTEXT: &str = "spacedrive 2024 vault manifest key";
+const THUMBNAIL_KEY_CONTEXT: &str = "spacedrive 2024 vault thumbnail key";
+
+/// Unwrapped vault master key for a library
+///
+/// Only ever held in memory while the library's vaults are unlocked. The inner key is
+/// zeroized on drop.
+#[derive(Clone)]
+pub struct VaultKey(SecretKey);
+
+impl VaultKey {
+	/// Key sealing the object with the given id
+	pub fn object_key(&self, object_id: Uuid) -> SecretKey {
+		self.derive(FILE_KEY_CONTEXT, object_id)
+	}
+
+	/// Key sealing the thumbnail of the object with the given id
+	pub fn thumbnail_key(&self, object_id: Uuid) -> SecretKey {
+		self.derive(THUMBNAIL_KEY_CONTEXT, object_id)
+	}
+
+	/// Key sealing the manifest of the vault at the given location
+	pub fn manifest_key(&self, location_id: Uuid) -> SecretKey {
+		self.derive(MANIFEST_KEY_CONTEXT, location_id)
+	}
+
+	fn derive(&self, context: &str, id: Uuid) -> SecretKey {
+		let mut material = Vec::with_capacity(48);
+		material.extend_from_slice(self.0.as_ref());
+		material.extend_from_slice(id.as_bytes());
+		let material = Protected::new(material);
+
+		let derived = Protected::new(blake3::derive_key(context, material.expose()));
+		SecretKey::try_from(derived.expose().as_slice()).expect("derived keys are 32 bytes")
+	}
+}
+
+impl std::fmt::Debug for VaultKey {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("VaultKey(<REDACTED>)")
+	}
+}
+
+/// The vault master key sealed under a password-derived key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WrappedMasterKey {
+	pub salt: Vec<u8>,
+	/// Nonce followed by the sealed master key
+	pub sealed_key: Vec<u8>,
+}
+
+impl WrappedMasterKey {
+	/// Generate a new master key and wrap it with `password`
+	pub fn generate(password: &Protected<String>) -> Result<(Self, VaultKey), VaultError> {
+		let mut rng = CryptoRng::new()?;
+		let master = SecretKey::generate(&mut rng);
+		let salt = rng.generate_vec(16);
+
+		let wrapping_key = password_key(password, &salt)?;
+		let block = wrapping_key.encrypt(master.as_ref(), &mut rng)?;
+
+		let mut sealed_key = block.nonce.to_vec();
+		sealed_key.extend_from_slice(&block.cipher_text);
+
+		Ok((Self { salt, sealed_key }, VaultKey(master)))
+	}
+
+	/// Recover the master key, failing with [`VaultError::IncorrectPassword`] on a bad password
+	pub fn unseal(&self, password: &Protected<String>) -> Result<VaultKey, VaultError> {
+		if self.sealed_key.len() < 24 {
+			return Err(VaultError::Corrupt(
+				"wrapped master key is truncated".to_string(),
+			));
+		}
+
+		let wrapping_key = password_key(password, &self.salt)?;
+		let master = Protected::new(
+			wrapping_key
+				.decrypt(self.sealed_key.as_slice().into())
+				.map_err(|_| VaultError::IncorrectPassword)?,
+		);
+
+		Ok(VaultKey(SecretKey::try_from(master.expose().as_slice())?))
+	}
+}
+
+fn password_key(password: &Protected<String>, salt: &[u8]) -> Result<SecretKey, VaultError> {
+	let mut derived = vec![0u8; 32];
+	Argon2::default()
+		.hash_password_into(password.expose().as_bytes(), salt, &mut derived)
+		.map_err(|e| VaultError::Corrupt(format!("password derivation failed: {}", e)))?;
+	let derived = Protected::new(derived);
+
+	Ok(SecretKey::try_from(derived.expose().as_slice())?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_wrap_and_unwrap_master_key() {
+		let password = Protected::new("correct horse battery staple".to_string());
+		let (wrapped, key) = WrappedMasterKey::generate(&password).unwrap();
+
+		let unwrapped = wrapped.unseal(&password).unwrap();
+		let id = Uuid::new_v4();
+		assert!(unwrapped.object_key(id) == key.object_key(id));
+		assert!(unwrapped.object_key(id) != key.object_key(Uuid::new_v4()));
+		assert!(unwrapped.manifest_key(id) != key.object_key(id));
+		assert!(unwrapped.thumbnail_key(id) != key.object_key(id));
+
+		assert!(matches!(
+			wrapped.unseal(&Protected::new("wrong".to_string())),
+			Err(VaultError::IncorrectPassword)
+		));
+	}
+}
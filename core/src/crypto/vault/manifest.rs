@@ -0,0 +1,47 @@
+//! Encrypted vault manifest
+//!
+//! The manifest is the vault's own metadata database: original names, sizes, types and
+//! timestamps of every object. It is sealed with the vault's manifest key, so nothing
+//! about a vault's contents is readable while it is locked.
+
+use crate::domain::ContentKind;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Plaintext metadata of one file stored in a vault
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultEntry {
+	pub id: Uuid,
+	/// Original file name, including extension
+	pub name: String,
+	pub size: u64,
+	pub kind: ContentKind,
+	pub mime_type: Option<String>,
+	pub modified_at: Option<DateTime<Utc>>,
+	pub added_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VaultManifest {
+	pub entries: BTreeMap<Uuid, VaultEntry>,
+}
+
+impl VaultManifest {
+	/// Entries whose name contains `query`, case-insensitively, sorted by name
+	pub fn search(&self, query: Option<&str>) -> Vec<&VaultEntry> {
+		let query = query.map(str::to_lowercase);
+		let mut entries: Vec<_> = self
+			.entries
+			.values()
+			.filter(|entry| match &query {
+				Some(query) => entry.name.to_lowercase().contains(query),
+				None => true,
+			})
+			.collect();
+		entries.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+		entries
+	}
+}
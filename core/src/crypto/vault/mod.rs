@@ -0,0 +1,220 @@
+//! Encrypted vault locations
+//!
+//! A vault is a location whose files are stored encrypted at rest. Files added to a vault
+//! are sealed with per-file keys derived from the library's vault master key, and their
+//! plaintext metadata lives only in the vault's encrypted manifest, never in the library
+//! database. Vaults are unlocked per session: the master key is unwrapped with the vault
+//! password and held in memory for that session alone, until it locks the library's
+//! vaults again. Other sessions on the same library stay locked until they unlock too.
+
+pub mod format;
+pub mod keys;
+pub mod manifest;
+pub mod store;
+
+pub use keys::{VaultKey, WrappedMasterKey};
+pub use manifest::{VaultEntry, VaultManifest};
+pub use store::VaultStore;
+
+use super::key_manager::{KeyManager, KeyManagerError};
+use crate::infra::action::journal::SessionKey;
+use sd_crypto::Protected;
+use std::{collections::HashMap, sync::Arc};
+use thiserror::Error;
+use tokio::sync::{Mutex, RwLock};
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum VaultError {
+	#[error("Vaults in this library are locked")]
+	Locked,
+
+	#[error("No vault password has been set for this library")]
+	NotConfigured,
+
+	#[error("Incorrect vault password")]
+	IncorrectPassword,
+
+	#[error("Vault data is corrupt: {0}")]
+	Corrupt(String),
+
+	#[error("Crypto error: {0}")]
+	Crypto(#[from] sd_crypto::Error),
+
+	#[error("Key manager error: {0}")]
+	KeyManager(#[from] KeyManagerError),
+
+	#[error("IO error: {0}")]
+	Io(#[from] std::io::Error),
+
+	#[error("Task failed: {0}")]
+	Join(#[from] tokio::task::JoinError),
+}
+
+/// Tracks which sessions have unlocked the vaults of which libraries
+pub struct VaultManager {
+	key_manager: Arc<KeyManager>,
+	unlocked: RwLock<HashMap<(Uuid, SessionKey), VaultKey>>,
+	/// Held while a library's master key is checked for and generated, so concurrent first
+	/// unlocks can't each store a key of their own
+	initializing: Mutex<HashMap<Uuid, Arc<Mutex<()>>>>,
+}
+
+impl VaultManager {
+	pub fn new(key_manager: Arc<KeyManager>) -> Self {
+		Self {
+			key_manager,
+			unlocked: RwLock::new(HashMap::new()),
+			initializing: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Whether a vault password has been set for the library
+	pub async fn is_configured(&self, library_id: Uuid) -> Result<bool, VaultError> {
+		match self.key_manager.get_secret(&secret_key(library_id)).await {
+			Ok(_) => Ok(true),
+			Err(KeyManagerError::KeyNotFound(_)) => Ok(false),
+			Err(e) => Err(e.into()),
+		}
+	}
+
+	/// Unlock the library's vaults, setting `password` as the vault password if none exists yet
+	pub async fn unlock_or_initialize(
+		&self,
+		library_id: Uuid,
+		session: &SessionKey,
+		password: &Protected<String>,
+	) -> Result<VaultKey, VaultError> {
+		let init_lock = self
+			.initializing
+			.lock()
+			.await
+			.entry(library_id)
+			.or_default()
+			.clone();
+		let _guard = init_lock.lock().await;
+
+		if self.is_configured(library_id).await? {
+			return self.unlock(library_id, session, password).await;
+		}
+
+		let (wrapped, key) = WrappedMasterKey::generate(password)?;
+		let bytes =
+			rmp_serde::to_vec_named(&wrapped).map_err(|e| VaultError::Corrupt(e.to_string()))?;
+		self.key_manager
+			.set_secret(&secret_key(library_id), &bytes)
+			.await?;
+
+		self.unlocked
+			.write()
+			.await
+			.insert((library_id, session.clone()), key.clone());
+		Ok(key)
+	}
+
+	/// Unlock the library's vaults for `session`
+	pub async fn unlock(
+		&self,
+		library_id: Uuid,
+		session: &SessionKey,
+		password: &Protected<String>,
+	) -> Result<VaultKey, VaultError> {
+		let bytes = match self.key_manager.get_secret(&secret_key(library_id)).await {
+			Ok(bytes) => bytes,
+			Err(KeyManagerError::KeyNotFound(_)) => return Err(VaultError::NotConfigured),
+			Err(e) => return Err(e.into()),
+		};
+		let wrapped: WrappedMasterKey =
+			rmp_serde::from_slice(&bytes).map_err(|e| VaultError::Corrupt(e.to_string()))?;
+
+		let key = wrapped.unseal(password)?;
+		self.unlocked
+			.write()
+			.await
+			.insert((library_id, session.clone()), key.clone());
+		Ok(key)
+	}
+
+	/// Forget `session`'s copy of the library's master key, returning whether it was unlocked
+	pub async fn lock(&self, library_id: Uuid, session: &SessionKey) -> bool {
+		self.unlocked
+			.write()
+			.await
+			.remove(&(library_id, session.clone()))
+			.is_some()
+	}
+
+	/// Forget the library's master key in every session, e.g. when the library closes
+	pub async fn lock_library(&self, library_id: Uuid) {
+		self.unlocked
+			.write()
+			.await
+			.retain(|(unlocked_library, _), _| *unlocked_library != library_id);
+	}
+
+	pub async fn is_unlocked(&self, library_id: Uuid, session: &SessionKey) -> bool {
+		self.unlocked
+			.read()
+			.await
+			.contains_key(&(library_id, session.clone()))
+	}
+
+	/// The library's master key, if `session` has unlocked its vaults
+	pub async fn key(
+		&self,
+		library_id: Uuid,
+		session: &SessionKey,
+	) -> Result<VaultKey, VaultError> {
+		self.unlocked
+			.read()
+			.await
+			.get(&(library_id, session.clone()))
+			.cloned()
+			.ok_or(VaultError::Locked)
+	}
+}
+
+fn secret_key(library_id: Uuid) -> String {
+	format!("vault_master_key_{}", library_id)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_concurrent_first_unlocks_share_one_key() {
+		let temp = tempfile::tempdir().unwrap();
+		let key_manager = KeyManager::new_with_fallback(
+			temp.path().to_path_buf(),
+			Some(temp.path().join("device_key.txt")),
+		)
+		.unwrap();
+		let vaults = VaultManager::new(Arc::new(key_manager));
+		let library_id = Uuid::new_v4();
+		let password = Protected::new("pw".to_string());
+		let (first, second) = (
+			SessionKey {
+				device_id: Uuid::new_v4(),
+				user_id: None,
+			},
+			SessionKey {
+				device_id: Uuid::new_v4(),
+				user_id: None,
+			},
+		);
+
+		let (a, b) = tokio::join!(
+			vaults.unlock_or_initialize(library_id, &first, &password),
+			vaults.unlock_or_initialize(library_id, &second, &password),
+		);
+		let (a, b) = (a.unwrap(), b.unwrap());
+
+		// A store sealed under either key opens with the other and with the stored one
+		let store = VaultStore::new(temp.path().join("vault"), Uuid::new_v4());
+		store.initialize(&a).await.unwrap();
+		store.load_manifest(&b).await.unwrap();
+		let stored = vaults.unlock(library_id, &first, &password).await.unwrap();
+		store.load_manifest(&stored).await.unwrap();
+	}
+}
@@ -0,0 +1,287 @@
+//! Vault directory layout and object I/O
+//!
+//! ```text
+//! <location root>/
+//!   manifest.sdv            sealed VaultManifest
+//!   objects/ab/<id>.sdv     sealed file contents, sharded by the first byte of the id
+//!   thumbnails/<id>.sdv     sealed WebP thumbnails
+//! ```
+//!
+//! Object names are random ids, so the directory reveals only how many files a vault
+//! holds and roughly how large they are.
+
+use super::{format, keys::VaultKey, manifest::VaultManifest, VaultError};
+use std::{
+	fs::File,
+	io::{BufReader, BufWriter},
+	path::{Path, PathBuf},
+};
+use tokio::task::spawn_blocking;
+use uuid::Uuid;
+
+const MANIFEST_FILE: &str = "manifest.sdv";
+const OBJECTS_DIR: &str = "objects";
+const THUMBNAILS_DIR: &str = "thumbnails";
+
+/// A vault rooted at a location's directory
+#[derive(Debug, Clone)]
+pub struct VaultStore {
+	root: PathBuf,
+	location_id: Uuid,
+}
+
+impl VaultStore {
+	pub fn new(root: impl Into<PathBuf>, location_id: Uuid) -> Self {
+		Self {
+			root: root.into(),
+			location_id,
+		}
+	}
+
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+
+	/// Create the directory layout and an empty manifest
+	pub async fn initialize(&self, key: &VaultKey) -> Result<(), VaultError> {
+		tokio::fs::create_dir_all(self.root.join(OBJECTS_DIR)).await?;
+		tokio::fs::create_dir_all(self.root.join(THUMBNAILS_DIR)).await?;
+
+		if !tokio::fs::try_exists(self.manifest_path()).await? {
+			self.save_manifest(key, &VaultManifest::default()).await?;
+		}
+		Ok(())
+	}
+
+	pub async fn load_manifest(&self, key: &VaultKey) -> Result<VaultManifest, VaultError> {
+		let sealed = tokio::fs::read(self.manifest_path()).await?;
+		let manifest_key = key.manifest_key(self.location_id);
+		let location_id = self.location_id;
+
+		let bytes =
+			spawn_blocking(move || format::decrypt_bytes(&manifest_key, location_id, &sealed))
+				.await??;
+		rmp_serde::from_slice(&bytes).map_err(|e| VaultError::Corrupt(e.to_string()))
+	}
+
+	/// Replace the manifest atomically, so a crash never leaves a half-written one
+	pub async fn save_manifest(
+		&self,
+		key: &VaultKey,
+		manifest: &VaultManifest,
+	) -> Result<(), VaultError> {
+		let bytes =
+			rmp_serde::to_vec_named(manifest).map_err(|e| VaultError::Corrupt(e.to_string()))?;
+		let manifest_key = key.manifest_key(self.location_id);
+		let location_id = self.location_id;
+
+		let sealed =
+			spawn_blocking(move || format::encrypt_bytes(&manifest_key, location_id, &bytes))
+				.await??;
+
+		let temp_path = self.root.join(format!("{}.tmp", MANIFEST_FILE));
+		tokio::fs::write(&temp_path, sealed).await?;
+		tokio::fs::rename(&temp_path, self.manifest_path()).await?;
+		Ok(())
+	}
+
+	/// Encrypt `source` into the vault as object `object_id`, returning the plaintext size
+	pub async fn write_object(
+		&self,
+		key: &VaultKey,
+		object_id: Uuid,
+		source: &Path,
+	) -> Result<u64, VaultError> {
+		let object_key = key.object_key(object_id);
+		let source = source.to_path_buf();
+		let destination = self.object_path(object_id);
+
+		spawn_blocking(move || {
+			if let Some(parent) = destination.parent() {
+				std::fs::create_dir_all(parent)?;
+			}
+
+			let reader = BufReader::new(File::open(&source)?);
+			let writer = BufWriter::new(File::create(&destination)?);
+			let result = format::encrypt_stream(&object_key, object_id, reader, writer);
+			if result.is_err() {
+				let _ = std::fs::remove_file(&destination);
+			}
+			result
+		})
+		.await?
+	}
+
+	/// Decrypt object `object_id` to `destination`, which must not exist yet
+	pub async fn read_object_to(
+		&self,
+		key: &VaultKey,
+		object_id: Uuid,
+		destination: &Path,
+	) -> Result<u64, VaultError> {
+		let object_key = key.object_key(object_id);
+		let source = self.object_path(object_id);
+		let destination = destination.to_path_buf();
+
+		spawn_blocking(move || {
+			let reader = BufReader::new(File::open(&source)?);
+			let writer = BufWriter::new(File::create_new(&destination)?);
+			let result = format::decrypt_stream(&object_key, object_id, reader, writer);
+			// Never leave partially decrypted plaintext behind
+			if result.is_err() {
+				let _ = std::fs::remove_file(&destination);
+			}
+			result
+		})
+		.await?
+	}
+
+	pub async fn remove_object(&self, object_id: Uuid) -> Result<(), VaultError> {
+		tokio::fs::remove_file(self.object_path(object_id)).await?;
+		match tokio::fs::remove_file(self.thumbnail_path(object_id)).await {
+			Ok(()) => Ok(()),
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+			Err(e) => Err(e.into()),
+		}
+	}
+
+	pub async fn write_thumbnail(
+		&self,
+		key: &VaultKey,
+		object_id: Uuid,
+		webp: Vec<u8>,
+	) -> Result<(), VaultError> {
+		let thumbnail_key = key.thumbnail_key(object_id);
+		let sealed =
+			spawn_blocking(move || format::encrypt_bytes(&thumbnail_key, object_id, &webp))
+				.await??;
+		tokio::fs::write(self.thumbnail_path(object_id), sealed).await?;
+		Ok(())
+	}
+
+	pub async fn read_thumbnail(
+		&self,
+		key: &VaultKey,
+		object_id: Uuid,
+	) -> Result<Option<Vec<u8>>, VaultError> {
+		let sealed = match tokio::fs::read(self.thumbnail_path(object_id)).await {
+			Ok(sealed) => sealed,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+			Err(e) => return Err(e.into()),
+		};
+
+		let thumbnail_key = key.thumbnail_key(object_id);
+		let webp =
+			spawn_blocking(move || format::decrypt_bytes(&thumbnail_key, object_id, &sealed))
+				.await??;
+		Ok(Some(webp))
+	}
+
+	fn manifest_path(&self) -> PathBuf {
+		self.root.join(MANIFEST_FILE)
+	}
+
+	fn object_path(&self, object_id: Uuid) -> PathBuf {
+		let id = object_id.simple().to_string();
+		self.root
+			.join(OBJECTS_DIR)
+			.join(&id[..2])
+			.join(format!("{}.sdv", id))
+	}
+
+	fn thumbnail_path(&self, object_id: Uuid) -> PathBuf {
+		self.root
+			.join(THUMBNAILS_DIR)
+			.join(format!("{}.sdv", object_id.simple()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::crypto::vault::{keys::WrappedMasterKey, manifest::VaultEntry};
+	use crate::domain::ContentKind;
+	use sd_crypto::Protected;
+
+	#[tokio::test]
+	async fn test_objects_and_manifest_round_trip() {
+		let temp = tempfile::tempdir().unwrap();
+		let (_, key) = WrappedMasterKey::generate(&Protected::new("pw".to_string())).unwrap();
+		let store = VaultStore::new(temp.path().join("vault"), Uuid::new_v4());
+		store.initialize(&key).await.unwrap();
+
+		let source = temp.path().join("secret.txt");
+		std::fs::write(&source, b"launch codes").unwrap();
+
+		let id = Uuid::new_v4();
+		let size = store.write_object(&key, id, &source).await.unwrap();
+		assert_eq!(size, 12);
+
+		let mut manifest = store.load_manifest(&key).await.unwrap();
+		manifest.entries.insert(
+			id,
+			VaultEntry {
+				id,
+				name: "secret.txt".to_string(),
+				size,
+				kind: ContentKind::Text,
+				mime_type: Some("text/plain".to_string()),
+				modified_at: None,
+				added_at: chrono::Utc::now(),
+			},
+		);
+		store.save_manifest(&key, &manifest).await.unwrap();
+
+		// Nothing on disk mentions the plaintext name or contents
+		for entry in walkdir(store.root()) {
+			let bytes = std::fs::read(&entry).unwrap();
+			assert!(!bytes.windows(10).any(|w| w == b"secret.txt"));
+			assert!(!bytes.windows(12).any(|w| w == b"launch codes"));
+		}
+
+		let reloaded = store.load_manifest(&key).await.unwrap();
+		assert_eq!(reloaded.search(Some("SECRET"))[0].id, id);
+
+		let out = temp.path().join("out.txt");
+		store.read_object_to(&key, id, &out).await.unwrap();
+		assert_eq!(std::fs::read(&out).unwrap(), b"launch codes");
+
+		// Decrypting never overwrites an existing file
+		assert!(store.read_object_to(&key, id, &out).await.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_thumbnails_are_sealed_apart_from_contents() {
+		let temp = tempfile::tempdir().unwrap();
+		let (_, key) = WrappedMasterKey::generate(&Protected::new("pw".to_string())).unwrap();
+		let store = VaultStore::new(temp.path().join("vault"), Uuid::new_v4());
+		store.initialize(&key).await.unwrap();
+
+		let id = Uuid::new_v4();
+		store
+			.write_thumbnail(&key, id, b"RIFF webp".to_vec())
+			.await
+			.unwrap();
+		assert_eq!(
+			store.read_thumbnail(&key, id).await.unwrap().as_deref(),
+			Some(b"RIFF webp".as_slice())
+		);
+
+		// A thumbnail can't be passed off as the object's contents
+		let sealed = std::fs::read(store.thumbnail_path(id)).unwrap();
+		assert!(format::decrypt_bytes(&key.object_key(id), id, &sealed).is_err());
+	}
+
+	fn walkdir(dir: &Path) -> Vec<PathBuf> {
+		let mut files = Vec::new();
+		for entry in std::fs::read_dir(dir).unwrap() {
+			let path = entry.unwrap().path();
+			if path.is_dir() {
+				files.extend(walkdir(&path));
+			} else {
+				files.push(path);
+			}
+		}
+		files
+	}
+}
@@ -19,6 +19,7 @@ use uuid::Uuid;
 /// - A physical file at a specific path on a specific device
 /// - A content-addressed file that can be sourced from any device
 /// - A sidecar (derivative data) attached to content
+/// - A file or folder inside an archive (zip, tar, 7z)
 ///
 /// This enum-based approach enables resilient file operations by allowing
 /// content-based paths to be resolved to optimal physical locations at runtime.
@@ -57,6 +58,15 @@ pub enum SdPath {
 		/// The storage format (webp, json, msgpack, etc.)
 		format: SidecarFormat,
 	},
+	/// A file or folder inside an archive, browsed in place without extracting it
+	Archive {
+		/// The device slug of the device holding the archive
+		device_slug: String,
+		/// The path of the archive file on that device
+		archive_path: PathBuf,
+		/// The '/'-separated path inside the archive, empty for its root
+		inner_path: String,
+	},
 }
 
 impl<'de> Deserialize<'de> for SdPath {
@@ -90,6 +100,13 @@ impl<'de> Deserialize<'de> for SdPath {
 			format: String,
 		}
 
+		#[derive(Deserialize)]
+		struct SdPathArchiveHelper {
+			device_slug: String,
+			archive_path: String,
+			inner_path: String,
+		}
+
 		#[derive(Deserialize)]
 		#[serde(untagged)]
 		enum SdPathHelper {
@@ -97,6 +114,7 @@ impl<'de> Deserialize<'de> for SdPath {
 			Cloud { Cloud: SdPathCloudHelper },
 			Content { Content: SdPathContentHelper },
 			Sidecar { Sidecar: SdPathSidecarHelper },
+			Archive { Archive: SdPathArchiveHelper },
 		}
 
 		let helper = SdPathHelper::deserialize(deserializer)?;
@@ -140,6 +158,11 @@ impl<'de> Deserialize<'de> for SdPath {
 					format,
 				})
 			}
+			SdPathHelper::Archive { Archive: archive } => Ok(SdPath::Archive {
+				device_slug: archive.device_slug,
+				archive_path: PathBuf::from(archive.archive_path),
+				inner_path: archive.inner_path,
+			}),
 		}
 	}
 }
@@ -191,6 +214,19 @@ impl SdPath {
 		}
 	}
 
+	/// Create an SdPath for a file or folder inside an archive
+	pub fn archive(
+		device_slug: String,
+		archive_path: impl Into<PathBuf>,
+		inner_path: impl Into<String>,
+	) -> Self {
+		Self::Archive {
+			device_slug,
+			archive_path: archive_path.into(),
+			inner_path: normalize_inner_path(&inner_path.into()),
+		}
+	}
+
 	/// Create an SdPath for a local file on this device
 	pub fn local(path: impl Into<PathBuf>) -> Self {
 		Self::Physical {
@@ -206,6 +242,7 @@ impl SdPath {
 			Self::Cloud { .. } => false,   // Cloud paths are never local
 			Self::Content { .. } => false, // Content paths are abstract, not inherently local
 			Self::Sidecar { .. } => false, // Sidecar paths are abstract, must be resolved
+			Self::Archive { .. } => self.as_local_archive().is_some(),
 		}
 	}
 
@@ -223,6 +260,21 @@ impl SdPath {
 			Self::Cloud { .. } => None,
 			Self::Content { .. } => None,
 			Self::Sidecar { .. } => None,
+			Self::Archive { .. } => None, // Archive members must be extracted first
+		}
+	}
+
+	/// Get the local archive file and inner path if this is an archive path on this device
+	pub fn as_local_archive(&self) -> Option<(&Path, &str)> {
+		match self {
+			Self::Archive {
+				device_slug,
+				archive_path,
+				inner_path,
+			} if *device_slug == "local" || *device_slug == get_current_device_slug() => {
+				Some((archive_path, inner_path))
+			}
+			_ => None,
 		}
 	}
 
@@ -257,6 +309,18 @@ impl SdPath {
 					format.extension()
 				)
 			}
+			Self::Archive {
+				device_slug,
+				archive_path,
+				inner_path,
+			} => {
+				format!(
+					"archive://{}/{}!/{}",
+					device_slug,
+					archive_path.display(),
+					inner_path
+				)
+			}
 		}
 	}
 
@@ -274,6 +338,19 @@ impl SdPath {
 					format!("{}.{}", variant.as_str(), format.extension()).into_boxed_str(),
 				))
 			}
+			Self::Archive {
+				archive_path,
+				inner_path,
+				..
+			} => match inner_path
+				.rsplit('/')
+				.next()
+				.filter(|name| !name.is_empty())
+			{
+				Some(name) => Some(name),
+				// The archive root is named after the archive itself
+				None => archive_path.file_name()?.to_str(),
+			},
 		}
 	}
 
@@ -298,6 +375,25 @@ impl SdPath {
 			}
 			Self::Content { .. } => None, // Content paths don't have parents
 			Self::Sidecar { .. } => None, // Sidecar paths don't have parents
+			Self::Archive {
+				device_slug,
+				archive_path,
+				inner_path,
+			} => {
+				if inner_path.is_empty() {
+					// Leaving the archive root returns to the folder holding the archive
+					return archive_path.parent().map(|p| Self::Physical {
+						device_slug: device_slug.clone(),
+						path: p.to_path_buf(),
+					});
+				}
+				let parent_inner = inner_path.rsplit_once('/').map_or("", |(parent, _)| parent);
+				Some(Self::Archive {
+					device_slug: device_slug.clone(),
+					archive_path: archive_path.clone(),
+					inner_path: parent_inner.to_string(),
+				})
+			}
 		}
 	}
 
@@ -331,6 +427,18 @@ impl SdPath {
 			}
 			Self::Content { .. } => panic!("Cannot join paths to content addresses"),
 			Self::Sidecar { .. } => panic!("Cannot join paths to sidecar addresses"),
+			Self::Archive {
+				device_slug,
+				archive_path,
+				inner_path,
+			} => {
+				let joined = format!("{}/{}", inner_path, path.as_ref().to_string_lossy());
+				Self::Archive {
+					device_slug: device_slug.clone(),
+					archive_path: archive_path.clone(),
+					inner_path: normalize_inner_path(&joined),
+				}
+			}
 		}
 	}
 
@@ -357,6 +465,10 @@ impl SdPath {
 			}
 			Self::Content { .. } => None, // Content paths don't have volumes until resolved
 			Self::Sidecar { .. } => None, // Sidecar paths don't have volumes until resolved
+			Self::Archive { .. } => match self.as_local_archive() {
+				Some((archive_path, _)) => volume_manager.volume_for_path(archive_path).await,
+				None => None,
+			},
 		}
 	}
 
@@ -405,6 +517,7 @@ impl SdPath {
 	/// - "s3://bucket/path/to/file" -> Cloud path
 	/// - "content://content_id" -> Content path
 	/// - "sidecar://content_id/thumbs/grid@2x.webp" -> Sidecar path
+	/// - "archive://device-slug/path/to/file.zip!/inner/path" -> Archive path
 	/// - "/local/path" -> Local physical path
 	///
 	/// Note: This is a synchronous version that doesn't require context.
@@ -484,6 +597,22 @@ impl SdPath {
 				})
 			}
 
+			"archive" => {
+				// Parse: archive://device-slug/path/to/file.zip!/inner/path
+				let (slug, path) = rest
+					.split_once('/')
+					.ok_or(SdPathParseError::InvalidFormat)?;
+				let (archive_path, inner_path) = path
+					.split_once("!/")
+					.ok_or(SdPathParseError::InvalidArchivePath)?;
+
+				Ok(Self::archive(
+					slug.to_string(),
+					PathBuf::from("/").join(archive_path),
+					inner_path,
+				))
+			}
+
 			_ => {
 				// Try to parse as cloud service scheme
 				let service = crate::volume::backend::CloudServiceType::from_scheme(scheme)
@@ -534,6 +663,7 @@ impl SdPath {
 			Self::Cloud { .. } => None,
 			Self::Content { .. } => None,
 			Self::Sidecar { .. } => None,
+			Self::Archive { .. } => None,
 		}
 	}
 
@@ -551,6 +681,7 @@ impl SdPath {
 			Self::Cloud { .. } => None,
 			Self::Content { .. } => None,
 			Self::Sidecar { .. } => None,
+			Self::Archive { .. } => None,
 		}
 	}
 
@@ -561,6 +692,7 @@ impl SdPath {
 			Self::Sidecar { content_id, .. } => Some(*content_id),
 			Self::Physical { .. } => None,
 			Self::Cloud { .. } => None,
+			Self::Archive { .. } => None,
 		}
 	}
 
@@ -575,6 +707,7 @@ impl SdPath {
 			Self::Physical { .. } => None,
 			Self::Content { .. } => None,
 			Self::Sidecar { .. } => None,
+			Self::Archive { .. } => None,
 		}
 	}
 
@@ -592,6 +725,7 @@ impl SdPath {
 			Self::Physical { .. } => None,
 			Self::Content { .. } => None,
 			Self::Sidecar { .. } => None,
+			Self::Archive { .. } => None,
 		}
 	}
 
@@ -615,6 +749,11 @@ impl SdPath {
 		matches!(self, Self::Sidecar { .. })
 	}
 
+	/// Check if this is an Archive path
+	pub fn is_archive(&self) -> bool {
+		matches!(self, Self::Archive { .. })
+	}
+
 	/// Try to get as a Physical path, returning device_slug and path
 	pub fn as_physical(&self) -> Option<(&str, &PathBuf)> {
 		match self {
@@ -622,6 +761,7 @@ impl SdPath {
 			Self::Cloud { .. } => None,
 			Self::Content { .. } => None,
 			Self::Sidecar { .. } => None,
+			Self::Archive { .. } => None,
 		}
 	}
 
@@ -636,6 +776,7 @@ impl SdPath {
 			Self::Physical { .. } => None,
 			Self::Content { .. } => None,
 			Self::Sidecar { .. } => None,
+			Self::Archive { .. } => None,
 		}
 	}
 
@@ -651,6 +792,7 @@ impl SdPath {
 			Self::Physical { .. } => None,
 			Self::Cloud { .. } => None,
 			Self::Content { .. } => None,
+			Self::Archive { .. } => None,
 		}
 	}
 
@@ -682,10 +824,20 @@ impl SdPath {
 				// TODO: Implement sidecar resolution
 				Err(PathResolutionError::NoOnlineInstancesFound(*content_id))
 			}
+			Self::Archive { .. } => Ok(self.clone()), // Archive members are read in place
 		}
 	}
 }
 
+/// Normalize a path inside an archive to '/'-separated segments, dropping empty and '.' ones
+fn normalize_inner_path(inner_path: &str) -> String {
+	inner_path
+		.split(['/', '\\'])
+		.filter(|segment| !segment.is_empty() && *segment != ".")
+		.collect::<Vec<_>>()
+		.join("/")
+}
+
 /// Error type for path resolution
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PathResolutionError {
@@ -744,6 +896,7 @@ pub enum SdPathParseError {
 	InvalidSidecarKind,
 	InvalidSidecarFormat,
 	MissingExtension,
+	InvalidArchivePath,
 }
 
 impl fmt::Display for SdPathParseError {
@@ -760,6 +913,7 @@ impl fmt::Display for SdPathParseError {
 			Self::InvalidSidecarKind => write!(f, "Invalid sidecar kind"),
 			Self::InvalidSidecarFormat => write!(f, "Invalid sidecar format"),
 			Self::MissingExtension => write!(f, "Missing file extension in sidecar path"),
+			Self::InvalidArchivePath => write!(f, "Invalid archive path format"),
 		}
 	}
 }
@@ -903,6 +1057,38 @@ mod tests {
 		assert!(path.is_local());
 	}
 
+	#[test]
+	fn test_sdpath_network_share_uri_parsing() {
+		let path = SdPath::from_uri("sftp://nas.local:2222/volume1/photos/cat.jpg").unwrap();
+		match &path {
+			SdPath::Cloud {
+				service,
+				identifier,
+				path,
+			} => {
+				assert_eq!(*service, crate::volume::backend::CloudServiceType::Sftp);
+				assert_eq!(identifier, "nas.local:2222");
+				assert_eq!(path, "volume1/photos/cat.jpg");
+			}
+			_ => panic!("Expected Cloud variant"),
+		}
+		assert_eq!(SdPath::from_uri(&path.to_string()).unwrap(), path);
+
+		let path = SdPath::from_uri("webdav://cloud.example.com/Documents/report.pdf").unwrap();
+		match path {
+			SdPath::Cloud {
+				service,
+				identifier,
+				path,
+			} => {
+				assert_eq!(service, crate::volume::backend::CloudServiceType::WebDav);
+				assert_eq!(identifier, "cloud.example.com");
+				assert_eq!(path, "Documents/report.pdf");
+			}
+			_ => panic!("Expected Cloud variant"),
+		}
+	}
+
 	#[test]
 	fn test_sdpath_sidecar_creation() {
 		let content_id = Uuid::new_v4();
@@ -1007,4 +1193,49 @@ mod tests {
 		assert!(!path.is_cloud());
 		assert!(!path.is_content());
 	}
+
+	#[test]
+	fn test_sdpath_archive_uri_round_trip() {
+		let path = SdPath::archive(
+			"test-device".to_string(),
+			"/home/user/photos.zip",
+			"/2024//summer/./beach.jpg",
+		);
+
+		assert!(path.is_archive());
+		assert_eq!(path.file_name(), Some("beach.jpg"));
+		assert_eq!(
+			path.display(),
+			"archive://test-device//home/user/photos.zip!/2024/summer/beach.jpg"
+		);
+		assert_eq!(SdPath::from_uri(&path.display()).unwrap(), path);
+
+		assert!(matches!(
+			SdPath::from_uri("archive://test-device/home/user/photos.zip"),
+			Err(SdPathParseError::InvalidArchivePath)
+		));
+	}
+
+	#[test]
+	fn test_sdpath_archive_navigation() {
+		let root = SdPath::archive("test-device".to_string(), "/home/user/photos.zip", "");
+		assert_eq!(root.file_name(), Some("photos.zip"));
+
+		let child = root.join("2024").join("summer");
+		assert_eq!(
+			child,
+			SdPath::archive(
+				"test-device".to_string(),
+				"/home/user/photos.zip",
+				"2024/summer"
+			)
+		);
+		assert_eq!(child.parent().unwrap().parent(), Some(root.clone()));
+
+		// The parent of the archive root is the folder holding the archive
+		assert_eq!(
+			root.parent(),
+			Some(SdPath::physical("test-device".to_string(), "/home/user"))
+		);
+	}
 }
@@ -344,10 +344,18 @@ pub struct JobPolicies {
 	#[serde(default)]
 	pub ocr: OcrPolicy,
 
+	/// Document text extraction policy (full-text content search)
+	#[serde(default)]
+	pub text_extraction: TextExtractionPolicy,
+
 	/// Speech-to-text transcription policy
 	#[serde(default)]
 	pub speech_to_text: SpeechPolicy,
 
+	/// Text embedding policy (semantic search)
+	#[serde(default)]
+	pub embedding: EmbeddingPolicy,
+
 	/// Object detection policy (future)
 	#[serde(default)]
 	pub object_detection: ObjectDetectionPolicy,
@@ -360,7 +368,9 @@ impl Default for JobPolicies {
 			thumbstrip: ThumbstripPolicy::default(),
 			proxy: ProxyPolicy::default(),
 			ocr: OcrPolicy::default(),
+			text_extraction: TextExtractionPolicy::default(),
 			speech_to_text: SpeechPolicy::default(),
+			embedding: EmbeddingPolicy::default(),
 			object_detection: ObjectDetectionPolicy::default(),
 		}
 	}
@@ -510,6 +520,47 @@ impl OcrPolicy {
 	}
 }
 
+/// Document text extraction policy
+///
+/// Indexes the text of plain text, source code, PDF, DOCX/ODT and EPUB files
+/// so full searches can match on file contents.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TextExtractionPolicy {
+	/// Whether to extract document text on this location
+	pub enabled: bool,
+
+	/// Skip structured documents larger than this (MB); plain text is truncated instead
+	pub max_file_size_mb: u64,
+
+	/// Whether to re-extract text that is already indexed
+	pub reprocess: bool,
+}
+
+impl Default for TextExtractionPolicy {
+	fn default() -> Self {
+		Self {
+			enabled: true, // Cheap compared to OCR, most files are skipped by extension
+			max_file_size_mb: 50,
+			reprocess: false,
+		}
+	}
+}
+
+impl TextExtractionPolicy {
+	/// Convert this policy to a TextExtractionJobConfig for job dispatch
+	pub fn to_job_config(
+		&self,
+		location_id: Option<Uuid>,
+	) -> crate::ops::media::text_extraction::TextExtractionJobConfig {
+		crate::ops::media::text_extraction::TextExtractionJobConfig {
+			location_id,
+			entry_uuid: None,
+			max_file_size: self.max_file_size_mb * 1024 * 1024,
+			reprocess: self.reprocess,
+		}
+	}
+}
+
 /// Speech-to-text transcription policy
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct SpeechPolicy {
@@ -554,6 +605,50 @@ impl SpeechPolicy {
 	}
 }
 
+/// Text embedding policy
+///
+/// Embeds file names, extracted text, OCR output and transcripts so searches
+/// can match files by meaning as well as by keyword.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct EmbeddingPolicy {
+	/// Whether to compute embeddings on this location
+	pub enabled: bool,
+
+	/// Model to use (e.g., "minilm-l6-v2")
+	pub model: String,
+
+	/// Whether to re-embed files that are already indexed
+	pub reprocess: bool,
+}
+
+impl Default for EmbeddingPolicy {
+	fn default() -> Self {
+		Self {
+			enabled: false, // Requires a model download
+			model: crate::ops::models::EmbeddingModel::default()
+				.id()
+				.to_string(),
+			reprocess: false,
+		}
+	}
+}
+
+impl EmbeddingPolicy {
+	/// Convert this policy to an EmbeddingJobConfig for job dispatch
+	#[cfg(feature = "embeddings")]
+	pub fn to_job_config(
+		&self,
+		location_id: Option<Uuid>,
+	) -> crate::ops::media::embedding::EmbeddingJobConfig {
+		crate::ops::media::embedding::EmbeddingJobConfig {
+			location_id,
+			entry_uuid: None,
+			model: self.model.clone(),
+			reprocess: self.reprocess,
+		}
+	}
+}
+
 /// Object detection policy (for future AI features)
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct ObjectDetectionPolicy {
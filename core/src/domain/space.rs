@@ -434,6 +434,9 @@ pub enum ItemType {
 	/// Tag filter
 	Tag { tag_id: Uuid },
 
+	/// Saved search (smart collection)
+	SavedSearch { saved_search_id: Uuid },
+
 	/// Any arbitrary path (dragged from explorer)
 	Path { sd_path: SdPath },
 }
@@ -206,6 +206,8 @@ pub enum TagSource {
 	Import,
 	/// Synchronized from another device
 	Sync,
+	/// Applied by an auto-tag or automation rule (the rule's UUID is the applied context)
+	Rule,
 }
 
 /// Result of merging tag applications during sync
@@ -737,6 +737,16 @@ impl Volume {
 		)
 	}
 
+	/// Check if changes on this volume have to be found by polling
+	///
+	/// OS change notifications only cover changes made through this machine, so they
+	/// miss edits made by other machines on network shares and on most FUSE mounts.
+	pub fn requires_polling_watcher(&self) -> bool {
+		matches!(self.mount_type, MountType::Network)
+			|| matches!(self.disk_type, DiskType::Network)
+			|| self.file_system.is_network_or_fuse()
+	}
+
 	/// Get capacity utilization percentage
 	pub fn utilization_percentage(&self) -> f64 {
 		if self.total_capacity == 0 {
@@ -974,6 +984,24 @@ impl FileSystem {
 			_ => FileSystem::Other(s.to_string()),
 		}
 	}
+
+	/// Check if this is a network filesystem or one served through FUSE
+	pub fn is_network_or_fuse(&self) -> bool {
+		match self {
+			FileSystem::NFS | FileSystem::SMB => true,
+			FileSystem::Other(name) => {
+				let name = name.to_lowercase();
+				name.starts_with("nfs")
+					|| name.starts_with("smb")
+					|| name.starts_with("fuse")
+					|| matches!(
+						name.as_str(),
+						"cifs" | "afpfs" | "webdav" | "davfs" | "9p" | "sshfs" | "macfuse"
+					)
+			}
+			_ => false,
+		}
+	}
 }
 
 impl std::fmt::Display for VolumeType {
@@ -1091,4 +1119,28 @@ mod tests {
 		volume.file_system = FileSystem::Btrfs;
 		assert!(volume.supports_cow());
 	}
+
+	#[test]
+	fn test_requires_polling_watcher() {
+		let fingerprint = VolumeFingerprint("test".to_string());
+		let mut volume = Volume::new(
+			Uuid::new_v4(),
+			fingerprint,
+			"Test".to_string(),
+			PathBuf::from("/test"),
+		);
+
+		volume.file_system = FileSystem::Ext4;
+		assert!(!volume.requires_polling_watcher());
+
+		volume.file_system = FileSystem::Other("fuse.sshfs".to_string());
+		assert!(volume.requires_polling_watcher());
+
+		volume.file_system = FileSystem::Other("cifs".to_string());
+		assert!(volume.requires_polling_watcher());
+
+		volume.file_system = FileSystem::APFS;
+		volume.mount_type = MountType::Network;
+		assert!(volume.requires_polling_watcher());
+	}
 }
@@ -1,6 +1,9 @@
 //! Error types for the Action System
 
-use crate::{common::errors::CoreError, infra::job::error::JobError, library::LibraryError};
+use crate::{
+	common::errors::CoreError, crypto::vault::VaultError, infra::job::error::JobError,
+	library::LibraryError,
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use uuid::Uuid;
@@ -67,6 +70,10 @@ pub enum ActionError {
 	#[error("Action was cancelled")]
 	Cancelled,
 
+	/// The filesystem no longer matches what a journaled action left behind
+	#[error("{0} since the action ran")]
+	StateDrifted(String),
+
 	/// Device manager error
 	#[error("Device manager error: {0}")]
 	DeviceManager(String),
@@ -107,6 +114,18 @@ impl From<CoreError> for ActionError {
 	}
 }
 
+impl From<VaultError> for ActionError {
+	fn from(error: VaultError) -> Self {
+		match error {
+			VaultError::IncorrectPassword => ActionError::Validation {
+				field: "password".to_string(),
+				message: error.to_string(),
+			},
+			other => ActionError::Internal(other.to_string()),
+		}
+	}
+}
+
 impl From<std::io::Error> for ActionError {
 	fn from(error: std::io::Error) -> Self {
 		ActionError::Io {
@@ -0,0 +1,226 @@
+//! Undo journal - per-session history of reversible actions
+//!
+//! When a library action is dispatched on behalf of an API session, the ActionManager
+//! asks it for an [`UndoOperation`] before it executes. If the action succeeds (for
+//! actions that run as a job, once that job completes), the operation is pushed onto the
+//! session's undo stack. `action.undo` pops it, reverses it and moves it to the redo
+//! stack; `action.redo` does the opposite. Recording a new action clears the redo stack,
+//! like any editor.
+//!
+//! History lives in memory only: it describes filesystem state at a point in time and
+//! would be meaningless after the daemon restarts.
+
+use crate::{infra::api::SessionContext, ops::action::UndoOperation};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Maximum number of undoable actions kept per session
+pub const MAX_UNDO_HISTORY: usize = 100;
+
+/// Identifies whose history an action belongs to
+///
+/// API sessions are derived per request, so history is keyed on the authenticated
+/// device (and user, once user sessions exist) rather than on a request.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SessionKey {
+	pub device_id: Uuid,
+	pub user_id: Option<Uuid>,
+}
+
+impl From<&SessionContext> for SessionKey {
+	fn from(session: &SessionContext) -> Self {
+		Self {
+			device_id: session.auth.device_id,
+			user_id: session.auth.user_id,
+		}
+	}
+}
+
+/// A recorded action and how to reverse it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+	/// Audit log UUID of the action that was recorded
+	pub action_id: Uuid,
+	/// Action kind, e.g. `files.rename`
+	pub action_kind: String,
+	pub recorded_at: DateTime<Utc>,
+	pub operation: UndoOperation,
+}
+
+#[derive(Debug, Default)]
+struct SessionHistory {
+	undo: VecDeque<JournalEntry>,
+	redo: Vec<JournalEntry>,
+}
+
+/// Undo and redo stacks for every session, per library
+#[derive(Debug, Default)]
+pub struct UndoJournal {
+	sessions: Mutex<HashMap<(Uuid, SessionKey), SessionHistory>>,
+}
+
+impl UndoJournal {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Record a newly executed action, discarding anything that could be redone
+	pub fn record(&self, library_id: Uuid, session: &SessionKey, entry: JournalEntry) {
+		self.with_history(library_id, session, |history| {
+			history.redo.clear();
+			Self::push_undo(history, entry);
+		})
+	}
+
+	/// Take the most recent undoable action
+	///
+	/// Callers must hand the entry back with [`Self::complete_undo`] once it has been
+	/// reversed, or [`Self::restore_undo`] if reversing it failed.
+	pub fn take_undo(&self, library_id: Uuid, session: &SessionKey) -> Option<JournalEntry> {
+		self.with_history(library_id, session, |history| history.undo.pop_back())
+	}
+
+	/// Put back an entry whose undo failed
+	pub fn restore_undo(&self, library_id: Uuid, session: &SessionKey, entry: JournalEntry) {
+		self.with_history(library_id, session, |history| {
+			Self::push_undo(history, entry)
+		})
+	}
+
+	/// Move a reversed entry onto the redo stack
+	pub fn complete_undo(&self, library_id: Uuid, session: &SessionKey, entry: JournalEntry) {
+		self.with_history(library_id, session, |history| history.redo.push(entry))
+	}
+
+	/// Take the most recently undone action
+	///
+	/// Callers must hand the entry back with [`Self::complete_redo`] or
+	/// [`Self::restore_redo`].
+	pub fn take_redo(&self, library_id: Uuid, session: &SessionKey) -> Option<JournalEntry> {
+		self.with_history(library_id, session, |history| history.redo.pop())
+	}
+
+	/// Put back an entry whose redo failed
+	pub fn restore_redo(&self, library_id: Uuid, session: &SessionKey, entry: JournalEntry) {
+		self.with_history(library_id, session, |history| history.redo.push(entry))
+	}
+
+	/// Move a re-applied entry back onto the undo stack, keeping the rest of the redo stack
+	pub fn complete_redo(&self, library_id: Uuid, session: &SessionKey, entry: JournalEntry) {
+		self.with_history(library_id, session, |history| {
+			Self::push_undo(history, entry)
+		})
+	}
+
+	/// Number of actions that can currently be undone and redone
+	pub fn depth(&self, library_id: Uuid, session: &SessionKey) -> (usize, usize) {
+		self.with_history(library_id, session, |history| {
+			(history.undo.len(), history.redo.len())
+		})
+	}
+
+	fn push_undo(history: &mut SessionHistory, entry: JournalEntry) {
+		history.undo.push_back(entry);
+		while history.undo.len() > MAX_UNDO_HISTORY {
+			history.undo.pop_front();
+		}
+	}
+
+	fn with_history<T>(
+		&self,
+		library_id: Uuid,
+		session: &SessionKey,
+		f: impl FnOnce(&mut SessionHistory) -> T,
+	) -> T {
+		let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+		f(sessions.entry((library_id, session.clone())).or_default())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ops::action::{PathMove, PathState};
+	use std::path::PathBuf;
+
+	fn new_session() -> SessionKey {
+		SessionKey {
+			device_id: Uuid::new_v4(),
+			user_id: None,
+		}
+	}
+
+	fn entry(name: &str) -> JournalEntry {
+		JournalEntry {
+			action_id: Uuid::new_v4(),
+			action_kind: "files.rename".to_string(),
+			recorded_at: Utc::now(),
+			operation: UndoOperation::Move {
+				moves: vec![PathMove {
+					from: PathBuf::from("/tmp").join(name),
+					to: PathBuf::from("/tmp").join(format!("{}.renamed", name)),
+					state: PathState {
+						is_dir: false,
+						size: 0,
+						modified_at: None,
+					},
+				}],
+			},
+		}
+	}
+
+	#[test]
+	fn test_undo_redo_round_trip() {
+		let journal = UndoJournal::new();
+		let library_id = Uuid::new_v4();
+		let session = new_session();
+
+		let first = entry("a");
+		journal.record(library_id, &session, first.clone());
+		journal.record(library_id, &session, entry("b"));
+		assert_eq!(journal.depth(library_id, &session), (2, 0));
+
+		let undone = journal.take_undo(library_id, &session).unwrap();
+		assert_eq!(undone.action_kind, "files.rename");
+		journal.complete_undo(library_id, &session, undone);
+		assert_eq!(journal.depth(library_id, &session), (1, 1));
+
+		let redone = journal.take_redo(library_id, &session).unwrap();
+		journal.complete_redo(library_id, &session, redone);
+		assert_eq!(journal.depth(library_id, &session), (2, 0));
+
+		// Other sessions and libraries have their own history
+		assert!(journal.take_undo(library_id, &new_session()).is_none());
+		assert!(journal.take_undo(Uuid::new_v4(), &session).is_none());
+
+		let top = journal.take_undo(library_id, &session).unwrap();
+		journal.complete_undo(library_id, &session, top);
+		assert_eq!(
+			journal.take_undo(library_id, &session).unwrap().action_id,
+			first.action_id
+		);
+	}
+
+	#[test]
+	fn test_record_clears_redo_and_caps_history() {
+		let journal = UndoJournal::new();
+		let library_id = Uuid::new_v4();
+		let session = new_session();
+
+		journal.record(library_id, &session, entry("a"));
+		let undone = journal.take_undo(library_id, &session).unwrap();
+		journal.complete_undo(library_id, &session, undone);
+		assert_eq!(journal.depth(library_id, &session), (0, 1));
+
+		journal.record(library_id, &session, entry("b"));
+		assert_eq!(journal.depth(library_id, &session), (1, 0));
+
+		for i in 0..MAX_UNDO_HISTORY + 10 {
+			journal.record(library_id, &session, entry(&i.to_string()));
+		}
+		assert_eq!(journal.depth(library_id, &session), (MAX_UNDO_HISTORY, 0));
+	}
+}
@@ -1,9 +1,18 @@
 //! Action manager - central router for all actions
 
-use super::error::{ActionError, ActionResult};
+use super::{
+	error::{ActionError, ActionResult},
+	journal::{JournalEntry, SessionKey},
+};
 use crate::{
 	context::CoreContext,
-	infra::db::entities::{audit_log, AuditLog, AuditLogActive},
+	infra::{
+		db::entities::{audit_log, AuditLog, AuditLogActive},
+		job::{
+			manager::JobManager,
+			types::{JobId, JobStatus},
+		},
+	},
 };
 use sea_orm::{
 	ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect, Set,
@@ -74,6 +83,29 @@ impl ActionManager {
 		&self,
 		library_id: Option<Uuid>,
 		action: A,
+	) -> Result<A::Output, super::error::ActionError> {
+		self.run_library_action(library_id, action, None).await
+	}
+
+	/// Dispatch a library-scoped action on behalf of an API session
+	///
+	/// Reversible actions are recorded in the session's undo journal once they succeed.
+	pub async fn dispatch_library_for_session<A: super::LibraryAction>(
+		&self,
+		library_id: Option<Uuid>,
+		mut action: A,
+		session: SessionKey,
+	) -> Result<A::Output, super::error::ActionError> {
+		action.bind_session(&session);
+		self.run_library_action(library_id, action, Some(session))
+			.await
+	}
+
+	async fn run_library_action<A: super::LibraryAction>(
+		&self,
+		library_id: Option<Uuid>,
+		action: A,
+		session: Option<SessionKey>,
 	) -> Result<A::Output, super::error::ActionError> {
 		let library_id =
 			library_id.ok_or(ActionError::LibraryNotFound(library_id.unwrap_or_default()))?;
@@ -107,6 +139,20 @@ impl ActionManager {
 			}
 		}
 
+		// Capture the inverse before execution changes anything
+		let undo_operation = match &session {
+			Some(_) => match action.undo_operation(&library).await {
+				Ok(operation) => operation,
+				Err(e) => {
+					tracing::warn!("Failed to capture undo state for {}: {}", action_kind, e);
+					None
+				}
+			},
+			None => None,
+		};
+		let action_id = Uuid::parse_str(&audit_entry.uuid).unwrap_or_else(|_| Uuid::new_v4());
+		let jobs = library.jobs().clone();
+
 		// Execute the action with validated library
 		let result = action.execute(library, self.context.clone()).await;
 
@@ -118,9 +164,52 @@ impl ActionManager {
 		self.finalize_audit_log(audit_entry, &audit_result, library_id)
 			.await?;
 
+		// Record reversible actions in the session's undo journal
+		if let (Ok(output), Some(session), Some(operation)) = (&result, session, undo_operation) {
+			let entry = JournalEntry {
+				action_id,
+				action_kind: action_kind.to_string(),
+				recorded_at: chrono::Utc::now(),
+				operation,
+			};
+			match A::background_job(output) {
+				// The action only queued its job, which may still fail
+				Some(job_id) => {
+					let context = self.context.clone();
+					tokio::spawn(async move {
+						if Self::job_succeeded(&jobs, job_id).await {
+							context.undo_journal.record(library_id, &session, entry);
+						} else {
+							tracing::debug!(
+								"Not recording {} for undo: its job did not complete",
+								entry.action_kind
+							);
+						}
+					});
+				}
+				None => self
+					.context
+					.undo_journal
+					.record(library_id, &session, entry),
+			}
+		}
+
 		result
 	}
 
+	/// Wait for a job to finish and report whether it completed successfully
+	async fn job_succeeded(jobs: &JobManager, job_id: JobId) -> bool {
+		if let Some(handle) = jobs.get_job(job_id).await {
+			return handle.wait().await.is_ok();
+		}
+
+		// The job already finished and left the running set
+		matches!(
+			jobs.get_job_info(job_id.0).await,
+			Ok(Some(info)) if matches!(info.status, JobStatus::Completed)
+		)
+	}
+
 	/// Validate a library action and return the validation result
 	/// This allows checking for confirmations before executing
 	pub async fn validate_library<A: super::LibraryAction>(
@@ -14,6 +14,7 @@ pub mod context;
 #[cfg(test)]
 mod context_test;
 pub mod error;
+pub mod journal;
 pub mod manager;
 pub mod output;
 pub mod receipt;
@@ -128,6 +129,32 @@ pub trait LibraryAction: Send + Sync + 'static {
 		Ok(())
 	}
 
+	/// Bind the API session this action runs in (optional)
+	/// Called before validation when the action is dispatched for a session
+	fn bind_session(&mut self, _session: &journal::SessionKey) {}
+
+	/// Capture how to reverse this action (optional)
+	/// Called right before execution; reversible actions return the state they are about
+	/// to change so the ActionManager can record it in the session's undo journal
+	fn undo_operation(
+		&self,
+		_library: &std::sync::Arc<crate::library::Library>,
+	) -> impl std::future::Future<
+		Output = Result<
+			Option<crate::ops::action::UndoOperation>,
+			crate::infra::action::error::ActionError,
+		>,
+	> + Send {
+		async { Ok(None) }
+	}
+
+	/// The job carrying out this action, if it runs in the background (optional)
+	/// Reversible actions backed by a job are only recorded in the undo journal once that
+	/// job has completed successfully
+	fn background_job(_output: &Self::Output) -> Option<crate::infra::job::types::JobId> {
+		None
+	}
+
 	/// Execute this action with validated library and core context
 	fn execute(
 		self,
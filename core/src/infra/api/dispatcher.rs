@@ -11,7 +11,7 @@ use super::{
 };
 use crate::{
 	context::CoreContext,
-	infra::action::{manager::ActionManager, CoreAction, LibraryAction},
+	infra::action::{journal::SessionKey, manager::ActionManager, CoreAction, LibraryAction},
 	infra::query::{manager::QueryManager, CoreQuery, LibraryQuery},
 };
 use bincode::config::standard;
@@ -87,10 +87,10 @@ impl ApiDispatcher {
 		// 3. Create action from input
 		let action = A::from_input(action_input).map_err(|e| ApiError::invalid_input(e))?;
 
-		// 4. Dispatch action
+		// 4. Dispatch action (reversible actions join the session's undo history)
 		let action_manager = ActionManager::new(self.core_context.clone());
 		let result = action_manager
-			.dispatch_library(Some(library_id), action)
+			.dispatch_library_for_session(Some(library_id), action, SessionKey::from(&session))
 			.await
 			.map_err(ApiError::from)?;
 
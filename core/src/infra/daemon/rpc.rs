@@ -334,6 +334,7 @@ impl RpcServer {
 										&core,
 										&shutdown_tx,
 										&connections,
+										&connection_count,
 										connection_id,
 										&response_tx,
 										&event_buffer
@@ -418,6 +419,7 @@ impl RpcServer {
 		core: &Arc<Core>,
 		shutdown_tx: &mpsc::Sender<()>,
 		connections: &Arc<RwLock<HashMap<Uuid, Connection>>>,
+		connection_count: &Arc<AtomicUsize>,
 		connection_id: Uuid,
 		response_tx: &mpsc::UnboundedSender<DaemonResponse>,
 		event_buffer: &Arc<EventBuffer>,
@@ -540,6 +542,25 @@ impl RpcServer {
 				DaemonResponse::LogsUnsubscribed
 			}
 
+			DaemonRequest::Metrics => {
+				match crate::ops::core::metrics::collect_metrics(&core.context).await {
+					Ok(mut metrics) => {
+						metrics.connections.rpc_connections =
+							Some(connection_count.load(Ordering::Relaxed) as u64);
+						metrics.connections.rpc_event_streams =
+							Some(connections.read().await.len() as u64);
+
+						match serde_json::to_value(&metrics) {
+							Ok(json) => DaemonResponse::JsonOk(json),
+							Err(e) => DaemonResponse::Error(DaemonError::SerializationError(
+								e.to_string(),
+							)),
+						}
+					}
+					Err(e) => DaemonResponse::Error(DaemonError::OperationFailed(e.to_string())),
+				}
+			}
+
 			DaemonRequest::Shutdown => {
 				// Signal shutdown to main loop
 				let _ = shutdown_tx.send(()).await;
@@ -30,6 +30,8 @@ pub enum DaemonRequest {
 	},
 	/// Unsubscribe from logs
 	UnsubscribeLogs,
+	/// Snapshot of core metrics, including RPC connection counts
+	Metrics,
 	Shutdown,
 }
 
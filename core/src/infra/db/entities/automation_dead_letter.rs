@@ -0,0 +1,40 @@
+//! Automation dead letter entity
+//!
+//! Log of automation actions that still failed after their retries, kept with the
+//! event and rendered action so they can be inspected. Device-local and not synced.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "automation_dead_letter")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+
+	#[sea_orm(unique)]
+	pub uuid: Uuid,
+
+	/// Rule that fired (the rule may since have been deleted)
+	pub rule_uuid: Uuid,
+
+	pub rule_name: String,
+
+	/// Event context the rule matched, as passed to filters and templates
+	pub event: Json,
+
+	/// AutomationAction with templates rendered
+	pub action: Json,
+
+	/// Error from the final attempt
+	pub error: String,
+
+	pub attempts: i32,
+
+	pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
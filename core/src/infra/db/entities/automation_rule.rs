@@ -0,0 +1,43 @@
+//! Automation rule entity
+//!
+//! "When an event matching this filter is emitted, run this action" rules.
+//! Rows are device-local (webhook endpoints and volumes belong to this machine) and are
+//! not synced.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "automation_rule")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+
+	#[sea_orm(unique)]
+	pub uuid: Uuid,
+
+	pub name: String,
+
+	/// Event variant the rule listens for, e.g. "VolumeMountChanged"
+	pub event_type: String,
+
+	/// Vec<EventFieldFilter> as JSON
+	pub filters: Json,
+
+	/// AutomationAction as JSON
+	pub action: Json,
+
+	pub enabled: bool,
+
+	/// Minimum seconds between two firings of the rule
+	pub cooldown_secs: i32,
+
+	pub created_at: DateTimeUtc,
+
+	pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A prior version of a file kept by a backup conduit
+///
+/// When a backup sync overwrites or deletes a file on the target, the old copy is moved
+/// into the target's versions area and recorded here, tied to the generation that
+/// superseded it.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "file_version")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+
+	#[sea_orm(unique)]
+	pub uuid: Uuid,
+
+	/// Foreign key to sync_conduit
+	pub conduit_id: i32,
+
+	/// Foreign key to the sync_generation that superseded this version
+	pub generation_id: i32,
+
+	/// Path of the file relative to the conduit's roots
+	pub relative_path: String,
+
+	/// Path of the kept copy relative to the target root
+	pub version_path: String,
+
+	/// Why the version was kept: "modified" or "deleted"
+	pub reason: String,
+
+	/// Size of the kept copy in bytes
+	pub size: i64,
+
+	/// Modification time of the file when it was superseded
+	pub modified_at: Option<DateTime<Utc>>,
+
+	/// When the version was moved into the versions area
+	pub archived_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	/// Foreign key to sync_conduit
+	#[sea_orm(
+		belongs_to = "super::sync_conduit::Entity",
+		from = "Column::ConduitId",
+		to = "super::sync_conduit::Column::Id"
+	)]
+	SyncConduit,
+
+	/// Foreign key to sync_generation
+	#[sea_orm(
+		belongs_to = "super::sync_generation::Entity",
+		from = "Column::GenerationId",
+		to = "super::sync_generation::Column::Id"
+	)]
+	SyncGeneration,
+}
+
+impl Related<super::sync_conduit::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::SyncConduit.def()
+	}
+}
+
+impl Related<super::sync_generation::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::SyncGeneration.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Why a version was kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VersionReason {
+	/// The source file changed and the target copy was overwritten
+	Modified,
+	/// The source file was removed and the target copy deleted
+	Deleted,
+}
+
+impl VersionReason {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Modified => "modified",
+			Self::Deleted => "deleted",
+		}
+	}
+
+	pub fn from_str(s: &str) -> Option<Self> {
+		match s {
+			"modified" => Some(Self::Modified),
+			"deleted" => Some(Self::Deleted),
+			_ => None,
+		}
+	}
+}
+
+impl std::fmt::Display for VersionReason {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.as_str())
+	}
+}
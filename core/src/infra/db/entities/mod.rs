@@ -11,12 +11,14 @@ pub mod device_state_tombstone;
 pub mod directory_paths;
 pub mod entry;
 pub mod entry_closure;
+pub mod file_version;
 pub mod image_media_data;
 pub mod location;
 pub mod mime_type;
 pub mod user_metadata;
 
 // Tagging system
+pub mod auto_tag_rule;
 pub mod tag;
 pub mod tag_closure;
 pub mod tag_relationship;
@@ -24,9 +26,12 @@ pub mod tag_usage_pattern;
 pub mod user_metadata_tag;
 
 pub mod audit_log;
+pub mod automation_dead_letter;
+pub mod automation_rule;
 pub mod collection;
 pub mod collection_entry;
 pub mod indexer_rule;
+pub mod saved_search;
 pub mod sidecar;
 pub mod sidecar_availability;
 pub mod space;
@@ -34,12 +39,17 @@ pub mod space_group;
 pub mod space_item;
 pub mod sync_conduit;
 pub mod sync_generation;
+pub mod sync_policy;
+pub mod trash_item;
+pub mod vault_location;
 pub mod video_media_data;
 pub mod volume;
 
 // Re-export all entities
 pub use audio_media_data::Entity as AudioMediaData;
 pub use audit_log::Entity as AuditLog;
+pub use automation_dead_letter::Entity as AutomationDeadLetter;
+pub use automation_rule::Entity as AutomationRule;
 pub use cloud_credential::Entity as CloudCredential;
 pub use collection::Entity as Collection;
 pub use collection_entry::Entity as CollectionEntry;
@@ -49,9 +59,11 @@ pub use device_state_tombstone::Entity as DeviceStateTombstone;
 pub use directory_paths::Entity as DirectoryPaths;
 pub use entry::Entity as Entry;
 pub use entry_closure::Entity as EntryClosure;
+pub use file_version::Entity as FileVersion;
 pub use image_media_data::Entity as ImageMediaData;
 pub use indexer_rule::Entity as IndexerRule;
 pub use location::Entity as Location;
+pub use saved_search::Entity as SavedSearch;
 pub use sidecar::Entity as Sidecar;
 pub use sidecar_availability::Entity as SidecarAvailability;
 pub use space::Entity as Space;
@@ -59,11 +71,15 @@ pub use space_group::Entity as SpaceGroup;
 pub use space_item::Entity as SpaceItem;
 pub use sync_conduit::Entity as SyncConduit;
 pub use sync_generation::Entity as SyncGeneration;
+pub use sync_policy::Entity as SyncPolicy;
+pub use trash_item::Entity as TrashItem;
 pub use user_metadata::Entity as UserMetadata;
+pub use vault_location::Entity as VaultLocation;
 pub use video_media_data::Entity as VideoMediaData;
 pub use volume::Entity as Volume;
 
 // Tagging entities
+pub use auto_tag_rule::Entity as AutoTagRule;
 pub use tag::Entity as Tag;
 pub use tag_closure::Entity as TagClosure;
 pub use tag_relationship::Entity as TagRelationship;
@@ -73,6 +89,8 @@ pub use user_metadata_tag::Entity as UserMetadataTag;
 // Re-export active models for easy access
 pub use audio_media_data::ActiveModel as AudioMediaDataActive;
 pub use audit_log::ActiveModel as AuditLogActive;
+pub use automation_dead_letter::ActiveModel as AutomationDeadLetterActive;
+pub use automation_rule::ActiveModel as AutomationRuleActive;
 pub use cloud_credential::ActiveModel as CloudCredentialActive;
 pub use collection::ActiveModel as CollectionActive;
 pub use collection_entry::ActiveModel as CollectionEntryActive;
@@ -82,9 +100,11 @@ pub use device_state_tombstone::ActiveModel as DeviceStateTombstoneActive;
 pub use directory_paths::ActiveModel as DirectoryPathsActive;
 pub use entry::ActiveModel as EntryActive;
 pub use entry_closure::ActiveModel as EntryClosureActive;
+pub use file_version::ActiveModel as FileVersionActive;
 pub use image_media_data::ActiveModel as ImageMediaDataActive;
 pub use indexer_rule::ActiveModel as IndexerRuleActive;
 pub use location::ActiveModel as LocationActive;
+pub use saved_search::ActiveModel as SavedSearchActive;
 pub use sidecar::ActiveModel as SidecarActive;
 pub use sidecar_availability::ActiveModel as SidecarAvailabilityActive;
 pub use space::ActiveModel as SpaceActive;
@@ -92,11 +112,15 @@ pub use space_group::ActiveModel as SpaceGroupActive;
 pub use space_item::ActiveModel as SpaceItemActive;
 pub use sync_conduit::ActiveModel as SyncConduitActive;
 pub use sync_generation::ActiveModel as SyncGenerationActive;
+pub use sync_policy::ActiveModel as SyncPolicyActive;
+pub use trash_item::ActiveModel as TrashItemActive;
 pub use user_metadata::ActiveModel as UserMetadataActive;
+pub use vault_location::ActiveModel as VaultLocationActive;
 pub use video_media_data::ActiveModel as VideoMediaDataActive;
 pub use volume::ActiveModel as VolumeActive;
 
 // Tagging active models
+pub use auto_tag_rule::ActiveModel as AutoTagRuleActive;
 pub use tag::ActiveModel as TagActive;
 pub use tag_closure::ActiveModel as TagClosureActive;
 pub use tag_relationship::ActiveModel as TagRelationshipActive;
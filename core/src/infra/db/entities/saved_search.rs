@@ -0,0 +1,202 @@
+//! Saved search entity
+//!
+//! A persisted `FileSearchInput` that behaves as a smart collection: its membership is
+//! whatever the search currently returns.
+
+use crate::infra::sync::{ChangeType, SharedChangeEntry, Syncable};
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::NotSet, Set};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "saved_search")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+
+	#[sea_orm(unique)]
+	pub uuid: Uuid,
+
+	pub name: String,
+
+	pub description: Option<String>,
+
+	/// FileSearchInput (query, scope, filters, sort and page size) as JSON
+	pub search: Json,
+
+	pub created_at: DateTime<Utc>,
+
+	pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// Syncable Implementation
+//
+// Saved searches are SHARED resources using HLC-ordered log-based replication. Only the
+// search definition syncs; each device evaluates membership against its own index.
+impl Syncable for Model {
+	const SYNC_MODEL: &'static str = "saved_search";
+
+	fn sync_id(&self) -> Uuid {
+		self.uuid
+	}
+
+	fn version(&self) -> i64 {
+		1
+	}
+
+	fn exclude_fields() -> Option<&'static [&'static str]> {
+		Some(&["id", "created_at", "updated_at"])
+	}
+
+	fn sync_depends_on() -> &'static [&'static str] {
+		&[]
+	}
+
+	async fn lookup_id_by_uuid(
+		uuid: Uuid,
+		db: &DatabaseConnection,
+	) -> Result<Option<i32>, sea_orm::DbErr> {
+		use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+		Ok(Entity::find()
+			.filter(Column::Uuid.eq(uuid))
+			.one(db)
+			.await?
+			.map(|r| r.id))
+	}
+
+	async fn lookup_uuid_by_id(
+		id: i32,
+		db: &DatabaseConnection,
+	) -> Result<Option<Uuid>, sea_orm::DbErr> {
+		Ok(Entity::find_by_id(id).one(db).await?.map(|r| r.uuid))
+	}
+
+	async fn batch_lookup_ids_by_uuids(
+		uuids: std::collections::HashSet<Uuid>,
+		db: &DatabaseConnection,
+	) -> Result<std::collections::HashMap<Uuid, i32>, sea_orm::DbErr> {
+		use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+		if uuids.is_empty() {
+			return Ok(std::collections::HashMap::new());
+		}
+		let records = Entity::find()
+			.filter(Column::Uuid.is_in(uuids))
+			.all(db)
+			.await?;
+		Ok(records.into_iter().map(|r| (r.uuid, r.id)).collect())
+	}
+
+	async fn batch_lookup_uuids_by_ids(
+		ids: std::collections::HashSet<i32>,
+		db: &DatabaseConnection,
+	) -> Result<std::collections::HashMap<i32, Uuid>, sea_orm::DbErr> {
+		use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+		if ids.is_empty() {
+			return Ok(std::collections::HashMap::new());
+		}
+		let records = Entity::find().filter(Column::Id.is_in(ids)).all(db).await?;
+		Ok(records.into_iter().map(|r| (r.id, r.uuid)).collect())
+	}
+
+	async fn query_for_sync(
+		_device_id: Option<Uuid>,
+		since: Option<chrono::DateTime<chrono::Utc>>,
+		_cursor: Option<(chrono::DateTime<chrono::Utc>, Uuid)>,
+		batch_size: usize,
+		db: &DatabaseConnection,
+	) -> Result<Vec<(Uuid, serde_json::Value, chrono::DateTime<chrono::Utc>)>, sea_orm::DbErr> {
+		use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+
+		let mut query = Entity::find();
+
+		if let Some(since_time) = since {
+			query = query.filter(Column::UpdatedAt.gte(since_time));
+		}
+
+		query = query.limit(batch_size as u64);
+
+		let results = query.all(db).await?;
+
+		let mut sync_results = Vec::new();
+		for search in results {
+			let json = match search.to_sync_json() {
+				Ok(j) => j,
+				Err(e) => {
+					tracing::warn!(error = %e, uuid = %search.uuid, "Failed to serialize saved search for sync");
+					continue;
+				}
+			};
+
+			sync_results.push((search.uuid, json, search.updated_at));
+		}
+
+		Ok(sync_results)
+	}
+
+	async fn apply_shared_change(
+		entry: SharedChangeEntry,
+		db: &DatabaseConnection,
+	) -> Result<(), sea_orm::DbErr> {
+		match entry.change_type {
+			ChangeType::Insert | ChangeType::Update => {
+				let data = entry.data.as_object().ok_or_else(|| {
+					sea_orm::DbErr::Custom("Saved search data is not an object".to_string())
+				})?;
+
+				let field = |name: &str| data.get(name).cloned().unwrap_or(serde_json::Value::Null);
+				let parse_err = |name: &str, e: serde_json::Error| {
+					sea_orm::DbErr::Custom(format!("Invalid {}: {}", name, e))
+				};
+
+				let uuid: Uuid =
+					serde_json::from_value(field("uuid")).map_err(|e| parse_err("uuid", e))?;
+
+				let active = ActiveModel {
+					id: NotSet,
+					uuid: Set(uuid),
+					name: Set(
+						serde_json::from_value(field("name")).map_err(|e| parse_err("name", e))?
+					),
+					description: Set(serde_json::from_value(field("description"))
+						.map_err(|e| parse_err("description", e))?),
+					search: Set(field("search")),
+					created_at: Set(chrono::Utc::now()),
+					updated_at: Set(chrono::Utc::now()),
+				};
+
+				Entity::insert(active)
+					.on_conflict(
+						sea_orm::sea_query::OnConflict::column(Column::Uuid)
+							.update_columns([
+								Column::Name,
+								Column::Description,
+								Column::Search,
+								Column::UpdatedAt,
+							])
+							.to_owned(),
+					)
+					.exec(db)
+					.await?;
+			}
+
+			ChangeType::Delete => {
+				Entity::delete_many()
+					.filter(Column::Uuid.eq(entry.record_uuid))
+					.exec(db)
+					.await?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+// Register with sync system via inventory
+crate::register_syncable_shared!(Model, "saved_search", "saved_search");
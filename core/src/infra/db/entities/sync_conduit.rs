@@ -1,3 +1,4 @@
+use crate::infra::job::schedule::ScheduleTrigger;
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -22,13 +23,13 @@ pub struct Model {
 	/// Target directory entry ID (must be a directory)
 	pub target_entry_id: i32,
 
-	/// Sync mode: "mirror", "bidirectional", or "selective"
+	/// Sync mode: "mirror", "bidirectional", "selective" or "backup"
 	pub sync_mode: String,
 
 	/// Whether this conduit is active
 	pub enabled: bool,
 
-	/// Sync schedule: "instant", "interval:5m", or "manual"
+	/// Sync schedule: "instant", "manual", "interval:5m" or "cron:<expression>"
 	pub schedule: String,
 
 	/// Whether to use indexer rules for filtering
@@ -61,6 +62,9 @@ pub struct Model {
 	/// Total bytes transferred
 	pub bytes_transferred: i64,
 
+	/// Version retention rules for backup mode (`RetentionPolicy`), None for the defaults
+	pub retention_policy: Option<Json>,
+
 	pub created_at: DateTime<Utc>,
 	pub updated_at: DateTime<Utc>,
 }
@@ -86,6 +90,10 @@ pub enum Relation {
 	/// One-to-many relationship with sync generations
 	#[sea_orm(has_many = "super::sync_generation::Entity")]
 	SyncGenerations,
+
+	/// One-to-many relationship with kept file versions
+	#[sea_orm(has_many = "super::file_version::Entity")]
+	FileVersions,
 }
 
 impl Related<super::sync_generation::Entity> for Entity {
@@ -94,8 +102,28 @@ impl Related<super::sync_generation::Entity> for Entity {
 	}
 }
 
+impl Related<super::file_version::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::FileVersions.def()
+	}
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
+/// Parse a conduit schedule string, rejecting anything that isn't a known form
+pub fn parse_schedule(schedule: &str) -> Result<Option<ScheduleTrigger>, String> {
+	match schedule {
+		"instant" | "manual" => Ok(None),
+		s if s.starts_with("interval:") || s.starts_with("cron:") => {
+			ScheduleTrigger::parse(s).map(Some)
+		}
+		other => Err(format!(
+			"Invalid sync schedule '{}': expected instant, manual, interval:<duration> or cron:<expression>",
+			other
+		)),
+	}
+}
+
 /// Sync mode variants
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SyncMode {
@@ -105,6 +133,8 @@ pub enum SyncMode {
 	Bidirectional,
 	/// Intelligent local storage management (future)
 	Selective,
+	/// One-way sync that keeps prior versions of overwritten and deleted files
+	Backup,
 }
 
 impl SyncMode {
@@ -113,6 +143,7 @@ impl SyncMode {
 			Self::Mirror => "mirror",
 			Self::Bidirectional => "bidirectional",
 			Self::Selective => "selective",
+			Self::Backup => "backup",
 		}
 	}
 
@@ -121,6 +152,7 @@ impl SyncMode {
 			"mirror" => Some(Self::Mirror),
 			"bidirectional" => Some(Self::Bidirectional),
 			"selective" => Some(Self::Selective),
+			"backup" => Some(Self::Backup),
 			_ => None,
 		}
 	}
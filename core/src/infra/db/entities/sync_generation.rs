@@ -39,6 +39,9 @@ pub struct Model {
 	/// Number of errors encountered during this sync
 	pub errors_encountered: i32,
 
+	/// Number of prior file versions kept during this sync (backup mode)
+	pub files_versioned: i32,
+
 	/// When verification was performed (None if not yet verified)
 	pub verified_at: Option<DateTime<Utc>>,
 
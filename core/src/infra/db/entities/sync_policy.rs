@@ -0,0 +1,213 @@
+//! Sync policy entity
+//!
+//! A device's selective sync subscription: which data other devices replicate to it.
+//! One row per subscribing device, keyed by the device's UUID.
+
+use crate::infra::sync::{ChangeType, SharedChangeEntry, Syncable};
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::NotSet, Set};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "sync_policy")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+
+	/// Subscribing device, also the sync ID of the policy
+	#[sea_orm(unique)]
+	pub device_uuid: Uuid,
+
+	/// SyncTier as snake_case string ("full", "metadata_only", "selective")
+	pub tier: String,
+
+	/// Location UUIDs whose entries replicate (selective tier)
+	pub locations: Json,
+
+	/// Volume UUIDs whose entries replicate (selective tier)
+	pub volumes: Json,
+
+	/// Tag UUIDs whose tagged entries replicate (selective tier)
+	pub tags: Json,
+
+	/// Model types that replicate, empty for all (selective tier)
+	pub model_types: Json,
+
+	pub created_at: DateTime<Utc>,
+
+	pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// Syncable Implementation
+//
+// Sync policies are SHARED resources so the devices that hold the data know what each
+// peer subscribes to. They always replicate, whatever the receiving device's own policy.
+impl Syncable for Model {
+	const SYNC_MODEL: &'static str = "sync_policy";
+
+	fn sync_id(&self) -> Uuid {
+		self.device_uuid
+	}
+
+	fn version(&self) -> i64 {
+		1
+	}
+
+	fn exclude_fields() -> Option<&'static [&'static str]> {
+		Some(&["id", "created_at", "updated_at"])
+	}
+
+	fn sync_depends_on() -> &'static [&'static str] {
+		&[]
+	}
+
+	async fn lookup_id_by_uuid(
+		uuid: Uuid,
+		db: &DatabaseConnection,
+	) -> Result<Option<i32>, sea_orm::DbErr> {
+		use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+		Ok(Entity::find()
+			.filter(Column::DeviceUuid.eq(uuid))
+			.one(db)
+			.await?
+			.map(|r| r.id))
+	}
+
+	async fn lookup_uuid_by_id(
+		id: i32,
+		db: &DatabaseConnection,
+	) -> Result<Option<Uuid>, sea_orm::DbErr> {
+		Ok(Entity::find_by_id(id).one(db).await?.map(|r| r.device_uuid))
+	}
+
+	async fn batch_lookup_ids_by_uuids(
+		uuids: std::collections::HashSet<Uuid>,
+		db: &DatabaseConnection,
+	) -> Result<std::collections::HashMap<Uuid, i32>, sea_orm::DbErr> {
+		use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+		if uuids.is_empty() {
+			return Ok(std::collections::HashMap::new());
+		}
+		let records = Entity::find()
+			.filter(Column::DeviceUuid.is_in(uuids))
+			.all(db)
+			.await?;
+		Ok(records.into_iter().map(|r| (r.device_uuid, r.id)).collect())
+	}
+
+	async fn batch_lookup_uuids_by_ids(
+		ids: std::collections::HashSet<i32>,
+		db: &DatabaseConnection,
+	) -> Result<std::collections::HashMap<i32, Uuid>, sea_orm::DbErr> {
+		use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+		if ids.is_empty() {
+			return Ok(std::collections::HashMap::new());
+		}
+		let records = Entity::find().filter(Column::Id.is_in(ids)).all(db).await?;
+		Ok(records.into_iter().map(|r| (r.id, r.device_uuid)).collect())
+	}
+
+	async fn query_for_sync(
+		_device_id: Option<Uuid>,
+		since: Option<chrono::DateTime<chrono::Utc>>,
+		_cursor: Option<(chrono::DateTime<chrono::Utc>, Uuid)>,
+		batch_size: usize,
+		db: &DatabaseConnection,
+	) -> Result<Vec<(Uuid, serde_json::Value, chrono::DateTime<chrono::Utc>)>, sea_orm::DbErr> {
+		use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+
+		let mut query = Entity::find();
+
+		if let Some(since_time) = since {
+			query = query.filter(Column::UpdatedAt.gte(since_time));
+		}
+
+		query = query.limit(batch_size as u64);
+
+		let results = query.all(db).await?;
+
+		let mut sync_results = Vec::new();
+		for policy in results {
+			let json = match policy.to_sync_json() {
+				Ok(j) => j,
+				Err(e) => {
+					tracing::warn!(error = %e, device = %policy.device_uuid, "Failed to serialize sync policy for sync");
+					continue;
+				}
+			};
+
+			sync_results.push((policy.device_uuid, json, policy.updated_at));
+		}
+
+		Ok(sync_results)
+	}
+
+	async fn apply_shared_change(
+		entry: SharedChangeEntry,
+		db: &DatabaseConnection,
+	) -> Result<(), sea_orm::DbErr> {
+		match entry.change_type {
+			ChangeType::Insert | ChangeType::Update => {
+				let data = entry.data.as_object().ok_or_else(|| {
+					sea_orm::DbErr::Custom("Sync policy data is not an object".to_string())
+				})?;
+
+				let field = |name: &str| data.get(name).cloned().unwrap_or(serde_json::Value::Null);
+				let list = |name: &str| match field(name) {
+					serde_json::Value::Null => serde_json::json!([]),
+					value => value,
+				};
+
+				let tier: String = serde_json::from_value(field("tier"))
+					.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid tier: {}", e)))?;
+
+				let active = ActiveModel {
+					id: NotSet,
+					device_uuid: Set(entry.record_uuid),
+					tier: Set(tier),
+					locations: Set(list("locations")),
+					volumes: Set(list("volumes")),
+					tags: Set(list("tags")),
+					model_types: Set(list("model_types")),
+					created_at: Set(chrono::Utc::now()),
+					updated_at: Set(chrono::Utc::now()),
+				};
+
+				Entity::insert(active)
+					.on_conflict(
+						sea_orm::sea_query::OnConflict::column(Column::DeviceUuid)
+							.update_columns([
+								Column::Tier,
+								Column::Locations,
+								Column::Volumes,
+								Column::Tags,
+								Column::ModelTypes,
+								Column::UpdatedAt,
+							])
+							.to_owned(),
+					)
+					.exec(db)
+					.await?;
+			}
+
+			ChangeType::Delete => {
+				Entity::delete_many()
+					.filter(Column::DeviceUuid.eq(entry.record_uuid))
+					.exec(db)
+					.await?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+// Register with sync system via inventory
+crate::register_syncable_shared!(Model, "sync_policy", "sync_policy");
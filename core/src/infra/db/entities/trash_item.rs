@@ -0,0 +1,51 @@
+//! Trash item entity
+//!
+//! Ledger of files and directories Spacedrive moved to the system trash.
+//! Rows are device-local (the trash lives on this machine's disk) and are not synced.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "trash_item")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+
+	#[sea_orm(unique)]
+	pub uuid: Uuid,
+
+	/// Serialized `SdPath` the item was trashed from
+	pub original_path: Json,
+
+	/// Absolute path of the item inside the system trash
+	pub trash_path: String,
+
+	/// File name at the time of deletion (including extension)
+	pub name: String,
+
+	/// Entry type: 0=File, 1=Directory, 2=Symlink
+	pub kind: i32,
+
+	/// Size in bytes (aggregate size for directories)
+	pub size: i64,
+
+	/// UUID of the trashed root entry, if the item was indexed
+	pub entry_uuid: Option<Uuid>,
+
+	/// Content identity of the trashed root entry, if it had one
+	pub content_identity_uuid: Option<Uuid>,
+
+	/// Snapshot of the trashed entry and its descendants (`Vec<TrashedEntry>`)
+	pub entries: Json,
+
+	/// Tags applied to the trashed entries (`Vec<TrashedTag>`)
+	pub tags: Json,
+
+	pub trashed_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
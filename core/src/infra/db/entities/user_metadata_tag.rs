@@ -129,6 +129,7 @@ pub enum TagSource {
 	AI,
 	Import,
 	Sync,
+	Rule,
 }
 
 impl TagSource {
@@ -138,6 +139,7 @@ impl TagSource {
 			TagSource::AI => "ai",
 			TagSource::Import => "import",
 			TagSource::Sync => "sync",
+			TagSource::Rule => "rule",
 		}
 	}
 
@@ -147,6 +149,7 @@ impl TagSource {
 			"ai" => Some(TagSource::AI),
 			"import" => Some(TagSource::Import),
 			"sync" => Some(TagSource::Sync),
+			"rule" => Some(TagSource::Rule),
 			_ => None,
 		}
 	}
@@ -0,0 +1,39 @@
+//! Vault location entity
+//!
+//! Marks a location as a vault: its files are stored encrypted and their plaintext
+//! metadata lives only in the vault's encrypted manifest, never in the library index.
+//! Rows are device-local and are not synced.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "vault_location")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+
+	#[sea_orm(unique)]
+	pub location_id: i32,
+
+	pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::location::Entity",
+		from = "Column::LocationId",
+		to = "super::location::Column::Id",
+		on_delete = "Cascade"
+	)]
+	Location,
+}
+
+impl Related<super::location::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Location.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
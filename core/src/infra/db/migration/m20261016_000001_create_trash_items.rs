@@ -0,0 +1,92 @@
+//! Create trash_item table for the trash ledger
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.create_table(
+				Table::create()
+					.table(TrashItem::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(TrashItem::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(ColumnDef::new(TrashItem::Uuid).uuid().not_null().unique_key())
+					.col(ColumnDef::new(TrashItem::OriginalPath).json().not_null())
+					.col(ColumnDef::new(TrashItem::TrashPath).string().not_null())
+					.col(ColumnDef::new(TrashItem::Name).string().not_null())
+					.col(ColumnDef::new(TrashItem::Kind).integer().not_null())
+					.col(
+						ColumnDef::new(TrashItem::Size)
+							.big_integer()
+							.not_null()
+							.default(0),
+					)
+					.col(ColumnDef::new(TrashItem::EntryUuid).uuid())
+					.col(ColumnDef::new(TrashItem::ContentIdentityUuid).uuid())
+					.col(ColumnDef::new(TrashItem::Entries).json().not_null())
+					.col(ColumnDef::new(TrashItem::Tags).json().not_null())
+					.col(
+						ColumnDef::new(TrashItem::TrashedAt)
+							.timestamp_with_time_zone()
+							.not_null(),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_index(
+				Index::create()
+					.name("idx_trash_item_trashed_at")
+					.table(TrashItem::Table)
+					.col(TrashItem::TrashedAt)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_index(
+				Index::create()
+					.name("idx_trash_item_entry_uuid")
+					.table(TrashItem::Table)
+					.col(TrashItem::EntryUuid)
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_table(Table::drop().table(TrashItem::Table).to_owned())
+			.await
+	}
+}
+
+#[derive(DeriveIden)]
+enum TrashItem {
+	Table,
+	Id,
+	Uuid,
+	OriginalPath,
+	TrashPath,
+	Name,
+	Kind,
+	Size,
+	EntryUuid,
+	ContentIdentityUuid,
+	Entries,
+	Tags,
+	TrashedAt,
+}
@@ -0,0 +1,82 @@
+//! FTS5 Content Search Index Migration
+//!
+//! Creates the FTS5 virtual table holding text extracted from file bodies.
+//! Rows are keyed by content identity (rowid = content_identities.id) so every
+//! entry sharing the same content shares one index row. The indexed
+//! content_hash lets the extraction job detect stale rows, and triggers drop
+//! rows whose content identity is removed or rehashed.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		// Create FTS5 virtual table for extracted file text
+		manager
+			.get_connection()
+			.execute_unprepared(
+				r#"
+                CREATE VIRTUAL TABLE IF NOT EXISTS content_search_index USING fts5(
+                    content_hash UNINDEXED,
+                    body,
+                    tokenize="unicode61 remove_diacritics 2",
+                    prefix='2,3'
+                );
+                "#,
+			)
+			.await?;
+
+		// Drop extracted text when its content identity is deleted
+		manager
+			.get_connection()
+			.execute_unprepared(
+				r#"
+                CREATE TRIGGER IF NOT EXISTS content_search_delete
+                AFTER DELETE ON content_identities
+                BEGIN
+                    DELETE FROM content_search_index WHERE rowid = old.id;
+                END;
+                "#,
+			)
+			.await?;
+
+		// Drop extracted text when a content identity is rehashed so it gets re-extracted
+		manager
+			.get_connection()
+			.execute_unprepared(
+				r#"
+                CREATE TRIGGER IF NOT EXISTS content_search_rehash
+                AFTER UPDATE OF content_hash ON content_identities
+                WHEN old.content_hash IS NOT new.content_hash
+                BEGIN
+                    DELETE FROM content_search_index WHERE rowid = old.id;
+                END;
+                "#,
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.get_connection()
+			.execute_unprepared("DROP TRIGGER IF EXISTS content_search_rehash;")
+			.await?;
+
+		manager
+			.get_connection()
+			.execute_unprepared("DROP TRIGGER IF EXISTS content_search_delete;")
+			.await?;
+
+		manager
+			.get_connection()
+			.execute_unprepared("DROP TABLE IF EXISTS content_search_index;")
+			.await?;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,64 @@
+//! Create vault_location table marking locations whose files are encrypted at rest
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.create_table(
+				Table::create()
+					.table(VaultLocation::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(VaultLocation::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(VaultLocation::LocationId)
+							.integer()
+							.not_null()
+							.unique_key(),
+					)
+					.col(
+						ColumnDef::new(VaultLocation::CreatedAt)
+							.timestamp_with_time_zone()
+							.not_null(),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.from(VaultLocation::Table, VaultLocation::LocationId)
+							.to(Locations::Table, Locations::Id)
+							.on_delete(ForeignKeyAction::Cascade),
+					)
+					.to_owned(),
+			)
+			.await
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_table(Table::drop().table(VaultLocation::Table).to_owned())
+			.await
+	}
+}
+
+#[derive(DeriveIden)]
+enum VaultLocation {
+	Table,
+	Id,
+	LocationId,
+	CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Locations {
+	Table,
+	Id,
+}
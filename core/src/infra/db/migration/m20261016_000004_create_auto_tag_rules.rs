@@ -0,0 +1,107 @@
+//! Create auto_tag_rule table for declarative, rule-based tagging
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.create_table(
+				Table::create()
+					.table(AutoTagRule::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(AutoTagRule::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(AutoTagRule::Uuid)
+							.uuid()
+							.not_null()
+							.unique_key(),
+					)
+					.col(ColumnDef::new(AutoTagRule::Name).string().not_null())
+					.col(ColumnDef::new(AutoTagRule::TagUuid).uuid().not_null())
+					.col(ColumnDef::new(AutoTagRule::Conditions).json().not_null())
+					.col(
+						ColumnDef::new(AutoTagRule::MatchAll)
+							.boolean()
+							.not_null()
+							.default(true),
+					)
+					.col(
+						ColumnDef::new(AutoTagRule::Enabled)
+							.boolean()
+							.not_null()
+							.default(true),
+					)
+					.col(
+						ColumnDef::new(AutoTagRule::CreatedAt)
+							.timestamp_with_time_zone()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(AutoTagRule::UpdatedAt)
+							.timestamp_with_time_zone()
+							.not_null(),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		// Rule-applied tags are found by source and rule UUID when a rule is deleted
+		manager
+			.create_index(
+				Index::create()
+					.name("idx_user_metadata_tag_source_context")
+					.table(UserMetadataTag::Table)
+					.col(UserMetadataTag::Source)
+					.col(UserMetadataTag::AppliedContext)
+					.if_not_exists()
+					.to_owned(),
+			)
+			.await
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_index(
+				Index::drop()
+					.name("idx_user_metadata_tag_source_context")
+					.table(UserMetadataTag::Table)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.drop_table(Table::drop().table(AutoTagRule::Table).to_owned())
+			.await
+	}
+}
+
+#[derive(DeriveIden)]
+enum AutoTagRule {
+	Table,
+	Id,
+	Uuid,
+	Name,
+	TagUuid,
+	Conditions,
+	MatchAll,
+	Enabled,
+	CreatedAt,
+	UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum UserMetadataTag {
+	Table,
+	Source,
+	AppliedContext,
+}
@@ -0,0 +1,64 @@
+//! Create saved_search table for persisted searches shown as smart collections
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.create_table(
+				Table::create()
+					.table(SavedSearch::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(SavedSearch::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(SavedSearch::Uuid)
+							.uuid()
+							.not_null()
+							.unique_key(),
+					)
+					.col(ColumnDef::new(SavedSearch::Name).string().not_null())
+					.col(ColumnDef::new(SavedSearch::Description).string())
+					.col(ColumnDef::new(SavedSearch::Search).json().not_null())
+					.col(
+						ColumnDef::new(SavedSearch::CreatedAt)
+							.timestamp_with_time_zone()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(SavedSearch::UpdatedAt)
+							.timestamp_with_time_zone()
+							.not_null(),
+					)
+					.to_owned(),
+			)
+			.await
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_table(Table::drop().table(SavedSearch::Table).to_owned())
+			.await
+	}
+}
+
+#[derive(DeriveIden)]
+enum SavedSearch {
+	Table,
+	Id,
+	Uuid,
+	Name,
+	Description,
+	Search,
+	CreatedAt,
+	UpdatedAt,
+}
@@ -0,0 +1,178 @@
+//! Create automation_rule and automation_dead_letter tables for event automation
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.create_table(
+				Table::create()
+					.table(AutomationRule::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(AutomationRule::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(AutomationRule::Uuid)
+							.uuid()
+							.not_null()
+							.unique_key(),
+					)
+					.col(ColumnDef::new(AutomationRule::Name).string().not_null())
+					.col(
+						ColumnDef::new(AutomationRule::EventType)
+							.string()
+							.not_null(),
+					)
+					.col(ColumnDef::new(AutomationRule::Filters).json().not_null())
+					.col(ColumnDef::new(AutomationRule::Action).json().not_null())
+					.col(
+						ColumnDef::new(AutomationRule::Enabled)
+							.boolean()
+							.not_null()
+							.default(true),
+					)
+					.col(
+						ColumnDef::new(AutomationRule::CooldownSecs)
+							.integer()
+							.not_null()
+							.default(0),
+					)
+					.col(
+						ColumnDef::new(AutomationRule::CreatedAt)
+							.timestamp_with_time_zone()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(AutomationRule::UpdatedAt)
+							.timestamp_with_time_zone()
+							.not_null(),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_table(
+				Table::create()
+					.table(AutomationDeadLetter::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(AutomationDeadLetter::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(AutomationDeadLetter::Uuid)
+							.uuid()
+							.not_null()
+							.unique_key(),
+					)
+					.col(
+						ColumnDef::new(AutomationDeadLetter::RuleUuid)
+							.uuid()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(AutomationDeadLetter::RuleName)
+							.string()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(AutomationDeadLetter::Event)
+							.json()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(AutomationDeadLetter::Action)
+							.json()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(AutomationDeadLetter::Error)
+							.string()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(AutomationDeadLetter::Attempts)
+							.integer()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(AutomationDeadLetter::CreatedAt)
+							.timestamp_with_time_zone()
+							.not_null(),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_index(
+				Index::create()
+					.name("idx_automation_dead_letter_created_at")
+					.table(AutomationDeadLetter::Table)
+					.col(AutomationDeadLetter::CreatedAt)
+					.to_owned(),
+			)
+			.await
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_index(
+				Index::drop()
+					.name("idx_automation_dead_letter_created_at")
+					.table(AutomationDeadLetter::Table)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.drop_table(Table::drop().table(AutomationDeadLetter::Table).to_owned())
+			.await?;
+
+		manager
+			.drop_table(Table::drop().table(AutomationRule::Table).to_owned())
+			.await
+	}
+}
+
+#[derive(DeriveIden)]
+enum AutomationRule {
+	Table,
+	Id,
+	Uuid,
+	Name,
+	EventType,
+	Filters,
+	Action,
+	Enabled,
+	CooldownSecs,
+	CreatedAt,
+	UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum AutomationDeadLetter {
+	Table,
+	Id,
+	Uuid,
+	RuleUuid,
+	RuleName,
+	Event,
+	Action,
+	Error,
+	Attempts,
+	CreatedAt,
+}
@@ -0,0 +1,73 @@
+//! Create sync_policy table for per-device selective sync subscriptions
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.create_table(
+				Table::create()
+					.table(SyncPolicy::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(SyncPolicy::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(SyncPolicy::DeviceUuid)
+							.uuid()
+							.not_null()
+							.unique_key(),
+					)
+					.col(
+						ColumnDef::new(SyncPolicy::Tier)
+							.string()
+							.not_null()
+							.default("full"),
+					)
+					.col(ColumnDef::new(SyncPolicy::Locations).json().not_null())
+					.col(ColumnDef::new(SyncPolicy::Volumes).json().not_null())
+					.col(ColumnDef::new(SyncPolicy::Tags).json().not_null())
+					.col(ColumnDef::new(SyncPolicy::ModelTypes).json().not_null())
+					.col(
+						ColumnDef::new(SyncPolicy::CreatedAt)
+							.timestamp_with_time_zone()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(SyncPolicy::UpdatedAt)
+							.timestamp_with_time_zone()
+							.not_null(),
+					)
+					.to_owned(),
+			)
+			.await
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_table(Table::drop().table(SyncPolicy::Table).to_owned())
+			.await
+	}
+}
+
+#[derive(DeriveIden)]
+enum SyncPolicy {
+	Table,
+	Id,
+	DeviceUuid,
+	Tier,
+	Locations,
+	Volumes,
+	Tags,
+	ModelTypes,
+	CreatedAt,
+	UpdatedAt,
+}
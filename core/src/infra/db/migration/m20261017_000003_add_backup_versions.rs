@@ -0,0 +1,173 @@
+//! Add versioned backup support to file sync conduits
+//!
+//! Adds a retention policy to conduits, a per-generation count of versioned files, and
+//! the file_version table recording prior versions kept in a backup target's versions area.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		// Retention rules as JSON (backup mode; None uses the default rules)
+		manager
+			.alter_table(
+				Table::alter()
+					.table(SyncConduit::Table)
+					.add_column(ColumnDef::new(SyncConduit::RetentionPolicy).json())
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(SyncGeneration::Table)
+					.add_column(
+						ColumnDef::new(SyncGeneration::FilesVersioned)
+							.integer()
+							.not_null()
+							.default(0),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_table(
+				Table::create()
+					.table(FileVersion::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(FileVersion::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(FileVersion::Uuid)
+							.uuid()
+							.not_null()
+							.unique_key(),
+					)
+					.col(ColumnDef::new(FileVersion::ConduitId).integer().not_null())
+					.col(
+						ColumnDef::new(FileVersion::GenerationId)
+							.integer()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(FileVersion::RelativePath)
+							.string()
+							.not_null(),
+					)
+					.col(ColumnDef::new(FileVersion::VersionPath).string().not_null())
+					.col(ColumnDef::new(FileVersion::Reason).string().not_null())
+					.col(
+						ColumnDef::new(FileVersion::Size)
+							.big_integer()
+							.not_null()
+							.default(0),
+					)
+					.col(ColumnDef::new(FileVersion::ModifiedAt).timestamp_with_time_zone())
+					.col(
+						ColumnDef::new(FileVersion::ArchivedAt)
+							.timestamp_with_time_zone()
+							.not_null(),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk_file_version_conduit")
+							.from(FileVersion::Table, FileVersion::ConduitId)
+							.to(SyncConduit::Table, SyncConduit::Id)
+							.on_delete(ForeignKeyAction::Cascade),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk_file_version_generation")
+							.from(FileVersion::Table, FileVersion::GenerationId)
+							.to(SyncGeneration::Table, SyncGeneration::Id)
+							.on_delete(ForeignKeyAction::Cascade),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		// Versions are listed and pruned per file of a conduit
+		manager
+			.create_index(
+				Index::create()
+					.name("idx_file_version_conduit_path")
+					.table(FileVersion::Table)
+					.col(FileVersion::ConduitId)
+					.col(FileVersion::RelativePath)
+					.to_owned(),
+			)
+			.await
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_index(
+				Index::drop()
+					.name("idx_file_version_conduit_path")
+					.table(FileVersion::Table)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.drop_table(Table::drop().table(FileVersion::Table).to_owned())
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(SyncGeneration::Table)
+					.drop_column(SyncGeneration::FilesVersioned)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(SyncConduit::Table)
+					.drop_column(SyncConduit::RetentionPolicy)
+					.to_owned(),
+			)
+			.await
+	}
+}
+
+#[derive(DeriveIden)]
+enum SyncConduit {
+	Table,
+	Id,
+	RetentionPolicy,
+}
+
+#[derive(DeriveIden)]
+enum SyncGeneration {
+	Table,
+	Id,
+	FilesVersioned,
+}
+
+#[derive(DeriveIden)]
+enum FileVersion {
+	Table,
+	Id,
+	Uuid,
+	ConduitId,
+	GenerationId,
+	RelativePath,
+	VersionPath,
+	Reason,
+	Size,
+	ModifiedAt,
+	ArchivedAt,
+}
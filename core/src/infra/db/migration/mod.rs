@@ -38,6 +38,14 @@ mod m20260104_000001_replace_device_id_with_volume_id;
 mod m20260105_000001_add_volume_id_to_locations;
 mod m20260114_000001_fix_search_index_include_directories;
 mod m20260123_000001_remove_legacy_sync_columns;
+mod m20261016_000001_create_trash_items;
+mod m20261016_000002_create_content_search_index;
+mod m20261016_000003_create_vault_locations;
+mod m20261016_000004_create_auto_tag_rules;
+mod m20261016_000005_create_saved_searches;
+mod m20261017_000001_create_automation_rules;
+mod m20261017_000002_create_sync_policies;
+mod m20261017_000003_add_backup_versions;
 
 pub struct Migrator;
 
@@ -81,6 +89,14 @@ impl MigratorTrait for Migrator {
 			Box::new(m20260105_000001_add_volume_id_to_locations::Migration),
 			Box::new(m20260114_000001_fix_search_index_include_directories::Migration),
 			Box::new(m20260123_000001_remove_legacy_sync_columns::Migration),
+			Box::new(m20261016_000001_create_trash_items::Migration),
+			Box::new(m20261016_000002_create_content_search_index::Migration),
+			Box::new(m20261016_000003_create_vault_locations::Migration),
+			Box::new(m20261016_000004_create_auto_tag_rules::Migration),
+			Box::new(m20261016_000005_create_saved_searches::Migration),
+			Box::new(m20261017_000001_create_automation_rules::Migration),
+			Box::new(m20261017_000002_create_sync_policies::Migration),
+			Box::new(m20261017_000003_add_backup_versions::Migration),
 		]
 	}
 }
@@ -1,8 +1,10 @@
 //! Database infrastructure using SeaORM
 
 use sea_orm::ConnectionTrait;
-use sea_orm::{ConnectOptions, Database as SeaDatabase, DatabaseConnection, DbErr};
-use sea_orm_migration::MigratorTrait;
+use sea_orm::{
+	ConnectOptions, Database as SeaDatabase, DatabaseConnection, DbBackend, DbErr, Statement,
+};
+use sea_orm_migration::{MigrationName, MigratorTrait};
 use std::path::Path;
 use std::time::Duration;
 use tracing::info;
@@ -53,7 +55,6 @@ impl Database {
 
 		let conn = SeaDatabase::connect(opt).await?;
 		// Apply SQLite PRAGMAs for better write throughput (URL is sqlite:// so this is safe)
-		use sea_orm::Statement;
 		let _ = conn
 			.execute(Statement::from_string(
 				sea_orm::DatabaseBackend::Sqlite,
@@ -117,7 +118,6 @@ impl Database {
 
 		let conn = SeaDatabase::connect(opt).await?;
 		// Apply SQLite PRAGMAs (URL is sqlite:// so this is safe)
-		use sea_orm::Statement;
 		let _ = conn
 			.execute(Statement::from_string(
 				sea_orm::DatabaseBackend::Sqlite,
@@ -161,8 +161,59 @@ impl Database {
 		Ok(())
 	}
 
+	/// Name of the most recent migration applied to this database
+	pub async fn schema_version(&self) -> Result<Option<String>, DbErr> {
+		Ok(self.applied_migrations().await?.pop())
+	}
+
+	/// Applied migrations this build doesn't know, i.e. the database was written by a newer version
+	pub async fn unknown_migrations(&self) -> Result<Vec<String>, DbErr> {
+		let known: std::collections::HashSet<String> = migration::Migrator::migrations()
+			.iter()
+			.map(|m| m.name().to_string())
+			.collect();
+
+		Ok(self
+			.applied_migrations()
+			.await?
+			.into_iter()
+			.filter(|name| !known.contains(name))
+			.collect())
+	}
+
+	/// Write a consistent copy of the database to `path`, which must not exist
+	pub async fn snapshot_to(&self, path: &Path) -> Result<(), DbErr> {
+		self.conn
+			.execute(Statement::from_sql_and_values(
+				DbBackend::Sqlite,
+				"VACUUM INTO ?",
+				[path.to_string_lossy().to_string().into()],
+			))
+			.await?;
+		Ok(())
+	}
+
+	/// Close the connection pool
+	pub async fn close(self) -> Result<(), DbErr> {
+		self.conn.close().await
+	}
+
 	/// Get the database connection
 	pub fn conn(&self) -> &DatabaseConnection {
 		&self.conn
 	}
+
+	async fn applied_migrations(&self) -> Result<Vec<String>, DbErr> {
+		let rows = self
+			.conn
+			.query_all(Statement::from_string(
+				DbBackend::Sqlite,
+				"SELECT version FROM seaql_migrations ORDER BY version",
+			))
+			.await?;
+
+		rows.iter()
+			.map(|row| row.try_get_by_index::<String>(0))
+			.collect()
+	}
 }
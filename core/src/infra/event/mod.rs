@@ -329,6 +329,17 @@ pub enum Event {
 		resource_id: Uuid,
 	},
 
+	// Saved search events
+	/// A smart collection's members changed when its saved search was re-evaluated
+	SavedSearchMembershipChanged {
+		library_id: Uuid,
+		saved_search_id: Uuid,
+		/// Entries that now match the search
+		added: Vec<Uuid>,
+		/// Entries that no longer match the search
+		removed: Vec<Uuid>,
+	},
+
 	// Legacy events (for compatibility)
 	LocationAdded {
 		library_id: Uuid,
@@ -363,6 +374,19 @@ pub enum Event {
 		field: String,
 	},
 
+	// Extension events
+	/// A notification sent by a WASM extension, for clients to show the user
+	ExtensionNotification {
+		extension_id: String,
+		title: Option<String>,
+		message: String,
+	},
+	/// A WASM extension exceeded a resource limit and won't be called until it's resumed
+	ExtensionSuspended {
+		extension_id: String,
+		reason: String,
+	},
+
 	// Custom events for extensibility
 	Custom {
 		event_type: String,
@@ -982,6 +1006,9 @@ impl EventFilter for Event {
 			}
 			| Event::FilesModified {
 				library_id: lid, ..
+			}
+			| Event::SavedSearchMembershipChanged {
+				library_id: lid, ..
 			} => *lid == library_id,
 			Event::SyncStateChanged {
 				library_id: lid, ..
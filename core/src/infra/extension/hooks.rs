@@ -0,0 +1,610 @@
+//! Event, schedule and startup hooks registered by WASM extensions
+//!
+//! Extensions register hooks through host functions, usually from `plugin_init()`.
+//! The event loop delivers each `EventBus` event that has handlers to the handler
+//! exports as the JSON context automation rules see (`{ "type", "library_id", "data" }`),
+//! after the handler's field filters have matched. The schedule loop invokes scheduled
+//! exports when their trigger fires, and dispatches the jobs extensions held back until the
+//! system is idle. Both loops only hold a weak reference to the core context, so they stop
+//! once the core is dropped. Time and idleness are read through a [`HookClock`], which tests
+//! replace to drive schedules and idle dispatch without waiting on the wall clock.
+
+use std::{
+	collections::HashMap,
+	sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak},
+	time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+use crate::{
+	context::CoreContext,
+	infra::{
+		event::{Event, EventSubscriber},
+		job::{
+			schedule::{system_idle, ScheduleTrigger},
+			types::JobPriority,
+		},
+	},
+	ops::automation::{
+		engine::{event_context, matches_filters},
+		rule::EventFieldFilter,
+	},
+};
+
+use super::manager::PluginError;
+
+/// How often the schedule loop checks for due schedules
+const SCHEDULE_TICK: Duration = Duration::from_secs(1);
+
+/// How often held back jobs check whether the system has become idle
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Source of the current time and system idleness for the hook loops
+pub trait HookClock: Send + Sync {
+	/// Current time, which schedules fall due against
+	fn now(&self) -> DateTime<Utc>;
+
+	/// Whether jobs held back until the system is idle may run
+	fn system_idle(&self) -> bool;
+}
+
+/// The wall clock and the system's actual load
+pub struct SystemClock;
+
+impl HookClock for SystemClock {
+	fn now(&self) -> DateTime<Utc> {
+		Utc::now()
+	}
+
+	fn system_idle(&self) -> bool {
+		system_idle()
+	}
+}
+
+/// An extension export subscribed to an event type
+#[derive(Debug, Clone)]
+pub struct EventHandlerRegistration {
+	pub extension_id: String,
+	/// Event variant name (e.g., "EntryCreated")
+	pub event_type: String,
+	/// WASM export called with the event context
+	pub export_fn: String,
+	/// Filters the event context must pass, in the automation rule format
+	pub filters: Vec<EventFieldFilter>,
+}
+
+/// An extension export invoked on a schedule
+#[derive(Debug, Clone)]
+pub struct ScheduleRegistration {
+	pub extension_id: String,
+	pub trigger: ScheduleTrigger,
+	/// WASM export called when the trigger fires
+	pub export_fn: String,
+	/// When the export is next due
+	pub next_run: DateTime<Utc>,
+}
+
+/// An extension job held back until the system is idle
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdleDispatch {
+	pub extension_id: String,
+	pub library_id: Uuid,
+	/// Full registered job name
+	pub job_name: String,
+	pub args: Value,
+	pub priority: JobPriority,
+}
+
+/// Runtime registry for extension hooks
+pub struct ExtensionHookRegistry {
+	/// Map from event type to the handlers subscribed to it
+	event_handlers: RwLock<HashMap<String, Vec<EventHandlerRegistration>>>,
+	schedules: RwLock<Vec<ScheduleRegistration>>,
+	/// Map from extension ID to the exports called once it has loaded
+	startup_handlers: RwLock<HashMap<String, Vec<String>>>,
+	/// Jobs waiting for the system to be idle, oldest first
+	idle_dispatches: RwLock<Vec<IdleDispatch>>,
+	/// When the waiting jobs last checked for idleness
+	last_idle_check: RwLock<Option<DateTime<Utc>>>,
+	/// Held while waiting jobs are dispatched, so two ticks can't dispatch the same job
+	dispatching: tokio::sync::Mutex<()>,
+	clock: RwLock<Arc<dyn HookClock>>,
+}
+
+impl Default for ExtensionHookRegistry {
+	fn default() -> Self {
+		Self {
+			event_handlers: RwLock::default(),
+			schedules: RwLock::default(),
+			startup_handlers: RwLock::default(),
+			idle_dispatches: RwLock::default(),
+			last_idle_check: RwLock::default(),
+			dispatching: tokio::sync::Mutex::default(),
+			clock: RwLock::new(Arc::new(SystemClock)),
+		}
+	}
+}
+
+impl ExtensionHookRegistry {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Replace the clock schedules and idle dispatch are driven by
+	pub fn set_clock(&self, clock: Arc<dyn HookClock>) {
+		*write(&self.clock) = clock;
+	}
+
+	fn clock(&self) -> Arc<dyn HookClock> {
+		read(&self.clock).clone()
+	}
+
+	/// Subscribe an export to an event type
+	pub fn register_event_handler(
+		&self,
+		extension_id: String,
+		event_type: String,
+		export_fn: String,
+		filters: Vec<EventFieldFilter>,
+	) {
+		tracing::info!(
+			"Registered extension event handler: {} -> {}:{}",
+			event_type,
+			extension_id,
+			export_fn
+		);
+
+		write(&self.event_handlers)
+			.entry(event_type.clone())
+			.or_default()
+			.push(EventHandlerRegistration {
+				extension_id,
+				event_type,
+				export_fn,
+				filters,
+			});
+	}
+
+	/// Invoke an export whenever the trigger fires, starting from its next fire time
+	pub fn register_schedule(
+		&self,
+		extension_id: String,
+		trigger: ScheduleTrigger,
+		export_fn: String,
+	) -> Result<(), String> {
+		let next_run = trigger.next_after(self.clock().now())?;
+
+		tracing::info!(
+			"Registered extension schedule: {}:{} next runs at {}",
+			extension_id,
+			export_fn,
+			next_run
+		);
+
+		write(&self.schedules).push(ScheduleRegistration {
+			extension_id,
+			trigger,
+			export_fn,
+			next_run,
+		});
+		Ok(())
+	}
+
+	/// Call an export once the extension has finished loading
+	pub fn register_startup_handler(&self, extension_id: String, export_fn: String) {
+		write(&self.startup_handlers)
+			.entry(extension_id)
+			.or_default()
+			.push(export_fn);
+	}
+
+	/// Handlers subscribed to an event type
+	pub fn handlers_for(&self, event_type: &str) -> Vec<EventHandlerRegistration> {
+		read(&self.event_handlers)
+			.get(event_type)
+			.cloned()
+			.unwrap_or_default()
+	}
+
+	/// Startup handlers registered by an extension
+	pub fn startup_handlers(&self, extension_id: &str) -> Vec<String> {
+		read(&self.startup_handlers)
+			.get(extension_id)
+			.cloned()
+			.unwrap_or_default()
+	}
+
+	/// Schedules due at `now`, as they were when they fell due. Each one's next run is
+	/// computed from `now`, so runs missed while an earlier call was slow collapse into one.
+	pub fn take_due_schedules(&self, now: DateTime<Utc>) -> Vec<ScheduleRegistration> {
+		let mut due = Vec::new();
+
+		write(&self.schedules).retain_mut(|schedule| {
+			if schedule.next_run > now {
+				return true;
+			}
+			due.push(schedule.clone());
+
+			match schedule.trigger.next_after(now) {
+				Ok(next_run) => {
+					schedule.next_run = next_run;
+					true
+				}
+				Err(e) => {
+					tracing::warn!(
+						extension = %schedule.extension_id,
+						export_fn = %schedule.export_fn,
+						"Dropping schedule with no next run: {}",
+						e
+					);
+					false
+				}
+			}
+		});
+
+		due
+	}
+
+	/// Hold a job back until the system is idle, returning false if the same job with the
+	/// same arguments is already waiting
+	pub fn queue_idle_dispatch(&self, dispatch: IdleDispatch) -> bool {
+		let mut waiting = write(&self.idle_dispatches);
+		if waiting.contains(&dispatch) {
+			return false;
+		}
+		waiting.push(dispatch);
+		true
+	}
+
+	/// Jobs waiting for the system to be idle
+	pub fn idle_dispatches(&self) -> Vec<IdleDispatch> {
+		read(&self.idle_dispatches).clone()
+	}
+
+	/// Drop the jobs waiting to run in a library (called when it closes)
+	pub fn cancel_library_dispatches(&self, library_id: Uuid) {
+		write(&self.idle_dispatches).retain(|dispatch| dispatch.library_id != library_id);
+	}
+
+	/// Remove every hook an extension registered (called on unload)
+	pub fn unregister_extension(&self, extension_id: &str) {
+		for handlers in write(&self.event_handlers).values_mut() {
+			handlers.retain(|handler| handler.extension_id != extension_id);
+		}
+		write(&self.schedules).retain(|schedule| schedule.extension_id != extension_id);
+		write(&self.startup_handlers).remove(extension_id);
+		write(&self.idle_dispatches).retain(|dispatch| dispatch.extension_id != extension_id);
+	}
+
+	/// Invoke the schedules that are due, then dispatch the waiting jobs if the system has
+	/// been idle since they last checked. The schedule loop calls this every second.
+	pub async fn tick(&self, context: &CoreContext) {
+		for schedule in self.take_due_schedules(self.clock().now()) {
+			let payload = json!({
+				"trigger": schedule.trigger,
+				"scheduled_for": schedule.next_run,
+			});
+			invoke(
+				context,
+				&schedule.extension_id,
+				&schedule.export_fn,
+				&payload,
+				None,
+			)
+			.await;
+		}
+
+		self.dispatch_idle_jobs(context).await;
+	}
+
+	async fn dispatch_idle_jobs(&self, context: &CoreContext) {
+		let Ok(_dispatching) = self.dispatching.try_lock() else {
+			return;
+		};
+		let waiting = self.idle_dispatches();
+		if waiting.is_empty() {
+			return;
+		}
+
+		let clock = self.clock();
+		let now = clock.now();
+		{
+			let mut last_check = write(&self.last_idle_check);
+			let poll_interval = chrono::Duration::from_std(IDLE_POLL_INTERVAL).unwrap_or_default();
+			if last_check.is_some_and(|last| now - last < poll_interval) {
+				return;
+			}
+			*last_check = Some(now);
+		}
+		if !clock.system_idle() {
+			return;
+		}
+
+		for dispatch in waiting {
+			let Some(library) = context.get_library(dispatch.library_id).await else {
+				tracing::debug!(
+					extension = %dispatch.extension_id,
+					"Library {} closed before job {} was dispatched",
+					dispatch.library_id,
+					dispatch.job_name
+				);
+				self.remove_idle_dispatch(&dispatch);
+				continue;
+			};
+
+			// One waiting job at a time, and only into a library with nothing running
+			if library.jobs().has_running_jobs().await {
+				continue;
+			}
+
+			// The extension may have been unloaded while earlier jobs were dispatched
+			if !self.remove_idle_dispatch(&dispatch) {
+				continue;
+			}
+
+			if let Err(e) = library
+				.jobs()
+				.dispatch_by_name_with_priority(
+					&dispatch.job_name,
+					dispatch.args.clone(),
+					dispatch.priority,
+				)
+				.await
+			{
+				tracing::error!(
+					extension = %dispatch.extension_id,
+					"Failed to dispatch job {}: {}",
+					dispatch.job_name,
+					e
+				);
+			}
+		}
+	}
+
+	/// Stop a job waiting, returning whether it was still waiting
+	fn remove_idle_dispatch(&self, dispatch: &IdleDispatch) -> bool {
+		let mut waiting = write(&self.idle_dispatches);
+		match waiting.iter().position(|waiting| waiting == dispatch) {
+			Some(index) => {
+				waiting.remove(index);
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Start the event and schedule loops
+	pub(super) fn start(self: &Arc<Self>, context: &Arc<CoreContext>) {
+		tokio::spawn(Self::event_loop(
+			Arc::downgrade(context),
+			self.clone(),
+			context.events.subscribe(),
+		));
+		tokio::spawn(Self::schedule_loop(Arc::downgrade(context), self.clone()));
+	}
+
+	async fn event_loop(
+		context: Weak<CoreContext>,
+		hooks: Arc<Self>,
+		mut subscriber: EventSubscriber,
+	) {
+		loop {
+			let event = match subscriber.recv().await {
+				Ok(event) => event,
+				Err(RecvError::Lagged(skipped)) => {
+					tracing::warn!(
+						skipped,
+						"Extension event loop lagged, some events were not delivered"
+					);
+					continue;
+				}
+				Err(RecvError::Closed) => break,
+			};
+
+			if let Event::LibraryClosed { id, .. } | Event::LibraryDeleted { id, .. } = &event {
+				hooks.cancel_library_dispatches(*id);
+			}
+
+			let handlers = hooks.handlers_for(event.variant_name());
+			if handlers.is_empty() {
+				continue;
+			}
+			let Some(context) = context.upgrade() else {
+				break;
+			};
+
+			let event_context = event_context(&event);
+			let library_id = event_context["library_id"]
+				.as_str()
+				.and_then(|id| Uuid::parse_str(id).ok());
+
+			for handler in handlers
+				.iter()
+				.filter(|handler| matches_filters(&handler.filters, &event_context))
+			{
+				invoke(
+					&context,
+					&handler.extension_id,
+					&handler.export_fn,
+					&event_context,
+					library_id,
+				)
+				.await;
+			}
+		}
+
+		tracing::debug!("Extension event loop stopped");
+	}
+
+	async fn schedule_loop(context: Weak<CoreContext>, hooks: Arc<Self>) {
+		let mut ticker = tokio::time::interval(SCHEDULE_TICK);
+
+		loop {
+			ticker.tick().await;
+
+			let Some(context) = context.upgrade() else {
+				break;
+			};
+			hooks.tick(&context).await;
+		}
+
+		tracing::debug!("Extension schedule loop stopped");
+	}
+}
+
+/// Call a hook export, logging failures rather than returning them
+async fn invoke(
+	context: &CoreContext,
+	extension_id: &str,
+	export_fn: &str,
+	payload: &Value,
+	library_id: Option<Uuid>,
+) {
+	let Some(pm) = context.get_plugin_manager().await else {
+		return;
+	};
+
+	let instance = pm.read().await.instance(extension_id);
+	let result = match instance {
+		Ok(instance) => {
+			let (export_fn, payload) = (export_fn.to_string(), payload.clone());
+			instance
+				.run(move |instance| instance.call_handler(&export_fn, &payload, library_id))
+				.await
+		}
+		Err(e) => Err(e),
+	};
+
+	match result {
+		Ok(0) => {}
+		Ok(code) => tracing::warn!(
+			extension = %extension_id,
+			export_fn = %export_fn,
+			"Extension hook returned error code {}",
+			code
+		),
+		Err(e @ (PluginError::PermissionDenied(_) | PluginError::Suspended(..))) => {
+			tracing::debug!(
+				extension = %extension_id,
+				export_fn = %export_fn,
+				"Skipped extension hook: {}",
+				e
+			)
+		}
+		Err(e) => tracing::warn!(
+			extension = %extension_id,
+			export_fn = %export_fn,
+			"Extension hook failed: {}",
+			e
+		),
+	}
+}
+
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+	lock.read().unwrap_or_else(|poisoned| {
+		tracing::warn!("Extension hook registry lock was poisoned, recovering");
+		poisoned.into_inner()
+	})
+}
+
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+	lock.write().unwrap_or_else(|poisoned| {
+		tracing::warn!("Extension hook registry lock was poisoned, recovering");
+		poisoned.into_inner()
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_due_schedules_advance() {
+		let hooks = ExtensionHookRegistry::new();
+		hooks
+			.register_schedule(
+				"test".to_string(),
+				ScheduleTrigger::Interval { seconds: 60 },
+				"on_tick".to_string(),
+			)
+			.unwrap();
+
+		let now = Utc::now();
+		assert!(hooks.take_due_schedules(now).is_empty());
+
+		// Several missed intervals still fire once
+		let later = now + chrono::Duration::minutes(5);
+		let due = hooks.take_due_schedules(later);
+		assert_eq!(due.len(), 1);
+		assert_eq!(due[0].export_fn, "on_tick");
+		assert!(hooks.take_due_schedules(later).is_empty());
+
+		hooks.unregister_extension("test");
+		assert!(hooks
+			.take_due_schedules(later + chrono::Duration::hours(1))
+			.is_empty());
+	}
+
+	#[test]
+	fn test_unregister_extension_hooks() {
+		let hooks = ExtensionHookRegistry::new();
+		hooks.register_event_handler(
+			"a".to_string(),
+			"EntryCreated".to_string(),
+			"on_entry".to_string(),
+			vec![],
+		);
+		hooks.register_event_handler(
+			"b".to_string(),
+			"EntryCreated".to_string(),
+			"on_entry".to_string(),
+			vec![],
+		);
+		hooks.register_startup_handler("a".to_string(), "on_start".to_string());
+
+		assert_eq!(hooks.handlers_for("EntryCreated").len(), 2);
+		assert_eq!(hooks.startup_handlers("a"), vec!["on_start".to_string()]);
+
+		hooks.unregister_extension("a");
+		let handlers = hooks.handlers_for("EntryCreated");
+		assert_eq!(handlers.len(), 1);
+		assert_eq!(handlers[0].extension_id, "b");
+		assert!(hooks.startup_handlers("a").is_empty());
+	}
+
+	fn idle_dispatch(extension_id: &str, library_id: Uuid, args: Value) -> IdleDispatch {
+		IdleDispatch {
+			extension_id: extension_id.to_string(),
+			library_id,
+			job_name: format!("{}:scan", extension_id),
+			args,
+			priority: JobPriority::NORMAL,
+		}
+	}
+
+	#[test]
+	fn test_idle_dispatches_dedupe_and_cancel() {
+		let hooks = ExtensionHookRegistry::new();
+		let (library, other_library) = (Uuid::new_v4(), Uuid::new_v4());
+
+		assert!(hooks.queue_idle_dispatch(idle_dispatch("a", library, json!({}))));
+		assert!(!hooks.queue_idle_dispatch(idle_dispatch("a", library, json!({}))));
+		assert!(hooks.queue_idle_dispatch(idle_dispatch("a", library, json!({ "deep": true }))));
+		assert!(hooks.queue_idle_dispatch(idle_dispatch("a", other_library, json!({}))));
+		assert!(hooks.queue_idle_dispatch(idle_dispatch("b", library, json!({}))));
+		assert_eq!(hooks.idle_dispatches().len(), 4);
+
+		hooks.cancel_library_dispatches(other_library);
+		assert_eq!(hooks.idle_dispatches().len(), 3);
+
+		hooks.unregister_extension("a");
+		assert_eq!(
+			hooks.idle_dispatches(),
+			vec![idle_dispatch("b", library, json!({}))]
+		);
+	}
+}
@@ -5,14 +5,40 @@
 //! generic Wire method calls to the existing `execute_json_operation()` function
 //! used by daemon RPC.
 
-use std::sync::Arc;
+use std::{
+	fs::File,
+	io::{Read, Seek, SeekFrom},
+	sync::Arc,
+};
 
 use uuid::Uuid;
 use wasmer::{FunctionEnvMut, Memory, MemoryView, WasmPtr};
 
-use crate::{infra::daemon::rpc::RpcServer, Core};
+use crate::{
+	infra::{
+		daemon::rpc::RpcServer,
+		event::Event,
+		job::{schedule::ScheduleTrigger, types::JobPriority},
+	},
+	ops::{automation::rule::EventFieldFilter, core::events::ALL_EVENTS},
+	Core,
+};
 
-use super::permissions::ExtensionPermissions;
+use super::{
+	hooks::{ExtensionHookRegistry, IdleDispatch},
+	limits::ExtensionResourceTracker,
+	media_handlers::{MediaHandlerKind, MediaHandlerRegistry},
+	permissions::ExtensionPermissions,
+};
+
+/// `job_dispatch` flag: hold the job back until the system is idle
+pub const JOB_DISPATCH_WHEN_IDLE: u32 = 1;
+
+/// Most bytes a single `media_read` returns
+const MAX_MEDIA_READ_BYTES: u32 = 16 * 1024 * 1024;
+
+/// Largest thumbnail or metadata document a media handler can hand back
+const MAX_MEDIA_OUTPUT_BYTES: u32 = 64 * 1024 * 1024;
 
 /// Environment passed to all host functions
 pub struct PluginEnv {
@@ -22,6 +48,15 @@ pub struct PluginEnv {
 	pub permissions: ExtensionPermissions,
 	pub memory: Memory,
 	pub job_registry: Arc<super::job_registry::ExtensionJobRegistry>,
+	pub hooks: Arc<ExtensionHookRegistry>,
+	pub resources: Arc<ExtensionResourceTracker>,
+	/// Library of the event being handled, used by host calls that don't name one
+	pub current_library: Option<Uuid>,
+	pub media_handlers: Arc<MediaHandlerRegistry>,
+	/// File of the media handler call in progress, read through `media_read`
+	pub media_file: Option<File>,
+	/// Result the media handler in progress handed back through `media_output`
+	pub media_output: Option<Vec<u8>>,
 }
 
 /// THE MAIN HOST FUNCTION - Generic Wire RPC
@@ -34,6 +69,9 @@ pub struct PluginEnv {
 /// - `library_id_ptr`: 0 for None, or pointer to 16 UUID bytes
 /// - `payload_ptr`, `payload_len`: JSON payload string
 ///
+/// Operations that run past the extension's host call timeout are abandoned and the
+/// extension is suspended.
+///
 /// # Returns
 /// Pointer to result JSON string in WASM memory (or 0 on error)
 pub fn host_spacedrive_call(
@@ -111,7 +149,7 @@ pub fn host_spacedrive_call(
 	);
 
 	// 5. Call operation handlers directly (same as execute_json_operation does)
-	let result = tokio::runtime::Handle::current().block_on(async {
+	let operation = async {
 		// Create base session
 		let base_session = match plugin_env.api_dispatcher.create_base_session() {
 			Ok(s) => s,
@@ -143,7 +181,26 @@ pub fn host_spacedrive_call(
 		}
 
 		Err(format!("Unknown method: {}", method))
-	});
+	};
+
+	let timeout = plugin_env.permissions.host_call_timeout;
+	let result = match tokio::runtime::Handle::current()
+		.block_on(async { tokio::time::timeout(timeout, operation).await })
+	{
+		Ok(result) => result,
+		Err(_) => {
+			let reason = format!("{} took longer than {}s", method, timeout.as_secs());
+			plugin_env
+				.resources
+				.record_host_call_timeout(&plugin_env.extension_id);
+			plugin_env.resources.suspend(
+				&plugin_env.extension_id,
+				reason.clone(),
+				&plugin_env.core_context.events,
+			);
+			return write_error_to_memory(&memory, &mut store, &reason);
+		}
+	};
 
 	// 6. Write result to WASM memory
 	match result {
@@ -462,3 +519,531 @@ pub fn host_register_job(
 		}
 	}
 }
+
+/// Subscribe an export to an event type
+///
+/// # Arguments
+/// - `event_type_ptr`, `event_type_len`: Event variant name (e.g., "EntryCreated")
+/// - `export_fn_ptr`, `export_fn_len`: WASM export called as `fn(ptr: u32, len: u32) -> i32`
+///   with the event context JSON
+/// - `filters_ptr`, `filters_len`: JSON array of automation field filters (len 0 = none)
+///
+/// # Returns
+/// 0 on success, 1 on error
+pub fn host_register_event_handler(
+	mut env: FunctionEnvMut<PluginEnv>,
+	event_type_ptr: WasmPtr<u8>,
+	event_type_len: u32,
+	export_fn_ptr: WasmPtr<u8>,
+	export_fn_len: u32,
+	filters_ptr: WasmPtr<u8>,
+	filters_len: u32,
+) -> i32 {
+	let (plugin_env, store) = env.data_and_store_mut();
+	let memory_view = plugin_env.memory.view(&store);
+
+	let (event_type, export_fn) = match (
+		read_string_from_wasm(&memory_view, event_type_ptr, event_type_len),
+		read_string_from_wasm(&memory_view, export_fn_ptr, export_fn_len),
+	) {
+		(Ok(event_type), Ok(export_fn)) => (event_type, export_fn),
+		_ => {
+			tracing::error!("Failed to read event handler registration");
+			return 1;
+		}
+	};
+
+	let filters: Vec<EventFieldFilter> = if filters_len == 0 {
+		Vec::new()
+	} else {
+		let parsed = read_string_from_wasm(&memory_view, filters_ptr, filters_len)
+			.map_err(|e| e.to_string())
+			.and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+		match parsed {
+			Ok(filters) => filters,
+			Err(e) => {
+				tracing::error!(extension = %plugin_env.extension_id, "Invalid event filters: {}", e);
+				return 1;
+			}
+		}
+	};
+
+	if !ALL_EVENTS.contains(&event_type.as_str()) {
+		tracing::error!(extension = %plugin_env.extension_id, "Unknown event type: {}", event_type);
+		return 1;
+	}
+
+	if !plugin_env.permissions.can_receive_event(&event_type) {
+		tracing::warn!(
+			extension = %plugin_env.extension_id,
+			"Permission denied: not allowed to receive {} events",
+			event_type
+		);
+		return 1;
+	}
+
+	plugin_env.hooks.register_event_handler(
+		plugin_env.extension_id.clone(),
+		event_type,
+		export_fn,
+		filters,
+	);
+	0
+}
+
+/// Invoke an export on a schedule
+///
+/// # Arguments
+/// - `schedule_ptr`, `schedule_len`: `"interval:5m"`, `"cron:0 3 * * *"` or a bare cron
+///   expression
+/// - `export_fn_ptr`, `export_fn_len`: WASM export called as `fn(ptr: u32, len: u32) -> i32`
+///   with `{ "trigger", "scheduled_for" }`
+///
+/// # Returns
+/// 0 on success, 1 on error
+pub fn host_register_schedule(
+	mut env: FunctionEnvMut<PluginEnv>,
+	schedule_ptr: WasmPtr<u8>,
+	schedule_len: u32,
+	export_fn_ptr: WasmPtr<u8>,
+	export_fn_len: u32,
+) -> i32 {
+	let (plugin_env, store) = env.data_and_store_mut();
+	let memory_view = plugin_env.memory.view(&store);
+
+	let (schedule, export_fn) = match (
+		read_string_from_wasm(&memory_view, schedule_ptr, schedule_len),
+		read_string_from_wasm(&memory_view, export_fn_ptr, export_fn_len),
+	) {
+		(Ok(schedule), Ok(export_fn)) => (schedule, export_fn),
+		_ => {
+			tracing::error!("Failed to read schedule registration");
+			return 1;
+		}
+	};
+
+	if !plugin_env.permissions.can_schedule() {
+		tracing::warn!(
+			extension = %plugin_env.extension_id,
+			"Permission denied: not allowed to register schedules"
+		);
+		return 1;
+	}
+
+	let result = ScheduleTrigger::parse(&schedule).and_then(|trigger| {
+		plugin_env
+			.hooks
+			.register_schedule(plugin_env.extension_id.clone(), trigger, export_fn)
+	});
+
+	match result {
+		Ok(()) => 0,
+		Err(e) => {
+			tracing::error!(extension = %plugin_env.extension_id, "Invalid schedule '{}': {}", schedule, e);
+			1
+		}
+	}
+}
+
+/// Call an export once the extension has finished loading
+///
+/// The export is called as `fn(ptr: u32, len: u32) -> i32` after `plugin_init()` returns.
+///
+/// # Returns
+/// 0 on success, 1 on error
+pub fn host_register_startup_handler(
+	mut env: FunctionEnvMut<PluginEnv>,
+	export_fn_ptr: WasmPtr<u8>,
+	export_fn_len: u32,
+) -> i32 {
+	let (plugin_env, store) = env.data_and_store_mut();
+	let memory_view = plugin_env.memory.view(&store);
+
+	let export_fn = match read_string_from_wasm(&memory_view, export_fn_ptr, export_fn_len) {
+		Ok(name) => name,
+		Err(e) => {
+			tracing::error!("Failed to read export function name: {}", e);
+			return 1;
+		}
+	};
+
+	plugin_env
+		.hooks
+		.register_startup_handler(plugin_env.extension_id.clone(), export_fn);
+	0
+}
+
+/// Dispatch one of the extension's own jobs
+///
+/// The job is queued in the background and doesn't block the caller. A job held back until
+/// the system is idle waits in the hook registry, which drops it if the extension unloads or
+/// the library closes first.
+///
+/// # Arguments
+/// - `job_name_ptr`, `job_name_len`: Registered job name, full name or `#[job]` function name
+/// - `library_id_ptr`: 0 for the library of the event being handled, or pointer to 16 UUID bytes
+/// - `args_ptr`, `args_len`: JSON job state (len 0 = `{}`)
+/// - `priority`: Job priority (-1 low, 0 normal, 1 high)
+/// - `flags`: `JOB_DISPATCH_WHEN_IDLE`
+///
+/// # Returns
+/// 0 if the job was accepted, 1 on error
+pub fn host_job_dispatch(
+	mut env: FunctionEnvMut<PluginEnv>,
+	job_name_ptr: WasmPtr<u8>,
+	job_name_len: u32,
+	library_id_ptr: u32,
+	args_ptr: WasmPtr<u8>,
+	args_len: u32,
+	priority: i32,
+	flags: u32,
+) -> i32 {
+	let (plugin_env, store) = env.data_and_store_mut();
+	let memory_view = plugin_env.memory.view(&store);
+
+	let job_name = match read_string_from_wasm(&memory_view, job_name_ptr, job_name_len) {
+		Ok(name) => name,
+		Err(e) => {
+			tracing::error!("Failed to read job name: {}", e);
+			return 1;
+		}
+	};
+
+	let library_id = if library_id_ptr == 0 {
+		plugin_env.current_library
+	} else {
+		match read_uuid_from_wasm(&memory_view, WasmPtr::new(library_id_ptr)) {
+			Ok(uuid) => Some(uuid),
+			Err(e) => {
+				tracing::error!("Failed to read library UUID: {}", e);
+				return 1;
+			}
+		}
+	};
+
+	let args: serde_json::Value = if args_len == 0 {
+		serde_json::json!({})
+	} else {
+		let parsed = read_string_from_wasm(&memory_view, args_ptr, args_len)
+			.map_err(|e| e.to_string())
+			.and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+		match parsed {
+			Ok(args) => args,
+			Err(e) => {
+				tracing::error!(extension = %plugin_env.extension_id, "Invalid job args: {}", e);
+				return 1;
+			}
+		}
+	};
+
+	if !plugin_env.permissions.can_dispatch_jobs() {
+		tracing::warn!(
+			extension = %plugin_env.extension_id,
+			"Permission denied: not allowed to dispatch jobs"
+		);
+		return 1;
+	}
+
+	let Some(library_id) = library_id else {
+		tracing::error!(
+			extension = %plugin_env.extension_id,
+			"Job {} needs a library outside of library event handlers",
+			job_name
+		);
+		return 1;
+	};
+
+	if !plugin_env.permissions.can_access_library(library_id) {
+		tracing::warn!(
+			extension = %plugin_env.extension_id,
+			"Permission denied: cannot access library {}",
+			library_id
+		);
+		return 1;
+	}
+
+	if let Err(e) = plugin_env.permissions.try_check_rate_limit() {
+		tracing::warn!(extension = %plugin_env.extension_id, "{}", e);
+		return 1;
+	}
+
+	let Some(registration) = plugin_env
+		.job_registry
+		.resolve_job(&plugin_env.extension_id, &job_name)
+	else {
+		tracing::error!(
+			extension = %plugin_env.extension_id,
+			"Extension has no job named {}",
+			job_name
+		);
+		return 1;
+	};
+
+	if flags & JOB_DISPATCH_WHEN_IDLE != 0 {
+		// The hook registry's schedule loop dispatches it once the system is idle
+		let queued = plugin_env.hooks.queue_idle_dispatch(IdleDispatch {
+			extension_id: registration.extension_id,
+			library_id,
+			job_name: registration.full_name,
+			args,
+			priority: JobPriority(priority),
+		});
+		if !queued {
+			tracing::debug!(
+				extension = %plugin_env.extension_id,
+				"Job {} is already waiting for the system to be idle",
+				job_name
+			);
+		}
+		return 0;
+	}
+
+	let context = plugin_env.core_context.clone();
+	tokio::spawn(async move {
+		let Some(library) = context.get_library(library_id).await else {
+			tracing::warn!(
+				"Library {} closed before job {} was dispatched",
+				library_id,
+				registration.full_name
+			);
+			return;
+		};
+
+		if let Err(e) = library
+			.jobs()
+			.dispatch_by_name_with_priority(&registration.full_name, args, JobPriority(priority))
+			.await
+		{
+			tracing::error!(
+				extension = %registration.extension_id,
+				"Failed to dispatch job {}: {}",
+				registration.full_name,
+				e
+			);
+		}
+	});
+
+	0
+}
+
+/// Send a notification to connected clients
+///
+/// # Arguments
+/// - `title_ptr`, `title_len`: Notification title (len 0 = none)
+/// - `message_ptr`, `message_len`: Notification message
+///
+/// # Returns
+/// 0 on success, 1 on error
+pub fn host_notify(
+	mut env: FunctionEnvMut<PluginEnv>,
+	title_ptr: WasmPtr<u8>,
+	title_len: u32,
+	message_ptr: WasmPtr<u8>,
+	message_len: u32,
+) -> i32 {
+	let (plugin_env, store) = env.data_and_store_mut();
+	let memory_view = plugin_env.memory.view(&store);
+
+	let title = if title_len == 0 {
+		None
+	} else {
+		match read_string_from_wasm(&memory_view, title_ptr, title_len) {
+			Ok(title) => Some(title),
+			Err(e) => {
+				tracing::error!("Failed to read notification title: {}", e);
+				return 1;
+			}
+		}
+	};
+
+	let message = match read_string_from_wasm(&memory_view, message_ptr, message_len) {
+		Ok(msg) => msg,
+		Err(e) => {
+			tracing::error!("Failed to read notification message: {}", e);
+			return 1;
+		}
+	};
+
+	if !plugin_env.permissions.can_notify() {
+		tracing::warn!(
+			extension = %plugin_env.extension_id,
+			"Permission denied: not allowed to send notifications"
+		);
+		return 1;
+	}
+
+	if let Err(e) = plugin_env.permissions.try_check_rate_limit() {
+		tracing::warn!(extension = %plugin_env.extension_id, "{}", e);
+		return 1;
+	}
+
+	plugin_env
+		.core_context
+		.events
+		.emit(Event::ExtensionNotification {
+			extension_id: plugin_env.extension_id.clone(),
+			title,
+			message,
+		});
+	0
+}
+
+// === Media Handler Functions ===
+
+/// Register an export as a thumbnailer or metadata extractor
+///
+/// # Arguments
+/// - `kind`: 0 for a thumbnailer, 1 for a metadata extractor
+/// - `patterns_ptr`, `patterns_len`: JSON array of MIME types (`"model/vnd.blender"`),
+///   MIME wildcards (`"image/*"`) and file extensions with a leading dot (`".blend"`)
+/// - `export_fn_ptr`, `export_fn_len`: WASM export called as `fn(request_ptr: u32,
+///   request_len: u32, data_ptr: u32, data_len: u32) -> i32` with the request JSON and
+///   the start of the file
+///
+/// # Returns
+/// 0 on success, 1 on error
+pub fn host_register_media_handler(
+	mut env: FunctionEnvMut<PluginEnv>,
+	kind: u32,
+	patterns_ptr: WasmPtr<u8>,
+	patterns_len: u32,
+	export_fn_ptr: WasmPtr<u8>,
+	export_fn_len: u32,
+) -> i32 {
+	let (plugin_env, store) = env.data_and_store_mut();
+	let memory_view = plugin_env.memory.view(&store);
+
+	let (patterns, export_fn) = match (
+		read_string_from_wasm(&memory_view, patterns_ptr, patterns_len),
+		read_string_from_wasm(&memory_view, export_fn_ptr, export_fn_len),
+	) {
+		(Ok(patterns), Ok(export_fn)) => (patterns, export_fn),
+		_ => {
+			tracing::error!("Failed to read media handler registration");
+			return 1;
+		}
+	};
+
+	if !plugin_env.permissions.can_handle_media() {
+		tracing::warn!(
+			extension = %plugin_env.extension_id,
+			"Permission denied: not allowed to register media handlers"
+		);
+		return 1;
+	}
+
+	let result = MediaHandlerKind::try_from(kind).and_then(|kind| {
+		let patterns: Vec<String> = serde_json::from_str(&patterns)
+			.map_err(|e| format!("Invalid media handler patterns: {}", e))?;
+		plugin_env.media_handlers.register(
+			plugin_env.extension_id.clone(),
+			kind,
+			patterns,
+			export_fn,
+		)
+	});
+
+	match result {
+		Ok(()) => 0,
+		Err(e) => {
+			tracing::error!(extension = %plugin_env.extension_id, "{}", e);
+			1
+		}
+	}
+}
+
+/// Read part of the file a media handler was called for
+///
+/// # Arguments
+/// - `offset`: Byte offset in the file
+/// - `buf_ptr`, `buf_len`: Buffer to read into (at most `MAX_MEDIA_READ_BYTES` are read)
+///
+/// # Returns
+/// Bytes read (0 at the end of the file), or -1 outside a media handler call or on error
+pub fn host_media_read(
+	mut env: FunctionEnvMut<PluginEnv>,
+	offset: u64,
+	buf_ptr: WasmPtr<u8>,
+	buf_len: u32,
+) -> i64 {
+	let (plugin_env, store) = env.data_and_store_mut();
+
+	let Some(file) = plugin_env.media_file.as_mut() else {
+		tracing::warn!(
+			extension = %plugin_env.extension_id,
+			"media_read called outside a media handler"
+		);
+		return -1;
+	};
+
+	let mut buffer = vec![0u8; buf_len.min(MAX_MEDIA_READ_BYTES) as usize];
+	let read = file
+		.seek(SeekFrom::Start(offset))
+		.and_then(|_| file.read(&mut buffer));
+	let read = match read {
+		Ok(read) => read,
+		Err(e) => {
+			tracing::error!(extension = %plugin_env.extension_id, "Failed to read media file: {}", e);
+			return -1;
+		}
+	};
+
+	let memory_view = plugin_env.memory.view(&store);
+	match buf_ptr
+		.slice(&memory_view, read as u32)
+		.and_then(|slice| slice.write_slice(&buffer[..read]))
+	{
+		Ok(()) => read as i64,
+		Err(e) => {
+			tracing::error!("Failed to write to WASM memory: {:?}", e);
+			-1
+		}
+	}
+}
+
+/// Hand back a media handler's result
+///
+/// Thumbnailers return an encoded image (PNG, JPEG, WebP, ...) and metadata extractors
+/// a JSON document. A later call replaces an earlier one.
+///
+/// # Returns
+/// 0 on success, 1 outside a media handler call or on error
+pub fn host_media_output(
+	mut env: FunctionEnvMut<PluginEnv>,
+	output_ptr: WasmPtr<u8>,
+	output_len: u32,
+) -> i32 {
+	let (plugin_env, store) = env.data_and_store_mut();
+
+	if plugin_env.media_file.is_none() {
+		tracing::warn!(
+			extension = %plugin_env.extension_id,
+			"media_output called outside a media handler"
+		);
+		return 1;
+	}
+	if output_len > MAX_MEDIA_OUTPUT_BYTES {
+		tracing::error!(
+			extension = %plugin_env.extension_id,
+			"Media handler output of {} bytes is over the {} byte limit",
+			output_len,
+			MAX_MEDIA_OUTPUT_BYTES
+		);
+		return 1;
+	}
+
+	let memory_view = plugin_env.memory.view(&store);
+	match output_ptr
+		.slice(&memory_view, output_len)
+		.and_then(|slice| slice.read_to_vec())
+	{
+		Ok(output) => {
+			plugin_env.media_output = Some(output);
+			0
+		}
+		Err(e) => {
+			tracing::error!("Failed to read media handler output: {:?}", e);
+			1
+		}
+	}
+}
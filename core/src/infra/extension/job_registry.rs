@@ -96,6 +96,19 @@ impl ExtensionJobRegistry {
 			.cloned()
 	}
 
+	/// Find one of an extension's own jobs by the name it dispatched
+	///
+	/// Accepts the registered job name ("email_scan"), the full name
+	/// ("finance:email_scan") or the name of the function the SDK's `#[job]` macro was
+	/// applied to, whose export is `execute_<name>`.
+	pub fn resolve_job(&self, extension_id: &str, name: &str) -> Option<ExtensionJobRegistration> {
+		let export_fn = format!("execute_{}", name);
+
+		self.list_jobs_for_extension(extension_id)
+			.into_iter()
+			.find(|reg| reg.job_name == name || reg.full_name == name || reg.export_fn == export_fn)
+	}
+
 	/// Create a WasmJob instance from a registered job name
 	pub fn create_wasm_job(&self, full_name: &str, state_json: String) -> Result<WasmJob, String> {
 		let registration = self
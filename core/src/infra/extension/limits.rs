@@ -0,0 +1,403 @@
+//! Resource limits for WASM extensions
+//!
+//! CPU time is metered with fuel: every WASM operator costs one point, and each call into
+//! an extension starts with a fresh budget (a larger one when it runs a job). Linear memory
+//! is capped by tunables that clamp each memory's maximum to the extension's
+//! `max_memory_mb` when it is created. An extension that runs out of fuel, traps at its
+//! memory cap or blocks a host call past its timeout is suspended until it's resumed or
+//! reloaded, and an `ExtensionSuspended` event is emitted.
+
+use std::{
+	collections::HashMap,
+	ptr::NonNull,
+	sync::{
+		atomic::{AtomicU32, Ordering},
+		Arc, RwLock, RwLockReadGuard, RwLockWriteGuard,
+	},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tokio::sync::Semaphore;
+use wasmer::{
+	vm::{
+		MemoryError, MemoryStyle, TableStyle, VMMemory, VMMemoryDefinition, VMTable,
+		VMTableDefinition,
+	},
+	wasmparser::Operator,
+	BaseTunables, CompilerConfig, Cranelift, EngineBuilder, MemoryType, Pages, Store, TableType,
+	Target, Tunables,
+};
+use wasmer_middlewares::Metering;
+
+use crate::infra::event::{Event, EventBus};
+
+use super::permissions::ExtensionPermissions;
+
+/// Size of a WASM page
+const WASM_PAGE_SIZE: u64 = 64 * 1024;
+
+/// Most pages a 32-bit linear memory can have
+const MAX_WASM_PAGES: u64 = 65536;
+
+/// Fuel available to a module's start function when it's instantiated
+const INSTANTIATION_FUEL: u64 = 1_000_000_000;
+
+/// Fuel cost of a WASM operator
+fn operator_cost(_operator: &Operator) -> u64 {
+	1
+}
+
+/// Create a store whose modules are metered and whose memories are capped by `memory_limit`
+pub(super) fn limited_store(memory_limit: MemoryLimit) -> Store {
+	let mut compiler = Cranelift::default();
+	compiler.push_middleware(Arc::new(Metering::new(INSTANTIATION_FUEL, operator_cost)));
+
+	let mut engine = EngineBuilder::new(compiler).engine();
+	engine.set_tunables(LimitingTunables {
+		base: BaseTunables::for_target(&Target::default()),
+		limit: memory_limit,
+	});
+
+	Store::new(engine)
+}
+
+/// Memory cap applied to memories as they're created
+///
+/// Each extension has its own store, which the plugin manager creates with the cap from
+/// the extension's permissions.
+#[derive(Debug, Clone, Default)]
+pub(super) struct MemoryLimit(Arc<AtomicU32>);
+
+impl MemoryLimit {
+	pub(super) fn set_mb(&self, max_memory_mb: usize) {
+		let pages = (max_memory_mb as u64 * 1024 * 1024 / WASM_PAGE_SIZE).min(MAX_WASM_PAGES);
+		self.0.store(pages as u32, Ordering::SeqCst);
+	}
+
+	fn pages(&self) -> Pages {
+		Pages(self.0.load(Ordering::SeqCst))
+	}
+}
+
+/// Tunables that clamp memory maximums to the current `MemoryLimit`
+struct LimitingTunables {
+	base: BaseTunables,
+	limit: MemoryLimit,
+}
+
+impl LimitingTunables {
+	fn adjust_memory(&self, requested: &MemoryType) -> MemoryType {
+		let limit = self.limit.pages();
+		let mut adjusted = *requested;
+		adjusted.maximum = Some(requested.maximum.map_or(limit, |max| max.min(limit)));
+		adjusted
+	}
+
+	fn validate_memory(&self, ty: &MemoryType) -> Result<(), MemoryError> {
+		let limit = self.limit.pages();
+		if ty.minimum > limit {
+			return Err(MemoryError::Generic(format!(
+				"Memory needs {} pages but the extension is limited to {}",
+				ty.minimum.0, limit.0
+			)));
+		}
+		Ok(())
+	}
+}
+
+impl Tunables for LimitingTunables {
+	fn memory_style(&self, memory: &MemoryType) -> MemoryStyle {
+		self.base.memory_style(&self.adjust_memory(memory))
+	}
+
+	fn table_style(&self, table: &TableType) -> TableStyle {
+		self.base.table_style(table)
+	}
+
+	fn create_host_memory(
+		&self,
+		ty: &MemoryType,
+		style: &MemoryStyle,
+	) -> Result<VMMemory, MemoryError> {
+		let adjusted = self.adjust_memory(ty);
+		self.validate_memory(&adjusted)?;
+		self.base.create_host_memory(&adjusted, style)
+	}
+
+	unsafe fn create_vm_memory(
+		&self,
+		ty: &MemoryType,
+		style: &MemoryStyle,
+		vm_definition_location: NonNull<VMMemoryDefinition>,
+	) -> Result<VMMemory, MemoryError> {
+		let adjusted = self.adjust_memory(ty);
+		self.validate_memory(&adjusted)?;
+		self.base
+			.create_vm_memory(&adjusted, style, vm_definition_location)
+	}
+
+	fn create_host_table(&self, ty: &TableType, style: &TableStyle) -> Result<VMTable, String> {
+		self.base.create_host_table(ty, style)
+	}
+
+	unsafe fn create_vm_table(
+		&self,
+		ty: &TableType,
+		style: &TableStyle,
+		vm_definition_location: NonNull<VMTableDefinition>,
+	) -> Result<VMTable, String> {
+		self.base.create_vm_table(ty, style, vm_definition_location)
+	}
+}
+
+/// Resource usage and limits of a loaded extension
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ExtensionResourceUsage {
+	pub extension_id: String,
+	/// Calls into the extension, including hooks and job runs
+	pub calls: u64,
+	/// Fuel used by all calls
+	pub fuel_consumed: u64,
+	/// Fuel used by the most recent call
+	pub last_call_fuel: u64,
+	pub max_fuel_per_call: u64,
+	pub max_fuel_per_job: u64,
+	/// Size of the extension's linear memory after its last call
+	pub memory_bytes: u64,
+	pub max_memory_bytes: u64,
+	pub running_jobs: usize,
+	pub max_concurrent_jobs: usize,
+	/// Host calls abandoned after the timeout
+	pub host_call_timeouts: u64,
+	/// Why the extension was suspended, if it is
+	pub suspended_reason: Option<String>,
+	pub suspended_at: Option<DateTime<Utc>>,
+}
+
+struct ExtensionResources {
+	usage: ExtensionResourceUsage,
+	/// One permit per job the extension can run at once
+	job_slots: Arc<Semaphore>,
+}
+
+/// Tracks resource usage and suspensions of loaded extensions
+#[derive(Default)]
+pub struct ExtensionResourceTracker {
+	extensions: RwLock<HashMap<String, ExtensionResources>>,
+}
+
+impl ExtensionResourceTracker {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Start tracking an extension with the limits from its permissions
+	pub fn register(&self, extension_id: &str, permissions: &ExtensionPermissions) {
+		let usage = ExtensionResourceUsage {
+			extension_id: extension_id.to_string(),
+			calls: 0,
+			fuel_consumed: 0,
+			last_call_fuel: 0,
+			max_fuel_per_call: permissions.max_fuel_per_call,
+			max_fuel_per_job: permissions.max_fuel_per_job,
+			memory_bytes: 0,
+			max_memory_bytes: permissions.max_memory_mb as u64 * 1024 * 1024,
+			running_jobs: 0,
+			max_concurrent_jobs: permissions.max_concurrent_jobs,
+			host_call_timeouts: 0,
+			suspended_reason: None,
+			suspended_at: None,
+		};
+
+		let previous = write(&self.extensions).insert(
+			extension_id.to_string(),
+			ExtensionResources {
+				usage,
+				job_slots: Arc::new(Semaphore::new(permissions.max_concurrent_jobs)),
+			},
+		);
+		if let Some(previous) = previous {
+			previous.job_slots.close();
+		}
+	}
+
+	/// Stop tracking an extension (called on unload). Jobs waiting for a slot fail.
+	pub fn unregister(&self, extension_id: &str) {
+		if let Some(resources) = write(&self.extensions).remove(extension_id) {
+			resources.job_slots.close();
+		}
+	}
+
+	/// Record a finished call and the fuel it used
+	pub fn record_call(&self, extension_id: &str, fuel: u64) {
+		if let Some(resources) = write(&self.extensions).get_mut(extension_id) {
+			resources.usage.calls += 1;
+			resources.usage.fuel_consumed = resources.usage.fuel_consumed.saturating_add(fuel);
+			resources.usage.last_call_fuel = fuel;
+		}
+	}
+
+	/// Record the size of an extension's linear memory after a call
+	pub fn record_memory(&self, extension_id: &str, memory_bytes: u64) {
+		if let Some(resources) = write(&self.extensions).get_mut(extension_id) {
+			resources.usage.memory_bytes = memory_bytes;
+		}
+	}
+
+	/// Record a host call that was abandoned after the timeout
+	pub fn record_host_call_timeout(&self, extension_id: &str) {
+		if let Some(resources) = write(&self.extensions).get_mut(extension_id) {
+			resources.usage.host_call_timeouts += 1;
+		}
+	}
+
+	/// Suspend an extension, emitting `ExtensionSuspended` unless it already was
+	pub fn suspend(&self, extension_id: &str, reason: String, events: &EventBus) {
+		{
+			let mut extensions = write(&self.extensions);
+			let Some(resources) = extensions.get_mut(extension_id) else {
+				return;
+			};
+			if resources.usage.suspended_reason.is_some() {
+				return;
+			}
+			resources.usage.suspended_reason = Some(reason.clone());
+			resources.usage.suspended_at = Some(Utc::now());
+		}
+
+		tracing::warn!(extension = %extension_id, "Suspended extension: {}", reason);
+		events.emit(Event::ExtensionSuspended {
+			extension_id: extension_id.to_string(),
+			reason,
+		});
+	}
+
+	/// Lift a suspension, returning whether the extension was suspended
+	pub fn resume(&self, extension_id: &str) -> bool {
+		match write(&self.extensions).get_mut(extension_id) {
+			Some(resources) => {
+				resources.usage.suspended_at = None;
+				resources.usage.suspended_reason.take().is_some()
+			}
+			None => false,
+		}
+	}
+
+	/// Why an extension is suspended, if it is
+	pub fn suspended_reason(&self, extension_id: &str) -> Option<String> {
+		read(&self.extensions)
+			.get(extension_id)
+			.and_then(|resources| resources.usage.suspended_reason.clone())
+	}
+
+	/// Semaphore a job must hold a permit from while it runs
+	pub fn job_slots(&self, extension_id: &str) -> Option<Arc<Semaphore>> {
+		read(&self.extensions)
+			.get(extension_id)
+			.map(|resources| resources.job_slots.clone())
+	}
+
+	/// Usage of an extension, without its memory size
+	pub fn usage(&self, extension_id: &str) -> Option<ExtensionResourceUsage> {
+		read(&self.extensions).get(extension_id).map(|resources| {
+			let mut usage = resources.usage.clone();
+			usage.running_jobs = usage
+				.max_concurrent_jobs
+				.saturating_sub(resources.job_slots.available_permits());
+			usage
+		})
+	}
+}
+
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+	lock.read().unwrap_or_else(|poisoned| {
+		tracing::warn!("Extension resource tracker lock was poisoned, recovering");
+		poisoned.into_inner()
+	})
+}
+
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+	lock.write().unwrap_or_else(|poisoned| {
+		tracing::warn!("Extension resource tracker lock was poisoned, recovering");
+		poisoned.into_inner()
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::infra::extension::types::{ManifestPermissions, RateLimits};
+
+	fn tracker_with(extension_id: &str, concurrent_jobs: usize) -> ExtensionResourceTracker {
+		let manifest_perms = ManifestPermissions {
+			rate_limits: RateLimits {
+				requests_per_minute: 1000,
+				concurrent_jobs,
+			},
+			..Default::default()
+		};
+		let tracker = ExtensionResourceTracker::new();
+		tracker.register(
+			extension_id,
+			&ExtensionPermissions::from_manifest(extension_id.to_string(), &manifest_perms),
+		);
+		tracker
+	}
+
+	#[test]
+	fn test_suspend_and_resume() {
+		let tracker = tracker_with("test", 1);
+		let events = EventBus::default();
+		let mut subscriber = events.subscribe();
+
+		tracker.record_call("test", 100);
+		tracker.record_call("test", 50);
+		tracker.suspend("test", "out of fuel".to_string(), &events);
+		tracker.suspend("test", "again".to_string(), &events);
+
+		let usage = tracker.usage("test").unwrap();
+		assert_eq!(usage.calls, 2);
+		assert_eq!(usage.fuel_consumed, 150);
+		assert_eq!(usage.last_call_fuel, 50);
+		assert_eq!(usage.suspended_reason.as_deref(), Some("out of fuel"));
+
+		// Only the first suspension is announced
+		assert!(matches!(
+			subscriber.try_recv(),
+			Ok(Event::ExtensionSuspended { reason, .. }) if reason == "out of fuel"
+		));
+		assert!(subscriber.try_recv().is_err());
+
+		assert!(tracker.resume("test"));
+		assert!(tracker.suspended_reason("test").is_none());
+		assert!(!tracker.resume("test"));
+	}
+
+	#[tokio::test]
+	async fn test_job_slots() {
+		let tracker = tracker_with("test", 1);
+		let slots = tracker.job_slots("test").unwrap();
+
+		let permit = slots.clone().try_acquire_owned().unwrap();
+		assert_eq!(tracker.usage("test").unwrap().running_jobs, 1);
+		assert!(slots.clone().try_acquire_owned().is_err());
+		drop(permit);
+		assert_eq!(tracker.usage("test").unwrap().running_jobs, 0);
+
+		// Unloading fails jobs still waiting for a slot
+		tracker.unregister("test");
+		assert!(slots.acquire_owned().await.is_err());
+		assert!(tracker.usage("test").is_none());
+	}
+
+	#[test]
+	fn test_memory_limit_pages() {
+		let limit = MemoryLimit::default();
+		limit.set_mb(1);
+		assert_eq!(limit.pages(), Pages(16));
+		limit.set_mb(1024 * 1024);
+		assert_eq!(limit.pages(), Pages(MAX_WASM_PAGES as u32));
+	}
+}
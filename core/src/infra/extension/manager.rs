@@ -1,23 +1,39 @@
 //! WASM Plugin Manager
 //!
 //! Manages the lifecycle of WASM extensions: loading, unloading, hot-reload.
+//! Loaded instances are kept so hooks and jobs can call their exports later. Every call
+//! into an extension is metered against its fuel limit (see `limits`). Each extension runs
+//! in its own store behind its own lock, so a long call into one extension doesn't hold up
+//! the others or the manager.
 
 use std::collections::HashMap;
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard};
 
 use chrono::Utc;
 use thiserror::Error;
 use tokio::sync::RwLock;
+use uuid::Uuid;
 use wasmer::{imports, Function, FunctionEnv, Instance, Memory, Module, Store};
+use wasmer_middlewares::metering::{get_remaining_points, set_remaining_points, MeteringPoints};
 
-use crate::{context::CoreContext, infra::api::ApiDispatcher};
+use crate::{
+	context::CoreContext,
+	infra::{api::ApiDispatcher, event::EventBus},
+};
 
+use super::hooks::ExtensionHookRegistry;
 use super::host_functions::{self, host_spacedrive_call, host_spacedrive_log, PluginEnv};
 use super::job_registry::ExtensionJobRegistry;
-use super::permissions::ExtensionPermissions;
+use super::limits::{limited_store, ExtensionResourceTracker, ExtensionResourceUsage, MemoryLimit};
+use super::media_handlers::{MediaHandlerRegistration, MediaHandlerRegistry};
+use super::permissions::{ExtensionPermissions, PermissionError};
 use super::types::{ExtensionManifest, LoadedPlugin};
 
+/// Bytes from the start of a file passed to media handlers; they read the rest on demand
+const MEDIA_HEAD_BYTES: u64 = 1024 * 1024;
+
 #[derive(Error, Debug)]
 pub enum PluginError {
 	#[error("Plugin not found: {0}")]
@@ -35,18 +51,56 @@ pub enum PluginError {
 	#[error("Plugin already loaded: {0}")]
 	AlreadyLoaded(String),
 
+	#[error("Failed to call {0}: {1}")]
+	CallFailed(String, String),
+
+	#[error("Permission denied: {0}")]
+	PermissionDenied(#[from] PermissionError),
+
+	#[error("Extension {0} is suspended: {1}")]
+	Suspended(String, String),
+
+	#[error("Resource limit exceeded: {0}")]
+	LimitExceeded(String),
+
 	#[error("I/O error: {0}")]
 	Io(#[from] std::io::Error),
 }
 
+/// A live plugin instance, the store it runs in and the environment its host functions see
+struct PluginRuntime {
+	store: Store,
+	instance: Instance,
+	env: FunctionEnv<PluginEnv>,
+	/// Set on unload, so handles taken before it stop calling in
+	unloaded: bool,
+}
+
+/// Handle to a loaded extension's instance
+///
+/// A call locks the instance for as long as the export runs and blocks the calling
+/// thread, so async callers take a handle from the plugin manager and make the call from
+/// `spawn_blocking` rather than under the manager's lock.
+#[derive(Clone)]
+pub struct ExtensionInstance {
+	extension_id: String,
+	runtime: Arc<Mutex<PluginRuntime>>,
+	resources: Arc<ExtensionResourceTracker>,
+	events: Arc<EventBus>,
+}
+
 /// Manages WASM plugin lifecycle
 pub struct PluginManager {
-	store: Store,
 	plugins: Arc<RwLock<HashMap<String, LoadedPlugin>>>,
+	/// Instances by extension ID
+	runtimes: HashMap<String, Arc<Mutex<PluginRuntime>>>,
 	plugin_dir: PathBuf,
 	core_context: Arc<CoreContext>,
 	api_dispatcher: Arc<ApiDispatcher>,
 	job_registry: Arc<ExtensionJobRegistry>,
+	hooks: Arc<ExtensionHookRegistry>,
+	resources: Arc<ExtensionResourceTracker>,
+	media_handlers: Arc<MediaHandlerRegistry>,
 }
 
 impl PluginManager {
@@ -56,15 +110,20 @@ impl PluginManager {
 		core_context: Arc<CoreContext>,
 		api_dispatcher: Arc<ApiDispatcher>,
 	) -> Self {
-		let store = Store::default();
+		// Event and schedule delivery runs for the lifetime of the core
+		let hooks = Arc::new(ExtensionHookRegistry::new());
+		hooks.start(&core_context);
 
 		Self {
-			store,
 			plugins: Arc::new(RwLock::new(HashMap::new())),
+			runtimes: HashMap::new(),
 			plugin_dir,
 			core_context,
 			api_dispatcher,
 			job_registry: Arc::new(ExtensionJobRegistry::new()),
+			hooks,
+			resources: Arc::new(ExtensionResourceTracker::new()),
+			media_handlers: Arc::new(MediaHandlerRegistry::new()),
 		}
 	}
 
@@ -73,6 +132,21 @@ impl PluginManager {
 		self.job_registry.clone()
 	}
 
+	/// Get the resource tracker for loaded extensions
+	pub fn resources(&self) -> Arc<ExtensionResourceTracker> {
+		self.resources.clone()
+	}
+
+	/// Get the registry of extension thumbnailers and metadata extractors
+	pub fn media_handlers(&self) -> Arc<MediaHandlerRegistry> {
+		self.media_handlers.clone()
+	}
+
+	/// Get the registry of extension event handlers, schedules and idle jobs
+	pub fn hooks(&self) -> Arc<ExtensionHookRegistry> {
+		self.hooks.clone()
+	}
+
 	/// Load a WASM plugin from directory
 	///
 	/// Expected structure:
@@ -113,8 +187,11 @@ impl PluginManager {
 
 		tracing::debug!("Read {} bytes of WASM", wasm_bytes.len());
 
-		// 3. Compile WASM module
-		let module = Module::new(&self.store, wasm_bytes).map_err(|e| {
+		// 3. Compile WASM module into the extension's store, with its memories capped
+		let memory_limit = MemoryLimit::default();
+		memory_limit.set_mb(manifest.permissions.max_memory_mb);
+		let mut store = limited_store(memory_limit);
+		let module = Module::new(&store, wasm_bytes).map_err(|e| {
 			PluginError::CompilationFailed(format!("Failed to compile WASM: {}", e))
 		})?;
 
@@ -123,10 +200,13 @@ impl PluginManager {
 		// 4. Create plugin environment with temporary memory
 		let permissions =
 			ExtensionPermissions::from_manifest(manifest.id.clone(), &manifest.permissions);
+		let fuel_per_call = permissions.max_fuel_per_call;
+		self.resources.register(&manifest.id, &permissions);
 
 		// Create temporary memory (will be replaced with instance's memory)
-		let temp_memory = Memory::new(&mut self.store, wasmer::MemoryType::new(1, None, false))
+		let temp_memory = Memory::new(&mut store, wasmer::MemoryType::new(1, None, false))
 			.map_err(|e| {
+				self.resources.unregister(&manifest.id);
 				PluginError::InstantiationFailed(format!("Failed to create temp memory: {}", e))
 			})?;
 
@@ -137,68 +217,119 @@ impl PluginManager {
 			permissions,
 			memory: temp_memory,
 			job_registry: self.job_registry.clone(),
+			hooks: self.hooks.clone(),
+			resources: self.resources.clone(),
+			current_library: None,
+			media_handlers: self.media_handlers.clone(),
+			media_file: None,
+			media_output: None,
 		};
 
-		let env = FunctionEnv::new(&mut self.store, plugin_env);
+		let env = FunctionEnv::new(&mut store, plugin_env);
 
 		// 5. Create imports (host functions exposed to WASM)
 		let import_object = imports! {
 			"spacedrive" => {
 				// Core functions
 				"spacedrive_call" => Function::new_typed_with_env(
-					&mut self.store,
+					&mut store,
 					&env,
 					host_spacedrive_call
 				),
 				"spacedrive_log" => Function::new_typed_with_env(
-					&mut self.store,
+					&mut store,
 					&env,
 					host_spacedrive_log
 				),
 
 				// Job-specific functions
 				"job_report_progress" => Function::new_typed_with_env(
-					&mut self.store,
+					&mut store,
 					&env,
 					host_functions::host_job_report_progress
 				),
 				"job_checkpoint" => Function::new_typed_with_env(
-					&mut self.store,
+					&mut store,
 					&env,
 					host_functions::host_job_checkpoint
 				),
 				"job_check_interrupt" => Function::new_typed_with_env(
-					&mut self.store,
+					&mut store,
 					&env,
 					host_functions::host_job_check_interrupt
 				),
 				"job_add_warning" => Function::new_typed_with_env(
-					&mut self.store,
+					&mut store,
 					&env,
 					host_functions::host_job_add_warning
 				),
 				"job_increment_bytes" => Function::new_typed_with_env(
-					&mut self.store,
+					&mut store,
 					&env,
 					host_functions::host_job_increment_bytes
 				),
 				"job_increment_items" => Function::new_typed_with_env(
-					&mut self.store,
+					&mut store,
 					&env,
 					host_functions::host_job_increment_items
 				),
 
 				// Extension registration functions
 				"register_job" => Function::new_typed_with_env(
-					&mut self.store,
+					&mut store,
 					&env,
 					host_functions::host_register_job
 				),
+				"register_event_handler" => Function::new_typed_with_env(
+					&mut store,
+					&env,
+					host_functions::host_register_event_handler
+				),
+				"register_schedule" => Function::new_typed_with_env(
+					&mut store,
+					&env,
+					host_functions::host_register_schedule
+				),
+				"register_startup_handler" => Function::new_typed_with_env(
+					&mut store,
+					&env,
+					host_functions::host_register_startup_handler
+				),
+				"register_media_handler" => Function::new_typed_with_env(
+					&mut store,
+					&env,
+					host_functions::host_register_media_handler
+				),
+
+				// Media handler functions
+				"media_read" => Function::new_typed_with_env(
+					&mut store,
+					&env,
+					host_functions::host_media_read
+				),
+				"media_output" => Function::new_typed_with_env(
+					&mut store,
+					&env,
+					host_functions::host_media_output
+				),
+
+				// Agent functions
+				"job_dispatch" => Function::new_typed_with_env(
+					&mut store,
+					&env,
+					host_functions::host_job_dispatch
+				),
+				"notify" => Function::new_typed_with_env(
+					&mut store,
+					&env,
+					host_functions::host_notify
+				),
 			}
 		};
 
 		// 6. Instantiate WASM module
-		let instance = Instance::new(&mut self.store, &module, &import_object).map_err(|e| {
+		let instance = Instance::new(&mut store, &module, &import_object).map_err(|e| {
+			self.resources.unregister(&manifest.id);
 			PluginError::InstantiationFailed(format!("Failed to instantiate WASM: {}", e))
 		})?;
 
@@ -206,17 +337,23 @@ impl PluginManager {
 
 		// 7. Get actual memory from instance and update environment
 		let memory = instance.exports.get_memory("memory").map_err(|e| {
+			self.resources.unregister(&manifest.id);
 			PluginError::InstantiationFailed(format!("Plugin missing memory export: {}", e))
 		})?;
 
-		env.as_mut(&mut self.store).memory = memory.clone();
+		env.as_mut(&mut store).memory = memory.clone();
 
 		// 8. Call plugin initialization function
 		if let Ok(init_fn) = instance.exports.get_function("plugin_init") {
-			match init_fn.call(&mut self.store, &[]) {
+			set_remaining_points(&mut store, &instance, fuel_per_call);
+			match init_fn.call(&mut store, &[]) {
 				Ok(_) => tracing::info!("Plugin {} initialized successfully", plugin_id),
 				Err(e) => {
 					tracing::error!("Plugin init failed: {}", e);
+					self.hooks.unregister_extension(&manifest.id);
+					self.job_registry.unregister_extension_jobs(&manifest.id);
+					self.media_handlers.unregister_extension(&manifest.id);
+					self.resources.unregister(&manifest.id);
 					return Err(PluginError::InstantiationFailed(format!(
 						"plugin_init() failed: {}",
 						e
@@ -227,7 +364,45 @@ impl PluginManager {
 			tracing::warn!("Plugin {} has no plugin_init() function", plugin_id);
 		}
 
-		// 9. Store loaded plugin
+		// 9. Keep the instance and run startup handlers
+		let extension_id = manifest.id.clone();
+		let memory_bytes = memory.view(&store).data_size();
+		self.resources.record_memory(&extension_id, memory_bytes);
+		self.runtimes.insert(
+			extension_id.clone(),
+			Arc::new(Mutex::new(PluginRuntime {
+				store,
+				instance,
+				env,
+				unloaded: false,
+			})),
+		);
+
+		// Handlers may make host calls, which block on the runtime, so they run off it
+		for export_fn in self.hooks.startup_handlers(&extension_id) {
+			let payload = serde_json::json!({ "extension_id": extension_id });
+			let handler = export_fn.clone();
+			let result = match self.instance(&extension_id) {
+				Ok(instance) => {
+					instance
+						.run(move |instance| instance.call_handler(&handler, &payload, None))
+						.await
+				}
+				Err(e) => Err(e),
+			};
+			match result {
+				Ok(0) => {}
+				Ok(code) => tracing::warn!(
+					"Startup handler {} of plugin {} returned error code {}",
+					export_fn,
+					plugin_id,
+					code
+				),
+				Err(e) => tracing::warn!("Startup handler of plugin {} failed: {}", plugin_id, e),
+			}
+		}
+
+		// 10. Store loaded plugin
 		self.plugins.write().await.insert(
 			plugin_id.to_string(),
 			LoadedPlugin {
@@ -253,7 +428,34 @@ impl PluginManager {
 			.remove(plugin_id)
 			.ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?;
 
-		// TODO: Call plugin_cleanup() if exported
+		let extension_id = &plugin.manifest.id;
+		if let Some(runtime) = self.runtimes.remove(extension_id) {
+			// Waits for a call still running in the instance to finish
+			let cleanup = tokio::task::spawn_blocking(move || {
+				let mut runtime = lock(&runtime);
+				runtime.unloaded = true;
+
+				let runtime = &mut *runtime;
+				if let Ok(cleanup_fn) = runtime.instance.exports.get_function("plugin_cleanup") {
+					let fuel = runtime
+						.env
+						.as_ref(&runtime.store)
+						.permissions
+						.max_fuel_per_call;
+					set_remaining_points(&mut runtime.store, &runtime.instance, fuel);
+					if let Err(e) = cleanup_fn.call(&mut runtime.store, &[]) {
+						tracing::warn!("Plugin cleanup failed: {}", e);
+					}
+				}
+			});
+			if let Err(e) = cleanup.await {
+				tracing::warn!("Plugin cleanup failed: {}", e);
+			}
+		}
+		self.hooks.unregister_extension(extension_id);
+		self.job_registry.unregister_extension_jobs(extension_id);
+		self.media_handlers.unregister_extension(extension_id);
+		self.resources.unregister(extension_id);
 
 		tracing::info!("✓ Plugin {} unloaded", plugin_id);
 
@@ -285,6 +487,400 @@ impl PluginManager {
 			.get(plugin_id)
 			.map(|p| p.manifest.clone())
 	}
+
+	/// Let a suspended extension be called again, returning whether it was suspended
+	pub fn resume_plugin(&self, extension_id: &str) -> bool {
+		let resumed = self.resources.resume(extension_id);
+		if resumed {
+			tracing::info!("Resumed suspended plugin {}", extension_id);
+		}
+		resumed
+	}
+
+	/// Resource usage of every loaded extension
+	pub fn resource_usage(&self) -> Vec<ExtensionResourceUsage> {
+		let mut usage: Vec<_> = self
+			.runtimes
+			.keys()
+			.filter_map(|extension_id| self.resources.usage(extension_id))
+			.collect();
+		usage.sort_by(|a, b| a.extension_id.cmp(&b.extension_id));
+		usage
+	}
+
+	/// Handle to a loaded extension's instance, for calls made outside the manager's lock
+	pub fn instance(&self, extension_id: &str) -> Result<ExtensionInstance, PluginError> {
+		let runtime = self
+			.runtimes
+			.get(extension_id)
+			.ok_or_else(|| PluginError::NotFound(extension_id.to_string()))?;
+
+		Ok(ExtensionInstance {
+			extension_id: extension_id.to_string(),
+			runtime: runtime.clone(),
+			resources: self.resources.clone(),
+			events: self.core_context.events.clone(),
+		})
+	}
+
+	/// Call a hook export with a JSON payload and return its result code
+	///
+	/// See [`ExtensionInstance::call_handler`].
+	pub fn call_handler(
+		&self,
+		extension_id: &str,
+		export_fn: &str,
+		payload: &serde_json::Value,
+		library_id: Option<Uuid>,
+	) -> Result<i32, PluginError> {
+		self.instance(extension_id)?
+			.call_handler(export_fn, payload, library_id)
+	}
+
+	/// Run a job export and return its result code, with the job's fuel budget
+	///
+	/// See [`ExtensionInstance::call_job`].
+	pub fn call_job(
+		&self,
+		extension_id: &str,
+		export_fn: &str,
+		ctx_json: &str,
+		state_json: &str,
+		library_id: Uuid,
+	) -> Result<i32, PluginError> {
+		self.instance(extension_id)?
+			.call_job(export_fn, ctx_json, state_json, library_id)
+	}
+
+	/// Run a thumbnailer or metadata extractor on a file and return what it handed back
+	///
+	/// See [`ExtensionInstance::call_media_handler`].
+	pub fn call_media_handler(
+		&self,
+		handler: &MediaHandlerRegistration,
+		path: &Path,
+		request: &serde_json::Value,
+		library_id: Option<Uuid>,
+	) -> Result<Vec<u8>, PluginError> {
+		self.instance(&handler.extension_id)?.call_media_handler(
+			&handler.export_fn,
+			path,
+			request,
+			library_id,
+		)
+	}
+}
+
+impl ExtensionInstance {
+	/// Make a call on a blocking thread, where waiting for the instance and running the
+	/// export don't hold up the async runtime
+	pub async fn run<T: Send + 'static>(
+		self,
+		call: impl FnOnce(&Self) -> Result<T, PluginError> + Send + 'static,
+	) -> Result<T, PluginError> {
+		let extension_id = self.extension_id.clone();
+		tokio::task::spawn_blocking(move || call(&self))
+			.await
+			.map_err(|e| PluginError::CallFailed(extension_id, e.to_string()))?
+	}
+
+	/// Call a hook export with a JSON payload and return its result code
+	///
+	/// The export is called as `fn(ptr: u32, len: u32) -> i32` with the payload written
+	/// into memory from the guest's `wasm_alloc`. Host calls made while it runs default to
+	/// `library_id`, which the extension must be allowed to access.
+	pub fn call_handler(
+		&self,
+		export_fn: &str,
+		payload: &serde_json::Value,
+		library_id: Option<Uuid>,
+	) -> Result<i32, PluginError> {
+		let mut runtime = self.lock()?;
+		self.check_library_access(&runtime, library_id)?;
+
+		let payload = serde_json::to_vec(payload).map_err(|e| call_failed(export_fn, e))?;
+		let fuel = runtime.permissions().max_fuel_per_call;
+
+		runtime.set_current_library(library_id);
+		let result = self.metered(&mut runtime, export_fn, fuel, |runtime| {
+			let (ptr, len) = runtime.write_payload(export_fn, &payload)?;
+			let handler = runtime
+				.instance
+				.exports
+				.get_typed_function::<(u32, u32), i32>(&runtime.store, export_fn)
+				.map_err(|e| call_failed(export_fn, e))?;
+			let result = handler
+				.call(&mut runtime.store, ptr, len)
+				.map_err(|e| call_failed(export_fn, e));
+			runtime.free_payload(ptr, len);
+			result
+		});
+		runtime.set_current_library(None);
+
+		result
+	}
+
+	/// Run a job export and return its result code, with the job's fuel budget
+	///
+	/// The export is called as `fn(ctx_ptr: u32, ctx_len: u32, state_ptr: u32,
+	/// state_len: u32) -> i32`, the ABI the SDK's `#[job]` macro generates.
+	pub fn call_job(
+		&self,
+		export_fn: &str,
+		ctx_json: &str,
+		state_json: &str,
+		library_id: Uuid,
+	) -> Result<i32, PluginError> {
+		let mut runtime = self.lock()?;
+		let fuel = runtime.permissions().max_fuel_per_job;
+
+		runtime.set_current_library(Some(library_id));
+		let result = self.metered(&mut runtime, export_fn, fuel, |runtime| {
+			let (ctx_ptr, ctx_len) = runtime.write_payload(export_fn, ctx_json.as_bytes())?;
+			let (state_ptr, state_len) = runtime.write_payload(export_fn, state_json.as_bytes())?;
+			let job = runtime
+				.instance
+				.exports
+				.get_typed_function::<(u32, u32, u32, u32), i32>(&runtime.store, export_fn)
+				.map_err(|e| call_failed(export_fn, e))?;
+			let result = job
+				.call(&mut runtime.store, ctx_ptr, ctx_len, state_ptr, state_len)
+				.map_err(|e| call_failed(export_fn, e));
+			runtime.free_payload(state_ptr, state_len);
+			runtime.free_payload(ctx_ptr, ctx_len);
+			result
+		});
+		runtime.set_current_library(None);
+
+		result
+	}
+
+	/// Run a thumbnailer or metadata extractor on a file and return what it handed back
+	///
+	/// The export is called as `fn(request_ptr: u32, request_len: u32, data_ptr: u32,
+	/// data_len: u32) -> i32` with the request JSON and up to `MEDIA_HEAD_BYTES` from the
+	/// start of the file. It reads the rest with `media_read`, hands back its result with
+	/// `media_output` and returns 0. Rendering can be slow, so it runs with the job fuel
+	/// budget.
+	pub fn call_media_handler(
+		&self,
+		export_fn: &str,
+		path: &Path,
+		request: &serde_json::Value,
+		library_id: Option<Uuid>,
+	) -> Result<Vec<u8>, PluginError> {
+		let mut runtime = self.lock()?;
+		self.check_library_access(&runtime, library_id)?;
+
+		let request = serde_json::to_vec(request).map_err(|e| call_failed(export_fn, e))?;
+		let mut file = std::fs::File::open(path)?;
+		let mut head = Vec::new();
+		file.by_ref()
+			.take(MEDIA_HEAD_BYTES)
+			.read_to_end(&mut head)?;
+		let fuel = runtime.permissions().max_fuel_per_job;
+
+		{
+			let runtime = &mut *runtime;
+			let env = runtime.env.as_mut(&mut runtime.store);
+			env.current_library = library_id;
+			env.media_file = Some(file);
+			env.media_output = None;
+		}
+		let result = self.metered(&mut runtime, export_fn, fuel, |runtime| {
+			let (request_ptr, request_len) = runtime.write_payload(export_fn, &request)?;
+			let (data_ptr, data_len) = runtime.write_payload(export_fn, &head)?;
+			let handler = runtime
+				.instance
+				.exports
+				.get_typed_function::<(u32, u32, u32, u32), i32>(&runtime.store, export_fn)
+				.map_err(|e| call_failed(export_fn, e))?;
+			let result = handler
+				.call(
+					&mut runtime.store,
+					request_ptr,
+					request_len,
+					data_ptr,
+					data_len,
+				)
+				.map_err(|e| call_failed(export_fn, e));
+			runtime.free_payload(data_ptr, data_len);
+			runtime.free_payload(request_ptr, request_len);
+			result
+		});
+		let output = {
+			let runtime = &mut *runtime;
+			let env = runtime.env.as_mut(&mut runtime.store);
+			env.current_library = None;
+			env.media_file = None;
+			env.media_output.take()
+		};
+
+		match result? {
+			0 => output.ok_or_else(|| call_failed(export_fn, "no output was handed back")),
+			code => Err(call_failed(
+				export_fn,
+				format!("returned error code {}", code),
+			)),
+		}
+	}
+
+	/// Lock the instance of an extension that is still loaded and isn't suspended
+	fn lock(&self) -> Result<MutexGuard<'_, PluginRuntime>, PluginError> {
+		let runtime = lock(&self.runtime);
+		if runtime.unloaded {
+			return Err(PluginError::NotFound(self.extension_id.clone()));
+		}
+
+		if let Some(reason) = self.resources.suspended_reason(&self.extension_id) {
+			return Err(PluginError::Suspended(self.extension_id.clone(), reason));
+		}
+
+		Ok(runtime)
+	}
+
+	fn check_library_access(
+		&self,
+		runtime: &PluginRuntime,
+		library_id: Option<Uuid>,
+	) -> Result<(), PluginError> {
+		match library_id {
+			Some(library_id) if !runtime.permissions().can_access_library(library_id) => {
+				Err(PermissionError::LibraryAccessDenied(format!(
+					"Extension {} cannot access library {}",
+					self.extension_id, library_id
+				))
+				.into())
+			}
+			_ => Ok(()),
+		}
+	}
+
+	/// Run `call` with `fuel` available to the extension and record what it used
+	///
+	/// A call that fails after running out of fuel, or with the extension's memory at its
+	/// cap, suspends the extension.
+	fn metered(
+		&self,
+		runtime: &mut PluginRuntime,
+		export_fn: &str,
+		fuel: u64,
+		call: impl FnOnce(&mut PluginRuntime) -> Result<i32, PluginError>,
+	) -> Result<i32, PluginError> {
+		set_remaining_points(&mut runtime.store, &runtime.instance, fuel);
+		let result = call(runtime);
+
+		let remaining = get_remaining_points(&mut runtime.store, &runtime.instance);
+		let used = match remaining {
+			MeteringPoints::Remaining(points) => fuel.saturating_sub(points),
+			MeteringPoints::Exhausted => fuel,
+		};
+		self.resources.record_call(&self.extension_id, used);
+		self.resources
+			.record_memory(&self.extension_id, runtime.memory_bytes());
+
+		if result.is_ok() {
+			return result;
+		}
+
+		let reason = if matches!(remaining, MeteringPoints::Exhausted) {
+			format!("{} used all of its {} fuel", export_fn, fuel)
+		} else if runtime.memory_at_limit() {
+			format!("{} failed with memory at its limit", export_fn)
+		} else {
+			return result;
+		};
+
+		self.resources
+			.suspend(&self.extension_id, reason.clone(), &self.events);
+		Err(PluginError::LimitExceeded(reason))
+	}
+}
+
+impl PluginRuntime {
+	fn permissions(&self) -> &ExtensionPermissions {
+		&self.env.as_ref(&self.store).permissions
+	}
+
+	/// Library host calls default to while an export runs
+	fn set_current_library(&mut self, library_id: Option<Uuid>) {
+		self.env.as_mut(&mut self.store).current_library = library_id;
+	}
+
+	/// Current size of the extension's linear memory
+	fn memory_bytes(&self) -> u64 {
+		self.env
+			.as_ref(&self.store)
+			.memory
+			.view(&self.store)
+			.data_size()
+	}
+
+	/// Whether the extension's memory has grown to its cap
+	fn memory_at_limit(&self) -> bool {
+		let max_bytes = self.permissions().max_memory_mb as u64 * 1024 * 1024;
+
+		self.memory_bytes() >= max_bytes
+	}
+
+	/// Copy a payload into guest memory from its `wasm_alloc`, returning `(ptr, len)`
+	fn write_payload(
+		&mut self,
+		export_fn: &str,
+		payload: &[u8],
+	) -> Result<(u32, u32), PluginError> {
+		let len = payload.len() as u32;
+		if payload.is_empty() {
+			return Ok((0, 0));
+		}
+
+		let memory = self
+			.instance
+			.exports
+			.get_memory("memory")
+			.map_err(|e| call_failed(export_fn, e))?
+			.clone();
+		let alloc = self
+			.instance
+			.exports
+			.get_typed_function::<u32, u32>(&self.store, "wasm_alloc")
+			.map_err(|e| call_failed(export_fn, e))?;
+		let ptr = alloc
+			.call(&mut self.store, len)
+			.map_err(|e| call_failed(export_fn, e))?;
+		memory
+			.view(&self.store)
+			.write(ptr as u64, payload)
+			.map_err(|e| call_failed(export_fn, e))?;
+
+		Ok((ptr, len))
+	}
+
+	/// Release a payload with the guest's `wasm_free`, if it exports one
+	fn free_payload(&mut self, ptr: u32, len: u32) {
+		if ptr == 0 {
+			return;
+		}
+		if let Ok(free) = self
+			.instance
+			.exports
+			.get_typed_function::<(u32, u32), ()>(&self.store, "wasm_free")
+		{
+			let _ = free.call(&mut self.store, ptr, len);
+		}
+	}
+}
+
+/// Lock an extension's instance, recovering it if a call panicked while holding it
+fn lock(runtime: &Mutex<PluginRuntime>) -> MutexGuard<'_, PluginRuntime> {
+	runtime.lock().unwrap_or_else(|poisoned| {
+		tracing::warn!("Extension instance lock was poisoned, recovering");
+		poisoned.into_inner()
+	})
+}
+
+fn call_failed(export_fn: &str, error: impl std::fmt::Display) -> PluginError {
+	PluginError::CallFailed(export_fn.to_string(), error.to_string())
 }
 
 #[cfg(test)]
@@ -0,0 +1,276 @@
+//! Thumbnailers and metadata extractors registered by WASM extensions
+//!
+//! Extensions register an export as the handler for MIME types or file extensions,
+//! usually from `plugin_init()`. Thumbnail generation and metadata extraction look up a
+//! handler for files they meet, call it with the start of the file (the rest is
+//! available through ranged reads) and store what it returns as a sidecar.
+//!
+//! Patterns are exact MIME types (`"model/vnd.blender"`), MIME wildcards (`"image/*"`)
+//! or file extensions with a leading dot (`".blend"`). Exact matches win over
+//! wildcards; among equal matches the earliest registration wins.
+
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// What a media handler produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaHandlerKind {
+	/// Returns an encoded image (PNG, JPEG, WebP, ...) that core resizes into thumbnails
+	Thumbnailer,
+	/// Returns a JSON document stored as a metadata sidecar
+	MetadataExtractor,
+}
+
+impl MediaHandlerKind {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Thumbnailer => "thumbnailer",
+			Self::MetadataExtractor => "metadata",
+		}
+	}
+}
+
+impl TryFrom<u32> for MediaHandlerKind {
+	type Error = String;
+
+	fn try_from(value: u32) -> Result<Self, Self::Error> {
+		match value {
+			0 => Ok(Self::Thumbnailer),
+			1 => Ok(Self::MetadataExtractor),
+			_ => Err(format!("Invalid media handler kind: {}", value)),
+		}
+	}
+}
+
+/// An extension export registered as a thumbnailer or metadata extractor
+#[derive(Debug, Clone)]
+pub struct MediaHandlerRegistration {
+	pub extension_id: String,
+	pub kind: MediaHandlerKind,
+	/// MIME types, MIME wildcards and dotted file extensions the handler accepts
+	pub patterns: Vec<String>,
+	/// WASM export called with the request and the start of the file
+	pub export_fn: String,
+}
+
+impl MediaHandlerRegistration {
+	/// How specifically the handler matches a file: 2 for an exact MIME type or file
+	/// extension, 1 for a MIME wildcard, `None` if it doesn't
+	fn match_rank(&self, mime_type: Option<&str>, extension: Option<&str>) -> Option<u8> {
+		self.patterns
+			.iter()
+			.filter_map(|pattern| {
+				if let Some(pattern_ext) = pattern.strip_prefix('.') {
+					extension
+						.filter(|ext| ext.eq_ignore_ascii_case(pattern_ext))
+						.map(|_| 2)
+				} else if let Some(prefix) = pattern.strip_suffix("/*") {
+					mime_type
+						.and_then(|mime| mime.split_once('/'))
+						.filter(|(top_level, _)| top_level.eq_ignore_ascii_case(prefix))
+						.map(|_| 1)
+				} else {
+					mime_type
+						.filter(|mime| mime.eq_ignore_ascii_case(pattern))
+						.map(|_| 2)
+				}
+			})
+			.max()
+	}
+}
+
+/// Runtime registry for extension media handlers
+#[derive(Default)]
+pub struct MediaHandlerRegistry {
+	handlers: RwLock<Vec<MediaHandlerRegistration>>,
+}
+
+impl MediaHandlerRegistry {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Register an export as a handler for the given patterns
+	pub fn register(
+		&self,
+		extension_id: String,
+		kind: MediaHandlerKind,
+		patterns: Vec<String>,
+		export_fn: String,
+	) -> Result<(), String> {
+		if patterns.is_empty() {
+			return Err("A media handler needs at least one pattern".to_string());
+		}
+		if let Some(pattern) = patterns.iter().find(|pattern| !is_valid_pattern(pattern)) {
+			return Err(format!("Invalid media handler pattern '{}'", pattern));
+		}
+
+		tracing::info!(
+			"Registered extension {}: {:?} -> {}:{}",
+			kind.as_str(),
+			patterns,
+			extension_id,
+			export_fn
+		);
+
+		write(&self.handlers).push(MediaHandlerRegistration {
+			extension_id,
+			kind,
+			patterns,
+			export_fn,
+		});
+		Ok(())
+	}
+
+	/// The handler of a kind best matching a file, if any
+	pub fn find(
+		&self,
+		kind: MediaHandlerKind,
+		mime_type: Option<&str>,
+		extension: Option<&str>,
+	) -> Option<MediaHandlerRegistration> {
+		let handlers = read(&self.handlers);
+		let mut best: Option<(u8, &MediaHandlerRegistration)> = None;
+
+		for handler in handlers.iter().filter(|handler| handler.kind == kind) {
+			if let Some(rank) = handler.match_rank(mime_type, extension) {
+				if best.map_or(true, |(best_rank, _)| rank > best_rank) {
+					best = Some((rank, handler));
+				}
+			}
+		}
+
+		best.map(|(_, handler)| handler.clone())
+	}
+
+	/// Handlers registered by an extension
+	pub fn handlers_for_extension(&self, extension_id: &str) -> Vec<MediaHandlerRegistration> {
+		read(&self.handlers)
+			.iter()
+			.filter(|handler| handler.extension_id == extension_id)
+			.cloned()
+			.collect()
+	}
+
+	/// Remove every handler an extension registered (called on unload)
+	pub fn unregister_extension(&self, extension_id: &str) {
+		write(&self.handlers).retain(|handler| handler.extension_id != extension_id);
+	}
+}
+
+fn is_valid_pattern(pattern: &str) -> bool {
+	if let Some(extension) = pattern.strip_prefix('.') {
+		return !extension.is_empty() && !extension.contains(['.', '/']);
+	}
+	match pattern.split_once('/') {
+		Some((top_level, subtype)) => {
+			!top_level.is_empty() && top_level != "*" && !subtype.is_empty()
+		}
+		None => false,
+	}
+}
+
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+	lock.read().unwrap_or_else(|poisoned| {
+		tracing::warn!("Extension media handler registry lock was poisoned, recovering");
+		poisoned.into_inner()
+	})
+}
+
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+	lock.write().unwrap_or_else(|poisoned| {
+		tracing::warn!("Extension media handler registry lock was poisoned, recovering");
+		poisoned.into_inner()
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn patterns(patterns: &[&str]) -> Vec<String> {
+		patterns.iter().map(|p| p.to_string()).collect()
+	}
+
+	#[test]
+	fn test_find_prefers_exact_matches() {
+		let registry = MediaHandlerRegistry::new();
+		registry
+			.register(
+				"generic".to_string(),
+				MediaHandlerKind::Thumbnailer,
+				patterns(&["model/*"]),
+				"thumbnailer_any_model".to_string(),
+			)
+			.unwrap();
+		registry
+			.register(
+				"blender".to_string(),
+				MediaHandlerKind::Thumbnailer,
+				patterns(&["application/x-blender", ".blend"]),
+				"thumbnailer_blend".to_string(),
+			)
+			.unwrap();
+
+		let blend = registry
+			.find(MediaHandlerKind::Thumbnailer, None, Some("BLEND"))
+			.unwrap();
+		assert_eq!(blend.extension_id, "blender");
+
+		let blend = registry
+			.find(
+				MediaHandlerKind::Thumbnailer,
+				Some("application/x-blender"),
+				None,
+			)
+			.unwrap();
+		assert_eq!(blend.export_fn, "thumbnailer_blend");
+
+		let step = registry
+			.find(
+				MediaHandlerKind::Thumbnailer,
+				Some("model/step"),
+				Some("stp"),
+			)
+			.unwrap();
+		assert_eq!(step.extension_id, "generic");
+
+		assert!(registry
+			.find(MediaHandlerKind::MetadataExtractor, None, Some("blend"))
+			.is_none());
+		assert!(registry
+			.find(
+				MediaHandlerKind::Thumbnailer,
+				Some("text/plain"),
+				Some("txt")
+			)
+			.is_none());
+
+		registry.unregister_extension("blender");
+		let blend = registry
+			.find(
+				MediaHandlerKind::Thumbnailer,
+				Some("model/x-blender"),
+				Some("blend"),
+			)
+			.unwrap();
+		assert_eq!(blend.extension_id, "generic");
+		assert!(registry.handlers_for_extension("blender").is_empty());
+	}
+
+	#[test]
+	fn test_invalid_patterns_rejected() {
+		let registry = MediaHandlerRegistry::new();
+		let invalid_patterns: [&[&str]; 5] = [&[], &["blend"], &["*/*"], &["."], &["image/"]];
+		for invalid in invalid_patterns {
+			assert!(registry
+				.register(
+					"test".to_string(),
+					MediaHandlerKind::MetadataExtractor,
+					patterns(invalid),
+					"metadata_test".to_string(),
+				)
+				.is_err());
+		}
+		assert!(registry.handlers_for_extension("test").is_empty());
+	}
+}
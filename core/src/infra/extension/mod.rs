@@ -14,26 +14,46 @@
 //!
 //! - `manager`: Plugin lifecycle management (load, unload, hot-reload)
 //! - `host_functions`: WASM host functions (bridge to operation registry)
+//! - `hooks`: Event handlers, schedules and startup handlers registered by extensions
+//! - `limits`: Fuel metering, memory caps and suspension of extensions over their limits
+//! - `media_handlers`: Thumbnailers and metadata extractors registered by extensions
 //! - `permissions`: Capability-based security model
 //! - `types`: Shared types and manifest format
 
+#[cfg(feature = "wasm")]
+mod hooks;
 #[cfg(feature = "wasm")]
 mod host_functions;
 #[cfg(feature = "wasm")]
 mod job_registry;
 #[cfg(feature = "wasm")]
+mod limits;
+#[cfg(feature = "wasm")]
 mod manager;
 #[cfg(feature = "wasm")]
+mod media_handlers;
+#[cfg(feature = "wasm")]
 mod permissions;
 #[cfg(feature = "wasm")]
 mod types;
 #[cfg(feature = "wasm")]
 mod wasm_job;
 
+#[cfg(feature = "wasm")]
+pub use hooks::{
+	EventHandlerRegistration, ExtensionHookRegistry, HookClock, IdleDispatch, ScheduleRegistration,
+	SystemClock,
+};
+#[cfg(feature = "wasm")]
+pub use host_functions::JOB_DISPATCH_WHEN_IDLE;
 #[cfg(feature = "wasm")]
 pub use job_registry::{ExtensionJobRegistration, ExtensionJobRegistry};
 #[cfg(feature = "wasm")]
-pub use manager::PluginManager;
+pub use limits::{ExtensionResourceTracker, ExtensionResourceUsage};
+#[cfg(feature = "wasm")]
+pub use manager::{ExtensionInstance, PluginError, PluginManager};
+#[cfg(feature = "wasm")]
+pub use media_handlers::{MediaHandlerKind, MediaHandlerRegistration, MediaHandlerRegistry};
 #[cfg(feature = "wasm")]
 pub use permissions::{ExtensionPermissions, PermissionError};
 #[cfg(feature = "wasm")]
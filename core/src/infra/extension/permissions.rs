@@ -36,12 +36,30 @@ pub struct ExtensionPermissions {
 	/// Libraries this extension can access ("*" or specific UUIDs)
 	allowed_libraries: Vec<String>,
 
+	/// Event types this extension can subscribe to ("*" or variant names)
+	allowed_events: Vec<String>,
+
+	/// Whether this extension can register scheduled tasks
+	allow_schedules: bool,
+
+	/// Whether this extension can dispatch its own jobs
+	allow_job_dispatch: bool,
+
+	/// Whether this extension can send notifications
+	allow_notifications: bool,
+
+	/// Whether this extension can register thumbnailers and metadata extractors
+	allow_media_handlers: bool,
+
 	/// Rate limiting state
 	rate_limiter: Arc<RwLock<RateLimiter>>,
 
 	/// Resource limits
 	pub max_memory_mb: usize,
 	pub max_concurrent_jobs: usize,
+	pub max_fuel_per_call: u64,
+	pub max_fuel_per_job: u64,
+	pub host_call_timeout: Duration,
 }
 
 struct RateLimiter {
@@ -49,6 +67,31 @@ struct RateLimiter {
 	recent_requests: Vec<Instant>,
 }
 
+impl RateLimiter {
+	/// Record a request, or fail if the last minute is already at the limit
+	fn record(&mut self, extension_id: &str) -> Result<(), PermissionError> {
+		let now = Instant::now();
+		let one_minute_ago = now - Duration::from_secs(60);
+
+		// Remove requests older than 1 minute
+		self.recent_requests
+			.retain(|&timestamp| timestamp > one_minute_ago);
+
+		// Check if under limit
+		if self.recent_requests.len() >= self.requests_per_minute {
+			return Err(PermissionError::RateLimitExceeded(format!(
+				"Extension {} exceeded {} requests/minute",
+				extension_id, self.requests_per_minute
+			)));
+		}
+
+		// Record this request
+		self.recent_requests.push(now);
+
+		Ok(())
+	}
+}
+
 impl ExtensionPermissions {
 	/// Create permissions from manifest
 	pub fn from_manifest(extension_id: String, manifest_perms: &ManifestPermissions) -> Self {
@@ -56,12 +99,20 @@ impl ExtensionPermissions {
 			extension_id,
 			allowed_methods: manifest_perms.methods.clone(),
 			allowed_libraries: manifest_perms.libraries.clone(),
+			allowed_events: manifest_perms.events.clone(),
+			allow_schedules: manifest_perms.schedules,
+			allow_job_dispatch: manifest_perms.dispatch_jobs,
+			allow_notifications: manifest_perms.notifications,
+			allow_media_handlers: manifest_perms.media_handlers,
 			rate_limiter: Arc::new(RwLock::new(RateLimiter {
 				requests_per_minute: manifest_perms.rate_limits.requests_per_minute,
 				recent_requests: Vec::new(),
 			})),
 			max_memory_mb: manifest_perms.max_memory_mb,
 			max_concurrent_jobs: manifest_perms.rate_limits.concurrent_jobs,
+			max_fuel_per_call: manifest_perms.max_fuel_per_call,
+			max_fuel_per_job: manifest_perms.max_fuel_per_job,
+			host_call_timeout: Duration::from_secs(manifest_perms.host_call_timeout_secs),
 		}
 	}
 
@@ -86,30 +137,48 @@ impl ExtensionPermissions {
 			.any(|id| id.parse::<Uuid>().ok() == Some(library_id))
 	}
 
-	/// Check rate limit and record request
-	pub async fn check_rate_limit(&self) -> Result<(), PermissionError> {
-		let mut limiter = self.rate_limiter.write().await;
+	/// Check if extension can subscribe to this event type
+	pub fn can_receive_event(&self, event_type: &str) -> bool {
+		self.allowed_events
+			.iter()
+			.any(|allowed| allowed == "*" || allowed == event_type)
+	}
 
-		let now = Instant::now();
-		let one_minute_ago = now - Duration::from_secs(60);
+	/// Check if extension can register scheduled tasks
+	pub fn can_schedule(&self) -> bool {
+		self.allow_schedules
+	}
 
-		// Remove requests older than 1 minute
-		limiter
-			.recent_requests
-			.retain(|&timestamp| timestamp > one_minute_ago);
+	/// Check if extension can dispatch its own jobs
+	pub fn can_dispatch_jobs(&self) -> bool {
+		self.allow_job_dispatch
+	}
 
-		// Check if under limit
-		if limiter.recent_requests.len() >= limiter.requests_per_minute {
-			return Err(PermissionError::RateLimitExceeded(format!(
-				"Extension {} exceeded {} requests/minute",
-				self.extension_id, limiter.requests_per_minute
-			)));
-		}
+	/// Check if extension can send notifications
+	pub fn can_notify(&self) -> bool {
+		self.allow_notifications
+	}
 
-		// Record this request
-		limiter.recent_requests.push(now);
+	/// Check if extension can register thumbnailers and metadata extractors
+	pub fn can_handle_media(&self) -> bool {
+		self.allow_media_handlers
+	}
 
-		Ok(())
+	/// Check rate limit and record request
+	pub async fn check_rate_limit(&self) -> Result<(), PermissionError> {
+		self.rate_limiter.write().await.record(&self.extension_id)
+	}
+
+	/// Check rate limit from a host function, which runs synchronously and can't wait
+	/// for the limiter lock. A contended lock counts as exceeding the limit.
+	pub fn try_check_rate_limit(&self) -> Result<(), PermissionError> {
+		let mut limiter = self.rate_limiter.try_write().map_err(|_| {
+			PermissionError::RateLimitExceeded(format!(
+				"Extension {} rate limiter is busy",
+				self.extension_id
+			))
+		})?;
+		limiter.record(&self.extension_id)
 	}
 
 	/// Full permission check for a Wire operation
@@ -146,6 +215,7 @@ impl ExtensionPermissions {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::infra::extension::types::RateLimits;
 
 	#[test]
 	fn test_method_permission() {
@@ -153,12 +223,20 @@ mod tests {
 			extension_id: "test".to_string(),
 			allowed_methods: vec!["vdfs.".to_string(), "ai.ocr".to_string()],
 			allowed_libraries: vec!["*".to_string()],
+			allowed_events: vec![],
+			allow_schedules: false,
+			allow_job_dispatch: false,
+			allow_notifications: false,
+			allow_media_handlers: false,
 			rate_limiter: Arc::new(RwLock::new(RateLimiter {
 				requests_per_minute: 1000,
 				recent_requests: Vec::new(),
 			})),
 			max_memory_mb: 512,
 			max_concurrent_jobs: 10,
+			max_fuel_per_call: 1_000_000_000,
+			max_fuel_per_job: 100_000_000_000,
+			host_call_timeout: Duration::from_secs(30),
 		};
 
 		assert!(perms.can_call("vdfs.create_entry"));
@@ -175,12 +253,20 @@ mod tests {
 			extension_id: "test".to_string(),
 			allowed_methods: vec![],
 			allowed_libraries: vec!["*".to_string()],
+			allowed_events: vec![],
+			allow_schedules: false,
+			allow_job_dispatch: false,
+			allow_notifications: false,
+			allow_media_handlers: false,
 			rate_limiter: Arc::new(RwLock::new(RateLimiter {
 				requests_per_minute: 1000,
 				recent_requests: Vec::new(),
 			})),
 			max_memory_mb: 512,
 			max_concurrent_jobs: 10,
+			max_fuel_per_call: 1_000_000_000,
+			max_fuel_per_job: 100_000_000_000,
+			host_call_timeout: Duration::from_secs(30),
 		};
 
 		assert!(perms_all.can_access_library(lib_id));
@@ -189,15 +275,66 @@ mod tests {
 			extension_id: "test".to_string(),
 			allowed_methods: vec![],
 			allowed_libraries: vec![lib_id.to_string()],
+			allowed_events: vec![],
+			allow_schedules: false,
+			allow_job_dispatch: false,
+			allow_notifications: false,
+			allow_media_handlers: false,
 			rate_limiter: Arc::new(RwLock::new(RateLimiter {
 				requests_per_minute: 1000,
 				recent_requests: Vec::new(),
 			})),
 			max_memory_mb: 512,
 			max_concurrent_jobs: 10,
+			max_fuel_per_call: 1_000_000_000,
+			max_fuel_per_job: 100_000_000_000,
+			host_call_timeout: Duration::from_secs(30),
 		};
 
 		assert!(perms_specific.can_access_library(lib_id));
 		assert!(!perms_specific.can_access_library(Uuid::new_v4()));
 	}
+
+	#[test]
+	fn test_hook_permissions() {
+		let manifest_perms = ManifestPermissions {
+			events: vec!["EntryCreated".to_string()],
+			notifications: true,
+			..Default::default()
+		};
+		let perms = ExtensionPermissions::from_manifest("test".to_string(), &manifest_perms);
+
+		assert!(perms.can_receive_event("EntryCreated"));
+		assert!(!perms.can_receive_event("JobFailed"));
+		assert!(perms.can_notify());
+		assert!(!perms.can_schedule());
+		assert!(!perms.can_dispatch_jobs());
+		assert!(!perms.can_handle_media());
+
+		let all_events = ManifestPermissions {
+			events: vec!["*".to_string()],
+			..Default::default()
+		};
+		let perms = ExtensionPermissions::from_manifest("test".to_string(), &all_events);
+		assert!(perms.can_receive_event("JobFailed"));
+	}
+
+	#[test]
+	fn test_rate_limit() {
+		let manifest_perms = ManifestPermissions {
+			rate_limits: RateLimits {
+				requests_per_minute: 2,
+				concurrent_jobs: 1,
+			},
+			..Default::default()
+		};
+		let perms = ExtensionPermissions::from_manifest("test".to_string(), &manifest_perms);
+
+		assert!(perms.try_check_rate_limit().is_ok());
+		assert!(perms.try_check_rate_limit().is_ok());
+		assert!(matches!(
+			perms.try_check_rate_limit(),
+			Err(PermissionError::RateLimitExceeded(_))
+		));
+	}
 }
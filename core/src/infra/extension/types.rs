@@ -47,14 +47,62 @@ pub struct ManifestPermissions {
 	pub network_access: Vec<String>,
 
 	/// Resource limits
-	#[serde(default)]
+	#[serde(default = "default_max_memory_mb")]
 	pub max_memory_mb: usize,
+
+	/// Fuel (WASM operators executed) allowed per call into the extension
+	#[serde(default = "default_max_fuel_per_call")]
+	pub max_fuel_per_call: u64,
+
+	/// Fuel allowed per job run
+	#[serde(default = "default_max_fuel_per_job")]
+	pub max_fuel_per_job: u64,
+
+	/// Seconds a host call can take before it's abandoned
+	#[serde(default = "default_host_call_timeout_secs")]
+	pub host_call_timeout_secs: u64,
+
+	/// Event types the extension can subscribe to ("*" = all)
+	#[serde(default)]
+	pub events: Vec<String>,
+
+	/// Whether the extension can register scheduled tasks
+	#[serde(default)]
+	pub schedules: bool,
+
+	/// Whether the extension can dispatch its own jobs
+	#[serde(default)]
+	pub dispatch_jobs: bool,
+
+	/// Whether the extension can send notifications
+	#[serde(default)]
+	pub notifications: bool,
+
+	/// Whether the extension can register thumbnailers and metadata extractors
+	#[serde(default)]
+	pub media_handlers: bool,
 }
 
 fn default_all_libraries() -> Vec<String> {
 	vec!["*".to_string()]
 }
 
+fn default_max_memory_mb() -> usize {
+	512
+}
+
+fn default_max_fuel_per_call() -> u64 {
+	1_000_000_000
+}
+
+fn default_max_fuel_per_job() -> u64 {
+	100_000_000_000
+}
+
+fn default_host_call_timeout_secs() -> u64 {
+	30
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RateLimits {
 	#[serde(default = "default_requests_per_minute")]
@@ -88,7 +136,15 @@ impl Default for ManifestPermissions {
 			libraries: vec!["*".to_string()],
 			rate_limits: RateLimits::default(),
 			network_access: vec![],
-			max_memory_mb: 512,
+			max_memory_mb: default_max_memory_mb(),
+			max_fuel_per_call: default_max_fuel_per_call(),
+			max_fuel_per_job: default_max_fuel_per_job(),
+			host_call_timeout_secs: default_host_call_timeout_secs(),
+			events: vec![],
+			schedules: false,
+			dispatch_jobs: false,
+			notifications: false,
+			media_handlers: false,
 		}
 	}
 }
@@ -70,46 +70,69 @@ impl JobHandler for WasmJob {
 		});
 		let ctx_json_str = serde_json::to_string(&job_ctx_json).unwrap();
 
+		// Hold one of the extension's job slots while the job runs
+		let job_slots = pm
+			.read()
+			.await
+			.resources()
+			.job_slots(&self.extension_id)
+			.ok_or_else(|| {
+				crate::infra::job::error::JobError::ExecutionFailed(format!(
+					"Extension '{}' not loaded",
+					self.extension_id
+				))
+			})?;
+		if job_slots.available_permits() == 0 {
+			ctx.log(&format!(
+				"Waiting for a free job slot for extension '{}'",
+				self.extension_id
+			));
+		}
+		let _slot = job_slots.acquire_owned().await.map_err(|_| {
+			crate::infra::job::error::JobError::ExecutionFailed(format!(
+				"Extension '{}' was unloaded",
+				self.extension_id
+			))
+		})?;
+
 		ctx.log(&format!(
 			"Calling WASM function: {}::{}",
 			self.extension_id, self.export_fn
 		));
 
-		// Call WASM export function
-		let result = {
-			let pm_lock = pm.write().await;
-
-			// For now, just verify the plugin is loaded
-			let plugins = pm_lock.list_plugins().await;
-			if !plugins.contains(&self.extension_id) {
-				ctx.log(&format!(
-					"ERROR: Extension '{}' not loaded",
-					self.extension_id
-				));
-				return Err(crate::infra::job::error::JobError::ExecutionFailed(
-					format!("Extension '{}' not loaded", self.extension_id),
-				));
+		// Call WASM export function, outside the plugin manager's lock
+		let instance = pm.read().await.instance(&self.extension_id);
+		let result = match instance {
+			Ok(instance) => {
+				let export_fn = self.export_fn.clone();
+				let state_json = self.state_json.clone();
+				let library_id = ctx.library().id();
+				instance
+					.run(move |instance| {
+						instance.call_job(&export_fn, &ctx_json_str, &state_json, library_id)
+					})
+					.await
 			}
-
-			ctx.log(&format!("✓ Extension '{}' is loaded", self.extension_id));
-
-			// TODO: Actually call the WASM export
-			// Need to:
-			// 1. Get the Instance for this plugin
-			// 2. Get the export function
-			// 3. Write ctx_json_str and state_json to WASM memory
-			// 4. Call the function with pointers
-			// 5. Read result code
-
-			ctx.log("WASM export call not yet implemented");
-			ctx.log("But the job executed and extension is available!");
-
-			0 // Success code
+			Err(e) => Err(e),
 		};
 
-		ctx.log(&format!("✓ WASM job completed with code: {}", result));
-
-		Ok(JobOutput::Success)
+		match result {
+			Ok(0) => {
+				ctx.log("✓ WASM job completed");
+				Ok(JobOutput::Success)
+			}
+			// Exit codes from the SDK's JobResult
+			Ok(1) => Err(crate::infra::job::error::JobError::Interrupted),
+			Ok(code) => Err(crate::infra::job::error::JobError::ExecutionFailed(
+				format!("WASM job returned error code {}", code),
+			)),
+			Err(e) => {
+				ctx.log(&format!("ERROR: {}", e));
+				Err(crate::infra::job::error::JobError::ExecutionFailed(
+					e.to_string(),
+				))
+			}
+		}
 	}
 
 	async fn on_resume(&mut self, ctx: &JobContext<'_>) -> JobResult<()> {
@@ -114,6 +114,42 @@ pub mod checkpoint {
 	impl ActiveModelBehavior for ActiveModel {}
 }
 
+pub mod schedules {
+	use super::*;
+
+	/// Persisted job schedule
+	#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+	#[sea_orm(table_name = "job_schedules")]
+	pub struct Model {
+		#[sea_orm(primary_key, auto_increment = false)]
+		pub id: String,
+		pub name: String,
+		pub job_name: String,
+		pub args: JsonValue,
+
+		// Serialized ScheduleTrigger and Vec<ScheduleCondition>
+		pub trigger: JsonValue,
+		pub conditions: JsonValue,
+		pub jitter_secs: i64,
+		pub missed_runs: String,
+		pub enabled: bool,
+
+		// Run state
+		pub next_run_at: DateTime<Utc>,
+		pub last_run_at: Option<DateTime<Utc>>,
+		pub last_job_id: Option<String>,
+		/// Why the last due run was held back or failed to dispatch
+		pub last_error: Option<String>,
+
+		pub created_at: DateTime<Utc>,
+	}
+
+	#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+	pub enum Relation {}
+
+	impl ActiveModelBehavior for ActiveModel {}
+}
+
 /// Initialize job database
 pub async fn init_database(db_file_path: &Path) -> JobResult<DatabaseConnection> {
 	// Ensure the parent directory exists
@@ -153,6 +189,12 @@ async fn create_tables(db: &DatabaseConnection) -> JobResult<()> {
 	db.execute(db.get_database_backend().build(&checkpoint_statement))
 		.await?;
 
+	// Create schedules table if not exists
+	let mut schedules_statement = schema.create_table_from_entity(schedules::Entity);
+	schedules_statement.if_not_exists();
+	db.execute(db.get_database_backend().build(&schedules_statement))
+		.await?;
+
 	Ok(())
 }
 
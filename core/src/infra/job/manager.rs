@@ -1002,6 +1002,21 @@ impl JobManager {
 		&self.db
 	}
 
+	/// Core context, used by the scheduler to evaluate schedule conditions
+	pub(super) fn core_context(&self) -> &Arc<CoreContext> {
+		&self.context
+	}
+
+	/// Receiver that flips to `true` when the job manager shuts down
+	pub(super) fn shutdown_signal(&self) -> watch::Receiver<bool> {
+		self.shutdown_tx.subscribe()
+	}
+
+	/// Whether any job is currently running in this library
+	pub(crate) async fn has_running_jobs(&self) -> bool {
+		!self.running_jobs.read().await.is_empty()
+	}
+
 	/// List currently running jobs from memory (for live monitoring)
 	pub async fn list_running_jobs(&self) -> Vec<JobInfo> {
 		let device_id = self
@@ -13,6 +13,8 @@ pub mod manager;
 pub mod output;
 pub mod progress;
 pub mod registry;
+pub mod schedule;
+pub mod scheduler;
 pub mod traits;
 pub mod types;
 
@@ -35,4 +37,5 @@ pub mod prelude {
 
 pub use manager::JobManager;
 pub use registry::JobRegistry;
+pub use schedule::{JobScheduleInfo, JobScheduleSpec, ScheduleCondition, ScheduleTrigger};
 pub use types::{JobInfo, JobStatus};
@@ -75,6 +75,13 @@ pub enum JobOutput {
 		error_count: usize,
 	},
 
+	/// Content text extraction output
+	TextExtraction {
+		total_processed: usize,
+		success_count: usize,
+		error_count: usize,
+	},
+
 	/// Speech-to-text transcription output
 	SpeechToText {
 		total_processed: usize,
@@ -89,6 +96,34 @@ pub enum JobOutput {
 		error_count: usize,
 	},
 
+	/// Auto-tag rule application output
+	AutoTag {
+		entries_checked: usize,
+		tags_applied: usize,
+	},
+
+	/// Semantic search embedding output
+	Embedding {
+		total_processed: usize,
+		embedded_count: usize,
+		error_count: usize,
+	},
+
+	/// Archive creation output
+	FileCompress {
+		source_count: usize,
+		total_bytes: u64,
+		archive_size: u64,
+	},
+
+	/// Archive extraction output
+	FileExtract {
+		extracted_count: usize,
+		skipped_count: usize,
+		failed_count: usize,
+		total_bytes: u64,
+	},
+
 	/// Generic output with custom data
 	#[specta(skip)]
 	Custom(serde_json::Value),
@@ -265,6 +300,17 @@ impl fmt::Display for JobOutput {
 					total_processed, success_count, error_count
 				)
 			}
+			Self::TextExtraction {
+				total_processed,
+				success_count,
+				error_count,
+			} => {
+				write!(
+					f,
+					"Text extraction: {} processed ({} indexed, {} errors)",
+					total_processed, success_count, error_count
+				)
+			}
 			Self::SpeechToText {
 				total_processed,
 				success_count,
@@ -287,6 +333,50 @@ impl fmt::Display for JobOutput {
 					total_processed, success_count, error_count
 				)
 			}
+			Self::AutoTag {
+				entries_checked,
+				tags_applied,
+			} => {
+				write!(
+					f,
+					"Auto-tag: {} files checked ({} tags applied)",
+					entries_checked, tags_applied
+				)
+			}
+			Self::Embedding {
+				total_processed,
+				embedded_count,
+				error_count,
+			} => {
+				write!(
+					f,
+					"Embedding: {} processed ({} embedded, {} errors)",
+					total_processed, embedded_count, error_count
+				)
+			}
+			Self::FileCompress {
+				source_count,
+				total_bytes,
+				archive_size,
+			} => {
+				write!(
+					f,
+					"Compressed {} items ({} bytes into {} bytes)",
+					source_count, total_bytes, archive_size
+				)
+			}
+			Self::FileExtract {
+				extracted_count,
+				skipped_count,
+				failed_count,
+				total_bytes,
+			} => {
+				write!(
+					f,
+					"Extracted {} archives ({} skipped, {} failed, {} bytes)",
+					extracted_count, skipped_count, failed_count, total_bytes
+				)
+			}
 			Self::Custom(_) => write!(f, "Custom output"),
 		}
 	}
@@ -0,0 +1,313 @@
+//! Job schedule definitions
+//!
+//! A schedule dispatches a registered job by name with JSON args whenever its trigger
+//! fires and its conditions hold. Schedules are persisted in the job database; the
+//! scheduler loop that runs them lives in `scheduler.rs`.
+
+use chrono::{DateTime, Local, Utc};
+use croner::Cron;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// When a schedule fires
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleTrigger {
+	/// Standard cron expression (5 or 6 fields), evaluated in local time
+	Cron { expression: String },
+	/// Fixed interval since the previous run
+	Interval { seconds: u64 },
+}
+
+impl ScheduleTrigger {
+	/// Parse the string form used by conduit schedules and the CLI:
+	/// `"interval:5m"`, `"cron:0 3 * * *"` or a bare cron expression
+	pub fn parse(value: &str) -> Result<Self, String> {
+		let value = value.trim();
+		let trigger = if let Some(interval) = value.strip_prefix("interval:") {
+			Self::Interval {
+				seconds: parse_interval(interval)?.as_secs(),
+			}
+		} else {
+			Self::Cron {
+				expression: value
+					.strip_prefix("cron:")
+					.unwrap_or(value)
+					.trim()
+					.to_string(),
+			}
+		};
+		trigger.validate()?;
+		Ok(trigger)
+	}
+
+	pub fn validate(&self) -> Result<(), String> {
+		match self {
+			Self::Cron { expression } => parse_cron(expression).map(|_| ()),
+			Self::Interval { seconds } if *seconds == 0 => {
+				Err("Interval must be at least one second".to_string())
+			}
+			Self::Interval { .. } => Ok(()),
+		}
+	}
+
+	/// The first time strictly after `after` that this trigger fires
+	pub fn next_after(&self, after: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+		match self {
+			Self::Cron { expression } => {
+				let cron = parse_cron(expression)?;
+				cron.find_next_occurrence(&after.with_timezone(&Local), false)
+					.map(|next| next.with_timezone(&Utc))
+					.map_err(|e| format!("Cron expression '{}' has no next run: {}", expression, e))
+			}
+			Self::Interval { seconds } => Ok(after + chrono::Duration::seconds(*seconds as i64)),
+		}
+	}
+}
+
+impl std::fmt::Display for ScheduleTrigger {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Cron { expression } => write!(f, "cron:{}", expression),
+			Self::Interval { seconds } => write!(f, "interval:{}s", seconds),
+		}
+	}
+}
+
+fn parse_cron(expression: &str) -> Result<Cron, String> {
+	Cron::new(expression)
+		.with_seconds_optional()
+		.parse()
+		.map_err(|e| format!("Invalid cron expression '{}': {}", expression, e))
+}
+
+/// Parse an interval such as `"90s"`, `"5m"`, `"1h"` or `"2d"`; bare numbers are seconds
+pub fn parse_interval(value: &str) -> Result<Duration, String> {
+	let value = value.trim();
+	let split = value
+		.find(|c: char| !c.is_ascii_digit())
+		.unwrap_or(value.len());
+	let (amount, unit) = value.split_at(split);
+	let amount: u64 = amount
+		.parse()
+		.map_err(|_| format!("Invalid interval '{}'", value))?;
+	let multiplier = match unit.trim() {
+		"" | "s" => 1,
+		"m" => 60,
+		"h" => 60 * 60,
+		"d" => 24 * 60 * 60,
+		other => return Err(format!("Unknown interval unit '{}' in '{}'", other, value)),
+	};
+	if amount == 0 {
+		return Err("Interval must be at least one second".to_string());
+	}
+	Ok(Duration::from_secs(amount * multiplier))
+}
+
+/// A condition that must hold for a due schedule to be dispatched. Runs whose
+/// conditions aren't met stay due and are retried on the next scheduler tick.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleCondition {
+	/// The device is on AC power (always true on devices without a battery)
+	OnAcPower,
+	/// The system is lightly loaded and the library has no running jobs
+	Idle,
+	/// The given volume is mounted
+	VolumeMounted { volume_id: Uuid },
+}
+
+/// What to do with runs missed while the daemon was stopped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum MissedRunPolicy {
+	/// Run once on startup, however many runs were missed
+	#[default]
+	RunOnce,
+	/// Drop missed runs and wait for the next scheduled time
+	Skip,
+}
+
+impl MissedRunPolicy {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::RunOnce => "run_once",
+			Self::Skip => "skip",
+		}
+	}
+
+	pub fn from_str(s: &str) -> Option<Self> {
+		match s {
+			"run_once" => Some(Self::RunOnce),
+			"skip" => Some(Self::Skip),
+			_ => None,
+		}
+	}
+}
+
+/// Everything needed to create a schedule
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct JobScheduleSpec {
+	pub name: String,
+	/// Registered job name, e.g. `"indexer"` or `"auto_tag"`
+	pub job_name: String,
+	/// Job arguments, deserialized into the job on each run
+	pub args: serde_json::Value,
+	pub trigger: ScheduleTrigger,
+	pub conditions: Vec<ScheduleCondition>,
+	/// Random delay of up to this many seconds added to each run
+	pub jitter_seconds: u32,
+	pub missed_runs: MissedRunPolicy,
+}
+
+/// A persisted schedule and its run state
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct JobScheduleInfo {
+	pub id: Uuid,
+	pub name: String,
+	pub job_name: String,
+	pub args: serde_json::Value,
+	pub trigger: ScheduleTrigger,
+	pub conditions: Vec<ScheduleCondition>,
+	pub jitter_seconds: u32,
+	pub missed_runs: MissedRunPolicy,
+	pub enabled: bool,
+	pub next_run_at: DateTime<Utc>,
+	pub last_run_at: Option<DateTime<Utc>>,
+	pub last_job_id: Option<Uuid>,
+	/// Why the last due run was held back, if it was
+	pub last_error: Option<String>,
+	pub created_at: DateTime<Utc>,
+}
+
+/// Whether the device is running on AC power
+pub(crate) fn on_ac_power() -> bool {
+	#[cfg(target_os = "linux")]
+	{
+		let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+			return true;
+		};
+		let mut has_battery = false;
+		for supply in supplies.flatten() {
+			let path = supply.path();
+			let kind = std::fs::read_to_string(path.join("type")).unwrap_or_default();
+			match kind.trim() {
+				"Mains" | "USB" => {
+					if std::fs::read_to_string(path.join("online")).is_ok_and(|v| v.trim() == "1") {
+						return true;
+					}
+				}
+				"Battery" => has_battery = true,
+				_ => {}
+			}
+		}
+		!has_battery
+	}
+
+	#[cfg(target_os = "macos")]
+	{
+		std::process::Command::new("pmset")
+			.args(["-g", "batt"])
+			.output()
+			.map(|output| String::from_utf8_lossy(&output.stdout).contains("AC Power"))
+			.unwrap_or(true)
+	}
+
+	#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+	{
+		true
+	}
+}
+
+/// Whether the system load is low enough to count as idle
+///
+/// Unix reports a load average, which is compared against the core count. Windows has no
+/// load average, so CPU usage since the previous call is used instead; the first call has
+/// nothing to compare against and reports busy.
+pub(crate) fn system_idle() -> bool {
+	#[cfg(unix)]
+	{
+		let load = sysinfo::System::load_average();
+		let cores = num_cpus::get().max(1) as f64;
+		load.one / cores < 0.5
+	}
+
+	#[cfg(not(unix))]
+	{
+		use once_cell::sync::Lazy;
+		use std::sync::Mutex;
+
+		static SYSTEM: Lazy<Mutex<Option<sysinfo::System>>> = Lazy::new(|| Mutex::new(None));
+
+		let mut system = SYSTEM.lock().unwrap_or_else(|e| e.into_inner());
+		match system.as_mut() {
+			Some(system) => {
+				system.refresh_cpu_usage();
+				system.global_cpu_usage() < 50.0
+			}
+			None => {
+				let mut sampled = sysinfo::System::new();
+				sampled.refresh_cpu_usage();
+				*system = Some(sampled);
+				false
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::TimeZone;
+
+	#[test]
+	fn parses_intervals() {
+		assert_eq!(parse_interval("90s").unwrap(), Duration::from_secs(90));
+		assert_eq!(parse_interval("5m").unwrap(), Duration::from_secs(300));
+		assert_eq!(parse_interval("2h").unwrap(), Duration::from_secs(7200));
+		assert_eq!(parse_interval("1d").unwrap(), Duration::from_secs(86400));
+		assert_eq!(parse_interval("45").unwrap(), Duration::from_secs(45));
+		assert!(parse_interval("0m").is_err());
+		assert!(parse_interval("5w").is_err());
+		assert!(parse_interval("m").is_err());
+	}
+
+	#[test]
+	fn parses_trigger_strings() {
+		assert_eq!(
+			ScheduleTrigger::parse("interval:5m").unwrap(),
+			ScheduleTrigger::Interval { seconds: 300 }
+		);
+		assert_eq!(
+			ScheduleTrigger::parse("cron:0 3 * * *").unwrap(),
+			ScheduleTrigger::Cron {
+				expression: "0 3 * * *".to_string()
+			}
+		);
+		assert!(ScheduleTrigger::parse("*/15 * * * *").is_ok());
+		assert!(ScheduleTrigger::parse("not a cron").is_err());
+		assert!(ScheduleTrigger::parse("interval:soon").is_err());
+	}
+
+	#[test]
+	fn computes_next_run() {
+		let now = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+
+		let interval = ScheduleTrigger::Interval { seconds: 300 };
+		assert_eq!(
+			interval.next_after(now).unwrap(),
+			now + chrono::Duration::minutes(5)
+		);
+
+		let cron = ScheduleTrigger::parse("*/15 * * * *").unwrap();
+		let next = cron.next_after(now).unwrap();
+		assert!(next > now);
+		assert!(next <= now + chrono::Duration::minutes(15));
+		assert_eq!(
+			cron.next_after(next).unwrap() - next,
+			chrono::Duration::minutes(15)
+		);
+	}
+}
@@ -0,0 +1,336 @@
+//! Job scheduler
+//!
+//! Runs the persisted schedules in a library's job database. The scheduler loop wakes
+//! every `TICK_INTERVAL`, dispatches due schedules whose conditions hold and works out
+//! each schedule's next run. Next runs are always computed from the current time, so
+//! any number of runs missed while the daemon was stopped collapse into one. A schedule
+//! whose previous job is still active stays due until that job finishes, so runs never
+//! overlap.
+
+use super::{
+	database::schedules,
+	error::{JobError, JobResult},
+	manager::JobManager,
+	registry::REGISTRY,
+	schedule::{
+		on_ac_power, system_idle, JobScheduleInfo, JobScheduleSpec, MissedRunPolicy,
+		ScheduleCondition, ScheduleTrigger,
+	},
+	types::JobId,
+};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use sea_orm::{
+	ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, QueryOrder,
+};
+use std::{sync::Arc, time::Duration};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// How often the scheduler checks for due schedules
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+impl JobManager {
+	/// Persist a new schedule. The job name must be registered and `args` must
+	/// deserialize into it, so a schedule can't be created that would never run.
+	pub async fn create_schedule(&self, spec: JobScheduleSpec) -> JobResult<JobScheduleInfo> {
+		if REGISTRY.has_job(&spec.job_name) {
+			REGISTRY.create_job(&spec.job_name, spec.args.clone())?;
+		} else if !spec.job_name.contains(':') {
+			// Extension jobs ("extension:job") are resolved when dispatched
+			return Err(JobError::NotFound(format!(
+				"Job type '{}' not found",
+				spec.job_name
+			)));
+		}
+		spec.trigger.validate().map_err(JobError::Other)?;
+
+		let now = Utc::now();
+		let next_run_at = spec.trigger.next_after(now).map_err(JobError::Other)?;
+
+		let model = schedules::ActiveModel {
+			id: Set(Uuid::new_v4().to_string()),
+			name: Set(spec.name),
+			job_name: Set(spec.job_name),
+			args: Set(spec.args),
+			trigger: Set(serde_json::to_value(&spec.trigger).map_err(JobError::serialization)?),
+			conditions: Set(
+				serde_json::to_value(&spec.conditions).map_err(JobError::serialization)?
+			),
+			jitter_secs: Set(spec.jitter_seconds as i64),
+			missed_runs: Set(spec.missed_runs.as_str().to_string()),
+			enabled: Set(true),
+			next_run_at: Set(with_jitter(next_run_at, spec.jitter_seconds as i64)),
+			last_run_at: Set(None),
+			last_job_id: Set(None),
+			last_error: Set(None),
+			created_at: Set(now),
+		}
+		.insert(self.database().conn())
+		.await?;
+
+		info!(
+			schedule_id = %model.id,
+			job = %model.job_name,
+			next_run_at = %model.next_run_at,
+			"Created job schedule"
+		);
+		schedule_info(model)
+	}
+
+	/// List all schedules, soonest first
+	pub async fn list_schedules(&self) -> JobResult<Vec<JobScheduleInfo>> {
+		schedules::Entity::find()
+			.order_by_asc(schedules::Column::NextRunAt)
+			.all(self.database().conn())
+			.await?
+			.into_iter()
+			.map(schedule_info)
+			.collect()
+	}
+
+	/// Delete a schedule. Returns false if it didn't exist.
+	pub async fn delete_schedule(&self, id: Uuid) -> JobResult<bool> {
+		let result = schedules::Entity::delete_by_id(id.to_string())
+			.exec(self.database().conn())
+			.await?;
+		Ok(result.rows_affected > 0)
+	}
+
+	/// Start the scheduler loop for this library. It stops when the job manager
+	/// shuts down or is dropped.
+	pub fn start_scheduler(self: &Arc<Self>) {
+		let manager = Arc::downgrade(self);
+		let mut shutdown = self.shutdown_signal();
+
+		tokio::spawn(async move {
+			if let Some(jobs) = manager.upgrade() {
+				if let Err(e) = jobs.catch_up_missed_runs().await {
+					warn!("Failed to reconcile missed job schedule runs: {}", e);
+				}
+			}
+
+			let mut ticker = tokio::time::interval(TICK_INTERVAL);
+			loop {
+				tokio::select! {
+					_ = ticker.tick() => {}
+					_ = shutdown.changed() => break,
+				}
+				if *shutdown.borrow() {
+					break;
+				}
+				let Some(jobs) = manager.upgrade() else {
+					break;
+				};
+				if let Err(e) = jobs.run_due_schedules().await {
+					warn!("Failed to run due job schedules: {}", e);
+				}
+			}
+			debug!("Job scheduler stopped");
+		});
+	}
+
+	/// Dispatch every enabled schedule that is due and whose conditions hold.
+	/// Returns the IDs of the dispatched jobs.
+	pub async fn run_due_schedules(&self) -> JobResult<Vec<JobId>> {
+		let now = Utc::now();
+		let due = schedules::Entity::find()
+			.filter(schedules::Column::Enabled.eq(true))
+			.filter(schedules::Column::NextRunAt.lte(now))
+			.order_by_asc(schedules::Column::NextRunAt)
+			.all(self.database().conn())
+			.await?;
+
+		let mut dispatched = Vec::new();
+		for model in due {
+			let schedule = match schedule_info(model.clone()) {
+				Ok(schedule) => schedule,
+				Err(e) => {
+					self.disable_broken_schedule(model, e.to_string()).await?;
+					continue;
+				}
+			};
+
+			// A previous run that's still going, or unmet conditions, leave the run due,
+			// so it fires once they clear
+			let held = match schedule.last_job_id {
+				Some(job_id) if self.job_is_active(JobId(job_id)).await => Some(format!(
+					"Waiting for the previous run ({}) to finish",
+					job_id
+				)),
+				_ => self.unmet_condition(&schedule.conditions).await,
+			};
+			if let Some(reason) = held {
+				if model.last_error.as_deref() != Some(reason.as_str()) {
+					debug!(schedule_id = %schedule.id, "Holding scheduled job: {}", reason);
+					let mut active: schedules::ActiveModel = model.into();
+					active.last_error = Set(Some(reason));
+					active.update(self.database().conn()).await?;
+				}
+				continue;
+			}
+
+			let next_run_at = match schedule.trigger.next_after(now) {
+				Ok(next) => with_jitter(next, schedule.jitter_seconds as i64),
+				Err(e) => {
+					self.disable_broken_schedule(model, e).await?;
+					continue;
+				}
+			};
+			let mut active: schedules::ActiveModel = model.into();
+			active.next_run_at = Set(next_run_at);
+			active.last_run_at = Set(Some(now));
+
+			match self
+				.dispatch_by_name(&schedule.job_name, schedule.args.clone())
+				.await
+			{
+				Ok(handle) => {
+					info!(
+						schedule_id = %schedule.id,
+						job = %schedule.job_name,
+						job_id = %handle.id(),
+						"Dispatched scheduled job"
+					);
+					active.last_job_id = Set(Some(handle.id().to_string()));
+					active.last_error = Set(None);
+					dispatched.push(handle.id());
+				}
+				Err(e) => {
+					warn!(schedule_id = %schedule.id, "Failed to dispatch scheduled job: {}", e);
+					active.last_error = Set(Some(e.to_string()));
+				}
+			}
+			active.update(self.database().conn()).await?;
+		}
+
+		Ok(dispatched)
+	}
+
+	/// Apply each schedule's missed run policy to runs that fell due while the
+	/// daemon was stopped. `RunOnce` schedules are left due and fire on the first tick.
+	/// Called by the scheduler loop when it starts.
+	pub async fn catch_up_missed_runs(&self) -> JobResult<()> {
+		let now = Utc::now();
+		let overdue = schedules::Entity::find()
+			.filter(schedules::Column::Enabled.eq(true))
+			.filter(schedules::Column::NextRunAt.lt(now))
+			.all(self.database().conn())
+			.await?;
+
+		for model in overdue {
+			let schedule = match schedule_info(model.clone()) {
+				Ok(schedule) => schedule,
+				Err(e) => {
+					self.disable_broken_schedule(model, e.to_string()).await?;
+					continue;
+				}
+			};
+			match schedule.missed_runs {
+				MissedRunPolicy::RunOnce => {
+					info!(schedule_id = %schedule.id, "Running missed scheduled job once");
+				}
+				MissedRunPolicy::Skip => {
+					let next_run_at = match schedule.trigger.next_after(now) {
+						Ok(next) => next,
+						Err(e) => {
+							self.disable_broken_schedule(model, e).await?;
+							continue;
+						}
+					};
+					debug!(schedule_id = %schedule.id, "Skipping missed scheduled job runs");
+					let mut active: schedules::ActiveModel = model.into();
+					active.next_run_at =
+						Set(with_jitter(next_run_at, schedule.jitter_seconds as i64));
+					active.update(self.database().conn()).await?;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Disable a schedule whose stored trigger or conditions can't be used, so one
+	/// bad row doesn't stop the rest from running. The reason is kept in `last_error`.
+	async fn disable_broken_schedule(
+		&self,
+		model: schedules::Model,
+		error: String,
+	) -> JobResult<()> {
+		warn!(schedule_id = %model.id, "Disabling job schedule: {}", error);
+		let mut active: schedules::ActiveModel = model.into();
+		active.enabled = Set(false);
+		active.last_error = Set(Some(error));
+		active.update(self.database().conn()).await?;
+		Ok(())
+	}
+
+	/// Whether a job is still queued, running or paused
+	async fn job_is_active(&self, job_id: JobId) -> bool {
+		self.get_job(job_id)
+			.await
+			.is_some_and(|handle| !handle.status().is_terminal())
+	}
+
+	/// The first condition that doesn't currently hold, described for `last_error`
+	async fn unmet_condition(&self, conditions: &[ScheduleCondition]) -> Option<String> {
+		for condition in conditions {
+			let met = match condition {
+				ScheduleCondition::OnAcPower => on_ac_power(),
+				ScheduleCondition::Idle => system_idle() && !self.has_running_jobs().await,
+				ScheduleCondition::VolumeMounted { volume_id } => self
+					.core_context()
+					.volume_manager
+					.get_all_volumes()
+					.await
+					.iter()
+					.any(|volume| volume.id == *volume_id && volume.is_mounted),
+			};
+			if !met {
+				return Some(match condition {
+					ScheduleCondition::OnAcPower => "Waiting for AC power".to_string(),
+					ScheduleCondition::Idle => "Waiting for the system to be idle".to_string(),
+					ScheduleCondition::VolumeMounted { volume_id } => {
+						format!("Waiting for volume {} to be mounted", volume_id)
+					}
+				});
+			}
+		}
+		None
+	}
+}
+
+/// Delay `at` by a random amount of up to `jitter_secs`
+fn with_jitter(at: DateTime<Utc>, jitter_secs: i64) -> DateTime<Utc> {
+	if jitter_secs <= 0 {
+		return at;
+	}
+	at + chrono::Duration::seconds(rand::thread_rng().gen_range(0..=jitter_secs))
+}
+
+fn schedule_info(model: schedules::Model) -> JobResult<JobScheduleInfo> {
+	let trigger: ScheduleTrigger =
+		serde_json::from_value(model.trigger).map_err(JobError::serialization)?;
+	let conditions: Vec<ScheduleCondition> =
+		serde_json::from_value(model.conditions).map_err(JobError::serialization)?;
+
+	Ok(JobScheduleInfo {
+		id: Uuid::parse_str(&model.id).map_err(JobError::serialization)?,
+		name: model.name,
+		job_name: model.job_name,
+		args: model.args,
+		trigger,
+		conditions,
+		jitter_seconds: model.jitter_secs.max(0) as u32,
+		missed_runs: MissedRunPolicy::from_str(&model.missed_runs).unwrap_or_default(),
+		enabled: model.enabled,
+		next_run_at: model.next_run_at,
+		last_run_at: model.last_run_at,
+		last_job_id: model
+			.last_job_id
+			.as_deref()
+			.and_then(|id| Uuid::parse_str(id).ok()),
+		last_error: model.last_error,
+		created_at: model.created_at,
+	})
+}
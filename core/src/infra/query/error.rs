@@ -1,6 +1,6 @@
 //! Error types for the Query System
 
-use crate::{common::errors::CoreError, library::LibraryError};
+use crate::{common::errors::CoreError, crypto::vault::VaultError, library::LibraryError};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use uuid::Uuid;
@@ -107,6 +107,12 @@ impl From<CoreError> for QueryError {
 	}
 }
 
+impl From<VaultError> for QueryError {
+	fn from(error: VaultError) -> Self {
+		QueryError::Internal(error.to_string())
+	}
+}
+
 impl From<std::io::Error> for QueryError {
 	fn from(error: std::io::Error) -> Self {
 		QueryError::Io {
@@ -233,6 +233,7 @@ mod tests {
 			record_uuid: Uuid::new_v4(),
 			change_type: ChangeType::Insert,
 			data: serde_json::json!({"name": "important"}),
+			field_clocks: None,
 		};
 
 		let event = SyncEvent::SharedChange {
@@ -278,6 +279,7 @@ mod tests {
 				record_uuid: Uuid::new_v4(),
 				change_type: ChangeType::Insert,
 				data: serde_json::json!({}),
+				field_clocks: None,
 			},
 		};
 		assert!(shared_event.is_critical());
@@ -144,6 +144,9 @@ pub enum SyncEventType {
 
 	/// Sync error occurred
 	SyncError,
+
+	/// Concurrent edits to the same field of a shared record, resolved by HLC
+	FieldConflict,
 }
 
 impl SyncEventType {
@@ -163,6 +166,7 @@ impl SyncEventType {
 			Self::PeerConnected => (EventCategory::Network, EventSeverity::Info),
 			Self::PeerDisconnected => (EventCategory::Network, EventSeverity::Info),
 			Self::SyncError => (EventCategory::Error, EventSeverity::Error),
+			Self::FieldConflict => (EventCategory::DataFlow, EventSeverity::Warning),
 		}
 	}
 
@@ -182,6 +186,7 @@ impl SyncEventType {
 			Self::PeerConnected => "peer_connected",
 			Self::PeerDisconnected => "peer_disconnected",
 			Self::SyncError => "sync_error",
+			Self::FieldConflict => "field_conflict",
 		}
 	}
 
@@ -201,6 +206,7 @@ impl SyncEventType {
 			"peer_connected" => Some(Self::PeerConnected),
 			"peer_disconnected" => Some(Self::PeerDisconnected),
 			"sync_error" => Some(Self::SyncError),
+			"field_conflict" => Some(Self::FieldConflict),
 			_ => None,
 		}
 	}
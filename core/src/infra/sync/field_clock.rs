@@ -0,0 +1,501 @@
+//! Per-field clocks for merging concurrent edits to shared resources
+//!
+//! Shared changes carry the full record, so comparing whole-record HLCs loses one
+//! side's edits when two devices change different fields of the same record while
+//! offline. Instead, every field remembers the HLC of its last write:
+//!
+//! - A local commit stamps the fields whose value changed with the new HLC, and
+//!   records the clock each of them overwrote (its base)
+//! - An incoming change wins a field only if its clock for that field is newer, so
+//!   edits to disjoint fields merge
+//! - A field the sender changed without having seen our latest write to it (its
+//!   base is older than our clock) is a true conflict; the newer HLC still wins, and
+//!   the conflict is reported so nothing is lost silently
+//!
+//! Clocks live in sync.db next to the peer log, keyed by record UUID.
+
+use super::{hlc::HLC, peer_log::PeerLogError, SharedChangeEntry};
+use sea_orm::{ConnectionTrait, DbBackend, Statement};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+/// Fields that change alongside every edit of a record
+///
+/// They are merged by HLC like any other field but never reported as conflicts.
+const UNCONTESTED_FIELDS: &[&str] = &["updated_at", "date_modified", "version"];
+
+/// Per-field clocks carried by a shared change
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FieldClocks {
+	/// Clock of the last write to each field, as known by the sender
+	pub written: BTreeMap<String, HLC>,
+
+	/// For fields changed by this change, the clock the sender overwrote
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub bases: BTreeMap<String, HLC>,
+}
+
+/// Last known value of a field and the clock of the write that set it
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldState {
+	pub value: serde_json::Value,
+	pub hlc: HLC,
+}
+
+/// How a same-field conflict was resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+	/// Our write was newer and was kept
+	KeptLocal,
+	/// The incoming write was newer and replaced ours
+	TookRemote,
+}
+
+/// Two devices wrote the same field without seeing each other's write
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct FieldConflict {
+	pub model_type: String,
+	pub record_uuid: Uuid,
+	pub field: String,
+	pub local_value: serde_json::Value,
+	pub local_hlc: String,
+	pub remote_value: serde_json::Value,
+	pub remote_hlc: String,
+	pub resolution: ConflictResolution,
+}
+
+/// Outcome of merging an incoming change into the local field clocks
+#[derive(Debug, Clone)]
+pub struct FieldMerge {
+	/// Record to apply: incoming values, except for fields where ours are newer
+	pub data: serde_json::Value,
+
+	/// Fields taken from the incoming change, to be stored once it is applied
+	pub accepted: Vec<(String, FieldState)>,
+
+	/// Same-field conflicts found while merging
+	pub conflicts: Vec<FieldConflict>,
+}
+
+/// Stamp a locally committed record against the last known state of its fields
+///
+/// Returns the clocks to send with the change and the fields whose value changed.
+pub fn stamp_fields(
+	previous: &HashMap<String, FieldState>,
+	data: &serde_json::Value,
+	hlc: HLC,
+) -> (FieldClocks, Vec<(String, FieldState)>) {
+	let mut clocks = FieldClocks::default();
+	let mut changed = Vec::new();
+
+	let Some(fields) = data.as_object() else {
+		return (clocks, changed);
+	};
+
+	for (field, value) in fields {
+		match previous.get(field) {
+			Some(state) if state.value == *value => {
+				clocks.written.insert(field.clone(), state.hlc);
+			}
+			previous_state => {
+				if let Some(state) = previous_state {
+					clocks.bases.insert(field.clone(), state.hlc);
+				}
+				clocks.written.insert(field.clone(), hlc);
+				changed.push((
+					field.clone(),
+					FieldState {
+						value: value.clone(),
+						hlc,
+					},
+				));
+			}
+		}
+	}
+
+	(clocks, changed)
+}
+
+/// Merge an incoming insert or update into the local state of its fields
+///
+/// Changes from peers without field clocks count as a write of every field at the
+/// change's HLC, and can't be checked for conflicts.
+pub fn merge_fields(local: &HashMap<String, FieldState>, entry: &SharedChangeEntry) -> FieldMerge {
+	let mut data = entry.data.clone();
+	let mut accepted = Vec::new();
+	let mut conflicts = Vec::new();
+
+	let Some(fields) = data.as_object_mut() else {
+		return FieldMerge {
+			data,
+			accepted,
+			conflicts,
+		};
+	};
+
+	for (field, remote_value) in fields.iter_mut() {
+		let remote_hlc = entry
+			.field_clocks
+			.as_ref()
+			.and_then(|clocks| clocks.written.get(field))
+			.copied()
+			.unwrap_or(entry.hlc);
+
+		let Some(local_state) = local.get(field) else {
+			accepted.push((
+				field.clone(),
+				FieldState {
+					value: remote_value.clone(),
+					hlc: remote_hlc,
+				},
+			));
+			continue;
+		};
+
+		if remote_hlc == local_state.hlc {
+			continue;
+		}
+
+		let remote_wins = remote_hlc > local_state.hlc;
+
+		if let Some(clocks) = &entry.field_clocks {
+			// The sender overwrote an older clock than ours, so it never saw our write
+			let changed_by_entry = remote_hlc == entry.hlc;
+			let concurrent = clocks
+				.bases
+				.get(field)
+				.map_or(true, |base| *base < local_state.hlc);
+
+			if changed_by_entry
+				&& concurrent
+				&& *remote_value != local_state.value
+				&& !UNCONTESTED_FIELDS.contains(&field.as_str())
+			{
+				conflicts.push(FieldConflict {
+					model_type: entry.model_type.clone(),
+					record_uuid: entry.record_uuid,
+					field: field.clone(),
+					local_value: local_state.value.clone(),
+					local_hlc: local_state.hlc.to_string(),
+					remote_value: remote_value.clone(),
+					remote_hlc: remote_hlc.to_string(),
+					resolution: if remote_wins {
+						ConflictResolution::TookRemote
+					} else {
+						ConflictResolution::KeptLocal
+					},
+				});
+			}
+		}
+
+		if remote_wins {
+			accepted.push((
+				field.clone(),
+				FieldState {
+					value: remote_value.clone(),
+					hlc: remote_hlc,
+				},
+			));
+		} else {
+			*remote_value = local_state.value.clone();
+		}
+	}
+
+	FieldMerge {
+		data,
+		accepted,
+		conflicts,
+	}
+}
+
+/// Storage of per-field clocks in sync.db
+pub struct FieldClockStore;
+
+impl FieldClockStore {
+	/// Initialize the shared_field_clocks table in sync.db
+	pub async fn init_table<C: ConnectionTrait>(conn: &C) -> Result<(), PeerLogError> {
+		conn.execute(Statement::from_string(
+			DbBackend::Sqlite,
+			r#"
+			CREATE TABLE IF NOT EXISTS shared_field_clocks (
+				record_uuid TEXT NOT NULL,
+				field TEXT NOT NULL,
+				model_type TEXT NOT NULL,
+				hlc TEXT NOT NULL,
+				value TEXT NOT NULL,
+				PRIMARY KEY (record_uuid, field)
+			)
+			"#
+			.to_string(),
+		))
+		.await
+		.map_err(|e| PeerLogError::QueryError(e.to_string()))?;
+
+		Ok(())
+	}
+
+	/// Load the state of every known field of a record
+	pub async fn load<C: ConnectionTrait>(
+		conn: &C,
+		record_uuid: Uuid,
+	) -> Result<HashMap<String, FieldState>, PeerLogError> {
+		let rows = conn
+			.query_all(Statement::from_sql_and_values(
+				DbBackend::Sqlite,
+				"SELECT field, hlc, value FROM shared_field_clocks WHERE record_uuid = ?",
+				vec![record_uuid.to_string().into()],
+			))
+			.await
+			.map_err(|e| PeerLogError::QueryError(e.to_string()))?;
+
+		let mut states = HashMap::with_capacity(rows.len());
+		for row in rows {
+			let field: String = row
+				.try_get("", "field")
+				.map_err(|e| PeerLogError::QueryError(e.to_string()))?;
+			let hlc_str: String = row
+				.try_get("", "hlc")
+				.map_err(|e| PeerLogError::QueryError(e.to_string()))?;
+			let value_json: String = row
+				.try_get("", "value")
+				.map_err(|e| PeerLogError::QueryError(e.to_string()))?;
+
+			let hlc =
+				HLC::from_string(&hlc_str).map_err(|e| PeerLogError::ParseError(e.to_string()))?;
+			let value = serde_json::from_str(&value_json)
+				.map_err(|e| PeerLogError::SerializationError(e.to_string()))?;
+
+			states.insert(field, FieldState { value, hlc });
+		}
+
+		Ok(states)
+	}
+
+	/// Store the new state of some fields of a record
+	pub async fn save<C: ConnectionTrait>(
+		conn: &C,
+		model_type: &str,
+		record_uuid: Uuid,
+		fields: &[(String, FieldState)],
+	) -> Result<(), PeerLogError> {
+		for (field, state) in fields {
+			let value_json = serde_json::to_string(&state.value)
+				.map_err(|e| PeerLogError::SerializationError(e.to_string()))?;
+
+			conn.execute(Statement::from_sql_and_values(
+				DbBackend::Sqlite,
+				r#"
+				INSERT OR REPLACE INTO shared_field_clocks (record_uuid, field, model_type, hlc, value)
+				VALUES (?, ?, ?, ?, ?)
+				"#,
+				vec![
+					record_uuid.to_string().into(),
+					field.clone().into(),
+					model_type.into(),
+					state.hlc.to_string().into(),
+					value_json.into(),
+				],
+			))
+			.await
+			.map_err(|e| PeerLogError::QueryError(e.to_string()))?;
+		}
+
+		Ok(())
+	}
+
+	/// Forget the fields of a deleted record
+	pub async fn clear<C: ConnectionTrait>(
+		conn: &C,
+		record_uuid: Uuid,
+	) -> Result<(), PeerLogError> {
+		conn.execute(Statement::from_sql_and_values(
+			DbBackend::Sqlite,
+			"DELETE FROM shared_field_clocks WHERE record_uuid = ?",
+			vec![record_uuid.to_string().into()],
+		))
+		.await
+		.map_err(|e| PeerLogError::QueryError(e.to_string()))?;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::infra::sync::{hlc::HLCGenerator, time_source::FakeTimeSource, ChangeType, PeerLog};
+	use serde_json::json;
+	use std::sync::Arc;
+	use tempfile::TempDir;
+
+	/// One offline device: its own peer log and HLC generator on a shared fake clock
+	struct Device {
+		log: PeerLog,
+		hlc: HLCGenerator,
+		_dir: TempDir,
+	}
+
+	impl Device {
+		async fn new(time: &FakeTimeSource) -> Self {
+			let dir = TempDir::new().unwrap();
+			let device_id = Uuid::new_v4();
+			let log = PeerLog::open(Uuid::new_v4(), device_id, dir.path())
+				.await
+				.unwrap();
+			let hlc = HLCGenerator::new(device_id, Arc::new(time.clone()));
+			Self {
+				log,
+				hlc,
+				_dir: dir,
+			}
+		}
+
+		/// Commit a local write of the whole record
+		async fn commit(&self, record_uuid: Uuid, data: serde_json::Value) -> SharedChangeEntry {
+			let mut entry = SharedChangeEntry {
+				hlc: self.hlc.next(),
+				model_type: "tag".to_string(),
+				record_uuid,
+				change_type: ChangeType::Update,
+				data,
+				field_clocks: None,
+			};
+			self.log.stamp_field_clocks(&mut entry).await.unwrap();
+			entry
+		}
+
+		/// Receive a change from another device, returning the applied record
+		async fn receive(&self, entry: &SharedChangeEntry) -> FieldMerge {
+			self.hlc.update(entry.hlc);
+			let local = self.log.field_states(entry.record_uuid).await.unwrap();
+			let merge = merge_fields(&local, entry);
+			self.log
+				.save_field_states(&entry.model_type, entry.record_uuid, &merge.accepted)
+				.await
+				.unwrap();
+			merge
+		}
+	}
+
+	fn tag(name: &str, color: &str) -> serde_json::Value {
+		json!({ "canonical_name": name, "color": color, "updated_at": "t" })
+	}
+
+	#[tokio::test]
+	async fn test_disjoint_fields_merge() {
+		let time = FakeTimeSource::new(1000);
+		let (a, b) = (Device::new(&time).await, Device::new(&time).await);
+		let record = Uuid::new_v4();
+
+		// Both devices start from the same synced record
+		let created = a.commit(record, tag("work", "red")).await;
+		b.receive(&created).await;
+
+		// Offline: A renames, B recolors
+		time.advance(10);
+		let rename = a.commit(record, tag("office", "red")).await;
+		time.advance(10);
+		let recolor = b.commit(record, tag("work", "blue")).await;
+
+		let on_a = a.receive(&recolor).await;
+		let on_b = b.receive(&rename).await;
+
+		assert_eq!(on_a.data["canonical_name"], "office");
+		assert_eq!(on_a.data["color"], "blue");
+		assert_eq!(on_b.data["canonical_name"], "office");
+		assert_eq!(on_b.data["color"], "blue");
+		assert!(on_a.conflicts.is_empty());
+		assert!(on_b.conflicts.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_same_field_conflict_is_reported() {
+		let time = FakeTimeSource::new(1000);
+		let (a, b) = (Device::new(&time).await, Device::new(&time).await);
+		let record = Uuid::new_v4();
+
+		let created = a.commit(record, tag("work", "red")).await;
+		b.receive(&created).await;
+
+		time.advance(10);
+		let a_edit = a.commit(record, tag("work", "green")).await;
+		time.advance(10);
+		let b_edit = b.commit(record, tag("work", "blue")).await;
+
+		// B's write is newer, so both converge on blue and both report the conflict
+		let on_a = a.receive(&b_edit).await;
+		let on_b = b.receive(&a_edit).await;
+
+		assert_eq!(on_a.data["color"], "blue");
+		assert_eq!(on_b.data["color"], "blue");
+		assert_eq!(on_a.conflicts.len(), 1);
+		assert_eq!(on_a.conflicts[0].field, "color");
+		assert_eq!(on_a.conflicts[0].resolution, ConflictResolution::TookRemote);
+		assert_eq!(on_b.conflicts.len(), 1);
+		assert_eq!(on_b.conflicts[0].resolution, ConflictResolution::KeptLocal);
+		assert!(on_b.accepted.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_sequential_edits_do_not_conflict() {
+		let time = FakeTimeSource::new(1000);
+		let (a, b) = (Device::new(&time).await, Device::new(&time).await);
+		let record = Uuid::new_v4();
+
+		let created = a.commit(record, tag("work", "red")).await;
+		b.receive(&created).await;
+
+		time.advance(10);
+		let a_edit = a.commit(record, tag("work", "green")).await;
+		b.receive(&a_edit).await;
+
+		// B edits the same field after seeing A's write
+		time.advance(10);
+		let b_edit = b.commit(record, tag("work", "blue")).await;
+		let on_a = a.receive(&b_edit).await;
+
+		assert_eq!(on_a.data["color"], "blue");
+		assert!(on_a.conflicts.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_redelivery_is_a_no_op() {
+		let time = FakeTimeSource::new(1000);
+		let (a, b) = (Device::new(&time).await, Device::new(&time).await);
+		let record = Uuid::new_v4();
+
+		let created = a.commit(record, tag("work", "red")).await;
+		assert_eq!(b.receive(&created).await.accepted.len(), 3);
+
+		let again = b.receive(&created).await;
+		assert!(again.accepted.is_empty());
+		assert!(again.conflicts.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_change_without_clocks_uses_record_hlc() {
+		let time = FakeTimeSource::new(1000);
+		let a = Device::new(&time).await;
+		let record = Uuid::new_v4();
+
+		a.commit(record, tag("work", "red")).await;
+		let local = a.log.field_states(record).await.unwrap();
+
+		let stale = SharedChangeEntry {
+			hlc: HLC::now(Uuid::new_v4(), &FakeTimeSource::new(500)),
+			model_type: "tag".to_string(),
+			record_uuid: record,
+			change_type: ChangeType::Update,
+			data: tag("home", "red"),
+			field_clocks: None,
+		};
+		let merge = merge_fields(&local, &stale);
+
+		assert_eq!(merge.data["canonical_name"], "work");
+		assert!(merge.accepted.is_empty());
+		assert!(merge.conflicts.is_empty());
+	}
+}
@@ -102,6 +102,25 @@ pub async fn convert_fk_to_uuid(
 	Ok(())
 }
 
+/// Whether every reference in a record points at a row replicated to the receiving device
+///
+/// Used by selective sync before a record is sent: `is_replicated` is called with each
+/// non-null UUID FK. References are never dropped, so a record pointing at a row the
+/// device won't receive is held back rather than arriving with a dependency that can't
+/// be resolved; it's sent once a wider policy makes the device re-sync.
+pub fn references_replicated(
+	data: &Value,
+	mappings: &[FKMapping],
+	is_replicated: impl Fn(&FKMapping, Uuid) -> bool,
+) -> bool {
+	mappings.iter().all(|fk| {
+		data.get(fk.uuid_field_name())
+			.and_then(|v| v.as_str())
+			.and_then(|s| Uuid::parse_str(s).ok())
+			.map_or(true, |uuid| is_replicated(fk, uuid))
+	})
+}
+
 /// Look up UUID for a local integer ID via the registry
 async fn lookup_uuid_for_local_id(
 	table: &str,
@@ -429,4 +448,25 @@ mod tests {
 		let fk = FKMapping::new("entry_id", "entries");
 		assert_eq!(fk.uuid_field_name(), "entry_uuid");
 	}
+
+	#[test]
+	fn test_references_replicated() {
+		let mappings = vec![
+			FKMapping::new("parent_id", "entries"),
+			FKMapping::new("content_id", "content_identities"),
+		];
+		let data = json!({
+			"parent_uuid": null,
+			"content_uuid": Uuid::new_v4().to_string(),
+		});
+
+		assert!(references_replicated(&data, &mappings, |fk, _| {
+			fk.target_table != "entries"
+		}));
+
+		// A reference to a row the device won't receive holds the record back
+		assert!(!references_replicated(&data, &mappings, |fk, _| {
+			fk.target_table != "content_identities"
+		}));
+	}
 }
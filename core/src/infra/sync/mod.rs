@@ -3,6 +3,7 @@
 //! Core sync components for peer-to-peer synchronization:
 //! - HLC for distributed ordering
 //! - Per-peer logs for shared resource changes
+//! - Per-field clocks for merging concurrent edits to shared resources
 //! - Syncable trait for model registration
 //! - Transaction manager for atomic commits
 //! - Unified configuration for all sync behavior
@@ -16,6 +17,7 @@ pub mod dependency_graph;
 pub mod deterministic;
 pub mod event_bus;
 pub mod event_log;
+pub mod field_clock;
 pub mod fk_mapper;
 pub mod hlc;
 pub mod peer_log;
@@ -41,9 +43,10 @@ pub use event_log::{
 	BatchAggregator, BatchAggregatorConfig, EventCategory, EventSeverity, SyncEventLog,
 	SyncEventLogger, SyncEventQuery, SyncEventType,
 };
+pub use field_clock::{ConflictResolution, FieldClocks, FieldConflict, FieldMerge, FieldState};
 pub use fk_mapper::{
-	batch_map_sync_json_to_local, convert_fk_to_uuid, map_sync_json_to_local, BatchFkMapResult,
-	FKMapping,
+	batch_map_sync_json_to_local, convert_fk_to_uuid, map_sync_json_to_local,
+	references_replicated, BatchFkMapResult, FKMapping,
 };
 pub use hlc::{HLCGenerator, HLC};
 pub use peer_log::{ChangeType, PeerLog, PeerLogError, SharedChangeEntry};
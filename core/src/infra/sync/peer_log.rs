@@ -3,11 +3,15 @@
 //! Each device maintains a small, prunable log of its own changes to shared resources.
 //! This log is ordered by HLC and pruned once all peers have acknowledged receiving changes.
 
-use super::hlc::HLC;
+use super::{
+	field_clock::{stamp_fields, FieldClockStore, FieldClocks, FieldState},
+	hlc::HLC,
+};
 use sea_orm::{
 	entity::prelude::*, ConnectionTrait, Database, DatabaseConnection, DbBackend, Statement,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -58,7 +62,8 @@ impl PeerLog {
 				record_uuid TEXT NOT NULL,
 				change_type TEXT NOT NULL,
 				data TEXT NOT NULL,
-				created_at TEXT NOT NULL
+				created_at TEXT NOT NULL,
+				field_clocks TEXT
 			)
 			"#
 			.to_string(),
@@ -66,6 +71,27 @@ impl PeerLog {
 		.await
 		.map_err(|e| PeerLogError::QueryError(e.to_string()))?;
 
+		// sync.db files created before field-level merge lack the field_clocks column
+		let has_field_clocks = conn
+			.query_one(Statement::from_string(
+				DbBackend::Sqlite,
+				"SELECT COUNT(*) AS count FROM pragma_table_info('shared_changes') WHERE name = 'field_clocks'"
+					.to_string(),
+			))
+			.await
+			.map_err(|e| PeerLogError::QueryError(e.to_string()))?
+			.and_then(|row| row.try_get::<i64>("", "count").ok())
+			.unwrap_or(0)
+			> 0;
+		if !has_field_clocks {
+			conn.execute(Statement::from_string(
+				DbBackend::Sqlite,
+				"ALTER TABLE shared_changes ADD COLUMN field_clocks TEXT".to_string(),
+			))
+			.await
+			.map_err(|e| PeerLogError::QueryError(e.to_string()))?;
+		}
+
 		// Indexes for efficient queries
 		conn.execute(Statement::from_string(
 			DbBackend::Sqlite,
@@ -107,6 +133,9 @@ impl PeerLog {
 			.await
 			.map_err(|e| PeerLogError::QueryError(e.to_string()))?;
 
+		// shared_field_clocks table (field-level merge of shared resources)
+		FieldClockStore::init_table(conn).await?;
+
 		// backfill_checkpoints table (resumable backfill)
 		super::checkpoints::BackfillCheckpointStore::init_table(conn)
 			.await
@@ -187,14 +216,20 @@ impl PeerLog {
 		let change_type_str = entry.change_type.to_string();
 		let data_json = serde_json::to_string(&entry.data)
 			.map_err(|e| PeerLogError::SerializationError(e.to_string()))?;
+		let field_clocks_json = entry
+			.field_clocks
+			.as_ref()
+			.map(serde_json::to_string)
+			.transpose()
+			.map_err(|e| PeerLogError::SerializationError(e.to_string()))?;
 		let created_at = chrono::Utc::now().to_rfc3339();
 
 		self.conn
 			.execute(Statement::from_sql_and_values(
 				DbBackend::Sqlite,
 				r#"
-				INSERT INTO shared_changes (hlc, model_type, record_uuid, change_type, data, created_at)
-				VALUES (?, ?, ?, ?, ?, ?)
+				INSERT INTO shared_changes (hlc, model_type, record_uuid, change_type, data, created_at, field_clocks)
+				VALUES (?, ?, ?, ?, ?, ?, ?)
 				"#,
 				vec![
 					hlc_str.into(),
@@ -203,6 +238,7 @@ impl PeerLog {
 					change_type_str.into(),
 					data_json.into(),
 					created_at.into(),
+					field_clocks_json.into(),
 				],
 			))
 			.await
@@ -221,13 +257,13 @@ impl PeerLog {
 				let hlc_str = hlc.to_string();
 				Statement::from_sql_and_values(
 					DbBackend::Sqlite,
-					"SELECT hlc, model_type, record_uuid, change_type, data FROM shared_changes WHERE hlc > ? ORDER BY hlc ASC",
+					"SELECT hlc, model_type, record_uuid, change_type, data, field_clocks FROM shared_changes WHERE hlc > ? ORDER BY hlc ASC",
 					vec![hlc_str.into()],
 				)
 			}
 			None => Statement::from_string(
 				DbBackend::Sqlite,
-				"SELECT hlc, model_type, record_uuid, change_type, data FROM shared_changes ORDER BY hlc ASC".to_string(),
+				"SELECT hlc, model_type, record_uuid, change_type, data, field_clocks FROM shared_changes ORDER BY hlc ASC".to_string(),
 			),
 		};
 
@@ -266,12 +302,21 @@ impl PeerLog {
 			let data: serde_json::Value = serde_json::from_str(&data_json)
 				.map_err(|e| PeerLogError::SerializationError(e.to_string()))?;
 
+			let field_clocks_json: Option<String> = row
+				.try_get("", "field_clocks")
+				.map_err(|e| PeerLogError::QueryError(e.to_string()))?;
+			let field_clocks = field_clocks_json
+				.map(|json| serde_json::from_str(&json))
+				.transpose()
+				.map_err(|e| PeerLogError::SerializationError(e.to_string()))?;
+
 			entries.push(SharedChangeEntry {
 				hlc,
 				model_type,
 				record_uuid,
 				change_type,
 				data,
+				field_clocks,
 			});
 		}
 
@@ -438,6 +483,67 @@ impl PeerLog {
 		}
 	}
 
+	/// Whether a change with this exact HLC is already in the log
+	///
+	/// Used to recognize redelivered changes.
+	pub async fn contains(&self, hlc: HLC) -> Result<bool, PeerLogError> {
+		let result = self
+			.conn
+			.query_one(Statement::from_sql_and_values(
+				DbBackend::Sqlite,
+				"SELECT 1 AS found FROM shared_changes WHERE hlc = ?",
+				vec![hlc.to_string().into()],
+			))
+			.await
+			.map_err(|e| PeerLogError::QueryError(e.to_string()))?;
+
+		Ok(result.is_some())
+	}
+
+	/// Stamp a local change with per-field clocks before it is logged and broadcast
+	///
+	/// Fields whose value differs from the last known state get the change's HLC.
+	/// Deletes forget the record's fields instead.
+	pub async fn stamp_field_clocks(
+		&self,
+		entry: &mut SharedChangeEntry,
+	) -> Result<(), PeerLogError> {
+		if entry.change_type == ChangeType::Delete {
+			entry.field_clocks = None;
+			return FieldClockStore::clear(&self.conn, entry.record_uuid).await;
+		}
+
+		let previous = FieldClockStore::load(&self.conn, entry.record_uuid).await?;
+		let (clocks, changed) = stamp_fields(&previous, &entry.data, entry.hlc);
+		FieldClockStore::save(&self.conn, &entry.model_type, entry.record_uuid, &changed).await?;
+
+		entry.field_clocks = Some(clocks);
+		Ok(())
+	}
+
+	/// Last known state of each field of a record
+	pub async fn field_states(
+		&self,
+		record_uuid: Uuid,
+	) -> Result<HashMap<String, FieldState>, PeerLogError> {
+		FieldClockStore::load(&self.conn, record_uuid).await
+	}
+
+	/// Store fields taken from an applied incoming change
+	pub async fn save_field_states(
+		&self,
+		model_type: &str,
+		record_uuid: Uuid,
+		fields: &[(String, FieldState)],
+	) -> Result<(), PeerLogError> {
+		FieldClockStore::save(&self.conn, model_type, record_uuid, fields).await
+	}
+
+	/// Forget the fields of a deleted record
+	pub async fn clear_field_states(&self, record_uuid: Uuid) -> Result<(), PeerLogError> {
+		FieldClockStore::clear(&self.conn, record_uuid).await
+	}
+
 	/// Get database connection (for advanced queries)
 	pub fn conn(&self) -> &DatabaseConnection {
 		&self.conn
@@ -452,6 +558,9 @@ pub struct SharedChangeEntry {
 	pub record_uuid: Uuid,
 	pub change_type: ChangeType,
 	pub data: serde_json::Value,
+	/// Per-field clocks for merging concurrent edits (absent on deletes and from older peers)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub field_clocks: Option<FieldClocks>,
 }
 
 /// Type of database change
@@ -529,6 +638,7 @@ mod tests {
 			record_uuid: Uuid::new_v4(),
 			change_type: ChangeType::Insert,
 			data: serde_json::json!({"name": "test"}),
+			field_clocks: None,
 		};
 
 		peer_log.append(entry.clone()).await.unwrap();
@@ -551,6 +661,7 @@ mod tests {
 				record_uuid: Uuid::new_v4(),
 				change_type: ChangeType::Insert,
 				data: serde_json::json!({"name": format!("tag{}", i)}),
+				field_clocks: None,
 			};
 			peer_log.append(entry).await.unwrap();
 			tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
@@ -208,6 +208,20 @@ impl PeerWatermarkStore {
 			None => Ok(None),
 		}
 	}
+
+	/// Delete the watermarks of every peer, so the next catch-up starts from scratch
+	pub async fn clear<C: ConnectionTrait>(&self, conn: &C) -> Result<usize, WatermarkError> {
+		let result = conn
+			.execute(Statement::from_sql_and_values(
+				DbBackend::Sqlite,
+				"DELETE FROM peer_received_watermarks WHERE device_uuid = ?",
+				vec![self.device_uuid.to_string().into()],
+			))
+			.await
+			.map_err(|e| WatermarkError::QueryError(e.to_string()))?;
+
+		Ok(result.rows_affected() as usize)
+	}
 }
 
 /// Watermark errors
@@ -176,14 +176,21 @@ impl TransactionManager {
 		let hlc = hlc_generator.next();
 
 		// Create entry for peer log
-		let entry = crate::infra::sync::SharedChangeEntry {
+		let mut entry = crate::infra::sync::SharedChangeEntry {
 			hlc,
 			model_type: model_type.to_string(),
 			record_uuid,
 			change_type,
 			data: data.clone(),
+			field_clocks: None,
 		};
 
+		// Stamp changed fields so concurrent edits to other fields merge
+		peer_log
+			.stamp_field_clocks(&mut entry)
+			.await
+			.map_err(|e| TxError::SyncLog(format!("Failed to stamp field clocks: {}", e)))?;
+
 		// Write to peer log (append-only, for sync and conflict resolution)
 		peer_log
 			.append(entry.clone())
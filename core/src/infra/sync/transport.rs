@@ -217,6 +217,7 @@ impl NetworkTransport for MockNetworkTransport {
 					entries: vec![],
 					current_state: None,
 					has_more: false,
+					cursor: None,
 				})
 			}
 			_ => Err(anyhow::anyhow!("Mock: unexpected request type")),
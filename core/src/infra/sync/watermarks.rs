@@ -265,6 +265,20 @@ impl ResourceWatermarkStore {
 
 		Ok(result.rows_affected() as usize)
 	}
+
+	/// Delete the watermarks of every peer and resource type
+	pub async fn clear<C: ConnectionTrait>(&self, conn: &C) -> Result<usize, WatermarkError> {
+		let result = conn
+			.execute(Statement::from_sql_and_values(
+				DbBackend::Sqlite,
+				"DELETE FROM device_resource_watermarks WHERE device_uuid = ?",
+				vec![self.device_uuid.to_string().into()],
+			))
+			.await
+			.map_err(|e| WatermarkError::QueryError(e.to_string()))?;
+
+		Ok(result.rows_affected() as usize)
+	}
 }
 
 /// Watermark errors
@@ -710,6 +710,11 @@ async fn register_default_protocol_handlers(
 	// This ensures file transfers can only target directories that are managed by Spacedrive.
 	file_transfer_handler.set_context(context.clone());
 
+	// Load compression and bandwidth limits from app config
+	if let Ok(app_config) = crate::config::AppConfig::load_from(&context.data_dir) {
+		file_transfer_handler.set_transfer_config(app_config.file_transfer);
+	}
+
 	// Get device ID for job activity handler
 	let device_id = context
 		.device_manager
@@ -772,13 +777,18 @@ async fn reload_protocol_configs(
 		}
 	}
 
+	// Reload file transfer compression and bandwidth limits
+	if let Some(handler) = guard.get_handler("file_transfer") {
+		if let Some(file_transfer_handler) =
+			handler
+				.as_any()
+				.downcast_ref::<crate::service::network::protocol::FileTransferProtocolHandler>()
+		{
+			file_transfer_handler.set_transfer_config(app_config.file_transfer);
+		}
+	}
+
 	// Future: Add config reloading for other protocol handlers here
-	// Example:
-	// if let Some(handler) = guard.get_handler("file_transfer") {
-	//     if let Some(ft_handler) = handler.as_any().downcast_ref::<FileTransferProtocolHandler>() {
-	//         ft_handler.set_config(app_config.file_transfer).await;
-	//     }
-	// }
 
 	Ok(())
 }
@@ -128,6 +128,32 @@ impl LibraryManager {
 		}
 	}
 
+	/// Pick an unused library directory for `name` under `location`, or the default libraries
+	/// directory, without creating it
+	pub async fn allocate_library_path(
+		&self,
+		name: &str,
+		location: Option<PathBuf>,
+	) -> Result<PathBuf> {
+		if name.is_empty() {
+			return Err(LibraryError::InvalidName(
+				"Name cannot be empty".to_string(),
+			));
+		}
+
+		let base_path = location.unwrap_or_else(|| {
+			self.search_paths.first().cloned().unwrap_or_else(|| {
+				dirs::home_dir()
+					.unwrap_or_else(|| PathBuf::from("."))
+					.join("Spacedrive")
+					.join("Libraries")
+			})
+		});
+		tokio::fs::create_dir_all(&base_path).await?;
+
+		find_unique_library_path(&base_path, &sanitize_filename(name)).await
+	}
+
 	/// Create a new library
 	pub async fn create_library(
 		&self,
@@ -539,6 +565,8 @@ impl LibraryManager {
 			sync_service: OnceCell::new(),      // Initialized later
 			file_sync_service: OnceCell::new(), // Initialized later
 			device_cache: Arc::new(std::sync::RwLock::new(device_cache)),
+			indexing_totals: Arc::new(Default::default()),
+			embedding_index: tokio::sync::OnceCell::new(),
 			_lock: std::sync::Mutex::new(Some(lock)),
 		});
 
@@ -582,6 +610,9 @@ impl LibraryManager {
 		// 	);
 		// }
 
+		// Start running persisted job schedules, catching up on runs missed while closed
+		library.jobs().start_scheduler();
+
 		// Initialize sync service if networking is available
 		// If networking isn't ready, sync simply won't be initialized until caller does it explicitly
 		// TODO: maybe consider checking if networking is enabled rather than just checking if it's available
@@ -697,6 +728,11 @@ impl LibraryManager {
 				// Continue with close even if shutdown has errors
 			}
 
+			// Vault keys must not outlive the library they unlock
+			if let Some(context) = self.context.read().await.as_ref() {
+				context.vault_manager.lock_library(id).await;
+			}
+
 			// Emit event
 			self.event_bus.emit(Event::LibraryClosed { id, name });
 
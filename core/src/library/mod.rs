@@ -24,6 +24,9 @@ use crate::infra::{
 	job::manager::JobManager,
 	sync::{SyncEventBus, TransactionManager},
 };
+use crate::ops::indexing::metrics::IndexingTotals;
+use crate::ops::media::embedding::{VectorIndex, VectorIndexError};
+use crate::ops::models::EmbeddingModel;
 use once_cell::sync::OnceCell;
 use sea_orm::ConnectionTrait;
 use std::collections::HashMap;
@@ -69,6 +72,12 @@ pub struct Library {
 	/// Loaded from this library's devices table for per-library device resolution
 	device_cache: Arc<StdRwLock<HashMap<String, Uuid>>>,
 
+	/// Running totals of completed indexer jobs, exported as metrics
+	indexing_totals: Arc<IndexingTotals>,
+
+	/// Semantic search vector index (loaded on first use)
+	embedding_index: tokio::sync::OnceCell<Arc<RwLock<VectorIndex>>>,
+
 	/// Lock preventing concurrent access (wrapped in Mutex to allow explicit release during shutdown)
 	_lock: std::sync::Mutex<Option<LibraryLock>>,
 }
@@ -113,6 +122,26 @@ impl Library {
 		&self.jobs
 	}
 
+	/// Get the running totals of completed indexer jobs
+	pub fn indexing_totals(&self) -> &Arc<IndexingTotals> {
+		&self.indexing_totals
+	}
+
+	/// Get the semantic search vector index, loading it from disk on first use
+	pub async fn embedding_index(
+		&self,
+	) -> std::result::Result<Arc<RwLock<VectorIndex>>, VectorIndexError> {
+		self.embedding_index
+			.get_or_try_init(|| async {
+				let model = EmbeddingModel::default();
+				let path = self.embeddings_dir().join(format!("{}.hnsw", model.id()));
+				let index = VectorIndex::open(&path, model.id(), model.dimensions()).await?;
+				Ok(Arc::new(RwLock::new(index)))
+			})
+			.await
+			.cloned()
+	}
+
 	/// Get the transaction manager
 	pub fn transaction_manager(&self) -> &Arc<TransactionManager> {
 		&self.transaction_manager
@@ -417,6 +446,11 @@ impl Library {
 		self.path.join("thumbnails")
 	}
 
+	/// Get the embeddings directory for this library
+	pub fn embeddings_dir(&self) -> PathBuf {
+		self.path.join("embeddings")
+	}
+
 	/// Get the job logs directory for this library
 	pub fn job_logs_dir(&self) -> PathBuf {
 		self.path.join("logs")
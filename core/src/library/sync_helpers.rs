@@ -352,14 +352,20 @@ impl Library {
 		for (record_uuid, data) in records {
 			let hlc = hlc_gen.next();
 
-			let entry = crate::infra::sync::SharedChangeEntry {
+			let mut entry = crate::infra::sync::SharedChangeEntry {
 				hlc,
 				model_type: model_type.to_string(),
 				record_uuid,
 				change_type,
 				data,
+				field_clocks: None,
 			};
 
+			peer_log
+				.stamp_field_clocks(&mut entry)
+				.await
+				.map_err(|e| anyhow::anyhow!("Failed to stamp field clocks: {}", e))?;
+
 			// Write to peer log (for durability and pruning)
 			peer_log
 				.append(entry.clone())
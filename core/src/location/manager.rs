@@ -71,6 +71,11 @@ impl LocationManager {
 					"Sidecar paths cannot be used as locations".to_string(),
 				));
 			}
+			crate::domain::addressing::SdPath::Archive { .. } => {
+				return Err(LocationError::InvalidPath(
+					"Paths inside archives cannot be used as locations".to_string(),
+				));
+			}
 		}
 
 		// Begin transaction
@@ -0,0 +1,13 @@
+//! Action history - undo and redo of reversible file and tag actions
+//!
+//! Reversible actions describe how to reverse themselves as an [`UndoOperation`],
+//! which the ActionManager records in the session's undo journal (see
+//! `infra::action::journal`). `action.undo` and `action.redo` walk that history.
+
+pub mod operation;
+pub mod redo;
+pub mod undo;
+
+pub use operation::{PathMove, PathState, UndoOperation};
+pub use redo::{ActionRedoAction, ActionRedoInput, ActionRedoOutput};
+pub use undo::{ActionUndoAction, ActionUndoInput, ActionUndoOutput};
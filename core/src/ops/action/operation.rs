@@ -0,0 +1,661 @@
+//! Reversible operations recorded in the undo journal
+//!
+//! Each variant describes what an action did, captured just before it ran, in enough
+//! detail to reverse it and to replay it. Both directions check that the filesystem
+//! still looks the way the action left it and refuse with `ActionError::StateDrifted`
+//! when it doesn't, rather than moving or removing something the user has since changed.
+
+use crate::{
+	context::CoreContext,
+	domain::addressing::{SdPath, SdPathBatch},
+	infra::{
+		action::{error::ActionError, LibraryAction},
+		db::entities::{content_identity, entry, tag, user_metadata, user_metadata_tag},
+		job::traits::{DynJob, Job, JobHandler},
+		sync::ChangeType,
+	},
+	library::Library,
+	ops::{
+		files::{
+			copy::{action::FileConflictResolution, FileCopyJob, MoveMode},
+			delete::DeleteJob,
+			trash::{ledger::TrashLedger, FileTrashRestoreAction, FileTrashRestoreInput},
+		},
+		tags::apply::{input::TagTargets, ApplyTagsAction, ApplyTagsInput},
+	},
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+	ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{HashMap, HashSet},
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+use tokio::fs;
+use uuid::Uuid;
+
+/// Filesystem state of an item when an action recorded it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathState {
+	pub is_dir: bool,
+	/// Size in bytes (0 for directories)
+	pub size: u64,
+	/// Modification time of files; directories change theirs whenever a child moves
+	pub modified_at: Option<DateTime<Utc>>,
+}
+
+impl PathState {
+	/// Capture the state of the item at `path`
+	pub async fn capture(path: &Path) -> std::io::Result<Self> {
+		let metadata = fs::symlink_metadata(path).await?;
+		if metadata.is_dir() {
+			return Ok(Self {
+				is_dir: true,
+				size: 0,
+				modified_at: None,
+			});
+		}
+
+		Ok(Self {
+			is_dir: false,
+			size: metadata.len(),
+			modified_at: metadata.modified().ok().map(DateTime::<Utc>::from),
+		})
+	}
+
+	/// Check that the item now at `path` is still the one that was recorded
+	pub async fn verify(&self, path: &Path) -> Result<(), ActionError> {
+		let current = match Self::capture(path).await {
+			Ok(current) => current,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+				return Err(drifted(path, "has been removed"));
+			}
+			Err(e) => return Err(ActionError::io_error(path.display().to_string(), e)),
+		};
+
+		if current.is_dir != self.is_dir {
+			return Err(drifted(path, "has been replaced"));
+		}
+
+		// Copies across filesystems may only preserve timestamps to the second
+		let same_mtime = match (current.modified_at, self.modified_at) {
+			(Some(a), Some(b)) => a.timestamp() == b.timestamp(),
+			(a, b) => a.is_none() && b.is_none(),
+		};
+		if current.size != self.size || !same_mtime {
+			return Err(drifted(path, "has been modified"));
+		}
+
+		Ok(())
+	}
+}
+
+/// An item moved (or renamed) from `from` to `to`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathMove {
+	pub from: PathBuf,
+	pub to: PathBuf,
+	/// State of the item before it was moved
+	pub state: PathState,
+}
+
+/// An item moved to the system trash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedPath {
+	pub path: PathBuf,
+	pub state: PathState,
+}
+
+/// What a tag application was applied to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TaggedTarget {
+	Entry(Uuid),
+	Content(Uuid),
+}
+
+/// A tag application as it was before `tags.apply` overwrote it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriorTagApplication {
+	pub applied_context: Option<String>,
+	pub applied_variant: Option<String>,
+	pub confidence: f32,
+	pub source: String,
+	pub instance_attributes: Option<serde_json::Value>,
+}
+
+/// State of one (target, tag) pair before `tags.apply` ran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagSnapshot {
+	pub target: TaggedTarget,
+	pub tag_id: Uuid,
+	/// `None` if the tag was not applied to the target yet
+	pub previous: Option<PriorTagApplication>,
+}
+
+/// A recorded action, in enough detail to reverse and replay it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum UndoOperation {
+	/// Items were moved or renamed (`files.rename`, `files.copy` in move mode)
+	Move { moves: Vec<PathMove> },
+	/// A folder was created, optionally with items moved into it (`files.createFolder`)
+	CreateFolder {
+		folder: PathBuf,
+		moves: Vec<PathMove>,
+	},
+	/// Tags were applied (`tags.apply`)
+	ApplyTags {
+		input: ApplyTagsInput,
+		snapshots: Vec<TagSnapshot>,
+	},
+	/// Items were moved to the system trash (`files.delete` without `permanent`)
+	Trash {
+		items: Vec<TrashedPath>,
+		/// Ledger rows older than this belong to earlier deletes
+		trashed_after: DateTime<Utc>,
+	},
+}
+
+impl UndoOperation {
+	/// Record moves of local items from `from` to `to`
+	///
+	/// Returns `None` when a move cannot be reversed safely: a source that doesn't exist,
+	/// or a destination that is already occupied (and would be overwritten or renamed
+	/// around).
+	pub async fn capture_moves(
+		pairs: impl IntoIterator<Item = (PathBuf, PathBuf)>,
+	) -> Result<Option<Vec<PathMove>>, ActionError> {
+		let mut moves = Vec::new();
+
+		for (from, to) in pairs {
+			if fs::symlink_metadata(&to).await.is_ok() {
+				return Ok(None);
+			}
+			let Ok(state) = PathState::capture(&from).await else {
+				return Ok(None);
+			};
+			moves.push(PathMove { from, to, state });
+		}
+
+		Ok(Some(moves))
+	}
+
+	/// Record local items about to be moved to the trash
+	pub async fn capture_trash(paths: &[SdPath]) -> Result<Option<Self>, ActionError> {
+		let trashed_after = Utc::now();
+		let mut items = Vec::with_capacity(paths.len());
+
+		for path in paths {
+			let Some(local) = path.as_local_path() else {
+				return Ok(None);
+			};
+			let Ok(state) = PathState::capture(local).await else {
+				return Ok(None);
+			};
+			items.push(TrashedPath {
+				path: local.to_path_buf(),
+				state,
+			});
+		}
+
+		Ok(Some(Self::Trash {
+			items,
+			trashed_after,
+		}))
+	}
+
+	/// Record the tag applications `input` is about to add or overwrite
+	pub async fn capture_tags(
+		db: &DatabaseConnection,
+		input: &ApplyTagsInput,
+	) -> Result<Self, ActionError> {
+		Ok(Self::ApplyTags {
+			input: input.clone(),
+			snapshots: snapshot_tags(db, input).await?,
+		})
+	}
+
+	/// Reverse the recorded action
+	///
+	/// Returns the operation to keep for a later redo.
+	pub async fn undo(
+		self,
+		library: &Arc<Library>,
+		context: &Arc<CoreContext>,
+	) -> Result<Self, ActionError> {
+		match self {
+			Self::Move { moves } => {
+				for m in &moves {
+					m.state.verify(&m.to).await?;
+					ensure_vacant(&m.from).await?;
+				}
+				move_items(library, moves.iter().rev().map(|m| (&m.to, &m.from))).await?;
+				Ok(Self::Move { moves })
+			}
+			Self::CreateFolder { folder, moves } => {
+				let state = PathState::capture(&folder)
+					.await
+					.map_err(|_| drifted(&folder, "has been removed"))?;
+				if !state.is_dir {
+					return Err(drifted(&folder, "has been replaced"));
+				}
+				for m in &moves {
+					m.state.verify(&m.to).await?;
+					ensure_vacant(&m.from).await?;
+				}
+
+				// Only the items the action moved in may be left, or removing the
+				// folder would take the user's newer files with it
+				let moved: HashSet<&Path> = moves.iter().map(|m| m.to.as_path()).collect();
+				let mut dir = fs::read_dir(&folder)
+					.await
+					.map_err(|e| ActionError::io_error(folder.display().to_string(), e))?;
+				while let Some(child) = dir
+					.next_entry()
+					.await
+					.map_err(|e| ActionError::io_error(folder.display().to_string(), e))?
+				{
+					if !moved.contains(child.path().as_path()) {
+						return Err(drifted(&folder, "has gained new items"));
+					}
+				}
+
+				move_items(library, moves.iter().rev().map(|m| (&m.to, &m.from))).await?;
+				fs::remove_dir(&folder)
+					.await
+					.map_err(|e| ActionError::io_error(folder.display().to_string(), e))?;
+				Ok(Self::CreateFolder { folder, moves })
+			}
+			Self::ApplyTags { input, snapshots } => {
+				revert_tags(library, context, &snapshots).await?;
+				Ok(Self::ApplyTags { input, snapshots })
+			}
+			Self::Trash {
+				items,
+				trashed_after,
+			} => {
+				restore_trashed(library, context, &items, trashed_after).await?;
+				Ok(Self::Trash {
+					items,
+					trashed_after,
+				})
+			}
+		}
+	}
+
+	/// Re-apply a previously undone action
+	///
+	/// Returns the operation to keep for undoing it again.
+	pub async fn redo(
+		self,
+		library: &Arc<Library>,
+		context: &Arc<CoreContext>,
+	) -> Result<Self, ActionError> {
+		match self {
+			Self::Move { moves } => {
+				for m in &moves {
+					m.state.verify(&m.from).await?;
+					ensure_vacant(&m.to).await?;
+				}
+				move_items(library, moves.iter().map(|m| (&m.from, &m.to))).await?;
+				Ok(Self::Move { moves })
+			}
+			Self::CreateFolder { folder, moves } => {
+				ensure_vacant(&folder).await?;
+				for m in &moves {
+					m.state.verify(&m.from).await?;
+				}
+
+				fs::create_dir(&folder)
+					.await
+					.map_err(|e| ActionError::io_error(folder.display().to_string(), e))?;
+				move_items(library, moves.iter().map(|m| (&m.from, &m.to))).await?;
+				Ok(Self::CreateFolder { folder, moves })
+			}
+			Self::ApplyTags { input, .. } => {
+				let snapshots = snapshot_tags(library.db().conn(), &input).await?;
+				ApplyTagsAction::new(input.clone())
+					.execute(library.clone(), context.clone())
+					.await?;
+				Ok(Self::ApplyTags { input, snapshots })
+			}
+			Self::Trash { items, .. } => {
+				for item in &items {
+					item.state.verify(&item.path).await?;
+				}
+
+				let trashed_after = Utc::now();
+				let targets = items.iter().map(|i| SdPath::local(&i.path)).collect();
+				run_job(library, DeleteJob::trash(SdPathBatch::new(targets))).await?;
+				Ok(Self::Trash {
+					items,
+					trashed_after,
+				})
+			}
+		}
+	}
+}
+
+fn drifted(path: &Path, reason: &str) -> ActionError {
+	ActionError::StateDrifted(format!("{} {}", path.display(), reason))
+}
+
+async fn ensure_vacant(path: &Path) -> Result<(), ActionError> {
+	match fs::symlink_metadata(path).await {
+		Ok(_) => Err(drifted(path, "has been taken by another item")),
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+		Err(e) => Err(ActionError::io_error(path.display().to_string(), e)),
+	}
+}
+
+/// Dispatch a job and wait for it, so the journal only moves on once it is done
+async fn run_job<J>(library: &Arc<Library>, job: J) -> Result<(), ActionError>
+where
+	J: Job + JobHandler + DynJob,
+{
+	let handle = library.jobs().dispatch(job).await?;
+	handle.wait().await?;
+	Ok(())
+}
+
+/// Move each item to an exact destination path through the copy job, which keeps
+/// index entries (and their UUIDs, tags and content links) attached to the moved files
+async fn move_items<'a>(
+	library: &Arc<Library>,
+	moves: impl Iterator<Item = (&'a PathBuf, &'a PathBuf)>,
+) -> Result<(), ActionError> {
+	for (source, destination) in moves {
+		// A single source with a vacant destination is moved to exactly that path
+		let job = FileCopyJob::new_move(
+			SdPathBatch::new(vec![SdPath::local(source)]),
+			SdPath::local(destination),
+			MoveMode::Move,
+		);
+		run_job(library, job).await?;
+
+		if fs::symlink_metadata(destination).await.is_err() {
+			return Err(ActionError::Internal(format!(
+				"Failed to move {} back to {}",
+				source.display(),
+				destination.display()
+			)));
+		}
+	}
+
+	Ok(())
+}
+
+async fn tag_db_ids(
+	db: &DatabaseConnection,
+	tag_ids: &[Uuid],
+) -> Result<HashMap<Uuid, i32>, ActionError> {
+	Ok(tag::Entity::find()
+		.filter(tag::Column::Uuid.is_in(tag_ids.to_vec()))
+		.all(db)
+		.await?
+		.into_iter()
+		.map(|t| (t.uuid, t.id))
+		.collect())
+}
+
+async fn snapshot_tags(
+	db: &DatabaseConnection,
+	input: &ApplyTagsInput,
+) -> Result<Vec<TagSnapshot>, ActionError> {
+	let targets: Vec<TaggedTarget> = match &input.targets {
+		TagTargets::Content(ids) => ids.iter().copied().map(TaggedTarget::Content).collect(),
+		TagTargets::Entry(ids) => entry::Entity::find()
+			.filter(entry::Column::Id.is_in(ids.clone()))
+			.all(db)
+			.await?
+			.into_iter()
+			.filter_map(|e| e.uuid.map(TaggedTarget::Entry))
+			.collect(),
+	};
+	let tag_db_ids = tag_db_ids(db, &input.tag_ids).await?;
+
+	let mut snapshots = Vec::with_capacity(targets.len() * input.tag_ids.len());
+	for target in targets {
+		let metadata = find_metadata(db, &target).await?;
+		for tag_id in &input.tag_ids {
+			let Some(&tag_db_id) = tag_db_ids.get(tag_id) else {
+				continue;
+			};
+			let previous = match &metadata {
+				Some(metadata) => {
+					find_application(db, metadata.id, tag_db_id)
+						.await?
+						.map(|model| PriorTagApplication {
+							applied_context: model.applied_context,
+							applied_variant: model.applied_variant,
+							confidence: model.confidence,
+							source: model.source,
+							instance_attributes: model.instance_attributes,
+						})
+				}
+				None => None,
+			};
+			snapshots.push(TagSnapshot {
+				target: target.clone(),
+				tag_id: *tag_id,
+				previous,
+			});
+		}
+	}
+
+	Ok(snapshots)
+}
+
+async fn find_metadata(
+	db: &DatabaseConnection,
+	target: &TaggedTarget,
+) -> Result<Option<user_metadata::Model>, ActionError> {
+	let filter = match target {
+		TaggedTarget::Entry(uuid) => user_metadata::Column::EntryUuid.eq(*uuid),
+		TaggedTarget::Content(uuid) => user_metadata::Column::ContentIdentityUuid.eq(*uuid),
+	};
+	Ok(user_metadata::Entity::find().filter(filter).one(db).await?)
+}
+
+async fn find_application(
+	db: &DatabaseConnection,
+	metadata_id: i32,
+	tag_db_id: i32,
+) -> Result<Option<user_metadata_tag::Model>, ActionError> {
+	Ok(user_metadata_tag::Entity::find()
+		.filter(user_metadata_tag::Column::UserMetadataId.eq(metadata_id))
+		.filter(user_metadata_tag::Column::TagId.eq(tag_db_id))
+		.one(db)
+		.await?)
+}
+
+/// Remove tag applications `tags.apply` added and restore the ones it overwrote
+async fn revert_tags(
+	library: &Arc<Library>,
+	context: &Arc<CoreContext>,
+	snapshots: &[TagSnapshot],
+) -> Result<(), ActionError> {
+	let db = library.db().conn();
+	let tag_ids: Vec<Uuid> = snapshots.iter().map(|s| s.tag_id).collect();
+	let tag_db_ids = tag_db_ids(db, &tag_ids).await?;
+	let sync_error =
+		|e: anyhow::Error| ActionError::Internal(format!("Failed to sync tag association: {}", e));
+
+	let mut affected_entry_uuids = Vec::new();
+	for snapshot in snapshots {
+		let (Some(metadata), Some(&tag_db_id)) = (
+			find_metadata(db, &snapshot.target).await?,
+			tag_db_ids.get(&snapshot.tag_id),
+		) else {
+			continue;
+		};
+		// Already removed since, nothing left to revert
+		let Some(current) = find_application(db, metadata.id, tag_db_id).await? else {
+			continue;
+		};
+
+		match &snapshot.previous {
+			None => {
+				current.clone().delete(db).await?;
+				library
+					.sync_model(&current, ChangeType::Delete)
+					.await
+					.map_err(sync_error)?;
+			}
+			Some(previous) => {
+				let version = current.version;
+				let mut model: user_metadata_tag::ActiveModel = current.into();
+				model.applied_context = Set(previous.applied_context.clone());
+				model.applied_variant = Set(previous.applied_variant.clone());
+				model.confidence = Set(previous.confidence);
+				model.source = Set(previous.source.clone());
+				model.instance_attributes = Set(previous.instance_attributes.clone());
+				model.updated_at = Set(Utc::now());
+				model.version = Set(version + 1);
+				let updated = model.update(db).await?;
+				library
+					.sync_model(&updated, ChangeType::Update)
+					.await
+					.map_err(sync_error)?;
+			}
+		}
+
+		match &snapshot.target {
+			TaggedTarget::Entry(uuid) => affected_entry_uuids.push(*uuid),
+			TaggedTarget::Content(uuid) => {
+				if let Some(ci) = content_identity::Entity::find()
+					.filter(content_identity::Column::Uuid.eq(*uuid))
+					.one(db)
+					.await?
+				{
+					affected_entry_uuids.extend(
+						entry::Entity::find()
+							.filter(entry::Column::ContentId.eq(ci.id))
+							.all(db)
+							.await?
+							.into_iter()
+							.filter_map(|e| e.uuid),
+					);
+				}
+			}
+		}
+	}
+
+	if !affected_entry_uuids.is_empty() {
+		affected_entry_uuids.sort();
+		affected_entry_uuids.dedup();
+		let resource_manager =
+			crate::domain::ResourceManager::new(Arc::new(db.clone()), context.events.clone());
+		if let Err(e) = resource_manager
+			.emit_resource_events("file", affected_entry_uuids)
+			.await
+		{
+			tracing::warn!("Failed to emit file resource events after undo: {}", e);
+		}
+	}
+
+	Ok(())
+}
+
+/// Put items a delete moved to the trash back where they were
+async fn restore_trashed(
+	library: &Arc<Library>,
+	context: &Arc<CoreContext>,
+	items: &[TrashedPath],
+	trashed_after: DateTime<Utc>,
+) -> Result<(), ActionError> {
+	let db = library.db().conn();
+	let ledger = TrashLedger::list(db)
+		.await
+		.map_err(|e| ActionError::Database(e.to_string()))?;
+
+	let mut item_ids = Vec::with_capacity(items.len());
+	for item in items {
+		// The ledger is newest first, so this is the delete being undone
+		let row = ledger.iter().find(|row| {
+			row.trashed_at >= trashed_after
+				&& serde_json::from_value::<SdPath>(row.original_path.clone())
+					.ok()
+					.and_then(|p| p.as_local_path().map(|p| p == item.path))
+					.unwrap_or(false)
+		});
+		let Some(row) = row else {
+			return Err(drifted(&item.path, "has been removed from the trash"));
+		};
+
+		let trash_path = PathBuf::from(&row.trash_path);
+		item.state
+			.verify(&trash_path)
+			.await
+			.map_err(|_| drifted(&item.path, "has changed or been removed from the trash"))?;
+		ensure_vacant(&item.path).await?;
+		item_ids.push(row.uuid);
+	}
+
+	let output = FileTrashRestoreAction::new(FileTrashRestoreInput {
+		item_ids,
+		on_conflict: Some(FileConflictResolution::Abort),
+	})
+	.execute(library.clone(), context.clone())
+	.await?;
+
+	if let Some(failure) = output.failed.first() {
+		return Err(ActionError::Internal(format!(
+			"Failed to restore trashed item: {}",
+			failure.error
+		)));
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_path_state_detects_drift() {
+		let temp = tempfile::tempdir().unwrap();
+		let file = temp.path().join("notes.txt");
+		std::fs::write(&file, b"first draft").unwrap();
+
+		let state = PathState::capture(&file).await.unwrap();
+		assert!(state.verify(&file).await.is_ok());
+
+		std::fs::write(&file, b"a much longer second draft").unwrap();
+		assert!(matches!(
+			state.verify(&file).await,
+			Err(ActionError::StateDrifted(_))
+		));
+
+		std::fs::remove_file(&file).unwrap();
+		std::fs::create_dir(&file).unwrap();
+		assert!(matches!(
+			state.verify(&file).await,
+			Err(ActionError::StateDrifted(_))
+		));
+	}
+
+	#[tokio::test]
+	async fn test_capture_moves_refuses_occupied_destination() {
+		let temp = tempfile::tempdir().unwrap();
+		let from = temp.path().join("a.txt");
+		let to = temp.path().join("b.txt");
+		std::fs::write(&from, b"a").unwrap();
+
+		let moves = UndoOperation::capture_moves([(from.clone(), to.clone())])
+			.await
+			.unwrap()
+			.unwrap();
+		assert_eq!(moves.len(), 1);
+		assert_eq!(moves[0].state.size, 1);
+
+		std::fs::write(&to, b"b").unwrap();
+		assert!(UndoOperation::capture_moves([(from, to)])
+			.await
+			.unwrap()
+			.is_none());
+	}
+}
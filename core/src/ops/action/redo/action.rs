@@ -0,0 +1,99 @@
+//! Action redo handler
+
+use super::{input::ActionRedoInput, output::ActionRedoOutput};
+use crate::{
+	context::CoreContext,
+	infra::action::{error::ActionError, journal::SessionKey, LibraryAction},
+	library::Library,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionRedoAction {
+	input: ActionRedoInput,
+	/// Session whose history is redone, bound by the ActionManager
+	#[serde(skip)]
+	session: Option<SessionKey>,
+}
+
+impl ActionRedoAction {
+	pub fn new(input: ActionRedoInput, session: SessionKey) -> Self {
+		Self {
+			input,
+			session: Some(session),
+		}
+	}
+}
+
+impl LibraryAction for ActionRedoAction {
+	type Input = ActionRedoInput;
+	type Output = ActionRedoOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self {
+			input,
+			session: None,
+		})
+	}
+
+	fn bind_session(&mut self, session: &SessionKey) {
+		self.session = Some(session.clone());
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let session = self.session.ok_or_else(|| {
+			ActionError::Internal("action.redo must be dispatched within a session".to_string())
+		})?;
+		let journal = &context.undo_journal;
+		let library_id = library.id();
+
+		let Some(mut entry) = journal.take_redo(library_id, &session) else {
+			return Err(ActionError::Validation {
+				field: "history".to_string(),
+				message: "Nothing to redo".to_string(),
+			});
+		};
+
+		if let Some(expected) = self.input.action_id {
+			if expected != entry.action_id {
+				journal.restore_redo(library_id, &session, entry);
+				return Err(ActionError::Validation {
+					field: "action_id".to_string(),
+					message: "The most recently undone action has changed".to_string(),
+				});
+			}
+		}
+
+		// Drift is checked before anything is touched, so a refused redo stays redoable
+		match entry.operation.clone().redo(&library, &context).await {
+			Ok(operation) => entry.operation = operation,
+			Err(e) => {
+				journal.restore_redo(library_id, &session, entry);
+				return Err(e);
+			}
+		}
+
+		let action_id = entry.action_id;
+		let action_kind = entry.action_kind.clone();
+		journal.complete_redo(library_id, &session, entry);
+		let (undo_depth, redo_depth) = journal.depth(library_id, &session);
+
+		Ok(ActionRedoOutput {
+			action_id,
+			action_kind,
+			undo_depth,
+			redo_depth,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"action.redo"
+	}
+}
+
+crate::register_library_action!(ActionRedoAction, "action.redo");
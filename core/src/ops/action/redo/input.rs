@@ -0,0 +1,13 @@
+//! Input types for redoing an action
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+/// Input for redoing the most recently undone action
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct ActionRedoInput {
+	/// Action the client expects to redo. When set and the most recently undone
+	/// action is a different one, nothing is redone.
+	pub action_id: Option<Uuid>,
+}
@@ -0,0 +1,9 @@
+//! Re-apply the most recently undone action of a session
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::ActionRedoAction;
+pub use input::ActionRedoInput;
+pub use output::ActionRedoOutput;
@@ -0,0 +1,17 @@
+//! Output types for redoing an action
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ActionRedoOutput {
+	/// Audit log ID of the action that was redone
+	pub action_id: Uuid,
+	/// Kind of the redone action, e.g. `files.rename`
+	pub action_kind: String,
+	/// Actions that can now be undone in this session
+	pub undo_depth: usize,
+	/// Actions left to redo
+	pub redo_depth: usize,
+}
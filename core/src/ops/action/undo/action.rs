@@ -0,0 +1,99 @@
+//! Action undo handler
+
+use super::{input::ActionUndoInput, output::ActionUndoOutput};
+use crate::{
+	context::CoreContext,
+	infra::action::{error::ActionError, journal::SessionKey, LibraryAction},
+	library::Library,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionUndoAction {
+	input: ActionUndoInput,
+	/// Session whose history is undone, bound by the ActionManager
+	#[serde(skip)]
+	session: Option<SessionKey>,
+}
+
+impl ActionUndoAction {
+	pub fn new(input: ActionUndoInput, session: SessionKey) -> Self {
+		Self {
+			input,
+			session: Some(session),
+		}
+	}
+}
+
+impl LibraryAction for ActionUndoAction {
+	type Input = ActionUndoInput;
+	type Output = ActionUndoOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self {
+			input,
+			session: None,
+		})
+	}
+
+	fn bind_session(&mut self, session: &SessionKey) {
+		self.session = Some(session.clone());
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let session = self.session.ok_or_else(|| {
+			ActionError::Internal("action.undo must be dispatched within a session".to_string())
+		})?;
+		let journal = &context.undo_journal;
+		let library_id = library.id();
+
+		let Some(mut entry) = journal.take_undo(library_id, &session) else {
+			return Err(ActionError::Validation {
+				field: "history".to_string(),
+				message: "Nothing to undo".to_string(),
+			});
+		};
+
+		if let Some(expected) = self.input.action_id {
+			if expected != entry.action_id {
+				journal.restore_undo(library_id, &session, entry);
+				return Err(ActionError::Validation {
+					field: "action_id".to_string(),
+					message: "The most recent action has changed".to_string(),
+				});
+			}
+		}
+
+		// Drift is checked before anything is touched, so a refused undo stays undoable
+		match entry.operation.clone().undo(&library, &context).await {
+			Ok(operation) => entry.operation = operation,
+			Err(e) => {
+				journal.restore_undo(library_id, &session, entry);
+				return Err(e);
+			}
+		}
+
+		let action_id = entry.action_id;
+		let action_kind = entry.action_kind.clone();
+		journal.complete_undo(library_id, &session, entry);
+		let (undo_depth, redo_depth) = journal.depth(library_id, &session);
+
+		Ok(ActionUndoOutput {
+			action_id,
+			action_kind,
+			undo_depth,
+			redo_depth,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"action.undo"
+	}
+}
+
+crate::register_library_action!(ActionUndoAction, "action.undo");
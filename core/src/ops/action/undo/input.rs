@@ -0,0 +1,13 @@
+//! Input types for undoing an action
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+/// Input for undoing the most recent action
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct ActionUndoInput {
+	/// Action the client expects to undo. When set and the most recent action is a
+	/// different one (e.g. another window acted in between), nothing is undone.
+	pub action_id: Option<Uuid>,
+}
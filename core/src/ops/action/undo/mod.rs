@@ -0,0 +1,9 @@
+//! Undo the most recent reversible action of a session
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::ActionUndoAction;
+pub use input::ActionUndoInput;
+pub use output::ActionUndoOutput;
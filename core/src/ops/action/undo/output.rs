@@ -0,0 +1,17 @@
+//! Output types for undoing an action
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ActionUndoOutput {
+	/// Audit log ID of the action that was undone
+	pub action_id: Uuid,
+	/// Kind of the undone action, e.g. `files.rename`
+	pub action_kind: String,
+	/// Actions left to undo in this session
+	pub undo_depth: usize,
+	/// Actions that can now be redone
+	pub redo_depth: usize,
+}
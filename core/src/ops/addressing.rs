@@ -36,6 +36,8 @@ impl PathResolver {
 			}
 			// Cloud paths are already resolved (no additional resolution needed)
 			SdPath::Cloud { .. } => Ok(path.clone()),
+			// Archive members are read in place from the archive file
+			SdPath::Archive { .. } => Ok(path.clone()),
 			// If content-based, find the optimal physical path
 			SdPath::Content { content_id } => unimplemented!(),
 			// Sidecar paths need to be resolved to physical locations
@@ -0,0 +1,87 @@
+//! Create automation rule action
+
+use super::{input::CreateAutomationRuleInput, output::CreateAutomationRuleOutput};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::{error::ActionError, LibraryAction},
+		db::entities::{automation_rule, tag},
+		event::Event,
+	},
+	library::Library,
+	ops::automation::{list::AutomationRuleInfo, rule::AutomationAction, AUTOMATION_RULE_RESOURCE},
+};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAutomationRuleAction {
+	input: CreateAutomationRuleInput,
+}
+
+impl LibraryAction for CreateAutomationRuleAction {
+	type Input = CreateAutomationRuleInput;
+	type Output = CreateAutomationRuleOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		input.validate()?;
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let db = library.db().conn();
+
+		if let AutomationAction::ApplyTag { tag_id } = &self.input.action {
+			tag::Entity::find()
+				.filter(tag::Column::Uuid.eq(*tag_id))
+				.one(db)
+				.await?
+				.ok_or_else(|| ActionError::Validation {
+					field: "action".to_string(),
+					message: format!("Tag {} not found", tag_id),
+				})?;
+		}
+
+		let now = Utc::now();
+		let model = automation_rule::ActiveModel {
+			uuid: Set(Uuid::new_v4()),
+			name: Set(self.input.name.trim().to_string()),
+			event_type: Set(self.input.event_type),
+			filters: Set(serde_json::to_value(&self.input.filters)?),
+			action: Set(serde_json::to_value(&self.input.action)?),
+			enabled: Set(true),
+			cooldown_secs: Set(self.input.cooldown_seconds.min(i32::MAX as u32) as i32),
+			created_at: Set(now),
+			updated_at: Set(now),
+			..Default::default()
+		}
+		.insert(db)
+		.await?;
+
+		// Lets the automation service pick up the rule
+		let info = AutomationRuleInfo::try_from(model.clone())
+			.map_err(|e| ActionError::Internal(format!("Invalid automation rule: {}", e)))?;
+		library.event_bus().emit(Event::ResourceChanged {
+			resource_type: AUTOMATION_RULE_RESOURCE.to_string(),
+			resource: serde_json::to_value(&info)?,
+			metadata: None,
+		});
+
+		Ok(CreateAutomationRuleOutput {
+			rule_id: model.uuid,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"automation.rules.create"
+	}
+}
+
+crate::register_library_action!(CreateAutomationRuleAction, "automation.rules.create");
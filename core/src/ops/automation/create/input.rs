@@ -0,0 +1,41 @@
+//! Input for creating an automation rule
+
+use crate::ops::automation::rule::{validate_rule, AutomationAction, EventFieldFilter};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CreateAutomationRuleInput {
+	/// Display name for the rule
+	pub name: String,
+
+	/// Event variant that triggers the rule, e.g. "VolumeMountChanged" or "JobFailed"
+	pub event_type: String,
+
+	/// Conditions on the event's fields, all of which must match
+	#[serde(default)]
+	pub filters: Vec<EventFieldFilter>,
+
+	/// What to do when the rule fires
+	pub action: AutomationAction,
+
+	/// Minimum seconds between two firings, so a burst of matching events fires the
+	/// rule once
+	#[serde(default = "default_cooldown_seconds")]
+	pub cooldown_seconds: u32,
+}
+
+fn default_cooldown_seconds() -> u32 {
+	60
+}
+
+impl CreateAutomationRuleInput {
+	/// Validate the input
+	pub fn validate(&self) -> Result<(), String> {
+		if self.name.trim().is_empty() {
+			return Err("name cannot be empty".to_string());
+		}
+
+		validate_rule(&self.event_type, &self.filters, &self.action)
+	}
+}
@@ -0,0 +1,9 @@
+//! Create an automation rule
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::CreateAutomationRuleAction;
+pub use input::CreateAutomationRuleInput;
+pub use output::CreateAutomationRuleOutput;
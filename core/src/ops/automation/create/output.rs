@@ -0,0 +1,10 @@
+//! Output for creating an automation rule
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CreateAutomationRuleOutput {
+	pub rule_id: Uuid,
+}
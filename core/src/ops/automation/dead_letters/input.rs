@@ -0,0 +1,16 @@
+//! Input for listing automation dead letters
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct ListAutomationDeadLettersInput {
+	/// Only dead letters from this rule
+	#[serde(default)]
+	pub rule_id: Option<Uuid>,
+
+	/// Maximum number returned, newest first (default 100)
+	#[serde(default)]
+	pub limit: Option<u64>,
+}
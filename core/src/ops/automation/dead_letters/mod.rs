@@ -0,0 +1,9 @@
+//! List the automation dead-letter log
+
+pub mod input;
+pub mod output;
+pub mod query;
+
+pub use input::ListAutomationDeadLettersInput;
+pub use output::{AutomationDeadLetterInfo, ListAutomationDeadLettersOutput};
+pub use query::ListAutomationDeadLettersQuery;
@@ -0,0 +1,25 @@
+//! Output for listing automation dead letters
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AutomationDeadLetterInfo {
+	pub id: Uuid,
+	pub rule_id: Uuid,
+	pub rule_name: String,
+	/// Event context the rule matched
+	pub event: serde_json::Value,
+	/// Action with its templates rendered
+	pub action: serde_json::Value,
+	pub error: String,
+	pub attempts: u32,
+	pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListAutomationDeadLettersOutput {
+	pub dead_letters: Vec<AutomationDeadLetterInfo>,
+}
@@ -0,0 +1,77 @@
+//! List automation dead letters query
+
+use super::{
+	input::ListAutomationDeadLettersInput,
+	output::{AutomationDeadLetterInfo, ListAutomationDeadLettersOutput},
+};
+use crate::{
+	context::CoreContext,
+	infra::{
+		db::entities::automation_dead_letter,
+		query::{LibraryQuery, QueryError, QueryResult},
+	},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+const DEFAULT_LIMIT: u64 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListAutomationDeadLettersQuery {
+	pub input: ListAutomationDeadLettersInput,
+}
+
+impl LibraryQuery for ListAutomationDeadLettersQuery {
+	type Input = ListAutomationDeadLettersInput;
+	type Output = ListAutomationDeadLettersOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library in session".to_string()))?;
+		let library = context
+			.get_library(library_id)
+			.await
+			.ok_or(QueryError::LibraryNotFound(library_id))?;
+
+		let mut query = automation_dead_letter::Entity::find()
+			.order_by_desc(automation_dead_letter::Column::CreatedAt)
+			.limit(self.input.limit.unwrap_or(DEFAULT_LIMIT));
+		if let Some(rule_id) = self.input.rule_id {
+			query = query.filter(automation_dead_letter::Column::RuleUuid.eq(rule_id));
+		}
+
+		let dead_letters = query
+			.all(library.db().conn())
+			.await?
+			.into_iter()
+			.map(|model| AutomationDeadLetterInfo {
+				id: model.uuid,
+				rule_id: model.rule_uuid,
+				rule_name: model.rule_name,
+				event: model.event,
+				action: model.action,
+				error: model.error,
+				attempts: model.attempts.max(0) as u32,
+				created_at: model.created_at,
+			})
+			.collect();
+
+		Ok(ListAutomationDeadLettersOutput { dead_letters })
+	}
+}
+
+crate::register_library_query!(
+	ListAutomationDeadLettersQuery,
+	"automation.dead_letters.list"
+);
@@ -0,0 +1,63 @@
+//! Delete automation rule action
+
+use super::{input::DeleteAutomationRuleInput, output::DeleteAutomationRuleOutput};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::{error::ActionError, LibraryAction},
+		db::entities::automation_rule,
+		event::Event,
+	},
+	library::Library,
+	ops::automation::AUTOMATION_RULE_RESOURCE,
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteAutomationRuleAction {
+	input: DeleteAutomationRuleInput,
+}
+
+impl LibraryAction for DeleteAutomationRuleAction {
+	type Input = DeleteAutomationRuleInput;
+	type Output = DeleteAutomationRuleOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let result = automation_rule::Entity::delete_many()
+			.filter(automation_rule::Column::Uuid.eq(self.input.rule_id))
+			.exec(library.db().conn())
+			.await?;
+
+		if result.rows_affected == 0 {
+			return Err(ActionError::Validation {
+				field: "rule_id".to_string(),
+				message: format!("Automation rule {} not found", self.input.rule_id),
+			});
+		}
+
+		library.event_bus().emit(Event::ResourceDeleted {
+			resource_type: AUTOMATION_RULE_RESOURCE.to_string(),
+			resource_id: self.input.rule_id,
+		});
+
+		Ok(DeleteAutomationRuleOutput {
+			rule_id: self.input.rule_id,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"automation.rules.delete"
+	}
+}
+
+crate::register_library_action!(DeleteAutomationRuleAction, "automation.rules.delete");
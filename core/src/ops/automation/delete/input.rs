@@ -0,0 +1,10 @@
+//! Input for deleting an automation rule
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DeleteAutomationRuleInput {
+	pub rule_id: Uuid,
+}
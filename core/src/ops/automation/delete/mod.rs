@@ -0,0 +1,9 @@
+//! Delete an automation rule
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::DeleteAutomationRuleAction;
+pub use input::DeleteAutomationRuleInput;
+pub use output::DeleteAutomationRuleOutput;
@@ -0,0 +1,11 @@
+//! Output for deleting an automation rule
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+/// Dead letters the rule produced are kept
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DeleteAutomationRuleOutput {
+	pub rule_id: Uuid,
+}
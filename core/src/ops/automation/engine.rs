@@ -0,0 +1,521 @@
+//! Automation engine
+//!
+//! Turns events into the JSON context rules are matched against, renders `{{path}}`
+//! templates from it and runs rule actions. Actions that still fail after their
+//! attempts are written to the dead-letter log, with webhook headers redacted.
+
+use super::rule::{webhook_url, AutomationAction, EventFieldFilter};
+use crate::{
+	context::CoreContext,
+	domain::tag::TagSource,
+	infra::{
+		action::LibraryAction,
+		api::SessionContext,
+		db::entities::{automation_dead_letter, automation_rule, entry},
+		event::Event,
+		job::types::JobId,
+		wire::registry::LIBRARY_ACTIONS,
+	},
+	library::Library,
+	ops::tags::apply::{
+		action::ApplyTagsAction,
+		input::{ApplyTagsInput, TagTargets},
+	},
+	volume::{VolumeFingerprint, VolumeManager},
+};
+use chrono::Utc;
+use globset::Glob;
+use sea_orm::{
+	ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+	QuerySelect, Set,
+};
+use serde_json::{json, Value};
+use std::{sync::Arc, time::Duration};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Dead letters kept per library; older ones are pruned
+const MAX_DEAD_LETTERS: u64 = 500;
+
+/// Timeout for a single webhook delivery attempt
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Build the context filters and templates see for an event:
+///
+/// ```json
+/// { "type": "JobFailed", "library_id": null, "data": { "job_id": "…", "error": "…" } }
+/// ```
+///
+/// `data` holds the event's fields. Volume events also get a `volume` object once
+/// [`attach_volume`] has run.
+pub fn event_context(event: &Event) -> Value {
+	// Unit variants serialize to a bare string and have no data
+	let data = match serde_json::to_value(event) {
+		Ok(Value::Object(map)) => map.into_iter().next().map(|(_, data)| data),
+		_ => None,
+	}
+	.unwrap_or(Value::Null);
+
+	let library_id = match event {
+		Event::LibraryCreated { id, .. }
+		| Event::LibraryOpened { id, .. }
+		| Event::LibraryClosed { id, .. }
+		| Event::LibraryDeleted { id, .. } => Some(*id),
+		_ => data["library_id"]
+			.as_str()
+			.and_then(|id| Uuid::parse_str(id).ok()),
+	};
+
+	json!({
+		"type": event.variant_name(),
+		"library_id": library_id,
+		"data": data,
+	})
+}
+
+/// Add the volume a volume event refers to, so rules can match on `volume.name`
+/// and templates can use `volume.mount_point`
+pub async fn attach_volume(context: &mut Value, volumes: &VolumeManager) {
+	let volume = if context["type"] == "VolumeAdded" {
+		Some(context["data"].clone())
+	} else if let Some(fingerprint) = context["data"]["fingerprint"].as_str() {
+		volumes
+			.get_volume(&VolumeFingerprint(fingerprint.to_string()))
+			.await
+			.and_then(|volume| serde_json::to_value(volume).ok())
+	} else {
+		None
+	};
+
+	if let (Some(volume), Some(map)) = (volume, context.as_object_mut()) {
+		map.insert("volume".to_string(), volume);
+	}
+}
+
+/// Look up a dot path such as `data.added.0` in a context
+pub fn lookup<'a>(context: &'a Value, path: &str) -> Option<&'a Value> {
+	path.split('.')
+		.try_fold(context, |value, segment| match value {
+			Value::Object(map) => map.get(segment),
+			Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+			_ => None,
+		})
+}
+
+/// Whether the context passes every filter
+pub fn matches_filters(filters: &[EventFieldFilter], context: &Value) -> bool {
+	filters.iter().all(|filter| {
+		let field = lookup(context, filter.field());
+		match filter {
+			EventFieldFilter::Equals { value, .. } => field == Some(value),
+			EventFieldFilter::NotEquals { value, .. } => field != Some(value),
+			EventFieldFilter::Contains { value, .. } => match (field, value) {
+				(Some(Value::String(s)), Value::String(needle)) => s.contains(needle.as_str()),
+				(Some(Value::Array(items)), needle) => items.contains(needle),
+				_ => false,
+			},
+			EventFieldFilter::Glob { pattern, .. } => match field.and_then(value_as_string) {
+				Some(s) => Glob::new(pattern)
+					.map(|glob| glob.compile_matcher().is_match(s))
+					.unwrap_or(false),
+				None => false,
+			},
+			EventFieldFilter::Exists { .. } => field.is_some_and(|v| !v.is_null()),
+		}
+	})
+}
+
+fn value_as_string(value: &Value) -> Option<String> {
+	match value {
+		Value::String(s) => Some(s.clone()),
+		Value::Null | Value::Array(_) | Value::Object(_) => None,
+		other => Some(other.to_string()),
+	}
+}
+
+/// Replace `{{path}}` templates in every string of `value` with fields of the context
+pub fn render(value: &Value, context: &Value) -> Result<Value, String> {
+	match value {
+		Value::String(s) => render_str(s, context),
+		Value::Array(items) => items
+			.iter()
+			.map(|item| render(item, context))
+			.collect::<Result<Vec<_>, _>>()
+			.map(Value::Array),
+		Value::Object(map) => map
+			.iter()
+			.map(|(key, item)| Ok((key.clone(), render(item, context)?)))
+			.collect::<Result<serde_json::Map<_, _>, String>>()
+			.map(Value::Object),
+		other => Ok(other.clone()),
+	}
+}
+
+fn render_str(template: &str, context: &Value) -> Result<Value, String> {
+	let resolve = |path: &str| {
+		lookup(context, path.trim())
+			.filter(|v| !v.is_null())
+			.ok_or_else(|| format!("Template field '{}' is not in the event", path.trim()))
+	};
+
+	// A string that is exactly one template keeps the field's JSON type
+	if let Some(path) = template
+		.strip_prefix("{{")
+		.and_then(|rest| rest.strip_suffix("}}"))
+		.filter(|path| !path.contains("{{") && !path.contains("}}"))
+	{
+		return resolve(path).cloned();
+	}
+
+	let mut rendered = String::with_capacity(template.len());
+	let mut rest = template;
+	while let Some(start) = rest.find("{{") {
+		let Some(end) = rest[start..].find("}}") else {
+			break;
+		};
+		rendered.push_str(&rest[..start]);
+		let value = resolve(&rest[start + 2..start + end])?;
+		rendered.push_str(&value_as_string(value).unwrap_or_else(|| value.to_string()));
+		rest = &rest[start + end + 2..];
+	}
+	rendered.push_str(rest);
+	Ok(Value::String(rendered))
+}
+
+fn render_action(action: &AutomationAction, context: &Value) -> Result<AutomationAction, String> {
+	Ok(match action {
+		AutomationAction::Action { action, input } => AutomationAction::Action {
+			action: action.clone(),
+			input: render(input, context)?,
+		},
+		AutomationAction::Job { job_name, args } => AutomationAction::Job {
+			job_name: job_name.clone(),
+			args: render(args, context)?,
+		},
+		AutomationAction::ApplyTag { tag_id } => AutomationAction::ApplyTag { tag_id: *tag_id },
+		AutomationAction::Webhook {
+			url,
+			headers,
+			max_attempts,
+			allow_remote,
+		} => AutomationAction::Webhook {
+			url: value_as_string(&render_str(url, context)?).unwrap_or_default(),
+			headers: headers.clone(),
+			max_attempts: *max_attempts,
+			allow_remote: *allow_remote,
+		},
+	})
+}
+
+/// Enabled automation rules of a library
+pub async fn load_rules(db: &DatabaseConnection) -> Result<Vec<automation_rule::Model>, DbErr> {
+	automation_rule::Entity::find()
+		.filter(automation_rule::Column::Enabled.eq(true))
+		.order_by_asc(automation_rule::Column::CreatedAt)
+		.all(db)
+		.await
+}
+
+/// Run a rule's action for an event, returning the job it dispatched, if any. Failures
+/// that survive the action's attempts are written to the dead-letter log and returned.
+pub async fn fire(
+	context: Arc<CoreContext>,
+	library: Arc<Library>,
+	rule: automation_rule::Model,
+	event: Value,
+) -> Result<Option<JobId>, String> {
+	let action: AutomationAction = serde_json::from_value(rule.action.clone())
+		.map_err(|e| format!("Invalid action on automation rule {}: {}", rule.uuid, e))?;
+
+	let (rendered, result) = match render_action(&action, &event) {
+		Ok(rendered) => {
+			let result = execute(&context, &library, &rule, &rendered, &event).await;
+			(rendered, result)
+		}
+		Err(e) => (action, Err((e, 0))),
+	};
+
+	let (error, attempts) = match result {
+		Ok(job_id) => {
+			debug!(rule = %rule.uuid, "Automation rule fired");
+			return Ok(job_id);
+		}
+		Err(failure) => failure,
+	};
+
+	warn!(rule = %rule.uuid, attempts, "Automation action failed: {}", error);
+	if let Err(e) = record_dead_letter(
+		library.db().conn(),
+		&rule,
+		&event,
+		&rendered,
+		&error,
+		attempts,
+	)
+	.await
+	{
+		warn!(rule = %rule.uuid, "Failed to record automation dead letter: {}", e);
+	}
+	Err(error)
+}
+
+/// Execute a rendered action, returning the error and attempt count on failure
+async fn execute(
+	context: &Arc<CoreContext>,
+	library: &Arc<Library>,
+	rule: &automation_rule::Model,
+	action: &AutomationAction,
+	event: &Value,
+) -> Result<Option<JobId>, (String, i32)> {
+	match action {
+		AutomationAction::Action { action, input } => {
+			let method = format!("action:{}.input", action);
+			let handler = LIBRARY_ACTIONS
+				.get(method.as_str())
+				.ok_or_else(|| (format!("Unknown library action '{}'", action), 1))?;
+			let session = SessionContext::device_session(
+				crate::device::get_current_device_id(),
+				crate::device::get_current_device_slug(),
+			)
+			.with_library(library.id());
+			handler(context.clone(), session, input.clone())
+				.await
+				.map(|_| None)
+				.map_err(|e| (e, 1))
+		}
+		AutomationAction::Job { job_name, args } => library
+			.jobs()
+			.dispatch_by_name(job_name, args.clone())
+			.await
+			.map(|handle| Some(handle.id()))
+			.map_err(|e| (e.to_string(), 1)),
+		AutomationAction::ApplyTag { tag_id } => apply_tag(context, library, rule, *tag_id, event)
+			.await
+			.map(|_| None)
+			.map_err(|e| (e, 1)),
+		AutomationAction::Webhook {
+			url,
+			headers,
+			max_attempts,
+			allow_remote,
+		} => {
+			// Rendered templates can point anywhere, so check the final URL
+			let url = webhook_url(url, *allow_remote).map_err(|e| (e, 0))?;
+			let payload = json!({
+				"rule_id": rule.uuid,
+				"rule_name": rule.name,
+				"library_id": library.id(),
+				"fired_at": Utc::now(),
+				"event": event,
+			});
+			post_webhook(url, headers, &payload, *max_attempts)
+				.await
+				.map(|_| None)
+		}
+	}
+}
+
+async fn apply_tag(
+	context: &Arc<CoreContext>,
+	library: &Arc<Library>,
+	rule: &automation_rule::Model,
+	tag_id: Uuid,
+	event: &Value,
+) -> Result<(), String> {
+	let entry_uuids: Vec<Uuid> = match (&event["data"]["entry_id"], &event["data"]["added"]) {
+		(Value::String(id), _) => vec![id.as_str()],
+		(_, Value::Array(ids)) => ids.iter().filter_map(Value::as_str).collect(),
+		_ => Vec::new(),
+	}
+	.into_iter()
+	.filter_map(|id| Uuid::parse_str(id).ok())
+	.collect();
+	if entry_uuids.is_empty() {
+		return Err("Event has no entries to tag".to_string());
+	}
+
+	let entry_ids: Vec<i32> = entry::Entity::find()
+		.filter(entry::Column::Uuid.is_in(entry_uuids))
+		.select_only()
+		.column(entry::Column::Id)
+		.into_tuple()
+		.all(library.db().conn())
+		.await
+		.map_err(|e| e.to_string())?;
+	if entry_ids.is_empty() {
+		return Err("The event's entries are no longer indexed".to_string());
+	}
+
+	let action = ApplyTagsAction::from_input(ApplyTagsInput {
+		targets: TagTargets::Entry(entry_ids),
+		tag_ids: vec![tag_id],
+		source: Some(TagSource::Rule),
+		confidence: Some(1.0),
+		applied_context: Some(rule.uuid.to_string()),
+		instance_attributes: None,
+	})?;
+	context
+		.get_action_manager()
+		.await
+		.ok_or_else(|| "Action manager not available".to_string())?
+		.dispatch_library(Some(library.id()), action)
+		.await
+		.map(|_| ())
+		.map_err(|e| e.to_string())
+}
+
+/// POST the payload, retrying with exponential backoff (1s, 2s, 4s, … capped at 30s)
+async fn post_webhook(
+	url: reqwest::Url,
+	headers: &std::collections::HashMap<String, String>,
+	payload: &Value,
+	max_attempts: u32,
+) -> Result<(), (String, i32)> {
+	let client = reqwest::Client::builder()
+		.timeout(WEBHOOK_TIMEOUT)
+		.build()
+		.map_err(|e| (e.to_string(), 0))?;
+
+	let mut last_error = String::new();
+	for attempt in 1..=max_attempts.max(1) {
+		let mut request = client.post(url.clone()).json(payload);
+		for (name, value) in headers {
+			request = request.header(name, value);
+		}
+
+		match request.send().await {
+			Ok(response) if response.status().is_success() => return Ok(()),
+			Ok(response) => last_error = format!("Webhook returned {}", response.status()),
+			Err(e) => last_error = format!("Webhook request failed: {}", e),
+		}
+
+		if attempt < max_attempts {
+			let delay =
+				Duration::from_secs(1u64 << (attempt - 1).min(5)).min(Duration::from_secs(30));
+			debug!(
+				url = %url,
+				attempt, "Webhook delivery failed, retrying in {:?}", delay
+			);
+			tokio::time::sleep(delay).await;
+		}
+	}
+
+	Err((last_error, max_attempts as i32))
+}
+
+async fn record_dead_letter(
+	db: &DatabaseConnection,
+	rule: &automation_rule::Model,
+	event: &Value,
+	action: &AutomationAction,
+	error: &str,
+	attempts: i32,
+) -> Result<(), DbErr> {
+	automation_dead_letter::ActiveModel {
+		uuid: Set(Uuid::new_v4()),
+		rule_uuid: Set(rule.uuid),
+		rule_name: Set(rule.name.clone()),
+		event: Set(event.clone()),
+		action: Set(serde_json::to_value(action.clone().redacted()).unwrap_or(Value::Null)),
+		error: Set(error.to_string()),
+		attempts: Set(attempts),
+		created_at: Set(Utc::now()),
+		..Default::default()
+	}
+	.insert(db)
+	.await?;
+
+	// Keep the log bounded
+	let stale: Vec<i32> = automation_dead_letter::Entity::find()
+		.select_only()
+		.column(automation_dead_letter::Column::Id)
+		.order_by_desc(automation_dead_letter::Column::CreatedAt)
+		.offset(MAX_DEAD_LETTERS)
+		.into_tuple()
+		.all(db)
+		.await?;
+	if !stale.is_empty() {
+		automation_dead_letter::Entity::delete_many()
+			.filter(automation_dead_letter::Column::Id.is_in(stale))
+			.exec(db)
+			.await?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn context() -> Value {
+		json!({
+			"type": "JobFailed",
+			"library_id": null,
+			"data": { "job_id": "abc", "job_type": "indexer", "error": "disk full", "added": ["x", "y"] },
+			"volume": { "name": "EOS_DIGITAL", "mount_point": "/media/EOS_DIGITAL", "total_capacity": 64 },
+		})
+	}
+
+	#[test]
+	fn matches_field_filters() {
+		let ctx = context();
+		assert!(matches_filters(
+			&[
+				EventFieldFilter::Equals {
+					field: "data.job_type".to_string(),
+					value: json!("indexer"),
+				},
+				EventFieldFilter::Glob {
+					field: "volume.name".to_string(),
+					pattern: "EOS_*".to_string(),
+				},
+				EventFieldFilter::Contains {
+					field: "data.added".to_string(),
+					value: json!("y"),
+				},
+				EventFieldFilter::Exists {
+					field: "data.error".to_string(),
+				},
+			],
+			&ctx
+		));
+		assert!(!matches_filters(
+			&[EventFieldFilter::NotEquals {
+				field: "data.job_type".to_string(),
+				value: json!("indexer"),
+			}],
+			&ctx
+		));
+		assert!(!matches_filters(
+			&[EventFieldFilter::Exists {
+				field: "data.missing".to_string(),
+			}],
+			&ctx
+		));
+	}
+
+	#[test]
+	fn renders_templates() {
+		let ctx = context();
+		assert_eq!(
+			render(
+				&json!({
+					"path": "{{volume.mount_point}}/DCIM",
+					"size": "{{volume.total_capacity}}",
+					"first": "{{ data.added.0 }}",
+					"plain": true,
+				}),
+				&ctx
+			)
+			.unwrap(),
+			json!({
+				"path": "/media/EOS_DIGITAL/DCIM",
+				"size": 64,
+				"first": "x",
+				"plain": true,
+			})
+		);
+		assert!(render(&json!("{{data.nope}}"), &ctx).is_err());
+	}
+}
@@ -0,0 +1,9 @@
+//! List automation rules
+
+pub mod input;
+pub mod output;
+pub mod query;
+
+pub use input::ListAutomationRulesInput;
+pub use output::{AutomationRuleInfo, ListAutomationRulesOutput};
+pub use query::ListAutomationRulesQuery;
@@ -0,0 +1,45 @@
+//! Output for listing automation rules
+
+use crate::{
+	infra::db::entities::automation_rule,
+	ops::automation::rule::{AutomationAction, EventFieldFilter},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+/// A rule as shown to clients. Webhook header values are redacted.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AutomationRuleInfo {
+	pub id: Uuid,
+	pub name: String,
+	pub event_type: String,
+	pub filters: Vec<EventFieldFilter>,
+	pub action: AutomationAction,
+	pub enabled: bool,
+	pub cooldown_seconds: u32,
+	pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<automation_rule::Model> for AutomationRuleInfo {
+	type Error = serde_json::Error;
+
+	fn try_from(model: automation_rule::Model) -> Result<Self, Self::Error> {
+		Ok(Self {
+			id: model.uuid,
+			name: model.name,
+			event_type: model.event_type,
+			filters: serde_json::from_value(model.filters)?,
+			action: serde_json::from_value::<AutomationAction>(model.action)?.redacted(),
+			enabled: model.enabled,
+			cooldown_seconds: model.cooldown_secs.max(0) as u32,
+			created_at: model.created_at,
+		})
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListAutomationRulesOutput {
+	pub rules: Vec<AutomationRuleInfo>,
+}
@@ -0,0 +1,57 @@
+//! List automation rules query
+
+use super::{
+	input::ListAutomationRulesInput,
+	output::{AutomationRuleInfo, ListAutomationRulesOutput},
+};
+use crate::{
+	context::CoreContext,
+	infra::{
+		db::entities::automation_rule,
+		query::{LibraryQuery, QueryError, QueryResult},
+	},
+};
+use sea_orm::{EntityTrait, QueryOrder};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListAutomationRulesQuery {
+	pub input: ListAutomationRulesInput,
+}
+
+impl LibraryQuery for ListAutomationRulesQuery {
+	type Input = ListAutomationRulesInput;
+	type Output = ListAutomationRulesOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library in session".to_string()))?;
+		let library = context
+			.get_library(library_id)
+			.await
+			.ok_or(QueryError::LibraryNotFound(library_id))?;
+
+		let rules = automation_rule::Entity::find()
+			.order_by_asc(automation_rule::Column::CreatedAt)
+			.all(library.db().conn())
+			.await?
+			.into_iter()
+			.map(AutomationRuleInfo::try_from)
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(ListAutomationRulesOutput { rules })
+	}
+}
+
+crate::register_library_query!(ListAutomationRulesQuery, "automation.rules.list");
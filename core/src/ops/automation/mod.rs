@@ -0,0 +1,34 @@
+//! Event automation
+//!
+//! Device-local rules of the form "when an event matching these filters is emitted, run
+//! this action". Actions dispatch a registered library action or job, tag the event's
+//! entries, or POST the event to a webhook. Webhooks only reach this machine unless a
+//! rule allows remote hosts, and are retried with backoff; actions that still fail land
+//! in a per-library dead-letter log.
+//! `service::automation` listens to the event bus and fires the rules.
+
+pub mod create;
+pub mod dead_letters;
+pub mod delete;
+pub mod engine;
+pub mod list;
+pub mod rule;
+
+pub use create::{
+	CreateAutomationRuleAction, CreateAutomationRuleInput, CreateAutomationRuleOutput,
+};
+pub use dead_letters::{
+	AutomationDeadLetterInfo, ListAutomationDeadLettersInput, ListAutomationDeadLettersOutput,
+	ListAutomationDeadLettersQuery,
+};
+pub use delete::{
+	DeleteAutomationRuleAction, DeleteAutomationRuleInput, DeleteAutomationRuleOutput,
+};
+pub use list::{
+	AutomationRuleInfo, ListAutomationRulesInput, ListAutomationRulesOutput,
+	ListAutomationRulesQuery,
+};
+pub use rule::{AutomationAction, EventFieldFilter};
+
+/// Resource type used in `ResourceChanged`/`ResourceDeleted` events for rules
+pub const AUTOMATION_RULE_RESOURCE: &str = "automation_rule";
@@ -0,0 +1,187 @@
+//! Automation rule definitions: event field filters and the actions a rule runs
+
+use crate::{
+	infra::{job::registry::REGISTRY, wire::registry::LIBRARY_ACTIONS},
+	ops::core::events::{ALL_EVENTS, NOISY_EVENTS},
+};
+use globset::Glob;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use std::{collections::HashMap, net::IpAddr};
+use uuid::Uuid;
+
+/// A condition on one field of the event context.
+///
+/// `field` is a dot path into the context built for each event, e.g. `data.job_type`,
+/// `data.is_mounted` or `volume.name` (see [`super::engine::event_context`]).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum EventFieldFilter {
+	/// Field equals the JSON value exactly
+	Equals { field: String, value: Value },
+	/// Field is missing or differs from the JSON value
+	NotEquals { field: String, value: Value },
+	/// String field contains the substring, or array field contains the value
+	Contains { field: String, value: Value },
+	/// String field matches the glob pattern
+	Glob { field: String, pattern: String },
+	/// Field is present and not null
+	Exists { field: String },
+}
+
+impl EventFieldFilter {
+	pub fn field(&self) -> &str {
+		match self {
+			Self::Equals { field, .. }
+			| Self::NotEquals { field, .. }
+			| Self::Contains { field, .. }
+			| Self::Glob { field, .. }
+			| Self::Exists { field } => field,
+		}
+	}
+}
+
+/// What a rule does when it fires.
+///
+/// String values in `input`, `args` and `url` may contain `{{path}}` templates, which are
+/// replaced by fields of the event context. A string that is exactly one template is
+/// replaced by the field's JSON value, so `"{{data.entry_id}}"` stays a UUID string and
+/// `"{{data.count}}"` becomes a number.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AutomationAction {
+	/// Dispatch a registered library action, e.g. `files.copy`, with its JSON input
+	Action { action: String, input: Value },
+	/// Dispatch a registered job by name with JSON args
+	Job { job_name: String, args: Value },
+	/// Tag the entries the event refers to (`data.entry_id` or `data.added`)
+	ApplyTag { tag_id: Uuid },
+	/// POST a JSON payload describing the event to a URL
+	Webhook {
+		url: String,
+		/// Sent with every delivery; redacted when rules are listed
+		#[serde(default)]
+		headers: HashMap<String, String>,
+		/// Delivery attempts before the payload goes to the dead-letter log
+		#[serde(default = "default_webhook_attempts")]
+		max_attempts: u32,
+		/// Allow URLs on other hosts. Without it webhooks only reach this machine, so
+		/// library events don't leave the device unless a rule asks for it.
+		#[serde(default)]
+		allow_remote: bool,
+	},
+}
+
+fn default_webhook_attempts() -> u32 {
+	3
+}
+
+/// Stands in for webhook header values when rules are returned to clients
+pub const REDACTED: &str = "[redacted]";
+
+impl AutomationAction {
+	pub fn kind(&self) -> &'static str {
+		match self {
+			Self::Action { .. } => "action",
+			Self::Job { .. } => "job",
+			Self::ApplyTag { .. } => "apply_tag",
+			Self::Webhook { .. } => "webhook",
+		}
+	}
+
+	/// The action with webhook header values, which often carry credentials, hidden
+	pub fn redacted(mut self) -> Self {
+		if let Self::Webhook { headers, .. } = &mut self {
+			for value in headers.values_mut() {
+				*value = REDACTED.to_string();
+			}
+		}
+		self
+	}
+}
+
+/// Parse a rendered webhook URL, rejecting non-HTTP schemes and, unless `allow_remote`
+/// is set, hosts other than this machine
+pub fn webhook_url(url: &str, allow_remote: bool) -> Result<reqwest::Url, String> {
+	let parsed =
+		reqwest::Url::parse(url).map_err(|e| format!("Invalid webhook URL '{}': {}", url, e))?;
+	if !matches!(parsed.scheme(), "http" | "https") {
+		return Err("webhook URL must be http or https".to_string());
+	}
+
+	if !allow_remote {
+		let host = parsed.host_str().unwrap_or_default();
+		let loopback = host.eq_ignore_ascii_case("localhost")
+			|| host
+				.trim_start_matches('[')
+				.trim_end_matches(']')
+				.parse::<IpAddr>()
+				.is_ok_and(|ip| ip.is_loopback());
+		if !loopback {
+			return Err(format!(
+				"webhook host '{}' is not this machine; set allow_remote to deliver to it",
+				host
+			));
+		}
+	}
+
+	Ok(parsed)
+}
+
+/// Check a rule's trigger, filters and action before it's stored
+pub fn validate_rule(
+	event_type: &str,
+	filters: &[EventFieldFilter],
+	action: &AutomationAction,
+) -> Result<(), String> {
+	if !ALL_EVENTS.contains(&event_type) {
+		return Err(format!("Unknown event type '{}'", event_type));
+	}
+	if NOISY_EVENTS.contains(&event_type) {
+		return Err(format!(
+			"'{}' is emitted too often to trigger automations",
+			event_type
+		));
+	}
+
+	for filter in filters {
+		if filter.field().trim().is_empty() {
+			return Err("filter field cannot be empty".to_string());
+		}
+		if let EventFieldFilter::Glob { pattern, .. } = filter {
+			Glob::new(pattern).map_err(|e| format!("Invalid glob '{}': {}", pattern, e))?;
+		}
+	}
+
+	match action {
+		AutomationAction::Action { action, .. } => {
+			let method = format!("action:{}.input", action);
+			if !LIBRARY_ACTIONS.contains_key(method.as_str()) {
+				return Err(format!("Unknown library action '{}'", action));
+			}
+		}
+		AutomationAction::Job { job_name, .. } => {
+			if !REGISTRY.has_job(job_name) {
+				return Err(format!("Job type '{}' not found", job_name));
+			}
+		}
+		AutomationAction::ApplyTag { .. } => {}
+		AutomationAction::Webhook {
+			url,
+			max_attempts,
+			allow_remote,
+			..
+		} => {
+			// Templated URLs can only be checked once rendered
+			if !url.contains("{{") {
+				webhook_url(url, *allow_remote)?;
+			}
+			if *max_attempts == 0 {
+				return Err("max_attempts must be at least 1".to_string());
+			}
+		}
+	}
+
+	Ok(())
+}
@@ -6,7 +6,10 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 
 use crate::{
-	config::{AppConfig, JobLoggingConfig, LoggingConfig, Preferences, ServiceConfig},
+	config::{
+		app_config::FileTransferConfig, AppConfig, JobLoggingConfig, LoggingConfig, Preferences,
+		ServiceConfig,
+	},
 	context::CoreContext,
 	infra::query::{CoreQuery, QueryError, QueryResult},
 };
@@ -44,6 +47,9 @@ pub struct AppConfigOutput {
 
 	/// Proxy pairing configuration
 	pub proxy_pairing: ProxyPairingConfigOutput,
+
+	/// Peer file transfer configuration
+	pub file_transfer: FileTransferConfig,
 }
 
 /// User preferences output
@@ -122,6 +128,7 @@ impl From<&AppConfig> for AppConfigOutput {
 				vouch_response_timeout: config.proxy_pairing.vouch_response_timeout,
 				vouch_queue_retry_limit: config.proxy_pairing.vouch_queue_retry_limit,
 			},
+			file_transfer: config.file_transfer.clone(),
 		}
 	}
 }
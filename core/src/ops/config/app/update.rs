@@ -7,7 +7,7 @@ use specta::Type;
 use tracing::info;
 
 use crate::{
-	config::AppConfig,
+	config::{app_config::FileTransferConfig, AppConfig},
 	context::CoreContext,
 	infra::action::{error::ActionError, CoreAction, ValidationResult},
 };
@@ -75,6 +75,10 @@ pub struct UpdateAppConfigInput {
 	/// Maximum retries for queued vouches
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub proxy_pairing_vouch_queue_retry_limit: Option<u32>,
+
+	/// Peer file transfer compression and bandwidth limits (replaces the current settings)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub file_transfer: Option<FileTransferConfig>,
 }
 
 /// Output for update app configuration action
@@ -174,6 +178,16 @@ impl CoreAction for UpdateAppConfigAction {
 			}
 		}
 
+		if let Some(ref file_transfer) = self.input.file_transfer {
+			file_transfer
+				.bandwidth
+				.validate()
+				.map_err(|message| ActionError::Validation {
+					field: "file_transfer".to_string(),
+					message,
+				})?;
+		}
+
 		Ok(ValidationResult::Success { metadata: None })
 	}
 
@@ -295,6 +309,13 @@ impl CoreAction for UpdateAppConfigAction {
 			}
 		}
 
+		if let Some(ref file_transfer) = self.input.file_transfer {
+			if config.file_transfer != *file_transfer {
+				config.file_transfer = file_transfer.clone();
+				changes.push("file_transfer");
+			}
+		}
+
 		if changes.is_empty() {
 			return Ok(UpdateAppConfigOutput {
 				success: true,
@@ -318,6 +339,15 @@ impl CoreAction for UpdateAppConfigAction {
 					pairing.set_proxy_config(config.proxy_pairing.clone()).await;
 				}
 			}
+			if let Some(handler) = guard.get_handler("file_transfer") {
+				if let Some(file_transfer) =
+					handler
+						.as_any()
+						.downcast_ref::<crate::service::network::protocol::FileTransferProtocolHandler>(
+						) {
+					file_transfer.set_transfer_config(config.file_transfer.clone());
+				}
+			}
 		}
 
 		// Emit config change events for each changed field
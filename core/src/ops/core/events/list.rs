@@ -3,6 +3,77 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::sync::Arc;
 
+/// All event types that can be emitted.
+/// This list should match the Event enum in core/src/infra/event/mod.rs
+pub const ALL_EVENTS: &[&str] = &[
+	// Core lifecycle
+	"CoreStarted",
+	"CoreShutdown",
+	// Library events
+	"LibraryCreated",
+	"LibraryOpened",
+	"LibraryClosed",
+	"LibraryDeleted",
+	"LibraryStatisticsUpdated",
+	// Entry events
+	"EntryCreated",
+	"EntryModified",
+	"EntryDeleted",
+	"EntryMoved",
+	// Raw filesystem changes
+	"FsRawChange",
+	// Volume events
+	"VolumeAdded",
+	"VolumeRemoved",
+	"VolumeUpdated",
+	"VolumeSpeedTested",
+	"VolumeMountChanged",
+	"VolumeError",
+	// Job events
+	"JobQueued",
+	"JobStarted",
+	"JobProgress",
+	"JobCompleted",
+	"JobFailed",
+	"JobCancelled",
+	"JobPaused",
+	"JobResumed",
+	// Indexing events
+	"IndexingStarted",
+	"IndexingProgress",
+	"IndexingCompleted",
+	"IndexingFailed",
+	// Device events
+	"DeviceConnected",
+	"DeviceDisconnected",
+	// Resource events
+	"ResourceChanged",
+	"ResourceDeleted",
+	// Saved search events
+	"SavedSearchMembershipChanged",
+	// Legacy compatibility
+	"LocationAdded",
+	"LocationRemoved",
+	"FilesIndexed",
+	"ThumbnailsGenerated",
+	"FileOperationCompleted",
+	"FilesModified",
+	// Extension events
+	"ExtensionNotification",
+	"ExtensionSuspended",
+	// Log events
+	"LogMessage",
+	// Custom events
+	"Custom",
+];
+
+/// High-frequency events that are excluded by default
+pub const NOISY_EVENTS: &[&str] = &[
+	"LogMessage",       // Every log becomes an event
+	"JobProgress",      // Sent frequently during job execution
+	"IndexingProgress", // Sent frequently during indexing
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct ListEventsInput {}
 
@@ -41,77 +112,8 @@ impl CoreQuery for ListEventsQuery {
 		_context: Arc<crate::context::CoreContext>,
 		_session: crate::infra::api::SessionContext,
 	) -> QueryResult<Self::Output> {
-		// Define all available event types
-		// This list should match the Event enum in core/src/infra/event/mod.rs
-		let all_events = vec![
-			// Core lifecycle
-			"CoreStarted",
-			"CoreShutdown",
-			// Library events
-			"LibraryCreated",
-			"LibraryOpened",
-			"LibraryClosed",
-			"LibraryDeleted",
-			"LibraryStatisticsUpdated",
-			// Entry events
-			"EntryCreated",
-			"EntryModified",
-			"EntryDeleted",
-			"EntryMoved",
-			// Raw filesystem changes
-			"FsRawChange",
-			// Volume events
-			"VolumeAdded",
-			"VolumeRemoved",
-			"VolumeUpdated",
-			"VolumeSpeedTested",
-			"VolumeMountChanged",
-			"VolumeError",
-			// Job events
-			"JobQueued",
-			"JobStarted",
-			"JobProgress",
-			"JobCompleted",
-			"JobFailed",
-			"JobCancelled",
-			"JobPaused",
-			"JobResumed",
-			// Indexing events
-			"IndexingStarted",
-			"IndexingProgress",
-			"IndexingCompleted",
-			"IndexingFailed",
-			// Device events
-			"DeviceConnected",
-			"DeviceDisconnected",
-			// Resource events
-			"ResourceChanged",
-			"ResourceDeleted",
-			// Legacy compatibility
-			"LocationAdded",
-			"LocationRemoved",
-			"FilesIndexed",
-			"ThumbnailsGenerated",
-			"FileOperationCompleted",
-			"FilesModified",
-			// Log events
-			"LogMessage",
-			// Custom events
-			"Custom",
-		]
-		.into_iter()
-		.map(String::from)
-		.collect();
-
-		// Define noisy events (high-frequency, excluded by default)
-		let noisy_events = vec![
-			"LogMessage",       // Every log becomes an event
-			"JobProgress",      // Sent frequently during job execution
-			"IndexingProgress", // Sent frequently during indexing
-		]
-		.into_iter()
-		.map(String::from)
-		.collect();
+		let all_events = ALL_EVENTS.iter().map(|e| e.to_string()).collect();
+		let noisy_events = NOISY_EVENTS.iter().map(|e| e.to_string()).collect();
 
 		// Provide detailed information for each event
 		let event_info = vec![
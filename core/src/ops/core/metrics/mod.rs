@@ -0,0 +1,10 @@
+//! Core metrics query
+//!
+//! Collects a point-in-time snapshot of job, indexing, sync, volume and connection
+//! state for monitoring. The server app renders it in OpenMetrics format at `/metrics`.
+
+pub mod output;
+pub mod query;
+
+pub use output::*;
+pub use query::*;
@@ -0,0 +1,65 @@
+//! Core metrics output types
+
+use crate::{infra::job::types::JobStatus, ops::indexing::metrics::IndexingTotalsSnapshot};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Point-in-time snapshot of everything exported by `/metrics`
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CoreMetrics {
+	pub collected_at: DateTime<Utc>,
+	/// Job counts grouped by library, job type and status
+	pub jobs: Vec<JobCountMetric>,
+	pub libraries: Vec<LibraryMetrics>,
+	pub volumes: Vec<VolumeMetrics>,
+	pub connections: ConnectionMetrics,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct JobCountMetric {
+	pub library_id: Uuid,
+	pub job_type: String,
+	pub status: JobStatus,
+	pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct LibraryMetrics {
+	pub library_id: Uuid,
+	pub name: String,
+	/// Totals of indexer jobs completed since the daemon started
+	pub indexing: IndexingTotalsSnapshot,
+	/// Empty when the library's sync service isn't running
+	pub sync_peers: Vec<SyncPeerMetrics>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SyncPeerMetrics {
+	pub peer_id: Uuid,
+	/// How far this device's watermark trails the peer's
+	pub watermark_lag_ms: Option<u64>,
+	pub last_sync_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VolumeMetrics {
+	pub fingerprint: String,
+	pub name: String,
+	pub mount_point: PathBuf,
+	pub is_mounted: bool,
+	pub total_capacity: u64,
+	pub available_space: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct ConnectionMetrics {
+	/// Subscribers on the core event bus, including daemon event streams
+	pub event_bus_subscribers: u64,
+	/// Open daemon RPC connections. Only known when collected through the daemon.
+	pub rpc_connections: Option<u64>,
+	/// RPC connections currently streaming events
+	pub rpc_event_streams: Option<u64>,
+}
@@ -0,0 +1,124 @@
+//! Core metrics query
+
+use super::output::*;
+use crate::{
+	context::CoreContext,
+	infra::query::{CoreQuery, QueryError, QueryResult},
+	service::sync::metrics::snapshot::SyncMetricsSnapshot,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	sync::Arc,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CoreMetricsQuery;
+
+impl CoreQuery for CoreMetricsQuery {
+	type Input = ();
+	type Output = CoreMetrics;
+
+	fn from_input(_input: Self::Input) -> QueryResult<Self> {
+		Ok(Self)
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		_session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		collect_metrics(&context).await
+	}
+}
+
+crate::register_core_query!(CoreMetricsQuery, "core.metrics");
+
+/// Collect a metrics snapshot from every open library and the volume manager
+///
+/// RPC connection counts are left empty; the daemon fills them in when it answers a
+/// metrics request.
+pub async fn collect_metrics(context: &Arc<CoreContext>) -> QueryResult<CoreMetrics> {
+	let mut jobs = Vec::new();
+	let mut libraries = Vec::new();
+
+	for library in context.libraries().await.list().await {
+		let library_id = library.id();
+
+		let mut counts: BTreeMap<(String, String), JobCountMetric> = BTreeMap::new();
+		let job_infos = library
+			.jobs()
+			.list_jobs(None)
+			.await
+			.map_err(|e| QueryError::Internal(e.to_string()))?;
+		for job in job_infos {
+			counts
+				.entry((job.name.clone(), job.status.to_string()))
+				.or_insert_with(|| JobCountMetric {
+					library_id,
+					job_type: job.name,
+					status: job.status,
+					count: 0,
+				})
+				.count += 1;
+		}
+		jobs.extend(counts.into_values());
+
+		let sync_peers = match library.sync_service() {
+			Some(sync_service) => {
+				let snapshot =
+					SyncMetricsSnapshot::from_metrics(sync_service.metrics().metrics()).await;
+				let lag = &snapshot.performance.watermark_lag_ms;
+				let last_sync = &snapshot.data_volume.last_sync_per_peer;
+
+				lag.keys()
+					.chain(last_sync.keys())
+					.copied()
+					.collect::<BTreeSet<_>>()
+					.into_iter()
+					.map(|peer_id| SyncPeerMetrics {
+						peer_id,
+						watermark_lag_ms: lag.get(&peer_id).copied(),
+						last_sync_at: last_sync.get(&peer_id).copied(),
+					})
+					.collect()
+			}
+			None => Vec::new(),
+		};
+
+		libraries.push(LibraryMetrics {
+			library_id,
+			name: library.name().await,
+			indexing: library.indexing_totals().snapshot(),
+			sync_peers,
+		});
+	}
+
+	let volumes = context
+		.volume_manager
+		.get_all_volumes()
+		.await
+		.into_iter()
+		.map(|volume| VolumeMetrics {
+			fingerprint: volume.fingerprint.to_string(),
+			name: volume.name,
+			mount_point: volume.mount_point,
+			is_mounted: volume.is_mounted,
+			total_capacity: volume.total_capacity,
+			available_space: volume.available_space,
+		})
+		.collect();
+
+	Ok(CoreMetrics {
+		collected_at: Utc::now(),
+		jobs,
+		libraries,
+		volumes,
+		connections: ConnectionMetrics {
+			event_bus_subscribers: context.events.subscriber_count() as u64,
+			..Default::default()
+		},
+	})
+}
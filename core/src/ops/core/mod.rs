@@ -1,4 +1,5 @@
 pub mod ephemeral_status;
 pub mod events;
+pub mod metrics;
 pub mod reset;
 pub mod status;
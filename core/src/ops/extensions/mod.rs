@@ -0,0 +1,7 @@
+//! WASM extension operations
+
+mod resume;
+mod usage;
+
+pub use resume::*;
+pub use usage::*;
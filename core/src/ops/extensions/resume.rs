@@ -0,0 +1,51 @@
+//! Resume a suspended extension
+//!
+//! Extensions that exceed a resource limit are suspended and not called again until
+//! they're resumed here.
+
+use crate::{
+	context::CoreContext,
+	infra::action::{error::ActionError, CoreAction},
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ExtensionResumeInput {
+	pub extension_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ExtensionResumeOutput {
+	/// Whether the extension was suspended
+	pub resumed: bool,
+}
+
+pub struct ExtensionResumeAction {
+	input: ExtensionResumeInput,
+}
+
+impl CoreAction for ExtensionResumeAction {
+	type Input = ExtensionResumeInput;
+	type Output = ExtensionResumeOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self { input })
+	}
+
+	async fn execute(self, context: Arc<CoreContext>) -> Result<Self::Output, ActionError> {
+		let Some(pm) = context.get_plugin_manager().await else {
+			return Ok(ExtensionResumeOutput { resumed: false });
+		};
+
+		let resumed = pm.read().await.resume_plugin(&self.input.extension_id);
+		Ok(ExtensionResumeOutput { resumed })
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"extensions.resume"
+	}
+}
+
+crate::register_core_action!(ExtensionResumeAction, "extensions.resume");
@@ -0,0 +1,67 @@
+//! Extension resource usage query
+//!
+//! Reports fuel, memory and job usage against each loaded extension's limits, and
+//! whether it has been suspended for exceeding one.
+
+use crate::{
+	context::CoreContext,
+	infra::{
+		extension::ExtensionResourceUsage,
+		query::{CoreQuery, QueryResult},
+	},
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ExtensionUsageInput {
+	/// Only report this extension
+	#[serde(default)]
+	pub extension_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ExtensionUsageOutput {
+	pub extensions: Vec<ExtensionResourceUsage>,
+}
+
+pub struct ExtensionUsageQuery {
+	input: ExtensionUsageInput,
+}
+
+impl CoreQuery for ExtensionUsageQuery {
+	type Input = ExtensionUsageInput;
+	type Output = ExtensionUsageOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		_session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let Some(pm) = context.get_plugin_manager().await else {
+			return Ok(ExtensionUsageOutput { extensions: vec![] });
+		};
+
+		let extensions = pm
+			.read()
+			.await
+			.resource_usage()
+			.into_iter()
+			.filter(|usage| {
+				self.input
+					.extension_id
+					.as_ref()
+					.map_or(true, |id| &usage.extension_id == id)
+			})
+			.collect();
+
+		Ok(ExtensionUsageOutput { extensions })
+	}
+}
+
+crate::register_core_query!(ExtensionUsageQuery, "extensions.usage");
@@ -1,4 +1,3 @@
-// File sync operations module
+//! File sync operations
 
-// Placeholder for future API actions and queries
-// These will be implemented once the core service is tested
+pub mod versions;
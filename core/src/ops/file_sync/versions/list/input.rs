@@ -0,0 +1,15 @@
+//! Input types for list_file_versions query
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListFileVersionsInput {
+	/// Backup conduit whose versions to list
+	pub conduit_id: Uuid,
+
+	/// Only versions of this file, relative to the conduit's source
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub path: Option<String>,
+}
@@ -0,0 +1,9 @@
+//! List file versions operation
+
+pub mod input;
+pub mod output;
+pub mod query;
+
+pub use input::ListFileVersionsInput;
+pub use output::{FileVersionInfo, ListFileVersionsOutput};
+pub use query::ListFileVersions;
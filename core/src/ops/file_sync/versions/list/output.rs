@@ -0,0 +1,27 @@
+//! Output types for list_file_versions query
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListFileVersionsOutput {
+	/// Newest first
+	pub versions: Vec<FileVersionInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FileVersionInfo {
+	pub id: Uuid,
+	/// Path of the file relative to the conduit's source and target
+	pub relative_path: String,
+	/// "modified" or "deleted"
+	pub reason: String,
+	pub size: i64,
+	/// When the version was last modified before it was kept
+	pub modified_at: Option<DateTime<Utc>>,
+	pub archived_at: DateTime<Utc>,
+	/// Sync generation that kept the version
+	pub generation: Option<i64>,
+}
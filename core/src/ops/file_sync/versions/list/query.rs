@@ -0,0 +1,76 @@
+//! List file versions query
+
+use super::{FileVersionInfo, ListFileVersionsInput, ListFileVersionsOutput};
+use crate::{
+	context::CoreContext,
+	infra::{
+		db::entities::sync_conduit,
+		query::{LibraryQuery, QueryError, QueryResult},
+	},
+	service::file_sync::versions::VersionStore,
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use std::sync::Arc;
+
+/// List the prior file versions a backup conduit kept
+pub struct ListFileVersions {
+	pub input: ListFileVersionsInput,
+}
+
+impl LibraryQuery for ListFileVersions {
+	type Input = ListFileVersionsInput;
+	type Output = ListFileVersionsOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library in session".to_string()))?;
+
+		let library = context
+			.libraries()
+			.await
+			.get_library(library_id)
+			.await
+			.ok_or_else(|| QueryError::LibraryNotFound(library_id))?;
+
+		let db = library.db().conn();
+		let conduit = sync_conduit::Entity::find()
+			.filter(sync_conduit::Column::Uuid.eq(self.input.conduit_id))
+			.one(db)
+			.await?
+			.ok_or_else(|| {
+				QueryError::Internal(format!("Conduit {} not found", self.input.conduit_id))
+			})?;
+
+		let versions = VersionStore::new(Arc::new(db.clone()))
+			.list(conduit.id, self.input.path.as_deref())
+			.await
+			.map_err(|e| QueryError::Internal(format!("Failed to list versions: {}", e)))?;
+
+		Ok(ListFileVersionsOutput {
+			versions: versions
+				.into_iter()
+				.map(|(version, generation)| FileVersionInfo {
+					id: version.uuid,
+					relative_path: version.relative_path,
+					reason: version.reason,
+					size: version.size,
+					modified_at: version.modified_at,
+					archived_at: version.archived_at,
+					generation: generation.map(|g| g.generation),
+				})
+				.collect(),
+		})
+	}
+}
+
+// Register the query
+crate::register_library_query!(ListFileVersions, "file_sync.versions.list");
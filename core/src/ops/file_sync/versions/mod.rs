@@ -0,0 +1,4 @@
+//! Prior file versions kept by backup conduits
+
+pub mod list;
+pub mod restore;
@@ -0,0 +1,72 @@
+//! Restore file version action
+
+use super::{input::RestoreFileVersionInput, output::RestoreFileVersionOutput};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::{error::ActionError, LibraryAction},
+		db::entities::file_version,
+	},
+	library::Library,
+	service::file_sync::versions::VersionStore,
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Copy a prior file version kept by a backup conduit back out
+///
+/// The version stays kept, so the same version can be restored more than once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreFileVersionAction {
+	input: RestoreFileVersionInput,
+}
+
+impl RestoreFileVersionAction {
+	pub fn new(input: RestoreFileVersionInput) -> Self {
+		Self { input }
+	}
+}
+
+impl LibraryAction for RestoreFileVersionAction {
+	type Input = RestoreFileVersionInput;
+	type Output = RestoreFileVersionOutput;
+
+	fn from_input(input: RestoreFileVersionInput) -> Result<Self, String> {
+		if let Some(destination) = &input.destination {
+			if !destination.is_absolute() {
+				return Err("destination must be an absolute path".to_string());
+			}
+		}
+		Ok(RestoreFileVersionAction::new(input))
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let db = library.db().conn();
+		let version = file_version::Entity::find()
+			.filter(file_version::Column::Uuid.eq(self.input.version_id))
+			.one(db)
+			.await?
+			.ok_or_else(|| ActionError::Validation {
+				field: "version_id".to_string(),
+				message: format!("File version {} not found", self.input.version_id),
+			})?;
+
+		let restored_path = VersionStore::new(Arc::new(db.clone()))
+			.restore(&version, self.input.destination, self.input.overwrite)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to restore version: {}", e)))?;
+
+		Ok(RestoreFileVersionOutput { restored_path })
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"file_sync.versions.restore"
+	}
+}
+
+crate::register_library_action!(RestoreFileVersionAction, "file_sync.versions.restore");
@@ -0,0 +1,19 @@
+//! Input types for restore_file_version action
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RestoreFileVersionInput {
+	pub version_id: Uuid,
+
+	/// Where to restore to (defaults to the file's place in the conduit's source)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub destination: Option<PathBuf>,
+
+	/// Replace a file already at the destination
+	#[serde(default)]
+	pub overwrite: bool,
+}
@@ -0,0 +1,9 @@
+//! Restore file version operation
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::RestoreFileVersionAction;
+pub use input::RestoreFileVersionInput;
+pub use output::RestoreFileVersionOutput;
@@ -0,0 +1,10 @@
+//! Output types for restore_file_version action
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RestoreFileVersionOutput {
+	pub restored_path: PathBuf,
+}
@@ -0,0 +1,27 @@
+//! Archive browsing errors
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+pub type ArchiveResult<T> = Result<T, ArchiveError>;
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+	#[error("I/O error: {0}")]
+	Io(#[from] std::io::Error),
+
+	#[error("Zip error: {0}")]
+	Zip(#[from] zip::result::ZipError),
+
+	#[error("7z error: {0}")]
+	SevenZip(String),
+
+	#[error("Not a supported archive: {}", .0.display())]
+	UnsupportedFormat(PathBuf),
+
+	#[error("No such path in archive: {0}")]
+	NotFound(String),
+
+	#[error("Not a folder in archive: {0}")]
+	NotADirectory(String),
+}
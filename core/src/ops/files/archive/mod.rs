@@ -0,0 +1,90 @@
+//! Archive browsing
+//!
+//! Zip, tar (plain, gzip and zstd) and 7z archives can be browsed in place through
+//! [`SdPath::Archive`] paths. Listings are ephemeral: members are read from the archive's
+//! own directory rather than the library database, and copying a member out through
+//! `FileCopyJob` extracts it. The `files.compress` and `files.extract` jobs build on the
+//! same reader and the segment [`writer`].
+
+pub mod error;
+pub mod reader;
+pub mod thumbnail;
+pub mod writer;
+
+pub use error::{ArchiveError, ArchiveResult};
+pub use reader::{
+	extract, load_index, load_index_async, ArchiveEntry, ArchiveFormat, ArchiveIndex,
+};
+pub use thumbnail::{ArchiveThumbnailAction, ArchiveThumbnailInput, ArchiveThumbnailOutput};
+pub use writer::{append_source, finish_archive, source_size, CompressFormat};
+
+use crate::{
+	domain::{addressing::SdPath, file::File, ContentKind},
+	ops::indexing::{database_storage::EntryMetadata, state::EntryKind},
+};
+use std::{io, path::PathBuf, time::SystemTime};
+use uuid::Uuid;
+
+/// Stable ID for an archive member, which changes when the archive itself is modified
+pub fn member_id(path: &SdPath, archive_modified: Option<SystemTime>) -> Uuid {
+	let modified = archive_modified
+		.and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+		.map_or(0, |d| d.as_nanos());
+	Uuid::new_v5(
+		&Uuid::NAMESPACE_URL,
+		format!("{}#{}", path.display(), modified).as_bytes(),
+	)
+}
+
+/// Build the File shown for an archive member
+pub fn member_file(
+	path: SdPath,
+	entry: &ArchiveEntry,
+	archive_modified: Option<SystemTime>,
+	content_kind: ContentKind,
+) -> File {
+	let metadata = EntryMetadata {
+		path: PathBuf::from(&entry.path),
+		kind: if entry.is_dir {
+			EntryKind::Directory
+		} else {
+			EntryKind::File
+		},
+		size: entry.size,
+		// Members without their own timestamp inherit the archive's
+		modified: entry.modified.or(archive_modified),
+		accessed: None,
+		created: None,
+		inode: None,
+		permissions: None,
+		is_hidden: entry.name().starts_with('.'),
+	};
+
+	let mut file = File::from_ephemeral(member_id(&path, archive_modified), &metadata, path);
+	file.content_kind = content_kind;
+	file
+}
+
+/// Run a blocking archive operation, handing the byte counts it reports to `on_progress`
+pub async fn run_with_progress<T, F>(
+	operation: F,
+	mut on_progress: impl FnMut(u64),
+) -> ArchiveResult<T>
+where
+	T: Send + 'static,
+	F: FnOnce(&mut dyn FnMut(u64)) -> ArchiveResult<T> + Send + 'static,
+{
+	let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+	let task = tokio::task::spawn_blocking(move || {
+		operation(&mut |bytes| {
+			let _ = progress_tx.send(bytes);
+		})
+	});
+
+	while let Some(bytes) = progress_rx.recv().await {
+		on_progress(bytes);
+	}
+
+	task.await
+		.map_err(|e| ArchiveError::Io(io::Error::other(e)))?
+}
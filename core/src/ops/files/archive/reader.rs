@@ -0,0 +1,610 @@
+//! Reading archive contents without unpacking them
+//!
+//! Each supported format is read into an [`ArchiveIndex`], a flat map of the members keyed
+//! by their normalized inner path. Folders that only exist implicitly (a zip holding
+//! `a/b/c.txt` but no `a/` record) are synthesized so every member has a parent to browse.
+//! Indexes are cached per archive file and invalidated when the file changes, since tar
+//! archives have no central directory and must be decompressed in full to be listed.
+
+use super::error::{ArchiveError, ArchiveResult};
+use once_cell::sync::Lazy;
+use std::{
+	collections::{BTreeMap, HashMap},
+	fs::File,
+	io::{self, BufReader, Read, Write},
+	path::{Component, Path, PathBuf},
+	sync::{Arc, Mutex, PoisonError},
+	time::{Instant, SystemTime, UNIX_EPOCH},
+};
+use tracing::warn;
+
+/// Number of archive indexes kept in memory
+const INDEX_CACHE_CAPACITY: usize = 32;
+
+/// Buffer size when extracting members
+const EXTRACT_BUFFER_SIZE: usize = 64 * 1024;
+
+static INDEX_CACHE: Lazy<Mutex<HashMap<PathBuf, CachedIndex>>> =
+	Lazy::new(|| Mutex::new(HashMap::new()));
+
+struct CachedIndex {
+	modified: Option<SystemTime>,
+	len: u64,
+	last_used: Instant,
+	index: Arc<ArchiveIndex>,
+}
+
+/// Archive formats that can be browsed in place
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+	Zip,
+	Tar,
+	TarGz,
+	TarZst,
+	SevenZip,
+}
+
+/// Archive file name suffixes, longest match first
+const EXTENSIONS: [(&str, ArchiveFormat); 7] = [
+	(".tar.gz", ArchiveFormat::TarGz),
+	(".tgz", ArchiveFormat::TarGz),
+	(".tar.zst", ArchiveFormat::TarZst),
+	(".tzst", ArchiveFormat::TarZst),
+	(".tar", ArchiveFormat::Tar),
+	(".zip", ArchiveFormat::Zip),
+	(".7z", ArchiveFormat::SevenZip),
+];
+
+impl ArchiveFormat {
+	/// Detect the format from the archive's file name
+	pub fn from_path(path: &Path) -> Option<Self> {
+		let name = path.file_name()?.to_str()?.to_lowercase();
+		EXTENSIONS
+			.iter()
+			.find(|(suffix, _)| name.ends_with(suffix))
+			.map(|(_, format)| *format)
+	}
+
+	/// The archive's file name without its archive extension (`photos.tar.gz` -> `photos`)
+	pub fn stem(path: &Path) -> Option<&str> {
+		let name = path.file_name()?.to_str()?;
+		let lowercase = name.to_lowercase();
+		let (suffix, _) = EXTENSIONS
+			.iter()
+			.find(|(suffix, _)| lowercase.ends_with(suffix))?;
+		name.get(..name.len() - suffix.len())
+			.filter(|stem| !stem.is_empty())
+	}
+}
+
+/// A file or folder inside an archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+	/// Normalized '/'-separated path inside the archive
+	pub path: String,
+	pub is_dir: bool,
+	/// Uncompressed size, zero for folders
+	pub size: u64,
+	pub modified: Option<SystemTime>,
+}
+
+impl ArchiveEntry {
+	/// The last segment of the entry's path
+	pub fn name(&self) -> &str {
+		self.path.rsplit('/').next().unwrap_or(&self.path)
+	}
+
+	fn dir(path: String) -> Self {
+		Self {
+			path,
+			is_dir: true,
+			size: 0,
+			modified: None,
+		}
+	}
+}
+
+/// Listing of every member of an archive
+#[derive(Debug)]
+pub struct ArchiveIndex {
+	pub format: ArchiveFormat,
+	entries: BTreeMap<String, ArchiveEntry>,
+}
+
+impl ArchiveIndex {
+	/// Read the member listing of an archive, blocking while it is parsed
+	pub fn read(archive_path: &Path) -> ArchiveResult<Self> {
+		let format = ArchiveFormat::from_path(archive_path)
+			.ok_or_else(|| ArchiveError::UnsupportedFormat(archive_path.to_path_buf()))?;
+
+		let mut index = Self {
+			format,
+			entries: BTreeMap::new(),
+		};
+		index
+			.entries
+			.insert(String::new(), ArchiveEntry::dir(String::new()));
+
+		match format {
+			ArchiveFormat::Zip => {
+				let mut archive = zip::ZipArchive::new(BufReader::new(File::open(archive_path)?))?;
+				for i in 0..archive.len() {
+					let file = archive.by_index_raw(i)?;
+					let Some(path) = file.enclosed_name().and_then(|p| normalize_member_path(&p))
+					else {
+						warn!("Skipping unsafe zip member: {}", file.name());
+						continue;
+					};
+					index.insert(ArchiveEntry {
+						path,
+						is_dir: file.is_dir(),
+						size: if file.is_dir() { 0 } else { file.size() },
+						modified: file.last_modified().and_then(zip_time),
+					});
+				}
+			}
+			ArchiveFormat::Tar | ArchiveFormat::TarGz | ArchiveFormat::TarZst => {
+				let mut archive = tar::Archive::new(open_tar_stream(archive_path, format)?);
+				for entry in archive.entries()? {
+					let entry = entry?;
+					let entry_type = entry.header().entry_type();
+					if !entry_type.is_file() && !entry_type.is_dir() {
+						continue;
+					}
+					let Some(path) = normalize_member_path(&entry.path()?) else {
+						warn!("Skipping unsafe tar member: {}", entry.path()?.display());
+						continue;
+					};
+					index.insert(ArchiveEntry {
+						path,
+						is_dir: entry_type.is_dir(),
+						size: if entry_type.is_dir() { 0 } else { entry.size() },
+						modified: entry
+							.header()
+							.mtime()
+							.ok()
+							.map(|secs| UNIX_EPOCH + std::time::Duration::from_secs(secs)),
+					});
+				}
+			}
+			ArchiveFormat::SevenZip => {
+				let reader =
+					sevenz_rust::SevenZReader::open(archive_path, sevenz_rust::Password::empty())
+						.map_err(|e| ArchiveError::SevenZip(e.to_string()))?;
+				for file in &reader.archive().files {
+					let Some(path) = normalize_member_path(Path::new(file.name())) else {
+						warn!("Skipping unsafe 7z member: {}", file.name());
+						continue;
+					};
+					index.insert(ArchiveEntry {
+						path,
+						is_dir: file.is_directory(),
+						size: file.size(),
+						modified: None,
+					});
+				}
+			}
+		}
+
+		Ok(index)
+	}
+
+	/// Look up a member by its inner path, "" being the archive root
+	pub fn get(&self, inner_path: &str) -> Option<&ArchiveEntry> {
+		self.entries.get(inner_path)
+	}
+
+	/// Direct children of a folder inside the archive
+	pub fn children(&self, inner_path: &str) -> ArchiveResult<Vec<&ArchiveEntry>> {
+		match self.get(inner_path) {
+			Some(entry) if entry.is_dir => {}
+			Some(_) => return Err(ArchiveError::NotADirectory(inner_path.to_string())),
+			None => return Err(ArchiveError::NotFound(inner_path.to_string())),
+		}
+
+		Ok(self
+			.descendants(inner_path)
+			.filter(|entry| parent_of(&entry.path) == inner_path)
+			.collect())
+	}
+
+	/// Total uncompressed size of a member, including everything below it for folders
+	pub fn size_of(&self, inner_path: &str) -> u64 {
+		match self.get(inner_path) {
+			Some(entry) if entry.is_dir => self.descendants(inner_path).map(|e| e.size).sum(),
+			Some(entry) => entry.size,
+			None => 0,
+		}
+	}
+
+	/// Number of members in the archive, not counting the root
+	pub fn len(&self) -> usize {
+		self.entries.len() - 1
+	}
+
+	/// Whether the archive has no members
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	fn descendants<'a>(&'a self, inner_path: &'a str) -> impl Iterator<Item = &'a ArchiveEntry> {
+		let prefix = if inner_path.is_empty() {
+			String::new()
+		} else {
+			format!("{}/", inner_path)
+		};
+		self.entries
+			.range(prefix.clone()..)
+			.take_while(move |(path, _)| path.starts_with(&prefix))
+			.filter(|(path, _)| !path.is_empty())
+			.map(|(_, entry)| entry)
+	}
+
+	fn insert(&mut self, entry: ArchiveEntry) {
+		// Synthesize folders that are only implied by their members
+		let mut parent = parent_of(&entry.path);
+		while !self.entries.contains_key(parent) {
+			self.entries
+				.insert(parent.to_string(), ArchiveEntry::dir(parent.to_string()));
+			parent = parent_of(parent);
+		}
+
+		match self.entries.get_mut(&entry.path) {
+			// An explicit folder record replaces a synthesized one
+			Some(existing) if existing.is_dir && entry.is_dir => *existing = entry,
+			Some(_) => warn!("Duplicate archive member: {}", entry.path),
+			None => {
+				self.entries.insert(entry.path.clone(), entry);
+			}
+		}
+	}
+}
+
+/// Load an archive's index, reusing the cached one while the archive is unchanged
+pub fn load_index(archive_path: &Path) -> ArchiveResult<Arc<ArchiveIndex>> {
+	let metadata = std::fs::metadata(archive_path)?;
+	let modified = metadata.modified().ok();
+
+	{
+		let mut cache = INDEX_CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+		if let Some(cached) = cache.get_mut(archive_path) {
+			if cached.modified == modified && cached.len == metadata.len() {
+				cached.last_used = Instant::now();
+				return Ok(cached.index.clone());
+			}
+		}
+	}
+
+	let index = Arc::new(ArchiveIndex::read(archive_path)?);
+
+	let mut cache = INDEX_CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+	if cache.len() >= INDEX_CACHE_CAPACITY && !cache.contains_key(archive_path) {
+		let oldest = cache
+			.iter()
+			.min_by_key(|(_, cached)| cached.last_used)
+			.map(|(path, _)| path.clone());
+		if let Some(oldest) = oldest {
+			cache.remove(&oldest);
+		}
+	}
+	cache.insert(
+		archive_path.to_path_buf(),
+		CachedIndex {
+			modified,
+			len: metadata.len(),
+			last_used: Instant::now(),
+			index: index.clone(),
+		},
+	);
+
+	Ok(index)
+}
+
+/// Load an archive's index without blocking the async runtime
+pub async fn load_index_async(archive_path: &Path) -> ArchiveResult<Arc<ArchiveIndex>> {
+	let archive_path = archive_path.to_path_buf();
+	tokio::task::spawn_blocking(move || load_index(&archive_path))
+		.await
+		.map_err(|e| ArchiveError::Io(io::Error::other(e)))?
+}
+
+/// Extract a member of an archive to `destination`, blocking until it is written.
+///
+/// A file member is written to `destination` itself; a folder member has its contents
+/// written below `destination`. `on_progress` receives the number of bytes written since
+/// its last call. Returns the total number of bytes written.
+pub fn extract(
+	archive_path: &Path,
+	inner_path: &str,
+	destination: &Path,
+	mut on_progress: impl FnMut(u64),
+) -> ArchiveResult<u64> {
+	let format = ArchiveFormat::from_path(archive_path)
+		.ok_or_else(|| ArchiveError::UnsupportedFormat(archive_path.to_path_buf()))?;
+
+	let mut matched = false;
+	let mut written = 0;
+
+	// Where a member lands, or None when it isn't part of the extraction
+	let target_for = |path: &str| -> Option<PathBuf> {
+		if path == inner_path {
+			Some(destination.to_path_buf())
+		} else if inner_path.is_empty() {
+			Some(destination.join(path))
+		} else {
+			path.strip_prefix(inner_path)?
+				.strip_prefix('/')
+				.map(|rest| destination.join(rest))
+		}
+	};
+
+	let mut write_member = |target: PathBuf, is_dir: bool, reader: &mut dyn Read| {
+		matched = true;
+		if is_dir {
+			std::fs::create_dir_all(&target)?;
+			return Ok::<_, io::Error>(());
+		}
+		if let Some(parent) = target.parent() {
+			std::fs::create_dir_all(parent)?;
+		}
+		let mut file = File::create(&target)?;
+		written += copy_with_progress(reader, &mut file, &mut on_progress)?;
+		file.flush()
+	};
+
+	match format {
+		ArchiveFormat::Zip => {
+			let mut archive = zip::ZipArchive::new(BufReader::new(File::open(archive_path)?))?;
+			for i in 0..archive.len() {
+				let mut file = archive.by_index(i)?;
+				let Some(target) = file
+					.enclosed_name()
+					.and_then(|p| normalize_member_path(&p))
+					.and_then(|path| target_for(&path))
+				else {
+					continue;
+				};
+				let is_dir = file.is_dir();
+				write_member(target, is_dir, &mut file)?;
+			}
+		}
+		ArchiveFormat::Tar | ArchiveFormat::TarGz | ArchiveFormat::TarZst => {
+			let mut archive = tar::Archive::new(open_tar_stream(archive_path, format)?);
+			for entry in archive.entries()? {
+				let mut entry = entry?;
+				let entry_type = entry.header().entry_type();
+				if !entry_type.is_file() && !entry_type.is_dir() {
+					continue;
+				}
+				let Some(target) =
+					normalize_member_path(&entry.path()?).and_then(|path| target_for(&path))
+				else {
+					continue;
+				};
+				write_member(target, entry_type.is_dir(), &mut entry)?;
+			}
+		}
+		ArchiveFormat::SevenZip => {
+			let mut reader =
+				sevenz_rust::SevenZReader::open(archive_path, sevenz_rust::Password::empty())
+					.map_err(|e| ArchiveError::SevenZip(e.to_string()))?;
+			let mut result = Ok(());
+			reader
+				.for_each_entries(|file, reader| {
+					let target = normalize_member_path(Path::new(file.name()))
+						.and_then(|path| target_for(&path));
+					match target {
+						Some(target) => {
+							if let Err(e) = write_member(target, file.is_directory(), reader) {
+								result = Err(e);
+								return Ok(false);
+							}
+						}
+						// Solid blocks are decoded in order, so skipped members are still read
+						None => {
+							io::copy(reader, &mut io::sink())?;
+						}
+					}
+					Ok(true)
+				})
+				.map_err(|e| ArchiveError::SevenZip(e.to_string()))?;
+			result?;
+		}
+	}
+
+	if !matched {
+		return Err(ArchiveError::NotFound(inner_path.to_string()));
+	}
+
+	Ok(written)
+}
+
+fn open_tar_stream(archive_path: &Path, format: ArchiveFormat) -> io::Result<Box<dyn Read>> {
+	let file = BufReader::new(File::open(archive_path)?);
+	Ok(match format {
+		ArchiveFormat::TarGz => Box::new(flate2::read::GzDecoder::new(file)),
+		ArchiveFormat::TarZst => Box::new(zstd::stream::read::Decoder::with_buffer(file)?),
+		_ => Box::new(file),
+	})
+}
+
+pub(super) fn copy_with_progress(
+	reader: &mut dyn Read,
+	writer: &mut impl Write,
+	on_progress: &mut impl FnMut(u64),
+) -> io::Result<u64> {
+	let mut buffer = vec![0u8; EXTRACT_BUFFER_SIZE];
+	let mut total = 0;
+	loop {
+		let read = match reader.read(&mut buffer) {
+			Ok(0) => return Ok(total),
+			Ok(read) => read,
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(e),
+		};
+		writer.write_all(&buffer[..read])?;
+		total += read as u64;
+		on_progress(read as u64);
+	}
+}
+
+/// Normalize a member's path, rejecting ones that would escape the extraction folder
+fn normalize_member_path(path: &Path) -> Option<String> {
+	let mut segments = Vec::new();
+	for component in path.components() {
+		match component {
+			Component::Normal(segment) => segments.push(segment.to_str()?.replace('\\', "/")),
+			Component::CurDir | Component::RootDir => {}
+			Component::ParentDir | Component::Prefix(_) => return None,
+		}
+	}
+	let normalized = segments.join("/");
+	(!normalized.is_empty()).then_some(normalized)
+}
+
+fn parent_of(path: &str) -> &str {
+	path.rsplit_once('/').map_or("", |(parent, _)| parent)
+}
+
+fn zip_time(time: zip::DateTime) -> Option<SystemTime> {
+	let date = chrono::NaiveDate::from_ymd_opt(
+		time.year() as i32,
+		time.month() as u32,
+		time.day() as u32,
+	)?;
+	let datetime = date.and_hms_opt(
+		time.hour() as u32,
+		time.minute() as u32,
+		time.second() as u32,
+	)?;
+	Some(datetime.and_utc().into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::TempDir;
+
+	fn write_zip(path: &Path, files: &[(&str, &[u8])]) {
+		let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+		for (name, data) in files {
+			zip.start_file(*name, zip::write::SimpleFileOptions::default())
+				.unwrap();
+			zip.write_all(data).unwrap();
+		}
+		zip.finish().unwrap();
+	}
+
+	fn write_tar_gz(path: &Path, files: &[(&str, &[u8])]) {
+		let encoder =
+			flate2::write::GzEncoder::new(File::create(path).unwrap(), Default::default());
+		let mut tar = tar::Builder::new(encoder);
+		for (name, data) in files {
+			let mut header = tar::Header::new_gnu();
+			header.set_size(data.len() as u64);
+			header.set_mode(0o644);
+			header.set_cksum();
+			tar.append_data(&mut header, name, *data).unwrap();
+		}
+		tar.into_inner().unwrap().finish().unwrap();
+	}
+
+	#[test]
+	fn test_format_detection() {
+		assert_eq!(
+			ArchiveFormat::from_path(Path::new("a/Photos.ZIP")),
+			Some(ArchiveFormat::Zip)
+		);
+		assert_eq!(
+			ArchiveFormat::from_path(Path::new("backup.tar.gz")),
+			Some(ArchiveFormat::TarGz)
+		);
+		assert_eq!(
+			ArchiveFormat::from_path(Path::new("backup.tzst")),
+			Some(ArchiveFormat::TarZst)
+		);
+		assert_eq!(ArchiveFormat::from_path(Path::new("notes.txt")), None);
+		assert_eq!(
+			ArchiveFormat::stem(Path::new("a/Backup.TAR.ZST")),
+			Some("Backup")
+		);
+		assert_eq!(ArchiveFormat::stem(Path::new(".zip")), None);
+	}
+
+	#[test]
+	fn test_zip_listing_synthesizes_folders() {
+		let temp = TempDir::new().unwrap();
+		let archive = temp.path().join("photos.zip");
+		write_zip(
+			&archive,
+			&[
+				("2024/summer/beach.jpg", b"beach"),
+				("2024/notes.txt", b"hello"),
+				("readme.md", b"readme"),
+			],
+		);
+
+		let index = ArchiveIndex::read(&archive).unwrap();
+		assert_eq!(index.len(), 5);
+
+		let root: Vec<_> = index
+			.children("")
+			.unwrap()
+			.iter()
+			.map(|e| e.name())
+			.collect();
+		assert_eq!(root, vec!["2024", "readme.md"]);
+
+		let year = index.children("2024").unwrap();
+		assert_eq!(year.len(), 2);
+		assert!(index.get("2024/summer").unwrap().is_dir);
+		assert_eq!(index.size_of("2024"), 10);
+		assert!(matches!(
+			index.children("readme.md"),
+			Err(ArchiveError::NotADirectory(_))
+		));
+	}
+
+	#[test]
+	fn test_tar_gz_extracts_folder() {
+		let temp = TempDir::new().unwrap();
+		let archive = temp.path().join("backup.tar.gz");
+		write_tar_gz(
+			&archive,
+			&[
+				("docs/a.txt", b"aaa"),
+				("docs/sub/b.txt", b"bb"),
+				("c.txt", b"c"),
+			],
+		);
+
+		let destination = temp.path().join("out");
+		let mut reported = 0;
+		let written = extract(&archive, "docs", &destination, |n| reported += n).unwrap();
+
+		assert_eq!(written, 5);
+		assert_eq!(reported, 5);
+		assert_eq!(std::fs::read(destination.join("a.txt")).unwrap(), b"aaa");
+		assert_eq!(std::fs::read(destination.join("sub/b.txt")).unwrap(), b"bb");
+		assert!(!destination.join("c.txt").exists());
+	}
+
+	#[test]
+	fn test_extract_missing_member() {
+		let temp = TempDir::new().unwrap();
+		let archive = temp.path().join("photos.zip");
+		write_zip(&archive, &[("a.txt", b"a")]);
+
+		let result = extract(&archive, "b.txt", &temp.path().join("b.txt"), |_| {});
+		assert!(matches!(result, Err(ArchiveError::NotFound(_))));
+	}
+
+	#[test]
+	fn test_unsafe_member_paths_are_rejected() {
+		assert_eq!(normalize_member_path(Path::new("../etc/passwd")), None);
+		assert_eq!(
+			normalize_member_path(Path::new("/./a/b.txt")),
+			Some("a/b.txt".to_string())
+		);
+	}
+}
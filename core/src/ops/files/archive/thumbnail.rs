@@ -0,0 +1,351 @@
+//! Thumbnails for files inside archives
+//!
+//! Archive members have no content identity, so their thumbnails are keyed by the member's
+//! ID instead and written to the library's sidecar folder like any other thumbnail. The UI
+//! loads them through the regular sidecar URL with that ID in place of a content UUID.
+//!
+//! A member's ID changes whenever its archive is modified, so the library keeps a small
+//! ledger of the ID each member was last thumbnailed under. Thumbnails left behind by an
+//! older ID, or by an archive that no longer exists, are removed when the ledger is updated.
+
+use super::{extract, load_index_async, member_id};
+use crate::{
+	context::CoreContext,
+	domain::addressing::SdPath,
+	infra::action::{error::ActionError, LibraryAction},
+	ops::{
+		media::thumbnail::{ThumbnailGenerator, ThumbnailUtils, ThumbnailVariants},
+		sidecar::types::SidecarKind,
+	},
+};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// File in the library's sidecar folder holding the [`ThumbnailLedger`]
+const LEDGER_FILE: &str = "archive_thumbnails.json";
+
+/// Serializes ledger updates between concurrent thumbnail requests
+static LEDGER_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ArchiveThumbnailInput {
+	/// The file inside an archive to generate thumbnails for
+	pub path: SdPath,
+	/// Regenerate thumbnails that already exist
+	pub force: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ArchiveThumbnailOutput {
+	/// ID the thumbnails are stored under, used in place of a content UUID
+	pub thumbnail_id: Uuid,
+	/// Variant names that are available
+	pub variants: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveThumbnailAction {
+	input: ArchiveThumbnailInput,
+}
+
+impl LibraryAction for ArchiveThumbnailAction {
+	type Input = ArchiveThumbnailInput;
+	type Output = ArchiveThumbnailOutput;
+
+	fn from_input(input: ArchiveThumbnailInput) -> Result<Self, String> {
+		Ok(Self { input })
+	}
+
+	async fn validate(
+		&self,
+		_library: &Arc<crate::library::Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<crate::infra::action::ValidationResult, ActionError> {
+		if self.input.path.as_local_archive().is_none() {
+			return Err(ActionError::Validation {
+				field: "path".to_string(),
+				message: "Path must be inside an archive on this device".to_string(),
+			});
+		}
+
+		Ok(crate::infra::action::ValidationResult::Success { metadata: None })
+	}
+
+	async fn execute(
+		self,
+		library: Arc<crate::library::Library>,
+		context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let Some((archive_path, inner_path)) = self.input.path.as_local_archive() else {
+			return Err(ActionError::Internal(
+				"Path is not inside a local archive".to_string(),
+			));
+		};
+
+		let index = load_index_async(archive_path)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to read archive: {}", e)))?;
+		match index.get(inner_path) {
+			Some(entry) if !entry.is_dir => {}
+			_ => {
+				return Err(ActionError::Internal(format!(
+					"No file at {} in archive",
+					inner_path
+				)))
+			}
+		}
+
+		let mime_type = Path::new(inner_path)
+			.extension()
+			.and_then(|ext| ext.to_str())
+			.and_then(|ext| {
+				context
+					.file_type_registry()
+					.get_by_extension(ext)
+					.into_iter()
+					.max_by_key(|file_type| file_type.priority)
+					.and_then(|file_type| file_type.mime_types.first().cloned())
+			})
+			.filter(|mime| ThumbnailUtils::is_thumbnail_supported(mime))
+			.ok_or_else(|| {
+				ActionError::Internal(format!("Thumbnails aren't supported for {}", inner_path))
+			})?;
+
+		let archive_modified = tokio::fs::metadata(archive_path)
+			.await
+			.and_then(|metadata| metadata.modified())
+			.ok();
+		let thumbnail_id = member_id(&self.input.path, archive_modified);
+
+		let sidecar_manager = context
+			.get_sidecar_manager()
+			.await
+			.ok_or_else(|| ActionError::Internal("SidecarManager not available".to_string()))?;
+
+		// Work out which variants still need generating before extracting anything
+		let mut missing = Vec::new();
+		let mut variants = Vec::new();
+		for variant_config in ThumbnailVariants::defaults() {
+			let exists = sidecar_manager
+				.exists(
+					&library.id(),
+					&thumbnail_id,
+					&SidecarKind::Thumb,
+					&variant_config.variant,
+					&variant_config.format(),
+				)
+				.await
+				.unwrap_or(false);
+			if exists && !self.input.force {
+				variants.push(variant_config.variant.as_str().to_string());
+			} else {
+				missing.push(variant_config);
+			}
+		}
+
+		if missing.is_empty() {
+			return Ok(ArchiveThumbnailOutput {
+				thumbnail_id,
+				variants,
+			});
+		}
+
+		// Thumbnail generators read from disk, so the member is extracted to a temp file
+		let temp_dir = tempfile::tempdir()
+			.map_err(|e| ActionError::Internal(format!("Failed to create temp dir: {}", e)))?;
+		let source = temp_dir
+			.path()
+			.join(self.input.path.file_name().unwrap_or("member"));
+		{
+			let archive_path = archive_path.to_path_buf();
+			let inner_path = inner_path.to_string();
+			let source = source.clone();
+			tokio::task::spawn_blocking(move || {
+				extract(&archive_path, &inner_path, &source, |_| {})
+			})
+			.await
+			.map_err(|e| ActionError::Internal(e.to_string()))?
+			.map_err(|e| ActionError::Internal(format!("Failed to extract file: {}", e)))?;
+		}
+
+		let generator = ThumbnailGenerator::for_mime_type(&mime_type)
+			.map_err(|e| ActionError::Internal(e.to_string()))?;
+
+		for variant_config in missing {
+			let output_path = sidecar_manager
+				.compute_path(
+					&library.id(),
+					&thumbnail_id,
+					&SidecarKind::Thumb,
+					&variant_config.variant,
+					&variant_config.format(),
+				)
+				.await
+				.map_err(|e| ActionError::Internal(format!("Failed to compute path: {}", e)))?;
+
+			match generator
+				.generate(
+					&source,
+					&output_path.absolute_path,
+					variant_config.size,
+					variant_config.quality,
+				)
+				.await
+			{
+				Ok(_) => variants.push(variant_config.variant.as_str().to_string()),
+				Err(e) => warn!(
+					"Failed to generate thumbnail {} for {}: {}",
+					variant_config.variant.as_str(),
+					self.input.path,
+					e
+				),
+			}
+		}
+
+		let ledger_path = library.path().join("sidecars").join(LEDGER_FILE);
+		let _guard = LEDGER_LOCK.lock().await;
+		let mut ledger = ThumbnailLedger::load(&ledger_path).await;
+		let stale = ledger.record(&self.input.path, archive_path, thumbnail_id);
+		for stale_id in stale {
+			for variant_config in ThumbnailVariants::defaults() {
+				let Ok(stale_path) = sidecar_manager
+					.compute_path(
+						&library.id(),
+						&stale_id,
+						&SidecarKind::Thumb,
+						&variant_config.variant,
+						&variant_config.format(),
+					)
+					.await
+				else {
+					continue;
+				};
+				match tokio::fs::remove_file(&stale_path.absolute_path).await {
+					Ok(()) => debug!(
+						"Removed stale archive thumbnail {}",
+						stale_path.absolute_path.display()
+					),
+					Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+					Err(e) => warn!(
+						"Failed to remove stale archive thumbnail {}: {}",
+						stale_path.absolute_path.display(),
+						e
+					),
+				}
+			}
+		}
+		if let Err(e) = ledger.save(&ledger_path).await {
+			warn!("Failed to save archive thumbnail ledger: {}", e);
+		}
+
+		Ok(ArchiveThumbnailOutput {
+			thumbnail_id,
+			variants,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"files.archive.thumbnail"
+	}
+}
+
+/// The thumbnail ID each archive member was last thumbnailed under
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ThumbnailLedger {
+	/// Keyed by the member's archive path display
+	members: HashMap<String, LedgerEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LedgerEntry {
+	archive_path: PathBuf,
+	thumbnail_id: Uuid,
+}
+
+impl ThumbnailLedger {
+	/// Load the ledger, starting afresh if it is missing or unreadable
+	async fn load(path: &Path) -> Self {
+		match tokio::fs::read(path).await {
+			Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+				warn!("Discarding unreadable archive thumbnail ledger: {}", e);
+				Self::default()
+			}),
+			Err(_) => Self::default(),
+		}
+	}
+
+	async fn save(&self, path: &Path) -> std::io::Result<()> {
+		let bytes = serde_json::to_vec(self).map_err(std::io::Error::other)?;
+		tokio::fs::write(path, bytes).await
+	}
+
+	/// Record the thumbnail ID of a member, returning the IDs whose thumbnails are now
+	/// stale: the member's previous ID, and those of members whose archive is gone
+	fn record(&mut self, member: &SdPath, archive_path: &Path, thumbnail_id: Uuid) -> Vec<Uuid> {
+		let mut stale = Vec::new();
+		if let Some(previous) = self.members.insert(
+			member.display(),
+			LedgerEntry {
+				archive_path: archive_path.to_path_buf(),
+				thumbnail_id,
+			},
+		) {
+			if previous.thumbnail_id != thumbnail_id {
+				stale.push(previous.thumbnail_id);
+			}
+		}
+
+		self.members.retain(|_, entry| {
+			let exists = entry.archive_path.exists();
+			if !exists {
+				stale.push(entry.thumbnail_id);
+			}
+			exists
+		});
+
+		stale
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_ledger_reports_replaced_and_missing_thumbnails() {
+		let dir = tempfile::tempdir().unwrap();
+		let archive = dir.path().join("photos.zip");
+		std::fs::write(&archive, b"").unwrap();
+		let member = SdPath::archive("test-device".to_string(), &archive, "beach.jpg");
+
+		let mut ledger = ThumbnailLedger::default();
+		let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+		assert!(ledger.record(&member, &archive, first).is_empty());
+		assert!(ledger.record(&member, &archive, first).is_empty());
+
+		// The archive was modified, so the member is thumbnailed under a new ID
+		assert_eq!(ledger.record(&member, &archive, second), vec![first]);
+
+		// Once the archive is gone its members' thumbnails are stale too
+		let other_archive = dir.path().join("other.zip");
+		std::fs::write(&other_archive, b"").unwrap();
+		let other = SdPath::archive("test-device".to_string(), &other_archive, "a.jpg");
+		std::fs::remove_file(&archive).unwrap();
+		assert_eq!(
+			ledger.record(&other, &other_archive, Uuid::new_v4()),
+			vec![second]
+		);
+		assert_eq!(ledger.members.len(), 1);
+	}
+}
+
+crate::register_library_action!(ArchiveThumbnailAction, "files.archive.thumbnail");
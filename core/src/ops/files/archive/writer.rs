@@ -0,0 +1,335 @@
+//! Creating archives from files on disk
+//!
+//! Archives are written one source at a time, each source appended as a self-contained
+//! segment: zip archives are finished after every source and reopened in append mode,
+//! and tar.zst archives get one zstd frame per source (concatenated frames decode as a
+//! single stream). A job can record the archive's length after each segment and, after an
+//! interruption, truncate back to it and carry on with the next source.
+
+use super::{error::ArchiveResult, reader::copy_with_progress};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::{
+	fs::{self, File, Metadata, OpenOptions},
+	io::{self, Read, Seek, SeekFrom, Write},
+	path::{Path, PathBuf},
+};
+
+/// zstd level for tar.zst archives, favouring speed like the rest of the app
+const ZSTD_LEVEL: i32 = 3;
+
+/// Length of the end-of-archive marker that closes a tar stream
+const TAR_TRAILER_LEN: usize = 1024;
+
+/// Formats archives can be created in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum CompressFormat {
+	Zip,
+	TarZst,
+}
+
+impl CompressFormat {
+	/// File extension for archives in this format, without the leading dot
+	pub fn extension(&self) -> &'static str {
+		match self {
+			Self::Zip => "zip",
+			Self::TarZst => "tar.zst",
+		}
+	}
+}
+
+/// Append a file or folder to a partially written archive, returning the archive's new length
+///
+/// `written_len` is the length returned by the previous call (zero for a new archive);
+/// anything past it is discarded first. `name` is the source's path inside the archive.
+pub fn append_source(
+	archive_path: &Path,
+	format: CompressFormat,
+	written_len: u64,
+	source: &Path,
+	name: &str,
+	mut on_progress: impl FnMut(u64),
+) -> ArchiveResult<u64> {
+	let file = open_partial(archive_path, written_len)?;
+	let members = collect_members(source, name)?;
+
+	match format {
+		CompressFormat::Zip => {
+			let mut zip = if written_len == 0 {
+				zip::ZipWriter::new(file)
+			} else {
+				zip::ZipWriter::new_append(file)?
+			};
+
+			for (path, name, metadata) in members {
+				let options = zip_options(&metadata);
+				if metadata.is_dir() {
+					zip.add_directory(name, options)?;
+				} else {
+					zip.start_file(name, options)?;
+					copy_with_progress(&mut File::open(&path)?, &mut zip, &mut on_progress)?;
+				}
+			}
+
+			Ok(zip.finish()?.metadata()?.len())
+		}
+		CompressFormat::TarZst => {
+			let encoder = zstd::stream::write::Encoder::new(file, ZSTD_LEVEL)?;
+			let mut tar = tar::Builder::new(Segment {
+				inner: encoder,
+				closed: false,
+			});
+
+			for (path, name, metadata) in members {
+				let mut header = tar::Header::new_gnu();
+				header.set_metadata(&metadata);
+				if metadata.is_dir() {
+					tar.append_data(&mut header, &name, io::empty())?;
+				} else {
+					let reader = ProgressReader {
+						inner: File::open(&path)?,
+						on_progress: &mut on_progress,
+					};
+					tar.append_data(&mut header, &name, reader)?;
+				}
+			}
+
+			// The builder ends the tar stream when it's finished, but the end-of-archive
+			// marker must only come after the last source, so this segment's is swallowed
+			tar.get_mut().closed = true;
+			let segment = tar.into_inner()?;
+
+			Ok(segment.inner.finish()?.metadata()?.len())
+		}
+	}
+}
+
+/// Close an archive once every source has been appended, returning its final length
+pub fn finish_archive(
+	archive_path: &Path,
+	format: CompressFormat,
+	written_len: u64,
+) -> ArchiveResult<u64> {
+	let file = open_partial(archive_path, written_len)?;
+
+	match format {
+		// Zip archives are complete after every segment, but one with no sources is empty
+		CompressFormat::Zip if written_len > 0 => Ok(written_len),
+		CompressFormat::Zip => Ok(zip::ZipWriter::new(file).finish()?.metadata()?.len()),
+		CompressFormat::TarZst => {
+			let mut encoder = zstd::stream::write::Encoder::new(file, ZSTD_LEVEL)?;
+			encoder.write_all(&[0; TAR_TRAILER_LEN])?;
+			Ok(encoder.finish()?.metadata()?.len())
+		}
+	}
+}
+
+/// Total size of the files under a path, as it would be added to an archive
+pub fn source_size(source: &Path) -> u64 {
+	collect_members(source, "")
+		.map(|members| {
+			members
+				.iter()
+				.filter(|(_, _, metadata)| metadata.is_file())
+				.map(|(_, _, metadata)| metadata.len())
+				.sum()
+		})
+		.unwrap_or(0)
+}
+
+fn open_partial(archive_path: &Path, written_len: u64) -> io::Result<File> {
+	let mut file = OpenOptions::new()
+		.read(true)
+		.write(true)
+		.create(true)
+		.truncate(false)
+		.open(archive_path)?;
+	file.set_len(written_len)?;
+	file.seek(SeekFrom::End(0))?;
+	Ok(file)
+}
+
+/// Files and folders under `source` with their paths inside the archive, parents first
+///
+/// Symlinks are skipped rather than followed, so a link can't pull in files from
+/// outside the source or loop forever.
+fn collect_members(source: &Path, name: &str) -> io::Result<Vec<(PathBuf, String, Metadata)>> {
+	let mut members = Vec::new();
+	let mut stack = vec![(source.to_path_buf(), name.to_string())];
+
+	while let Some((path, name)) = stack.pop() {
+		let metadata = fs::symlink_metadata(&path)?;
+		if metadata.is_symlink() {
+			continue;
+		}
+
+		if metadata.is_dir() {
+			let mut children = fs::read_dir(&path)?
+				.map(|entry| entry.map(|entry| entry.file_name()))
+				.collect::<io::Result<Vec<_>>>()?;
+			// Reversed so they come off the stack in name order
+			children.sort_by(|a, b| b.cmp(a));
+			for child in children {
+				let child_name = format!("{}/{}", name, child.to_string_lossy());
+				stack.push((path.join(&child), child_name));
+			}
+		}
+
+		members.push((path, name, metadata));
+	}
+
+	Ok(members)
+}
+
+fn zip_options(metadata: &Metadata) -> zip::write::SimpleFileOptions {
+	let mut options = zip::write::SimpleFileOptions::default()
+		.compression_method(zip::CompressionMethod::Deflated)
+		.large_file(metadata.len() > u32::MAX as u64);
+
+	if let Some(modified) = metadata.modified().ok().and_then(zip_time) {
+		options = options.last_modified_time(modified);
+	}
+
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt;
+		options = options.unix_permissions(metadata.permissions().mode());
+	}
+
+	options
+}
+
+fn zip_time(time: std::time::SystemTime) -> Option<zip::DateTime> {
+	let time = DateTime::<Utc>::from(time);
+	zip::DateTime::from_date_and_time(
+		u16::try_from(time.year()).ok()?,
+		time.month() as u8,
+		time.day() as u8,
+		time.hour() as u8,
+		time.minute() as u8,
+		time.second() as u8,
+	)
+	.ok()
+}
+
+/// Writer for one tar segment, which drops anything written once it's closed
+struct Segment<W> {
+	inner: W,
+	closed: bool,
+}
+
+impl<W: Write> Write for Segment<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		if self.closed {
+			return Ok(buf.len());
+		}
+		self.inner.write(buf)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+}
+
+struct ProgressReader<'a, R, F> {
+	inner: R,
+	on_progress: &'a mut F,
+}
+
+impl<R: Read, F: FnMut(u64)> Read for ProgressReader<'_, R, F> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let read = self.inner.read(buf)?;
+		(self.on_progress)(read as u64);
+		Ok(read)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ops::files::archive::{extract, ArchiveIndex};
+	use tempfile::TempDir;
+
+	fn write_sources(root: &Path) -> (PathBuf, PathBuf) {
+		let folder = root.join("photos");
+		fs::create_dir_all(folder.join("2024")).unwrap();
+		fs::write(folder.join("2024/beach.jpg"), b"sand").unwrap();
+		fs::write(folder.join("cover.png"), b"cover").unwrap();
+		let file = root.join("notes.txt");
+		fs::write(&file, b"hello").unwrap();
+		(folder, file)
+	}
+
+	#[test]
+	fn test_segments_round_trip() {
+		let temp = TempDir::new().unwrap();
+		let (folder, file) = write_sources(temp.path());
+
+		for format in [CompressFormat::Zip, CompressFormat::TarZst] {
+			let archive = temp.path().join(format!("out.{}", format.extension()));
+
+			let mut len = append_source(&archive, format, 0, &folder, "photos", |_| {}).unwrap();
+			len = append_source(&archive, format, len, &file, "notes.txt", |_| {}).unwrap();
+			finish_archive(&archive, format, len).unwrap();
+
+			let index = ArchiveIndex::read(&archive).unwrap();
+			assert!(index.get("photos/2024").unwrap().is_dir);
+			assert_eq!(index.get("photos/2024/beach.jpg").unwrap().size, 4);
+			assert_eq!(index.get("notes.txt").unwrap().size, 5);
+		}
+	}
+
+	#[test]
+	fn test_tar_segments_leave_the_end_marker_to_finish() {
+		let temp = TempDir::new().unwrap();
+		let (_, file) = write_sources(temp.path());
+		let archive = temp.path().join("segment.tar.zst");
+
+		let len = append_source(
+			&archive,
+			CompressFormat::TarZst,
+			0,
+			&file,
+			"notes.txt",
+			|_| {},
+		)
+		.unwrap();
+		// One header block and one padded data block, with no end-of-archive marker
+		let segment = zstd::decode_all(File::open(&archive).unwrap()).unwrap();
+		assert_eq!(segment.len(), 1024);
+
+		finish_archive(&archive, CompressFormat::TarZst, len).unwrap();
+		let finished = zstd::decode_all(File::open(&archive).unwrap()).unwrap();
+		assert_eq!(finished.len(), 1024 + TAR_TRAILER_LEN);
+		assert!(finished[1024..].iter().all(|byte| *byte == 0));
+	}
+
+	#[test]
+	fn test_resume_discards_partial_segment() {
+		let temp = TempDir::new().unwrap();
+		let (folder, file) = write_sources(temp.path());
+
+		for format in [CompressFormat::Zip, CompressFormat::TarZst] {
+			let archive = temp.path().join(format!("resume.{}", format.extension()));
+
+			let len = append_source(&archive, format, 0, &file, "notes.txt", |_| {}).unwrap();
+
+			// Simulate a segment cut off halfway through
+			let mut partial = OpenOptions::new().append(true).open(&archive).unwrap();
+			partial.write_all(&[0xAB; 300]).unwrap();
+			drop(partial);
+
+			let len = append_source(&archive, format, len, &folder, "photos", |_| {}).unwrap();
+			finish_archive(&archive, format, len).unwrap();
+
+			let out = temp
+				.path()
+				.join(format!("extracted-{}", format.extension()));
+			let written = extract(&archive, "", &out, |_| {}).unwrap();
+			assert_eq!(written, 14);
+			assert_eq!(fs::read(out.join("notes.txt")).unwrap(), b"hello");
+			assert_eq!(fs::read(out.join("photos/cover.png")).unwrap(), b"cover");
+		}
+	}
+}
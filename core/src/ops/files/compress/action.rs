@@ -0,0 +1,111 @@
+//! File compress action handler
+
+use super::{input::FileCompressInput, job::FileCompressJob};
+use crate::{
+	context::CoreContext,
+	domain::addressing::SdPath,
+	infra::{
+		action::{error::ActionError, ConfirmationRequest, LibraryAction, ValidationResult},
+		job::handle::JobReceipt,
+	},
+	ops::files::copy::action::FileConflictResolution,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileCompressAction {
+	input: FileCompressInput,
+}
+
+impl LibraryAction for FileCompressAction {
+	type Input = FileCompressInput;
+	type Output = JobReceipt;
+
+	fn from_input(input: FileCompressInput) -> Result<Self, String> {
+		input
+			.validate()
+			.map_err(|errors| format!("Validation failed: {}", errors.join("; ")))?;
+		Ok(Self { input })
+	}
+
+	async fn validate(
+		&self,
+		_library: &Arc<crate::library::Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<ValidationResult, ActionError> {
+		let archive_path = self
+			.input
+			.archive_path()
+			.ok_or_else(|| ActionError::Validation {
+				field: "destination".to_string(),
+				message: "Archives can only be created on this device".to_string(),
+			})?;
+
+		if self.input.on_conflict.is_none() && archive_path.exists() {
+			let request = ConfirmationRequest {
+				message: format!("{} already exists", archive_path.display()),
+				choices: FileConflictResolution::CHOICES
+					.iter()
+					.map(|c| c.as_str().to_string())
+					.collect(),
+				metadata: Some(json!({
+					"conflicts": [{ "destination": archive_path.to_string_lossy() }],
+				})),
+			};
+			return Ok(ValidationResult::RequiresConfirmation(request));
+		}
+
+		Ok(ValidationResult::Success { metadata: None })
+	}
+
+	fn resolve_confirmation(&mut self, choice_index: usize) -> Result<(), ActionError> {
+		match FileConflictResolution::from_index(choice_index) {
+			Some(FileConflictResolution::Abort) => Err(ActionError::Cancelled),
+			Some(resolution) => {
+				self.input.on_conflict = Some(resolution);
+				Ok(())
+			}
+			None => Err(ActionError::Validation {
+				field: "choice".to_string(),
+				message: "Invalid choice selected".to_string(),
+			}),
+		}
+	}
+
+	async fn execute(
+		self,
+		library: Arc<crate::library::Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		if self.input.on_conflict == Some(FileConflictResolution::Abort) {
+			return Err(ActionError::Cancelled);
+		}
+
+		let archive_path = self.input.archive_path().ok_or_else(|| {
+			ActionError::Internal("Archive destination is not on this device".to_string())
+		})?;
+
+		let job = FileCompressJob::new(
+			self.input.sources,
+			SdPath::local(archive_path),
+			self.input.format,
+		)
+		.with_conflict_resolution(self.input.on_conflict);
+
+		let job_handle = library
+			.jobs()
+			.dispatch(job)
+			.await
+			.map_err(ActionError::Job)?;
+
+		Ok(job_handle.into())
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"files.compress"
+	}
+}
+
+crate::register_library_action!(FileCompressAction, "files.compress");
@@ -0,0 +1,143 @@
+//! Input types for archive creation
+
+use crate::{
+	domain::addressing::{SdPath, SdPathBatch},
+	ops::files::{archive::CompressFormat, copy::action::FileConflictResolution},
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+
+/// Input for compressing files into an archive
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FileCompressInput {
+	/// Files or folders to add to the archive
+	pub sources: SdPathBatch,
+
+	/// Path of the archive to create. The format's extension is added if it's missing.
+	pub destination: SdPath,
+
+	/// Format of the archive
+	pub format: CompressFormat,
+
+	/// How to handle an existing file at the destination (set by confirmation)
+	pub on_conflict: Option<FileConflictResolution>,
+}
+
+impl FileCompressInput {
+	/// Create a new input from local filesystem paths
+	pub fn new<D: Into<PathBuf>>(
+		sources: Vec<PathBuf>,
+		destination: D,
+		format: CompressFormat,
+	) -> Self {
+		Self {
+			sources: SdPathBatch::new(sources.into_iter().map(SdPath::local).collect()),
+			destination: SdPath::local(destination.into()),
+			format,
+			on_conflict: None,
+		}
+	}
+
+	/// Where the archive will be written, or `None` if the destination isn't on this device
+	pub fn archive_path(&self) -> Option<PathBuf> {
+		let path = self.destination.as_local_path()?;
+		let name = path.file_name()?.to_str()?;
+		let extension = format!(".{}", self.format.extension());
+
+		if name.to_lowercase().ends_with(&extension) {
+			Some(path.to_path_buf())
+		} else {
+			Some(path.with_file_name(format!("{}{}", name, extension)))
+		}
+	}
+
+	/// Validate the input
+	pub fn validate(&self) -> Result<(), Vec<String>> {
+		let mut errors = Vec::new();
+
+		if self.sources.paths.is_empty() {
+			errors.push("At least one source file must be specified".to_string());
+		}
+
+		if self
+			.sources
+			.paths
+			.iter()
+			.any(|p| p.as_local_path().is_none())
+		{
+			errors.push("Only files on this device can be compressed".to_string());
+		}
+
+		match self.archive_path() {
+			None => errors.push("Archives can only be created on this device".to_string()),
+			Some(archive_path) => {
+				// The archive would otherwise try to include itself while it's being written
+				let inside_source = self
+					.sources
+					.paths
+					.iter()
+					.filter_map(SdPath::as_local_path)
+					.any(|source| archive_path.starts_with(source));
+				if inside_source {
+					errors.push(
+						"Cannot create an archive inside a folder being compressed".to_string(),
+					);
+				}
+			}
+		}
+
+		if errors.is_empty() {
+			Ok(())
+		} else {
+			Err(errors)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_archive_path_adds_extension() {
+		let input = FileCompressInput::new(
+			vec!["/photos".into()],
+			"/backups/photos",
+			CompressFormat::TarZst,
+		);
+		assert_eq!(
+			input.archive_path(),
+			Some(PathBuf::from("/backups/photos.tar.zst"))
+		);
+
+		let input = FileCompressInput::new(
+			vec!["/photos".into()],
+			"/backups/Photos.ZIP",
+			CompressFormat::Zip,
+		);
+		assert_eq!(
+			input.archive_path(),
+			Some(PathBuf::from("/backups/Photos.ZIP"))
+		);
+	}
+
+	#[test]
+	fn test_validation() {
+		let input = FileCompressInput::new(Vec::new(), "/out.zip", CompressFormat::Zip);
+		let errors = input.validate().unwrap_err();
+		assert!(errors.iter().any(|e| e.contains("At least one source")));
+
+		let input = FileCompressInput::new(
+			vec!["/photos".into()],
+			"/photos/photos.zip",
+			CompressFormat::Zip,
+		);
+		let errors = input.validate().unwrap_err();
+		assert!(errors.iter().any(|e| e.contains("inside a folder")));
+
+		let input =
+			FileCompressInput::new(vec!["/photos".into()], "/photos.zip", CompressFormat::Zip);
+		assert!(input.validate().is_ok());
+	}
+}
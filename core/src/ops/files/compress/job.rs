@@ -0,0 +1,285 @@
+//! Archive creation job
+//!
+//! Sources are appended to a `.partial` file next to the destination one at a time, with a
+//! checkpoint after each so an interrupted job carries on from the next source. The finished
+//! archive is renamed into place and handed straight to the location responder, so it's
+//! indexed without waiting for the watcher.
+
+use crate::{
+	domain::addressing::{SdPath, SdPathBatch},
+	infra::job::{generic_progress::GenericProgress, prelude::*},
+	ops::{
+		files::{
+			archive::{
+				append_source, finish_archive, run_with_progress, source_size, CompressFormat,
+			},
+			copy::{action::FileConflictResolution, job::resolve_conflict},
+		},
+		indexing::responder,
+	},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+	path::{Path, PathBuf},
+	time::{Duration, Instant},
+};
+
+/// Minimum time between progress updates while a source is being compressed
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Job that compresses files and folders into an archive
+#[derive(Debug, Serialize, Deserialize, Job)]
+pub struct FileCompressJob {
+	pub sources: SdPathBatch,
+	/// Path of the archive to create
+	pub destination: SdPath,
+	pub format: CompressFormat,
+	#[serde(default)]
+	pub conflict_resolution: Option<FileConflictResolution>,
+
+	// Internal state for resumption
+	#[serde(default)]
+	completed_indices: Vec<usize>,
+	/// Archive path after conflict resolution
+	#[serde(default)]
+	target: Option<PathBuf>,
+	/// Length of the partial archive when the last source was finished
+	#[serde(default)]
+	written_len: u64,
+	/// Uncompressed bytes of the finished sources
+	#[serde(default)]
+	bytes_compressed: u64,
+	#[serde(skip, default = "Instant::now")]
+	started_at: Instant,
+}
+
+impl Job for FileCompressJob {
+	const NAME: &'static str = "file_compress";
+	const RESUMABLE: bool = true;
+	const DESCRIPTION: Option<&'static str> = Some("Compress files into an archive");
+}
+
+impl crate::infra::job::traits::DynJob for FileCompressJob {
+	fn job_name(&self) -> &'static str {
+		Self::NAME
+	}
+}
+
+/// Output from the file compress job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileCompressOutput {
+	/// The archive that was written, or None if an existing file was kept
+	pub archive: Option<SdPath>,
+	pub source_count: usize,
+	/// Uncompressed size of the sources
+	pub total_bytes: u64,
+	pub archive_size: u64,
+	pub duration: Duration,
+}
+
+impl From<FileCompressOutput> for JobOutput {
+	fn from(output: FileCompressOutput) -> Self {
+		JobOutput::FileCompress {
+			source_count: output.source_count,
+			total_bytes: output.total_bytes,
+			archive_size: output.archive_size,
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl JobHandler for FileCompressJob {
+	type Output = FileCompressOutput;
+
+	async fn run(&mut self, ctx: JobContext<'_>) -> JobResult<Self::Output> {
+		let sources = self
+			.sources
+			.paths
+			.iter()
+			.map(|source| {
+				source
+					.as_local_path()
+					.map(Path::to_path_buf)
+					.ok_or_else(|| {
+						JobError::execution(format!("{} is not on this device", source.display()))
+					})
+			})
+			.collect::<JobResult<Vec<_>>>()?;
+
+		let target = match self.target.clone() {
+			Some(target) => target,
+			None => {
+				let destination = self.destination.as_local_path().ok_or_else(|| {
+					JobError::execution("Archive destination is not on this device")
+				})?;
+				let Some(target) = resolve_conflict(destination, self.conflict_resolution).await?
+				else {
+					ctx.log(format!(
+						"Skipping existing archive: {}",
+						destination.display()
+					));
+					return Ok(self.output(None, 0));
+				};
+				self.target = Some(target.clone());
+				target
+			}
+		};
+		let partial = partial_path(&target);
+
+		ctx.progress(Progress::indeterminate("Calculating size"));
+		let total_bytes = {
+			let sources = sources.clone();
+			tokio::task::spawn_blocking(move || sources.iter().map(|s| source_size(s)).sum::<u64>())
+				.await
+				.map_err(|e| JobError::execution(e.to_string()))?
+		};
+
+		ctx.log(format!(
+			"Compressing {} sources into {}",
+			sources.len(),
+			target.display()
+		));
+
+		for (index, source) in sources.into_iter().enumerate() {
+			if self.completed_indices.contains(&index) {
+				continue;
+			}
+			ctx.check_interrupt().await?;
+
+			let name = source
+				.file_name()
+				.map(|name| name.to_string_lossy().into_owned())
+				.unwrap_or_else(|| "root".to_string());
+			let current = self.sources.paths[index].clone();
+
+			let mut bytes_done = self.bytes_compressed;
+			let mut last_report = Instant::now();
+			self.report_progress(&ctx, &current, bytes_done, total_bytes);
+
+			let written_len = run_with_progress(
+				{
+					let partial = partial.clone();
+					let (format, written_len) = (self.format, self.written_len);
+					move |on_progress| {
+						append_source(&partial, format, written_len, &source, &name, on_progress)
+					}
+				},
+				|bytes| {
+					bytes_done += bytes;
+					if last_report.elapsed() >= PROGRESS_INTERVAL {
+						last_report = Instant::now();
+						self.report_progress(&ctx, &current, bytes_done, total_bytes);
+					}
+				},
+			)
+			.await
+			.map_err(|e| {
+				JobError::execution(format!(
+					"Failed to add {} to the archive: {}",
+					current.display(),
+					e
+				))
+			})?;
+
+			self.written_len = written_len;
+			self.bytes_compressed = bytes_done;
+			self.completed_indices.push(index);
+			ctx.checkpoint().await?;
+		}
+
+		let archive_size = {
+			let (partial, format, written_len) = (partial.clone(), self.format, self.written_len);
+			tokio::task::spawn_blocking(move || finish_archive(&partial, format, written_len))
+				.await
+				.map_err(|e| JobError::execution(e.to_string()))?
+				.map_err(|e| JobError::execution(format!("Failed to finish archive: {}", e)))?
+		};
+		tokio::fs::rename(&partial, &target).await.map_err(|e| {
+			JobError::execution(format!("Failed to move archive into place: {}", e))
+		})?;
+
+		let library = ctx.library();
+		if let Err(e) =
+			responder::apply_created(library.core_context(), library.id(), vec![target.clone()])
+				.await
+		{
+			ctx.add_warning(format!("Failed to index {}: {}", target.display(), e));
+		}
+
+		ctx.log(format!(
+			"Compressed {} bytes into {} ({} bytes)",
+			self.bytes_compressed,
+			target.display(),
+			archive_size
+		));
+
+		Ok(self.output(Some(SdPath::local(target)), archive_size))
+	}
+}
+
+impl FileCompressJob {
+	/// Create a new compress job
+	pub fn new(sources: SdPathBatch, destination: SdPath, format: CompressFormat) -> Self {
+		Self {
+			sources,
+			destination,
+			format,
+			conflict_resolution: None,
+			completed_indices: Vec::new(),
+			target: None,
+			written_len: 0,
+			bytes_compressed: 0,
+			started_at: Instant::now(),
+		}
+	}
+
+	/// Set how an existing file at the destination is handled
+	pub fn with_conflict_resolution(mut self, resolution: Option<FileConflictResolution>) -> Self {
+		self.conflict_resolution = resolution;
+		self
+	}
+
+	fn report_progress(
+		&self,
+		ctx: &JobContext<'_>,
+		current: &SdPath,
+		bytes_done: u64,
+		total_bytes: u64,
+	) {
+		let completed = self.completed_indices.len() as u64;
+		let total = self.sources.paths.len() as u64;
+		let message = format!("Compressing: {}", current.display());
+
+		let progress = if total_bytes > 0 {
+			GenericProgress::new(
+				(bytes_done as f32 / total_bytes as f32).clamp(0.0, 1.0),
+				"Compressing",
+				message,
+			)
+			.with_bytes(bytes_done, total_bytes)
+		} else {
+			GenericProgress::new(0.0, "Compressing", message).with_completion(completed, total)
+		};
+
+		ctx.progress(Progress::generic(
+			progress.with_current_path(current.clone()),
+		));
+	}
+
+	fn output(&self, archive: Option<SdPath>, archive_size: u64) -> FileCompressOutput {
+		FileCompressOutput {
+			archive,
+			source_count: self.completed_indices.len(),
+			total_bytes: self.bytes_compressed,
+			archive_size,
+			duration: self.started_at.elapsed(),
+		}
+	}
+}
+
+/// Where an archive is written until it's complete
+fn partial_path(target: &Path) -> PathBuf {
+	let mut name = target.file_name().unwrap_or_default().to_os_string();
+	name.push(".partial");
+	target.with_file_name(name)
+}
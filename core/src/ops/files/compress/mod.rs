@@ -0,0 +1,11 @@
+//! Archive creation
+//!
+//! Compresses files and folders into a new zip or tar.zst archive.
+
+pub mod action;
+pub mod input;
+pub mod job;
+
+pub use action::FileCompressAction;
+pub use input::FileCompressInput;
+pub use job::{FileCompressJob, FileCompressOutput};
@@ -15,6 +15,7 @@ use crate::{
 		},
 		job::handle::JobHandle,
 	},
+	ops::action::UndoOperation,
 };
 use serde::{Deserialize, Serialize};
 use specta::Type;
@@ -32,7 +33,7 @@ pub enum FileConflictResolution {
 
 impl FileConflictResolution {
 	/// All available choices for conflict resolution
-	const CHOICES: [Self; 4] = [
+	pub(crate) const CHOICES: [Self; 4] = [
 		Self::Overwrite,
 		Self::AutoModifyName,
 		Self::Skip,
@@ -40,7 +41,7 @@ impl FileConflictResolution {
 	];
 
 	/// Convert to human-readable string
-	fn as_str(&self) -> &'static str {
+	pub(crate) fn as_str(&self) -> &'static str {
 		match self {
 			Self::Overwrite => "Overwrite the existing file",
 			Self::AutoModifyName => "Rename the new file (e.g., file.txt -> file (1).txt)",
@@ -50,7 +51,7 @@ impl FileConflictResolution {
 	}
 
 	/// Create from choice index
-	fn from_index(index: usize) -> Option<Self> {
+	pub(crate) fn from_index(index: usize) -> Option<Self> {
 		Self::CHOICES.get(index).copied()
 	}
 }
@@ -377,6 +378,27 @@ impl LibraryAction for FileCopyAction {
 		}
 	}
 
+	async fn undo_operation(
+		&self,
+		_library: &std::sync::Arc<crate::library::Library>,
+	) -> Result<Option<UndoOperation>, ActionError> {
+		// Only moves can be undone; a copy is reversed by deleting it
+		if !self.options.delete_after_copy {
+			return Ok(None);
+		}
+		let Some(pairs) = self.resolve_move_pairs() else {
+			return Ok(None);
+		};
+
+		Ok(UndoOperation::capture_moves(pairs)
+			.await?
+			.map(|moves| UndoOperation::Move { moves }))
+	}
+
+	fn background_job(output: &Self::Output) -> Option<crate::infra::job::types::JobId> {
+		Some(output.id)
+	}
+
 	async fn execute(
 		mut self,
 		library: std::sync::Arc<crate::library::Library>,
@@ -512,6 +534,32 @@ impl FileCopyAction {
 		Ok(conflicts)
 	}
 
+	/// Where each local source will end up, resolved the same way the job does
+	///
+	/// Returns `None` if any source or the destination is not local.
+	fn resolve_move_pairs(&self) -> Option<Vec<(PathBuf, PathBuf)>> {
+		let dest_path = self.destination.as_local_path()?;
+		let multiple = self.sources.paths.len() > 1;
+
+		self.sources
+			.paths
+			.iter()
+			.map(|source| {
+				let source_path = source.as_local_path()?;
+				let filename = source_path.file_name()?;
+				let target = if dest_path.is_file() {
+					// Dropped onto a file - the job moves next to it
+					dest_path.parent()?.join(filename)
+				} else if dest_path.is_dir() || multiple {
+					dest_path.join(filename)
+				} else {
+					dest_path.to_path_buf()
+				};
+				Some((source_path.to_path_buf(), target))
+			})
+			.collect()
+	}
+
 	/// Check if any destination files would cause conflicts (legacy method)
 	async fn check_for_conflicts(&self) -> Result<Option<PathBuf>, ActionError> {
 		let conflicts = self.check_for_conflicts_detailed().await?;
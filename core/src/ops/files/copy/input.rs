@@ -126,6 +126,7 @@ impl FileCopyInput {
 			move_mode: None, // Will be determined by job system
 			copy_method: self.copy_method.clone(),
 			conflict_resolution: None, // Set by action, not input
+			bandwidth_limit_mbps: None,
 		}
 	}
 
@@ -137,6 +138,15 @@ impl FileCopyInput {
 			errors.push("At least one source file must be specified".to_string());
 		}
 
+		// Archives are read-only, files can only be copied out of them
+		if self.move_files && self.sources.paths.iter().any(SdPath::is_archive) {
+			errors.push("Files inside archives can be copied but not moved".to_string());
+		}
+
+		if self.destination.is_archive() {
+			errors.push("Cannot copy files into an archive".to_string());
+		}
+
 		if errors.is_empty() {
 			Ok(())
 		} else {
@@ -223,4 +233,18 @@ mod tests {
 		let input = FileCopyInput::new(vec!["/file.txt".into()], "/dest/");
 		assert!(input.validate().is_ok());
 	}
+
+	#[test]
+	fn test_validation_archive_sources_are_read_only() {
+		let mut input = FileCopyInput::new(Vec::new(), "/dest/");
+		input.sources.paths.push(SdPath::archive(
+			"test-device".to_string(),
+			"/photos.zip",
+			"2024/beach.jpg",
+		));
+		assert!(input.validate().is_ok());
+
+		let errors = input.clone().with_move(true).validate().unwrap_err();
+		assert!(errors.iter().any(|e| e.contains("not moved")));
+	}
 }
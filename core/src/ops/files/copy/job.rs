@@ -8,6 +8,7 @@ use crate::{
 	domain::addressing::{SdPath, SdPathBatch},
 	infra::job::generic_progress::{GenericProgress, ToGenericProgress},
 	infra::job::prelude::*,
+	ops::files::archive::load_index_async,
 };
 use serde::{Deserialize, Serialize};
 use specta::Type;
@@ -126,6 +127,9 @@ pub struct CopyOptions {
 	pub move_mode: Option<MoveMode>,
 	pub copy_method: CopyMethod,
 	pub conflict_resolution: Option<super::action::FileConflictResolution>,
+	/// Cap on the bandwidth of transfers between devices, in megabits per second
+	#[serde(default)]
+	pub bandwidth_limit_mbps: Option<u32>,
 }
 
 impl Default for CopyOptions {
@@ -138,6 +142,7 @@ impl Default for CopyOptions {
 			move_mode: None,
 			copy_method: CopyMethod::Auto,
 			conflict_resolution: None,
+			bandwidth_limit_mbps: None,
 		}
 	}
 }
@@ -199,6 +204,7 @@ impl JobHandler for FileCopyJob {
 			transfer_rate: 0.0,
 			elapsed: None,
 			strategy_metadata: None,
+			transfer_stats: None,
 		};
 		ctx.progress(Progress::generic(progress.to_generic_progress()));
 
@@ -232,6 +238,7 @@ impl JobHandler for FileCopyJob {
 			transfer_rate: 0.0,
 			elapsed: None,
 			strategy_metadata: None,
+			transfer_stats: None,
 		};
 		ctx.progress(Progress::generic(progress.to_generic_progress()));
 
@@ -294,6 +301,7 @@ impl JobHandler for FileCopyJob {
 			transfer_rate: 0.0,
 			elapsed: None,
 			strategy_metadata: None,
+			transfer_stats: None,
 		};
 		ctx.progress(Progress::generic(progress.to_generic_progress()));
 
@@ -330,6 +338,7 @@ impl JobHandler for FileCopyJob {
 			transfer_rate: 0.0,
 			elapsed: None,
 			strategy_metadata: None,
+			transfer_stats: None,
 		};
 		ctx.progress(Progress::generic(progress.to_generic_progress()));
 
@@ -476,22 +485,27 @@ impl JobHandler for FileCopyJob {
 				transfer_rate: current_rate,
 				elapsed: current_elapsed,
 				strategy_metadata: current_strategy_metadata,
+				transfer_stats: None,
 			};
 			ctx.progress(Progress::generic(progress.to_generic_progress()));
 
 			// 1. Select the strategy with metadata
-			let (strategy, strategy_metadata) = CopyStrategyRouter::select_strategy_with_metadata(
-				&resolved_source,
-				&final_destination,
-				is_move,
-				&self.options.copy_method,
-				volume_manager.as_deref(),
-			)
-			.await;
+			let (mut strategy, strategy_metadata) =
+				CopyStrategyRouter::select_strategy_with_metadata(
+					&resolved_source,
+					&final_destination,
+					is_move,
+					&self.options.copy_method,
+					volume_manager.as_deref(),
+				)
+				.await;
 
 			// Store strategy metadata for progress updates
 			progress_aggregator.set_strategy_metadata(strategy_metadata);
 
+			strategy.limit_bandwidth(self.options.bandwidth_limit_mbps);
+			progress_aggregator.set_transfer_stats(strategy.transfer_stats());
+
 			info!(
 				"[JOB] About to execute strategy for {} -> {}",
 				resolved_source.display(),
@@ -526,7 +540,7 @@ impl JobHandler for FileCopyJob {
 						// Generate unique name if destination exists
 						if let Some(dest_path) = final_destination.as_local_path() {
 							if dest_path.exists() {
-								let unique_dest = self.generate_unique_name(&dest_path).await?;
+								let unique_dest = generate_unique_name(dest_path).await?;
 								SdPath::Physical {
 									device_slug: final_destination
 										.device_slug()
@@ -669,6 +683,7 @@ impl JobHandler for FileCopyJob {
 			transfer_rate: 0.0,
 			elapsed: Some(final_elapsed),
 			strategy_metadata: final_strategy_metadata,
+			transfer_stats: None,
 		};
 		ctx.progress(Progress::generic(progress.to_generic_progress()));
 
@@ -739,10 +754,31 @@ pub struct CopyProgress {
 	/// Strategy metadata for UI display
 	#[serde(default)]
 	pub strategy_metadata: Option<super::routing::CopyStrategyMetadata>,
+	/// Compression and throttling of the current transfer between devices
+	#[serde(default)]
+	pub transfer_stats: Option<RemoteTransferStats>,
 }
 
 impl JobProgress for CopyProgress {}
 
+/// Transport details of a transfer between devices, covering the current source
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct RemoteTransferStats {
+	/// Whether the current file is sent compressed
+	pub compressed: bool,
+	/// File bytes sent or received so far
+	pub bytes_transferred: u64,
+	/// Bytes that crossed the network for them, after compression and deltas
+	pub bytes_on_wire: u64,
+	/// Strictest bandwidth limit on the current file, in bytes per second
+	pub rate_limit: Option<u64>,
+	/// Time spent waiting on bandwidth limits
+	pub throttled: Duration,
+}
+
+/// Transfer statistics a strategy updates while the job reports them
+pub type SharedTransferStats = Arc<Mutex<RemoteTransferStats>>;
+
 /// Progress aggregator that tracks overall copy job progress with real-time speed calculation.
 struct ProgressAggregator<'a> {
 	ctx: &'a JobContext<'a>,
@@ -757,6 +793,7 @@ struct ProgressAggregator<'a> {
 	files_completed: Arc<Mutex<usize>>,
 	speed_tracker: Arc<Mutex<SpeedTracker>>,
 	strategy_metadata: Arc<Mutex<Option<super::routing::CopyStrategyMetadata>>>,
+	transfer_stats: Arc<Mutex<Option<SharedTransferStats>>>,
 }
 
 impl<'a> ProgressAggregator<'a> {
@@ -774,6 +811,7 @@ impl<'a> ProgressAggregator<'a> {
 			files_completed: Arc::new(Mutex::new(0)),
 			speed_tracker: Arc::new(Mutex::new(SpeedTracker::new())),
 			strategy_metadata: Arc::new(Mutex::new(None)),
+			transfer_stats: Arc::new(Mutex::new(None)),
 		}
 	}
 
@@ -789,6 +827,11 @@ impl<'a> ProgressAggregator<'a> {
 		*self.strategy_metadata.lock().unwrap() = Some(metadata);
 	}
 
+	/// Set the transfer statistics of the current strategy, if it moves data between devices
+	fn set_transfer_stats(&mut self, stats: Option<SharedTransferStats>) {
+		*self.transfer_stats.lock().unwrap() = stats;
+	}
+
 	/// Complete the current source item and update index
 	fn complete_source(&mut self) {
 		self.current_file_index += 1;
@@ -814,6 +857,7 @@ impl<'a> ProgressAggregator<'a> {
 		let error_count = self.error_count;
 		let speed_tracker = self.speed_tracker.clone();
 		let strategy_metadata = self.strategy_metadata.clone();
+		let transfer_stats = self.transfer_stats.clone();
 
 		Box::new(move |bytes_value: u64, signal_value: u64| {
 			// Signal: u64::MAX means a file has finished, bytes_value is its size
@@ -850,6 +894,11 @@ impl<'a> ProgressAggregator<'a> {
 
 			let current_strategy_metadata = strategy_metadata.lock().unwrap().clone();
 			let current_src_path = current_source_path.lock().unwrap().clone();
+			let current_transfer_stats = transfer_stats
+				.lock()
+				.unwrap()
+				.as_ref()
+				.map(|stats| stats.lock().unwrap().clone());
 
 			let copy_progress = CopyProgress {
 				phase: CopyPhase::Copying,
@@ -866,6 +915,7 @@ impl<'a> ProgressAggregator<'a> {
 				transfer_rate: rate,
 				elapsed: Some(elapsed),
 				strategy_metadata: current_strategy_metadata,
+				transfer_stats: current_transfer_stats,
 			};
 
 			// Log progress details every 100MB or on file completion
@@ -962,11 +1012,16 @@ impl ToGenericProgress for CopyProgress {
 			progress = progress.with_current_path(path.clone());
 		}
 
-		// Add strategy metadata for UI display
+		// Add strategy metadata and transfer details for UI display
+		let mut metadata = serde_json::Map::new();
 		if let Some(ref strategy_metadata) = self.strategy_metadata {
-			progress = progress.with_metadata(serde_json::json!({
-				"strategy": strategy_metadata
-			}));
+			metadata.insert("strategy".to_string(), serde_json::json!(strategy_metadata));
+		}
+		if let Some(ref transfer_stats) = self.transfer_stats {
+			metadata.insert("transfer".to_string(), serde_json::json!(transfer_stats));
+		}
+		if !metadata.is_empty() {
+			progress = progress.with_metadata(serde_json::Value::Object(metadata));
 		}
 
 		progress
@@ -1042,6 +1097,7 @@ impl FileCopyJob {
 			SdPath::Cloud { .. } => panic!("Cloud storage operations are not yet implemented"),
 			SdPath::Content { .. } => panic!("Cannot rename a content-addressed path"),
 			SdPath::Sidecar { .. } => panic!("Cannot rename a sidecar path"),
+			SdPath::Archive { .. } => panic!("Cannot rename a path inside an archive"),
 		};
 
 		Self::new_move(
@@ -1051,6 +1107,32 @@ impl FileCopyJob {
 		)
 	}
 
+	/// Size of the archive holding a member on another device, from its synced entry
+	async fn remote_archive_size(&self, ctx: &JobContext<'_>, source: &SdPath) -> u64 {
+		use crate::ops::indexing::PathResolver;
+
+		let SdPath::Archive {
+			device_slug,
+			archive_path,
+			..
+		} = source
+		else {
+			return 0;
+		};
+
+		let archive = SdPath::physical(device_slug.clone(), archive_path.clone());
+		match PathResolver::resolve_to_entry(ctx.library_db(), &archive).await {
+			Ok(Some(entry)) => entry.size as u64,
+			Ok(None) | Err(_) => {
+				ctx.log(format!(
+					"Warning: Could not find metadata for remote archive: {}",
+					archive.display()
+				));
+				0
+			}
+		}
+	}
+
 	/// Calculate total size for progress reporting
 	async fn calculate_total_size(&self, ctx: &JobContext<'_>) -> JobResult<u64> {
 		use crate::ops::indexing::PathResolver;
@@ -1061,6 +1143,14 @@ impl FileCopyJob {
 			if let Some(local_path) = source.as_local_path() {
 				// Local path - calculate directly from filesystem
 				total += self.get_path_size(local_path).await.unwrap_or(0);
+			} else if let Some((archive_path, inner_path)) = source.as_local_archive() {
+				// Archive member - uncompressed size from the archive listing
+				if let Ok(index) = load_index_async(archive_path).await {
+					total += index.size_of(inner_path);
+				}
+			} else if source.is_archive() {
+				// Archive on another device - the whole archive is pulled before extracting
+				total += self.remote_archive_size(ctx, source).await;
 			} else {
 				// Non-local path - query database for synced metadata
 				match PathResolver::resolve_to_entry(ctx.library_db(), source).await {
@@ -1162,6 +1252,18 @@ impl FileCopyJob {
 					.and_then(|e| e.uuid);
 
 				(size, metadata.is_dir(), entry_id)
+			} else if let Some((archive_path, inner_path)) = resolved_source.as_local_archive() {
+				// Archive member - read from the archive listing
+				let index = load_index_async(archive_path)
+					.await
+					.map_err(|e| JobError::execution(format!("Failed to read archive: {}", e)))?;
+				let is_dir = index.get(inner_path).is_some_and(|entry| entry.is_dir);
+				(index.size_of(inner_path), is_dir, None)
+			} else if resolved_source.is_archive() {
+				// Archive on another device - its listing isn't readable from here, so the
+				// member is sized by the archive that gets pulled
+				let size = self.remote_archive_size(ctx, &resolved_source).await;
+				(size, false, None)
 			} else {
 				// Remote path - query database for synced metadata
 				match PathResolver::resolve_to_entry(ctx.library_db(), &resolved_source).await {
@@ -1307,44 +1409,6 @@ impl FileCopyJob {
 		Ok(total)
 	}
 
-	/// Generate a unique filename by appending (1), (2), etc.
-	async fn generate_unique_name(&self, dest_path: &std::path::Path) -> JobResult<PathBuf> {
-		let mut counter = 1;
-		let mut new_path = dest_path.to_path_buf();
-
-		while tokio::fs::metadata(&new_path).await.is_ok() {
-			if let Some(parent) = dest_path.parent() {
-				if let Some(file_name) = dest_path.file_name() {
-					let file_name_str = file_name.to_string_lossy();
-
-					// Split filename and extension
-					if let Some(dot_pos) = file_name_str.rfind('.') {
-						let name = &file_name_str[..dot_pos];
-						let ext = &file_name_str[dot_pos..];
-						new_path = parent.join(format!("{} ({}){}", name, counter, ext));
-					} else {
-						// No extension
-						new_path = parent.join(format!("{} ({})", file_name_str, counter));
-					}
-				} else {
-					return Err(JobError::execution("Could not get filename"));
-				}
-			} else {
-				return Err(JobError::execution("Could not get parent directory"));
-			}
-
-			counter += 1;
-
-			if counter > 1000 {
-				return Err(JobError::execution(
-					"Could not generate unique filename after 1000 attempts",
-				));
-			}
-		}
-
-		Ok(new_path)
-	}
-
 	/// Delete source file after successful cross-volume move
 	async fn delete_source_file(&self, source: &std::path::Path) -> Result<(), std::io::Error> {
 		let metadata = tokio::fs::metadata(source).await?;
@@ -1359,6 +1423,73 @@ impl FileCopyJob {
 	}
 }
 
+/// Apply a conflict resolution to a path a job is about to create
+///
+/// Returns the path to write to, or `None` if the existing file should be left alone.
+pub(crate) async fn resolve_conflict(
+	dest_path: &std::path::Path,
+	resolution: Option<super::action::FileConflictResolution>,
+) -> JobResult<Option<PathBuf>> {
+	use super::action::FileConflictResolution;
+
+	if tokio::fs::symlink_metadata(dest_path).await.is_err() {
+		return Ok(Some(dest_path.to_path_buf()));
+	}
+
+	match resolution {
+		Some(FileConflictResolution::Overwrite) => Ok(Some(dest_path.to_path_buf())),
+		Some(FileConflictResolution::AutoModifyName) => {
+			generate_unique_name(dest_path).await.map(Some)
+		}
+		Some(FileConflictResolution::Skip) => Ok(None),
+		Some(FileConflictResolution::Abort) => {
+			Err(JobError::execution("Operation aborted by user"))
+		}
+		None => Err(JobError::execution(format!(
+			"{} already exists",
+			dest_path.display()
+		))),
+	}
+}
+
+/// Generate a unique filename by appending (1), (2), etc.
+pub(crate) async fn generate_unique_name(dest_path: &std::path::Path) -> JobResult<PathBuf> {
+	let mut counter = 1;
+	let mut new_path = dest_path.to_path_buf();
+
+	while tokio::fs::metadata(&new_path).await.is_ok() {
+		if let Some(parent) = dest_path.parent() {
+			if let Some(file_name) = dest_path.file_name() {
+				let file_name_str = file_name.to_string_lossy();
+
+				// Split filename and extension
+				if let Some(dot_pos) = file_name_str.rfind('.') {
+					let name = &file_name_str[..dot_pos];
+					let ext = &file_name_str[dot_pos..];
+					new_path = parent.join(format!("{} ({}){}", name, counter, ext));
+				} else {
+					// No extension
+					new_path = parent.join(format!("{} ({})", file_name_str, counter));
+				}
+			} else {
+				return Err(JobError::execution("Could not get filename"));
+			}
+		} else {
+			return Err(JobError::execution("Could not get parent directory"));
+		}
+
+		counter += 1;
+
+		if counter > 1000 {
+			return Err(JobError::execution(
+				"Could not generate unique filename after 1000 attempts",
+			));
+		}
+	}
+
+	Ok(new_path)
+}
+
 /// Output from file copy job
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FileCopyOutput {
@@ -1485,6 +1616,7 @@ impl MoveJob {
 			SdPath::Cloud { .. } => panic!("Cloud storage operations are not yet implemented"),
 			SdPath::Content { .. } => panic!("Cannot rename a content-addressed path"),
 			SdPath::Sidecar { .. } => panic!("Cannot rename a sidecar path"),
+			SdPath::Archive { .. } => panic!("Cannot rename a path inside an archive"),
 		};
 
 		Self::new(
@@ -1663,6 +1795,7 @@ mod tests {
 			transfer_rate: 10.0 * 1024.0 * 1024.0, // 10 MB/s
 			elapsed: Some(Duration::from_secs(5)),
 			strategy_metadata: None,
+			transfer_stats: None,
 		};
 
 		let generic = progress.to_generic_progress();
@@ -6,8 +6,8 @@
 use super::{
 	input::CopyMethod,
 	strategy::{
-		CopyStrategy, FastCopyStrategy, LocalMoveStrategy, LocalStreamCopyStrategy,
-		RemoteTransferStrategy,
+		ArchiveExtractStrategy, CopyStrategy, FastCopyStrategy, LocalMoveStrategy,
+		LocalStreamCopyStrategy, RemoteArchiveStrategy, RemoteTransferStrategy,
 	},
 };
 use crate::{domain::addressing::SdPath, volume::VolumeManager};
@@ -19,7 +19,8 @@ use tracing::info;
 /// Metadata about the selected copy strategy for UI display.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct CopyStrategyMetadata {
-	/// Internal strategy name (e.g., "LocalMove", "FastCopy", "LocalStream", "RemoteTransfer")
+	/// Internal strategy name (e.g., "LocalMove", "FastCopy", "LocalStream", "RemoteTransfer",
+	/// "ArchiveExtract", "RemoteArchive")
 	pub strategy_name: String,
 	/// Human-readable description (e.g., "Atomic move (same storage)")
 	pub strategy_description: String,
@@ -50,6 +51,16 @@ impl CopyStrategyRouter {
 			destination.device_slug()
 		);
 
+		// Files inside archives are always extracted, on this device
+		if source.is_archive() {
+			if archive_is_cross_device(source, destination) {
+				info!("[ROUTING] Archive on another device detected - selecting RemoteArchiveStrategy");
+				return Box::new(RemoteArchiveStrategy::default());
+			}
+			info!("[ROUTING] Archive source detected - selecting ArchiveExtractStrategy");
+			return Box::new(ArchiveExtractStrategy);
+		}
+
 		// Cross-device transfer - always use network strategy
 		// Compare device slugs to detect if paths are on different devices
 		let is_cross_device = match (source.device_slug(), destination.device_slug()) {
@@ -59,7 +70,7 @@ impl CopyStrategyRouter {
 
 		if is_cross_device {
 			info!("[ROUTING] Cross-device detected - selecting RemoteTransferStrategy");
-			return Box::new(RemoteTransferStrategy);
+			return Box::new(RemoteTransferStrategy::default());
 		}
 
 		info!("[ROUTING] Same device detected - selecting local strategy");
@@ -141,6 +152,30 @@ impl CopyStrategyRouter {
 		copy_method: &CopyMethod,
 		volume_manager: Option<&VolumeManager>,
 	) -> (Box<dyn CopyStrategy>, CopyStrategyMetadata) {
+		if source.is_archive() {
+			if archive_is_cross_device(source, destination) {
+				let metadata = CopyStrategyMetadata {
+					strategy_name: "RemoteArchive".to_string(),
+					strategy_description: "Extract from archive across devices".to_string(),
+					is_cross_device: true,
+					is_cross_volume: false,
+					is_fast_operation: false,
+					copy_method: copy_method.clone(),
+				};
+				return (Box::new(RemoteArchiveStrategy::default()), metadata);
+			}
+
+			let metadata = CopyStrategyMetadata {
+				strategy_name: "ArchiveExtract".to_string(),
+				strategy_description: "Extract from archive".to_string(),
+				is_cross_device: false,
+				is_cross_volume: false,
+				is_fast_operation: false,
+				copy_method: copy_method.clone(),
+			};
+			return (Box::new(ArchiveExtractStrategy), metadata);
+		}
+
 		let is_cross_device = match (source.device_slug(), destination.device_slug()) {
 			(Some(src_slug), Some(dst_slug)) => src_slug != dst_slug,
 			_ => false,
@@ -160,7 +195,7 @@ impl CopyStrategyRouter {
 				is_fast_operation: false,
 				copy_method: copy_method.clone(),
 			};
-			return (Box::new(RemoteTransferStrategy), metadata);
+			return (Box::new(RemoteTransferStrategy::default()), metadata);
 		}
 
 		// Same device - check storage topology
@@ -332,6 +367,14 @@ impl CopyStrategyRouter {
 		copy_method: &CopyMethod,
 		volume_manager: Option<&VolumeManager>,
 	) -> String {
+		if source.is_archive() {
+			return if archive_is_cross_device(source, destination) {
+				"Extract from archive across devices".to_string()
+			} else {
+				"Extract from archive".to_string()
+			};
+		}
+
 		// Check if cross-device using device slugs
 		let is_cross_device = match (source.device_slug(), destination.device_slug()) {
 			(Some(src_slug), Some(dst_slug)) => src_slug != dst_slug,
@@ -430,6 +473,20 @@ impl CopyStrategyRouter {
 		copy_method: &CopyMethod,
 		volume_manager: Option<&VolumeManager>,
 	) -> PerformanceEstimate {
+		if source.is_archive() {
+			let requires_network = archive_is_cross_device(source, destination);
+			return PerformanceEstimate {
+				speed_category: if requires_network {
+					SpeedCategory::Network
+				} else {
+					SpeedCategory::LocalDisk
+				},
+				supports_resume: false,
+				requires_network,
+				is_atomic: false,
+			};
+		}
+
 		// Cross-device transfers always use network
 		let is_cross_device = match (source.device_slug(), destination.device_slug()) {
 			(Some(src_slug), Some(dst_slug)) => src_slug != dst_slug,
@@ -524,6 +581,12 @@ impl CopyStrategyRouter {
 	}
 }
 
+/// Whether copying out of an archive crosses devices, which is the case unless both the
+/// archive and the destination are on this device
+fn archive_is_cross_device(source: &SdPath, destination: &SdPath) -> bool {
+	source.as_local_archive().is_none() || destination.as_local_path().is_none()
+}
+
 /// Performance characteristics of a copy strategy
 #[derive(Debug, Clone)]
 pub struct PerformanceEstimate {
@@ -545,3 +608,50 @@ pub enum SpeedCategory {
 	/// Network transfers
 	Network,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::device::get_current_device_slug;
+
+	#[tokio::test]
+	async fn test_archive_sources_route_by_device() {
+		let here = get_current_device_slug();
+		let dest = SdPath::local("/dest/beach.jpg");
+
+		let (_, local) = CopyStrategyRouter::select_strategy_with_metadata(
+			&SdPath::archive(here, "/photos.zip", "beach.jpg"),
+			&dest,
+			false,
+			&CopyMethod::Auto,
+			None,
+		)
+		.await;
+		assert_eq!(local.strategy_name, "ArchiveExtract");
+		assert!(!local.is_cross_device);
+
+		let remote_archive =
+			SdPath::archive("other-device".to_string(), "/photos.zip", "beach.jpg");
+		let (_, remote) = CopyStrategyRouter::select_strategy_with_metadata(
+			&remote_archive,
+			&dest,
+			false,
+			&CopyMethod::Auto,
+			None,
+		)
+		.await;
+		assert_eq!(remote.strategy_name, "RemoteArchive");
+		assert!(remote.is_cross_device);
+		assert!(
+			CopyStrategyRouter::estimate_performance(
+				&remote_archive,
+				&dest,
+				false,
+				&CopyMethod::Auto,
+				None
+			)
+			.await
+			.requires_network
+		);
+	}
+}
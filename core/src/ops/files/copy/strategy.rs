@@ -1,6 +1,6 @@
 //! # Copy Strategy Implementations
 //!
-//! `core::ops::files::copy::strategy` provides 4 specialized copy strategies, each optimized
+//! `core::ops::files::copy::strategy` provides 6 specialized copy strategies, each optimized
 //! for specific scenarios. The router selects strategies based on user preferences and system
 //! topology to maximize performance while respecting user intent.
 //!
@@ -17,8 +17,12 @@
 //! 2. **`FastCopyStrategy`** - CoW-optimized copy (APFS clones, Btrfs reflinks)
 //! 3. **`LocalStreamCopyStrategy`** - Chunked streaming with progress (cross-volume)
 //! 4. **`RemoteTransferStrategy`** - Encrypted network transfer (cross-device)
+//! 5. **`ArchiveExtractStrategy`** - Extraction of files inside an archive
+//! 6. **`RemoteArchiveStrategy`** - Extraction across devices, staged through this one
 //!
-//! The router picks RemoteTransferStrategy for cross-device transfers, LocalMoveStrategy for
+//! The router picks ArchiveExtractStrategy when the source is inside an archive and both it
+//! and the destination are on this device, RemoteArchiveStrategy for other archive sources,
+//! RemoteTransferStrategy for cross-device transfers, LocalMoveStrategy for
 //! same-volume moves, and FastCopyStrategy for same-volume copies in Atomic mode. Streaming
 //! mode or cross-volume operations use LocalStreamCopyStrategy for progress tracking.
 //!
@@ -38,7 +42,20 @@
 //! ```
 
 use crate::{
-	domain::addressing::SdPath, infra::job::prelude::*, ops::files::copy::job::CopyPhase,
+	domain::addressing::SdPath,
+	infra::job::prelude::*,
+	ops::files::{
+		archive::{extract, load_index_async},
+		copy::job::{CopyPhase, SharedTransferStats},
+	},
+	service::network::protocol::{
+		compression::{decompress_chunk, CompressionStats},
+		file_delta::{
+			is_delta_basis, send_delta, DeltaOp, DeltaWriter, FileSignature, MIN_DELTA_FILE_SIZE,
+		},
+		file_transfer::{encode_message, read_message, FileTransferMessage},
+		TransferCapabilities,
+	},
 	volume::VolumeManager,
 };
 use anyhow::Result;
@@ -69,6 +86,14 @@ pub trait CopyStrategy: Send + Sync {
 		verify_checksum: bool,
 		progress_callback: Option<&ProgressCallback<'a>>,
 	) -> Result<u64>;
+
+	/// Cap the bandwidth of transfers between devices, in megabits per second
+	fn limit_bandwidth(&mut self, _limit_mbps: Option<u32>) {}
+
+	/// Live transport statistics, for strategies that transfer between devices
+	fn transfer_stats(&self) -> Option<SharedTransferStats> {
+		None
+	}
 }
 
 /// Strategy for an atomic move on the same volume
@@ -251,8 +276,238 @@ impl CopyStrategy for FastCopyStrategy {
 	}
 }
 
+/// Strategy for copying a file or folder out of an archive on this device
+pub struct ArchiveExtractStrategy;
+
+#[async_trait]
+impl CopyStrategy for ArchiveExtractStrategy {
+	async fn execute<'a>(
+		&self,
+		ctx: &JobContext<'a>,
+		source: &SdPath,
+		destination: &SdPath,
+		_verify_checksum: bool,
+		progress_callback: Option<&ProgressCallback<'a>>,
+	) -> Result<u64> {
+		let (archive_path, inner_path) = source
+			.as_local_archive()
+			.ok_or_else(|| anyhow::anyhow!("Source archive is not on this device"))?;
+		let dest_path = destination
+			.as_local_path()
+			.ok_or_else(|| anyhow::anyhow!("Destination path is not local"))?;
+
+		let bytes_written =
+			extract_archive_member(archive_path, inner_path, dest_path, progress_callback).await?;
+
+		ctx.log(format!(
+			"Extracted {} -> {} ({} bytes)",
+			source.display(),
+			dest_path.display(),
+			bytes_written
+		));
+
+		Ok(bytes_written)
+	}
+}
+
+/// Strategy for copying out of an archive when the archive and the destination are on
+/// different devices. Archive members can only be read on the device holding the archive,
+/// so a remote archive is pulled here before extracting, and a member bound for another
+/// device is extracted here and then pushed.
+#[derive(Default)]
+pub struct RemoteArchiveStrategy {
+	transfer: RemoteTransferStrategy,
+}
+
+impl RemoteArchiveStrategy {
+	/// Pull a whole archive from another device, then extract the member locally
+	async fn pull_and_extract<'a>(
+		&self,
+		ctx: &JobContext<'a>,
+		source: &SdPath,
+		dest_path: &Path,
+		verify_checksum: bool,
+		progress_callback: Option<&ProgressCallback<'a>>,
+	) -> Result<u64> {
+		let SdPath::Archive {
+			device_slug,
+			archive_path,
+			inner_path,
+		} = source
+		else {
+			return Err(anyhow::anyhow!("Source is not inside an archive"));
+		};
+
+		// Stage the archive next to the destination so it lands on the same volume
+		let staging_root = dest_path.parent().unwrap_or(dest_path);
+		fs::create_dir_all(staging_root).await?;
+		let staging = tempfile::Builder::new()
+			.prefix(".sd-archive-")
+			.tempdir_in(staging_root)?;
+		let staged_archive = staging.path().join(
+			archive_path
+				.file_name()
+				.ok_or_else(|| anyhow::anyhow!("Archive path has no file name"))?,
+		);
+
+		ctx.log(format!(
+			"Pulling archive {} from device:{} to extract {}",
+			archive_path.display(),
+			device_slug,
+			inner_path
+		));
+		self.transfer
+			.execute(
+				ctx,
+				&SdPath::physical(device_slug.clone(), archive_path.clone()),
+				&SdPath::local(&staged_archive),
+				verify_checksum,
+				progress_callback,
+			)
+			.await?;
+
+		extract_archive_member(&staged_archive, inner_path, dest_path, progress_callback).await
+	}
+
+	/// Extract a member of a local archive, then push it to another device
+	async fn extract_and_push<'a>(
+		&self,
+		ctx: &JobContext<'a>,
+		archive_path: &Path,
+		inner_path: &str,
+		destination: &SdPath,
+		verify_checksum: bool,
+		progress_callback: Option<&ProgressCallback<'a>>,
+	) -> Result<u64> {
+		let index = load_index_async(archive_path).await?;
+		if index.get(inner_path).is_some_and(|entry| entry.is_dir) {
+			return Err(anyhow::anyhow!(
+				"Folders inside archives can only be copied to the device holding the archive"
+			));
+		}
+
+		let staging = tempfile::Builder::new().prefix("sd-archive-").tempdir()?;
+		let name = inner_path.rsplit('/').next().unwrap_or(inner_path);
+		let staged_member = staging.path().join(name);
+		extract_archive_member(archive_path, inner_path, &staged_member, None).await?;
+
+		self.transfer
+			.execute(
+				ctx,
+				&SdPath::local(&staged_member),
+				destination,
+				verify_checksum,
+				progress_callback,
+			)
+			.await
+	}
+}
+
+#[async_trait]
+impl CopyStrategy for RemoteArchiveStrategy {
+	async fn execute<'a>(
+		&self,
+		ctx: &JobContext<'a>,
+		source: &SdPath,
+		destination: &SdPath,
+		verify_checksum: bool,
+		progress_callback: Option<&ProgressCallback<'a>>,
+	) -> Result<u64> {
+		let bytes_written = match (source.as_local_archive(), destination.as_local_path()) {
+			(None, Some(dest_path)) => {
+				self.pull_and_extract(ctx, source, dest_path, verify_checksum, progress_callback)
+					.await?
+			}
+			(Some((archive_path, inner_path)), None) => {
+				self.extract_and_push(
+					ctx,
+					archive_path,
+					inner_path,
+					destination,
+					verify_checksum,
+					progress_callback,
+				)
+				.await?
+			}
+			(Some(_), Some(_)) => {
+				return ArchiveExtractStrategy
+					.execute(ctx, source, destination, verify_checksum, progress_callback)
+					.await
+			}
+			(None, None) => {
+				return Err(anyhow::anyhow!(
+					"Cannot copy out of an archive when neither it nor the destination is on this device"
+				))
+			}
+		};
+
+		ctx.log(format!(
+			"Extracted {} -> {} ({} bytes)",
+			source.display(),
+			destination.display(),
+			bytes_written
+		));
+
+		Ok(bytes_written)
+	}
+
+	fn limit_bandwidth(&mut self, limit_mbps: Option<u32>) {
+		self.transfer.limit_bandwidth(limit_mbps);
+	}
+
+	fn transfer_stats(&self) -> Option<SharedTransferStats> {
+		self.transfer.transfer_stats()
+	}
+}
+
+/// Extract a file or folder out of an archive on this device, reporting progress against
+/// its uncompressed size
+async fn extract_archive_member<'a>(
+	archive_path: &Path,
+	inner_path: &str,
+	dest_path: &Path,
+	progress_callback: Option<&ProgressCallback<'a>>,
+) -> Result<u64> {
+	let total = load_index_async(archive_path).await?.size_of(inner_path);
+	if let Some(callback) = progress_callback {
+		callback(0, total);
+	}
+
+	// Extraction is blocking, so progress is relayed back from the blocking thread
+	let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+	let extraction = tokio::task::spawn_blocking({
+		let archive_path = archive_path.to_path_buf();
+		let inner_path = inner_path.to_string();
+		let dest_path = dest_path.to_path_buf();
+		move || {
+			extract(&archive_path, &inner_path, &dest_path, |bytes| {
+				let _ = progress_tx.send(bytes);
+			})
+		}
+	});
+
+	let mut extracted = 0;
+	while let Some(bytes) = progress_rx.recv().await {
+		extracted += bytes;
+		if let Some(callback) = progress_callback {
+			callback(extracted, total);
+		}
+	}
+	let bytes_written = extraction.await??;
+
+	if let Some(callback) = progress_callback {
+		callback(bytes_written, u64::MAX);
+	}
+
+	Ok(bytes_written)
+}
+
 /// Strategy for transferring a file to/from another device
-pub struct RemoteTransferStrategy;
+#[derive(Default)]
+pub struct RemoteTransferStrategy {
+	bandwidth_limit_mbps: Option<u32>,
+	stats: SharedTransferStats,
+}
 
 impl RemoteTransferStrategy {
 	/// Detect the transfer direction based on source and destination paths.
@@ -392,6 +647,8 @@ impl RemoteTransferStrategy {
 				.map(|p| p.to_string_lossy().to_string())
 				.unwrap_or_default(),
 			file_metadata,
+			self.bandwidth_limit_mbps,
+			&self.stats,
 			ctx,
 			progress_callback,
 		)
@@ -502,6 +759,20 @@ impl RemoteTransferStrategy {
 			.await
 			.map_err(|e| anyhow::anyhow!("Failed to open bidirectional stream: {}", e))?;
 
+		// A local copy lets the source send only what changed
+		let basis_path =
+			if local_dest_path.is_dir() || local_dest_path.to_string_lossy().ends_with('/') {
+				source_path
+					.file_name()
+					.map(|name| local_dest_path.join(name))
+			} else {
+				Some(local_dest_path.to_path_buf())
+			};
+		let basis = match &basis_path {
+			Some(path) if is_delta_basis(path).await => FileSignature::of_file(path).await.ok(),
+			_ => None,
+		};
+
 		// Send PullRequest
 		let transfer_id = uuid::Uuid::new_v4();
 		let current_device_id = crate::device::get_current_device_id();
@@ -510,9 +781,15 @@ impl RemoteTransferStrategy {
 				transfer_id,
 				source_path: source_path.clone(),
 				requested_by: current_device_id,
+				capabilities: Some(TransferCapabilities {
+					delta: basis.is_some(),
+					compression: true,
+				}),
+				basis: basis.clone(),
+				rate_limit_mbps: self.bandwidth_limit_mbps,
 			};
 
-		let request_data = rmp_serde::to_vec(&pull_request)?;
+		let request_data = encode_message(&pull_request)?;
 
 		ctx.log(format!(
 			"Sending PullRequest {} for path: {}",
@@ -543,17 +820,18 @@ impl RemoteTransferStrategy {
 		let response: crate::service::network::protocol::file_transfer::FileTransferMessage =
 			rmp_serde::from_slice(&msg_buf)?;
 
-		let file_metadata = match response {
+		let (file_metadata, enabled) = match response {
 			crate::service::network::protocol::file_transfer::FileTransferMessage::PullResponse {
 				accepted: true,
 				file_metadata: Some(metadata),
+				capabilities,
 				..
 			} => {
 				ctx.log(format!(
 					"PullRequest accepted: {} bytes",
 					metadata.size
 				));
-				metadata
+				(metadata, capabilities.unwrap_or_default())
 			}
 			crate::service::network::protocol::file_transfer::FileTransferMessage::PullResponse {
 				accepted: false,
@@ -572,6 +850,30 @@ impl RemoteTransferStrategy {
 
 		let file_size = file_metadata.size;
 
+		// Throttling happens on the source, which was asked to respect our limit
+		let base_stats = {
+			let mut stats = self.stats.lock().unwrap();
+			stats.compressed = enabled.compression;
+			stats.rate_limit = self
+				.bandwidth_limit_mbps
+				.and_then(crate::service::network::protocol::bandwidth::mbps_to_bytes);
+			(stats.bytes_transferred, stats.bytes_on_wire)
+		};
+
+		if let (true, Some(basis_path), Some(basis)) = (enabled.delta, basis_path, basis) {
+			return receive_pull_delta(
+				ctx,
+				&mut recv_stream,
+				&basis_path,
+				&basis,
+				file_size,
+				verify_checksum,
+				&self.stats,
+				progress_callback,
+			)
+			.await;
+		}
+
 		// Ensure parent directory exists
 		if let Some(parent) = local_dest_path.parent() {
 			fs::create_dir_all(parent).await?;
@@ -603,6 +905,7 @@ impl RemoteTransferStrategy {
 			None
 		};
 		let mut total_bytes_received = 0u64;
+		let mut bytes_on_wire = 0u64;
 
 		ctx.log(format!(
 			"Receiving file chunks to: {}",
@@ -644,8 +947,22 @@ impl RemoteTransferStrategy {
 					chunk_index,
 					data,
 					chunk_checksum,
+					compression,
 					..
 				} => {
+					bytes_on_wire += data.len() as u64;
+					let data = match decompress_chunk(data, compression) {
+						Ok(data) => data,
+						Err(e) => {
+							let _ = fs::remove_file(&final_dest_path).await;
+							return Err(anyhow::anyhow!(
+								"Failed to decompress chunk {}: {}",
+								chunk_index,
+								e
+							));
+						}
+					};
+
 					// Verify chunk checksum
 					let calculated = blake3::hash(&data);
 					if calculated.as_bytes() != &chunk_checksum {
@@ -661,6 +978,11 @@ impl RemoteTransferStrategy {
 						h.update(&data);
 					}
 					total_bytes_received += data.len() as u64;
+					{
+						let mut stats = self.stats.lock().unwrap();
+						stats.bytes_transferred = base_stats.0 + total_bytes_received;
+						stats.bytes_on_wire = base_stats.1 + bytes_on_wire;
+					}
 
 					// Progress callback
 					if let Some(cb) = progress_callback {
@@ -796,6 +1118,14 @@ impl CopyStrategy for RemoteTransferStrategy {
 			}
 		}
 	}
+
+	fn limit_bandwidth(&mut self, limit_mbps: Option<u32>) {
+		self.bandwidth_limit_mbps = limit_mbps;
+	}
+
+	fn transfer_stats(&self) -> Option<SharedTransferStats> {
+		Some(self.stats.clone())
+	}
 }
 
 /// Helper function to get size of a path (file or directory)
@@ -1077,6 +1407,179 @@ async fn calculate_file_checksum(path: &Path) -> Result<String> {
 		.map_err(|e| anyhow::anyhow!("Failed to generate content hash: {}", e))
 }
 
+/// How long a PUSH waits for the receiver to answer its capabilities
+///
+/// Receivers that predate capability negotiation never answer, and get the whole file.
+/// The file transfer handler remembers them, so only the first PUSH to one waits.
+const NEGOTIATION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Read the receiver's answer to a TransferRequest, returning the features it enabled and
+/// the signature of its existing copy if it accepted a delta, or `None` if it never
+/// answered
+async fn negotiate_push<R>(
+	recv_stream: &mut R,
+) -> Result<Option<(TransferCapabilities, Option<FileSignature>)>>
+where
+	R: tokio::io::AsyncRead + Unpin,
+{
+	let response = match tokio::time::timeout(NEGOTIATION_TIMEOUT, read_message(recv_stream)).await
+	{
+		Ok(response) => response?,
+		Err(_) => return Ok(None),
+	};
+
+	match response {
+		FileTransferMessage::TransferResponse {
+			accepted: false,
+			reason,
+			..
+		} => Err(anyhow::anyhow!(
+			"Transfer rejected: {}",
+			reason.unwrap_or_else(|| "no reason given".to_string())
+		)),
+		FileTransferMessage::TransferResponse {
+			capabilities: Some(enabled @ TransferCapabilities { delta: true, .. }),
+			..
+		} => match read_message(recv_stream).await? {
+			FileTransferMessage::BlockSignatures { signature, .. } => {
+				Ok(Some((enabled, Some(signature))))
+			}
+			FileTransferMessage::TransferError { message, .. } => {
+				Err(anyhow::anyhow!("Transfer error: {}", message))
+			}
+			_ => Err(anyhow::anyhow!("Expected block signatures from receiver")),
+		},
+		FileTransferMessage::TransferResponse { capabilities, .. } => {
+			Ok(Some((capabilities.unwrap_or_default(), None)))
+		}
+		_ => Err(anyhow::anyhow!("Unexpected response to transfer request")),
+	}
+}
+
+/// Rebuild a PULLed file from the delta the source sent against our copy at `basis_path`
+///
+/// The rebuilt file only replaces the copy once it matches the source's content hash.
+async fn receive_pull_delta<'a, R>(
+	ctx: &JobContext<'a>,
+	recv_stream: &mut R,
+	basis_path: &Path,
+	basis: &FileSignature,
+	file_size: u64,
+	verify_checksum: bool,
+	stats: &SharedTransferStats,
+	progress_callback: Option<&ProgressCallback<'a>>,
+) -> Result<u64>
+where
+	R: tokio::io::AsyncRead + Unpin,
+{
+	let base_stats = {
+		let stats = stats.lock().unwrap();
+		(stats.bytes_transferred, stats.bytes_on_wire)
+	};
+	let mut literal_bytes = 0u64;
+
+	ctx.log(format!(
+		"Receiving delta against {} ({} blocks)",
+		basis_path.display(),
+		basis.blocks.len()
+	));
+
+	let mut writer = DeltaWriter::create(basis_path, basis).await?;
+	let final_checksum = loop {
+		let message = match read_message(recv_stream).await {
+			Ok(message) => message,
+			Err(e) => {
+				writer.abort().await;
+				return Err(anyhow::anyhow!("Delta transfer interrupted: {}", e));
+			}
+		};
+
+		match message {
+			FileTransferMessage::DeltaChunk { ops, .. } => {
+				literal_bytes += ops
+					.iter()
+					.map(|op| match op {
+						DeltaOp::Literal(data) => data.len() as u64,
+						DeltaOp::Copy { .. } => 0,
+					})
+					.sum::<u64>();
+				if let Err(e) = writer.apply(ops).await {
+					writer.abort().await;
+					return Err(anyhow::anyhow!("Failed to apply delta: {}", e));
+				}
+				{
+					let mut stats = stats.lock().unwrap();
+					stats.bytes_transferred = base_stats.0 + writer.written();
+					stats.bytes_on_wire = base_stats.1 + literal_bytes;
+				}
+				if let Some(callback) = progress_callback {
+					callback(writer.written(), file_size);
+				}
+			}
+			FileTransferMessage::TransferComplete {
+				final_checksum,
+				total_bytes,
+				..
+			} => {
+				if total_bytes != writer.written() {
+					writer.abort().await;
+					return Err(anyhow::anyhow!(
+						"Byte count mismatch: expected {}, got {}",
+						total_bytes,
+						writer.written()
+					));
+				}
+				break final_checksum;
+			}
+			FileTransferMessage::TransferError { message, .. } => {
+				writer.abort().await;
+				return Err(anyhow::anyhow!("Transfer error: {}", message));
+			}
+			_ => debug!("Received unexpected message during PULL delta transfer"),
+		}
+	};
+
+	let total_bytes = writer.written();
+	let finished = writer.finish().await?;
+
+	if verify_checksum && !final_checksum.is_empty() {
+		let checksum = match calculate_file_checksum(finished.partial_path()).await {
+			Ok(checksum) => checksum,
+			Err(e) => {
+				finished.discard().await;
+				return Err(e);
+			}
+		};
+		if checksum != final_checksum {
+			error!(
+				"Delta checksum mismatch: expected {}, got {}",
+				final_checksum, checksum
+			);
+			finished.discard().await;
+			return Err(anyhow::anyhow!("Final checksum mismatch"));
+		}
+	}
+
+	let destination = finished.commit().await?;
+
+	info!(
+		"PULL delta transfer completed: {} bytes to {}",
+		total_bytes,
+		destination.display()
+	);
+	ctx.log(format!(
+		"PULL delta transfer completed successfully: {} bytes to {}",
+		total_bytes,
+		destination.display()
+	));
+
+	if let Some(callback) = progress_callback {
+		callback(total_bytes, u64::MAX);
+	}
+
+	Ok(total_bytes)
+}
+
 /// Stream file data in chunks to the remote device using a persistent connection
 async fn stream_file_data<'a>(
 	file_path: &Path,
@@ -1086,6 +1589,8 @@ async fn stream_file_data<'a>(
 	destination_device_id: uuid::Uuid,
 	destination_path: String,
 	file_metadata: crate::service::network::protocol::FileMetadata,
+	bandwidth_limit_mbps: Option<u32>,
+	stats: &SharedTransferStats,
 	ctx: &JobContext<'a>,
 	progress_callback: Option<&ProgressCallback<'a>>,
 ) -> Result<()> {
@@ -1139,6 +1644,9 @@ async fn stream_file_data<'a>(
 
 	let chunk_size = 64 * 1024u32;
 	let total_chunks = ((total_size + chunk_size as u64 - 1) / chunk_size as u64) as u32;
+	let compress = file_transfer_protocol.compression_enabled()
+		&& file_transfer_protocol.should_compress(file_path);
+	let negotiate = file_transfer_protocol.negotiates_with(destination_device_id);
 
 	let transfer_request =
 		crate::service::network::protocol::file_transfer::FileTransferMessage::TransferRequest {
@@ -1148,9 +1656,13 @@ async fn stream_file_data<'a>(
 			chunk_size,
 			total_chunks,
 			destination_path: destination_path.clone(),
+			capabilities: negotiate.then(|| TransferCapabilities {
+				delta: total_size >= MIN_DELTA_FILE_SIZE,
+				compression: compress,
+			}),
 		};
 
-	let request_data = rmp_serde::to_vec(&transfer_request)?;
+	let request_data = encode_message(&transfer_request)?;
 
 	ctx.log(format!(
 		"Sending TransferRequest for {} bytes ({} chunks) to destination: {}",
@@ -1164,89 +1676,164 @@ async fn stream_file_data<'a>(
 	send_stream.write_all(&request_data).await?;
 	send_stream.flush().await?;
 
-	ctx.log("TransferRequest sent, now sending file chunks".to_string());
-
-	let mut file = tokio::fs::File::open(file_path).await?;
+	let (enabled, signature) = if negotiate {
+		ctx.log("TransferRequest sent, waiting for the receiver's capabilities".to_string());
+		match negotiate_push(&mut recv_stream).await? {
+			Some(negotiated) => negotiated,
+			None => {
+				debug!("Receiver did not negotiate capabilities, sending the whole file");
+				file_transfer_protocol.mark_legacy_peer(destination_device_id);
+				(TransferCapabilities::default(), None)
+			}
+		}
+	} else {
+		(TransferCapabilities::default(), None)
+	};
+	let compress = compress && enabled.compression && signature.is_none();
 
 	let chunk_size = 64 * 1024u64; // 64KB chunks
 	let total_chunks = (total_size + chunk_size - 1) / chunk_size;
-	let mut buffer = vec![0u8; chunk_size as usize];
 	let mut chunk_index = 0u32;
 	let mut bytes_transferred = 0u64;
 
-	ctx.log(format!(
-		"Starting to stream {} chunks ({} bytes) to device {}",
-		total_chunks, total_size, destination_device_id
-	));
+	let throttle = file_transfer_protocol.throttle(destination_device_id, bandwidth_limit_mbps);
+	let (base_stats, throttled_before) = {
+		let mut stats = stats.lock().unwrap();
+		stats.compressed = compress;
+		stats.rate_limit = throttle.limit();
+		(
+			(stats.bytes_transferred, stats.bytes_on_wire),
+			stats.throttled,
+		)
+	};
+	let record_stats = |covered: u64, on_wire: u64| {
+		let mut stats = stats.lock().unwrap();
+		stats.bytes_transferred = base_stats.0 + covered;
+		stats.bytes_on_wire = base_stats.1 + on_wire;
+		stats.rate_limit = throttle.limit();
+		stats.throttled = throttled_before + throttle.throttled_for();
+	};
 
-	loop {
-		ctx.check_interrupt().await?;
+	if let Some(signature) = signature {
+		ctx.log(format!(
+			"Receiver has an older copy ({} blocks), sending a delta to device {}",
+			signature.blocks.len(),
+			destination_device_id
+		));
 
-		let bytes_read = file.read(&mut buffer).await?;
-		if bytes_read == 0 {
-			break;
-		}
+		let (covered, literal_bytes) = send_delta(
+			&mut send_stream,
+			transfer_id,
+			file_path,
+			signature,
+			Some(&throttle),
+			|covered| {
+				if let Some(callback) = progress_callback {
+					callback(covered, total_size);
+				}
+			},
+		)
+		.await?;
+		file_transfer_protocol.record_chunk_received(&transfer_id, 0, literal_bytes)?;
+		bytes_transferred = covered;
+		record_stats(covered, literal_bytes);
 
-		// Checksum before encryption so receiver can verify decrypted data.
-		let chunk_data = &buffer[..bytes_read];
-		let chunk_checksum = blake3::hash(chunk_data);
+		ctx.log(format!(
+			"Delta sent: {} of {} bytes as literals",
+			literal_bytes, total_size
+		));
+	} else {
+		let mut file = tokio::fs::File::open(file_path).await?;
+		let mut buffer = vec![0u8; chunk_size as usize];
+		let mut compression_stats = CompressionStats::default();
 
-		// Skip encryption - Iroh already provides E2E encryption for the connection
-		let encrypted_data = chunk_data.to_vec();
-		let nonce = [0u8; 12]; // Dummy nonce since we're not encrypting
+		ctx.log(format!(
+			"Starting to stream {} chunks ({} bytes) to device {}",
+			total_chunks, total_size, destination_device_id
+		));
 
-		let chunk_message =
-			crate::service::network::protocol::file_transfer::FileTransferMessage::FileChunk {
-				transfer_id,
-				chunk_index,
-				data: encrypted_data,
-				nonce,
-				chunk_checksum: *chunk_checksum.as_bytes(),
-			};
+		loop {
+			ctx.check_interrupt().await?;
 
-		let message_data = rmp_serde::to_vec(&chunk_message)?;
+			let bytes_read = file.read(&mut buffer).await?;
+			if bytes_read == 0 {
+				break;
+			}
 
-		send_stream.write_u8(0).await?;
-		send_stream
-			.write_all(&(message_data.len() as u32).to_be_bytes())
-			.await
-			.map_err(|e| anyhow::anyhow!("Failed to write message length: {}", e))?;
-		send_stream
-			.write_all(&message_data)
-			.await
-			.map_err(|e| anyhow::anyhow!("Failed to write chunk data: {}", e))?;
-		send_stream
-			.flush()
-			.await
-			.map_err(|e| anyhow::anyhow!("Failed to flush stream: {}", e))?;
+			// Checksum before encryption so receiver can verify decrypted data.
+			let chunk_data = &buffer[..bytes_read];
+			let chunk_checksum = blake3::hash(chunk_data);
 
-		file_transfer_protocol.record_chunk_received(
-			&transfer_id,
-			chunk_index,
-			bytes_read as u64,
-		)?;
+			// Skip encryption - Iroh already provides E2E encryption for the connection
+			let (encrypted_data, compression) = compression_stats.encode(chunk_data, compress);
+			let nonce = [0u8; 12]; // Dummy nonce since we're not encrypting
+			throttle.consume(encrypted_data.len() as u64).await;
 
-		bytes_transferred += bytes_read as u64;
-		if let Some(callback) = progress_callback {
-			callback(bytes_transferred, total_size);
-		}
+			let chunk_message =
+				crate::service::network::protocol::file_transfer::FileTransferMessage::FileChunk {
+					transfer_id,
+					chunk_index,
+					data: encrypted_data,
+					nonce,
+					chunk_checksum: *chunk_checksum.as_bytes(),
+					compression,
+				};
+
+			let message_data = encode_message(&chunk_message)?;
+
+			send_stream.write_u8(0).await?;
+			send_stream
+				.write_all(&(message_data.len() as u32).to_be_bytes())
+				.await
+				.map_err(|e| anyhow::anyhow!("Failed to write message length: {}", e))?;
+			send_stream
+				.write_all(&message_data)
+				.await
+				.map_err(|e| anyhow::anyhow!("Failed to write chunk data: {}", e))?;
+			send_stream
+				.flush()
+				.await
+				.map_err(|e| anyhow::anyhow!("Failed to flush stream: {}", e))?;
+
+			file_transfer_protocol.record_chunk_received(
+				&transfer_id,
+				chunk_index,
+				bytes_read as u64,
+			)?;
+
+			bytes_transferred += bytes_read as u64;
+			record_stats(bytes_transferred, compression_stats.wire_bytes);
+			if let Some(callback) = progress_callback {
+				callback(bytes_transferred, total_size);
+			}
 
-		chunk_index += 1;
+			chunk_index += 1;
 
-		if chunk_index == 1 || chunk_index % 100 == 0 || chunk_index == total_chunks as u32 {
-			ctx.log(format!(
-				"Sent chunk {}/{} ({} bytes total)",
-				chunk_index, total_chunks, bytes_transferred
-			));
-		}
+			if chunk_index == 1 || chunk_index % 100 == 0 || chunk_index == total_chunks as u32 {
+				ctx.log(format!(
+					"Sent chunk {}/{} ({} bytes total)",
+					chunk_index, total_chunks, bytes_transferred
+				));
+			}
 
-		tokio::task::yield_now().await;
+			tokio::task::yield_now().await;
+		}
 	}
 
 	ctx.log(format!(
 		"All {} chunks sent, sending completion message",
 		chunk_index
 	));
+	{
+		let stats = stats.lock().unwrap();
+		ctx.log(format!(
+			"Sent {} bytes as {} on the wire (compressed: {}), throttled for {:?}",
+			stats.bytes_transferred - base_stats.0,
+			stats.bytes_on_wire - base_stats.1,
+			compress,
+			throttle.throttled_for()
+		));
+	}
 
 	let final_checksum = calculate_file_checksum(file_path).await?;
 	let completion_message =
@@ -1256,7 +1843,7 @@ async fn stream_file_data<'a>(
 			total_bytes: bytes_transferred,
 		};
 
-	let completion_data = rmp_serde::to_vec(&completion_message)?;
+	let completion_data = encode_message(&completion_message)?;
 
 	send_stream.write_u8(0).await?;
 	send_stream
@@ -1299,6 +1886,9 @@ async fn stream_file_data<'a>(
 			}
 			ctx.log("Received TransferFinalAck from receiver - transfer confirmed!".to_string());
 		}
+		crate::service::network::protocol::file_transfer::FileTransferMessage::TransferError { message, .. } => {
+			return Err(anyhow::anyhow!("Receiver rejected transfer: {}", message));
+		}
 		_ => {
 			return Err(anyhow::anyhow!("Expected TransferFinalAck, got different message type"));
 		}
@@ -6,9 +6,12 @@ use crate::{
 	context::CoreContext,
 	domain::addressing::{SdPath, SdPathBatch},
 	infra::action::{error::ActionError, LibraryAction, ValidationResult},
-	ops::files::{
-		copy::job::{FileCopyJob, MoveMode},
-		rename::validation::validate_filename,
+	ops::{
+		action::UndoOperation,
+		files::{
+			copy::job::{FileCopyJob, MoveMode},
+			rename::validation::validate_filename,
+		},
 	},
 	volume::{LocalBackend, VolumeBackend},
 };
@@ -85,11 +88,45 @@ impl LibraryAction for CreateFolderAction {
 					message: "Cannot create folders in sidecar storage".to_string(),
 				});
 			}
+			SdPath::Archive { .. } => {
+				return Err(ActionError::Validation {
+					field: "parent".to_string(),
+					message: "Cannot create folders inside archives".to_string(),
+				});
+			}
 		}
 
 		Ok(ValidationResult::Success { metadata: None })
 	}
 
+	async fn undo_operation(
+		&self,
+		_library: &Arc<crate::library::Library>,
+	) -> Result<Option<UndoOperation>, ActionError> {
+		let Some(parent) = self.parent.as_local_path() else {
+			return Ok(None);
+		};
+		let folder = parent.join(&self.name);
+		if tokio::fs::symlink_metadata(&folder).await.is_ok() {
+			return Ok(None);
+		}
+
+		let mut pairs = Vec::with_capacity(self.items.len());
+		for item in &self.items {
+			let Some(path) = item.as_local_path() else {
+				return Ok(None);
+			};
+			let Some(filename) = path.file_name() else {
+				return Ok(None);
+			};
+			pairs.push((path.to_path_buf(), folder.join(filename)));
+		}
+
+		Ok(UndoOperation::capture_moves(pairs)
+			.await?
+			.map(|moves| UndoOperation::CreateFolder { folder, moves }))
+	}
+
 	async fn execute(
 		self,
 		library: Arc<crate::library::Library>,
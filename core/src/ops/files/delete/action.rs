@@ -9,6 +9,7 @@ use crate::{
 		action::{error::ActionError, LibraryAction},
 		job::handle::JobHandle,
 	},
+	ops::action::UndoOperation,
 };
 use serde::{Deserialize, Serialize};
 use std::{path::PathBuf, sync::Arc};
@@ -46,6 +47,21 @@ impl LibraryAction for FileDeleteAction {
 		})
 	}
 
+	async fn undo_operation(
+		&self,
+		_library: &std::sync::Arc<crate::library::Library>,
+	) -> Result<Option<UndoOperation>, ActionError> {
+		// Only trashed items can be brought back
+		if self.options.permanent {
+			return Ok(None);
+		}
+		UndoOperation::capture_trash(&self.targets.paths).await
+	}
+
+	fn background_job(output: &Self::Output) -> Option<crate::infra::job::types::JobId> {
+		Some(output.id)
+	}
+
 	async fn execute(
 		self,
 		library: std::sync::Arc<crate::library::Library>,
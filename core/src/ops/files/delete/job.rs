@@ -1,8 +1,11 @@
 //! Delete job implementation
 
-use crate::{domain::addressing::SdPathBatch, infra::job::prelude::*};
+use crate::{
+	domain::addressing::SdPathBatch, infra::job::prelude::*, ops::files::trash::TrashLedger,
+};
 use serde::{Deserialize, Serialize};
 use std::{
+	collections::HashMap,
 	path::PathBuf,
 	time::{Duration, Instant},
 };
@@ -116,12 +119,45 @@ impl JobHandler for DeleteJob {
 			DeleteStrategyRouter::describe_strategy(&self.targets.paths).await;
 		ctx.log(format!("Using strategy: {}", strategy_description));
 
+		// Capture index state before trashing so the items can be restored later
+		let mut trash_snapshots = HashMap::new();
+		if matches!(self.mode, DeleteMode::Trash) {
+			for target in self.targets.paths.iter().filter(|p| p.is_local()) {
+				match TrashLedger::snapshot(ctx.library_db(), target).await {
+					Ok(snapshot) => {
+						trash_snapshots.insert(target.clone(), snapshot);
+					}
+					Err(e) => ctx.add_warning(format!(
+						"Failed to snapshot {} for the trash ledger: {}",
+						target.display(),
+						e
+					)),
+				}
+			}
+		}
+
 		// Execute deletion using selected strategy
 		let results = strategy
 			.execute(&ctx, &self.targets.paths, self.mode.clone())
 			.await
 			.map_err(|e| JobError::execution(format!("Strategy execution failed: {}", e)))?;
 
+		// Record trashed items in the ledger
+		for result in results.iter().filter(|r| r.success) {
+			let (Some(trash_path), Some(snapshot)) =
+				(&result.trash_path, trash_snapshots.remove(&result.path))
+			else {
+				continue;
+			};
+			if let Err(e) = TrashLedger::record(ctx.library_db(), snapshot, trash_path).await {
+				ctx.add_warning(format!(
+					"Failed to record {} in the trash ledger: {}",
+					result.path.display(),
+					e
+				));
+			}
+		}
+
 		// Aggregate results
 		let deleted_count = results.iter().filter(|r| r.success).count();
 		let failed_count = results.len() - deleted_count;
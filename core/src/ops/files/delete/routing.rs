@@ -15,7 +15,7 @@ impl DeleteStrategyRouter {
 		let all_local = paths.iter().all(|p| p.is_local());
 
 		if all_local {
-			Box::new(LocalDeleteStrategy)
+			Box::new(LocalDeleteStrategy::default())
 		} else {
 			// At least one remote path - use remote strategy
 			Box::new(RemoteDeleteStrategy)
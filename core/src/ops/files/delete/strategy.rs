@@ -22,6 +22,9 @@ pub struct DeleteResult {
 	pub success: bool,
 	pub bytes_freed: u64,
 	pub error: Option<String>,
+	/// Where the item ended up when moved to the system trash
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub trash_path: Option<PathBuf>,
 }
 
 /// Strategy for executing delete operations
@@ -37,7 +40,12 @@ pub trait DeleteStrategy: Send + Sync {
 }
 
 /// Local deletion strategy for same-device operations
-pub struct LocalDeleteStrategy;
+#[derive(Debug, Clone, Default)]
+pub struct LocalDeleteStrategy {
+	/// Overrides the platform trash directory (the freedesktop trash root on
+	/// Linux). `None` uses the current user's trash.
+	trash_root: Option<PathBuf>,
+}
 
 #[async_trait]
 impl DeleteStrategy for LocalDeleteStrategy {
@@ -60,16 +68,28 @@ impl DeleteStrategy for LocalDeleteStrategy {
 					let size = self.get_path_size(local_path).await.unwrap_or(0);
 
 					let deletion_result = match mode {
-						DeleteMode::Trash => self.move_to_trash(local_path).await,
-						DeleteMode::Permanent => self.permanent_delete(local_path).await,
-						DeleteMode::Secure => self.secure_delete(local_path).await,
+						DeleteMode::Trash => self.trash(local_path).await.map(Some),
+						DeleteMode::Permanent => {
+							self.permanent_delete(local_path).await.map(|_| None)
+						}
+						DeleteMode::Secure => self.secure_delete(local_path).await.map(|_| None),
 					};
 
-					DeleteResult {
-						path: path.clone(),
-						success: deletion_result.is_ok(),
-						bytes_freed: if deletion_result.is_ok() { size } else { 0 },
-						error: deletion_result.err().map(|e| e.to_string()),
+					match deletion_result {
+						Ok(trash_path) => DeleteResult {
+							path: path.clone(),
+							success: true,
+							bytes_freed: size,
+							error: None,
+							trash_path,
+						},
+						Err(e) => DeleteResult {
+							path: path.clone(),
+							success: false,
+							bytes_freed: 0,
+							error: Some(e.to_string()),
+							trash_path: None,
+						},
 					}
 				}
 
@@ -82,6 +102,7 @@ impl DeleteStrategy for LocalDeleteStrategy {
 					success: false,
 					bytes_freed: 0,
 					error: Some("Path is remote or unsupported".to_string()),
+					trash_path: None,
 				},
 			};
 
@@ -93,6 +114,13 @@ impl DeleteStrategy for LocalDeleteStrategy {
 }
 
 impl LocalDeleteStrategy {
+	/// Strategy that moves trashed items into `root` instead of the user's trash
+	pub fn with_trash_root(root: impl Into<PathBuf>) -> Self {
+		Self {
+			trash_root: Some(root.into()),
+		}
+	}
+
 	/// Delete a cloud path using VolumeBackend
 	async fn delete_cloud_path(
 		&self,
@@ -110,6 +138,7 @@ impl LocalDeleteStrategy {
 					"Delete mode {:?} not supported for cloud paths (only Permanent)",
 					mode
 				)),
+				trash_path: None,
 			};
 		}
 
@@ -122,6 +151,7 @@ impl LocalDeleteStrategy {
 					success: false,
 					bytes_freed: 0,
 					error: Some("Volume manager not available".to_string()),
+					trash_path: None,
 				}
 			}
 		};
@@ -135,6 +165,7 @@ impl LocalDeleteStrategy {
 					success: false,
 					bytes_freed: 0,
 					error: Some("Path is not a cloud path".to_string()),
+					trash_path: None,
 				}
 			}
 		};
@@ -152,6 +183,7 @@ impl LocalDeleteStrategy {
 						service.scheme(),
 						identifier
 					)),
+					trash_path: None,
 				}
 			}
 		};
@@ -165,6 +197,7 @@ impl LocalDeleteStrategy {
 					success: false,
 					bytes_freed: 0,
 					error: Some("Volume backend not available".to_string()),
+					trash_path: None,
 				}
 			}
 		};
@@ -182,12 +215,14 @@ impl LocalDeleteStrategy {
 				success: true,
 				bytes_freed: size,
 				error: None,
+				trash_path: None,
 			},
 			Err(e) => DeleteResult {
 				path: path.clone(),
 				success: false,
 				bytes_freed: 0,
 				error: Some(format!("Cloud deletion failed: {}", e)),
+				trash_path: None,
 			},
 		}
 	}
@@ -215,47 +250,47 @@ impl LocalDeleteStrategy {
 
 	/// Move file to system trash/recycle bin
 	pub async fn move_to_trash(&self, path: &Path) -> Result<(), std::io::Error> {
+		self.trash(path).await.map(|_| ())
+	}
+
+	/// Move file to system trash/recycle bin, returning where it ended up
+	pub async fn trash(&self, path: &Path) -> Result<PathBuf, std::io::Error> {
 		#[cfg(target_os = "macos")]
 		{
-			self.move_to_trash_macos(path).await?;
+			self.move_to_trash_macos(path).await
 		}
 
 		#[cfg(all(unix, not(target_os = "macos")))]
 		{
-			self.move_to_trash_unix(path).await?;
+			self.move_to_trash_unix(path).await
 		}
 
 		#[cfg(windows)]
 		{
-			self.move_to_trash_windows(path).await?;
+			self.move_to_trash_windows(path).await
 		}
-
-		Ok(())
 	}
 
-	#[cfg(unix)]
-	async fn move_to_trash_unix(&self, path: &Path) -> Result<(), std::io::Error> {
-		let home = std::env::var("HOME")
-			.map_err(|_| std::io::Error::new(std::io::ErrorKind::NotFound, "HOME not set"))?;
-
-		let trash_dir = std::path::Path::new(&home).join(".local/share/Trash/files");
-		fs::create_dir_all(&trash_dir).await?;
-
-		let filename = path.file_name().ok_or_else(|| {
-			std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid filename")
-		})?;
-
-		let trash_path = trash_dir.join(filename);
-		let final_trash_path = self.find_unique_trash_name(&trash_path).await?;
-
-		fs::rename(path, final_trash_path).await?;
+	/// Follows the freedesktop.org Trash spec so the item shows up (and can be
+	/// restored) in desktop file managers as well as in Spacedrive.
+	#[cfg(all(unix, not(target_os = "macos")))]
+	async fn move_to_trash_unix(&self, path: &Path) -> Result<PathBuf, std::io::Error> {
+		use crate::ops::files::trash::freedesktop::FreedesktopTrash;
 
-		Ok(())
+		let trash = match &self.trash_root {
+			Some(root) => FreedesktopTrash::at(root),
+			None => FreedesktopTrash::home()?,
+		};
+		let trashed = trash.trash(path).await?;
+		Ok(trashed.files_path)
 	}
 
 	#[cfg(windows)]
-	async fn move_to_trash_windows(&self, path: &Path) -> Result<(), std::io::Error> {
-		let temp_dir = std::env::temp_dir().join("spacedrive_trash");
+	async fn move_to_trash_windows(&self, path: &Path) -> Result<PathBuf, std::io::Error> {
+		let temp_dir = self
+			.trash_root
+			.clone()
+			.unwrap_or_else(|| std::env::temp_dir().join("spacedrive_trash"));
 		fs::create_dir_all(&temp_dir).await?;
 
 		let filename = path.file_name().ok_or_else(|| {
@@ -265,17 +300,25 @@ impl LocalDeleteStrategy {
 		let trash_path = temp_dir.join(filename);
 		let final_trash_path = self.find_unique_trash_name(&trash_path).await?;
 
-		fs::rename(path, final_trash_path).await?;
+		fs::rename(path, &final_trash_path).await?;
 
-		Ok(())
+		Ok(final_trash_path)
 	}
 
 	#[cfg(target_os = "macos")]
-	async fn move_to_trash_macos(&self, path: &Path) -> Result<(), std::io::Error> {
-		let home = std::env::var("HOME")
-			.map_err(|_| std::io::Error::new(std::io::ErrorKind::NotFound, "HOME not set"))?;
-
-		let trash_dir = std::path::Path::new(&home).join(".Trash");
+	async fn move_to_trash_macos(&self, path: &Path) -> Result<PathBuf, std::io::Error> {
+		let trash_dir = match &self.trash_root {
+			Some(root) => {
+				fs::create_dir_all(root).await?;
+				root.clone()
+			}
+			None => {
+				let home = std::env::var("HOME").map_err(|_| {
+					std::io::Error::new(std::io::ErrorKind::NotFound, "HOME not set")
+				})?;
+				std::path::Path::new(&home).join(".Trash")
+			}
+		};
 
 		let filename = path.file_name().ok_or_else(|| {
 			std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid filename")
@@ -284,12 +327,13 @@ impl LocalDeleteStrategy {
 		let trash_path = trash_dir.join(filename);
 		let final_trash_path = self.find_unique_trash_name(&trash_path).await?;
 
-		fs::rename(path, final_trash_path).await?;
+		fs::rename(path, &final_trash_path).await?;
 
-		Ok(())
+		Ok(final_trash_path)
 	}
 
 	/// Find a unique name in the trash directory
+	#[cfg(any(target_os = "macos", windows))]
 	async fn find_unique_trash_name(&self, base_path: &Path) -> Result<PathBuf, std::io::Error> {
 		let mut candidate = base_path.to_path_buf();
 		let mut counter = 1;
@@ -458,6 +502,7 @@ impl RemoteDeleteStrategy {
 			paths: paths.to_vec(),
 			mode,
 			request_id,
+			library_id: Some(ctx.library().id()),
 		};
 
 		// Serialize request
@@ -485,6 +530,7 @@ impl RemoteDeleteStrategy {
 				success: true,
 				bytes_freed: 0,
 				error: None,
+				trash_path: None,
 			})
 			.collect();
 
@@ -505,6 +551,9 @@ pub enum FileDeleteMessage {
 		paths: Vec<SdPath>,
 		mode: DeleteMode,
 		request_id: Uuid,
+		/// Library whose trash ledger records trashed paths on the receiving device
+		#[serde(default)]
+		library_id: Option<Uuid>,
 	},
 	Response {
 		request_id: Uuid,
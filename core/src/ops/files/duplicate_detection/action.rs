@@ -9,6 +9,7 @@ use crate::{
 		action::{error::ActionError, LibraryAction},
 		job::handle::JobHandle,
 	},
+	ops::media::perceptual_hash::max_distance_for_similarity,
 };
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -70,6 +71,9 @@ impl LibraryAction for DuplicateDetectionAction {
 			"size_only" => DetectionMode::SizeOnly,
 			"name_and_size" => DetectionMode::NameAndSize,
 			"deep_scan" => DetectionMode::DeepScan,
+			"perceptual" => DetectionMode::Perceptual {
+				max_distance: max_distance_for_similarity(self.threshold),
+			},
 			_ => DetectionMode::ContentHash,
 		};
 
@@ -100,6 +104,12 @@ impl LibraryAction for DuplicateDetectionAction {
 				message: "At least one path must be specified".to_string(),
 			});
 		}
+		if !(0.0..=1.0).contains(&self.threshold) {
+			return Err(ActionError::Validation {
+				field: "threshold".to_string(),
+				message: "Threshold must be between 0.0 and 1.0".to_string(),
+			});
+		}
 		Ok(())
 	}
 }
@@ -10,8 +10,10 @@ use std::path::PathBuf;
 pub struct DuplicateDetectionInput {
 	/// Paths to search for duplicates
 	pub paths: Vec<PathBuf>,
-	/// Detection algorithm to use
+	/// Detection algorithm to use: "size_only", "content_hash", "name_and_size",
+	/// "deep_scan" or "perceptual"
 	pub algorithm: String,
-	/// Similarity threshold (0.0 to 1.0)
+	/// Similarity threshold (0.0 to 1.0). For "perceptual", the minimum perceptual
+	/// similarity for two files to be grouped (e.g. 0.9 allows 6 of 64 bits to differ)
 	pub threshold: f64,
 }
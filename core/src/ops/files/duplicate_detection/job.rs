@@ -4,11 +4,24 @@ use crate::{
 	domain::content_identity::ContentHashGenerator,
 	infra::job::prelude::*,
 	domain::addressing::{SdPath, SdPathBatch},
+	filetype::FileTypeRegistry,
+	infra::db::entities::content_identity,
+	library::Library,
+	ops::{
+		indexing::PathResolver,
+		media::perceptual_hash::{
+			self, is_perceptual_hash_supported, FingerprintTree, MediaFingerprint,
+			PERCEPTUAL_HASH_VARIANT,
+		},
+		sidecar::types::{SidecarFormat, SidecarKind, SidecarVariant},
+	},
 };
+use sea_orm::EntityTrait;
 use serde::{Deserialize, Serialize};
 use std::{
 	collections::{HashMap, HashSet},
 	path::PathBuf,
+	sync::Arc,
 	time::{Duration, Instant},
 };
 use tokio::fs;
@@ -25,6 +38,9 @@ pub enum DetectionMode {
 	NameAndSize,
 	/// Deep comparison with full content verification
 	DeepScan,
+	/// Visually similar images and videos (resized, recompressed, re-exported),
+	/// matched by perceptual hash within `max_distance` differing bits (0-64)
+	Perceptual { max_distance: u32 },
 }
 
 /// Duplicate detection job for finding duplicate files
@@ -61,6 +77,10 @@ pub struct DuplicateGroup {
 	pub total_size: u64,
 	pub wasted_space: u64, // Size that could be saved by keeping only one copy
 	pub detection_method: String,
+	/// How alike the files are (1.0 = identical content). For perceptual groups this is
+	/// the lowest pairwise similarity that joined the group. None for size/name heuristics.
+	#[serde(default)]
+	pub similarity: Option<f64>,
 }
 
 /// Duplicate detection progress information
@@ -119,6 +139,9 @@ impl JobHandler for DuplicateDetectionJob {
 			DetectionMode::ContentHash => self.find_content_duplicates(&ctx).await?,
 			DetectionMode::NameAndSize => self.find_name_size_duplicates(&ctx).await?,
 			DetectionMode::DeepScan => self.find_deep_scan_duplicates(&ctx).await?,
+			DetectionMode::Perceptual { max_distance } => {
+				self.find_perceptual_duplicates(max_distance, &ctx).await?
+			}
 		};
 
 		let total_duplicates = duplicate_groups.iter().map(|g| g.files.len() - 1).sum();
@@ -298,6 +321,7 @@ impl DuplicateDetectionJob {
 					total_size: *size * files.len() as u64,
 					wasted_space,
 					detection_method: "Size comparison".to_string(),
+					similarity: None,
 				});
 			}
 		}
@@ -370,6 +394,7 @@ impl DuplicateDetectionJob {
 							total_size: *size * files.len() as u64,
 							wasted_space,
 							detection_method: format!("Content hash: {}", &hash[..8]),
+							similarity: Some(1.0),
 						});
 					}
 				}
@@ -410,6 +435,7 @@ impl DuplicateDetectionJob {
 					total_size: size * file_count,
 					wasted_space,
 					detection_method: format!("Name + size: {}", name),
+					similarity: None,
 				});
 			}
 		}
@@ -434,6 +460,189 @@ impl DuplicateDetectionJob {
 
 		Ok(hash_groups)
 	}
+
+	/// Find visually similar images and videos by perceptual hash
+	///
+	/// Files are linked when their fingerprints are within `max_distance` bits of
+	/// each other, and groups are the connected components of those links. Links
+	/// are found through a BK-tree rather than by comparing every pair.
+	async fn find_perceptual_duplicates(
+		&self,
+		max_distance: u32,
+		ctx: &JobContext<'_>,
+	) -> JobResult<Vec<DuplicateGroup>> {
+		let registry = FileTypeRegistry::new();
+		let library = ctx.library_arc();
+
+		// Resized copies differ in size, so every supported file is a candidate
+		let candidates: Vec<&FileInfo> = self
+			.size_groups
+			.values()
+			.flatten()
+			.filter(|file| {
+				file.path.as_local_path().map_or(false, |path| {
+					is_perceptual_hash_supported(registry.identify_by_extension(path))
+				})
+			})
+			.collect();
+		let total_candidates = candidates.len();
+
+		let mut fingerprinted: Vec<(&FileInfo, MediaFingerprint)> = Vec::new();
+		for (index, file) in candidates.into_iter().enumerate() {
+			ctx.check_interrupt().await?;
+
+			ctx.progress(Progress::structured(DuplicateProgress {
+				current_file: file.path.display(),
+				files_scanned: index + 1,
+				total_files: total_candidates,
+				duplicates_found: 0,
+				potential_savings: 0,
+				current_operation: "Computing perceptual hashes".to_string(),
+			}));
+
+			match perceptual_fingerprint(&library, file, &registry).await {
+				Ok(fingerprint) => fingerprinted.push((file, fingerprint)),
+				Err(e) => {
+					ctx.add_non_critical_error(format!(
+						"Failed to compute perceptual hash for {}: {}",
+						file.path.display(),
+						e
+					));
+				}
+			}
+		}
+
+		// Union-find over pairs within the threshold, tracking the weakest link per group.
+		// Each file is looked up among the files before it, so every pair is seen once.
+		let mut parent: Vec<usize> = (0..fingerprinted.len()).collect();
+		let mut worst_distance: Vec<u32> = vec![0; fingerprinted.len()];
+		let mut tree = FingerprintTree::new();
+
+		fn find(parent: &mut [usize], mut i: usize) -> usize {
+			while parent[i] != i {
+				parent[i] = parent[parent[i]];
+				i = parent[i];
+			}
+			i
+		}
+
+		for (i, (_, fingerprint)) in fingerprinted.iter().enumerate() {
+			ctx.check_interrupt().await?;
+
+			for (j, distance) in tree.find_within(fingerprint, max_distance) {
+				let (a, b) = (find(&mut parent, j), find(&mut parent, i));
+				let worst = worst_distance[a].max(worst_distance[b]).max(distance);
+				if a != b {
+					parent[b] = a;
+				}
+				worst_distance[a] = worst;
+			}
+
+			tree.insert(i, fingerprint);
+		}
+
+		let mut components: HashMap<usize, Vec<FileInfo>> = HashMap::new();
+		for (i, (file, _)) in fingerprinted.iter().enumerate() {
+			let root = find(&mut parent, i);
+			components.entry(root).or_default().push((*file).clone());
+		}
+
+		let mut groups = Vec::new();
+		for (root, files) in components {
+			if files.len() < 2 {
+				continue;
+			}
+
+			// Keep the largest (usually highest quality) copy, the rest is reclaimable
+			let total_size: u64 = files.iter().map(|f| f.size).sum();
+			let largest = files.iter().map(|f| f.size).max().unwrap_or(0);
+			let similarity = perceptual_hash::similarity(worst_distance[root]);
+
+			groups.push(DuplicateGroup {
+				files,
+				total_size,
+				wasted_space: total_size - largest,
+				detection_method: format!("Perceptual hash: {:.0}% similar", similarity * 100.0),
+				similarity: Some(similarity),
+			});
+		}
+
+		groups.sort_by(|a, b| b.wasted_space.cmp(&a.wasted_space));
+
+		Ok(groups)
+	}
+}
+
+/// Load a file's perceptual fingerprint from its sidecar, computing and storing it if missing
+///
+/// Only indexed files with a content identity can be cached; anything else is hashed
+/// on the fly.
+async fn perceptual_fingerprint(
+	library: &Arc<Library>,
+	file: &FileInfo,
+	registry: &FileTypeRegistry,
+) -> anyhow::Result<MediaFingerprint> {
+	let local_path = file
+		.path
+		.as_local_path()
+		.ok_or_else(|| anyhow::anyhow!("Perceptual hashing requires a local path"))?;
+	let kind = registry.identify_by_extension(local_path);
+
+	let db = library.db().conn();
+	let content_uuid = match PathResolver::resolve_to_entry(db, &file.path).await? {
+		Some(entry) => match entry.content_id {
+			Some(content_id) => content_identity::Entity::find_by_id(content_id)
+				.one(db)
+				.await?
+				.and_then(|ci| ci.uuid),
+			None => None,
+		},
+		None => None,
+	};
+	let sidecar_manager = library.core_context().get_sidecar_manager().await;
+
+	let (Some(content_uuid), Some(sidecar_manager)) = (content_uuid, sidecar_manager) else {
+		return Ok(perceptual_hash::compute_fingerprint(local_path, kind).await?);
+	};
+
+	let variant = SidecarVariant::new(PERCEPTUAL_HASH_VARIANT);
+	let sidecar_path = sidecar_manager
+		.compute_path(
+			&library.id(),
+			&content_uuid,
+			&SidecarKind::PerceptualHash,
+			&variant,
+			&SidecarFormat::Json,
+		)
+		.await?;
+
+	if let Ok(bytes) = fs::read(&sidecar_path.absolute_path).await {
+		if let Ok(fingerprint) = serde_json::from_slice::<MediaFingerprint>(&bytes) {
+			return Ok(fingerprint);
+		}
+	}
+
+	let fingerprint = perceptual_hash::compute_fingerprint(local_path, kind).await?;
+
+	let bytes = serde_json::to_vec(&fingerprint)?;
+	if let Some(parent) = sidecar_path.absolute_path.parent() {
+		fs::create_dir_all(parent).await?;
+	}
+	fs::write(&sidecar_path.absolute_path, &bytes).await?;
+
+	sidecar_manager
+		.record_sidecar(
+			library,
+			&content_uuid,
+			&SidecarKind::PerceptualHash,
+			&variant,
+			&SidecarFormat::Json,
+			bytes.len() as u64,
+			None,
+		)
+		.await?;
+
+	Ok(fingerprint)
 }
 
 /// Job output for duplicate detection
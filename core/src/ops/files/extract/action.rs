@@ -0,0 +1,114 @@
+//! File extract action handler
+
+use super::{input::FileExtractInput, job::FileExtractJob};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::{error::ActionError, ConfirmationRequest, LibraryAction, ValidationResult},
+		job::handle::JobReceipt,
+	},
+	ops::files::copy::action::FileConflictResolution,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileExtractAction {
+	input: FileExtractInput,
+}
+
+impl LibraryAction for FileExtractAction {
+	type Input = FileExtractInput;
+	type Output = JobReceipt;
+
+	fn from_input(input: FileExtractInput) -> Result<Self, String> {
+		input
+			.validate()
+			.map_err(|errors| format!("Validation failed: {}", errors.join("; ")))?;
+		Ok(Self { input })
+	}
+
+	async fn validate(
+		&self,
+		_library: &Arc<crate::library::Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<ValidationResult, ActionError> {
+		if self.input.on_conflict.is_some() {
+			return Ok(ValidationResult::Success { metadata: None });
+		}
+
+		let conflicts: Vec<_> = self
+			.input
+			.targets()
+			.into_iter()
+			.filter(|(_, target)| target.exists())
+			.collect();
+
+		if !conflicts.is_empty() {
+			let request = ConfirmationRequest {
+				message: format!(
+					"{} folder conflict{} detected",
+					conflicts.len(),
+					if conflicts.len() == 1 { "" } else { "s" }
+				),
+				choices: FileConflictResolution::CHOICES
+					.iter()
+					.map(|c| c.as_str().to_string())
+					.collect(),
+				metadata: Some(json!({
+					"conflicts": conflicts.iter().map(|(source, destination)| {
+						json!({
+							"source": source.to_string_lossy(),
+							"destination": destination.to_string_lossy(),
+						})
+					}).collect::<Vec<_>>(),
+				})),
+			};
+			return Ok(ValidationResult::RequiresConfirmation(request));
+		}
+
+		Ok(ValidationResult::Success { metadata: None })
+	}
+
+	fn resolve_confirmation(&mut self, choice_index: usize) -> Result<(), ActionError> {
+		match FileConflictResolution::from_index(choice_index) {
+			Some(FileConflictResolution::Abort) => Err(ActionError::Cancelled),
+			Some(resolution) => {
+				self.input.on_conflict = Some(resolution);
+				Ok(())
+			}
+			None => Err(ActionError::Validation {
+				field: "choice".to_string(),
+				message: "Invalid choice selected".to_string(),
+			}),
+		}
+	}
+
+	async fn execute(
+		self,
+		library: Arc<crate::library::Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		if self.input.on_conflict == Some(FileConflictResolution::Abort) {
+			return Err(ActionError::Cancelled);
+		}
+
+		let job = FileExtractJob::new(self.input.sources, self.input.destination)
+			.with_conflict_resolution(self.input.on_conflict);
+
+		let job_handle = library
+			.jobs()
+			.dispatch(job)
+			.await
+			.map_err(ActionError::Job)?;
+
+		Ok(job_handle.into())
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"files.extract"
+	}
+}
+
+crate::register_library_action!(FileExtractAction, "files.extract");
@@ -0,0 +1,116 @@
+//! Input types for archive extraction
+
+use crate::{
+	domain::addressing::{SdPath, SdPathBatch},
+	ops::files::{archive::ArchiveFormat, copy::action::FileConflictResolution},
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+
+/// Input for extracting archives
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FileExtractInput {
+	/// Archives to extract
+	pub sources: SdPathBatch,
+
+	/// Folder to extract into. Each archive gets its own folder inside it.
+	pub destination: SdPath,
+
+	/// How to handle an existing folder with an archive's name (set by confirmation)
+	pub on_conflict: Option<FileConflictResolution>,
+}
+
+impl FileExtractInput {
+	/// Create a new input from local filesystem paths
+	pub fn new<D: Into<PathBuf>>(sources: Vec<PathBuf>, destination: D) -> Self {
+		Self {
+			sources: SdPathBatch::new(sources.into_iter().map(SdPath::local).collect()),
+			destination: SdPath::local(destination.into()),
+			on_conflict: None,
+		}
+	}
+
+	/// The folder each archive will be extracted into, paired with the archive
+	pub fn targets(&self) -> Vec<(PathBuf, PathBuf)> {
+		let Some(destination) = self.destination.as_local_path() else {
+			return Vec::new();
+		};
+
+		self.sources
+			.paths
+			.iter()
+			.filter_map(SdPath::as_local_path)
+			.filter_map(|archive| {
+				let stem = ArchiveFormat::stem(archive)?;
+				Some((archive.to_path_buf(), destination.join(stem)))
+			})
+			.collect()
+	}
+
+	/// Validate the input
+	pub fn validate(&self) -> Result<(), Vec<String>> {
+		let mut errors = Vec::new();
+
+		if self.sources.paths.is_empty() {
+			errors.push("At least one archive must be specified".to_string());
+		}
+
+		for source in &self.sources.paths {
+			match source.as_local_path() {
+				None => errors.push(format!("{} is not on this device", source.display())),
+				Some(path) if ArchiveFormat::stem(path).is_none() => {
+					errors.push(format!("{} is not a supported archive", source.display()))
+				}
+				Some(_) => {}
+			}
+		}
+
+		if self.destination.as_local_path().is_none() {
+			errors.push("Archives can only be extracted on this device".to_string());
+		}
+
+		if errors.is_empty() {
+			Ok(())
+		} else {
+			Err(errors)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_targets_are_named_after_archives() {
+		let input = FileExtractInput::new(
+			vec![
+				"/downloads/photos.zip".into(),
+				"/downloads/site.tar.zst".into(),
+			],
+			"/projects",
+		);
+		assert!(input.validate().is_ok());
+		assert_eq!(
+			input.targets(),
+			vec![
+				(
+					PathBuf::from("/downloads/photos.zip"),
+					PathBuf::from("/projects/photos")
+				),
+				(
+					PathBuf::from("/downloads/site.tar.zst"),
+					PathBuf::from("/projects/site")
+				),
+			]
+		);
+	}
+
+	#[test]
+	fn test_validation_rejects_non_archives() {
+		let input = FileExtractInput::new(vec!["/downloads/notes.txt".into()], "/projects");
+		let errors = input.validate().unwrap_err();
+		assert!(errors.iter().any(|e| e.contains("not a supported archive")));
+	}
+}
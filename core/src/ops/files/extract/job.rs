@@ -0,0 +1,326 @@
+//! Archive extraction job
+//!
+//! Each archive is unpacked into a folder named after it inside the destination, with a
+//! checkpoint after each archive. Overwriting replaces an existing folder rather than merging
+//! into it. An archive that was only partly extracted when the job was interrupted is
+//! extracted again into the same folder on resume. Extracted folders are
+//! handed straight to the location responder, so they're indexed without waiting for the
+//! watcher.
+
+use crate::{
+	domain::addressing::{SdPath, SdPathBatch},
+	infra::job::{generic_progress::GenericProgress, prelude::*},
+	ops::{
+		files::{
+			archive::{extract, load_index_async, run_with_progress, ArchiveFormat},
+			copy::{action::FileConflictResolution, job::resolve_conflict},
+		},
+		indexing::responder,
+	},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+	path::{Path, PathBuf},
+	time::{Duration, Instant},
+};
+
+/// Minimum time between progress updates while an archive is being extracted
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Job that extracts archives into folders
+#[derive(Debug, Serialize, Deserialize, Job)]
+pub struct FileExtractJob {
+	pub sources: SdPathBatch,
+	/// Folder the archives are extracted into
+	pub destination: SdPath,
+	#[serde(default)]
+	pub conflict_resolution: Option<FileConflictResolution>,
+
+	// Internal state for resumption
+	#[serde(default)]
+	completed_indices: Vec<usize>,
+	/// Folder the archive in progress is going into, reused when the job resumes
+	#[serde(default)]
+	current_target: Option<PathBuf>,
+	#[serde(default)]
+	extracted: Vec<SdPath>,
+	#[serde(default)]
+	skipped_count: usize,
+	#[serde(default)]
+	failed_count: usize,
+	/// Bytes written for the finished archives
+	#[serde(default)]
+	bytes_extracted: u64,
+	#[serde(skip, default = "Instant::now")]
+	started_at: Instant,
+}
+
+impl Job for FileExtractJob {
+	const NAME: &'static str = "file_extract";
+	const RESUMABLE: bool = true;
+	const DESCRIPTION: Option<&'static str> = Some("Extract archives into folders");
+}
+
+impl crate::infra::job::traits::DynJob for FileExtractJob {
+	fn job_name(&self) -> &'static str {
+		Self::NAME
+	}
+}
+
+/// Output from the file extract job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileExtractOutput {
+	/// Folders the archives were extracted into
+	pub extracted: Vec<SdPath>,
+	pub skipped_count: usize,
+	pub failed_count: usize,
+	pub total_bytes: u64,
+	pub duration: Duration,
+}
+
+impl From<FileExtractOutput> for JobOutput {
+	fn from(output: FileExtractOutput) -> Self {
+		JobOutput::FileExtract {
+			extracted_count: output.extracted.len(),
+			skipped_count: output.skipped_count,
+			failed_count: output.failed_count,
+			total_bytes: output.total_bytes,
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl JobHandler for FileExtractJob {
+	type Output = FileExtractOutput;
+
+	async fn run(&mut self, ctx: JobContext<'_>) -> JobResult<Self::Output> {
+		let destination = self
+			.destination
+			.as_local_path()
+			.map(Path::to_path_buf)
+			.ok_or_else(|| JobError::execution("Extraction destination is not on this device"))?;
+		let archives = self
+			.sources
+			.paths
+			.iter()
+			.map(|source| {
+				source
+					.as_local_path()
+					.map(Path::to_path_buf)
+					.ok_or_else(|| {
+						JobError::execution(format!("{} is not on this device", source.display()))
+					})
+			})
+			.collect::<JobResult<Vec<_>>>()?;
+
+		// Uncompressed sizes come from the archive listings, which are cached for browsing
+		ctx.progress(Progress::indeterminate("Reading archives"));
+		let mut sizes = Vec::with_capacity(archives.len());
+		for archive in &archives {
+			sizes.push(match load_index_async(archive).await {
+				Ok(index) => index.size_of(""),
+				Err(_) => 0,
+			});
+		}
+		let total_bytes: u64 = sizes.iter().sum();
+		let mut bytes_done = sizes
+			.iter()
+			.enumerate()
+			.filter(|(index, _)| self.completed_indices.contains(index))
+			.map(|(_, size)| size)
+			.sum::<u64>();
+
+		ctx.log(format!(
+			"Extracting {} archives into {}",
+			archives.len(),
+			destination.display()
+		));
+
+		for (index, archive) in archives.into_iter().enumerate() {
+			if self.completed_indices.contains(&index) {
+				continue;
+			}
+			ctx.check_interrupt().await?;
+
+			let current = self.sources.paths[index].clone();
+			let target = match self.current_target.clone() {
+				Some(target) => target,
+				None => {
+					let stem = ArchiveFormat::stem(&archive).ok_or_else(|| {
+						JobError::execution(format!(
+							"{} is not a supported archive",
+							current.display()
+						))
+					})?;
+					match resolve_conflict(&destination.join(stem), self.conflict_resolution)
+						.await?
+					{
+						Some(target) => {
+							if self.conflict_resolution == Some(FileConflictResolution::Overwrite) {
+								if archive.starts_with(&target) {
+									return Err(JobError::execution(format!(
+										"Cannot replace {}, which holds the archive being extracted",
+										target.display()
+									)));
+								}
+								remove_existing(&target).await?;
+							}
+							target
+						}
+						None => {
+							ctx.log(format!(
+								"Skipping existing folder for {}",
+								current.display()
+							));
+							self.skipped_count += 1;
+							bytes_done += sizes[index];
+							self.completed_indices.push(index);
+							ctx.checkpoint().await?;
+							continue;
+						}
+					}
+				}
+			};
+			self.current_target = Some(target.clone());
+
+			let mut written = 0;
+			let mut last_report = Instant::now();
+			self.report_progress(&ctx, &current, bytes_done, total_bytes);
+
+			let result = run_with_progress(
+				{
+					let target = target.clone();
+					move |on_progress| extract(&archive, "", &target, on_progress)
+				},
+				|bytes| {
+					written += bytes;
+					if last_report.elapsed() >= PROGRESS_INTERVAL {
+						last_report = Instant::now();
+						self.report_progress(&ctx, &current, bytes_done + written, total_bytes);
+					}
+				},
+			)
+			.await;
+
+			match result {
+				Ok(bytes) => {
+					self.bytes_extracted += bytes;
+					self.extracted.push(SdPath::local(target.clone()));
+
+					let library = ctx.library();
+					if let Err(e) = responder::apply_created(
+						library.core_context(),
+						library.id(),
+						vec![target.clone()],
+					)
+					.await
+					{
+						ctx.add_warning(format!("Failed to index {}: {}", target.display(), e));
+					}
+				}
+				Err(e) => {
+					self.failed_count += 1;
+					ctx.add_non_critical_error(JobError::execution(format!(
+						"Failed to extract {}: {}",
+						current.display(),
+						e
+					)));
+				}
+			}
+
+			bytes_done += sizes[index];
+			self.current_target = None;
+			self.completed_indices.push(index);
+			ctx.checkpoint().await?;
+		}
+
+		ctx.log(format!(
+			"Extraction complete: {} extracted, {} skipped, {} failed",
+			self.extracted.len(),
+			self.skipped_count,
+			self.failed_count
+		));
+
+		Ok(FileExtractOutput {
+			extracted: self.extracted.clone(),
+			skipped_count: self.skipped_count,
+			failed_count: self.failed_count,
+			total_bytes: self.bytes_extracted,
+			duration: self.started_at.elapsed(),
+		})
+	}
+}
+
+/// Remove whatever is at a path an archive is about to be extracted to
+async fn remove_existing(target: &Path) -> JobResult<()> {
+	let metadata = match tokio::fs::symlink_metadata(target).await {
+		Ok(metadata) => metadata,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+		Err(e) => {
+			return Err(JobError::execution(format!(
+				"Failed to read {}: {}",
+				target.display(),
+				e
+			)))
+		}
+	};
+
+	let removed = if metadata.is_dir() {
+		tokio::fs::remove_dir_all(target).await
+	} else {
+		tokio::fs::remove_file(target).await
+	};
+	removed
+		.map_err(|e| JobError::execution(format!("Failed to replace {}: {}", target.display(), e)))
+}
+
+impl FileExtractJob {
+	/// Create a new extract job
+	pub fn new(sources: SdPathBatch, destination: SdPath) -> Self {
+		Self {
+			sources,
+			destination,
+			conflict_resolution: None,
+			completed_indices: Vec::new(),
+			current_target: None,
+			extracted: Vec::new(),
+			skipped_count: 0,
+			failed_count: 0,
+			bytes_extracted: 0,
+			started_at: Instant::now(),
+		}
+	}
+
+	/// Set how an existing folder with an archive's name is handled
+	pub fn with_conflict_resolution(mut self, resolution: Option<FileConflictResolution>) -> Self {
+		self.conflict_resolution = resolution;
+		self
+	}
+
+	fn report_progress(
+		&self,
+		ctx: &JobContext<'_>,
+		current: &SdPath,
+		bytes_done: u64,
+		total_bytes: u64,
+	) {
+		let completed = self.completed_indices.len() as u64;
+		let total = self.sources.paths.len() as u64;
+		let message = format!("Extracting: {}", current.display());
+
+		let progress = if total_bytes > 0 {
+			GenericProgress::new(
+				(bytes_done as f32 / total_bytes as f32).clamp(0.0, 1.0),
+				"Extracting",
+				message,
+			)
+			.with_bytes(bytes_done, total_bytes)
+		} else {
+			GenericProgress::new(0.0, "Extracting", message).with_completion(completed, total)
+		};
+
+		ctx.progress(Progress::generic(
+			progress.with_current_path(current.clone()),
+		));
+	}
+}
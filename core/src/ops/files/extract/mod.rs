@@ -0,0 +1,11 @@
+//! Archive extraction
+//!
+//! Unpacks archives into a folder named after each archive.
+
+pub mod action;
+pub mod input;
+pub mod job;
+
+pub use action::FileExtractAction;
+pub use input::FileExtractInput;
+pub use job::{FileExtractJob, FileExtractOutput};
@@ -1,10 +1,14 @@
 //! File operations - queries and actions for the File domain
 
+pub mod archive;
+pub mod compress;
 pub mod copy;
 pub mod create_folder;
 pub mod delete;
+pub mod extract;
 pub mod query;
 pub mod rename;
+pub mod trash;
 
 pub use create_folder::{CreateFolderAction, CreateFolderInput, CreateFolderOutput};
 pub use query::*;
@@ -136,6 +136,11 @@ impl LibraryQuery for DirectoryListingQuery {
 
 		let db = library.db();
 
+		// Archive contents are listed straight from the archive, never from the index
+		if self.input.path.is_archive() {
+			return self.query_archive_directory_impl(context).await;
+		}
+
 		// Check if this path's location has IndexMode::None
 		if let Some(should_use_ephemeral) = self.check_location_index_mode(db.conn()).await {
 			if should_use_ephemeral {
@@ -504,6 +509,12 @@ impl DirectoryListingQuery {
 						"Sidecar paths not supported for directory listing".to_string(),
 					));
 				}
+				SdPath::Archive { .. } => {
+					// This shouldn't happen since archives are listed separately
+					return Err(QueryError::Internal(
+						"Archive paths are not indexed".to_string(),
+					));
+				}
 			};
 
 			// Create entity model for conversion
@@ -804,6 +815,74 @@ impl DirectoryListingQuery {
 		})
 	}
 
+	/// List a folder inside an archive from the archive's own member listing
+	async fn query_archive_directory_impl(
+		&self,
+		context: Arc<CoreContext>,
+	) -> QueryResult<DirectoryListingOutput> {
+		use crate::ops::files::archive;
+
+		let Some((archive_path, inner_path)) = self.input.path.as_local_archive() else {
+			return Err(QueryError::Internal(format!(
+				"Archives can only be browsed on the device holding them: {}",
+				self.input.path
+			)));
+		};
+
+		let index = archive::load_index_async(archive_path).await.map_err(|e| {
+			QueryError::Internal(format!(
+				"Failed to read archive {}: {}",
+				archive_path.display(),
+				e
+			))
+		})?;
+		let children = index
+			.children(inner_path)
+			.map_err(|e| QueryError::Internal(e.to_string()))?;
+
+		let archive_modified = tokio::fs::metadata(archive_path)
+			.await
+			.and_then(|metadata| metadata.modified())
+			.ok();
+		let registry = context.file_type_registry();
+
+		let mut files = Vec::new();
+		for entry in children {
+			if !self.input.include_hidden.unwrap_or(false) && entry.name().starts_with('.') {
+				continue;
+			}
+
+			let content_kind = if entry.is_dir {
+				crate::domain::ContentKind::Unknown
+			} else {
+				registry.identify_by_extension(std::path::Path::new(entry.name()))
+			};
+			files.push(archive::member_file(
+				self.input.path.join(entry.name()),
+				entry,
+				archive_modified,
+				content_kind,
+			));
+		}
+
+		self.sort_files(&mut files);
+
+		let total_count = files.len() as u32;
+		let has_more = match self.input.limit {
+			Some(limit) if files.len() > limit as usize => {
+				files.truncate(limit as usize);
+				true
+			}
+			_ => false,
+		};
+
+		Ok(DirectoryListingOutput {
+			files,
+			total_count,
+			has_more,
+		})
+	}
+
 	/// Sort files according to the input options
 	fn sort_files(&self, files: &mut Vec<File>) {
 		use crate::domain::file::EntryKind;
@@ -987,6 +1066,12 @@ impl DirectoryListingQuery {
 					"Content-addressed paths not supported for directory browsing".to_string(),
 				))
 			}
+			SdPath::Archive { .. } => {
+				// Archive contents are never indexed
+				Err(QueryError::Internal(
+					"Archive paths are not indexed".to_string(),
+				))
+			}
 		}
 	}
 }
@@ -391,6 +391,11 @@ impl LibraryQuery for MediaListingQuery {
 							"Sidecar paths not supported for media listing".to_string(),
 						));
 					}
+					SdPath::Archive { .. } => {
+						return Err(QueryError::Internal(
+							"Archive paths not supported for media listing".to_string(),
+						));
+					}
 				}
 			} else {
 				// Fallback to constructing path from parent
@@ -416,6 +421,11 @@ impl LibraryQuery for MediaListingQuery {
 							"Sidecar paths not supported for media listing".to_string(),
 						));
 					}
+					SdPath::Archive { .. } => {
+						return Err(QueryError::Internal(
+							"Archive paths not supported for media listing".to_string(),
+						));
+					}
 				}
 			};
 
@@ -610,6 +620,9 @@ impl MediaListingQuery {
 			SdPath::Sidecar { .. } => Err(QueryError::Internal(
 				"Sidecar paths not supported for media listing".to_string(),
 			)),
+			SdPath::Archive { .. } => Err(QueryError::Internal(
+				"Archive paths not supported for media listing".to_string(),
+			)),
 			SdPath::Content { .. } => Err(QueryError::Internal(
 				"Content-addressed paths not supported for media listing".to_string(),
 			)),
@@ -9,7 +9,7 @@ use crate::{
 		action::{error::ActionError, LibraryAction, ValidationResult},
 		job::handle::JobReceipt,
 	},
-	ops::files::copy::job::FileCopyJob,
+	ops::{action::UndoOperation, files::copy::job::FileCopyJob},
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -69,12 +69,35 @@ impl LibraryAction for FileRenameAction {
 					message: "Cannot rename sidecar files directly".to_string(),
 				});
 			}
+			SdPath::Archive { .. } => {
+				return Err(ActionError::Validation {
+					field: "target".to_string(),
+					message: "Cannot rename files inside archives".to_string(),
+				});
+			}
 			_ => {}
 		}
 
 		Ok(ValidationResult::Success { metadata: None })
 	}
 
+	async fn undo_operation(
+		&self,
+		_library: &Arc<crate::library::Library>,
+	) -> Result<Option<UndoOperation>, ActionError> {
+		let Some(source) = self.target.as_local_path() else {
+			return Ok(None);
+		};
+		let destination = source.with_file_name(&self.new_name);
+
+		let moves = UndoOperation::capture_moves([(source.to_path_buf(), destination)]).await?;
+		Ok(moves.map(|moves| UndoOperation::Move { moves }))
+	}
+
+	fn background_job(output: &Self::Output) -> Option<crate::infra::job::types::JobId> {
+		Some(output.id)
+	}
+
 	async fn execute(
 		self,
 		library: Arc<crate::library::Library>,
@@ -0,0 +1,71 @@
+//! Trash empty action handler
+
+use super::{input::FileTrashEmptyInput, output::FileTrashEmptyOutput};
+use crate::{
+	context::CoreContext,
+	infra::action::{error::ActionError, LibraryAction},
+	library::Library,
+	ops::files::trash::{ledger::TrashLedger, purge_from_trash},
+};
+use serde::{Deserialize, Serialize};
+use std::{path::Path, sync::Arc};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileTrashEmptyAction {
+	input: FileTrashEmptyInput,
+}
+
+impl LibraryAction for FileTrashEmptyAction {
+	type Input = FileTrashEmptyInput;
+	type Output = FileTrashEmptyOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		if matches!(&input.item_ids, Some(ids) if ids.is_empty()) {
+			return Err("item_ids cannot be empty; omit it to empty the whole trash".to_string());
+		}
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let db = library.db().conn();
+
+		let items = TrashLedger::list(db)
+			.await
+			.map_err(|e| ActionError::Database(e.to_string()))?
+			.into_iter()
+			.filter(|item| match &self.input.item_ids {
+				Some(ids) => ids.contains(&item.uuid),
+				None => true,
+			});
+
+		let mut output = FileTrashEmptyOutput::default();
+
+		for item in items {
+			match purge_from_trash(Path::new(&item.trash_path)).await {
+				Ok(()) => {
+					TrashLedger::remove(db, item.uuid)
+						.await
+						.map_err(|e| ActionError::Database(e.to_string()))?;
+					output.purged_count += 1;
+					output.bytes_freed += item.size.max(0) as u64;
+				}
+				Err(e) => {
+					tracing::warn!("Failed to purge {} from the trash: {}", item.trash_path, e);
+					output.failed.push(item.uuid);
+				}
+			}
+		}
+
+		Ok(output)
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"files.trash.empty"
+	}
+}
+
+crate::register_library_action!(FileTrashEmptyAction, "files.trash.empty");
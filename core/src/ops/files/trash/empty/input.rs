@@ -0,0 +1,12 @@
+//! Input types for emptying the trash
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+/// Input for permanently removing trashed items
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct FileTrashEmptyInput {
+	/// Trash ledger IDs to purge. `None` empties everything Spacedrive trashed.
+	pub item_ids: Option<Vec<Uuid>>,
+}
@@ -0,0 +1,9 @@
+//! Permanently remove items from the trash
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::FileTrashEmptyAction;
+pub use input::FileTrashEmptyInput;
+pub use output::FileTrashEmptyOutput;
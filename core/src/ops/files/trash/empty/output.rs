@@ -0,0 +1,14 @@
+//! Output types for emptying the trash
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct FileTrashEmptyOutput {
+	/// Number of items permanently removed
+	pub purged_count: usize,
+	pub bytes_freed: u64,
+	/// Items that could not be removed from disk (kept in the ledger)
+	pub failed: Vec<Uuid>,
+}
@@ -0,0 +1,387 @@
+//! freedesktop.org Trash specification support
+//!
+//! Implements the "home trash" from the XDG Trash spec (version 1.0). Trashed
+//! items are moved into `$XDG_DATA_HOME/Trash/files/` and each one gets a
+//! matching `$XDG_DATA_HOME/Trash/info/<name>.trashinfo` recording the original
+//! absolute path and the deletion date, so that both Spacedrive and desktop file
+//! managers can list and restore them.
+//!
+//! Only the home trash is supported. Items on other mounts fail with the
+//! underlying cross-device rename error rather than being copied.
+
+use chrono::{Local, NaiveDateTime};
+use std::{
+	ffi::OsString,
+	io,
+	os::unix::ffi::{OsStrExt, OsStringExt},
+	path::{Path, PathBuf},
+};
+use tokio::{fs, io::AsyncWriteExt};
+
+const TRASH_INFO_HEADER: &str = "[Trash Info]";
+const TRASH_INFO_EXTENSION: &str = "trashinfo";
+const DELETION_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// A freedesktop trash directory (`files/` + `info/`)
+#[derive(Debug, Clone)]
+pub struct FreedesktopTrash {
+	root: PathBuf,
+}
+
+/// Where an item ended up after being trashed
+#[derive(Debug, Clone)]
+pub struct TrashedFile {
+	/// Name of the item inside `files/` (unique within this trash)
+	pub trash_name: OsString,
+	/// Absolute path of the item inside `files/`
+	pub files_path: PathBuf,
+	/// Absolute path of the `.trashinfo` file
+	pub info_path: PathBuf,
+	pub info: TrashInfo,
+}
+
+/// Contents of a `.trashinfo` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashInfo {
+	/// Absolute path the item was trashed from
+	pub original_path: PathBuf,
+	/// Local time the item was trashed
+	pub deletion_date: NaiveDateTime,
+}
+
+impl FreedesktopTrash {
+	/// The home trash of the current user.
+	///
+	/// Resolves to `$XDG_DATA_HOME/Trash`, falling back to `$HOME/.local/share/Trash`
+	/// when `XDG_DATA_HOME` is unset or not absolute, as required by the XDG base
+	/// directory spec.
+	pub fn home() -> io::Result<Self> {
+		let data_home = std::env::var_os("XDG_DATA_HOME")
+			.map(PathBuf::from)
+			.filter(|p| p.is_absolute());
+
+		let data_home = match data_home {
+			Some(dir) => dir,
+			None => {
+				let home = std::env::var_os("HOME")
+					.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "HOME not set"))?;
+				PathBuf::from(home).join(".local/share")
+			}
+		};
+
+		Ok(Self::at(data_home.join("Trash")))
+	}
+
+	/// A trash rooted at an explicit directory
+	pub fn at(root: impl Into<PathBuf>) -> Self {
+		Self { root: root.into() }
+	}
+
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+
+	pub fn files_dir(&self) -> PathBuf {
+		self.root.join("files")
+	}
+
+	pub fn info_dir(&self) -> PathBuf {
+		self.root.join("info")
+	}
+
+	/// Path of the `.trashinfo` file for an item in `files/`
+	pub fn info_path_for(&self, trash_name: &std::ffi::OsStr) -> PathBuf {
+		let mut info_name = trash_name.to_os_string();
+		info_name.push(".");
+		info_name.push(TRASH_INFO_EXTENSION);
+		self.info_dir().join(info_name)
+	}
+
+	/// Move `path` into the trash, writing its `.trashinfo` first.
+	///
+	/// The info file is created with `O_EXCL` to reserve a unique name, as the
+	/// spec requires, and is removed again if the move itself fails.
+	pub async fn trash(&self, path: &Path) -> io::Result<TrashedFile> {
+		let original_path = if path.is_absolute() {
+			path.to_path_buf()
+		} else {
+			std::env::current_dir()?.join(path)
+		};
+
+		let file_name = original_path.file_name().ok_or_else(|| {
+			io::Error::new(io::ErrorKind::InvalidInput, "Path has no file name")
+		})?;
+
+		fs::create_dir_all(self.files_dir()).await?;
+		fs::create_dir_all(self.info_dir()).await?;
+
+		let info = TrashInfo {
+			original_path: original_path.clone(),
+			deletion_date: Local::now().naive_local(),
+		};
+
+		let (trash_name, info_path) = self.reserve_info_file(file_name, &info).await?;
+		let files_path = self.files_dir().join(&trash_name);
+
+		if let Err(e) = fs::rename(&original_path, &files_path).await {
+			let _ = fs::remove_file(&info_path).await;
+			return Err(e);
+		}
+
+		Ok(TrashedFile {
+			trash_name,
+			files_path,
+			info_path,
+			info,
+		})
+	}
+
+	/// Move a trashed item back to `destination` and drop its `.trashinfo`
+	pub async fn restore(&self, trash_name: &std::ffi::OsStr, destination: &Path) -> io::Result<()> {
+		if fs::try_exists(destination).await? {
+			return Err(io::Error::new(
+				io::ErrorKind::AlreadyExists,
+				format!("{} already exists", destination.display()),
+			));
+		}
+
+		if let Some(parent) = destination.parent() {
+			fs::create_dir_all(parent).await?;
+		}
+
+		fs::rename(self.files_dir().join(trash_name), destination).await?;
+
+		match fs::remove_file(self.info_path_for(trash_name)).await {
+			Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+			_ => Ok(()),
+		}
+	}
+
+	/// Permanently remove a trashed item and its `.trashinfo`
+	pub async fn purge(&self, trash_name: &std::ffi::OsStr) -> io::Result<()> {
+		let files_path = self.files_dir().join(trash_name);
+
+		match fs::symlink_metadata(&files_path).await {
+			Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(&files_path).await?,
+			Ok(_) => fs::remove_file(&files_path).await?,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+			Err(e) => return Err(e),
+		}
+
+		match fs::remove_file(self.info_path_for(trash_name)).await {
+			Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+			_ => Ok(()),
+		}
+	}
+
+	/// Read the `.trashinfo` for an item in `files/`
+	pub async fn read_info(&self, trash_name: &std::ffi::OsStr) -> io::Result<TrashInfo> {
+		let contents = fs::read_to_string(self.info_path_for(trash_name)).await?;
+		TrashInfo::parse(&contents)
+	}
+
+	/// Create `info/<name>.trashinfo` exclusively, picking `name (n)` on collision
+	async fn reserve_info_file(
+		&self,
+		file_name: &std::ffi::OsStr,
+		info: &TrashInfo,
+	) -> io::Result<(OsString, PathBuf)> {
+		let contents = info.to_file_contents();
+		let base = Path::new(file_name);
+		let mut counter = 0u32;
+
+		loop {
+			let candidate = if counter == 0 {
+				file_name.to_os_string()
+			} else {
+				let stem = base.file_stem().unwrap_or(file_name).to_string_lossy();
+				match base.extension() {
+					Some(ext) => format!("{} ({}).{}", stem, counter, ext.to_string_lossy()).into(),
+					None => format!("{} ({})", stem, counter).into(),
+				}
+			};
+
+			let info_path = self.info_path_for(&candidate);
+			let files_taken = fs::try_exists(self.files_dir().join(&candidate)).await?;
+
+			if !files_taken {
+				match fs::OpenOptions::new()
+					.write(true)
+					.create_new(true)
+					.open(&info_path)
+					.await
+				{
+					Ok(mut file) => {
+						file.write_all(contents.as_bytes()).await?;
+						file.sync_all().await?;
+						return Ok((candidate, info_path));
+					}
+					Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+					Err(e) => return Err(e),
+				}
+			}
+
+			counter += 1;
+		}
+	}
+}
+
+impl TrashInfo {
+	/// Render as a `.trashinfo` file
+	pub fn to_file_contents(&self) -> String {
+		format!(
+			"{}\nPath={}\nDeletionDate={}\n",
+			TRASH_INFO_HEADER,
+			encode_path(&self.original_path),
+			self.deletion_date.format(DELETION_DATE_FORMAT)
+		)
+	}
+
+	/// Parse a `.trashinfo` file
+	pub fn parse(contents: &str) -> io::Result<Self> {
+		let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+
+		let mut in_group = false;
+		let mut path = None;
+		let mut date = None;
+
+		for line in contents.lines().map(str::trim) {
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			if line.starts_with('[') {
+				in_group = line == TRASH_INFO_HEADER;
+				continue;
+			}
+			if !in_group {
+				continue;
+			}
+			if let Some(value) = line.strip_prefix("Path=") {
+				path = Some(decode_path(value).ok_or_else(|| invalid("Invalid Path encoding"))?);
+			} else if let Some(value) = line.strip_prefix("DeletionDate=") {
+				date = Some(
+					NaiveDateTime::parse_from_str(value, DELETION_DATE_FORMAT)
+						.map_err(|_| invalid("Invalid DeletionDate"))?,
+				);
+			}
+		}
+
+		Ok(Self {
+			original_path: path.ok_or_else(|| invalid("Missing Path key"))?,
+			deletion_date: date.ok_or_else(|| invalid("Missing DeletionDate key"))?,
+		})
+	}
+}
+
+/// Percent-encode a path the way the spec expects (RFC 2396 escaping, `/` kept)
+fn encode_path(path: &Path) -> String {
+	let mut out = String::new();
+	for &byte in path.as_os_str().as_bytes() {
+		match byte {
+			b'A'..=b'Z'
+			| b'a'..=b'z'
+			| b'0'..=b'9'
+			| b'/'
+			| b'-'
+			| b'_'
+			| b'.'
+			| b'~'
+			| b'!'
+			| b'*'
+			| b'\''
+			| b'('
+			| b')' => out.push(byte as char),
+			_ => out.push_str(&format!("%{:02X}", byte)),
+		}
+	}
+	out
+}
+
+fn decode_path(value: &str) -> Option<PathBuf> {
+	let bytes = value.as_bytes();
+	let mut out = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+
+	while i < bytes.len() {
+		if bytes[i] == b'%' {
+			let hex = value.get(i + 1..i + 3)?;
+			out.push(u8::from_str_radix(hex, 16).ok()?);
+			i += 3;
+		} else {
+			out.push(bytes[i]);
+			i += 1;
+		}
+	}
+
+	Some(PathBuf::from(OsString::from_vec(out)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::TempDir;
+
+	#[test]
+	fn test_trash_info_roundtrip() {
+		let info = TrashInfo {
+			original_path: PathBuf::from("/home/user/My Photos/été #1.jpg"),
+			deletion_date: NaiveDateTime::parse_from_str(
+				"2026-10-16T12:30:05",
+				DELETION_DATE_FORMAT,
+			)
+			.unwrap(),
+		};
+
+		let contents = info.to_file_contents();
+		assert!(contents.starts_with("[Trash Info]\n"));
+		assert!(contents.contains("Path=/home/user/My%20Photos/%C3%A9t%C3%A9%20%231.jpg"));
+		assert!(contents.contains("DeletionDate=2026-10-16T12:30:05"));
+
+		assert_eq!(TrashInfo::parse(&contents).unwrap(), info);
+	}
+
+	#[test]
+	fn test_trash_info_rejects_missing_keys() {
+		assert!(TrashInfo::parse("[Trash Info]\nPath=/tmp/a\n").is_err());
+		assert!(TrashInfo::parse("[Other]\nPath=/tmp/a\nDeletionDate=2026-01-01T00:00:00\n").is_err());
+	}
+
+	#[tokio::test]
+	async fn test_trash_restore_and_name_collisions() {
+		let temp = TempDir::new().unwrap();
+		let trash = FreedesktopTrash::at(temp.path().join("Trash"));
+		let source_dir = temp.path().join("src");
+		fs::create_dir_all(&source_dir).await.unwrap();
+
+		let first = source_dir.join("report.txt");
+		fs::write(&first, "one").await.unwrap();
+		let trashed_first = trash.trash(&first).await.unwrap();
+		assert!(!first.exists());
+		assert_eq!(trashed_first.trash_name, OsString::from("report.txt"));
+		assert!(trashed_first.info_path.exists());
+
+		fs::write(&first, "two").await.unwrap();
+		let trashed_second = trash.trash(&first).await.unwrap();
+		assert_eq!(trashed_second.trash_name, OsString::from("report (1).txt"));
+
+		let info = trash.read_info(&trashed_second.trash_name).await.unwrap();
+		assert_eq!(info.original_path, first);
+
+		trash
+			.restore(&trashed_first.trash_name, &first)
+			.await
+			.unwrap();
+		assert_eq!(fs::read_to_string(&first).await.unwrap(), "one");
+		assert!(!trashed_first.info_path.exists());
+
+		// Restoring over an existing file is refused
+		assert!(trash
+			.restore(&trashed_second.trash_name, &first)
+			.await
+			.is_err());
+
+		trash.purge(&trashed_second.trash_name).await.unwrap();
+		assert!(!trashed_second.files_path.exists());
+		assert!(!trashed_second.info_path.exists());
+	}
+}
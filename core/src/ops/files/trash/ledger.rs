@@ -0,0 +1,645 @@
+//! Trash ledger
+//!
+//! Records what Spacedrive moved to the system trash so it can be listed and put
+//! back later. Before a delete job trashes a path, `TrashLedger::snapshot` captures
+//! the indexed entry tree (UUIDs, content identities, tags) while it still exists.
+//! On restore, `TrashLedger::relink` recreates the entries with their original
+//! UUIDs and content links, so the restored files keep their identity instead of
+//! being re-indexed as new files.
+
+use crate::{
+	domain::{
+		addressing::SdPath,
+		tag::{TagApplication, TagSource},
+	},
+	infra::db::entities::{
+		content_identity, entry, entry_closure, tag, trash_item, user_metadata, user_metadata_tag,
+	},
+	library::Library,
+	ops::{
+		indexing::{
+			state::{DirEntry, EntryKind},
+			DatabaseStorage, IndexerState, PathResolver,
+		},
+		metadata::manager::UserMetadataManager,
+	},
+};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+	ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+	QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+use uuid::Uuid;
+
+/// An indexed entry captured at trash time
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TrashedEntry {
+	/// Path relative to the trashed item (empty for the item itself)
+	pub relative_path: PathBuf,
+	pub entry_uuid: Uuid,
+	pub content_identity_uuid: Option<Uuid>,
+	/// Entry type: 0=File, 1=Directory, 2=Symlink
+	pub kind: i32,
+	pub size: i64,
+	pub modified_at: DateTime<Utc>,
+}
+
+/// An entry-scoped tag application captured at trash time
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TrashedTag {
+	pub entry_uuid: Uuid,
+	pub tag_id: Uuid,
+	pub applied_context: Option<String>,
+	pub applied_variant: Option<String>,
+	pub confidence: f32,
+	pub source: String,
+}
+
+/// Everything the ledger needs to know about a path before it is trashed
+#[derive(Debug, Clone)]
+pub struct TrashSnapshot {
+	pub original_path: SdPath,
+	pub name: String,
+	pub kind: i32,
+	pub size: i64,
+	pub entries: Vec<TrashedEntry>,
+	pub tags: Vec<TrashedTag>,
+}
+
+impl TrashSnapshot {
+	/// The trashed item's own entry, if it was indexed
+	pub fn root_entry(&self) -> Option<&TrashedEntry> {
+		self.entries
+			.iter()
+			.find(|e| e.relative_path.as_os_str().is_empty())
+	}
+}
+
+/// Result of re-linking a restored item to the index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum RelinkOutcome {
+	/// The restored entries were recreated with their original UUIDs
+	Relinked { entries: usize },
+	/// The index still held the entry (e.g. no watcher removed it)
+	AlreadyIndexed,
+	/// The restore destination is not inside an indexed location
+	NotIndexed,
+}
+
+pub struct TrashLedger;
+
+impl TrashLedger {
+	/// Capture the index state of a local path that is about to be trashed.
+	///
+	/// Paths that aren't indexed still produce a snapshot (with no entries) so that
+	/// anything Spacedrive trashes can be listed and restored.
+	pub async fn snapshot(db: &DatabaseConnection, path: &SdPath) -> Result<TrashSnapshot> {
+		let local_path = path
+			.as_local_path()
+			.ok_or_else(|| anyhow!("Only local paths can be trashed"))?;
+
+		let name = local_path
+			.file_name()
+			.map(|n| n.to_string_lossy().to_string())
+			.unwrap_or_default();
+
+		let metadata = tokio::fs::symlink_metadata(local_path).await?;
+		let fs_kind = if metadata.is_dir() {
+			1
+		} else if metadata.is_symlink() {
+			2
+		} else {
+			0
+		};
+
+		let Some(root) = PathResolver::resolve_to_entry(db, path).await? else {
+			return Ok(TrashSnapshot {
+				original_path: path.clone(),
+				name,
+				kind: fs_kind,
+				size: if metadata.is_file() {
+					metadata.len() as i64
+				} else {
+					0
+				},
+				entries: Vec::new(),
+				tags: Vec::new(),
+			});
+		};
+
+		let descendant_ids: Vec<i32> = entry_closure::Entity::find()
+			.filter(entry_closure::Column::AncestorId.eq(root.id))
+			.all(db)
+			.await?
+			.into_iter()
+			.map(|c| c.descendant_id)
+			.filter(|id| *id != root.id)
+			.collect();
+
+		let mut models: HashMap<i32, entry::Model> = HashMap::new();
+		for chunk in descendant_ids.chunks(900) {
+			for model in entry::Entity::find()
+				.filter(entry::Column::Id.is_in(chunk.to_vec()))
+				.all(db)
+				.await?
+			{
+				models.insert(model.id, model);
+			}
+		}
+
+		let content_ids: Vec<i32> = models
+			.values()
+			.chain(std::iter::once(&root))
+			.filter_map(|m| m.content_id)
+			.collect();
+		let mut content_uuids: HashMap<i32, Uuid> = HashMap::new();
+		for chunk in content_ids.chunks(900) {
+			for ci in content_identity::Entity::find()
+				.filter(content_identity::Column::Id.is_in(chunk.to_vec()))
+				.all(db)
+				.await?
+			{
+				if let Some(uuid) = ci.uuid {
+					content_uuids.insert(ci.id, uuid);
+				}
+			}
+		}
+
+		let mut entries = Vec::with_capacity(models.len() + 1);
+		for model in std::iter::once(&root).chain(models.values()) {
+			let Some(entry_uuid) = model.uuid else {
+				continue;
+			};
+			let relative_path = if model.id == root.id {
+				PathBuf::new()
+			} else {
+				match relative_path_of(model, root.id, &models) {
+					Some(path) => path,
+					None => continue,
+				}
+			};
+			entries.push(TrashedEntry {
+				relative_path,
+				entry_uuid,
+				content_identity_uuid: model
+					.content_id
+					.and_then(|id| content_uuids.get(&id).copied()),
+				kind: model.kind,
+				size: model.size,
+				modified_at: model.modified_at,
+			});
+		}
+
+		let tags =
+			Self::collect_entry_tags(db, entries.iter().map(|e| e.entry_uuid).collect()).await?;
+
+		Ok(TrashSnapshot {
+			original_path: path.clone(),
+			name,
+			kind: root.kind,
+			size: if root.kind == 1 {
+				root.aggregate_size
+			} else {
+				root.size
+			},
+			entries,
+			tags,
+		})
+	}
+
+	/// Persist a snapshot once the item has been moved into the trash
+	pub async fn record(
+		db: &DatabaseConnection,
+		snapshot: TrashSnapshot,
+		trash_path: &Path,
+	) -> Result<trash_item::Model> {
+		let root = snapshot.root_entry().cloned();
+
+		let model = trash_item::ActiveModel {
+			uuid: Set(Uuid::new_v4()),
+			original_path: Set(serde_json::to_value(&snapshot.original_path)?),
+			trash_path: Set(trash_path.to_string_lossy().to_string()),
+			name: Set(snapshot.name),
+			kind: Set(snapshot.kind),
+			size: Set(snapshot.size),
+			entry_uuid: Set(root.as_ref().map(|r| r.entry_uuid)),
+			content_identity_uuid: Set(root.and_then(|r| r.content_identity_uuid)),
+			entries: Set(serde_json::to_value(&snapshot.entries)?),
+			tags: Set(serde_json::to_value(&snapshot.tags)?),
+			trashed_at: Set(Utc::now()),
+			..Default::default()
+		};
+
+		Ok(model.insert(db).await?)
+	}
+
+	/// All ledger rows, most recently trashed first
+	pub async fn list(db: &DatabaseConnection) -> Result<Vec<trash_item::Model>> {
+		Ok(trash_item::Entity::find()
+			.order_by_desc(trash_item::Column::TrashedAt)
+			.all(db)
+			.await?)
+	}
+
+	pub async fn find(db: &DatabaseConnection, id: Uuid) -> Result<Option<trash_item::Model>> {
+		Ok(trash_item::Entity::find()
+			.filter(trash_item::Column::Uuid.eq(id))
+			.one(db)
+			.await?)
+	}
+
+	/// Whether a ledger row's item is still in the trash, rather than removed by hand or
+	/// expired by the system's trash retention
+	pub async fn is_available(item: &trash_item::Model) -> bool {
+		tokio::fs::symlink_metadata(&item.trash_path).await.is_ok()
+	}
+
+	pub async fn remove(db: &DatabaseConnection, id: Uuid) -> Result<()> {
+		trash_item::Entity::delete_many()
+			.filter(trash_item::Column::Uuid.eq(id))
+			.exec(db)
+			.await?;
+		Ok(())
+	}
+
+	/// Recreate index entries for an item that was moved back to `restored_path`.
+	///
+	/// Entries are created through `DatabaseStorage` (the same writer the indexer
+	/// and watcher use) with the ledger's UUIDs pre-seeded, then linked back to
+	/// their old content identities. Entry-scoped tags whose metadata was dropped
+	/// with the entries are re-applied.
+	pub async fn relink(
+		library: &Arc<Library>,
+		item: &trash_item::Model,
+		restored_path: &Path,
+	) -> Result<RelinkOutcome> {
+		let db = library.db().conn();
+
+		if PathResolver::resolve_to_entry(db, &SdPath::local(restored_path))
+			.await?
+			.is_some()
+		{
+			return Ok(RelinkOutcome::AlreadyIndexed);
+		}
+
+		let Some(parent_path) = restored_path.parent() else {
+			return Ok(RelinkOutcome::NotIndexed);
+		};
+		let parent_id = DatabaseStorage::resolve_parent_id(db, parent_path)
+			.await
+			.map_err(|e| anyhow!("{}", e))?;
+		let Some(parent_id) = parent_id else {
+			return Ok(RelinkOutcome::NotIndexed);
+		};
+		let volume_id = entry::Entity::find_by_id(parent_id)
+			.one(db)
+			.await?
+			.and_then(|p| p.volume_id)
+			.ok_or_else(|| anyhow!("Parent entry {} has no volume", parent_id))?;
+
+		let trashed: Vec<TrashedEntry> = serde_json::from_value(item.entries.clone())?;
+		let by_path: HashMap<PathBuf, &TrashedEntry> = trashed
+			.iter()
+			.map(|e| (restored_path.join(&e.relative_path), e))
+			.collect();
+
+		let mut state = IndexerState::new(&SdPath::local(restored_path));
+		state
+			.entry_id_cache
+			.insert(parent_path.to_path_buf(), parent_id);
+		for (path, trashed_entry) in &by_path {
+			state
+				.ephemeral_uuids
+				.insert(path.clone(), trashed_entry.entry_uuid);
+		}
+
+		// Walk breadth-first so every parent exists before its children
+		let mut queue = std::collections::VecDeque::from([restored_path.to_path_buf()]);
+		let mut created = 0usize;
+
+		while let Some(path) = queue.pop_front() {
+			let metadata = DatabaseStorage::extract_metadata(&path, None).await?;
+			let dir_entry = DirEntry {
+				path: path.clone(),
+				kind: metadata.kind,
+				size: metadata.size,
+				modified: metadata.modified,
+				inode: metadata.inode,
+			};
+
+			let entry_id = DatabaseStorage::create_entry(
+				&mut state,
+				db,
+				Some(library.as_ref()),
+				&dir_entry,
+				volume_id,
+				parent_path,
+			)
+			.await
+			.map_err(|e| anyhow!("{}", e))?;
+			created += 1;
+
+			if let Some(content_uuid) = by_path.get(&path).and_then(|e| e.content_identity_uuid) {
+				Self::link_content(library, db, entry_id, content_uuid).await?;
+			}
+
+			if metadata.kind == EntryKind::Directory {
+				let mut dir = tokio::fs::read_dir(&path).await?;
+				while let Some(child) = dir.next_entry().await? {
+					queue.push_back(child.path());
+				}
+			}
+		}
+
+		let tags: Vec<TrashedTag> = serde_json::from_value(item.tags.clone())?;
+		Self::reapply_entry_tags(library, tags).await?;
+
+		Ok(RelinkOutcome::Relinked { entries: created })
+	}
+
+	/// Point a recreated entry back at its old content identity, if it still exists
+	async fn link_content(
+		library: &Arc<Library>,
+		db: &DatabaseConnection,
+		entry_id: i32,
+		content_uuid: Uuid,
+	) -> Result<()> {
+		let Some(content) = content_identity::Entity::find()
+			.filter(content_identity::Column::Uuid.eq(content_uuid))
+			.one(db)
+			.await?
+		else {
+			// Content was garbage collected; the content phase will re-identify it
+			return Ok(());
+		};
+
+		let Some(model) = entry::Entity::find_by_id(entry_id).one(db).await? else {
+			return Ok(());
+		};
+
+		let entry_count = content.entry_count;
+		let mut content_active: content_identity::ActiveModel = content.into();
+		content_active.entry_count = Set(entry_count + 1);
+		let content = content_active.update(db).await?;
+
+		// Sync the content identity before the entry that references it
+		library
+			.sync_model_with_db(&content, crate::infra::sync::ChangeType::Update, db)
+			.await
+			.map_err(|e| anyhow!("Failed to sync relinked content identity: {}", e))?;
+
+		let mut entry_active: entry::ActiveModel = model.into();
+		entry_active.content_id = Set(Some(content.id));
+		let updated = entry_active.update(db).await?;
+
+		library
+			.sync_model_with_db(&updated, crate::infra::sync::ChangeType::Update, db)
+			.await
+			.map_err(|e| anyhow!("Failed to sync relinked entry: {}", e))?;
+
+		Ok(())
+	}
+
+	/// Re-apply entry-scoped tags whose user metadata no longer exists
+	async fn reapply_entry_tags(library: &Arc<Library>, tags: Vec<TrashedTag>) -> Result<()> {
+		if tags.is_empty() {
+			return Ok(());
+		}
+
+		let db = library.db().conn();
+		let manager = UserMetadataManager::new(Arc::new(db.clone()));
+
+		let mut by_entry: HashMap<Uuid, Vec<TrashedTag>> = HashMap::new();
+		for tag in tags {
+			by_entry.entry(tag.entry_uuid).or_default().push(tag);
+		}
+
+		for (entry_uuid, tags) in by_entry {
+			let existing = manager
+				.get_metadata_by_entry_uuid(entry_uuid)
+				.await
+				.map_err(|e| anyhow!("{}", e))?;
+			if existing.is_some() {
+				continue;
+			}
+
+			let applications = tags
+				.into_iter()
+				.map(|t| TagApplication {
+					tag_id: t.tag_id,
+					applied_context: t.applied_context,
+					applied_variant: t.applied_variant,
+					confidence: t.confidence,
+					source: TagSource::from_str(&t.source).unwrap_or(TagSource::User),
+					instance_attributes: HashMap::new(),
+					created_at: Utc::now(),
+					device_uuid: library.id(),
+				})
+				.collect();
+
+			let models = manager
+				.apply_semantic_tags_to_entry(entry_uuid, applications, library.id())
+				.await
+				.map_err(|e| anyhow!("{}", e))?;
+			for model in models {
+				library
+					.sync_model(&model, crate::infra::sync::ChangeType::Insert)
+					.await
+					.map_err(|e| anyhow!("Failed to sync restored tag: {}", e))?;
+			}
+		}
+
+		Ok(())
+	}
+
+	async fn collect_entry_tags<C: ConnectionTrait>(
+		db: &C,
+		entry_uuids: Vec<Uuid>,
+	) -> Result<Vec<TrashedTag>> {
+		let mut tags = Vec::new();
+
+		for chunk in entry_uuids.chunks(900) {
+			let metadata = user_metadata::Entity::find()
+				.filter(user_metadata::Column::EntryUuid.is_in(chunk.to_vec()))
+				.all(db)
+				.await?;
+
+			for meta in metadata {
+				let Some(entry_uuid) = meta.entry_uuid else {
+					continue;
+				};
+				let applications = user_metadata_tag::Entity::find()
+					.filter(user_metadata_tag::Column::UserMetadataId.eq(meta.id))
+					.find_also_related(tag::Entity)
+					.all(db)
+					.await?;
+
+				for (application, tag) in applications {
+					let Some(tag) = tag else {
+						continue;
+					};
+					tags.push(TrashedTag {
+						entry_uuid,
+						tag_id: tag.uuid,
+						applied_context: application.applied_context,
+						applied_variant: application.applied_variant,
+						confidence: application.confidence,
+						source: application.source,
+					});
+				}
+			}
+		}
+
+		Ok(tags)
+	}
+}
+
+/// Build the path of `model` relative to the trashed root by walking parent links
+fn relative_path_of(
+	model: &entry::Model,
+	root_id: i32,
+	models: &HashMap<i32, entry::Model>,
+) -> Option<PathBuf> {
+	let mut components = vec![file_name_of(model)];
+	let mut parent = model.parent_id;
+
+	while let Some(parent_id) = parent {
+		if parent_id == root_id {
+			return Some(components.into_iter().rev().collect());
+		}
+		let parent_model = models.get(&parent_id)?;
+		components.push(file_name_of(parent_model));
+		parent = parent_model.parent_id;
+	}
+
+	None
+}
+
+fn file_name_of(model: &entry::Model) -> String {
+	match &model.extension {
+		Some(ext) => format!("{}.{}", model.name, ext),
+		None => model.name.clone(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{infra::db::Database, ops::files::trash::move_out_of_trash};
+	use tempfile::TempDir;
+
+	async fn ledger_db(temp: &TempDir) -> Database {
+		let db = Database::create(&temp.path().join("library.db"))
+			.await
+			.unwrap();
+		db.migrate().await.unwrap();
+		db
+	}
+
+	/// Snapshot `path`, move it into `bin` and record it, as a delete job would
+	async fn trash(db: &DatabaseConnection, path: &Path, bin: &Path) -> trash_item::Model {
+		let snapshot = TrashLedger::snapshot(db, &SdPath::local(path))
+			.await
+			.unwrap();
+		tokio::fs::create_dir_all(bin).await.unwrap();
+		let trash_path = bin.join(path.file_name().unwrap());
+		tokio::fs::rename(path, &trash_path).await.unwrap();
+		TrashLedger::record(db, snapshot, &trash_path)
+			.await
+			.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_record_and_list() {
+		let temp = TempDir::new().unwrap();
+		let db = ledger_db(&temp).await;
+		let bin = temp.path().join("bin");
+
+		let notes = temp.path().join("notes.txt");
+		tokio::fs::write(&notes, b"hello").await.unwrap();
+		let album = temp.path().join("album");
+		tokio::fs::create_dir_all(album.join("2024")).await.unwrap();
+
+		let first = trash(db.conn(), &notes, &bin).await;
+		let second = trash(db.conn(), &album, &bin).await;
+
+		// Paths outside any location are recorded without index entries
+		assert_eq!(first.name, "notes.txt");
+		assert_eq!(first.kind, 0);
+		assert_eq!(first.size, 5);
+		assert_eq!(first.entry_uuid, None);
+		assert_eq!(second.kind, 1);
+		assert_eq!(
+			serde_json::from_value::<SdPath>(first.original_path.clone()).unwrap(),
+			SdPath::local(&notes)
+		);
+
+		let listed: Vec<_> = TrashLedger::list(db.conn())
+			.await
+			.unwrap()
+			.into_iter()
+			.map(|item| item.uuid)
+			.collect();
+		assert_eq!(listed, [second.uuid, first.uuid]);
+		assert_eq!(
+			TrashLedger::find(db.conn(), first.uuid)
+				.await
+				.unwrap()
+				.unwrap()
+				.trash_path,
+			bin.join("notes.txt").to_string_lossy()
+		);
+	}
+
+	#[tokio::test]
+	async fn test_restore_removes_the_ledger_row() {
+		let temp = TempDir::new().unwrap();
+		let db = ledger_db(&temp).await;
+		let notes = temp.path().join("notes.txt");
+		tokio::fs::write(&notes, b"hello").await.unwrap();
+
+		let item = trash(db.conn(), &notes, &temp.path().join("bin")).await;
+		assert!(!notes.exists());
+
+		move_out_of_trash(Path::new(&item.trash_path), &notes)
+			.await
+			.unwrap();
+		TrashLedger::remove(db.conn(), item.uuid).await.unwrap();
+
+		assert_eq!(tokio::fs::read(&notes).await.unwrap(), b"hello");
+		assert!(TrashLedger::find(db.conn(), item.uuid)
+			.await
+			.unwrap()
+			.is_none());
+		assert!(TrashLedger::list(db.conn()).await.unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_expired_items_are_unavailable() {
+		let temp = TempDir::new().unwrap();
+		let db = ledger_db(&temp).await;
+		let notes = temp.path().join("notes.txt");
+		tokio::fs::write(&notes, b"hello").await.unwrap();
+
+		let item = trash(db.conn(), &notes, &temp.path().join("bin")).await;
+		assert!(TrashLedger::is_available(&item).await);
+
+		// The system's trash retention purges the item behind Spacedrive's back
+		tokio::fs::remove_file(&item.trash_path).await.unwrap();
+		assert!(!TrashLedger::is_available(&item).await);
+
+		// The row is kept until the trash is emptied, so it can still be listed
+		assert!(TrashLedger::find(db.conn(), item.uuid)
+			.await
+			.unwrap()
+			.is_some());
+	}
+}
@@ -0,0 +1,7 @@
+//! List items Spacedrive moved to the trash
+
+pub mod output;
+pub mod query;
+
+pub use output::*;
+pub use query::*;
@@ -0,0 +1,35 @@
+use crate::domain::addressing::SdPath;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// An item in the trash ledger
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TrashedItem {
+	pub id: Uuid,
+	pub name: String,
+	/// Where the item was trashed from
+	pub original_path: SdPath,
+	/// Where the item currently lives inside the system trash
+	pub trash_path: PathBuf,
+	/// Entry type: 0=File, 1=Directory, 2=Symlink
+	pub kind: i32,
+	pub size: i64,
+	pub entry_uuid: Option<Uuid>,
+	pub content_identity_uuid: Option<Uuid>,
+	/// Number of indexed entries captured (the item plus its descendants)
+	pub entry_count: usize,
+	/// Tags that were applied to the item or its descendants
+	pub tag_ids: Vec<Uuid>,
+	pub trashed_at: DateTime<Utc>,
+	/// False if the item was removed from the trash outside of Spacedrive
+	pub available: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FileTrashListOutput {
+	pub items: Vec<TrashedItem>,
+	pub total_size: i64,
+}
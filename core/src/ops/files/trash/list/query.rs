@@ -0,0 +1,93 @@
+use super::output::{FileTrashListOutput, TrashedItem};
+use crate::infra::query::{QueryError, QueryResult};
+use crate::{
+	context::CoreContext,
+	infra::query::LibraryQuery,
+	ops::files::trash::ledger::{TrashLedger, TrashedEntry, TrashedTag},
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::{path::PathBuf, sync::Arc};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FileTrashListQueryInput {
+	/// Also return ledger rows whose item no longer exists in the trash
+	#[serde(default)]
+	pub include_unavailable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FileTrashListQuery {
+	pub include_unavailable: bool,
+}
+
+impl LibraryQuery for FileTrashListQuery {
+	type Input = FileTrashListQueryInput;
+	type Output = FileTrashListOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self {
+			include_unavailable: input.include_unavailable,
+		})
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library selected".to_string()))?;
+
+		let library = context
+			.libraries()
+			.await
+			.get_library(library_id)
+			.await
+			.ok_or_else(|| QueryError::Internal("Library not found".to_string()))?;
+
+		let rows = TrashLedger::list(library.db().conn())
+			.await
+			.map_err(|e| QueryError::Internal(e.to_string()))?;
+
+		let mut items = Vec::with_capacity(rows.len());
+		for row in rows {
+			let available = TrashLedger::is_available(&row).await;
+			if !available && !self.include_unavailable {
+				continue;
+			}
+
+			let original_path = serde_json::from_value(row.original_path)
+				.map_err(|e| QueryError::Internal(format!("Invalid trash ledger path: {}", e)))?;
+			let entries: Vec<TrashedEntry> =
+				serde_json::from_value(row.entries).unwrap_or_default();
+			let tags: Vec<TrashedTag> = serde_json::from_value(row.tags).unwrap_or_default();
+
+			let mut tag_ids: Vec<_> = tags.into_iter().map(|t| t.tag_id).collect();
+			tag_ids.sort_unstable();
+			tag_ids.dedup();
+
+			items.push(TrashedItem {
+				id: row.uuid,
+				name: row.name,
+				original_path,
+				trash_path: PathBuf::from(&row.trash_path),
+				kind: row.kind,
+				size: row.size,
+				entry_uuid: row.entry_uuid,
+				content_identity_uuid: row.content_identity_uuid,
+				entry_count: entries.len(),
+				tag_ids,
+				trashed_at: row.trashed_at,
+				available,
+			});
+		}
+
+		let total_size = items.iter().filter(|i| i.available).map(|i| i.size).sum();
+
+		Ok(FileTrashListOutput { items, total_size })
+	}
+}
+
+crate::register_library_query!(FileTrashListQuery, "files.trash.list");
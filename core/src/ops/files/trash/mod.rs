@@ -0,0 +1,94 @@
+//! Trash operations - browsing, restoring and emptying items trashed by Spacedrive
+//!
+//! `DeleteMode::Trash` records every trashed item in the trash ledger (see
+//! [`ledger`]). These operations read that ledger, move items back to where they
+//! came from and re-link them to their old index entries, or purge them for good.
+
+pub mod empty;
+#[cfg(all(unix, not(target_os = "macos")))]
+pub mod freedesktop;
+pub mod ledger;
+pub mod list;
+pub mod restore;
+
+pub use empty::{FileTrashEmptyAction, FileTrashEmptyInput, FileTrashEmptyOutput};
+pub use ledger::{RelinkOutcome, TrashLedger, TrashSnapshot, TrashedEntry, TrashedTag};
+pub use list::{FileTrashListQuery, FileTrashListQueryInput, FileTrashListOutput, TrashedItem};
+pub use restore::{FileTrashRestoreAction, FileTrashRestoreInput, FileTrashRestoreOutput};
+
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Move an item out of the system trash to `destination`.
+///
+/// On Linux the item's `.trashinfo` is removed as well when it lives in the
+/// freedesktop home trash; elsewhere this is a plain rename.
+pub(crate) async fn move_out_of_trash(
+	trash_path: &Path,
+	destination: &Path,
+) -> Result<(), std::io::Error> {
+	#[cfg(all(unix, not(target_os = "macos")))]
+	if let Some((trash, name)) = freedesktop_item(trash_path) {
+		return trash.restore(&name, destination).await;
+	}
+
+	if fs::try_exists(destination).await? {
+		return Err(std::io::Error::new(
+			std::io::ErrorKind::AlreadyExists,
+			format!("{} already exists", destination.display()),
+		));
+	}
+	if let Some(parent) = destination.parent() {
+		fs::create_dir_all(parent).await?;
+	}
+	fs::rename(trash_path, destination).await
+}
+
+/// Permanently remove an item from the system trash
+pub(crate) async fn purge_from_trash(trash_path: &Path) -> Result<(), std::io::Error> {
+	#[cfg(all(unix, not(target_os = "macos")))]
+	if let Some((trash, name)) = freedesktop_item(trash_path) {
+		return trash.purge(&name).await;
+	}
+
+	match fs::symlink_metadata(trash_path).await {
+		Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(trash_path).await,
+		Ok(_) => fs::remove_file(trash_path).await,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+		Err(e) => Err(e),
+	}
+}
+
+/// `name (1).ext`, `name (2).ext`, ... next to `path` until one is free
+pub(crate) async fn unique_sibling_path(path: &Path) -> Result<PathBuf, std::io::Error> {
+	let stem = path
+		.file_stem()
+		.map(|s| s.to_string_lossy().to_string())
+		.unwrap_or_default();
+	let extension = path.extension().map(|e| e.to_string_lossy().to_string());
+
+	let mut counter = 1;
+	loop {
+		let name = match &extension {
+			Some(ext) => format!("{} ({}).{}", stem, counter, ext),
+			None => format!("{} ({})", stem, counter),
+		};
+		let candidate = path.with_file_name(name);
+		if !fs::try_exists(&candidate).await? {
+			return Ok(candidate);
+		}
+		counter += 1;
+	}
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn freedesktop_item(
+	trash_path: &Path,
+) -> Option<(freedesktop::FreedesktopTrash, std::ffi::OsString)> {
+	let files_dir = trash_path.parent()?;
+	if files_dir.file_name()? != "files" {
+		return None;
+	}
+	let trash = freedesktop::FreedesktopTrash::at(files_dir.parent()?);
+	Some((trash, trash_path.file_name()?.to_os_string()))
+}
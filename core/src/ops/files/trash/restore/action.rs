@@ -0,0 +1,182 @@
+//! Trash restore action handler
+
+use super::{
+	input::FileTrashRestoreInput,
+	output::{FileTrashRestoreOutput, RestoredTrashItem, TrashRestoreFailure},
+};
+use crate::{
+	context::CoreContext,
+	domain::addressing::SdPath,
+	infra::action::{error::ActionError, LibraryAction},
+	library::Library,
+	ops::files::{
+		copy::action::FileConflictResolution,
+		delete::LocalDeleteStrategy,
+		trash::{ledger::TrashLedger, move_out_of_trash, unique_sibling_path},
+	},
+};
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Arc};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileTrashRestoreAction {
+	input: FileTrashRestoreInput,
+}
+
+impl FileTrashRestoreAction {
+	pub fn new(input: FileTrashRestoreInput) -> Self {
+		Self { input }
+	}
+}
+
+impl LibraryAction for FileTrashRestoreAction {
+	type Input = FileTrashRestoreInput;
+	type Output = FileTrashRestoreOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		input.validate()?;
+		Ok(Self::new(input))
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let db = library.db().conn();
+		let mut output = FileTrashRestoreOutput::default();
+		let mut restored_entry_uuids = Vec::new();
+
+		for id in self.input.item_ids {
+			let Some(item) = TrashLedger::find(db, id)
+				.await
+				.map_err(|e| ActionError::Database(e.to_string()))?
+			else {
+				output.failed.push(TrashRestoreFailure {
+					id,
+					error: "Trash item not found".to_string(),
+				});
+				continue;
+			};
+
+			let original_path: SdPath = serde_json::from_value(item.original_path.clone())?;
+			let Some(original_local) = original_path.as_local_path().map(|p| p.to_path_buf())
+			else {
+				output.failed.push(TrashRestoreFailure {
+					id,
+					error: "Original path is not on this device".to_string(),
+				});
+				continue;
+			};
+
+			let trash_path = PathBuf::from(&item.trash_path);
+			if !TrashLedger::is_available(&item).await {
+				output.failed.push(TrashRestoreFailure {
+					id,
+					error: "Item is no longer in the trash".to_string(),
+				});
+				continue;
+			}
+
+			let io_error =
+				|e: std::io::Error| ActionError::io_error(original_local.display().to_string(), e);
+
+			let destination = if tokio::fs::try_exists(&original_local).await? {
+				match self
+					.input
+					.on_conflict
+					.unwrap_or(FileConflictResolution::Skip)
+				{
+					FileConflictResolution::Skip => {
+						output.skipped.push(id);
+						continue;
+					}
+					FileConflictResolution::Abort => {
+						return Err(ActionError::Validation {
+							field: "on_conflict".to_string(),
+							message: format!(
+								"{} already exists, restore aborted",
+								original_local.display()
+							),
+						});
+					}
+					FileConflictResolution::AutoModifyName => unique_sibling_path(&original_local)
+						.await
+						.map_err(io_error)?,
+					FileConflictResolution::Overwrite => {
+						// Trash the occupant rather than destroying it, so it can be restored too
+						let occupant = SdPath::local(&original_local);
+						let snapshot = TrashLedger::snapshot(db, &occupant)
+							.await
+							.map_err(|e| ActionError::Internal(e.to_string()))?;
+						let occupant_trash_path = LocalDeleteStrategy::default()
+							.trash(&original_local)
+							.await
+							.map_err(io_error)?;
+						TrashLedger::record(db, snapshot, &occupant_trash_path)
+							.await
+							.map_err(|e| ActionError::Database(e.to_string()))?;
+						original_local.clone()
+					}
+				}
+			} else {
+				original_local.clone()
+			};
+
+			if let Err(e) = move_out_of_trash(&trash_path, &destination).await {
+				output.failed.push(TrashRestoreFailure {
+					id,
+					error: format!("Failed to move item out of the trash: {}", e),
+				});
+				continue;
+			}
+
+			let relink = match TrashLedger::relink(&library, &item, &destination).await {
+				Ok(outcome) => outcome,
+				Err(e) => {
+					// The file is back in place; the watcher or next rescan will index it
+					tracing::warn!(
+						"Restored {} but failed to re-link its index entries: {}",
+						destination.display(),
+						e
+					);
+					crate::ops::files::trash::RelinkOutcome::NotIndexed
+				}
+			};
+
+			TrashLedger::remove(db, id)
+				.await
+				.map_err(|e| ActionError::Database(e.to_string()))?;
+
+			if let Some(entry_uuid) = item.entry_uuid {
+				restored_entry_uuids.push(entry_uuid);
+			}
+
+			output.restored.push(RestoredTrashItem {
+				id,
+				restored_path: SdPath::local(destination),
+				relink,
+			});
+		}
+
+		// Emit resource events for restored files (frontend reactivity)
+		if !restored_entry_uuids.is_empty() {
+			let resource_manager =
+				crate::domain::ResourceManager::new(Arc::new(db.clone()), context.events.clone());
+			if let Err(e) = resource_manager
+				.emit_resource_events("file", restored_entry_uuids)
+				.await
+			{
+				tracing::warn!("Failed to emit file resource events after restore: {}", e);
+			}
+		}
+
+		Ok(output)
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"files.trash.restore"
+	}
+}
+
+crate::register_library_action!(FileTrashRestoreAction, "files.trash.restore");
@@ -0,0 +1,34 @@
+//! Input types for trash restore operations
+
+use crate::ops::files::copy::action::FileConflictResolution;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+/// Input for restoring trashed items
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FileTrashRestoreInput {
+	/// Trash ledger IDs of the items to restore
+	pub item_ids: Vec<Uuid>,
+
+	/// What to do when something already exists at the original path.
+	/// Defaults to skipping the item.
+	pub on_conflict: Option<FileConflictResolution>,
+}
+
+impl FileTrashRestoreInput {
+	pub fn new(item_ids: Vec<Uuid>) -> Self {
+		Self {
+			item_ids,
+			on_conflict: None,
+		}
+	}
+
+	/// Validate the input
+	pub fn validate(&self) -> Result<(), String> {
+		if self.item_ids.is_empty() {
+			return Err("At least one trash item must be specified".to_string());
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,9 @@
+//! Restore items from the trash to their original location
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::FileTrashRestoreAction;
+pub use input::FileTrashRestoreInput;
+pub use output::FileTrashRestoreOutput;
@@ -0,0 +1,31 @@
+//! Output types for trash restore operations
+
+use crate::{domain::addressing::SdPath, ops::files::trash::ledger::RelinkOutcome};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+/// A restored trash item
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RestoredTrashItem {
+	pub id: Uuid,
+	/// Where the item was restored to (differs from the original path when renamed)
+	pub restored_path: SdPath,
+	/// How the item was reconnected to the index
+	pub relink: RelinkOutcome,
+}
+
+/// A trash item that could not be restored
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TrashRestoreFailure {
+	pub id: Uuid,
+	pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct FileTrashRestoreOutput {
+	pub restored: Vec<RestoredTrashItem>,
+	/// Items skipped because their original path is occupied
+	pub skipped: Vec<Uuid>,
+	pub failed: Vec<TrashRestoreFailure>,
+}
@@ -405,7 +405,13 @@ impl ChangeHandler for DatabaseAdapter {
 		use crate::ops::indexing::processor::{
 			load_location_processor_config, ContentHashProcessor, ProcessorEntry,
 		};
-		use crate::ops::media::{ocr::OcrProcessor, proxy::ProxyProcessor};
+		use crate::ops::media::{
+			ocr::OcrProcessor,
+			proxy::ProxyProcessor,
+			text_extraction::{
+				TextExtractionJob, TextExtractionJobConfig, TextExtractionProcessor,
+			},
+		};
 		#[cfg(feature = "ffmpeg")]
 		use crate::ops::media::{
 			thumbnail::ThumbnailProcessor, thumbstrip::ThumbstripProcessor,
@@ -584,6 +590,38 @@ impl ChangeHandler for DatabaseAdapter {
 			}
 		}
 
+		// Text extraction (follows the location's job policy so edited documents are re-indexed)
+		let text_policy = entities::location::Entity::find()
+			.filter(entities::location::Column::Uuid.eq(self.location_id))
+			.one(&self.db)
+			.await
+			.ok()
+			.flatten()
+			.and_then(|loc| loc.job_policies)
+			.and_then(|json| {
+				serde_json::from_str::<crate::domain::location::JobPolicies>(&json).ok()
+			})
+			.unwrap_or_default()
+			.text_extraction;
+
+		if let (true, Some(entry_uuid)) = (text_policy.enabled, entry.uuid) {
+			let proc_entry = build_proc_entry(&self.db, entry).await?;
+			let max_file_size = text_policy.max_file_size_mb * 1024 * 1024;
+			let text_proc = TextExtractionProcessor::new().with_max_file_size(max_file_size);
+
+			// Parsing documents can take seconds, so queue it rather than stall the watcher
+			if text_proc.should_process(&proc_entry) {
+				let job = TextExtractionJob::new(TextExtractionJobConfig {
+					entry_uuid: Some(entry_uuid),
+					max_file_size,
+					..Default::default()
+				});
+				if let Err(e) = library.jobs().dispatch(job).await {
+					tracing::warn!("Failed to queue text extraction: {}", e);
+				}
+			}
+		}
+
 		// Speech-to-text
 		#[cfg(feature = "speech-to-text")]
 		if proc_config
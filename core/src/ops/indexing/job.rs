@@ -443,11 +443,16 @@ impl IndexerJob {
 							self.db_operations.1 += state.entries_for_content.len() as u64;
 						}
 					} else {
-						ctx.log("Skipping content identification phase (mode=Shallow)");
+						// Auto-tagging follows content identification, so it is skipped too
+						ctx.log("Skipping content identification and auto-tagging phases (mode=Shallow)");
 						state.phase = Phase::Complete;
 					}
 				}
 
+				Phase::AutoTagging => {
+					phases::run_auto_tag_phase(state, &ctx).await?;
+				}
+
 				Phase::Complete => break,
 			}
 		}
@@ -476,6 +481,9 @@ impl IndexerJob {
 		};
 
 		ctx.log(&metrics.format_summary());
+		ctx.library()
+			.indexing_totals()
+			.record(&state.stats, &metrics);
 
 		#[cfg(feature = "ffmpeg")]
 		if self.config.mode == IndexMode::Deep && !self.config.is_ephemeral() {
@@ -6,7 +6,10 @@
 
 use serde::{Deserialize, Serialize};
 use specta::Type;
-use std::time::{Duration, Instant};
+use std::{
+	sync::atomic::{AtomicU64, Ordering},
+	time::{Duration, Instant},
+};
 
 /// Complete snapshot of indexer performance after job completion.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -195,3 +198,69 @@ impl IndexerMetrics {
 		)
 	}
 }
+
+/// Running totals of every indexer job completed in a library since the daemon started.
+///
+/// Exported as monotonic counters on the server's `/metrics` endpoint, where throughput is
+/// derived from their rate of change.
+#[derive(Debug, Default)]
+pub struct IndexingTotals {
+	runs: AtomicU64,
+	files: AtomicU64,
+	dirs: AtomicU64,
+	bytes: AtomicU64,
+	errors: AtomicU64,
+	duration_ms: AtomicU64,
+	// f64 bit patterns of the most recent run's throughput
+	last_files_per_second: AtomicU64,
+	last_bytes_per_second: AtomicU64,
+}
+
+/// Serializable view of [`IndexingTotals`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct IndexingTotalsSnapshot {
+	pub runs: u64,
+	pub files: u64,
+	pub dirs: u64,
+	pub bytes: u64,
+	pub errors: u64,
+	pub duration_ms: u64,
+	pub last_files_per_second: f64,
+	pub last_bytes_per_second: f64,
+}
+
+impl IndexingTotals {
+	/// Adds a finished job's stats to the totals.
+	pub fn record(&self, stats: &super::state::IndexerStats, metrics: &IndexerMetrics) {
+		self.runs.fetch_add(1, Ordering::Relaxed);
+		self.files.fetch_add(stats.files, Ordering::Relaxed);
+		self.dirs.fetch_add(stats.dirs, Ordering::Relaxed);
+		self.bytes.fetch_add(stats.bytes, Ordering::Relaxed);
+		self.errors.fetch_add(stats.errors, Ordering::Relaxed);
+		self.duration_ms
+			.fetch_add(metrics.total_duration.as_millis() as u64, Ordering::Relaxed);
+		self.last_files_per_second.store(
+			(metrics.files_per_second as f64).to_bits(),
+			Ordering::Relaxed,
+		);
+		self.last_bytes_per_second
+			.store(metrics.bytes_per_second.to_bits(), Ordering::Relaxed);
+	}
+
+	pub fn snapshot(&self) -> IndexingTotalsSnapshot {
+		IndexingTotalsSnapshot {
+			runs: self.runs.load(Ordering::Relaxed),
+			files: self.files.load(Ordering::Relaxed),
+			dirs: self.dirs.load(Ordering::Relaxed),
+			bytes: self.bytes.load(Ordering::Relaxed),
+			errors: self.errors.load(Ordering::Relaxed),
+			duration_ms: self.duration_ms.load(Ordering::Relaxed),
+			last_files_per_second: f64::from_bits(
+				self.last_files_per_second.load(Ordering::Relaxed),
+			),
+			last_bytes_per_second: f64::from_bits(
+				self.last_bytes_per_second.load(Ordering::Relaxed),
+			),
+		}
+	}
+}
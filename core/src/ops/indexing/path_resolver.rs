@@ -238,6 +238,10 @@ impl PathResolver {
 				// Sidecars don't have entries
 				Ok(None)
 			}
+			SdPath::Archive { .. } => {
+				// Archive members are listed ephemerally, not indexed
+				Ok(None)
+			}
 		}
 	}
 
@@ -0,0 +1,71 @@
+//! # Auto-Tagging
+//!
+//! `core::ops::indexing::phases::auto_tag` applies the library's auto-tag rules to the files
+//! content identification just processed, so path, extension, size, date and content kind
+//! rules take effect as files are indexed. Rules on EXIF fields only match once media
+//! metadata has been extracted; the retroactive `AutoTagJob` catches those up.
+//!
+//! The phase only runs in `Content` and `Deep` mode, after content identification. A
+//! `Shallow` index never reaches it, so rules reach shallow-indexed files through
+//! `AutoTagJob` alone.
+
+use crate::{
+	infra::job::prelude::{JobContext, JobError},
+	ops::{indexing::state::IndexerState, tags::rules::AutoTagEngine},
+};
+use std::sync::Arc;
+
+/// Applies enabled auto-tag rules to the entries collected during content identification.
+///
+/// Failures are logged as non-critical: rules that can't be loaded or applied shouldn't
+/// fail indexing.
+pub async fn run_auto_tag_phase(
+	state: &mut IndexerState,
+	ctx: &JobContext<'_>,
+) -> Result<(), JobError> {
+	match AutoTagEngine::load(ctx.library_db(), None).await {
+		Ok(engine) => {
+			ctx.check_interrupt().await?;
+
+			if !engine.is_empty() && !state.entries_for_auto_tag.is_empty() {
+				apply_rules(&engine, &state.entries_for_auto_tag, ctx).await;
+			}
+		}
+		Err(e) => ctx.add_non_critical_error(format!(
+			"Skipping auto-tagging, rules failed to load: {}",
+			e
+		)),
+	}
+
+	state.entries_for_auto_tag.clear();
+	state.phase = crate::ops::indexing::state::Phase::Complete;
+	Ok(())
+}
+
+async fn apply_rules(engine: &AutoTagEngine, entry_ids: &[i32], ctx: &JobContext<'_>) {
+	match engine.apply_to_entries(ctx.library(), entry_ids).await {
+		Ok((stats, tagged_entries)) => {
+			ctx.log(format!(
+				"Auto-tagging applied {} tags across {} files",
+				stats.tags_applied, stats.entries_checked
+			));
+
+			if !tagged_entries.is_empty() {
+				let resource_manager = crate::domain::ResourceManager::new(
+					Arc::new(ctx.library_db().clone()),
+					ctx.library().event_bus().clone(),
+				);
+				if let Err(e) = resource_manager
+					.emit_resource_events("file", tagged_entries)
+					.await
+				{
+					tracing::warn!(
+						"Failed to emit file resource events after auto-tagging: {}",
+						e
+					);
+				}
+			}
+		}
+		Err(e) => ctx.add_non_critical_error(format!("Auto-tagging failed: {}", e)),
+	}
+}
@@ -55,7 +55,7 @@ pub async fn run_content_phase(
 
 	if total == 0 {
 		ctx.log("No files to process for content identification");
-		state.phase = crate::ops::indexing::state::Phase::Complete;
+		state.phase = crate::ops::indexing::state::Phase::AutoTagging;
 		return Ok(());
 	}
 
@@ -71,6 +71,9 @@ pub async fn run_content_phase(
 		let chunk_size = CHUNK_SIZE.min(state.entries_for_content.len());
 		let chunk: Vec<_> = state.entries_for_content.drain(..chunk_size).collect();
 		let chunk_len = chunk.len();
+		state
+			.entries_for_auto_tag
+			.extend(chunk.iter().map(|(entry_id, _)| *entry_id));
 
 		let indexer_progress = IndexerProgress {
 			phase: IndexPhase::ContentIdentification {
@@ -314,6 +317,6 @@ pub async fn run_content_phase(
 		success_count, error_count, total
 	));
 
-	state.phase = crate::ops::indexing::state::Phase::Complete;
+	state.phase = crate::ops::indexing::state::Phase::AutoTagging;
 	Ok(())
 }
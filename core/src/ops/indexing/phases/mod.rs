@@ -1,6 +1,6 @@
 //! # Indexer Execution Phases
 //!
-//! The indexer runs in four sequential phases to enable resumability and incremental
+//! The indexer runs in five sequential phases to enable resumability and incremental
 //! progress tracking. Each phase is independently checkpointed so interrupted jobs can
 //! resume mid-phase without reprocessing completed work. This prevents re-walking large
 //! directories or re-hashing files after crashes or cancellations.
@@ -9,14 +9,16 @@
 //! entries into database records with stable UUIDs. Aggregation bubbles up directory
 //! sizes from leaves to root (required for accurate folder size reporting). Content
 //! identification hashes file contents for deduplication and generates deterministic
-//! sync UUIDs.
+//! sync UUIDs. Auto-tagging then applies the library's tag rules to the identified files.
 
 pub mod aggregation;
+pub mod auto_tag;
 pub mod content;
 pub mod discovery;
 pub mod processing;
 
 pub use aggregation::run_aggregation_phase;
+pub use auto_tag::run_auto_tag_phase;
 pub use content::run_content_phase;
 pub use discovery::run_discovery_phase;
 pub use processing::run_processing_phase;
@@ -3,13 +3,16 @@
 //! Thin adapter over `DatabaseAdapter` that translates filesystem
 //! events into database mutations. The watcher calls `apply_batch` with events;
 //! this module delegates to the unified change handling infrastructure.
+//! Jobs that create files call `apply_created` to index them without
+//! waiting for the watcher to notice.
 
 use crate::context::CoreContext;
 use crate::ops::indexing::change_detection::{self, ChangeConfig, DatabaseAdapter};
 use crate::ops::indexing::rules::RuleToggles;
 use anyhow::Result;
 use sd_fs_watcher::FsEvent;
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -79,3 +82,58 @@ pub async fn apply_batch(
 
 	change_detection::apply_batch(&mut handler, events, &config).await
 }
+
+/// Indexes paths that were just created by a job.
+///
+/// Each path is matched to the watched location containing it and applied as
+/// a create event, so new folders are indexed along with their contents. Paths
+/// outside any watched location are ignored. The watcher's own events for the
+/// same paths arrive later and are treated as modifies of the existing entries.
+pub async fn apply_created(
+	context: &Arc<CoreContext>,
+	library_id: Uuid,
+	paths: Vec<PathBuf>,
+) -> Result<()> {
+	let Some(watcher) = context.get_fs_watcher().await else {
+		return Ok(());
+	};
+	let locations = watcher.watched_locations().await;
+
+	let mut by_location = HashMap::new();
+	for path in paths {
+		// The innermost location wins when locations are nested
+		let Some(meta) = locations
+			.iter()
+			.filter(|meta| meta.library_id == library_id && path.starts_with(&meta.root_path))
+			.max_by_key(|meta| meta.root_path.components().count())
+		else {
+			continue;
+		};
+
+		let event = if path.is_dir() {
+			FsEvent::create_dir(path)
+		} else {
+			FsEvent::create_file(path)
+		};
+		by_location
+			.entry(meta.id)
+			.or_insert_with(|| (meta, Vec::new()))
+			.1
+			.push(event);
+	}
+
+	for (meta, events) in by_location.into_values() {
+		apply_batch(
+			context,
+			library_id,
+			meta.id,
+			events,
+			meta.rule_toggles,
+			&meta.root_path,
+			None,
+		)
+		.await?;
+	}
+
+	Ok(())
+}
@@ -67,6 +67,7 @@ pub(crate) enum Phase {
 	Processing,
 	Aggregation,
 	ContentIdentification,
+	AutoTagging,
 	Complete,
 }
 
@@ -119,6 +120,8 @@ pub struct IndexerState {
 	pub(crate) seen_paths: HashSet<PathBuf>,
 	pub(crate) entry_batches: Vec<Vec<DirEntry>>,
 	pub(crate) entries_for_content: Vec<(i32, PathBuf)>,
+	#[serde(default)]
+	pub(crate) entries_for_auto_tag: Vec<i32>,
 	pub(crate) entry_id_cache: HashMap<PathBuf, i32>,
 	// UUIDs from ephemeral indexing preserved when creating persistent entries.
 	// This ensures files browsed before enabling indexing keep the same UUID,
@@ -161,6 +164,7 @@ impl IndexerState {
 			seen_paths: HashSet::new(),
 			entry_batches: Vec::new(),
 			entries_for_content: Vec::new(),
+			entries_for_auto_tag: Vec::new(),
 			entry_id_cache: HashMap::new(),
 			ephemeral_uuids: HashMap::new(),
 			existing_entries: HashMap::new(),
@@ -0,0 +1,65 @@
+//! Create job schedule action
+
+use super::{input::CreateJobScheduleInput, output::CreateJobScheduleOutput};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::{error::ActionError, LibraryAction},
+		job::error::JobError,
+	},
+	library::Library,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateJobScheduleAction {
+	input: CreateJobScheduleInput,
+}
+
+impl LibraryAction for CreateJobScheduleAction {
+	type Input = CreateJobScheduleInput;
+	type Output = CreateJobScheduleOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		input.validate()?;
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let schedule = library
+			.jobs()
+			.create_schedule(self.input.into())
+			.await
+			.map_err(|e| match e {
+				JobError::NotFound(message) => ActionError::Validation {
+					field: "job_name".to_string(),
+					message,
+				},
+				JobError::Serialization(message) => ActionError::Validation {
+					field: "args".to_string(),
+					message,
+				},
+				JobError::Other(message) => ActionError::Validation {
+					field: "trigger".to_string(),
+					message,
+				},
+				other => ActionError::Internal(format!("Failed to create job schedule: {}", other)),
+			})?;
+
+		Ok(CreateJobScheduleOutput {
+			schedule_id: schedule.id,
+			next_run_at: schedule.next_run_at,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"jobs.schedule.create"
+	}
+}
+
+crate::register_library_action!(CreateJobScheduleAction, "jobs.schedule.create");
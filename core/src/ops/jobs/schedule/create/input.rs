@@ -0,0 +1,63 @@
+//! Input for creating a job schedule
+
+use crate::infra::job::schedule::{
+	JobScheduleSpec, MissedRunPolicy, ScheduleCondition, ScheduleTrigger,
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CreateJobScheduleInput {
+	/// Display name for the schedule
+	pub name: String,
+
+	/// Registered job to dispatch, e.g. `"indexer"` or `"auto_tag"`
+	pub job_name: String,
+
+	/// Job arguments, as accepted by `jobs` dispatch by name
+	pub args: serde_json::Value,
+
+	/// Cron expression or interval the job runs on
+	pub trigger: ScheduleTrigger,
+
+	/// Conditions that must all hold for a due run to be dispatched
+	#[serde(default)]
+	pub conditions: Vec<ScheduleCondition>,
+
+	/// Random delay of up to this many seconds added to each run
+	#[serde(default)]
+	pub jitter_seconds: u32,
+
+	/// What to do with runs missed while the daemon was stopped
+	#[serde(default)]
+	pub missed_runs: MissedRunPolicy,
+}
+
+impl CreateJobScheduleInput {
+	/// Validate the input
+	pub fn validate(&self) -> Result<(), String> {
+		if self.name.trim().is_empty() {
+			return Err("name cannot be empty".to_string());
+		}
+
+		if self.job_name.trim().is_empty() {
+			return Err("job_name cannot be empty".to_string());
+		}
+
+		self.trigger.validate()
+	}
+}
+
+impl From<CreateJobScheduleInput> for JobScheduleSpec {
+	fn from(input: CreateJobScheduleInput) -> Self {
+		Self {
+			name: input.name.trim().to_string(),
+			job_name: input.job_name,
+			args: input.args,
+			trigger: input.trigger,
+			conditions: input.conditions,
+			jitter_seconds: input.jitter_seconds,
+			missed_runs: input.missed_runs,
+		}
+	}
+}
@@ -0,0 +1,9 @@
+//! Create a job schedule
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::CreateJobScheduleAction;
+pub use input::CreateJobScheduleInput;
+pub use output::CreateJobScheduleOutput;
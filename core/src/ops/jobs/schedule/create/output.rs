@@ -0,0 +1,14 @@
+//! Output for creating a job schedule
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CreateJobScheduleOutput {
+	pub schedule_id: Uuid,
+
+	/// When the job will first run
+	pub next_run_at: DateTime<Utc>,
+}
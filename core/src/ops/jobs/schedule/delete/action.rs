@@ -0,0 +1,44 @@
+//! Delete job schedule action
+
+use super::{input::DeleteJobScheduleInput, output::DeleteJobScheduleOutput};
+use crate::{
+	context::CoreContext,
+	infra::action::{error::ActionError, LibraryAction},
+	library::Library,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteJobScheduleAction {
+	input: DeleteJobScheduleInput,
+}
+
+impl LibraryAction for DeleteJobScheduleAction {
+	type Input = DeleteJobScheduleInput;
+	type Output = DeleteJobScheduleOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let deleted = library
+			.jobs()
+			.delete_schedule(self.input.schedule_id)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to delete job schedule: {}", e)))?;
+
+		Ok(DeleteJobScheduleOutput { deleted })
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"jobs.schedule.delete"
+	}
+}
+
+crate::register_library_action!(DeleteJobScheduleAction, "jobs.schedule.delete");
@@ -0,0 +1,10 @@
+//! Input for deleting a job schedule
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DeleteJobScheduleInput {
+	pub schedule_id: Uuid,
+}
@@ -0,0 +1,9 @@
+//! Delete a job schedule
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::DeleteJobScheduleAction;
+pub use input::DeleteJobScheduleInput;
+pub use output::DeleteJobScheduleOutput;
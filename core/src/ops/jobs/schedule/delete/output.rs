@@ -0,0 +1,10 @@
+//! Output for deleting a job schedule
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DeleteJobScheduleOutput {
+	/// False if no schedule had this ID. Jobs it already dispatched keep running.
+	pub deleted: bool,
+}
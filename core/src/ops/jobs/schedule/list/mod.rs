@@ -0,0 +1,9 @@
+//! List job schedules
+
+pub mod input;
+pub mod output;
+pub mod query;
+
+pub use input::ListJobSchedulesInput;
+pub use output::ListJobSchedulesOutput;
+pub use query::ListJobSchedulesQuery;
@@ -0,0 +1,11 @@
+//! Output for listing job schedules
+
+use crate::infra::job::schedule::JobScheduleInfo;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListJobSchedulesOutput {
+	/// Schedules ordered by next run, soonest first
+	pub schedules: Vec<JobScheduleInfo>,
+}
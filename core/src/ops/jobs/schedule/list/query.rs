@@ -0,0 +1,48 @@
+//! List job schedules query
+
+use super::{input::ListJobSchedulesInput, output::ListJobSchedulesOutput};
+use crate::{
+	context::CoreContext,
+	infra::query::{LibraryQuery, QueryError, QueryResult},
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListJobSchedulesQuery {
+	pub input: ListJobSchedulesInput,
+}
+
+impl LibraryQuery for ListJobSchedulesQuery {
+	type Input = ListJobSchedulesInput;
+	type Output = ListJobSchedulesOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library in session".to_string()))?;
+		let library = context
+			.get_library(library_id)
+			.await
+			.ok_or(QueryError::LibraryNotFound(library_id))?;
+
+		let schedules = library
+			.jobs()
+			.list_schedules()
+			.await
+			.map_err(|e| QueryError::Internal(e.to_string()))?;
+
+		Ok(ListJobSchedulesOutput { schedules })
+	}
+}
+
+crate::register_library_query!(ListJobSchedulesQuery, "jobs.schedule.list");
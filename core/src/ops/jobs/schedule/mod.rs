@@ -0,0 +1,13 @@
+//! Job schedules
+//!
+//! Persisted schedules that dispatch a registered job with JSON args on a cron
+//! expression or interval, optionally only on AC power, when idle or while a volume
+//! is mounted. The library's job manager runs them; see `infra::job::scheduler`.
+
+pub mod create;
+pub mod delete;
+pub mod list;
+
+pub use create::{CreateJobScheduleAction, CreateJobScheduleInput, CreateJobScheduleOutput};
+pub use delete::{DeleteJobScheduleAction, DeleteJobScheduleInput, DeleteJobScheduleOutput};
+pub use list::{ListJobSchedulesInput, ListJobSchedulesOutput, ListJobSchedulesQuery};
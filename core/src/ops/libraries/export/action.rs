@@ -1,10 +1,17 @@
 //! Library export action handler
 
-use super::input::LibraryExportInput;
+use super::{
+	input::LibraryExportInput,
+	manifest::{
+		copy_dir, LibraryExportManifest, CONFIG_FILENAME, DATABASE_FILENAME,
+		LIBRARY_EXPORT_FORMAT_VERSION, MANIFEST_FILENAME, PREVIEWS_DIR, SIDECARS_DIR,
+	},
+};
 use crate::{
 	context::CoreContext,
 	infra::action::{error::ActionError, LibraryAction},
 };
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -32,7 +39,7 @@ impl LibraryAction for LibraryExportAction {
 	async fn execute(
 		self,
 		library: std::sync::Arc<crate::library::Library>,
-		_context: Arc<CoreContext>,
+		context: Arc<CoreContext>,
 	) -> Result<Self::Output, ActionError> {
 		// Ensure parent directory exists
 		if let Some(parent) = self.input.export_path.parent() {
@@ -50,47 +57,81 @@ impl LibraryAction for LibraryExportAction {
 			ActionError::Internal(format!("Failed to create export directory: {}", e))
 		})?;
 
+		let database_path = export_dir.join(DATABASE_FILENAME);
+		if database_path.exists() {
+			return Err(ActionError::Validation {
+				field: "export_path".to_string(),
+				message: "Export directory already contains an export".to_string(),
+			});
+		}
+
 		// Export library config
 		let config = library.config().await;
-		let config_path = export_dir.join("library.json");
+		let config_path = export_dir.join(CONFIG_FILENAME);
 		let config_json = serde_json::to_string_pretty(&config)
 			.map_err(|e| ActionError::Internal(format!("Failed to serialize config: {}", e)))?;
 		tokio::fs::write(&config_path, config_json)
 			.await
 			.map_err(|e| ActionError::Internal(format!("Failed to write config: {}", e)))?;
 
-		// Export database (as SQL dump for portability)
-		// TODO: Implement actual database export
-		let db_export_path = export_dir.join("database.sql");
-		tokio::fs::write(&db_export_path, "-- Database export not yet implemented")
+		// Snapshot the database, consistent even while the library is in use
+		library
+			.db()
+			.snapshot_to(&database_path)
 			.await
-			.map_err(|e| {
-				ActionError::Internal(format!("Failed to write database export: {}", e))
-			})?;
+			.map_err(|e| ActionError::Internal(format!("Failed to export database: {}", e)))?;
+		let schema_version = library.db().schema_version().await?;
 
 		let mut exported_files = vec![
 			config_path.to_string_lossy().to_string(),
-			db_export_path.to_string_lossy().to_string(),
+			database_path.to_string_lossy().to_string(),
 		];
 
-		// Optionally export thumbnails
-		if self.input.include_thumbnails {
-			let thumbnails_src = library.path().join("thumbnails");
-			if thumbnails_src.exists() {
-				// TODO: Copy thumbnails directory
-				exported_files.push("thumbnails/".to_string());
-			}
+		// Optionally export thumbnails, which live with the other sidecars
+		let sidecars_src = library.path().join(SIDECARS_DIR);
+		let includes_sidecars = self.input.include_thumbnails && sidecars_src.exists();
+		if includes_sidecars {
+			copy_dir(&sidecars_src, &export_dir.join(SIDECARS_DIR))
+				.await
+				.map_err(|e| ActionError::Internal(format!("Failed to export sidecars: {}", e)))?;
+			exported_files.push(format!("{}/", SIDECARS_DIR));
 		}
 
 		// Optionally export previews
-		if self.input.include_previews {
-			let previews_src = library.path().join("previews");
-			if previews_src.exists() {
-				// TODO: Copy previews directory
-				exported_files.push("previews/".to_string());
-			}
+		let previews_src = library.path().join(PREVIEWS_DIR);
+		let includes_previews = self.input.include_previews && previews_src.exists();
+		if includes_previews {
+			copy_dir(&previews_src, &export_dir.join(PREVIEWS_DIR))
+				.await
+				.map_err(|e| ActionError::Internal(format!("Failed to export previews: {}", e)))?;
+			exported_files.push(format!("{}/", PREVIEWS_DIR));
 		}
 
+		// Written last, so a partial export is never mistaken for a complete one
+		let manifest = LibraryExportManifest {
+			format_version: LIBRARY_EXPORT_FORMAT_VERSION,
+			library_id: library.id(),
+			library_name: config.name.clone(),
+			schema_version,
+			device_id: context
+				.device_manager
+				.device_id()
+				.map_err(ActionError::device_manager_error)?,
+			exported_at: Utc::now(),
+			includes_sidecars,
+			includes_previews,
+		};
+		manifest
+			.save(export_dir)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to write manifest: {}", e)))?;
+		exported_files.push(
+			export_dir
+				.join(MANIFEST_FILENAME)
+				.to_string_lossy()
+				.to_string(),
+		);
+
 		Ok(super::output::LibraryExportOutput {
 			library_id: library.id(),
 			library_name: config.name.clone(),
@@ -0,0 +1,77 @@
+//! Library export archive manifest
+//!
+//! An export is a directory holding the library's config, a snapshot of its database and,
+//! optionally, its sidecars and previews:
+//!
+//! ```text
+//! export.json    this manifest
+//! library.json   library config
+//! library.db     database snapshot
+//! sidecars/      thumbnails and other derivative data (include_thumbnails)
+//! previews/      (include_previews)
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Bumped whenever the archive layout changes incompatibly
+pub const LIBRARY_EXPORT_FORMAT_VERSION: u32 = 1;
+
+pub const MANIFEST_FILENAME: &str = "export.json";
+pub const CONFIG_FILENAME: &str = "library.json";
+pub const DATABASE_FILENAME: &str = "library.db";
+pub const SIDECARS_DIR: &str = "sidecars";
+pub const PREVIEWS_DIR: &str = "previews";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryExportManifest {
+	pub format_version: u32,
+	pub library_id: Uuid,
+	pub library_name: String,
+	/// Most recent database migration applied when the snapshot was taken
+	pub schema_version: Option<String>,
+	/// Device that produced the export; its locations and volumes are remapped on import
+	pub device_id: Uuid,
+	pub exported_at: DateTime<Utc>,
+	pub includes_sidecars: bool,
+	pub includes_previews: bool,
+}
+
+impl LibraryExportManifest {
+	pub async fn load(archive: &Path) -> std::io::Result<Self> {
+		let json = tokio::fs::read(archive.join(MANIFEST_FILENAME)).await?;
+		serde_json::from_slice(&json)
+			.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+	}
+
+	pub async fn save(&self, archive: &Path) -> std::io::Result<()> {
+		let json = serde_json::to_vec_pretty(self)
+			.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+		tokio::fs::write(archive.join(MANIFEST_FILENAME), json).await
+	}
+}
+
+/// Recursively copy `src` into `dst`, returning the number of files copied
+pub async fn copy_dir(src: &Path, dst: &Path) -> std::io::Result<u64> {
+	let mut copied = 0;
+	let mut pending = vec![(src.to_path_buf(), dst.to_path_buf())];
+
+	while let Some((from, to)) = pending.pop() {
+		tokio::fs::create_dir_all(&to).await?;
+		let mut entries = tokio::fs::read_dir(&from).await?;
+		while let Some(entry) = entries.next_entry().await? {
+			let target = to.join(entry.file_name());
+			let file_type = entry.file_type().await?;
+			if file_type.is_dir() {
+				pending.push((entry.path(), target));
+			} else if file_type.is_file() {
+				tokio::fs::copy(entry.path(), &target).await?;
+				copied += 1;
+			}
+		}
+	}
+
+	Ok(copied)
+}
@@ -2,4 +2,5 @@
 
 pub mod action;
 pub mod input;
+pub mod manifest;
 pub mod output;
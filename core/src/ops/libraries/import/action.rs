@@ -0,0 +1,411 @@
+//! Library import action handler
+
+use super::{
+	input::{LibraryImportConflict, LibraryImportInput},
+	output::LibraryImportOutput,
+	remap::remap_to_local_device,
+};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::{error::ActionError, CoreAction},
+		db::Database,
+	},
+	library::{config::LibraryConfig, Library, LibraryError, LibraryManager},
+	ops::libraries::export::manifest::{
+		copy_dir, LibraryExportManifest, CONFIG_FILENAME, DATABASE_FILENAME,
+		LIBRARY_EXPORT_FORMAT_VERSION, PREVIEWS_DIR, SIDECARS_DIR,
+	},
+};
+use chrono::Utc;
+use std::{
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LibraryImportAction {
+	input: LibraryImportInput,
+}
+
+impl LibraryImportAction {
+	pub fn new(input: LibraryImportInput) -> Self {
+		Self { input }
+	}
+}
+
+impl CoreAction for LibraryImportAction {
+	type Input = LibraryImportInput;
+	type Output = LibraryImportOutput;
+
+	fn from_input(input: LibraryImportInput) -> Result<Self, String> {
+		Ok(LibraryImportAction::new(input))
+	}
+
+	async fn execute(self, context: Arc<CoreContext>) -> Result<Self::Output, ActionError> {
+		let archive = &self.input.archive_path;
+		let manifest = load_manifest(archive).await?;
+		let library_manager = context.libraries().await;
+
+		// Resolve a library ID conflict before anything is written
+		let existing_open = library_manager.get_library(manifest.library_id).await;
+		let existing_on_disk = library_manager
+			.scan_for_libraries()
+			.await?
+			.into_iter()
+			.find(|discovered| discovered.config.id == manifest.library_id);
+		let conflict = existing_open.is_some() || existing_on_disk.is_some();
+
+		let library_id = match (conflict, self.input.on_conflict) {
+			(false, _) | (true, LibraryImportConflict::Replace) => manifest.library_id,
+			(true, LibraryImportConflict::NewId) => Uuid::new_v4(),
+			(true, LibraryImportConflict::Fail) => {
+				return Err(ActionError::Validation {
+					field: "on_conflict".to_string(),
+					message: format!("Library {} already exists", manifest.library_id),
+				});
+			}
+		};
+
+		let name = self
+			.input
+			.name
+			.clone()
+			.unwrap_or_else(|| manifest.library_name.clone());
+
+		// A replacement takes over the existing library's directory unless told otherwise
+		let replacing = conflict && library_id == manifest.library_id;
+		let existing_path = match (&existing_open, &existing_on_disk) {
+			(Some(library), _) => Some(library.path().to_path_buf()),
+			(None, Some(discovered)) => Some(discovered.path.clone()),
+			(None, None) => None,
+		};
+		let library_path = match (&existing_path, replacing, &self.input.path) {
+			(Some(path), true, None) => path.clone(),
+			_ => {
+				library_manager
+					.allocate_library_path(&name, self.input.path.clone())
+					.await?
+			}
+		};
+
+		// Stage under a name the library watcher ignores, so the library can't be opened
+		// before its database is migrated and remapped
+		let staging_path = library_path.with_extension("sdimport");
+		let staged = stage_library(
+			archive,
+			&staging_path,
+			&manifest,
+			library_id,
+			&name,
+			&context,
+		)
+		.await;
+		let stats = match staged {
+			Ok(stats) => stats,
+			Err(e) => {
+				let _ = tokio::fs::remove_dir_all(&staging_path).await;
+				return Err(e);
+			}
+		};
+
+		// Move the existing library aside rather than deleting it, so it can be put back
+		// if its replacement fails to open
+		let set_aside = match (replacing, existing_path) {
+			(true, Some(path)) => {
+				let set_aside = SetAsideLibrary::new(
+					&library_manager,
+					&context,
+					manifest.library_id,
+					path,
+					existing_open.is_some(),
+				)
+				.await;
+				match set_aside {
+					Ok(set_aside) => Some(set_aside),
+					Err(e) => {
+						let _ = tokio::fs::remove_dir_all(&staging_path).await;
+						return Err(e);
+					}
+				}
+			}
+			_ => None,
+		};
+
+		let library =
+			match install_library(&library_manager, &staging_path, &library_path, &context).await {
+				Ok(library) => library,
+				Err(e) => {
+					let _ = tokio::fs::remove_dir_all(&staging_path).await;
+					if let Some(set_aside) = set_aside {
+						set_aside.restore(&library_manager, &context).await;
+					}
+					return Err(e);
+				}
+			};
+
+		let replaced_existing = set_aside.is_some();
+		if let Some(set_aside) = set_aside {
+			set_aside.discard().await;
+			info!("Replaced library {} with imported copy", library_id);
+		}
+
+		if let Some(sidecar_manager) = context.get_sidecar_manager().await {
+			if let Err(e) = sidecar_manager.init_library(&library).await {
+				warn!(
+					"Failed to initialize sidecar manager for library {}: {}",
+					library.id(),
+					e
+				);
+			}
+		}
+
+		info!(
+			"Imported library {} from {:?} ({} locations remapped)",
+			library.id(),
+			archive,
+			stats.locations
+		);
+
+		Ok(LibraryImportOutput {
+			library_id: library.id(),
+			source_library_id: manifest.library_id,
+			name: library.name().await,
+			path: library.path().to_path_buf(),
+			source_schema_version: manifest.schema_version,
+			replaced_existing,
+			remapped_locations: stats.locations,
+			remapped_volumes: stats.volumes,
+			unmatched_volumes: stats.unmatched_volumes,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"library.import"
+	}
+
+	async fn validate(
+		&self,
+		_context: Arc<CoreContext>,
+	) -> Result<crate::infra::action::ValidationResult, ActionError> {
+		if let Some(name) = &self.input.name {
+			if name.trim().is_empty() {
+				return Err(ActionError::Validation {
+					field: "name".to_string(),
+					message: "Library name cannot be empty".to_string(),
+				});
+			}
+		}
+
+		load_manifest(&self.input.archive_path).await?;
+
+		Ok(crate::infra::action::ValidationResult::Success { metadata: None })
+	}
+}
+
+async fn load_manifest(archive: &Path) -> Result<LibraryExportManifest, ActionError> {
+	let manifest =
+		LibraryExportManifest::load(archive)
+			.await
+			.map_err(|e| ActionError::Validation {
+				field: "archive_path".to_string(),
+				message: format!("Not a library export: {}", e),
+			})?;
+
+	if manifest.format_version > LIBRARY_EXPORT_FORMAT_VERSION {
+		return Err(ActionError::Validation {
+			field: "archive_path".to_string(),
+			message: format!(
+				"Export format {} is newer than this version of Spacedrive supports",
+				manifest.format_version
+			),
+		});
+	}
+
+	if !archive.join(DATABASE_FILENAME).is_file() {
+		return Err(ActionError::Validation {
+			field: "archive_path".to_string(),
+			message: "Export has no database snapshot".to_string(),
+		});
+	}
+
+	Ok(manifest)
+}
+
+/// An existing library moved out of the way while its replacement is installed
+struct SetAsideLibrary {
+	id: Uuid,
+	original: PathBuf,
+	aside: PathBuf,
+	was_open: bool,
+}
+
+impl SetAsideLibrary {
+	/// Close the library if it is open and rename its directory to a name the library
+	/// watcher ignores
+	async fn new(
+		library_manager: &LibraryManager,
+		context: &Arc<CoreContext>,
+		id: Uuid,
+		original: PathBuf,
+		was_open: bool,
+	) -> Result<Self, ActionError> {
+		if was_open {
+			library_manager.close_library(id).await?;
+		}
+
+		let aside = original.with_extension("sdreplaced");
+		if let Err(e) = tokio::fs::rename(&original, &aside).await {
+			if was_open {
+				// Nothing has changed on disk, so the library can simply be opened again
+				let _ = library_manager
+					.open_library(&original, context.clone())
+					.await;
+			}
+			return Err(ActionError::io_error(original.display().to_string(), e));
+		}
+
+		Ok(Self {
+			id,
+			original,
+			aside,
+			was_open,
+		})
+	}
+
+	/// Put the library back where it was, reopening it if it had been open
+	async fn restore(self, library_manager: &LibraryManager, context: &Arc<CoreContext>) {
+		if let Err(e) = tokio::fs::rename(&self.aside, &self.original).await {
+			error!(
+				"Failed to restore library {} from {:?}: {}",
+				self.id, self.aside, e
+			);
+			return;
+		}
+
+		if self.was_open {
+			match library_manager
+				.open_library(&self.original, context.clone())
+				.await
+			{
+				Ok(_) | Err(LibraryError::AlreadyOpen(_)) => {}
+				Err(e) => error!("Failed to reopen restored library {}: {}", self.id, e),
+			}
+		}
+		info!("Restored library {} after a failed import", self.id);
+	}
+
+	/// Delete the replaced library for good
+	async fn discard(self) {
+		if let Err(e) = tokio::fs::remove_dir_all(&self.aside).await {
+			warn!(
+				"Failed to remove replaced library at {:?}: {}",
+				self.aside, e
+			);
+		}
+	}
+}
+
+/// Move a staged library into place and open it
+async fn install_library(
+	library_manager: &LibraryManager,
+	staging_path: &Path,
+	library_path: &Path,
+	context: &Arc<CoreContext>,
+) -> Result<Arc<Library>, ActionError> {
+	tokio::fs::rename(staging_path, library_path)
+		.await
+		.map_err(|e| ActionError::io_error(library_path.display().to_string(), e))?;
+
+	// The library watcher may have opened it first
+	match library_manager
+		.open_library(library_path, context.clone())
+		.await
+	{
+		Ok(library) => Ok(library),
+		Err(LibraryError::AlreadyOpen(id)) => library_manager
+			.get_library(id)
+			.await
+			.ok_or_else(|| ActionError::Internal("Imported library was closed".to_string())),
+		Err(e) => {
+			// Move it back out of the way so the caller can clean it up
+			let _ = tokio::fs::rename(library_path, staging_path).await;
+			Err(e.into())
+		}
+	}
+}
+
+/// Copy the export into `staging_path` and bring it up to date for this device
+async fn stage_library(
+	archive: &Path,
+	staging_path: &Path,
+	manifest: &LibraryExportManifest,
+	library_id: Uuid,
+	name: &str,
+	context: &CoreContext,
+) -> Result<super::remap::RemapStats, ActionError> {
+	tokio::fs::create_dir_all(staging_path)
+		.await
+		.map_err(|e| ActionError::io_error(staging_path.display().to_string(), e))?;
+	tokio::fs::create_dir_all(staging_path.join("exports")).await?;
+
+	let mut config = LibraryConfig::load(&archive.join(CONFIG_FILENAME)).await?;
+	config.id = library_id;
+	config.name = name.to_string();
+	config.updated_at = Utc::now();
+	let config_json = serde_json::to_string_pretty(&config)?;
+	tokio::fs::write(staging_path.join(CONFIG_FILENAME), config_json).await?;
+
+	for dir in [SIDECARS_DIR, PREVIEWS_DIR] {
+		let src = archive.join(dir);
+		if src.is_dir() {
+			copy_dir(&src, &staging_path.join(dir)).await?;
+		} else {
+			tokio::fs::create_dir_all(staging_path.join(dir)).await?;
+		}
+	}
+
+	let db_path = staging_path.join(DATABASE_FILENAME);
+	tokio::fs::copy(archive.join(DATABASE_FILENAME), &db_path).await?;
+
+	let db = Database::open(&db_path).await?;
+	let unknown = db.unknown_migrations().await?;
+	if !unknown.is_empty() {
+		let _ = db.close().await;
+		return Err(ActionError::Validation {
+			field: "archive_path".to_string(),
+			message: format!(
+				"Export was created by a newer version of Spacedrive (schema {})",
+				unknown.join(", ")
+			),
+		});
+	}
+	db.migrate().await?;
+
+	let local_device = context
+		.device_manager
+		.device_id()
+		.map_err(ActionError::device_manager_error)?;
+	let local_slug = context
+		.device_manager
+		.slug_for_library(library_id)
+		.map_err(ActionError::device_manager_error)?;
+	let local_volumes = context.volume_manager.get_all_volumes().await;
+
+	let stats = remap_to_local_device(
+		db.conn(),
+		manifest.device_id,
+		local_device,
+		&local_slug,
+		&local_volumes,
+		manifest.includes_sidecars,
+	)
+	.await;
+	db.close().await?;
+
+	Ok(stats?)
+}
+
+crate::register_core_action!(LibraryImportAction, "libraries.import");
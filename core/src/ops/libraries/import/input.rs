@@ -0,0 +1,37 @@
+//! Input types for library import operations
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+
+/// Input for importing a library from an export produced by `libraries.export`
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct LibraryImportInput {
+	/// Export directory to import
+	pub archive_path: PathBuf,
+
+	/// Optional new name for the imported library (overrides the exported name)
+	pub name: Option<String>,
+
+	/// Optional directory to place the library in (defaults to the libraries directory)
+	pub path: Option<PathBuf>,
+
+	/// What to do when a library with the exported library's ID already exists
+	#[serde(default)]
+	pub on_conflict: LibraryImportConflict,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum LibraryImportConflict {
+	/// Refuse to import
+	#[default]
+	Fail,
+	/// Import as a separate library with a new ID
+	NewId,
+	/// Delete the existing library, including its data, and import in its place
+	///
+	/// The existing library is kept aside until the import has opened, and put back if
+	/// it fails.
+	Replace,
+}
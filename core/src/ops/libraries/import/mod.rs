@@ -0,0 +1,10 @@
+//! Library import operation
+
+pub mod action;
+pub mod input;
+pub mod output;
+mod remap;
+
+pub use action::LibraryImportAction;
+pub use input::{LibraryImportConflict, LibraryImportInput};
+pub use output::LibraryImportOutput;
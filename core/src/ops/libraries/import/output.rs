@@ -0,0 +1,45 @@
+//! Library import operation output
+
+use crate::infra::action::output::ActionOutputTrait;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct LibraryImportOutput {
+	pub library_id: Uuid,
+	/// ID of the exported library, which differs from `library_id` when imported with a new ID
+	pub source_library_id: Uuid,
+	pub name: String,
+	pub path: PathBuf,
+	/// Schema version of the exported database before it was migrated
+	pub source_schema_version: Option<String>,
+	/// Whether an existing library was replaced
+	pub replaced_existing: bool,
+	/// Locations of the exporting device now owned by this device
+	pub remapped_locations: u64,
+	/// Volumes of the exporting device matched to volumes mounted on this device
+	pub remapped_volumes: u64,
+	/// Volumes of the exporting device with no local match, left offline
+	pub unmatched_volumes: u64,
+}
+
+impl ActionOutputTrait for LibraryImportOutput {
+	fn to_json(&self) -> serde_json::Value {
+		serde_json::to_value(self).unwrap_or(serde_json::Value::Null)
+	}
+
+	fn display_message(&self) -> String {
+		format!(
+			"Imported library '{}' with ID {} at {}",
+			self.name,
+			self.library_id,
+			self.path.display()
+		)
+	}
+
+	fn output_type(&self) -> &'static str {
+		"library.import.completed"
+	}
+}
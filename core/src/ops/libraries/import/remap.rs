@@ -0,0 +1,177 @@
+//! Hand an imported library's device-bound records over to the importing device
+
+use crate::{domain::volume::Volume, infra::db::entities};
+use sea_orm::{
+	sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr,
+	EntityTrait, QueryFilter, Set, TransactionTrait,
+};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Debug, Default)]
+pub struct RemapStats {
+	pub locations: u64,
+	pub volumes: u64,
+	pub unmatched_volumes: u64,
+}
+
+/// Reassign everything the exporting device owned to the local device
+///
+/// The exporting device's record becomes the local device's record unless the local device
+/// is already known to the library, in which case only its locations move over. Volumes
+/// are matched to locally mounted volumes by mount point; volumes without a match stay
+/// offline and their locations' volume is cleared, to be resolved again when they show up.
+pub async fn remap_to_local_device(
+	db: &DatabaseConnection,
+	exporting_device: Uuid,
+	local_device: Uuid,
+	local_slug: &str,
+	local_volumes: &[Volume],
+	includes_sidecars: bool,
+) -> Result<RemapStats, DbErr> {
+	let mut stats = RemapStats::default();
+	if exporting_device == local_device {
+		return Ok(stats);
+	}
+
+	let txn = db.begin().await?;
+
+	let Some(exported) = entities::device::Entity::find()
+		.filter(entities::device::Column::Uuid.eq(exporting_device))
+		.one(&txn)
+		.await?
+	else {
+		return Ok(stats);
+	};
+	let existing_local = entities::device::Entity::find()
+		.filter(entities::device::Column::Uuid.eq(local_device))
+		.one(&txn)
+		.await?;
+
+	// Volumes reference devices by UUID, so they move before the device record changes
+	let volumes = entities::volume::Entity::find()
+		.filter(entities::volume::Column::DeviceId.eq(exporting_device))
+		.all(&txn)
+		.await?;
+	for volume in volumes {
+		let local_match = local_volumes.iter().find(|local| {
+			volume.mount_point.as_deref() == Some(&*local.mount_point.to_string_lossy())
+		});
+		let uuid_taken = match local_match {
+			Some(local) => entities::volume::Entity::find()
+				.filter(entities::volume::Column::Uuid.eq(local.id))
+				.one(&txn)
+				.await?
+				.is_some(),
+			None => false,
+		};
+
+		let volume_id = volume.id;
+		let mut active: entities::volume::ActiveModel = volume.into();
+		active.device_id = Set(local_device);
+		match local_match.filter(|_| !uuid_taken) {
+			Some(local) => {
+				active.uuid = Set(local.id);
+				active.fingerprint = Set(local.fingerprint.0.clone());
+				active.is_online = Set(true);
+				stats.volumes += 1;
+			}
+			None => {
+				active.is_online = Set(false);
+				stats.unmatched_volumes += 1;
+
+				entities::location::Entity::update_many()
+					.col_expr(
+						entities::location::Column::VolumeId,
+						Expr::value(Option::<i32>::None),
+					)
+					.filter(entities::location::Column::VolumeId.eq(volume_id))
+					.exec(&txn)
+					.await?;
+			}
+		}
+		active.update(&txn).await?;
+	}
+
+	match existing_local {
+		None => {
+			// Keep the exported slug if the local one is already taken by another device
+			let slug_taken = entities::device::Entity::find()
+				.filter(entities::device::Column::Slug.eq(local_slug))
+				.one(&txn)
+				.await?
+				.is_some();
+
+			let exported_id = exported.id;
+			let mut active: entities::device::ActiveModel = exported.into();
+			active.uuid = Set(local_device);
+			if !slug_taken {
+				active.slug = Set(local_slug.to_string());
+			}
+			active.update(&txn).await?;
+
+			stats.locations = entities::location::Entity::find()
+				.filter(entities::location::Column::DeviceId.eq(exported_id))
+				.all(&txn)
+				.await?
+				.len() as u64;
+		}
+		Some(local) => {
+			stats.locations = entities::location::Entity::update_many()
+				.col_expr(entities::location::Column::DeviceId, Expr::value(local.id))
+				.filter(entities::location::Column::DeviceId.eq(exported.id))
+				.exec(&txn)
+				.await?
+				.rows_affected;
+		}
+	}
+
+	if includes_sidecars {
+		remap_sidecar_availability(&txn, exporting_device, local_device).await?;
+	}
+
+	txn.commit().await?;
+	Ok(stats)
+}
+
+/// Record the exporting device's sidecars as held by the local device
+///
+/// Where the local device already has a row for the same sidecar, the exported row is
+/// dropped rather than colliding with it.
+async fn remap_sidecar_availability(
+	txn: &DatabaseTransaction,
+	exporting_device: Uuid,
+	local_device: Uuid,
+) -> Result<(), DbErr> {
+	use entities::sidecar_availability::{Column, Entity};
+
+	let held: HashSet<(Uuid, String, String)> = Entity::find()
+		.filter(Column::DeviceUuid.eq(local_device))
+		.all(txn)
+		.await?
+		.into_iter()
+		.map(|row| (row.content_uuid, row.kind, row.variant))
+		.collect();
+
+	let duplicates: Vec<i32> = Entity::find()
+		.filter(Column::DeviceUuid.eq(exporting_device))
+		.all(txn)
+		.await?
+		.into_iter()
+		.filter(|row| held.contains(&(row.content_uuid, row.kind.clone(), row.variant.clone())))
+		.map(|row| row.id)
+		.collect();
+	for chunk in duplicates.chunks(900) {
+		Entity::delete_many()
+			.filter(Column::Id.is_in(chunk.to_vec()))
+			.exec(txn)
+			.await?;
+	}
+
+	Entity::update_many()
+		.col_expr(Column::DeviceUuid, Expr::value(local_device))
+		.filter(Column::DeviceUuid.eq(exporting_device))
+		.exec(txn)
+		.await?;
+	Ok(())
+}
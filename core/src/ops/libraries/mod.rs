@@ -3,6 +3,7 @@
 pub mod create;
 pub mod delete;
 pub mod export;
+pub mod import;
 pub mod info;
 pub mod list;
 pub mod open;
@@ -11,6 +12,7 @@ pub mod rename;
 pub use create::*;
 pub use delete::*;
 pub use export::*;
+pub use import::*;
 pub use info::*;
 pub use list::*;
 pub use open::*;
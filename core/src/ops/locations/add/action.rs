@@ -186,6 +186,12 @@ impl LibraryAction for LocationAddAction {
 					message: "Sidecar paths cannot be used as locations".to_string(),
 				});
 			}
+			SdPath::Archive { .. } => {
+				return Err(ActionError::Validation {
+					field: "path".to_string(),
+					message: "Paths inside archives cannot be used as locations".to_string(),
+				});
+			}
 		}
 
 		// Check for duplicate locations
@@ -27,7 +27,9 @@ pub enum JobType {
 	Thumbnail,
 	Thumbstrip,
 	Ocr,
+	TextExtraction,
 	SpeechToText,
+	Embedding,
 	ObjectDetection,
 }
 
@@ -37,7 +39,9 @@ impl std::fmt::Display for JobType {
 			JobType::Thumbnail => write!(f, "thumbnail"),
 			JobType::Thumbstrip => write!(f, "thumbstrip"),
 			JobType::Ocr => write!(f, "ocr"),
+			JobType::TextExtraction => write!(f, "text_extraction"),
 			JobType::SpeechToText => write!(f, "speech_to_text"),
+			JobType::Embedding => write!(f, "embedding"),
 			JobType::ObjectDetection => write!(f, "object_detection"),
 		}
 	}
@@ -168,6 +172,24 @@ impl LibraryAction for LocationTriggerJobAction {
 				})?
 			}
 
+			JobType::TextExtraction => {
+				if !job_policies.text_extraction.enabled && !self.input.force {
+					return Err(ActionError::Validation {
+						field: "job_type".to_string(),
+						message: "Text extraction is disabled for this location. Use force=true to override.".to_string(),
+					});
+				}
+
+				let config = job_policies
+					.text_extraction
+					.to_job_config(Some(self.input.location_id));
+				let job = crate::ops::media::text_extraction::TextExtractionJob::new(config);
+
+				library.jobs().dispatch(job).await.map_err(|e| {
+					ActionError::Internal(format!("Failed to dispatch text extraction job: {}", e))
+				})?
+			}
+
 			#[cfg(feature = "speech-to-text")]
 			JobType::SpeechToText => {
 				if !job_policies.speech_to_text.enabled && !self.input.force {
@@ -187,6 +209,25 @@ impl LibraryAction for LocationTriggerJobAction {
 				})?
 			}
 
+			#[cfg(feature = "embeddings")]
+			JobType::Embedding => {
+				if !job_policies.embedding.enabled && !self.input.force {
+					return Err(ActionError::Validation {
+						field: "job_type".to_string(),
+						message: "Embedding is disabled for this location. Use force=true to override.".to_string(),
+					});
+				}
+
+				let config = job_policies
+					.embedding
+					.to_job_config(Some(self.input.location_id));
+				let job = crate::ops::media::embedding::EmbeddingJob::new(config);
+
+				library.jobs().dispatch(job).await.map_err(|e| {
+					ActionError::Internal(format!("Failed to dispatch embedding job: {}", e))
+				})?
+			}
+
 			#[cfg(not(feature = "ffmpeg"))]
 			JobType::Thumbnail | JobType::Thumbstrip => {
 				return Err(ActionError::Validation {
@@ -206,6 +247,15 @@ impl LibraryAction for LocationTriggerJobAction {
 				});
 			}
 
+			#[cfg(not(feature = "embeddings"))]
+			JobType::Embedding => {
+				return Err(ActionError::Validation {
+					field: "job_type".to_string(),
+					message: "Embedding requires ONNX Runtime support which is not enabled"
+						.to_string(),
+				});
+			}
+
 			JobType::ObjectDetection => {
 				return Err(ActionError::Validation {
 					field: "job_type".to_string(),
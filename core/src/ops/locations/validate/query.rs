@@ -54,6 +54,11 @@ impl LibraryQuery for ValidateLocationPathQuery {
 					"Content and Sidecar paths cannot be validated as locations".to_string(),
 				))
 			}
+			SdPath::Archive { .. } => {
+				return Err(QueryError::Internal(
+					"Paths inside archives cannot be validated as locations".to_string(),
+				))
+			}
 		};
 
 		// Calculate path depth from root
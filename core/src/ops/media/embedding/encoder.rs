@@ -0,0 +1,158 @@
+//! Sentence embeddings from an ONNX text model on the CPU
+
+use super::index::normalized;
+use crate::ops::models::{EmbeddingModel, EmbeddingModelManager};
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use ort::{
+	session::{builder::GraphOptimizationLevel, Session},
+	value::Tensor,
+};
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+};
+use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+use tracing::info;
+
+/// Threads one inference may use, leaving the rest of the CPU to other jobs
+const INTRA_OP_THREADS: usize = 2;
+
+/// Encoders loaded in this process, keyed by model directory
+static ENCODERS: Lazy<Mutex<HashMap<PathBuf, Arc<Mutex<TextEncoder>>>>> =
+	Lazy::new(Default::default);
+
+pub struct TextEncoder {
+	session: Session,
+	tokenizer: Tokenizer,
+	dimensions: usize,
+}
+
+impl TextEncoder {
+	/// Load a downloaded model from the data directory
+	pub fn load(model: EmbeddingModel, data_dir: &Path) -> Result<Self> {
+		let manager = EmbeddingModelManager::new(data_dir);
+
+		let mut tokenizer = Tokenizer::from_file(manager.get_tokenizer_path(&model))
+			.map_err(|e| anyhow!("Failed to load tokenizer: {}", e))?;
+		tokenizer
+			.with_truncation(Some(TruncationParams {
+				max_length: model.max_tokens(),
+				..Default::default()
+			}))
+			.map_err(|e| anyhow!("Invalid truncation settings: {}", e))?;
+		tokenizer.with_padding(Some(PaddingParams::default()));
+
+		let session = Session::builder()?
+			.with_optimization_level(GraphOptimizationLevel::Level3)?
+			.with_intra_threads(INTRA_OP_THREADS)?
+			.commit_from_file(manager.get_model_path(&model))
+			.context("Failed to load embedding model")?;
+
+		info!("Loaded embedding model {}", model.display_name());
+
+		Ok(Self {
+			session,
+			tokenizer,
+			dimensions: model.dimensions(),
+		})
+	}
+
+	/// Load a model once per process, shared by the embedding job and searches
+	///
+	/// Loading is blocking; call from `spawn_blocking`.
+	pub fn shared(model: EmbeddingModel, data_dir: &Path) -> Result<Arc<Mutex<Self>>> {
+		let key = EmbeddingModelManager::new(data_dir).get_model_dir(&model);
+		let mut encoders = ENCODERS.lock().unwrap_or_else(|e| e.into_inner());
+
+		if let Some(encoder) = encoders.get(&key) {
+			return Ok(encoder.clone());
+		}
+
+		let encoder = Arc::new(Mutex::new(Self::load(model, data_dir)?));
+		encoders.insert(key, encoder.clone());
+		Ok(encoder)
+	}
+
+	pub fn dimensions(&self) -> usize {
+		self.dimensions
+	}
+
+	/// Embed a batch of texts into unit vectors
+	///
+	/// Blocking and CPU bound; call from `spawn_blocking`.
+	pub fn embed(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+		if texts.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let encodings = self
+			.tokenizer
+			.encode_batch(texts.to_vec(), true)
+			.map_err(|e| anyhow!("Tokenization failed: {}", e))?;
+
+		// Padding makes every encoding as long as the longest one
+		let batch_size = encodings.len();
+		let seq_len = encodings[0].len();
+
+		let mut input_ids = Vec::with_capacity(batch_size * seq_len);
+		let mut attention_mask = Vec::with_capacity(batch_size * seq_len);
+		let mut token_type_ids = Vec::with_capacity(batch_size * seq_len);
+		for encoding in &encodings {
+			input_ids.extend(encoding.get_ids().iter().map(|&id| id as i64));
+			attention_mask.extend(encoding.get_attention_mask().iter().map(|&m| m as i64));
+			token_type_ids.extend(encoding.get_type_ids().iter().map(|&t| t as i64));
+		}
+
+		let shape = [batch_size, seq_len];
+		let outputs = self.session.run(ort::inputs![
+			"input_ids" => Tensor::from_array((shape, input_ids))?,
+			"attention_mask" => Tensor::from_array((shape, attention_mask.clone()))?,
+			"token_type_ids" => Tensor::from_array((shape, token_type_ids))?,
+		])?;
+
+		// [batch, seq_len, hidden]
+		let (output_shape, hidden_states) =
+			outputs["last_hidden_state"].try_extract_tensor::<f32>()?;
+		let hidden_size = output_shape[2] as usize;
+		if hidden_size != self.dimensions {
+			return Err(anyhow!(
+				"Model produced {} dimensions, expected {}",
+				hidden_size,
+				self.dimensions
+			));
+		}
+
+		Ok((0..batch_size)
+			.map(|i| {
+				let offset = i * seq_len;
+				mean_pool(
+					&hidden_states[offset * hidden_size..(offset + seq_len) * hidden_size],
+					&attention_mask[offset..offset + seq_len],
+					hidden_size,
+				)
+			})
+			.collect())
+	}
+}
+
+/// Average the token states the attention mask covers, then normalize
+fn mean_pool(states: &[f32], mask: &[i64], hidden_size: usize) -> Vec<f32> {
+	let mut pooled = vec![0.0; hidden_size];
+	let mut tokens = 0.0;
+
+	for (token, &included) in states.chunks_exact(hidden_size).zip(mask) {
+		if included == 0 {
+			continue;
+		}
+		pooled.iter_mut().zip(token).for_each(|(sum, x)| *sum += x);
+		tokens += 1.0;
+	}
+
+	if tokens > 0.0 {
+		pooled.iter_mut().for_each(|x| *x /= tokens);
+	}
+
+	normalized(pooled)
+}
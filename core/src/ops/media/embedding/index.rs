@@ -0,0 +1,596 @@
+//! Persistent approximate nearest neighbour index for embeddings
+//!
+//! A Hierarchical Navigable Small World graph over unit vectors, stored as one
+//! MessagePack file per library. Each content identity owns one vector per text
+//! chunk; searches return the best chunk per content identity.
+
+use serde::{Deserialize, Serialize};
+use std::{
+	cmp::Ordering,
+	collections::{BinaryHeap, HashMap, HashSet},
+	path::{Path, PathBuf},
+};
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+/// Neighbours kept per node on upper layers
+const MAX_NEIGHBOURS: usize = 16;
+
+/// Neighbours kept per node on the bottom layer, which every node is part of
+const MAX_NEIGHBOURS_BOTTOM: usize = 2 * MAX_NEIGHBOURS;
+
+/// Candidate list size while linking a new node (higher = better graph, slower inserts)
+const EF_CONSTRUCTION: usize = 100;
+
+/// Minimum candidate list size while searching
+const EF_SEARCH: usize = 64;
+
+/// Rebuild the graph once this share of nodes has been removed
+const MAX_DELETED_RATIO: f32 = 0.3;
+
+#[derive(Error, Debug)]
+pub enum VectorIndexError {
+	#[error("IO error: {0}")]
+	Io(#[from] std::io::Error),
+
+	#[error("Serialization error: {0}")]
+	Serialization(#[from] rmp_serde::encode::Error),
+
+	#[error("Deserialization error: {0}")]
+	Deserialization(#[from] rmp_serde::decode::Error),
+
+	#[error("Expected {expected} dimensions, got {actual}")]
+	Dimensions { expected: usize, actual: usize },
+}
+
+pub type Result<T> = std::result::Result<T, VectorIndexError>;
+
+/// A content identity close to the query vector
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorHit {
+	pub content_id: i32,
+	/// Content hash the vectors were computed from
+	pub content_hash: String,
+	/// Cosine similarity of the best matching chunk
+	pub similarity: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Node {
+	content_id: i32,
+	vector: Vec<f32>,
+	/// Neighbour node ids, one list per layer the node is part of
+	layers: Vec<Vec<u32>>,
+	deleted: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexedContent {
+	content_hash: String,
+	nodes: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VectorIndex {
+	model_id: String,
+	dimensions: usize,
+	nodes: Vec<Node>,
+	entry_point: Option<u32>,
+	contents: HashMap<i32, IndexedContent>,
+	deleted_nodes: usize,
+	#[serde(skip)]
+	path: PathBuf,
+	#[serde(skip)]
+	dirty: bool,
+}
+
+impl VectorIndex {
+	/// Create an empty index that is saved to `path`
+	pub fn new(path: impl Into<PathBuf>, model_id: &str, dimensions: usize) -> Self {
+		Self {
+			model_id: model_id.to_string(),
+			dimensions,
+			nodes: Vec::new(),
+			entry_point: None,
+			contents: HashMap::new(),
+			deleted_nodes: 0,
+			path: path.into(),
+			dirty: false,
+		}
+	}
+
+	/// Load the index at `path`, starting empty if it doesn't exist or was built by another model
+	pub async fn open(path: &Path, model_id: &str, dimensions: usize) -> Result<Self> {
+		if !tokio::fs::try_exists(path).await? {
+			debug!("No vector index at {}, starting empty", path.display());
+			return Ok(Self::new(path, model_id, dimensions));
+		}
+
+		let bytes = tokio::fs::read(path).await?;
+		let mut index: Self = match rmp_serde::from_slice(&bytes) {
+			Ok(index) => index,
+			Err(e) => {
+				warn!(
+					"Vector index at {} is unreadable, rebuilding: {}",
+					path.display(),
+					e
+				);
+				return Ok(Self::new(path, model_id, dimensions));
+			}
+		};
+
+		if index.model_id != model_id || index.dimensions != dimensions {
+			info!(
+				"Vector index was built with {} ({} dimensions), rebuilding for {}",
+				index.model_id, index.dimensions, model_id
+			);
+			return Ok(Self::new(path, model_id, dimensions));
+		}
+
+		index.path = path.to_path_buf();
+		Ok(index)
+	}
+
+	/// Write the index to disk if it changed since it was loaded or last saved
+	pub async fn save(&mut self) -> Result<()> {
+		if !self.dirty {
+			return Ok(());
+		}
+
+		if let Some(parent) = self.path.parent() {
+			tokio::fs::create_dir_all(parent).await?;
+		}
+
+		// Write then rename so a crash never leaves a truncated index behind
+		let bytes = rmp_serde::to_vec(self)?;
+		let temp_path = self.path.with_extension("tmp");
+		tokio::fs::write(&temp_path, bytes).await?;
+		tokio::fs::rename(&temp_path, &self.path).await?;
+
+		self.dirty = false;
+		Ok(())
+	}
+
+	pub fn model_id(&self) -> &str {
+		&self.model_id
+	}
+
+	pub fn dimensions(&self) -> usize {
+		self.dimensions
+	}
+
+	/// Number of indexed content identities
+	pub fn len(&self) -> usize {
+		self.contents.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.contents.is_empty()
+	}
+
+	/// Whether a content identity is indexed at this hash
+	pub fn is_current(&self, content_id: i32, content_hash: &str) -> bool {
+		self.contents
+			.get(&content_id)
+			.is_some_and(|content| content.content_hash == content_hash)
+	}
+
+	/// Indexed content identities with the hash their vectors were computed from
+	pub fn indexed(&self) -> impl Iterator<Item = (i32, &str)> {
+		self.contents
+			.iter()
+			.map(|(id, content)| (*id, content.content_hash.as_str()))
+	}
+
+	/// Replace the vectors of a content identity
+	pub fn upsert(
+		&mut self,
+		content_id: i32,
+		content_hash: &str,
+		vectors: Vec<Vec<f32>>,
+	) -> Result<()> {
+		if let Some(vector) = vectors.iter().find(|v| v.len() != self.dimensions) {
+			return Err(VectorIndexError::Dimensions {
+				expected: self.dimensions,
+				actual: vector.len(),
+			});
+		}
+
+		self.remove(content_id);
+
+		let nodes = vectors
+			.into_iter()
+			.map(|vector| self.insert_node(content_id, vector))
+			.collect();
+		self.contents.insert(
+			content_id,
+			IndexedContent {
+				content_hash: content_hash.to_string(),
+				nodes,
+			},
+		);
+		self.dirty = true;
+
+		Ok(())
+	}
+
+	/// Remove the vectors of a content identity, returning whether it was indexed
+	pub fn remove(&mut self, content_id: i32) -> bool {
+		let Some(content) = self.contents.remove(&content_id) else {
+			return false;
+		};
+
+		// Removed nodes stay in the graph as waypoints until the next rebuild
+		for node in content.nodes {
+			self.nodes[node as usize].deleted = true;
+			self.deleted_nodes += 1;
+		}
+		self.dirty = true;
+
+		if self.deleted_nodes as f32 > self.nodes.len() as f32 * MAX_DELETED_RATIO {
+			self.rebuild();
+		}
+
+		true
+	}
+
+	/// Find the `limit` content identities closest to `query`
+	pub fn search(&self, query: &[f32], limit: usize) -> Vec<VectorHit> {
+		let Some(entry_point) = self.entry_point else {
+			return Vec::new();
+		};
+		if query.len() != self.dimensions || limit == 0 {
+			return Vec::new();
+		}
+
+		let query = normalized(query.to_vec());
+		let top_layer = self.nodes[entry_point as usize].layers.len() - 1;
+
+		let mut nearest = vec![entry_point];
+		for layer in (1..=top_layer).rev() {
+			nearest = self.search_layer(&query, &nearest, 1, layer);
+		}
+
+		// Content identities have several chunks, so look further than `limit` nodes
+		let ef = EF_SEARCH.max(limit * 4);
+		let candidates = self.search_layer(&query, &nearest, ef, 0);
+
+		let mut seen = HashSet::new();
+		let mut hits = Vec::with_capacity(limit);
+		for node_id in candidates {
+			let node = &self.nodes[node_id as usize];
+			if node.deleted || !seen.insert(node.content_id) {
+				continue;
+			}
+			let Some(content) = self.contents.get(&node.content_id) else {
+				continue;
+			};
+
+			hits.push(VectorHit {
+				content_id: node.content_id,
+				content_hash: content.content_hash.clone(),
+				similarity: dot(&query, &node.vector),
+			});
+			if hits.len() == limit {
+				break;
+			}
+		}
+
+		hits
+	}
+
+	fn insert_node(&mut self, content_id: i32, vector: Vec<f32>) -> u32 {
+		let id = self.nodes.len() as u32;
+		let vector = normalized(vector);
+		let level = random_level(id);
+
+		self.nodes.push(Node {
+			content_id,
+			vector,
+			layers: vec![Vec::new(); level + 1],
+			deleted: false,
+		});
+
+		let Some(entry_point) = self.entry_point else {
+			self.entry_point = Some(id);
+			return id;
+		};
+
+		let query = self.nodes[id as usize].vector.clone();
+		let top_layer = self.nodes[entry_point as usize].layers.len() - 1;
+
+		// Greedily descend to the layer the new node starts on
+		let mut nearest = vec![entry_point];
+		for layer in (level + 1..=top_layer).rev() {
+			nearest = self.search_layer(&query, &nearest, 1, layer);
+		}
+
+		for layer in (0..=level.min(top_layer)).rev() {
+			let candidates = self.search_layer(&query, &nearest, EF_CONSTRUCTION, layer);
+			let max_neighbours = max_neighbours(layer);
+
+			let neighbours: Vec<u32> = candidates.iter().copied().take(max_neighbours).collect();
+			for &neighbour in &neighbours {
+				self.link(neighbour, id, layer);
+			}
+			self.nodes[id as usize].layers[layer] = neighbours;
+
+			nearest = candidates;
+		}
+
+		if level > top_layer {
+			self.entry_point = Some(id);
+		}
+
+		id
+	}
+
+	/// Add an edge from `from` to `to`, keeping only the closest neighbours
+	fn link(&mut self, from: u32, to: u32, layer: usize) {
+		let max_neighbours = max_neighbours(layer);
+		let from_vector = &self.nodes[from as usize].vector;
+
+		let mut neighbours = self.nodes[from as usize].layers[layer].clone();
+		neighbours.push(to);
+		if neighbours.len() > max_neighbours {
+			neighbours.sort_by(|a, b| {
+				let da = distance(from_vector, &self.nodes[*a as usize].vector);
+				let db = distance(from_vector, &self.nodes[*b as usize].vector);
+				da.total_cmp(&db)
+			});
+			neighbours.truncate(max_neighbours);
+		}
+
+		self.nodes[from as usize].layers[layer] = neighbours;
+	}
+
+	/// Best-first search of one layer, returning up to `ef` node ids closest first
+	fn search_layer(
+		&self,
+		query: &[f32],
+		entry_points: &[u32],
+		ef: usize,
+		layer: usize,
+	) -> Vec<u32> {
+		let mut visited: HashSet<u32> = entry_points.iter().copied().collect();
+		let mut candidates = BinaryHeap::new();
+		let mut results = BinaryHeap::new();
+
+		for &id in entry_points {
+			let dist = distance(query, &self.nodes[id as usize].vector);
+			candidates.push(std::cmp::Reverse(Scored { dist, id }));
+			results.push(Scored { dist, id });
+		}
+
+		while let Some(std::cmp::Reverse(current)) = candidates.pop() {
+			let furthest = results.peek().map_or(f32::INFINITY, |s: &Scored| s.dist);
+			if current.dist > furthest && results.len() >= ef {
+				break;
+			}
+
+			let Some(neighbours) = self.nodes[current.id as usize].layers.get(layer) else {
+				continue;
+			};
+			for &neighbour in neighbours {
+				if !visited.insert(neighbour) {
+					continue;
+				}
+
+				let dist = distance(query, &self.nodes[neighbour as usize].vector);
+				let furthest = results.peek().map_or(f32::INFINITY, |s| s.dist);
+				if results.len() < ef || dist < furthest {
+					candidates.push(std::cmp::Reverse(Scored {
+						dist,
+						id: neighbour,
+					}));
+					results.push(Scored {
+						dist,
+						id: neighbour,
+					});
+					if results.len() > ef {
+						results.pop();
+					}
+				}
+			}
+		}
+
+		results
+			.into_sorted_vec()
+			.into_iter()
+			.map(|scored| scored.id)
+			.collect()
+	}
+
+	/// Rebuild the graph from the nodes that are still in use
+	fn rebuild(&mut self) {
+		debug!(
+			"Rebuilding vector index ({} of {} nodes removed)",
+			self.deleted_nodes,
+			self.nodes.len()
+		);
+
+		let nodes = std::mem::take(&mut self.nodes);
+		let contents = std::mem::take(&mut self.contents);
+		self.entry_point = None;
+		self.deleted_nodes = 0;
+
+		let mut vectors: Vec<Option<Vec<f32>>> =
+			nodes.into_iter().map(|node| Some(node.vector)).collect();
+
+		for (content_id, content) in contents {
+			let new_nodes = content
+				.nodes
+				.iter()
+				.filter_map(|&node| vectors[node as usize].take())
+				.map(|vector| self.insert_node(content_id, vector))
+				.collect();
+			self.contents.insert(
+				content_id,
+				IndexedContent {
+					content_hash: content.content_hash,
+					nodes: new_nodes,
+				},
+			);
+		}
+	}
+}
+
+/// A node and its distance to the query, ordered by distance
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Scored {
+	dist: f32,
+	id: u32,
+}
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Scored {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.dist
+			.total_cmp(&other.dist)
+			.then_with(|| self.id.cmp(&other.id))
+	}
+}
+
+fn max_neighbours(layer: usize) -> usize {
+	if layer == 0 {
+		MAX_NEIGHBOURS_BOTTOM
+	} else {
+		MAX_NEIGHBOURS
+	}
+}
+
+/// Layer a node is inserted up to, exponentially less likely the higher it is
+///
+/// Derived from the node id so the graph is deterministic for the same inserts.
+fn random_level(id: u32) -> usize {
+	// splitmix64
+	let mut x = (id as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+	x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	x ^= x >> 31;
+
+	let uniform = ((x >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+	let level_multiplier = 1.0 / (MAX_NEIGHBOURS as f64).ln();
+	(-uniform.ln() * level_multiplier) as usize
+}
+
+/// Scale a vector to unit length so cosine similarity is a dot product
+pub fn normalized(mut vector: Vec<f32>) -> Vec<f32> {
+	let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+	if norm > 0.0 {
+		vector.iter_mut().for_each(|x| *x /= norm);
+	}
+	vector
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+	a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn distance(a: &[f32], b: &[f32]) -> f32 {
+	1.0 - dot(a, b)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::tempdir;
+
+	/// Deterministic pseudo-random unit vector
+	fn vector(seed: u32, dimensions: usize) -> Vec<f32> {
+		let values = (0..dimensions)
+			.map(|i| {
+				let x = random_level(seed.wrapping_mul(7919).wrapping_add(i as u32)) as f32;
+				(seed as f32 * 0.37 + i as f32 * 1.31 + x).sin()
+			})
+			.collect();
+		normalized(values)
+	}
+
+	#[test]
+	fn test_search_finds_nearest_content() {
+		let mut index = VectorIndex::new("unused", "test", 16);
+		for id in 0..500 {
+			index
+				.upsert(id, &format!("hash-{}", id), vec![vector(id as u32, 16)])
+				.unwrap();
+		}
+
+		for id in [0, 123, 499] {
+			let hits = index.search(&vector(id as u32, 16), 3);
+			assert_eq!(hits[0].content_id, id);
+			assert!((hits[0].similarity - 1.0).abs() < 1e-4);
+			assert_eq!(hits[0].content_hash, format!("hash-{}", id));
+		}
+	}
+
+	#[test]
+	fn test_chunks_collapse_to_best_match_per_content() {
+		let mut index = VectorIndex::new("unused", "test", 8);
+		index
+			.upsert(1, "a", vec![vector(1, 8), vector(2, 8), vector(3, 8)])
+			.unwrap();
+		index.upsert(2, "b", vec![vector(4, 8)]).unwrap();
+
+		let hits = index.search(&vector(2, 8), 10);
+		assert_eq!(hits.len(), 2);
+		assert_eq!(hits[0].content_id, 1);
+		assert!((hits[0].similarity - 1.0).abs() < 1e-4);
+	}
+
+	#[test]
+	fn test_upsert_replaces_and_remove_rebuilds() {
+		let mut index = VectorIndex::new("unused", "test", 8);
+		for id in 0..20 {
+			index.upsert(id, "v1", vec![vector(id as u32, 8)]).unwrap();
+		}
+		assert!(index.is_current(5, "v1"));
+
+		index.upsert(5, "v2", vec![vector(100, 8)]).unwrap();
+		assert!(index.is_current(5, "v2"));
+		assert_eq!(index.search(&vector(100, 8), 1)[0].content_id, 5);
+
+		for id in 0..10 {
+			assert!(index.remove(id));
+		}
+		assert!(!index.remove(0));
+		assert_eq!(index.len(), 10);
+		// Enough nodes were removed to trigger a rebuild
+		assert!(index.nodes.len() < 21);
+		assert!(index
+			.search(&vector(3, 8), 20)
+			.iter()
+			.all(|hit| hit.content_id >= 10));
+	}
+
+	#[test]
+	fn test_rejects_wrong_dimensions() {
+		let mut index = VectorIndex::new("unused", "test", 8);
+		assert!(index.upsert(1, "a", vec![vec![1.0; 4]]).is_err());
+		assert!(index.search(&[1.0; 4], 1).is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_persists_and_resets_on_model_change() {
+		let dir = tempdir().unwrap();
+		let path = dir.path().join("embeddings").join("test.hnsw");
+
+		let mut index = VectorIndex::open(&path, "test", 8).await.unwrap();
+		index.upsert(7, "hash", vec![vector(7, 8)]).unwrap();
+		index.save().await.unwrap();
+
+		let reopened = VectorIndex::open(&path, "test", 8).await.unwrap();
+		assert!(reopened.is_current(7, "hash"));
+		assert_eq!(reopened.search(&vector(7, 8), 1)[0].content_id, 7);
+
+		let other_model = VectorIndex::open(&path, "other", 8).await.unwrap();
+		assert!(other_model.is_empty());
+	}
+}
@@ -0,0 +1,392 @@
+//! Embedding job for semantic search indexing
+
+use super::{source::EmbeddingDocument, TextEncoder};
+use crate::{
+	domain::location::JobPolicies,
+	infra::{
+		db::entities::{entry, location},
+		job::{prelude::*, traits::DynJob},
+	},
+	ops::models::{ensure_embedding_model, EmbeddingModel},
+};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Statement};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use tokio::task::spawn_blocking;
+use uuid::Uuid;
+
+/// Content identities embedded per inference batch
+const BATCH_SIZE: usize = 16;
+
+/// Batches embedded between vector index saves. Saving rewrites the whole index,
+/// so it happens periodically and once processing ends rather than after every batch.
+const SAVE_INTERVAL_BATCHES: usize = 16;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct EmbeddingJobConfig {
+	/// Location ID to process (None = every location whose policy enables embeddings)
+	pub location_id: Option<Uuid>,
+	/// Single entry UUID to process (for UI-triggered single file)
+	pub entry_uuid: Option<Uuid>,
+	/// Embedding model ID (e.g., "minilm-l6-v2")
+	pub model: String,
+	/// Re-embed content that is already indexed at its current hash
+	pub reprocess: bool,
+}
+
+impl Default for EmbeddingJobConfig {
+	fn default() -> Self {
+		Self {
+			location_id: None,
+			entry_uuid: None,
+			model: EmbeddingModel::default().id().to_string(),
+			reprocess: false,
+		}
+	}
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmbeddingJobState {
+	phase: EmbeddingPhase,
+	content_ids: Vec<i32>,
+	processed: usize,
+	embedded_count: usize,
+	error_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum EmbeddingPhase {
+	Discovery,
+	Processing,
+	Complete,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EmbeddingJob {
+	config: EmbeddingJobConfig,
+	state: EmbeddingJobState,
+}
+
+impl EmbeddingJob {
+	pub fn new(config: EmbeddingJobConfig) -> Self {
+		Self {
+			config,
+			state: EmbeddingJobState {
+				phase: EmbeddingPhase::Discovery,
+				content_ids: Vec::new(),
+				processed: 0,
+				embedded_count: 0,
+				error_count: 0,
+			},
+		}
+	}
+
+	pub fn from_location(location_id: Uuid) -> Self {
+		Self::new(EmbeddingJobConfig {
+			location_id: Some(location_id),
+			..Default::default()
+		})
+	}
+
+	fn model(&self) -> JobResult<EmbeddingModel> {
+		EmbeddingModel::from_str(&self.config.model)
+			.ok_or_else(|| JobError::execution(format!("Invalid model: {}", self.config.model)))
+	}
+}
+
+impl Job for EmbeddingJob {
+	const NAME: &'static str = "embedding";
+	const RESUMABLE: bool = true;
+	const DESCRIPTION: Option<&'static str> =
+		Some("Compute text embeddings of files for semantic search");
+}
+
+#[async_trait::async_trait]
+impl JobHandler for EmbeddingJob {
+	type Output = EmbeddingJobOutput;
+
+	async fn run(&mut self, ctx: JobContext<'_>) -> JobResult<Self::Output> {
+		match self.state.phase {
+			EmbeddingPhase::Discovery => {
+				self.run_discovery(&ctx).await?;
+				self.state.phase = EmbeddingPhase::Processing;
+				ctx.checkpoint().await?;
+			}
+			EmbeddingPhase::Processing => {}
+			EmbeddingPhase::Complete => return Ok(self.output()),
+		}
+
+		let model = self.model()?;
+		let data_dir = crate::config::default_data_dir()
+			.map_err(|e| JobError::execution(format!("Failed to get data dir: {}", e)))?;
+		let encoder = spawn_blocking(move || TextEncoder::shared(model, &data_dir))
+			.await
+			.map_err(|e| JobError::execution(format!("Model loading panicked: {}", e)))?
+			.map_err(|e| JobError::execution(format!("Failed to load model: {}", e)))?;
+
+		let index = ctx
+			.library()
+			.embedding_index()
+			.await
+			.map_err(|e| JobError::execution(format!("Failed to open vector index: {}", e)))?;
+
+		let total = self.state.content_ids.len();
+		let mut unsaved_batches = 0;
+
+		while self.state.processed < total {
+			ctx.check_interrupt().await?;
+
+			let batch_end = (self.state.processed + BATCH_SIZE).min(total);
+			let batch = self.state.content_ids[self.state.processed..batch_end].to_vec();
+
+			// The content may have been removed since discovery
+			let mut documents = Vec::with_capacity(batch.len());
+			for content_id in batch {
+				if let Some(document) = EmbeddingDocument::load(ctx.library(), content_id).await? {
+					documents.push(document);
+				}
+			}
+
+			let chunks: Vec<Vec<String>> = documents.iter().map(|doc| doc.chunks()).collect();
+			let texts: Vec<String> = chunks.iter().flatten().cloned().collect();
+
+			let batch_encoder = encoder.clone();
+			let embedded = spawn_blocking(move || {
+				batch_encoder
+					.lock()
+					.unwrap_or_else(|e| e.into_inner())
+					.embed(&texts)
+			})
+			.await
+			.map_err(|e| JobError::execution(format!("Embedding panicked: {}", e)))?;
+
+			match embedded {
+				Ok(vectors) => {
+					let mut vectors = vectors.into_iter();
+					let mut index = index.write().await;
+					for (document, document_chunks) in documents.iter().zip(&chunks) {
+						let document_vectors =
+							vectors.by_ref().take(document_chunks.len()).collect();
+						match index.upsert(
+							document.content_id,
+							&document.content_hash,
+							document_vectors,
+						) {
+							Ok(()) => self.state.embedded_count += 1,
+							Err(e) => {
+								ctx.add_non_critical_error(format!(
+									"Failed to index content {}: {}",
+									document.content_id, e
+								));
+								self.state.error_count += 1;
+							}
+						}
+					}
+				}
+				Err(e) => {
+					ctx.add_non_critical_error(format!(
+						"Embedding failed for {} files: {}",
+						documents.len(),
+						e
+					));
+					self.state.error_count += documents.len();
+				}
+			}
+
+			self.state.processed = batch_end;
+
+			ctx.progress(Progress::Count {
+				current: self.state.processed,
+				total,
+			});
+
+			// Persist the index before the checkpoint so a resume never skips unsaved work
+			unsaved_batches += 1;
+			if unsaved_batches >= SAVE_INTERVAL_BATCHES || self.state.processed == total {
+				index.write().await.save().await.map_err(|e| {
+					JobError::execution(format!("Failed to save vector index: {}", e))
+				})?;
+				ctx.checkpoint().await?;
+				unsaved_batches = 0;
+			}
+		}
+
+		self.state.phase = EmbeddingPhase::Complete;
+		ctx.log(format!(
+			"Embedding complete: {} embedded, {} errors",
+			self.state.embedded_count, self.state.error_count
+		));
+
+		Ok(self.output())
+	}
+}
+
+impl EmbeddingJob {
+	fn output(&self) -> EmbeddingJobOutput {
+		EmbeddingJobOutput {
+			total_processed: self.state.processed,
+			embedded_count: self.state.embedded_count,
+			error_count: self.state.error_count,
+		}
+	}
+
+	async fn run_discovery(&mut self, ctx: &JobContext<'_>) -> JobResult<()> {
+		// Make sure the model is available before queueing any work
+		let data_dir = crate::config::default_data_dir()
+			.map_err(|e| JobError::execution(format!("Failed to get data dir: {}", e)))?;
+		ensure_embedding_model(ctx, self.model()?, &data_dir).await?;
+
+		let db = ctx.library_db();
+		let index = ctx
+			.library()
+			.embedding_index()
+			.await
+			.map_err(|e| JobError::execution(format!("Failed to open vector index: {}", e)))?;
+
+		// Drop vectors of content that was deleted or changed since it was embedded
+		let current_hashes: HashMap<i32, String> = db
+			.query_all(Statement::from_string(
+				db.get_database_backend(),
+				"SELECT id, content_hash FROM content_identities",
+			))
+			.await?
+			.into_iter()
+			.map(|row| Ok((row.try_get("", "id")?, row.try_get("", "content_hash")?)))
+			.collect::<Result<_, sea_orm::DbErr>>()?;
+		{
+			let mut index = index.write().await;
+			let stale: Vec<i32> = index
+				.indexed()
+				.filter(|(id, hash)| current_hashes.get(id).map(String::as_str) != Some(*hash))
+				.map(|(id, _)| id)
+				.collect();
+			for content_id in &stale {
+				index.remove(*content_id);
+			}
+			if !stale.is_empty() {
+				ctx.log(format!("Removed {} stale embeddings", stale.len()));
+				index.save().await.map_err(|e| {
+					JobError::execution(format!("Failed to save vector index: {}", e))
+				})?;
+			}
+		}
+
+		let candidates = if let Some(entry_uuid) = self.config.entry_uuid {
+			// Single file mode (from UI action)
+			let entry_model = entry::Entity::find()
+				.filter(entry::Column::Uuid.eq(entry_uuid))
+				.one(db)
+				.await?
+				.ok_or_else(|| JobError::execution("Entry not found"))?;
+			entry_model.content_id.into_iter().collect()
+		} else {
+			let root_entry_ids = self.location_roots(db).await?;
+			ctx.log(format!(
+				"Discovering embedding candidates in {} location(s)",
+				root_entry_ids.len()
+			));
+
+			let mut content_ids = Vec::new();
+			for root_entry_id in root_entry_ids {
+				let rows = db
+					.query_all(Statement::from_sql_and_values(
+						db.get_database_backend(),
+						r#"
+							SELECT DISTINCT e.content_id AS content_id
+							FROM entry_closure ec
+							JOIN entries e ON e.id = ec.descendant_id
+							WHERE ec.ancestor_id = ?
+							AND e.kind = 0
+							AND e.content_id IS NOT NULL
+						"#,
+						[root_entry_id.into()],
+					))
+					.await?;
+				for row in rows {
+					content_ids.push(row.try_get::<i32>("", "content_id")?);
+				}
+			}
+			content_ids
+		};
+
+		// Content identities are shared across entries and locations, so embed each once
+		let index = index.read().await;
+		let mut seen = HashSet::new();
+		for content_id in candidates {
+			let Some(hash) = current_hashes.get(&content_id) else {
+				continue;
+			};
+			if !seen.insert(content_id) {
+				continue;
+			}
+			if self.config.reprocess || !index.is_current(content_id, hash) {
+				self.state.content_ids.push(content_id);
+			}
+		}
+
+		ctx.log(format!(
+			"Discovery complete: {} files need embedding",
+			self.state.content_ids.len()
+		));
+
+		Ok(())
+	}
+
+	/// Root entries of the locations to scan
+	///
+	/// An explicit location is always scanned (policy checks happen where the job is
+	/// dispatched); otherwise only locations whose policy enables embeddings are.
+	async fn location_roots(&self, db: &sea_orm::DatabaseConnection) -> JobResult<Vec<i32>> {
+		let mut query = location::Entity::find();
+		if let Some(location_id) = self.config.location_id {
+			query = query.filter(location::Column::Uuid.eq(location_id));
+		}
+
+		let locations = query.all(db).await?;
+
+		Ok(locations
+			.into_iter()
+			.filter(|loc| {
+				self.config.location_id.is_some()
+					|| loc
+						.job_policies
+						.as_deref()
+						.and_then(|json| serde_json::from_str::<JobPolicies>(json).ok())
+						.unwrap_or_default()
+						.embedding
+						.enabled
+			})
+			.filter_map(|loc| loc.entry_id)
+			.collect())
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct EmbeddingJobOutput {
+	pub total_processed: usize,
+	pub embedded_count: usize,
+	pub error_count: usize,
+}
+
+impl From<EmbeddingJobOutput> for JobOutput {
+	fn from(output: EmbeddingJobOutput) -> Self {
+		JobOutput::Embedding {
+			total_processed: output.total_processed,
+			embedded_count: output.embedded_count,
+			error_count: output.error_count,
+		}
+	}
+}
+
+impl DynJob for EmbeddingJob {
+	fn job_name(&self) -> &'static str {
+		"Semantic Embedding"
+	}
+}
+
+impl From<EmbeddingJob> for Box<dyn DynJob> {
+	fn from(job: EmbeddingJob) -> Self {
+		Box::new(job)
+	}
+}
@@ -0,0 +1,95 @@
+//! Text embeddings for local semantic search
+//!
+//! Turns what is known about a file as text (its names, extracted document text, OCR
+//! output and speech transcripts) into vectors with a small CPU-only ONNX model, and
+//! stores them in a per-library nearest neighbour index keyed by content identity.
+//! `SearchMode::Normal` and `SearchMode::Full` blend vector similarity with their
+//! FTS5 ranking.
+//!
+//! Only text is embedded. Images are searchable by meaning through their names and
+//! OCR output, not their pixels: that would need an image-text model such as CLIP,
+//! whose vectors don't share a space with this index.
+//!
+//! Computing embeddings requires the `embeddings` feature. Without it, or before the
+//! model is downloaded, searches fall back to FTS5 alone.
+
+pub mod index;
+pub mod source;
+
+#[cfg(feature = "embeddings")]
+pub mod encoder;
+#[cfg(feature = "embeddings")]
+pub mod job;
+
+pub use index::{VectorHit, VectorIndex, VectorIndexError};
+pub use source::EmbeddingDocument;
+
+#[cfg(feature = "embeddings")]
+pub use encoder::TextEncoder;
+#[cfg(feature = "embeddings")]
+pub use job::{EmbeddingJob, EmbeddingJobConfig};
+
+/// Most chunks embedded per content identity; text beyond is not searchable by meaning
+pub const MAX_CHUNKS: usize = 8;
+
+/// Target chunk length in characters, roughly the model's 256 token window
+pub const CHUNK_CHARS: usize = 1000;
+
+/// Split text into chunks of about `max_chars`, breaking on whitespace where possible
+pub fn chunk_text(text: &str, max_chars: usize, max_chunks: usize) -> Vec<String> {
+	let mut chunks = Vec::new();
+	let mut current = String::new();
+	let mut current_chars = 0;
+
+	for word in text.split_whitespace() {
+		if chunks.len() == max_chunks {
+			return chunks;
+		}
+
+		let word_chars = word.chars().count();
+		if current_chars > 0 && current_chars + 1 + word_chars > max_chars {
+			chunks.push(std::mem::take(&mut current));
+			current_chars = 0;
+			if chunks.len() == max_chunks {
+				return chunks;
+			}
+		}
+
+		if current_chars > 0 {
+			current.push(' ');
+			current_chars += 1;
+		}
+
+		// A single overlong word is cut rather than emitted as an oversized chunk
+		if word_chars > max_chars {
+			current.extend(word.chars().take(max_chars));
+			current_chars = max_chars;
+		} else {
+			current.push_str(word);
+			current_chars += word_chars;
+		}
+	}
+
+	if !current.is_empty() && chunks.len() < max_chunks {
+		chunks.push(current);
+	}
+
+	chunks
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_chunk_text() {
+		assert!(chunk_text("   ", 10, 4).is_empty());
+		assert_eq!(chunk_text("one two", 10, 4), vec!["one two"]);
+		assert_eq!(
+			chunk_text("alpha beta gamma delta", 11, 4),
+			vec!["alpha beta", "gamma delta"]
+		);
+		assert_eq!(chunk_text("a b c d e f", 3, 2), vec!["a b", "c d"]);
+		assert_eq!(chunk_text("abcdefghij", 4, 4), vec!["abcd"]);
+	}
+}
@@ -0,0 +1,187 @@
+//! Text gathered from a content identity for embedding
+
+use super::{chunk_text, CHUNK_CHARS, MAX_CHUNKS};
+use crate::{
+	library::Library,
+	ops::sidecar::types::{SidecarFormat, SidecarKind, SidecarVariant},
+};
+use sea_orm::{ConnectionTrait, DbErr, Statement};
+use tracing::debug;
+use uuid::Uuid;
+
+/// Distinct file names included per content identity
+const MAX_NAMES: usize = 3;
+
+/// Everything searchable by meaning about one content identity
+#[derive(Debug, Clone)]
+pub struct EmbeddingDocument {
+	pub content_id: i32,
+	pub content_hash: String,
+	/// Names of the entries with this content, made readable
+	pub names: Vec<String>,
+	/// Extracted document text, OCR output and transcript, in that order
+	pub text: String,
+}
+
+impl EmbeddingDocument {
+	/// Load the names, extracted text, OCR output and transcript of a content identity
+	pub async fn load(library: &Library, content_id: i32) -> Result<Option<Self>, DbErr> {
+		let db = library.db().conn();
+
+		let Some(row) = db
+			.query_one(Statement::from_sql_and_values(
+				db.get_database_backend(),
+				r#"
+					SELECT ci.uuid AS uuid, ci.content_hash AS content_hash,
+						ci.text_content AS ocr_text,
+						(SELECT body FROM content_search_index WHERE rowid = ci.id) AS body
+					FROM content_identities ci
+					WHERE ci.id = ?
+				"#,
+				[content_id.into()],
+			))
+			.await?
+		else {
+			return Ok(None);
+		};
+
+		let content_uuid: Option<Uuid> = row.try_get("", "uuid")?;
+		let content_hash: String = row.try_get("", "content_hash")?;
+		let body: Option<String> = row.try_get("", "body")?;
+		let ocr_text: Option<String> = row.try_get("", "ocr_text")?;
+
+		let name_rows = db
+			.query_all(Statement::from_sql_and_values(
+				db.get_database_backend(),
+				"SELECT DISTINCT name, extension FROM entries WHERE content_id = ? LIMIT ?",
+				[content_id.into(), (MAX_NAMES as i64).into()],
+			))
+			.await?;
+		let mut names = Vec::with_capacity(name_rows.len());
+		for row in name_rows {
+			let name: String = row.try_get("", "name")?;
+			let extension: Option<String> = row.try_get("", "extension")?;
+			names.push(readable_name(&name, extension.as_deref()));
+		}
+
+		let transcript = match content_uuid {
+			Some(uuid) => load_transcript(library, &uuid).await,
+			None => None,
+		};
+
+		let text = [body, ocr_text, transcript]
+			.into_iter()
+			.flatten()
+			.map(|text| text.trim().to_string())
+			.filter(|text| !text.is_empty())
+			.collect::<Vec<_>>()
+			.join("\n\n");
+
+		Ok(Some(Self {
+			content_id,
+			content_hash,
+			names,
+			text,
+		}))
+	}
+
+	/// Chunks to embed: the file names first, then the body text
+	pub fn chunks(&self) -> Vec<String> {
+		let mut chunks = Vec::new();
+		if !self.names.is_empty() {
+			chunks.push(self.names.join(", "));
+		}
+		chunks.extend(chunk_text(
+			&self.text,
+			CHUNK_CHARS,
+			MAX_CHUNKS - chunks.len(),
+		));
+		chunks
+	}
+}
+
+/// Turn `IMG_beach-sunset` + `jpg` into `IMG beach sunset jpg` so words tokenize cleanly
+pub fn readable_name(name: &str, extension: Option<&str>) -> String {
+	let mut readable = name
+		.split(|c: char| c == '_' || c == '-' || c == '.')
+		.filter(|part| !part.is_empty())
+		.collect::<Vec<_>>()
+		.join(" ");
+	if let Some(extension) = extension.filter(|ext| !ext.is_empty()) {
+		readable.push(' ');
+		readable.push_str(extension);
+	}
+	readable
+}
+
+/// Read the speech transcript sidecar of a content identity, if there is one
+async fn load_transcript(library: &Library, content_uuid: &Uuid) -> Option<String> {
+	let sidecar_manager = library.core_context().get_sidecar_manager().await?;
+	let path = sidecar_manager
+		.compute_path(
+			&library.id(),
+			content_uuid,
+			&SidecarKind::Transcript,
+			&SidecarVariant::new("srt"),
+			&SidecarFormat::Text,
+		)
+		.await
+		.ok()?;
+
+	match tokio::fs::read_to_string(&path.absolute_path).await {
+		Ok(srt) => Some(srt_to_text(&srt)),
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+		Err(e) => {
+			debug!("Failed to read transcript for {}: {}", content_uuid, e);
+			None
+		}
+	}
+}
+
+/// Plain text of an SRT subtitle file, without cue numbers and timestamps
+pub fn srt_to_text(srt: &str) -> String {
+	srt.lines()
+		.map(str::trim)
+		.filter(|line| {
+			!line.is_empty()
+				&& !line.contains("-->")
+				&& !line.chars().all(|c| c.is_ascii_digit() || c == '\u{feff}')
+		})
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_readable_name() {
+		assert_eq!(
+			readable_name("IMG_beach-sunset", Some("jpg")),
+			"IMG beach sunset jpg"
+		);
+		assert_eq!(readable_name("notes", None), "notes");
+	}
+
+	#[test]
+	fn test_srt_to_text() {
+		let srt = "1\n00:00:00,000 --> 00:00:02,000\nHello there\n\n2\n00:00:02,000 --> 00:00:04,000\nGeneral Kenobi\n";
+		assert_eq!(srt_to_text(srt), "Hello there General Kenobi");
+	}
+
+	#[test]
+	fn test_chunks_start_with_names() {
+		let document = EmbeddingDocument {
+			content_id: 1,
+			content_hash: "hash".to_string(),
+			names: vec!["invoice march pdf".to_string()],
+			text: "word ".repeat(5000),
+		};
+
+		let chunks = document.chunks();
+		assert_eq!(chunks.len(), MAX_CHUNKS);
+		assert_eq!(chunks[0], "invoice march pdf");
+		assert!(chunks[1].len() <= CHUNK_CHARS);
+	}
+}
@@ -1,7 +1,15 @@
 //! Media metadata extraction utilities
 
 use crate::infra::db::entities::{audio_media_data, image_media_data, video_media_data};
+#[cfg(feature = "wasm")]
+use crate::{
+	infra::extension::MediaHandlerKind,
+	library::Library,
+	ops::sidecar::types::{SidecarFormat, SidecarKind, SidecarVariant},
+};
 use chrono::{DateTime, Utc};
+use sd_images::ConvertibleExtension;
+use sd_media_metadata::exif::ExifMetadata;
 use sea_orm::ActiveValue::Set;
 use std::path::Path;
 use uuid::Uuid;
@@ -23,10 +31,19 @@ pub async fn extract_image_metadata_with_blurhash(
 	uuid: Uuid,
 	blurhash: Option<String>,
 ) -> Result<image_media_data::ActiveModel, Box<dyn std::error::Error + Send + Sync>> {
-	// Extract EXIF metadata
-	let exif = sd_media_metadata::exif::ExifMetadata::from_path(path)
-		.await?
-		.ok_or("No EXIF data found")?;
+	// Extract EXIF metadata. sd-images reads camera RAW containers, whose EXIF data often
+	// describes only the embedded preview, so it provides their dimensions too.
+	let (exif, raw_dimensions) =
+		if ConvertibleExtension::try_from(path).is_ok_and(ConvertibleExtension::is_raw) {
+			extract_raw_exif(path).await?
+		} else {
+			(ExifMetadata::from_path(path).await?, None)
+		};
+	let exif = exif.ok_or("No EXIF data found")?;
+	let (width, height) = raw_dimensions.map_or(
+		(exif.resolution.width, exif.resolution.height),
+		|(width, height)| (width as i32, height as i32),
+	);
 
 	// Convert MediaDate to DateTime<Utc>
 	let date_taken = exif.date_taken.map(|d| match d {
@@ -47,8 +64,8 @@ pub async fn extract_image_metadata_with_blurhash(
 	Ok(image_media_data::ActiveModel {
 		id: sea_orm::ActiveValue::NotSet,
 		uuid: Set(uuid),
-		width: Set(exif.resolution.width as i32),
-		height: Set(exif.resolution.height as i32),
+		width: Set(width),
+		height: Set(height),
 		blurhash: Set(blurhash),
 		date_taken: Set(date_taken.map(Into::into)),
 		latitude: Set(latitude),
@@ -72,6 +89,24 @@ pub async fn extract_image_metadata_with_blurhash(
 	})
 }
 
+/// EXIF metadata and dimensions of a camera RAW file
+async fn extract_raw_exif(
+	path: &Path,
+) -> Result<(Option<ExifMetadata>, Option<(u32, u32)>), Box<dyn std::error::Error + Send + Sync>> {
+	let path = path.to_path_buf();
+
+	tokio::task::spawn_blocking(move || {
+		let raw = sd_images::extract_raw_metadata(&path)
+			.map_err(|e| format!("Failed to read RAW file: {}", e))?;
+		let exif = match raw.exif {
+			Some(exif) => ExifMetadata::from_slice(&exif)?,
+			None => None,
+		};
+		Ok::<_, Box<dyn std::error::Error + Send + Sync>>((exif, raw.dimensions))
+	})
+	.await?
+}
+
 /// Extract video metadata from FFmpeg
 ///
 /// Optionally pass a blurhash if it has already been generated elsewhere
@@ -296,3 +331,103 @@ pub async fn extract_audio_metadata(
 		updated_at: Set(chrono::Utc::now().into()),
 	})
 }
+
+/// Run the metadata extractor an extension registered for a file and store the JSON it
+/// returns as a `Metadata` sidecar, with the extension ID as the variant
+///
+/// Returns the ID of the extension that extracted metadata, or `None` if no extension
+/// handles the file or its sidecar already exists.
+#[cfg(feature = "wasm")]
+pub async fn extract_extension_metadata(
+	library: &Library,
+	content_uuid: Uuid,
+	path: &Path,
+	mime_type: Option<&str>,
+	extension: Option<&str>,
+) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+	let context = library.core_context();
+	let Some(plugin_manager) = context.get_plugin_manager().await else {
+		return Ok(None);
+	};
+	let Some(handler) = plugin_manager.read().await.media_handlers().find(
+		MediaHandlerKind::MetadataExtractor,
+		mime_type,
+		extension,
+	) else {
+		return Ok(None);
+	};
+
+	let sidecar_manager = context
+		.get_sidecar_manager()
+		.await
+		.ok_or("SidecarManager not available")?;
+	let variant = SidecarVariant::new(handler.extension_id.clone());
+	if sidecar_manager
+		.exists(
+			&library.id(),
+			&content_uuid,
+			&SidecarKind::Metadata,
+			&variant,
+			&SidecarFormat::Json,
+		)
+		.await
+		.unwrap_or(false)
+	{
+		return Ok(None);
+	}
+
+	let request = serde_json::json!({
+		"mime_type": mime_type,
+		"extension": extension,
+		"file_size": tokio::fs::metadata(path).await?.len(),
+	});
+	let instance = plugin_manager
+		.read()
+		.await
+		.instance(&handler.extension_id)?;
+	let export_fn = handler.export_fn.clone();
+	let source_path = path.to_path_buf();
+	let library_id = library.id();
+	let output = instance
+		.run(move |instance| {
+			instance.call_media_handler(&export_fn, &source_path, &request, Some(library_id))
+		})
+		.await?;
+
+	// Reject output that isn't JSON rather than storing it as a metadata sidecar
+	let metadata: serde_json::Value = serde_json::from_slice(&output).map_err(|e| {
+		format!(
+			"Extension {} returned invalid metadata: {}",
+			handler.extension_id, e
+		)
+	})?;
+	let bytes = serde_json::to_vec(&metadata)?;
+
+	let sidecar_path = sidecar_manager
+		.compute_path(
+			&library.id(),
+			&content_uuid,
+			&SidecarKind::Metadata,
+			&variant,
+			&SidecarFormat::Json,
+		)
+		.await?;
+	if let Some(parent) = sidecar_path.absolute_path.parent() {
+		tokio::fs::create_dir_all(parent).await?;
+	}
+	tokio::fs::write(&sidecar_path.absolute_path, &bytes).await?;
+
+	sidecar_manager
+		.record_sidecar(
+			library,
+			&content_uuid,
+			&SidecarKind::Metadata,
+			&variant,
+			&SidecarFormat::Json,
+			bytes.len() as u64,
+			None,
+		)
+		.await?;
+
+	Ok(Some(handler.extension_id))
+}
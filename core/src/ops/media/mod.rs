@@ -9,12 +9,18 @@
 //! - Audio metadata extraction
 //! - Image optimization
 //! - Blurhash generation for image placeholders
+//! - Perceptual hashing for near-duplicate detection
+//! - Text extraction from document bodies for full-text search
+//! - Text embeddings for semantic search
 
 pub mod blurhash;
+pub mod embedding;
 pub mod metadata_extractor;
 pub mod ocr;
+pub mod perceptual_hash;
 pub mod proxy;
 pub mod splat;
+pub mod text_extraction;
 
 pub mod speech;
 pub mod thumbnail;
@@ -22,6 +28,8 @@ pub mod thumbstrip;
 
 pub use metadata_extractor::{extract_image_metadata, extract_image_metadata_with_blurhash};
 
+#[cfg(feature = "wasm")]
+pub use metadata_extractor::extract_extension_metadata;
 #[cfg(feature = "ffmpeg")]
 pub use metadata_extractor::{
 	extract_audio_metadata, extract_video_metadata, extract_video_metadata_with_blurhash,
@@ -29,7 +37,10 @@ pub use metadata_extractor::{
 pub use ocr::{OcrJob, OcrProcessor};
 pub use proxy::{ProxyJob, ProxyProcessor};
 pub use splat::{GaussianSplatJob, GaussianSplatProcessor};
+pub use text_extraction::{TextExtractionJob, TextExtractionProcessor};
 
+#[cfg(feature = "embeddings")]
+pub use embedding::EmbeddingJob;
 #[cfg(feature = "speech-to-text")]
 pub use speech::{SpeechToTextJob, SpeechToTextProcessor};
 #[cfg(feature = "ffmpeg")]
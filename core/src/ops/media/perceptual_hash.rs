@@ -0,0 +1,487 @@
+//! Perceptual hashing for images and videos
+//!
+//! Perceptual hashes stay stable when an image is resized, recompressed or
+//! re-exported, so two files that *look* the same end up a few bits apart even
+//! though their bytes (and content hashes) are completely different.
+//!
+//! Each frame is fingerprinted with two 64-bit hashes:
+//! - dHash: compares neighbouring pixels of a 9x8 grayscale thumbnail (gradient)
+//! - pHash: compares the low-frequency DCT coefficients of a 32x32 thumbnail
+//!
+//! Videos are fingerprinted by sampling a fixed number of frames across their duration.
+//! Fingerprints are stored as a `SidecarKind::PerceptualHash` sidecar.
+
+use crate::domain::ContentKind;
+use image::{imageops::FilterType, DynamicImage};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path};
+use thiserror::Error;
+use tokio::task::spawn_blocking;
+
+/// Number of bits in each hash
+pub const HASH_BITS: u32 = 64;
+
+/// Sidecar variant for the current hash layout (bump when the algorithm changes)
+pub const PERCEPTUAL_HASH_VARIANT: &str = "dhash_phash_64";
+
+/// Number of frames sampled from a video, spread evenly between 10% and 90%
+#[cfg(feature = "ffmpeg")]
+const VIDEO_SAMPLE_FRAMES: usize = 5;
+
+#[derive(Error, Debug)]
+pub enum PerceptualHashError {
+	#[error("Unsupported media type for perceptual hashing")]
+	Unsupported,
+
+	#[error("Image decoding failed: {0}")]
+	Image(String),
+
+	#[error("Video decoding failed: {0}")]
+	Video(String),
+
+	#[error("No frames could be decoded")]
+	NoFrames,
+
+	#[error("Task join error: {0}")]
+	Join(#[from] tokio::task::JoinError),
+}
+
+/// Perceptual hashes of a single image or video frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PerceptualHash {
+	pub dhash: u64,
+	pub phash: u64,
+}
+
+impl PerceptualHash {
+	/// Hash a decoded image
+	pub fn from_image(image: &DynamicImage) -> Self {
+		Self {
+			dhash: dhash(image),
+			phash: phash(image),
+		}
+	}
+
+	/// Hamming distance to another hash (0..=64)
+	///
+	/// Both hashes have to agree, so the larger of the two distances is used.
+	/// This keeps dHash from matching unrelated images with similar gradients
+	/// and pHash from matching images with similar overall tone.
+	pub fn distance(&self, other: &Self) -> u32 {
+		hamming_distance(self.dhash, other.dhash).max(hamming_distance(self.phash, other.phash))
+	}
+}
+
+/// Perceptual fingerprint of a file: one hash for images, several sampled frames for videos
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaFingerprint {
+	pub frames: Vec<PerceptualHash>,
+}
+
+impl MediaFingerprint {
+	/// Mean per-frame distance to another fingerprint
+	///
+	/// Returns `None` when the fingerprints can't be compared (an image against a
+	/// video, or videos where a different number of frames could be decoded).
+	pub fn distance(&self, other: &Self) -> Option<u32> {
+		if self.frames.is_empty() || self.frames.len() != other.frames.len() {
+			return None;
+		}
+
+		let total: u32 = self
+			.frames
+			.iter()
+			.zip(&other.frames)
+			.map(|(a, b)| a.distance(b))
+			.sum();
+
+		Some(total.div_ceil(self.frames.len() as u32))
+	}
+
+	/// Summed per-frame distance to another fingerprint with the same frame count
+	///
+	/// Unlike the rounded mean from [`MediaFingerprint::distance`], this satisfies
+	/// the triangle inequality, which is what [`FingerprintTree`] relies on.
+	fn total_distance(&self, other: &Self) -> u32 {
+		self.frames
+			.iter()
+			.zip(&other.frames)
+			.map(|(a, b)| a.distance(b))
+			.sum()
+	}
+}
+
+/// BK-tree over fingerprints for finding near-duplicates without comparing every pair
+///
+/// Fingerprints are only comparable when they have the same number of frames, so
+/// each frame count gets its own tree. A radius query only descends into children
+/// whose edge distance is within the radius of the query's distance to the node,
+/// which prunes most of the tree for the small thresholds used in practice.
+#[derive(Debug, Default)]
+pub struct FingerprintTree<'a> {
+	roots: HashMap<usize, usize>,
+	nodes: Vec<TreeNode<'a>>,
+}
+
+#[derive(Debug)]
+struct TreeNode<'a> {
+	id: usize,
+	fingerprint: &'a MediaFingerprint,
+	children: HashMap<u32, usize>,
+}
+
+impl<'a> FingerprintTree<'a> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Add a fingerprint under a caller-chosen id. Empty fingerprints are ignored.
+	pub fn insert(&mut self, id: usize, fingerprint: &'a MediaFingerprint) {
+		if fingerprint.frames.is_empty() {
+			return;
+		}
+
+		let new_index = self.nodes.len();
+		self.nodes.push(TreeNode {
+			id,
+			fingerprint,
+			children: Default::default(),
+		});
+
+		let Some(&root) = self.roots.get(&fingerprint.frames.len()) else {
+			self.roots.insert(fingerprint.frames.len(), new_index);
+			return;
+		};
+
+		let mut current = root;
+		loop {
+			let distance = self.nodes[current].fingerprint.total_distance(fingerprint);
+			match self.nodes[current].children.get(&distance) {
+				Some(&child) => current = child,
+				None => {
+					self.nodes[current].children.insert(distance, new_index);
+					return;
+				}
+			}
+		}
+	}
+
+	/// Ids of all fingerprints whose [`MediaFingerprint::distance`] to `fingerprint`
+	/// is at most `max_distance`, together with that distance
+	pub fn find_within(
+		&self,
+		fingerprint: &MediaFingerprint,
+		max_distance: u32,
+	) -> Vec<(usize, u32)> {
+		let frame_count = fingerprint.frames.len();
+		let Some(&root) = self.roots.get(&frame_count) else {
+			return Vec::new();
+		};
+
+		// The mean is rounded up, so a mean within `max_distance` is a sum within this
+		let radius = max_distance * frame_count as u32;
+		let mut matches = Vec::new();
+		let mut stack = vec![root];
+
+		while let Some(index) = stack.pop() {
+			let node = &self.nodes[index];
+			let distance = node.fingerprint.total_distance(fingerprint);
+
+			if distance <= radius {
+				matches.push((node.id, distance.div_ceil(frame_count as u32)));
+			}
+
+			let low = distance.saturating_sub(radius);
+			let high = distance + radius;
+			stack.extend(
+				node.children
+					.iter()
+					.filter(|(edge, _)| (low..=high).contains(*edge))
+					.map(|(_, &child)| child),
+			);
+		}
+
+		matches
+	}
+}
+
+/// Number of differing bits between two hashes
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+	(a ^ b).count_ones()
+}
+
+/// Convert a Hamming distance into a similarity score (1.0 = identical)
+pub fn similarity(distance: u32) -> f64 {
+	1.0 - f64::from(distance.min(HASH_BITS)) / f64::from(HASH_BITS)
+}
+
+/// Convert a similarity threshold (0.0 to 1.0) into the largest Hamming distance it allows
+pub fn max_distance_for_similarity(threshold: f64) -> u32 {
+	let threshold = threshold.clamp(0.0, 1.0);
+	((1.0 - threshold) * f64::from(HASH_BITS)).round() as u32
+}
+
+/// Difference hash: one bit per horizontally adjacent pixel pair of a 9x8 grayscale thumbnail
+fn dhash(image: &DynamicImage) -> u64 {
+	let small = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+
+	let mut hash = 0u64;
+	for y in 0..8 {
+		for x in 0..8 {
+			hash <<= 1;
+			if small.get_pixel(x, y)[0] < small.get_pixel(x + 1, y)[0] {
+				hash |= 1;
+			}
+		}
+	}
+
+	hash
+}
+
+/// DCT hash: sign of the 8x8 lowest DCT frequencies of a 32x32 grayscale thumbnail
+/// relative to their median
+fn phash(image: &DynamicImage) -> u64 {
+	const SIZE: usize = 32;
+	const LOW: usize = 8;
+
+	let small = image
+		.resize_exact(SIZE as u32, SIZE as u32, FilterType::Triangle)
+		.to_luma8();
+	let pixels: Vec<f64> = small.pixels().map(|p| f64::from(p[0])).collect();
+
+	// Only the low frequencies are needed, so precompute their cosine terms
+	let mut cosines = [[0f64; SIZE]; LOW];
+	for (u, row) in cosines.iter_mut().enumerate() {
+		for (x, value) in row.iter_mut().enumerate() {
+			*value = (std::f64::consts::PI * (2 * x + 1) as f64 * u as f64 / (2 * SIZE) as f64)
+				.cos();
+		}
+	}
+
+	let mut coefficients = [0f64; LOW * LOW];
+	for v in 0..LOW {
+		for u in 0..LOW {
+			let mut sum = 0.0;
+			for y in 0..SIZE {
+				for x in 0..SIZE {
+					sum += pixels[y * SIZE + x] * cosines[u][x] * cosines[v][y];
+				}
+			}
+			coefficients[v * LOW + u] = sum;
+		}
+	}
+
+	// The DC term only reflects average brightness, keep it out of the median
+	let mut sorted = coefficients[1..].to_vec();
+	sorted.sort_by(|a, b| a.total_cmp(b));
+	let median = sorted[sorted.len() / 2];
+
+	coefficients
+		.iter()
+		.fold(0u64, |hash, &c| (hash << 1) | u64::from(c > median))
+}
+
+/// Whether files of this kind can be perceptually hashed in this build
+pub fn is_perceptual_hash_supported(kind: ContentKind) -> bool {
+	match kind {
+		ContentKind::Image => true,
+		ContentKind::Video => cfg!(feature = "ffmpeg"),
+		_ => false,
+	}
+}
+
+/// Compute the perceptual fingerprint of an image or video file
+pub async fn compute_fingerprint(
+	path: impl AsRef<Path>,
+	kind: ContentKind,
+) -> Result<MediaFingerprint, PerceptualHashError> {
+	match kind {
+		ContentKind::Image => compute_image_fingerprint(path).await,
+		#[cfg(feature = "ffmpeg")]
+		ContentKind::Video => compute_video_fingerprint(path).await,
+		_ => Err(PerceptualHashError::Unsupported),
+	}
+}
+
+async fn compute_image_fingerprint(
+	path: impl AsRef<Path>,
+) -> Result<MediaFingerprint, PerceptualHashError> {
+	let path = path.as_ref().to_path_buf();
+
+	spawn_blocking(move || {
+		let image = sd_images::format_image(&path)
+			.map_err(|e| PerceptualHashError::Image(e.to_string()))?;
+
+		Ok(MediaFingerprint {
+			frames: vec![PerceptualHash::from_image(&image)],
+		})
+	})
+	.await?
+}
+
+#[cfg(feature = "ffmpeg")]
+async fn compute_video_fingerprint(
+	path: impl AsRef<Path>,
+) -> Result<MediaFingerprint, PerceptualHashError> {
+	use image::RgbImage;
+	use sd_ffmpeg::{FrameDecoder, ThumbnailSize};
+
+	let path = path.as_ref().to_path_buf();
+
+	spawn_blocking(move || {
+		let mut decoder = FrameDecoder::new(&path, true, false)
+			.map_err(|e| PerceptualHashError::Video(e.to_string()))?;
+
+		// A frame has to be decoded before the duration is known
+		decoder
+			.decode_video_frame()
+			.map_err(|e| PerceptualHashError::Video(e.to_string()))?;
+
+		let duration = decoder
+			.get_duration_secs()
+			.ok_or_else(|| PerceptualHashError::Video("Video has no duration".to_string()))?;
+
+		let mut frames = Vec::with_capacity(VIDEO_SAMPLE_FRAMES);
+		for i in 0..VIDEO_SAMPLE_FRAMES {
+			let position = 0.1 + 0.8 * i as f64 / (VIDEO_SAMPLE_FRAMES - 1) as f64;
+
+			if decoder.seek((duration * position) as i64).is_err()
+				|| decoder.decode_video_frame().is_err()
+			{
+				continue;
+			}
+
+			// Small frames are plenty for a 32x32 hash and keep scaling cheap
+			let Ok(frame) = decoder.get_scaled_video_frame(Some(ThumbnailSize::Scale(128)), false)
+			else {
+				continue;
+			};
+
+			if let Some(rgb) = RgbImage::from_raw(frame.width, frame.height, frame.data) {
+				frames.push(PerceptualHash::from_image(&DynamicImage::ImageRgb8(rgb)));
+			}
+		}
+
+		if frames.is_empty() {
+			return Err(PerceptualHashError::NoFrames);
+		}
+
+		Ok(MediaFingerprint { frames })
+	})
+	.await?
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::{Rgb, RgbImage};
+
+	fn gradient(width: u32, height: u32) -> DynamicImage {
+		DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+			let v = ((x * 255 / width) ^ (y * 255 / height)) as u8;
+			Rgb([v, v / 2, 255 - v])
+		}))
+	}
+
+	#[test]
+	fn test_resized_image_is_near_duplicate() {
+		let original = PerceptualHash::from_image(&gradient(640, 480));
+		let resized = PerceptualHash::from_image(&gradient(640, 480).resize_exact(
+			200,
+			150,
+			FilterType::Lanczos3,
+		));
+
+		assert!(original.distance(&resized) <= 6);
+	}
+
+	#[test]
+	fn test_different_images_are_far_apart() {
+		let horizontal = DynamicImage::ImageRgb8(RgbImage::from_fn(256, 256, |x, _| {
+			Rgb([x as u8, x as u8, x as u8])
+		}));
+		let vertical = DynamicImage::ImageRgb8(RgbImage::from_fn(256, 256, |_, y| {
+			Rgb([y as u8, y as u8, y as u8])
+		}));
+
+		let a = PerceptualHash::from_image(&horizontal);
+		let b = PerceptualHash::from_image(&vertical);
+
+		assert!(a.distance(&b) > 10);
+	}
+
+	#[test]
+	fn test_fingerprint_distance_requires_matching_frames() {
+		let hash = PerceptualHash::from_image(&gradient(64, 64));
+		let image = MediaFingerprint { frames: vec![hash] };
+		let video = MediaFingerprint {
+			frames: vec![hash, hash],
+		};
+
+		assert_eq!(image.distance(&image), Some(0));
+		assert_eq!(image.distance(&video), None);
+	}
+
+	#[test]
+	fn test_fingerprint_tree_matches_pairwise_search() {
+		// Deterministic pseudo-random hashes, with a few near copies mixed in
+		let mut state = 0x9e37_79b9_7f4a_7c15u64;
+		let mut next = move || {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			state
+		};
+
+		let mut fingerprints = Vec::new();
+		for i in 0..200 {
+			let frames = if i % 3 == 0 { 5 } else { 1 };
+			let fingerprint = MediaFingerprint {
+				frames: (0..frames)
+					.map(|_| PerceptualHash {
+						dhash: next(),
+						phash: next(),
+					})
+					.collect(),
+			};
+			if i % 10 == 0 {
+				let mut near = fingerprint.clone();
+				for frame in &mut near.frames {
+					frame.dhash ^= 0b101;
+					frame.phash ^= 0b1;
+				}
+				fingerprints.push(near);
+			}
+			fingerprints.push(fingerprint);
+		}
+
+		let mut tree = FingerprintTree::new();
+		for (id, fingerprint) in fingerprints.iter().enumerate() {
+			tree.insert(id, fingerprint);
+		}
+
+		for max_distance in [0, 2, 6, 30] {
+			for (i, query) in fingerprints.iter().enumerate() {
+				let mut found = tree.find_within(query, max_distance);
+				found.sort_unstable();
+
+				let expected: Vec<(usize, u32)> = fingerprints
+					.iter()
+					.enumerate()
+					.filter_map(|(j, other)| Some((j, query.distance(other)?)))
+					.filter(|(_, distance)| *distance <= max_distance)
+					.collect();
+
+				assert_eq!(found, expected, "query {} at distance {}", i, max_distance);
+			}
+		}
+	}
+
+	#[test]
+	fn test_similarity_threshold_conversion() {
+		assert_eq!(max_distance_for_similarity(1.0), 0);
+		assert_eq!(max_distance_for_similarity(0.9), 6);
+		assert_eq!(similarity(0), 1.0);
+		assert_eq!(similarity(32), 0.5);
+	}
+}
@@ -0,0 +1,424 @@
+//! Format-specific text extractors
+//!
+//! Each extractor turns a file into plain text suitable for the FTS5 tokenizer.
+//! Container formats (DOCX, ODT, EPUB) are zip archives of XML documents, so they
+//! share one XML-to-text walker that keeps paragraph breaks and drops markup.
+
+use crate::domain::ContentKind;
+use quick_xml::{escape::resolve_predefined_entity, events::Event, Reader};
+use std::{
+	fs::File,
+	io::{BufReader, Read},
+	path::Path,
+};
+use thiserror::Error;
+use zip::ZipArchive;
+
+/// Bytes inspected when deciding whether a "text" file is actually binary
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+#[derive(Error, Debug)]
+pub enum TextExtractionError {
+	#[error("File appears to be binary")]
+	Binary,
+
+	#[error("IO error: {0}")]
+	Io(#[from] std::io::Error),
+
+	#[error("Archive error: {0}")]
+	Zip(#[from] zip::result::ZipError),
+
+	#[error("XML error: {0}")]
+	Xml(String),
+
+	#[error("PDF error: {0}")]
+	Pdf(String),
+
+	#[error("Task join error: {0}")]
+	Join(#[from] tokio::task::JoinError),
+}
+
+/// Formats the content extractor knows how to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFormat {
+	/// Plain text, Markdown, source code and config files
+	Plain,
+	Pdf,
+	Docx,
+	Odt,
+	Epub,
+}
+
+impl TextFormat {
+	/// Pick an extractor from the file extension, falling back to the content kind
+	pub fn detect(extension: Option<&str>, kind: ContentKind) -> Option<Self> {
+		let extension = extension.map(|e| e.to_ascii_lowercase());
+
+		match extension.as_deref() {
+			Some("pdf") => Some(Self::Pdf),
+			Some("docx") => Some(Self::Docx),
+			Some("odt") => Some(Self::Odt),
+			Some("epub") => Some(Self::Epub),
+			_ => match kind {
+				ContentKind::Text | ContentKind::Code | ContentKind::Config => Some(Self::Plain),
+				_ => None,
+			},
+		}
+	}
+}
+
+/// Extract text from a file, reading at most `max_bytes` of plain text input
+pub fn extract(
+	path: &Path,
+	format: TextFormat,
+	max_bytes: u64,
+) -> Result<String, TextExtractionError> {
+	match format {
+		TextFormat::Plain => extract_plain(path, max_bytes),
+		TextFormat::Pdf => extract_pdf(path),
+		TextFormat::Docx => extract_zipped_xml(path, &["word/document.xml"]),
+		TextFormat::Odt => extract_zipped_xml(path, &["content.xml"]),
+		TextFormat::Epub => extract_epub(path),
+	}
+}
+
+fn extract_plain(path: &Path, max_bytes: u64) -> Result<String, TextExtractionError> {
+	let mut bytes = Vec::new();
+	File::open(path)?.take(max_bytes).read_to_end(&mut bytes)?;
+
+	if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+		return Err(TextExtractionError::Binary);
+	}
+
+	Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn extract_pdf(path: &Path) -> Result<String, TextExtractionError> {
+	let pages =
+		sd_images::extract_pdf_text(path).map_err(|e| TextExtractionError::Pdf(e.to_string()))?;
+
+	Ok(pages.join("\n\n"))
+}
+
+/// Concatenate the text of the named XML documents inside a zip container
+fn extract_zipped_xml(path: &Path, documents: &[&str]) -> Result<String, TextExtractionError> {
+	let mut archive = ZipArchive::new(BufReader::new(File::open(path)?))?;
+
+	let mut text = String::new();
+	for name in documents {
+		let xml = read_zip_entry(&mut archive, name)?;
+		xml_to_text(&xml, &mut text)?;
+	}
+
+	Ok(text)
+}
+
+/// Read the chapters of an EPUB in spine (reading) order
+fn extract_epub(path: &Path) -> Result<String, TextExtractionError> {
+	let mut archive = ZipArchive::new(BufReader::new(File::open(path)?))?;
+
+	let chapters = match epub_spine(&mut archive)? {
+		Some(chapters) if !chapters.is_empty() => chapters,
+		// Malformed package: fall back to every XHTML document in archive order
+		_ => archive
+			.file_names()
+			.filter(|name| {
+				let name = name.to_ascii_lowercase();
+				name.ends_with(".xhtml") || name.ends_with(".html") || name.ends_with(".htm")
+			})
+			.map(str::to_string)
+			.collect(),
+	};
+
+	let mut text = String::new();
+	for chapter in chapters {
+		// Spine entries can point at missing files; skip rather than fail the whole book
+		let Ok(xml) = read_zip_entry(&mut archive, &chapter) else {
+			continue;
+		};
+		xml_to_text(&xml, &mut text)?;
+		text.push('\n');
+	}
+
+	Ok(text)
+}
+
+/// Resolve the chapter paths listed in the EPUB package spine
+fn epub_spine<R: Read + std::io::Seek>(
+	archive: &mut ZipArchive<R>,
+) -> Result<Option<Vec<String>>, TextExtractionError> {
+	let Ok(container) = read_zip_entry(archive, "META-INF/container.xml") else {
+		return Ok(None);
+	};
+
+	let Some(package_path) = find_attribute(&container, b"rootfile", b"full-path")?
+		.into_iter()
+		.next()
+	else {
+		return Ok(None);
+	};
+
+	let package = read_zip_entry(archive, &package_path)?;
+	let base = Path::new(&package_path)
+		.parent()
+		.map(Path::to_path_buf)
+		.unwrap_or_default();
+
+	let mut manifest = std::collections::HashMap::new();
+	let mut spine = Vec::new();
+
+	let mut reader = Reader::from_reader(package.as_slice());
+	loop {
+		match reader.read_event() {
+			Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+				b"item" => {
+					if let (Some(id), Some(href)) = (attribute(&e, b"id"), attribute(&e, b"href")) {
+						manifest.insert(id, href);
+					}
+				}
+				b"itemref" => {
+					if let Some(idref) = attribute(&e, b"idref") {
+						spine.push(idref);
+					}
+				}
+				_ => {}
+			},
+			Ok(Event::Eof) => break,
+			Ok(_) => {}
+			Err(e) => return Err(TextExtractionError::Xml(e.to_string())),
+		}
+	}
+
+	Ok(Some(
+		spine
+			.iter()
+			.filter_map(|id| manifest.get(id))
+			.map(|href| zip_path(&base.join(href)))
+			.collect(),
+	))
+}
+
+fn read_zip_entry<R: Read + std::io::Seek>(
+	archive: &mut ZipArchive<R>,
+	name: &str,
+) -> Result<Vec<u8>, TextExtractionError> {
+	let mut entry = archive.by_name(name)?;
+	let mut bytes = Vec::with_capacity(entry.size() as usize);
+	entry.read_to_end(&mut bytes)?;
+	Ok(bytes)
+}
+
+/// Zip entry names always use forward slashes
+fn zip_path(path: &Path) -> String {
+	path.components()
+		.map(|c| c.as_os_str().to_string_lossy())
+		.collect::<Vec<_>>()
+		.join("/")
+}
+
+fn attribute(element: &quick_xml::events::BytesStart<'_>, name: &[u8]) -> Option<String> {
+	element
+		.attributes()
+		.flatten()
+		.find(|a| a.key.local_name().as_ref() == name)
+		.and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+fn find_attribute(
+	xml: &[u8],
+	element: &[u8],
+	name: &[u8],
+) -> Result<Vec<String>, TextExtractionError> {
+	let mut values = Vec::new();
+	let mut reader = Reader::from_reader(xml);
+
+	loop {
+		match reader.read_event() {
+			Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == element => {
+				values.extend(attribute(&e, name));
+			}
+			Ok(Event::Eof) => break,
+			Ok(_) => {}
+			Err(e) => return Err(TextExtractionError::Xml(e.to_string())),
+		}
+	}
+
+	Ok(values)
+}
+
+/// Append the character data of an XML document to `out`
+///
+/// Block-level elements (paragraphs, headings, list items, table rows) end with a
+/// newline so that words from neighbouring paragraphs are never glued together.
+/// Element names are matched without namespace prefixes, which covers WordprocessingML
+/// (`w:p`, `w:tab`), OpenDocument (`text:p`, `text:s`) and XHTML alike.
+pub fn xml_to_text(xml: &[u8], out: &mut String) -> Result<(), TextExtractionError> {
+	let mut reader = Reader::from_reader(xml);
+	// Depth inside elements whose text is not document content
+	let mut skip_depth = 0usize;
+
+	loop {
+		let event = reader
+			.read_event()
+			.map_err(|e| TextExtractionError::Xml(e.to_string()))?;
+
+		match event {
+			Event::Start(e) => {
+				if skip_depth > 0 || is_skipped(e.local_name().as_ref()) {
+					skip_depth += 1;
+				}
+			}
+			Event::End(e) => {
+				if skip_depth > 0 {
+					skip_depth -= 1;
+				} else if is_block(e.local_name().as_ref()) {
+					out.push('\n');
+				}
+			}
+			Event::Empty(e) if skip_depth == 0 => match e.local_name().as_ref() {
+				b"tab" => out.push('\t'),
+				b"s" => out.push(' '),
+				b"br" | b"cr" | b"line-break" => out.push('\n'),
+				_ => {}
+			},
+			Event::Text(e) if skip_depth == 0 => {
+				let text = e
+					.xml_content()
+					.map_err(|e| TextExtractionError::Xml(e.to_string()))?;
+				out.push_str(&text);
+			}
+			Event::CData(e) if skip_depth == 0 => {
+				out.push_str(&String::from_utf8_lossy(&e));
+			}
+			Event::GeneralRef(e) if skip_depth == 0 => {
+				if let Ok(Some(ch)) = e.resolve_char_ref() {
+					out.push(ch);
+				} else if let Ok(name) = e.decode() {
+					// Unknown HTML entities (&nbsp; and friends) still separate words
+					out.push_str(resolve_predefined_entity(&name).unwrap_or(" "));
+				}
+			}
+			Event::Eof => break,
+			_ => {}
+		}
+	}
+
+	Ok(())
+}
+
+fn is_block(name: &[u8]) -> bool {
+	matches!(
+		name,
+		b"p" | b"h"
+			| b"h1" | b"h2"
+			| b"h3" | b"h4"
+			| b"h5" | b"h6"
+			| b"li" | b"tr"
+			| b"div" | b"title"
+			| b"blockquote"
+			| b"pre"
+	)
+}
+
+fn is_skipped(name: &[u8]) -> bool {
+	matches!(name, b"head" | b"script" | b"style" | b"instrText")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::Write;
+	use zip::write::SimpleFileOptions;
+
+	fn write_zip(path: &Path, files: &[(&str, &str)]) {
+		let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+		for (name, body) in files {
+			zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+			zip.write_all(body.as_bytes()).unwrap();
+		}
+		zip.finish().unwrap();
+	}
+
+	#[test]
+	fn test_detect_format() {
+		assert_eq!(
+			TextFormat::detect(Some("PDF"), ContentKind::Document),
+			Some(TextFormat::Pdf)
+		);
+		assert_eq!(
+			TextFormat::detect(Some("md"), ContentKind::Text),
+			Some(TextFormat::Plain)
+		);
+		assert_eq!(TextFormat::detect(Some("jpg"), ContentKind::Image), None);
+	}
+
+	#[test]
+	fn test_xml_to_text_keeps_paragraph_breaks() {
+		let xml = br#"<w:document xmlns:w="w"><w:body>
+			<w:p><w:r><w:t>Quarterly</w:t></w:r><w:r><w:tab/><w:t>report</w:t></w:r></w:p>
+			<w:p><w:r><w:instrText>PAGE</w:instrText><w:t>Tom &amp; Jerry</w:t></w:r></w:p>
+		</w:body></w:document>"#;
+
+		let mut text = String::new();
+		xml_to_text(xml, &mut text).unwrap();
+
+		assert!(text.contains("Quarterly\treport\n"));
+		assert!(text.contains("Tom & Jerry\n"));
+		assert!(!text.contains("PAGE"));
+	}
+
+	#[test]
+	fn test_plain_text_rejects_binary() {
+		let dir = tempfile::tempdir().unwrap();
+		let text = dir.path().join("notes.md");
+		let binary = dir.path().join("blob.txt");
+		std::fs::write(&text, "# Heading\nbody").unwrap();
+		std::fs::write(&binary, [0x7f, b'E', b'L', b'F', 0, 0, 1]).unwrap();
+
+		assert_eq!(
+			extract(&text, TextFormat::Plain, 1024).unwrap(),
+			"# Heading\nbody"
+		);
+		assert!(matches!(
+			extract(&binary, TextFormat::Plain, 1024),
+			Err(TextExtractionError::Binary)
+		));
+	}
+
+	#[test]
+	fn test_epub_follows_spine_order() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("book.epub");
+		write_zip(
+			&path,
+			&[
+				(
+					"META-INF/container.xml",
+					r#"<container><rootfiles><rootfile full-path="OEBPS/content.opf"/></rootfiles></container>"#,
+				),
+				(
+					"OEBPS/content.opf",
+					r#"<package><manifest>
+						<item id="a" href="text/one.xhtml"/>
+						<item id="b" href="text/two.xhtml"/>
+					</manifest><spine><itemref idref="b"/><itemref idref="a"/></spine></package>"#,
+				),
+				(
+					"OEBPS/text/one.xhtml",
+					"<html><head><title>skip</title></head><body><p>First chapter</p></body></html>",
+				),
+				(
+					"OEBPS/text/two.xhtml",
+					"<html><body><p>Second chapter</p></body></html>",
+				),
+			],
+		);
+
+		let text = extract(&path, TextFormat::Epub, 1024).unwrap();
+		let second = text.find("Second chapter").unwrap();
+		let first = text.find("First chapter").unwrap();
+
+		assert!(second < first);
+		assert!(!text.contains("skip"));
+	}
+}
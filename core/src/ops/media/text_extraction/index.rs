@@ -0,0 +1,53 @@
+//! Reads and writes for the `content_search_index` FTS5 table
+
+use sea_orm::{ConnectionTrait, DbErr, Statement};
+
+/// Whether text for this content identity has been indexed at its current hash
+pub async fn is_indexed(
+	db: &impl ConnectionTrait,
+	content_id: i32,
+	content_hash: &str,
+) -> Result<bool, DbErr> {
+	let row = db
+		.query_one(Statement::from_sql_and_values(
+			db.get_database_backend(),
+			"SELECT 1 FROM content_search_index WHERE rowid = ? AND content_hash = ?",
+			[content_id.into(), content_hash.into()],
+		))
+		.await?;
+
+	Ok(row.is_some())
+}
+
+/// Replace the indexed text of a content identity
+///
+/// An empty body is stored too, so files without any text are not retried on every run.
+pub async fn store(
+	db: &impl ConnectionTrait,
+	content_id: i32,
+	content_hash: &str,
+	body: &str,
+) -> Result<(), DbErr> {
+	remove(db, content_id).await?;
+
+	db.execute(Statement::from_sql_and_values(
+		db.get_database_backend(),
+		"INSERT INTO content_search_index(rowid, content_hash, body) VALUES (?, ?, ?)",
+		[content_id.into(), content_hash.into(), body.into()],
+	))
+	.await?;
+
+	Ok(())
+}
+
+/// Remove the indexed text of a content identity
+pub async fn remove(db: &impl ConnectionTrait, content_id: i32) -> Result<(), DbErr> {
+	db.execute(Statement::from_sql_and_values(
+		db.get_database_backend(),
+		"DELETE FROM content_search_index WHERE rowid = ?",
+		[content_id.into()],
+	))
+	.await?;
+
+	Ok(())
+}
@@ -0,0 +1,316 @@
+//! Content extraction job for batch full-text indexing
+
+use super::{processor::TextExtractionProcessor, TextFormat, DEFAULT_MAX_FILE_SIZE};
+use crate::{
+	domain::{location::JobPolicies, ContentKind},
+	infra::{
+		db::entities::{entry, location},
+		job::{prelude::*, traits::DynJob},
+	},
+	ops::indexing::{processor::ProcessorEntry, state::EntryKind, PathResolver},
+};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Statement};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TextExtractionJobConfig {
+	/// Location ID to process (None = every location whose policy enables text extraction)
+	pub location_id: Option<Uuid>,
+	/// Single entry UUID to process (for UI-triggered single file)
+	pub entry_uuid: Option<Uuid>,
+	/// Skip PDF, office and EPUB files larger than this (plain text is truncated instead)
+	pub max_file_size: u64,
+	/// Re-extract content that is already indexed at its current hash
+	pub reprocess: bool,
+}
+
+impl Default for TextExtractionJobConfig {
+	fn default() -> Self {
+		Self {
+			location_id: None,
+			entry_uuid: None,
+			max_file_size: DEFAULT_MAX_FILE_SIZE,
+			reprocess: false,
+		}
+	}
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TextExtractionJobState {
+	phase: TextExtractionPhase,
+	entries: Vec<(i32, std::path::PathBuf)>, // (entry_id, path)
+	processed: usize,
+	success_count: usize,
+	error_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum TextExtractionPhase {
+	Discovery,
+	Processing,
+	Complete,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TextExtractionJob {
+	config: TextExtractionJobConfig,
+	state: TextExtractionJobState,
+}
+
+impl TextExtractionJob {
+	pub fn new(config: TextExtractionJobConfig) -> Self {
+		Self {
+			config,
+			state: TextExtractionJobState {
+				phase: TextExtractionPhase::Discovery,
+				entries: Vec::new(),
+				processed: 0,
+				success_count: 0,
+				error_count: 0,
+			},
+		}
+	}
+
+	pub fn from_location(location_id: Uuid) -> Self {
+		Self::new(TextExtractionJobConfig {
+			location_id: Some(location_id),
+			..Default::default()
+		})
+	}
+}
+
+impl Job for TextExtractionJob {
+	const NAME: &'static str = "text_extraction";
+	const RESUMABLE: bool = true;
+	const DESCRIPTION: Option<&'static str> =
+		Some("Extract text from documents for full-text content search");
+}
+
+#[async_trait::async_trait]
+impl JobHandler for TextExtractionJob {
+	type Output = TextExtractionJobOutput;
+
+	async fn run(&mut self, ctx: JobContext<'_>) -> JobResult<Self::Output> {
+		match self.state.phase {
+			TextExtractionPhase::Discovery => {
+				self.run_discovery(&ctx).await?;
+				self.state.phase = TextExtractionPhase::Processing;
+				ctx.checkpoint().await?;
+			}
+			TextExtractionPhase::Processing => {}
+			TextExtractionPhase::Complete => return Ok(self.output()),
+		}
+
+		let processor = TextExtractionProcessor::new()
+			.with_max_file_size(self.config.max_file_size)
+			.with_reprocess(self.config.reprocess);
+
+		let total = self.state.entries.len();
+
+		while self.state.processed < total {
+			ctx.check_interrupt().await?;
+
+			let (entry_id, path) = &self.state.entries[self.state.processed];
+
+			// The entry may have been removed since discovery
+			let Some(entry_model) = entry::Entity::find_by_id(*entry_id)
+				.one(ctx.library_db())
+				.await?
+			else {
+				self.state.processed += 1;
+				continue;
+			};
+
+			let proc_entry = ProcessorEntry {
+				id: *entry_id,
+				uuid: entry_model.uuid,
+				path: path.clone(),
+				kind: EntryKind::File,
+				size: entry_model.size as u64,
+				content_id: entry_model.content_id,
+				mime_type: None,
+			};
+
+			if processor.should_process(&proc_entry) {
+				match processor.process(ctx.library_db(), &proc_entry).await {
+					Ok(result) if result.artifacts_created > 0 => self.state.success_count += 1,
+					Ok(_) => {}
+					Err(e) => {
+						ctx.add_non_critical_error(format!(
+							"Text extraction failed for {}: {}",
+							path.display(),
+							e
+						));
+						self.state.error_count += 1;
+					}
+				}
+			}
+
+			self.state.processed += 1;
+
+			ctx.progress(Progress::Count {
+				current: self.state.processed,
+				total,
+			});
+
+			if self.state.processed % 25 == 0 {
+				ctx.checkpoint().await?;
+			}
+		}
+
+		self.state.phase = TextExtractionPhase::Complete;
+		ctx.log(format!(
+			"Text extraction complete: {} indexed, {} errors",
+			self.state.success_count, self.state.error_count
+		));
+
+		Ok(self.output())
+	}
+}
+
+impl TextExtractionJob {
+	fn output(&self) -> TextExtractionJobOutput {
+		TextExtractionJobOutput {
+			total_processed: self.state.processed,
+			success_count: self.state.success_count,
+			error_count: self.state.error_count,
+		}
+	}
+
+	async fn run_discovery(&mut self, ctx: &JobContext<'_>) -> JobResult<()> {
+		let db = ctx.library_db();
+
+		// Single file mode (from UI action)
+		if let Some(entry_uuid) = self.config.entry_uuid {
+			let entry_model = entry::Entity::find()
+				.filter(entry::Column::Uuid.eq(entry_uuid))
+				.one(db)
+				.await?
+				.ok_or_else(|| JobError::execution("Entry not found"))?;
+
+			let path = PathResolver::get_full_path(db, entry_model.id).await?;
+			self.state.entries.push((entry_model.id, path));
+			return Ok(());
+		}
+
+		let root_entry_ids = self.location_roots(db).await?;
+		ctx.log(format!(
+			"Discovering text extraction candidates in {} location(s)",
+			root_entry_ids.len()
+		));
+
+		// Content identities are shared across entries, so each one only needs extracting once
+		let mut seen_content = HashSet::new();
+
+		for root_entry_id in root_entry_ids {
+			let rows = db
+				.query_all(Statement::from_sql_and_values(
+					db.get_database_backend(),
+					r#"
+						SELECT e.id AS entry_id, e.extension AS extension,
+							ci.id AS content_id, ci.kind_id AS kind_id
+						FROM entry_closure ec
+						JOIN entries e ON e.id = ec.descendant_id
+						JOIN content_identities ci ON ci.id = e.content_id
+						WHERE ec.ancestor_id = ?
+						AND e.kind = 0
+						AND (? OR NOT EXISTS (
+							SELECT 1 FROM content_search_index csi
+							WHERE csi.rowid = ci.id AND csi.content_hash = ci.content_hash
+						))
+					"#,
+					[root_entry_id.into(), self.config.reprocess.into()],
+				))
+				.await?;
+
+			for row in rows {
+				let entry_id: i32 = row.try_get("", "entry_id")?;
+				let content_id: i32 = row.try_get("", "content_id")?;
+				let extension: Option<String> = row.try_get("", "extension")?;
+				let kind_id: i32 = row.try_get("", "kind_id")?;
+
+				let kind = ContentKind::try_from(kind_id).unwrap_or(ContentKind::Unknown);
+				if TextFormat::detect(extension.as_deref(), kind).is_none()
+					|| !seen_content.insert(content_id)
+				{
+					continue;
+				}
+
+				match PathResolver::get_full_path(db, entry_id).await {
+					Ok(path) => self.state.entries.push((entry_id, path)),
+					Err(e) => ctx.add_non_critical_error(format!(
+						"Failed to resolve path for entry {}: {}",
+						entry_id, e
+					)),
+				}
+			}
+		}
+
+		ctx.log(format!(
+			"Discovery complete: {} entries need text extraction",
+			self.state.entries.len()
+		));
+
+		Ok(())
+	}
+
+	/// Root entries of the locations to scan
+	///
+	/// An explicit location is always scanned (policy checks happen where the job is
+	/// dispatched); otherwise only locations whose policy enables text extraction are.
+	async fn location_roots(&self, db: &sea_orm::DatabaseConnection) -> JobResult<Vec<i32>> {
+		let mut query = location::Entity::find();
+		if let Some(location_id) = self.config.location_id {
+			query = query.filter(location::Column::Uuid.eq(location_id));
+		}
+
+		let locations = query.all(db).await?;
+
+		Ok(locations
+			.into_iter()
+			.filter(|loc| {
+				self.config.location_id.is_some()
+					|| loc
+						.job_policies
+						.as_deref()
+						.and_then(|json| serde_json::from_str::<JobPolicies>(json).ok())
+						.unwrap_or_default()
+						.text_extraction
+						.enabled
+			})
+			.filter_map(|loc| loc.entry_id)
+			.collect())
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TextExtractionJobOutput {
+	pub total_processed: usize,
+	pub success_count: usize,
+	pub error_count: usize,
+}
+
+impl From<TextExtractionJobOutput> for JobOutput {
+	fn from(output: TextExtractionJobOutput) -> Self {
+		JobOutput::TextExtraction {
+			total_processed: output.total_processed,
+			success_count: output.success_count,
+			error_count: output.error_count,
+		}
+	}
+}
+
+impl DynJob for TextExtractionJob {
+	fn job_name(&self) -> &'static str {
+		"Content Text Extraction"
+	}
+}
+
+impl From<TextExtractionJob> for Box<dyn DynJob> {
+	fn from(job: TextExtractionJob) -> Self {
+		Box::new(job)
+	}
+}
@@ -0,0 +1,53 @@
+//! Content text extraction for full-text search
+//!
+//! Pulls searchable text out of file bodies (plain text, Markdown, source code,
+//! PDF, DOCX/ODT and EPUB) and stores it in the `content_search_index` FTS5 table,
+//! keyed by content identity. `SearchMode::Full` queries that table to match files
+//! by what they contain rather than what they are called.
+
+pub mod extract;
+pub mod index;
+pub mod job;
+pub mod processor;
+
+pub use extract::{TextExtractionError, TextFormat};
+pub use job::{TextExtractionJob, TextExtractionJobConfig};
+pub use processor::TextExtractionProcessor;
+
+use std::path::Path;
+use tokio::task::spawn_blocking;
+
+/// Default size limit for files handed to an extractor
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 50 * 1024 * 1024;
+
+/// Upper bound on the text stored per content identity
+///
+/// Beyond this the FTS index grows faster than search quality improves.
+pub const MAX_INDEXED_TEXT_BYTES: usize = 2 * 1024 * 1024;
+
+/// Extract text from a file with the extractor for `format`
+pub async fn extract_text_from_file(
+	path: impl AsRef<Path>,
+	format: TextFormat,
+	max_file_size: u64,
+) -> Result<String, TextExtractionError> {
+	let path = path.as_ref().to_path_buf();
+
+	// Parsing archives and PDFs is CPU bound
+	let mut text = spawn_blocking(move || extract::extract(&path, format, max_file_size)).await??;
+
+	truncate_to_char_boundary(&mut text, MAX_INDEXED_TEXT_BYTES);
+	Ok(text)
+}
+
+fn truncate_to_char_boundary(text: &mut String, max_bytes: usize) {
+	if text.len() <= max_bytes {
+		return;
+	}
+
+	let mut end = max_bytes;
+	while !text.is_char_boundary(end) {
+		end -= 1;
+	}
+	text.truncate(end);
+}
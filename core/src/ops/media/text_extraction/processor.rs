@@ -0,0 +1,116 @@
+//! Text extraction processor - atomic operation for indexing file contents
+
+use super::{extract::TextExtractionError, index, TextFormat, DEFAULT_MAX_FILE_SIZE};
+use crate::domain::ContentKind;
+use crate::infra::db::entities::content_identity;
+use crate::ops::indexing::processor::{ProcessorEntry, ProcessorResult};
+use crate::ops::indexing::state::EntryKind;
+use anyhow::Result;
+use sea_orm::EntityTrait;
+use serde_json::Value;
+use tracing::debug;
+
+pub struct TextExtractionProcessor {
+	max_file_size: u64,
+	reprocess: bool,
+}
+
+impl Default for TextExtractionProcessor {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl TextExtractionProcessor {
+	pub fn new() -> Self {
+		Self {
+			max_file_size: DEFAULT_MAX_FILE_SIZE,
+			reprocess: false,
+		}
+	}
+
+	pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+		self.max_file_size = max_file_size;
+		self
+	}
+
+	pub fn with_reprocess(mut self, reprocess: bool) -> Self {
+		self.reprocess = reprocess;
+		self
+	}
+
+	pub fn with_settings(mut self, settings: &Value) -> Result<Self> {
+		if let Some(max) = settings.get("max_file_size").and_then(|v| v.as_u64()) {
+			self.max_file_size = max;
+		}
+
+		if let Some(reprocess) = settings.get("reprocess").and_then(|v| v.as_bool()) {
+			self.reprocess = reprocess;
+		}
+
+		Ok(self)
+	}
+
+	pub fn should_process(&self, entry: &ProcessorEntry) -> bool {
+		matches!(entry.kind, EntryKind::File) && entry.content_id.is_some()
+	}
+
+	pub async fn process(
+		&self,
+		db: &sea_orm::DatabaseConnection,
+		entry: &ProcessorEntry,
+	) -> Result<ProcessorResult> {
+		let content_id = entry
+			.content_id
+			.ok_or_else(|| anyhow::anyhow!("Entry has no content_id"))?;
+
+		let ci = content_identity::Entity::find_by_id(content_id)
+			.one(db)
+			.await?
+			.ok_or_else(|| anyhow::anyhow!("ContentIdentity not found"))?;
+
+		let kind = ContentKind::try_from(ci.kind_id).unwrap_or(ContentKind::Unknown);
+		let extension = entry.path.extension().and_then(|e| e.to_str());
+		let Some(format) = TextFormat::detect(extension, kind) else {
+			return Ok(ProcessorResult::success(0, 0));
+		};
+
+		// Plain text is read up to the limit, structured formats must be parsed whole
+		if format != TextFormat::Plain && entry.size > self.max_file_size {
+			debug!(
+				"Skipping text extraction for {} ({} bytes exceeds limit)",
+				entry.path.display(),
+				entry.size
+			);
+			return Ok(ProcessorResult::success(0, 0));
+		}
+
+		if !self.reprocess && index::is_indexed(db, content_id, &ci.content_hash).await? {
+			return Ok(ProcessorResult::success(0, 0));
+		}
+
+		debug!(
+			"→ Extracting text ({:?}) from: {}",
+			format,
+			entry.path.display()
+		);
+
+		let text =
+			match super::extract_text_from_file(&entry.path, format, self.max_file_size).await {
+				Ok(text) => text,
+				// Record binary files as empty so they aren't retried on every run
+				Err(TextExtractionError::Binary) => String::new(),
+				Err(e) => return Err(e.into()),
+			};
+
+		index::store(db, content_id, &ci.content_hash, &text).await?;
+
+		debug!("✓ Indexed {} bytes of text", text.len());
+
+		Ok(ProcessorResult::success(1, text.len() as u64))
+	}
+
+	pub fn name(&self) -> &'static str {
+		"text_extraction"
+	}
+}
@@ -1,10 +1,24 @@
 //! Thumbnail generation engine using existing Spacedrive crates
 
 use super::error::{ThumbnailError, ThumbnailResult};
-use sd_media_metadata::exif::Orientation;
+use super::utils::ThumbnailUtils;
+use crate::library::Library;
+use sd_images::ConvertibleExtension;
+use sd_media_metadata::exif::{ExifReader, Orientation};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
+#[cfg(feature = "wasm")]
+use crate::infra::extension::{
+	MediaHandlerKind, MediaHandlerRegistration, PluginError, PluginManager,
+};
+#[cfg(feature = "wasm")]
+use std::sync::Arc;
+#[cfg(feature = "wasm")]
+use tokio::sync::{Mutex, RwLock};
+#[cfg(feature = "wasm")]
+use uuid::Uuid;
+
 /// Information about a generated thumbnail
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThumbnailInfo {
@@ -20,6 +34,10 @@ pub enum ThumbnailGenerator {
 	Image(ImageGenerator),
 	Video(VideoGenerator),
 	Document(DocumentGenerator),
+	#[cfg(feature = "wasm")]
+	Extension(ExtensionGenerator),
+	/// Tries the first generator, then the second if it fails
+	Fallback(Box<ThumbnailGenerator>, Box<ThumbnailGenerator>),
 }
 
 impl ThumbnailGenerator {
@@ -44,6 +62,40 @@ impl ThumbnailGenerator {
 		}
 	}
 
+	/// Create a generator for a file
+	///
+	/// A thumbnailer an extension registered for the file's MIME type or extension is
+	/// preferred over the built-in generators, which it falls back to if it fails. Images
+	/// are the exception: the built-in image generator goes first, and the extension only
+	/// renders the ones it can't decode.
+	pub async fn for_file(
+		library: &Library,
+		mime_type: Option<&str>,
+		extension: Option<&str>,
+	) -> ThumbnailResult<Self> {
+		let built_in = mime_type
+			.filter(|mime_type| ThumbnailUtils::is_thumbnail_supported(mime_type))
+			.and_then(|mime_type| Self::for_mime_type(mime_type).ok());
+
+		#[cfg(feature = "wasm")]
+		if let Some(generator) = ExtensionGenerator::for_file(library, mime_type, extension).await {
+			let generator = Self::Extension(generator);
+			return Ok(match built_in {
+				Some(image @ Self::Image(_)) => {
+					Self::Fallback(Box::new(image), Box::new(generator))
+				}
+				Some(built_in) => Self::Fallback(Box::new(generator), Box::new(built_in)),
+				None => generator,
+			});
+		}
+		#[cfg(not(feature = "wasm"))]
+		let _ = library;
+
+		built_in.ok_or_else(|| {
+			ThumbnailError::unsupported_format(mime_type.or(extension).unwrap_or("unknown"))
+		})
+	}
+
 	/// Generate thumbnail
 	pub async fn generate(
 		&self,
@@ -51,11 +103,47 @@ impl ThumbnailGenerator {
 		output_path: &Path,
 		size: u32,
 		quality: u8,
+	) -> ThumbnailResult<ThumbnailInfo> {
+		let (first, fallback) = match self {
+			Self::Fallback(first, second) => (first.as_ref(), Some(second.as_ref())),
+			generator => (generator, None),
+		};
+
+		match (
+			first
+				.generate_alone(source_path, output_path, size, quality)
+				.await,
+			fallback,
+		) {
+			(Err(e), Some(fallback)) => {
+				tracing::debug!(
+					"Thumbnail generation failed for {}, trying the fallback generator: {}",
+					source_path.display(),
+					e
+				);
+				fallback
+					.generate_alone(source_path, output_path, size, quality)
+					.await
+			}
+			(result, _) => result,
+		}
+	}
+
+	/// Generate with this generator only, without falling back
+	async fn generate_alone(
+		&self,
+		source_path: &Path,
+		output_path: &Path,
+		size: u32,
+		quality: u8,
 	) -> ThumbnailResult<ThumbnailInfo> {
 		match self {
 			Self::Image(gen) => gen.generate(source_path, output_path, size, quality).await,
 			Self::Video(gen) => gen.generate(source_path, output_path, size, quality).await,
 			Self::Document(gen) => gen.generate(source_path, output_path, size, quality).await,
+			#[cfg(feature = "wasm")]
+			Self::Extension(gen) => gen.generate(source_path, output_path, size, quality).await,
+			Self::Fallback(..) => Err(ThumbnailError::other("Fallback generators can't be nested")),
 		}
 	}
 }
@@ -90,62 +178,16 @@ impl ImageGenerator {
 		let output_path = output_path.to_path_buf();
 
 		let thumbnail_info = tokio::task::spawn_blocking(move || {
-			// Use sd-images to load and process the image
-			let mut img = sd_images::format_image(&source_path)
+			// Use sd-images to load and process the image, with its EXIF orientation
+			let (mut img, orientation) = load_oriented_image(&source_path)
 				.map_err(|e| ThumbnailError::other(format!("Failed to load image: {}", e)))?;
 
 			// Apply EXIF orientation correction if available
-			if let Some(orientation) = Orientation::from_path(&source_path) {
+			if let Some(orientation) = orientation {
 				img = orientation.correct_thumbnail(img);
 			}
 
-			// Blurhash generation disabled for performance
-			let blurhash: Option<String> = None;
-
-			// Calculate target dimensions maintaining aspect ratio
-			let (original_width, original_height) = (img.width(), img.height());
-			let (target_width, target_height) =
-				calculate_dimensions(original_width, original_height, size);
-
-			// Resize using high-quality algorithm
-			let thumbnail = img.resize(
-				target_width,
-				target_height,
-				image::imageops::FilterType::Lanczos3,
-			);
-
-			// Convert to RGB8 for consistency
-			let rgb_thumbnail = thumbnail.to_rgb8();
-
-			// Get actual dimensions from the resized image (may differ from calculated due to rounding)
-			let actual_width = rgb_thumbnail.width();
-			let actual_height = rgb_thumbnail.height();
-
-			// Verify buffer size matches expected dimensions
-			let expected_size = (actual_width * actual_height * 3) as usize;
-			let actual_size = rgb_thumbnail.as_raw().len();
-
-			if expected_size != actual_size {
-				return Err(ThumbnailError::other(format!(
-					"Image buffer size mismatch: expected {} bytes for {}x{}, got {} bytes",
-					expected_size, actual_width, actual_height, actual_size
-				)));
-			}
-
-			// Encode as WebP using actual dimensions
-			let webp_encoder = webp::Encoder::from_rgb(&rgb_thumbnail, actual_width, actual_height);
-			let webp_memory = webp_encoder.encode(quality as f32);
-			let webp_data = webp_memory.to_vec();
-
-			// Write to file
-			std::fs::write(&output_path, &webp_data)?;
-
-			Ok::<ThumbnailInfo, ThumbnailError>(ThumbnailInfo {
-				size_bytes: webp_data.len(),
-				dimensions: (actual_width, actual_height),
-				format: "webp".to_string(),
-				blurhash,
-			})
+			write_webp_thumbnail(img, &output_path, size, quality)
 		})
 		.await
 		.map_err(|e| ThumbnailError::other(format!("Task join error: {}", e)))??;
@@ -154,6 +196,26 @@ impl ImageGenerator {
 	}
 }
 
+/// An image and its EXIF orientation
+///
+/// Camera RAW files are read once for both, with the EXIF data sd-images finds in
+/// containers the EXIF reader can't open itself (RAF, CR3, ORF).
+fn load_oriented_image(
+	path: &Path,
+) -> sd_images::Result<(sd_images::DynamicImage, Option<Orientation>)> {
+	if !ConvertibleExtension::try_from(path).is_ok_and(ConvertibleExtension::is_raw) {
+		return Ok((sd_images::format_image(path)?, Orientation::from_path(path)));
+	}
+
+	let raw = sd_images::RawFile::open(path)?;
+	let orientation = raw
+		.metadata()
+		.ok()
+		.and_then(|metadata| metadata.exif)
+		.and_then(|exif| Orientation::from_reader(&ExifReader::from_slice(&exif).ok()?));
+	Ok((raw.decode()?, orientation))
+}
+
 /// Video thumbnail generator using sd-ffmpeg crate
 #[derive(Debug)]
 pub struct VideoGenerator;
@@ -254,48 +316,194 @@ impl DocumentGenerator {
 				img = orientation.correct_thumbnail(img);
 			}
 
-			// Blurhash generation disabled for performance
-			let blurhash: Option<String> = None;
+			write_webp_thumbnail(img, &output_path, size, quality)
+		})
+		.await
+		.map_err(|e| ThumbnailError::other(format!("Task join error: {}", e)))??;
+
+		Ok(thumbnail_info)
+	}
+}
+
+/// Thumbnail generator backed by a thumbnailer a WASM extension registered
+///
+/// The extension renders the file into an image once, and each variant is resized from
+/// that render. A variant larger than the render asks the extension for a bigger one.
+#[cfg(feature = "wasm")]
+pub struct ExtensionGenerator {
+	handler: MediaHandlerRegistration,
+	plugin_manager: Arc<RwLock<PluginManager>>,
+	library_id: Uuid,
+	mime_type: Option<String>,
+	extension: Option<String>,
+	/// Last render and the size it was requested at
+	rendered: Mutex<Option<(u32, Arc<[u8]>)>>,
+}
 
-			// Calculate target dimensions maintaining aspect ratio
-			let (original_width, original_height) = (img.width(), img.height());
-			let (target_width, target_height) =
-				calculate_dimensions(original_width, original_height, size);
+#[cfg(feature = "wasm")]
+impl std::fmt::Debug for ExtensionGenerator {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("ExtensionGenerator")
+			.field("extension_id", &self.handler.extension_id)
+			.field("export_fn", &self.handler.export_fn)
+			.finish_non_exhaustive()
+	}
+}
 
-			// Resize using high-quality algorithm
-			let thumbnail = img.resize(
-				target_width,
-				target_height,
-				image::imageops::FilterType::Lanczos3,
-			);
+#[cfg(feature = "wasm")]
+impl ExtensionGenerator {
+	/// Generator for the thumbnailer best matching a file, if an extension registered one
+	pub async fn for_file(
+		library: &Library,
+		mime_type: Option<&str>,
+		extension: Option<&str>,
+	) -> Option<Self> {
+		let plugin_manager = library.core_context().get_plugin_manager().await?;
+		let handler = plugin_manager.read().await.media_handlers().find(
+			MediaHandlerKind::Thumbnailer,
+			mime_type,
+			extension,
+		)?;
+
+		Some(Self {
+			handler,
+			plugin_manager,
+			library_id: library.id(),
+			mime_type: mime_type.map(str::to_string),
+			extension: extension.map(str::to_string),
+			rendered: Mutex::new(None),
+		})
+	}
 
-			// Convert to RGB8 for WebP encoding
-			let rgb_thumbnail = thumbnail.to_rgb8();
+	pub async fn generate(
+		&self,
+		source_path: &Path,
+		output_path: &Path,
+		size: u32,
+		quality: u8,
+	) -> ThumbnailResult<ThumbnailInfo> {
+		if quality > 100 {
+			return Err(ThumbnailError::InvalidQuality(quality));
+		}
 
-			// Get actual dimensions from the resized image
-			let actual_width = rgb_thumbnail.width();
-			let actual_height = rgb_thumbnail.height();
+		// Ensure output directory exists
+		if let Some(parent) = output_path.parent() {
+			tokio::fs::create_dir_all(parent).await?;
+		}
 
-			// Encode as WebP using actual dimensions
-			let webp_encoder = webp::Encoder::from_rgb(&rgb_thumbnail, actual_width, actual_height);
-			let webp_memory = webp_encoder.encode(quality as f32);
-			let webp_data = webp_memory.to_vec();
+		let rendered = self.render(source_path, size).await?;
+		let output_path = output_path.to_path_buf();
 
-			// Write to file
-			std::fs::write(&output_path, &webp_data)?;
+		tokio::task::spawn_blocking(move || {
+			let img = image::load_from_memory(&rendered).map_err(|e| {
+				ThumbnailError::other(format!("Extension returned an unreadable image: {}", e))
+			})?;
 
-			Ok::<ThumbnailInfo, ThumbnailError>(ThumbnailInfo {
-				size_bytes: webp_data.len(),
-				dimensions: (actual_width, actual_height),
-				format: "webp".to_string(),
-				blurhash,
-			})
+			write_webp_thumbnail(img, &output_path, size, quality)
 		})
 		.await
-		.map_err(|e| ThumbnailError::other(format!("Task join error: {}", e)))??;
+		.map_err(|e| ThumbnailError::other(format!("Task join error: {}", e)))?
+	}
 
-		Ok(thumbnail_info)
+	/// The extension's render of the file, made at `size` or larger
+	async fn render(&self, source_path: &Path, size: u32) -> ThumbnailResult<Arc<[u8]>> {
+		let mut rendered = self.rendered.lock().await;
+		if let Some((rendered_size, image)) = rendered.as_ref() {
+			if *rendered_size >= size {
+				return Ok(image.clone());
+			}
+		}
+
+		let request = serde_json::json!({
+			"mime_type": self.mime_type,
+			"extension": self.extension,
+			"file_size": tokio::fs::metadata(source_path).await?.len(),
+			"target_size": size,
+		});
+		let thumbnailer_failed = |e: PluginError| {
+			ThumbnailError::other(format!(
+				"Extension {} thumbnailer failed: {}",
+				self.handler.extension_id, e
+			))
+		};
+
+		// Rendering can take a while, so it runs on a blocking thread and only locks the
+		// extension's own instance
+		let instance = self
+			.plugin_manager
+			.read()
+			.await
+			.instance(&self.handler.extension_id)
+			.map_err(thumbnailer_failed)?;
+		let export_fn = self.handler.export_fn.clone();
+		let source_path = source_path.to_path_buf();
+		let library_id = self.library_id;
+		let image: Arc<[u8]> = instance
+			.run(move |instance| {
+				instance.call_media_handler(&export_fn, &source_path, &request, Some(library_id))
+			})
+			.await
+			.map_err(thumbnailer_failed)?
+			.into();
+
+		*rendered = Some((size, image.clone()));
+		Ok(image)
+	}
+}
+
+/// Resize an image to fit within `size` and write it to `output_path` as WebP
+fn write_webp_thumbnail(
+	img: image::DynamicImage,
+	output_path: &Path,
+	size: u32,
+	quality: u8,
+) -> ThumbnailResult<ThumbnailInfo> {
+	// Blurhash generation disabled for performance
+	let blurhash: Option<String> = None;
+
+	// Calculate target dimensions maintaining aspect ratio
+	let (original_width, original_height) = (img.width(), img.height());
+	let (target_width, target_height) = calculate_dimensions(original_width, original_height, size);
+
+	// Resize using high-quality algorithm
+	let thumbnail = img.resize(
+		target_width,
+		target_height,
+		image::imageops::FilterType::Lanczos3,
+	);
+
+	// Convert to RGB8 for consistency
+	let rgb_thumbnail = thumbnail.to_rgb8();
+
+	// Get actual dimensions from the resized image (may differ from calculated due to rounding)
+	let actual_width = rgb_thumbnail.width();
+	let actual_height = rgb_thumbnail.height();
+
+	// Verify buffer size matches expected dimensions
+	let expected_size = (actual_width * actual_height * 3) as usize;
+	let actual_size = rgb_thumbnail.as_raw().len();
+
+	if expected_size != actual_size {
+		return Err(ThumbnailError::other(format!(
+			"Image buffer size mismatch: expected {} bytes for {}x{}, got {} bytes",
+			expected_size, actual_width, actual_height, actual_size
+		)));
 	}
+
+	// Encode as WebP using actual dimensions
+	let webp_encoder = webp::Encoder::from_rgb(&rgb_thumbnail, actual_width, actual_height);
+	let webp_memory = webp_encoder.encode(quality as f32);
+	let webp_data = webp_memory.to_vec();
+
+	// Write to file
+	std::fs::write(output_path, &webp_data)?;
+
+	Ok(ThumbnailInfo {
+		size_bytes: webp_data.len(),
+		dimensions: (actual_width, actual_height),
+		format: "webp".to_string(),
+		blurhash,
+	})
 }
 
 /// Calculate target dimensions maintaining aspect ratio
@@ -588,18 +588,30 @@ impl ThumbnailJob {
 					_ => None,
 				});
 
-		// Skip files with unsupported extensions
-		let mime_type = match mime_type {
-			Some(mt) => mt,
-			None => {
+		// Create appropriate generator for the file type, including extension thumbnailers
+		let generator = match ThumbnailGenerator::for_file(
+			library,
+			mime_type,
+			entry.extension.as_deref(),
+		)
+		.await
+		{
+			Ok(generator) => generator,
+			Err(ThumbnailError::UnsupportedFormat(_)) => {
+				// Extensions may still extract metadata from files nothing can preview
+				#[cfg(feature = "wasm")]
+				if !Self::is_cloud_path(&entry.relative_path) {
+					let source_path = library.path().join(&entry.relative_path);
+					Self::extract_extension_metadata_static(entry, mime_type, &source_path, ctx)
+						.await;
+				}
+
 				// Skip unsupported file types
 				return Ok(0);
 			}
+			Err(e) => return Err(e),
 		};
 
-		// Create appropriate generator for the file type
-		let generator = ThumbnailGenerator::for_mime_type(mime_type)?;
-
 		// Check if this is a cloud path or local path
 		let is_cloud = Self::is_cloud_path(&entry.relative_path);
 
@@ -876,6 +888,9 @@ impl ThumbnailJob {
 				}
 				_ => {}
 			}
+
+			#[cfg(feature = "wasm")]
+			Self::extract_extension_metadata_static(entry, mime_type, &source_path, ctx).await;
 		}
 
 		// Temp file (if any) is automatically cleaned up when dropped here
@@ -883,4 +898,32 @@ impl ThumbnailJob {
 
 		Ok(total_thumbnail_size)
 	}
+
+	/// Store metadata from the metadata extractor an extension registered for the entry
+	#[cfg(feature = "wasm")]
+	async fn extract_extension_metadata_static(
+		entry: &ThumbnailEntry,
+		mime_type: Option<&str>,
+		source_path: &std::path::Path,
+		ctx: &JobContext<'_>,
+	) {
+		use crate::ops::media::extract_extension_metadata;
+
+		match extract_extension_metadata(
+			ctx.library(),
+			entry.content_uuid,
+			source_path,
+			mime_type,
+			entry.extension.as_deref(),
+		)
+		.await
+		{
+			Ok(Some(extension_id)) => ctx.log(format!(
+				"Extension {} extracted metadata for {}",
+				extension_id, entry.relative_path
+			)),
+			Ok(None) => {}
+			Err(e) => ctx.log(format!("Failed to extract extension metadata: {}", e)),
+		}
+	}
 }
@@ -33,7 +33,8 @@ use uuid::Uuid;
 ///
 /// This function generates the default thumbnail variants for a single file
 /// and registers them as sidecars. It's designed to be called inline for
-/// individual file creates/updates rather than dispatching a job.
+/// individual file creates/updates rather than dispatching a job. Thumbnailers
+/// and metadata extractors registered by extensions are used for the file too.
 ///
 /// # Arguments
 /// * `library` - The library context
@@ -51,15 +52,45 @@ pub async fn generate_thumbnails_for_file(
 ) -> ThumbnailResult<usize> {
 	use tracing::{debug, warn};
 
-	// Check if thumbnail generation is supported for this file type
-	if !ThumbnailUtils::is_thumbnail_supported(mime_type) {
-		debug!(
-			"Thumbnail generation not supported for MIME type: {}",
-			mime_type
-		);
-		return Ok(0);
+	let extension = source_path.extension().and_then(|ext| ext.to_str());
+
+	// Extensions can extract metadata from formats core has no extractor for
+	#[cfg(feature = "wasm")]
+	match crate::ops::media::extract_extension_metadata(
+		library,
+		*content_uuid,
+		source_path,
+		Some(mime_type),
+		extension,
+	)
+	.await
+	{
+		Ok(Some(extension_id)) => debug!(
+			"Extension {} extracted metadata for {}",
+			extension_id,
+			source_path.display()
+		),
+		Ok(None) => {}
+		Err(e) => warn!(
+			"Extension metadata extraction failed for {}: {}",
+			source_path.display(),
+			e
+		),
 	}
 
+	// Create thumbnail generator for this file, skipping types nothing can preview
+	let generator = match ThumbnailGenerator::for_file(library, Some(mime_type), extension).await {
+		Ok(generator) => generator,
+		Err(ThumbnailError::UnsupportedFormat(_)) => {
+			debug!(
+				"Thumbnail generation not supported for MIME type: {}",
+				mime_type
+			);
+			return Ok(0);
+		}
+		Err(e) => return Err(e),
+	};
+
 	// Get sidecar manager
 	let sidecar_manager = library
 		.core_context()
@@ -67,9 +98,6 @@ pub async fn generate_thumbnails_for_file(
 		.await
 		.ok_or_else(|| ThumbnailError::other("SidecarManager not available"))?;
 
-	// Create thumbnail generator for this MIME type
-	let generator = ThumbnailGenerator::for_mime_type(mime_type)?;
-
 	// Generate default variants (grid@1x, grid@2x, detail@1x)
 	let variants = ThumbnailVariants::defaults();
 	let mut generated_count = 0;
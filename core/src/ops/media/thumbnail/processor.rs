@@ -82,10 +82,10 @@ impl ThumbnailProcessor {
 			return false;
 		}
 
-		entry
-			.mime_type
-			.as_ref()
-			.map_or(false, |m| ThumbnailUtils::is_thumbnail_supported(m))
+		// Thumbnailers registered by extensions are looked up when the entry is processed
+		entry.mime_type.as_ref().map_or(false, |m| {
+			cfg!(feature = "wasm") || ThumbnailUtils::is_thumbnail_supported(m)
+		})
 	}
 
 	pub async fn process(
@@ -117,11 +117,6 @@ impl ThumbnailProcessor {
 			.as_ref()
 			.ok_or_else(|| anyhow::anyhow!("Entry has no MIME type"))?;
 
-		if !ThumbnailUtils::is_thumbnail_supported(mime_type) {
-			debug!("Thumbnails not supported for MIME type: {}", mime_type);
-			return Ok(ProcessorResult::success(0, 0));
-		}
-
 		debug!("→ Generating thumbnails for: {}", entry.path.display());
 
 		let count = super::generate_thumbnails_for_file(
@@ -640,6 +640,7 @@ impl TagSource {
 			TagSource::AI => "ai",
 			TagSource::Import => "import",
 			TagSource::Sync => "sync",
+			TagSource::Rule => "rule",
 		}
 	}
 
@@ -649,6 +650,7 @@ impl TagSource {
 			"ai" => Some(TagSource::AI),
 			"import" => Some(TagSource::Import),
 			"sync" => Some(TagSource::Sync),
+			"rule" => Some(TagSource::Rule),
 			_ => None,
 		}
 	}
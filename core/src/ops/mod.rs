@@ -1,19 +1,29 @@
 //! Operations module - contains all business operations and use cases
 //!
 //! This module organizes all business operations for Spacedrive:
+//! - Action history (undo and redo of reversible actions)
+//! - Event automation (rules that run actions and webhooks on events)
+//! - Extension resource usage
 //! - Addressing operations (path resolution)
+//! - File sync operations (backup versions)
 //! - File operations (copy, move, delete, validate, duplicate detection)
 //! - Indexing operations
 //! - Media processing (thumbnails, etc.)
 //! - Content operations (deduplication, statistics)
 //! - Metadata operations (hierarchical tagging)
+//! - Vault operations (encrypted locations)
 
+pub mod action;
 pub mod addressing;
+pub mod automation;
 pub mod config;
 // pub mod content;
 pub mod core;
 pub mod devices;
 pub mod extension_test;
+#[cfg(feature = "wasm")]
+pub mod extensions;
+pub mod file_sync;
 pub mod files;
 pub mod indexing;
 pub mod jobs;
@@ -28,4 +38,5 @@ pub mod sidecar;
 pub mod spaces;
 pub mod sync;
 pub mod tags;
+pub mod vaults;
 pub mod volumes;
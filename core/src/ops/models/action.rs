@@ -1,6 +1,6 @@
 //! Model management actions
 
-use super::{download::ModelDownloadJob, whisper::WhisperModel};
+use super::{download::ModelDownloadJob, embedding::EmbeddingModel, whisper::WhisperModel};
 use crate::{
 	context::CoreContext,
 	infra::action::{error::ActionError, CoreAction},
@@ -134,3 +134,66 @@ impl CoreAction for DeleteWhisperModelAction {
 }
 
 crate::register_core_action!(DeleteWhisperModelAction, "models.whisper.delete");
+
+// ============================================================================
+// Download Embedding Model Action
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DownloadEmbeddingModelInput {
+	/// Model ID, e.g. "minilm-l6-v2"
+	pub model: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DownloadEmbeddingModelOutput {
+	/// Job ID for tracking download progress
+	pub job_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadEmbeddingModelAction {
+	input: DownloadEmbeddingModelInput,
+}
+
+impl CoreAction for DownloadEmbeddingModelAction {
+	type Input = DownloadEmbeddingModelInput;
+	type Output = DownloadEmbeddingModelOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self { input })
+	}
+
+	async fn execute(self, context: Arc<CoreContext>) -> Result<Self::Output, ActionError> {
+		let model = EmbeddingModel::from_str(&self.input.model).ok_or_else(|| {
+			ActionError::InvalidInput(format!("Invalid model name: {}", self.input.model))
+		})?;
+
+		let data_dir = crate::config::default_data_dir()
+			.map_err(|e| ActionError::Internal(format!("Failed to get data dir: {}", e)))?;
+
+		let job = ModelDownloadJob::for_embedding_model(model, data_dir);
+
+		// TODO: Model downloads should be core-level jobs, not library-level
+		let library = context
+			.get_primary_library()
+			.await
+			.ok_or_else(|| ActionError::Internal("No library available".to_string()))?;
+
+		let job_handle = library
+			.jobs()
+			.dispatch(job)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to dispatch job: {}", e)))?;
+
+		Ok(DownloadEmbeddingModelOutput {
+			job_id: job_handle.id().to_string(),
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"models.embeddings.download"
+	}
+}
+
+crate::register_core_action!(DownloadEmbeddingModelAction, "models.embeddings.download");
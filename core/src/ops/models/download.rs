@@ -1,6 +1,6 @@
 //! Model download job with progress tracking
 
-use super::{types::ModelInfo, whisper::WhisperModel};
+use super::{embedding::EmbeddingModel, types::ModelInfo, whisper::WhisperModel};
 use crate::infra::job::{prelude::*, traits::DynJob};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -11,7 +11,7 @@ use tracing::{debug, info};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct ModelDownloadConfig {
-	/// Model ID to download (e.g., "whisper-base")
+	/// Model ID to download (e.g., "whisper-base" or "minilm-l6-v2")
 	pub model_id: String,
 	/// Data directory for model storage
 	pub data_dir: PathBuf,
@@ -26,6 +26,19 @@ pub struct ModelDownloadState {
 	temp_path: PathBuf,
 	total_bytes: u64,
 	downloaded_bytes: u64,
+	/// Files still to fetch after the current one, for models made of several files
+	#[serde(default)]
+	pending_files: Vec<PendingFile>,
+	/// Bytes of the files already verified
+	#[serde(default)]
+	completed_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PendingFile {
+	url: String,
+	target_path: PathBuf,
+	size_bytes: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +66,8 @@ impl ModelDownloadJob {
 				temp_path: PathBuf::new(),
 				total_bytes: 0,
 				downloaded_bytes: 0,
+				pending_files: Vec::new(),
+				completed_bytes: 0,
 			},
 			config,
 		}
@@ -64,6 +79,21 @@ impl ModelDownloadJob {
 			data_dir,
 		})
 	}
+
+	pub fn for_embedding_model(model: EmbeddingModel, data_dir: PathBuf) -> Self {
+		Self::new(ModelDownloadConfig {
+			model_id: model.id().to_string(),
+			data_dir,
+		})
+	}
+
+	fn output(&self) -> ModelDownloadOutput {
+		ModelDownloadOutput {
+			model_id: self.state.model_id.clone(),
+			path: self.state.target_path.to_string_lossy().to_string(),
+			size_bytes: self.state.completed_bytes.max(self.state.total_bytes),
+		}
+	}
 }
 
 impl Job for ModelDownloadJob {
@@ -84,34 +114,39 @@ impl JobHandler for ModelDownloadJob {
 			}
 			DownloadPhase::Downloading => {}
 			DownloadPhase::Verifying => {}
-			DownloadPhase::Complete => {
-				return Ok(ModelDownloadOutput {
-					model_id: self.state.model_id.clone(),
-					path: self.state.target_path.to_string_lossy().to_string(),
-					size_bytes: self.state.total_bytes,
-				});
-			}
+			DownloadPhase::Complete => return Ok(self.output()),
 		}
 
-		// Download phase
-		if matches!(self.state.phase, DownloadPhase::Downloading) {
-			self.download(&ctx).await?;
-			self.state.phase = DownloadPhase::Verifying;
-		}
+		loop {
+			// Download phase
+			if matches!(self.state.phase, DownloadPhase::Downloading) {
+				self.download(&ctx).await?;
+				self.state.phase = DownloadPhase::Verifying;
+			}
+
+			// Verify phase
+			if matches!(self.state.phase, DownloadPhase::Verifying) {
+				self.verify(&ctx).await?;
+				self.state.completed_bytes += self.state.total_bytes;
+
+				// Move on to the next file of a multi-file model
+				if !self.state.pending_files.is_empty() {
+					let next = self.state.pending_files.remove(0);
+					self.start_file(next);
+					self.state.phase = DownloadPhase::Downloading;
+					ctx.checkpoint().await?;
+					continue;
+				}
+
+				self.state.phase = DownloadPhase::Complete;
+			}
 
-		// Verify phase
-		if matches!(self.state.phase, DownloadPhase::Verifying) {
-			self.verify(&ctx).await?;
-			self.state.phase = DownloadPhase::Complete;
+			break;
 		}
 
 		ctx.log("Model download complete");
 
-		Ok(ModelDownloadOutput {
-			model_id: self.state.model_id.clone(),
-			path: self.state.target_path.to_string_lossy().to_string(),
-			size_bytes: self.state.total_bytes,
-		})
+		Ok(self.output())
 	}
 }
 
@@ -137,6 +172,25 @@ impl ModelDownloadJob {
 				model.display_name(),
 				self.state.total_bytes / 1024 / 1024
 			));
+		} else if let Some(model) = EmbeddingModel::from_str(&self.config.model_id) {
+			let model_dir = super::get_embedding_models_dir(&self.config.data_dir).join(model.id());
+			tokio::fs::create_dir_all(&model_dir).await?;
+
+			let mut files = model.files().into_iter().map(|file| PendingFile {
+				url: file.url.to_string(),
+				target_path: model_dir.join(file.filename),
+				size_bytes: file.size_bytes,
+			});
+			if let Some(first) = files.next() {
+				self.start_file(first);
+			}
+			self.state.pending_files = files.collect();
+
+			ctx.log(format!(
+				"Downloading {} ({} MB) from Hugging Face",
+				model.display_name(),
+				model.size_bytes() / 1024 / 1024
+			));
 		} else {
 			return Err(JobError::execution(format!(
 				"Unknown model ID: {}",
@@ -147,6 +201,14 @@ impl ModelDownloadJob {
 		Ok(())
 	}
 
+	fn start_file(&mut self, file: PendingFile) {
+		self.state.download_url = file.url;
+		self.state.temp_path = file.target_path.with_extension("tmp");
+		self.state.target_path = file.target_path;
+		self.state.total_bytes = file.size_bytes;
+		self.state.downloaded_bytes = 0;
+	}
+
 	async fn download(&mut self, ctx: &JobContext<'_>) -> JobResult<()> {
 		use futures::StreamExt;
 
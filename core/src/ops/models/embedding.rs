@@ -0,0 +1,166 @@
+//! Text embedding model management
+
+use super::types::{ModelInfo, ModelProvider, ModelType};
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// A file that makes up an embedding model
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddingModelFile {
+	pub filename: &'static str,
+	pub url: &'static str,
+	pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingModel {
+	/// all-MiniLM-L6-v2 sentence transformer (384 dimensions)
+	MiniLmL6V2,
+}
+
+impl EmbeddingModel {
+	pub fn id(&self) -> &'static str {
+		match self {
+			Self::MiniLmL6V2 => "minilm-l6-v2",
+		}
+	}
+
+	pub fn display_name(&self) -> &'static str {
+		match self {
+			Self::MiniLmL6V2 => "all-MiniLM-L6-v2",
+		}
+	}
+
+	pub fn description(&self) -> &'static str {
+		match self {
+			Self::MiniLmL6V2 => "Small English text model, fast on CPU (90 MB)",
+		}
+	}
+
+	/// Length of the vectors the model produces
+	pub fn dimensions(&self) -> usize {
+		match self {
+			Self::MiniLmL6V2 => 384,
+		}
+	}
+
+	/// Longest input in tokens, anything after is truncated
+	pub fn max_tokens(&self) -> usize {
+		match self {
+			Self::MiniLmL6V2 => 256,
+		}
+	}
+
+	/// ONNX graph and tokenizer, in download order
+	pub fn files(&self) -> [EmbeddingModelFile; 2] {
+		match self {
+			Self::MiniLmL6V2 => [
+				EmbeddingModelFile {
+					filename: "model.onnx",
+					url: "https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2/resolve/main/onnx/model.onnx",
+					size_bytes: 90 * 1024 * 1024, // 90 MB
+				},
+				EmbeddingModelFile {
+					filename: "tokenizer.json",
+					url: "https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2/resolve/main/tokenizer.json",
+					size_bytes: 455 * 1024, // 455 KB
+				},
+			],
+		}
+	}
+
+	pub fn size_bytes(&self) -> u64 {
+		self.files().iter().map(|file| file.size_bytes).sum()
+	}
+
+	pub fn from_str(s: &str) -> Option<Self> {
+		match s.to_lowercase().as_str() {
+			"minilm-l6-v2" | "all-minilm-l6-v2" => Some(Self::MiniLmL6V2),
+			_ => None,
+		}
+	}
+
+	pub fn all() -> Vec<Self> {
+		vec![Self::MiniLmL6V2]
+	}
+}
+
+impl Default for EmbeddingModel {
+	fn default() -> Self {
+		Self::MiniLmL6V2
+	}
+}
+
+pub struct EmbeddingModelManager {
+	models_dir: PathBuf,
+}
+
+impl EmbeddingModelManager {
+	pub fn new(data_dir: &Path) -> Self {
+		Self {
+			models_dir: super::get_embedding_models_dir(data_dir),
+		}
+	}
+
+	/// Directory holding all files of a model
+	pub fn get_model_dir(&self, model: &EmbeddingModel) -> PathBuf {
+		self.models_dir.join(model.id())
+	}
+
+	/// Path of the ONNX graph
+	pub fn get_model_path(&self, model: &EmbeddingModel) -> PathBuf {
+		self.get_model_dir(model).join(model.files()[0].filename)
+	}
+
+	/// Path of the tokenizer definition
+	pub fn get_tokenizer_path(&self, model: &EmbeddingModel) -> PathBuf {
+		self.get_model_dir(model).join(model.files()[1].filename)
+	}
+
+	/// Check if every file of a model is downloaded
+	///
+	/// Downloads are renamed into place once verified, so existing files are complete.
+	pub async fn is_downloaded(&self, model: &EmbeddingModel) -> bool {
+		let dir = self.get_model_dir(model);
+		for file in model.files() {
+			if !tokio::fs::try_exists(dir.join(file.filename))
+				.await
+				.unwrap_or(false)
+			{
+				return false;
+			}
+		}
+		true
+	}
+
+	/// List all available models with download status
+	pub async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+		let mut models = Vec::new();
+
+		for model in EmbeddingModel::all() {
+			models.push(ModelInfo {
+				id: model.id().to_string(),
+				name: model.display_name().to_string(),
+				model_type: ModelType::Embedding,
+				size_bytes: model.size_bytes(),
+				provider: ModelProvider::HuggingFace {
+					repo: "sentence-transformers/all-MiniLM-L6-v2".to_string(),
+				},
+				filename: model.files()[0].filename.to_string(),
+				downloaded: self.is_downloaded(&model).await,
+				description: Some(model.description().to_string()),
+			});
+		}
+
+		Ok(models)
+	}
+
+	/// Delete a model
+	pub async fn delete_model(&self, model: &EmbeddingModel) -> Result<()> {
+		let dir = self.get_model_dir(model);
+		if dir.exists() {
+			tokio::fs::remove_dir_all(&dir).await?;
+		}
+		Ok(())
+	}
+}
@@ -1,6 +1,6 @@
 //! Model management queries
 
-use super::{types::ModelInfo, whisper::WhisperModelManager};
+use super::{embedding::EmbeddingModelManager, types::ModelInfo, whisper::WhisperModelManager};
 use crate::{context::CoreContext, infra::query::CoreQuery};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -48,3 +48,41 @@ impl CoreQuery for ListWhisperModelsQuery {
 }
 
 crate::register_core_query!(ListWhisperModelsQuery, "models.whisper.list");
+
+// ============================================================================
+// List Embedding Models Query
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListEmbeddingModelsInput {}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListEmbeddingModelsOutput {
+	pub models: Vec<ModelInfo>,
+}
+
+pub struct ListEmbeddingModelsQuery;
+
+impl CoreQuery for ListEmbeddingModelsQuery {
+	type Input = ListEmbeddingModelsInput;
+	type Output = ListEmbeddingModelsOutput;
+
+	fn from_input(_input: Self::Input) -> crate::infra::query::QueryResult<Self> {
+		Ok(Self)
+	}
+
+	async fn execute(
+		self,
+		_context: std::sync::Arc<CoreContext>,
+		_session: crate::infra::api::SessionContext,
+	) -> crate::infra::query::QueryResult<Self::Output> {
+		let data_dir = crate::config::default_data_dir()?;
+		let manager = EmbeddingModelManager::new(&data_dir);
+
+		Ok(ListEmbeddingModelsOutput {
+			models: manager.list_models().await?,
+		})
+	}
+}
+
+crate::register_core_query!(ListEmbeddingModelsQuery, "models.embeddings.list");
@@ -10,6 +10,8 @@ pub enum ModelType {
 	Whisper,
 	/// Tesseract OCR language data
 	Tesseract,
+	/// Text embedding model for semantic search
+	Embedding,
 }
 
 /// Model provider
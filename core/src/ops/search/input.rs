@@ -47,7 +47,7 @@ pub enum SearchMode {
 	Fast,
 	/// Normal search with semantic ranking (<100ms)
 	Normal,
-	/// Full search with content analysis (<500ms)
+	/// Full search that also matches extracted file contents (<500ms)
 	Full,
 }
 
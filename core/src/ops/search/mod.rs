@@ -9,6 +9,8 @@ pub mod filters;
 pub mod input;
 pub mod output;
 pub mod query;
+pub mod saved;
+pub mod semantic;
 pub mod sorting;
 
 #[cfg(test)]
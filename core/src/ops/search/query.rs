@@ -3,6 +3,7 @@
 use super::{
 	input::{FileSearchInput, SearchScope},
 	output::{EnhancedFileSearchOutput, EnhancedFileSearchResult, FileSearchOutput},
+	semantic::{self, MIN_SEMANTIC_SIMILARITY},
 };
 use crate::infra::query::{QueryError, QueryResult};
 use crate::{
@@ -13,26 +14,90 @@ use crate::{
 		content_identity, directory_paths, entry, sidecar, tag, user_metadata_tag,
 	},
 	infra::query::LibraryQuery,
+	library::Library,
+	ops::media::embedding::VectorHit,
 };
 use chrono::{DateTime, Utc};
 use sea_orm::{
-	ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, JoinType,
-	QueryFilter, QueryOrder, QuerySelect, RelationTrait, Statement,
+	sea_query::LikeExpr, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+	JoinType, QueryFilter, QueryOrder, QuerySelect, RelationTrait, Statement,
 };
 use serde::{Deserialize, Serialize};
 use specta::Type;
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 use uuid::Uuid;
 
+/// Score added to name matches whose contents also match
+const CONTENT_MATCH_BOOST: f32 = 0.3;
+
+/// How many more vector hits to fetch when scope or filters will discard some
+const SEMANTIC_FILTERED_OVERFETCH: usize = 4;
+
+/// Number of tokens FTS5 includes in a content snippet
+const CONTENT_SNIPPET_TOKENS: u32 = 24;
+
+/// Control characters wrapping matched terms in FTS5 snippets (stripped before returning)
+const SNIPPET_MATCH_START: char = '\u{2}';
+const SNIPPET_MATCH_END: char = '\u{3}';
+
+/// An entry whose content is close in meaning to the query
+struct SemanticMatch {
+	entry_id: i32,
+	entry_uuid: Uuid,
+	similarity: f32,
+}
+
+/// Columns needed to score a matched entry before its full result is loaded
+struct MatchedEntry {
+	entry_id: i32,
+	entry_uuid: Uuid,
+	size: i64,
+	extension: Option<String>,
+	modified_at: DateTime<Utc>,
+}
+
+impl MatchedEntry {
+	fn from_row(row: &sea_orm::QueryResult) -> Result<Self, sea_orm::DbErr> {
+		Ok(Self {
+			entry_id: row.try_get("", "id")?,
+			entry_uuid: row.try_get("", "uuid")?,
+			size: row.try_get("", "size")?,
+			extension: row.try_get("", "extension")?,
+			modified_at: row.try_get("", "modified_at")?,
+		})
+	}
+}
+
+/// A full-text match inside a file's extracted contents
+struct ContentMatch {
+	entry: MatchedEntry,
+	rank: f64,
+	snippet: String,
+}
+
 /// File search query
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct FileSearchQuery {
 	pub input: FileSearchInput,
+	/// Embedding to rank by meaning with, instead of embedding `input.query` with the
+	/// library's model
+	#[serde(skip)]
+	pub query_vector: Option<Vec<f32>>,
 }
 
 impl FileSearchQuery {
 	pub fn new(input: FileSearchInput) -> Self {
-		Self { input }
+		Self {
+			input,
+			query_vector: None,
+		}
+	}
+
+	/// Rank Normal and Full searches by similarity to `vector` rather than to the
+	/// embedded query text
+	pub fn with_query_vector(mut self, vector: Vec<f32>) -> Self {
+		self.query_vector = Some(vector);
+		self
 	}
 }
 
@@ -41,7 +106,7 @@ impl LibraryQuery for FileSearchQuery {
 	type Output = FileSearchOutput;
 
 	fn from_input(input: Self::Input) -> QueryResult<Self> {
-		Ok(Self { input })
+		Ok(Self::new(input))
 	}
 
 	async fn execute(
@@ -101,13 +166,15 @@ impl LibraryQuery for FileSearchQuery {
 						self.execute_fast_search(db.conn(), &device_slug_map)
 							.await?
 					}
-					crate::ops::search::input::SearchMode::Normal => {
-						self.execute_normal_search(db.conn(), &device_slug_map)
-							.await?
-					}
-					crate::ops::search::input::SearchMode::Full => {
-						self.execute_full_search(db.conn(), &device_slug_map)
-							.await?
+					crate::ops::search::input::SearchMode::Normal
+					| crate::ops::search::input::SearchMode::Full => {
+						self.execute_ranked_search(
+							&library,
+							db.conn(),
+							&device_slug_map,
+							context.file_type_registry(),
+						)
+						.await?
 					}
 				};
 
@@ -216,6 +283,37 @@ impl FileSearchQuery {
 		let score_map: std::collections::HashMap<i32, f64> = fts_results.iter().cloned().collect();
 		let entry_ids: Vec<i32> = fts_results.iter().map(|(id, _)| *id).collect();
 
+		let mut results = self
+			.load_search_results(db, &entry_ids, &score_map, &fts_query)
+			.await?;
+
+		tracing::info!(
+			"Built {} FileSearchResult objects from {} FTS5 results",
+			results.len(),
+			fts_count
+		);
+
+		// Sort by final score
+		results.sort_by(|a, b| {
+			b.score
+				.partial_cmp(&a.score)
+				.unwrap_or(std::cmp::Ordering::Equal)
+		});
+
+		Ok(results)
+	}
+
+	/// Load entries by ID with their joined data and convert them to search results
+	///
+	/// `score_map` holds the BM25 rank of each entry and `fts_query` is used to
+	/// highlight name and extension matches.
+	async fn load_search_results(
+		&self,
+		db: &DatabaseConnection,
+		entry_ids: &[i32],
+		score_map: &std::collections::HashMap<i32, f64>,
+		fts_query: &str,
+	) -> QueryResult<Vec<crate::ops::search::output::FileSearchResult>> {
 		// Build single efficient query with all joins
 		let entry_ids_str = entry_ids
 			.iter()
@@ -464,19 +562,6 @@ impl FileSearchQuery {
 			results.push(result);
 		}
 
-		tracing::info!(
-			"Built {} FileSearchResult objects from {} FTS5 results",
-			results.len(),
-			fts_count
-		);
-
-		// Sort by final score
-		results.sort_by(|a, b| {
-			b.score
-				.partial_cmp(&a.score)
-				.unwrap_or(std::cmp::Ordering::Equal)
-		});
-
 		Ok(results)
 	}
 
@@ -491,24 +576,8 @@ impl FileSearchQuery {
 
 		// Enhanced ranking for normal search
 		for result in &mut results {
-			let mut enhanced_score = result.score;
-
-			// Add metadata-based scoring
-			let size = result.file.size;
-			// Slightly boost files with reasonable sizes
-			if size > 1024 && size < 10_000_000 {
-				// 1KB to 10MB
-				enhanced_score += 0.1;
-			}
-
-			// Boost files with extensions that match common document types
-			if let Some(ref extension) = result.file.extension {
-				match extension.as_str() {
-					"pdf" | "doc" | "docx" | "txt" | "md" => enhanced_score += 0.2,
-					"jpg" | "png" | "gif" | "webp" => enhanced_score += 0.1,
-					_ => {}
-				}
-			}
+			let enhanced_score = result.score
+				+ Self::metadata_boost(result.file.size as i64, result.file.extension.as_deref());
 
 			// Update the score
 			result.score = enhanced_score;
@@ -528,29 +597,122 @@ impl FileSearchQuery {
 		Ok(results)
 	}
 
-	/// Execute full search with FTS5 + content analysis
+	/// Score added by normal search for sizes and extensions users usually look for
+	fn metadata_boost(size: i64, extension: Option<&str>) -> f32 {
+		let mut boost = 0.0;
+
+		// Slightly boost files with reasonable sizes (1KB to 10MB)
+		if size > 1024 && size < 10_000_000 {
+			boost += 0.1;
+		}
+
+		// Boost files with extensions that match common document types
+		match extension {
+			Some("pdf" | "doc" | "docx" | "txt" | "md") => boost += 0.2,
+			Some("jpg" | "png" | "gif" | "webp") => boost += 0.1,
+			_ => {}
+		}
+
+		boost
+	}
+
+	/// Execute full search with FTS5 over names plus extracted file contents
+	///
+	/// Name and content matches are merged and scored before paginating, so a page
+	/// holds at most `limit` results whichever way each file matched.
 	async fn execute_full_search(
 		&self,
 		db: &DatabaseConnection,
 		device_slug_map: &std::collections::HashMap<Uuid, String>,
+		registry: &FileTypeRegistry,
 	) -> QueryResult<Vec<crate::ops::search::output::FileSearchResult>> {
-		// Start with normal search results
-		let mut results = self.execute_normal_search(db, device_slug_map).await?;
+		if self.input.query.trim().is_empty() {
+			return self.execute_normal_search(db, device_slug_map).await;
+		}
+
+		let fts_query = self.build_fts5_query();
+		let name_matches = self.execute_name_search(db, &fts_query, registry).await?;
+		let content_matches = self
+			.execute_content_search(db, &fts_query, registry)
+			.await?;
+
+		tracing::info!(
+			"Full search matched {} names and {} file contents",
+			name_matches.len(),
+			content_matches.len()
+		);
+
+		let relevance =
+			crate::ops::search::sorting::RelevanceCalculator::new(self.input.query.clone());
+		let score = |entry: &MatchedEntry, rank: f64| {
+			rank as f32
+				+ relevance.calculate_recency_boost(entry.modified_at)
+				+ relevance.calculate_user_preference_boost(entry.entry_id)
+				+ Self::metadata_boost(entry.size, entry.extension.as_deref())
+		};
+
+		// BM25 rank per entry (name rank wins) and the merged score used for ordering
+		let mut ranks: std::collections::HashMap<i32, f64> = std::collections::HashMap::new();
+		let mut scores: std::collections::HashMap<i32, (Uuid, f32)> =
+			std::collections::HashMap::new();
+		for (entry, rank) in &name_matches {
+			ranks.insert(entry.entry_id, *rank);
+			scores.insert(entry.entry_id, (entry.entry_uuid, score(entry, *rank)));
+		}
+
+		let mut contents: std::collections::HashMap<Uuid, ContentMatch> =
+			std::collections::HashMap::new();
+		for content_match in content_matches {
+			let entry = &content_match.entry;
+			match scores.get_mut(&entry.entry_id) {
+				// Files that matched by name and by content rank above either alone
+				Some((_, merged)) => *merged += CONTENT_MATCH_BOOST,
+				None => {
+					ranks.insert(entry.entry_id, content_match.rank);
+					scores.insert(
+						entry.entry_id,
+						(entry.entry_uuid, score(entry, content_match.rank)),
+					);
+				}
+			}
+			contents.insert(entry.entry_uuid, content_match);
+		}
+
+		let mut ordered: Vec<(i32, Uuid, f32)> = scores
+			.into_iter()
+			.map(|(entry_id, (uuid, score))| (entry_id, uuid, score))
+			.collect();
+		ordered.sort_by(|a, b| b.2.total_cmp(&a.2).then(a.0.cmp(&b.0)));
+
+		let page: Vec<(i32, Uuid, f32)> = ordered
+			.into_iter()
+			.skip(self.input.pagination.offset as usize)
+			.take(self.input.pagination.limit as usize)
+			.collect();
+		if page.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let entry_ids: Vec<i32> = page.iter().map(|(entry_id, _, _)| *entry_id).collect();
+		let page_scores: std::collections::HashMap<Uuid, f32> = page
+			.iter()
+			.map(|(_, uuid, score)| (*uuid, *score))
+			.collect();
+
+		let mut results = self
+			.load_search_results(db, &entry_ids, &ranks, &fts_query)
+			.await?;
 
-		// For full search, we would add content analysis here
-		// This is a placeholder for future implementation
 		for result in &mut results {
-			// TODO: Add content extraction and analysis
-			// For now, just add a small boost for files that might have content
-			let size = result.file.size;
-			if size > 0 && size < 100_000_000 {
-				// Up to 100MB
-				result.score += 0.05;
-				result.score_breakdown.metadata_score += 0.05;
+			if let Some(&merged) = page_scores.get(&result.file.id) {
+				result.score_breakdown.metadata_score = merged - result.score;
+				result.score = merged;
+			}
+			if let Some(content_match) = contents.get(&result.file.id) {
+				Self::attach_content_match(result, content_match);
 			}
 		}
 
-		// Re-sort with content analysis scores
 		results.sort_by(|a, b| {
 			b.score
 				.partial_cmp(&a.score)
@@ -560,6 +722,486 @@ impl FileSearchQuery {
 		Ok(results)
 	}
 
+	/// SQL joins, conditions and parameters restricting a query over `entries e` to
+	/// the search scope and filters
+	///
+	/// The conditions start with `AND` so they can follow any `WHERE` clause, and the
+	/// parameters have to be bound after every placeholder that precedes them.
+	fn entry_constraints(
+		&self,
+		registry: &FileTypeRegistry,
+	) -> (String, String, Vec<sea_orm::Value>) {
+		let mut joins = String::new();
+		let mut conditions = String::new();
+		let mut params: Vec<sea_orm::Value> = Vec::new();
+
+		fn placeholders(count: usize) -> String {
+			vec!["?"; count].join(", ")
+		}
+
+		// Entries under a location's root, through the closure table
+		let location_condition = |count: usize| {
+			format!(
+				" AND e.id IN (
+					SELECT ec.descendant_id FROM entry_closure ec
+					JOIN locations l ON l.entry_id = ec.ancestor_id
+					WHERE l.uuid IN ({})
+				)",
+				placeholders(count)
+			)
+		};
+
+		match &self.input.scope {
+			SearchScope::Library => {}
+			SearchScope::Location { location_id } => {
+				conditions.push_str(&location_condition(1));
+				params.push((*location_id).into());
+			}
+			SearchScope::Path { path } => {
+				if let Some(path) = path.path() {
+					joins.push_str(" JOIN directory_paths dp ON dp.entry_id = e.parent_id");
+					conditions.push_str(" AND dp.path LIKE ? ESCAPE '\\'");
+					params.push(Self::path_prefix_pattern(path).into());
+				}
+			}
+		}
+
+		let filters = &self.input.filters;
+
+		if let Some(locations) = filters.locations.as_ref().filter(|l| !l.is_empty()) {
+			conditions.push_str(&location_condition(locations.len()));
+			params.extend(locations.iter().map(|id| (*id).into()));
+		}
+
+		if let Some(file_types) = filters.file_types.as_ref().filter(|t| !t.is_empty()) {
+			conditions.push_str(&format!(
+				" AND e.extension IN ({})",
+				placeholders(file_types.len())
+			));
+			params.extend(file_types.iter().map(|ext| ext.clone().into()));
+		}
+
+		if let Some(content_types) = filters.content_types.as_ref().filter(|t| !t.is_empty()) {
+			let extensions: Vec<&str> = content_types
+				.iter()
+				.flat_map(|kind| registry.get_extensions_for_category(*kind))
+				.collect();
+			if extensions.is_empty() {
+				conditions.push_str(" AND 0");
+			} else {
+				conditions.push_str(&format!(
+					" AND e.extension IN ({})",
+					placeholders(extensions.len())
+				));
+				params.extend(extensions.into_iter().map(|ext| ext.to_string().into()));
+			}
+		}
+
+		if let Some(size_range) = &filters.size_range {
+			if let Some(min) = size_range.min {
+				conditions.push_str(" AND e.size >= ?");
+				params.push((min as i64).into());
+			}
+			if let Some(max) = size_range.max {
+				conditions.push_str(" AND e.size <= ?");
+				params.push((max as i64).into());
+			}
+		}
+
+		if let Some(date_range) = &filters.date_range {
+			let column = match date_range.field {
+				crate::ops::search::input::DateField::CreatedAt => "e.created_at",
+				crate::ops::search::input::DateField::ModifiedAt => "e.modified_at",
+				crate::ops::search::input::DateField::AccessedAt => "e.accessed_at",
+				crate::ops::search::input::DateField::IndexedAt => "e.indexed_at",
+			};
+			if let Some(start) = date_range.start {
+				conditions.push_str(&format!(" AND {} >= ?", column));
+				params.push(start.into());
+			}
+			if let Some(end) = date_range.end {
+				conditions.push_str(&format!(" AND {} <= ?", column));
+				params.push(end.into());
+			}
+		}
+
+		(joins, conditions, params)
+	}
+
+	/// Search entry names within the scope and filters, without paginating
+	async fn execute_name_search(
+		&self,
+		db: &DatabaseConnection,
+		query: &str,
+		registry: &FileTypeRegistry,
+	) -> QueryResult<Vec<(MatchedEntry, f64)>> {
+		let (joins, conditions, constraint_params) = self.entry_constraints(registry);
+
+		let sql = format!(
+			r#"
+				WITH fts AS (
+					SELECT rowid, bm25(search_index) AS rank
+					FROM search_index
+					WHERE search_index MATCH ?
+					ORDER BY rank
+					LIMIT 5000
+				)
+				SELECT e.id, e.uuid, e.size, e.extension, e.modified_at, fts.rank
+				FROM fts
+				JOIN entries e ON e.id = fts.rowid
+				{joins}
+				WHERE e.uuid IS NOT NULL {conditions}
+			"#
+		);
+
+		let mut params: Vec<sea_orm::Value> = vec![query.into()];
+		params.extend(constraint_params);
+
+		let rows = db
+			.query_all(Statement::from_sql_and_values(
+				db.get_database_backend(),
+				&sql,
+				params,
+			))
+			.await?;
+
+		let mut matches = Vec::with_capacity(rows.len());
+		for row in rows {
+			matches.push((MatchedEntry::from_row(&row)?, row.try_get("", "rank")?));
+		}
+
+		Ok(matches)
+	}
+
+	/// Search extracted file text within the scope and filters, returning one match
+	/// per entry with a highlighted snippet
+	async fn execute_content_search(
+		&self,
+		db: &DatabaseConnection,
+		query: &str,
+		registry: &FileTypeRegistry,
+	) -> QueryResult<Vec<ContentMatch>> {
+		let (joins, conditions, constraint_params) = self.entry_constraints(registry);
+
+		// Entries share content identities, so the FTS match is fanned out to every entry
+		let sql = format!(
+			r#"
+				WITH matches AS (
+					SELECT rowid,
+						bm25(content_search_index) AS rank,
+						snippet(content_search_index, 1, ?, ?, '…', {}) AS snippet
+					FROM content_search_index
+					WHERE content_search_index MATCH ?
+					ORDER BY rank
+					LIMIT 5000
+				)
+				SELECT e.id, e.uuid, e.size, e.extension, e.modified_at,
+					matches.rank, matches.snippet
+				FROM matches
+				JOIN entries e ON e.content_id = matches.rowid
+				{joins}
+				WHERE e.uuid IS NOT NULL {conditions}
+			"#,
+			CONTENT_SNIPPET_TOKENS,
+		);
+
+		let mut params: Vec<sea_orm::Value> = vec![
+			SNIPPET_MATCH_START.to_string().into(),
+			SNIPPET_MATCH_END.to_string().into(),
+			query.into(),
+		];
+		params.extend(constraint_params);
+
+		let rows = db
+			.query_all(Statement::from_sql_and_values(
+				db.get_database_backend(),
+				&sql,
+				params,
+			))
+			.await?;
+
+		let mut matches = Vec::with_capacity(rows.len());
+		for row in rows {
+			matches.push(ContentMatch {
+				entry: MatchedEntry::from_row(&row)?,
+				rank: row.try_get("", "rank")?,
+				snippet: row.try_get("", "snippet")?,
+			});
+		}
+
+		Ok(matches)
+	}
+
+	/// Normal or Full keyword search, blended with semantic matches when available
+	///
+	/// Both rankings cover the first `offset + limit` results and are fused before the
+	/// page is cut, so semantic matches land on the page their score earns them.
+	async fn execute_ranked_search(
+		&self,
+		library: &Library,
+		db: &DatabaseConnection,
+		device_slug_map: &std::collections::HashMap<Uuid, String>,
+		registry: &FileTypeRegistry,
+	) -> QueryResult<Vec<crate::ops::search::output::FileSearchResult>> {
+		let candidates = (self.input.pagination.offset + self.input.pagination.limit) as usize;
+		let hits = if self.input.query.trim().is_empty() {
+			None
+		} else {
+			// Scope and filters drop some hits, so fetch extra when they apply
+			let (_, conditions, _) = self.entry_constraints(registry);
+			let factor = if conditions.is_empty() {
+				1
+			} else {
+				SEMANTIC_FILTERED_OVERFETCH
+			};
+			let vector = match &self.query_vector {
+				Some(vector) => Some(vector.clone()),
+				None => semantic::embed_query(library, &self.input.query).await,
+			};
+			match vector {
+				Some(vector) => {
+					semantic::nearest_contents(library, &vector, candidates * factor).await
+				}
+				None => None,
+			}
+		};
+		let Some(hits) = hits else {
+			return self
+				.execute_keyword_search(db, device_slug_map, registry)
+				.await;
+		};
+
+		let mut head = self.clone();
+		head.input.pagination.offset = 0;
+		head.input.pagination.limit = candidates as u32;
+		let keyword_results = head
+			.execute_keyword_search(db, device_slug_map, registry)
+			.await?;
+
+		self.apply_semantic_ranking(db, registry, keyword_results, &hits)
+			.await
+	}
+
+	async fn execute_keyword_search(
+		&self,
+		db: &DatabaseConnection,
+		device_slug_map: &std::collections::HashMap<Uuid, String>,
+		registry: &FileTypeRegistry,
+	) -> QueryResult<Vec<crate::ops::search::output::FileSearchResult>> {
+		match self.input.mode {
+			crate::ops::search::input::SearchMode::Full => {
+				self.execute_full_search(db, device_slug_map, registry)
+					.await
+			}
+			_ => self.execute_normal_search(db, device_slug_map).await,
+		}
+	}
+
+	/// Blend vector similarity into the leading keyword results by reciprocal rank
+	/// fusion and cut the requested page
+	///
+	/// Files that only match by meaning are added when similar enough to the query.
+	async fn apply_semantic_ranking(
+		&self,
+		db: &DatabaseConnection,
+		registry: &FileTypeRegistry,
+		keyword_results: Vec<crate::ops::search::output::FileSearchResult>,
+		hits: &[VectorHit],
+	) -> QueryResult<Vec<crate::ops::search::output::FileSearchResult>> {
+		let limit = self.input.pagination.limit as usize;
+		let offset = self.input.pagination.offset as usize;
+
+		let semantic_matches = self.load_semantic_matches(db, registry, hits).await?;
+		tracing::debug!(
+			"Semantic search returned {} results",
+			semantic_matches.len()
+		);
+		if semantic_matches.is_empty() {
+			return Ok(keyword_results
+				.into_iter()
+				.skip(offset)
+				.take(limit)
+				.collect());
+		}
+
+		let keyword_ranking: Vec<Uuid> = keyword_results.iter().map(|r| r.file.id).collect();
+		let semantic_ranking: Vec<Uuid> = semantic_matches.iter().map(|m| m.entry_uuid).collect();
+		let fused = semantic::reciprocal_rank_fusion(&[&keyword_ranking, &semantic_ranking]);
+		let similarity: std::collections::HashMap<Uuid, f32> = semantic_matches
+			.iter()
+			.map(|m| (m.entry_uuid, m.similarity))
+			.collect();
+
+		// Rank keyword results and close-enough semantic-only matches together, then page
+		let semantic_only: std::collections::HashMap<Uuid, i32> = semantic_matches
+			.iter()
+			.filter(|m| {
+				m.similarity >= MIN_SEMANTIC_SIMILARITY && !keyword_ranking.contains(&m.entry_uuid)
+			})
+			.map(|m| (m.entry_uuid, m.entry_id))
+			.collect();
+		let mut ranking: Vec<Uuid> = keyword_ranking
+			.iter()
+			.chain(semantic_only.keys())
+			.copied()
+			.collect();
+		let score = |id: &Uuid| fused.get(id).copied().unwrap_or(0.0);
+		ranking.sort_by(|a, b| score(b).total_cmp(&score(a)));
+		let page: Vec<Uuid> = ranking.into_iter().skip(offset).take(limit).collect();
+
+		// Files that only matched by meaning still need loading
+		let to_load: Vec<i32> = page
+			.iter()
+			.filter_map(|id| semantic_only.get(id).copied())
+			.collect();
+		let mut loaded: std::collections::HashMap<Uuid, _> = keyword_results
+			.into_iter()
+			.map(|result| (result.file.id, result))
+			.collect();
+		if !to_load.is_empty() {
+			let semantic_results = self
+				.load_search_results(
+					db,
+					&to_load,
+					&std::collections::HashMap::new(),
+					&self.build_fts5_query(),
+				)
+				.await?;
+			loaded.extend(
+				semantic_results
+					.into_iter()
+					.map(|result| (result.file.id, result)),
+			);
+		}
+
+		Ok(page
+			.iter()
+			.filter_map(|id| {
+				let mut result = loaded.remove(id)?;
+				result.score = score(id);
+				result.score_breakdown.semantic_score = similarity.get(id).copied();
+				Some(result)
+			})
+			.collect())
+	}
+
+	/// Fan vector hits out to the entries that share each content identity
+	///
+	/// Hits whose content changed since it was embedded are dropped, as are entries
+	/// outside the search's scope and filters.
+	async fn load_semantic_matches(
+		&self,
+		db: &DatabaseConnection,
+		registry: &FileTypeRegistry,
+		hits: &[VectorHit],
+	) -> QueryResult<Vec<SemanticMatch>> {
+		let hits_by_content: std::collections::HashMap<i32, &VectorHit> =
+			hits.iter().map(|hit| (hit.content_id, hit)).collect();
+		let content_ids = hits
+			.iter()
+			.map(|hit| hit.content_id.to_string())
+			.collect::<Vec<_>>()
+			.join(",");
+
+		let (joins, conditions, params) = self.entry_constraints(registry);
+		let sql = format!(
+			r#"
+				SELECT e.id, e.uuid, e.content_id, ci.content_hash
+				FROM entries e
+				JOIN content_identities ci ON ci.id = e.content_id
+				{joins}
+				WHERE e.content_id IN ({content_ids}) AND e.uuid IS NOT NULL {conditions}
+			"#
+		);
+
+		let rows = db
+			.query_all(Statement::from_sql_and_values(
+				db.get_database_backend(),
+				&sql,
+				params,
+			))
+			.await?;
+
+		let mut matches = Vec::with_capacity(rows.len());
+		for row in rows {
+			let content_id: i32 = row.try_get("", "content_id")?;
+			let content_hash: String = row.try_get("", "content_hash")?;
+			let Some(hit) = hits_by_content
+				.get(&content_id)
+				.filter(|hit| hit.content_hash == content_hash)
+			else {
+				continue;
+			};
+
+			matches.push(SemanticMatch {
+				entry_id: row.try_get("", "id")?,
+				entry_uuid: row.try_get("", "uuid")?,
+				similarity: hit.similarity,
+			});
+		}
+
+		matches.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+		Ok(matches)
+	}
+
+	fn attach_content_match(
+		result: &mut crate::ops::search::output::FileSearchResult,
+		content_match: &ContentMatch,
+	) {
+		let (text, highlights) = Self::extract_content_highlights(&content_match.snippet);
+		result.highlights.extend(highlights);
+		result.matched_content = Some(text);
+	}
+
+	/// A `LIKE ... ESCAPE '\'` pattern matching paths under `path`, with the wildcards
+	/// and escape character a path may contain matched literally
+	pub fn path_prefix_pattern(path: &Path) -> String {
+		let mut pattern = String::new();
+		for c in path.to_string_lossy().chars() {
+			if matches!(c, '%' | '_' | '\\') {
+				pattern.push('\\');
+			}
+			pattern.push(c);
+		}
+		pattern.push('%');
+		pattern
+	}
+
+	/// Turn an FTS5 snippet with match markers into plain text and content highlights
+	pub fn extract_content_highlights(
+		snippet: &str,
+	) -> (String, Vec<crate::ops::search::output::TextHighlight>) {
+		let mut text = String::with_capacity(snippet.len());
+		let mut ranges = Vec::new();
+		let mut start = None;
+
+		for ch in snippet.chars() {
+			match ch {
+				SNIPPET_MATCH_START => start = Some(text.len()),
+				SNIPPET_MATCH_END => {
+					if let Some(start) = start.take() {
+						ranges.push((start, text.len()));
+					}
+				}
+				// Collapse line breaks so the snippet reads as a single line
+				'\n' | '\r' | '\t' => text.push(' '),
+				_ => text.push(ch),
+			}
+		}
+
+		let highlights = ranges
+			.into_iter()
+			.map(|(start, end)| crate::ops::search::output::TextHighlight {
+				field: "content".to_string(),
+				text: text.clone(),
+				start,
+				end,
+			})
+			.collect();
+
+		(text, highlights)
+	}
+
 	/// Apply scope filters to the query condition
 	fn apply_scope_filter(&self, mut condition: Condition) -> Condition {
 		match &self.input.scope {
@@ -836,12 +1478,10 @@ impl FileSearchQuery {
 			if let Some(device_id) = path.device_id() {
 				if let Some(path_str) = path.path() {
 					// Join with directory_paths to filter by path
+					let pattern = LikeExpr::new(Self::path_prefix_pattern(path_str)).escape('\\');
 					query = query
 						.join(JoinType::LeftJoin, directory_paths::Relation::Entry.def())
-						.filter(
-							directory_paths::Column::Path
-								.like(&format!("{}%", path_str.to_string_lossy())),
-						);
+						.filter(directory_paths::Column::Path.like(pattern));
 				}
 			}
 		}
@@ -1003,7 +1643,7 @@ impl FileSearchQuery {
 						FROM fts
 						JOIN entries e ON e.id = fts.rowid
 						JOIN directory_paths dp ON dp.entry_id = e.parent_id
-						WHERE dp.path LIKE ?
+						WHERE dp.path LIKE ? ESCAPE '\'
 						ORDER BY fts.rank
 						LIMIT ? OFFSET ?
 					"#
@@ -1036,8 +1676,7 @@ impl FileSearchQuery {
 
 		let params = match &self.input.scope {
 			SearchScope::Path { path } if path.path().is_some() => {
-				let path_str = path.path().unwrap().to_string_lossy();
-				let like_pattern = format!("{}%", path_str);
+				let like_pattern = Self::path_prefix_pattern(path.path().unwrap());
 				tracing::info!(
 					"Path scope FTS5: query='{}', LIKE pattern='{}'",
 					query,
@@ -1221,6 +1860,7 @@ impl FileSearchQuery {
 	pub async fn execute_with_files(
 		&self,
 		db: &DatabaseConnection,
+		registry: &FileTypeRegistry,
 	) -> QueryResult<Vec<EnhancedFileSearchResult>> {
 		// Build device slug lookup map from database
 		use std::collections::HashMap;
@@ -1242,7 +1882,8 @@ impl FileSearchQuery {
 				self.execute_normal_search(db, &device_slug_map).await?
 			}
 			crate::ops::search::input::SearchMode::Full => {
-				self.execute_full_search(db, &device_slug_map).await?
+				self.execute_full_search(db, &device_slug_map, registry)
+					.await?
 			}
 		};
 
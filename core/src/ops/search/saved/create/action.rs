@@ -0,0 +1,74 @@
+//! Create saved search action
+
+use super::{input::CreateSavedSearchInput, output::CreateSavedSearchOutput};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::{error::ActionError, LibraryAction},
+		db::entities::saved_search,
+		sync::ChangeType,
+	},
+	library::Library,
+};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, NotSet, Set};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSavedSearchAction {
+	input: CreateSavedSearchInput,
+}
+
+impl CreateSavedSearchAction {
+	pub fn new(input: CreateSavedSearchInput) -> Self {
+		Self { input }
+	}
+}
+
+impl LibraryAction for CreateSavedSearchAction {
+	type Input = CreateSavedSearchInput;
+	type Output = CreateSavedSearchOutput;
+
+	fn from_input(input: CreateSavedSearchInput) -> Result<Self, String> {
+		input.validate()?;
+		Ok(CreateSavedSearchAction::new(input))
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let db = library.db().conn();
+
+		let now = Utc::now();
+		let saved_search = saved_search::ActiveModel {
+			id: NotSet,
+			uuid: Set(Uuid::new_v4()),
+			name: Set(self.input.name.trim().to_string()),
+			description: Set(self.input.description),
+			search: Set(serde_json::to_value(&self.input.search)?),
+			created_at: Set(now),
+			updated_at: Set(now),
+		}
+		.insert(db)
+		.await?;
+
+		library
+			.sync_model(&saved_search, ChangeType::Insert)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to sync saved search: {}", e)))?;
+
+		Ok(CreateSavedSearchOutput {
+			saved_search_id: saved_search.uuid,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"search.saved.create"
+	}
+}
+
+crate::register_library_action!(CreateSavedSearchAction, "search.saved.create");
@@ -0,0 +1,31 @@
+//! Input for creating a saved search
+
+use crate::ops::search::input::FileSearchInput;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CreateSavedSearchInput {
+	/// Display name for the smart collection
+	pub name: String,
+
+	pub description: Option<String>,
+
+	/// Search to persist; its pagination limit caps the collection's size
+	pub search: FileSearchInput,
+}
+
+impl CreateSavedSearchInput {
+	/// Validate the input
+	pub fn validate(&self) -> Result<(), String> {
+		if self.name.trim().is_empty() {
+			return Err("name cannot be empty".to_string());
+		}
+
+		if self.name.len() > 255 {
+			return Err("name cannot exceed 255 characters".to_string());
+		}
+
+		self.search.validate()
+	}
+}
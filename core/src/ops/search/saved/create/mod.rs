@@ -0,0 +1,9 @@
+//! Create a saved search
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::CreateSavedSearchAction;
+pub use input::CreateSavedSearchInput;
+pub use output::CreateSavedSearchOutput;
@@ -0,0 +1,11 @@
+//! Output for creating a saved search
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CreateSavedSearchOutput {
+	/// The created saved search's UUID
+	pub saved_search_id: Uuid,
+}
@@ -0,0 +1,81 @@
+//! Delete saved search action
+
+use super::{input::DeleteSavedSearchInput, output::DeleteSavedSearchOutput};
+use crate::{
+	context::CoreContext,
+	domain::{resource::EventEmitter, ItemType, SpaceItem},
+	infra::{
+		action::{error::ActionError, LibraryAction},
+		db::entities::{saved_search, space_item},
+		sync::ChangeType,
+	},
+	library::Library,
+};
+use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteSavedSearchAction {
+	input: DeleteSavedSearchInput,
+}
+
+impl LibraryAction for DeleteSavedSearchAction {
+	type Input = DeleteSavedSearchInput;
+	type Output = DeleteSavedSearchOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let db = library.db().conn();
+		let saved_search = saved_search::Entity::find()
+			.filter(saved_search::Column::Uuid.eq(self.input.saved_search_id))
+			.one(db)
+			.await?
+			.ok_or_else(|| ActionError::Validation {
+				field: "saved_search_id".to_string(),
+				message: format!("Saved search {} not found", self.input.saved_search_id),
+			})?;
+
+		saved_search.clone().delete(db).await?;
+		library
+			.sync_model(&saved_search, ChangeType::Delete)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to sync saved search: {}", e)))?;
+
+		// Unpin the search from any space it was pinned to
+		let item_type = serde_json::to_string(&ItemType::SavedSearch {
+			saved_search_id: saved_search.uuid,
+		})?;
+		let pinned = space_item::Entity::find()
+			.filter(space_item::Column::ItemType.eq(item_type))
+			.all(db)
+			.await?;
+
+		let space_items_removed = pinned.len();
+		for item in pinned {
+			item.clone().delete(db).await?;
+			library
+				.sync_model(&item, ChangeType::Delete)
+				.await
+				.map_err(|e| ActionError::Internal(format!("Failed to sync space item: {}", e)))?;
+			SpaceItem::emit_deleted(item.uuid, library.event_bus());
+		}
+
+		Ok(DeleteSavedSearchOutput {
+			space_items_removed,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"search.saved.delete"
+	}
+}
+
+crate::register_library_action!(DeleteSavedSearchAction, "search.saved.delete");
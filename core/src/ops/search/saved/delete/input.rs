@@ -0,0 +1,10 @@
+//! Input for deleting a saved search
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DeleteSavedSearchInput {
+	pub saved_search_id: Uuid,
+}
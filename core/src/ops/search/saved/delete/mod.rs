@@ -0,0 +1,9 @@
+//! Delete a saved search
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::DeleteSavedSearchAction;
+pub use input::DeleteSavedSearchInput;
+pub use output::DeleteSavedSearchOutput;
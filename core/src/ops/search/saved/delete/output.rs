@@ -0,0 +1,10 @@
+//! Output for deleting a saved search
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DeleteSavedSearchOutput {
+	/// Number of space items that pinned the search and were removed with it
+	pub space_items_removed: usize,
+}
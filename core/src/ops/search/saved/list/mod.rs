@@ -0,0 +1,9 @@
+//! List saved searches
+
+pub mod input;
+pub mod output;
+pub mod query;
+
+pub use input::ListSavedSearchesInput;
+pub use output::{ListSavedSearchesOutput, SavedSearchInfo};
+pub use query::ListSavedSearchesQuery;
@@ -0,0 +1,22 @@
+//! Output for listing saved searches
+
+use crate::ops::search::input::FileSearchInput;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListSavedSearchesOutput {
+	pub saved_searches: Vec<SavedSearchInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SavedSearchInfo {
+	pub id: Uuid,
+	pub name: String,
+	pub description: Option<String>,
+	pub search: FileSearchInput,
+	pub created_at: DateTime<Utc>,
+	pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,69 @@
+//! List saved searches query
+
+use super::{
+	input::ListSavedSearchesInput,
+	output::{ListSavedSearchesOutput, SavedSearchInfo},
+};
+use crate::{
+	context::CoreContext,
+	infra::{
+		db::entities::saved_search,
+		query::{LibraryQuery, QueryError, QueryResult},
+	},
+	ops::search::saved::membership::search_input,
+};
+use sea_orm::{EntityTrait, QueryOrder};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListSavedSearchesQuery {
+	pub input: ListSavedSearchesInput,
+}
+
+impl LibraryQuery for ListSavedSearchesQuery {
+	type Input = ListSavedSearchesInput;
+	type Output = ListSavedSearchesOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library in session".to_string()))?;
+		let library = context
+			.get_library(library_id)
+			.await
+			.ok_or(QueryError::LibraryNotFound(library_id))?;
+
+		let models = saved_search::Entity::find()
+			.order_by_asc(saved_search::Column::Name)
+			.all(library.db().conn())
+			.await?;
+
+		let saved_searches = models
+			.into_iter()
+			.map(|model| {
+				Ok(SavedSearchInfo {
+					search: search_input(&model)?,
+					id: model.uuid,
+					name: model.name,
+					description: model.description,
+					created_at: model.created_at,
+					updated_at: model.updated_at,
+				})
+			})
+			.collect::<QueryResult<Vec<_>>>()?;
+
+		Ok(ListSavedSearchesOutput { saved_searches })
+	}
+}
+
+crate::register_library_query!(ListSavedSearchesQuery, "search.saved.list");
@@ -0,0 +1,178 @@
+//! Smart collection membership
+//!
+//! A saved search's members are every entry its search returns; its page size only
+//! applies when the search is run. Membership isn't stored or synced: each device
+//! evaluates it against its own index and diffs against the previous evaluation to tell
+//! clients what changed.
+
+use crate::{
+	filetype::FileTypeRegistry,
+	infra::{
+		db::entities::{entry, saved_search},
+		query::{QueryError, QueryResult},
+	},
+	ops::search::{
+		input::{FileSearchInput, SearchScope},
+		query::FileSearchQuery,
+	},
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
+use uuid::Uuid;
+
+/// Results fetched per query while walking a saved search's full result set
+const EVALUATION_PAGE_SIZE: u32 = 1000;
+
+/// Entries that joined or left a saved search since it was last evaluated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MembershipChange {
+	pub saved_search_id: Uuid,
+	pub added: Vec<Uuid>,
+	pub removed: Vec<Uuid>,
+}
+
+/// Decode the search a saved search persists
+pub fn search_input(model: &saved_search::Model) -> QueryResult<FileSearchInput> {
+	serde_json::from_value(model.search.clone())
+		.map_err(|e| QueryError::Internal(format!("Invalid saved search {}: {}", model.uuid, e)))
+}
+
+/// UUIDs of every entry a saved search currently returns, across all pages
+pub async fn evaluate(
+	db: &DatabaseConnection,
+	registry: &FileTypeRegistry,
+	model: &saved_search::Model,
+) -> QueryResult<HashSet<Uuid>> {
+	let mut input = search_input(model)?;
+	input.pagination.limit = EVALUATION_PAGE_SIZE;
+	input.pagination.offset = 0;
+
+	let mut members = HashSet::new();
+	loop {
+		let page = FileSearchQuery::new(input.clone())
+			.execute_with_files(db, registry)
+			.await?;
+		let fetched = page.len();
+		members.extend(page.into_iter().map(|result| result.file.id));
+
+		if fetched < EVALUATION_PAGE_SIZE as usize {
+			return Ok(members);
+		}
+		input.pagination.offset += EVALUATION_PAGE_SIZE;
+	}
+}
+
+/// Last known members of every saved search in a library
+#[derive(Debug, Default)]
+pub struct MembershipTracker {
+	members: HashMap<Uuid, HashSet<Uuid>>,
+	/// Searches as of the last refresh, to tell which changes can affect them
+	searches: Vec<FileSearchInput>,
+}
+
+impl MembershipTracker {
+	/// Re-evaluate every saved search, returning those whose membership changed
+	///
+	/// A search seen for the first time only records its members; clients load those
+	/// through `search.saved.run`. Deleted searches are forgotten.
+	pub async fn refresh(
+		&mut self,
+		db: &DatabaseConnection,
+		registry: &FileTypeRegistry,
+	) -> QueryResult<Vec<MembershipChange>> {
+		let searches = saved_search::Entity::find().all(db).await?;
+		self.searches = searches
+			.iter()
+			.filter_map(|search| search_input(search).ok())
+			.collect();
+
+		let mut members = HashMap::with_capacity(searches.len());
+		let mut changes = Vec::new();
+		for search in searches {
+			let current = match evaluate(db, registry, &search).await {
+				Ok(current) => current,
+				Err(e) => {
+					warn!(saved_search = %search.uuid, "Failed to evaluate saved search: {}", e);
+					// Keep what we knew so the next successful evaluation diffs against it
+					if let Some(previous) = self.members.remove(&search.uuid) {
+						members.insert(search.uuid, previous);
+					}
+					continue;
+				}
+			};
+
+			if let Some(previous) = self.members.get(&search.uuid) {
+				let mut added: Vec<Uuid> = current.difference(previous).copied().collect();
+				let mut removed: Vec<Uuid> = previous.difference(&current).copied().collect();
+				if !added.is_empty() || !removed.is_empty() {
+					added.sort();
+					removed.sort();
+					changes.push(MembershipChange {
+						saved_search_id: search.uuid,
+						added,
+						removed,
+					});
+				}
+			}
+			members.insert(search.uuid, current);
+		}
+
+		self.members = members;
+		Ok(changes)
+	}
+
+	/// Whether a change to these resources can move entries in or out of a saved search
+	///
+	/// Files matter when they are members or belong to this library; tags and locations
+	/// only when a search filters on them. Other resource types never do.
+	pub async fn is_affected_by(
+		&self,
+		db: &DatabaseConnection,
+		resource_type: &str,
+		ids: &[Uuid],
+	) -> QueryResult<bool> {
+		if self.searches.is_empty() || ids.is_empty() {
+			return Ok(false);
+		}
+
+		match resource_type {
+			"file" => {
+				let is_member = self
+					.members
+					.values()
+					.any(|members| ids.iter().any(|id| members.contains(id)));
+				if is_member {
+					return Ok(true);
+				}
+
+				let in_library = entry::Entity::find()
+					.filter(entry::Column::Uuid.is_in(ids.iter().copied()))
+					.count(db)
+					.await?;
+				Ok(in_library > 0)
+			}
+			"tag" => Ok(self.searches.iter().any(|search| {
+				search.filters.tags.as_ref().is_some_and(|tags| {
+					tags.include
+						.iter()
+						.chain(&tags.exclude)
+						.any(|tag| ids.contains(tag))
+				})
+			})),
+			"location" => Ok(self.searches.iter().any(|search| {
+				let scoped = matches!(
+					&search.scope,
+					SearchScope::Location { location_id } if ids.contains(location_id)
+				);
+				let filtered = search
+					.filters
+					.locations
+					.as_ref()
+					.is_some_and(|locations| locations.iter().any(|id| ids.contains(id)));
+				scoped || filtered
+			})),
+			_ => Ok(false),
+		}
+	}
+}
@@ -0,0 +1,18 @@
+//! Saved searches
+//!
+//! A saved search persists a `FileSearchInput` (query, scope, filters, sort and page size)
+//! so it can be shown as a smart collection or pinned as a `SpaceItem`. The definition
+//! syncs between devices; membership is evaluated locally and re-evaluated as entries
+//! change, with a `SavedSearchMembershipChanged` event when it does.
+
+pub mod create;
+pub mod delete;
+pub mod list;
+pub mod membership;
+pub mod run;
+
+pub use create::{CreateSavedSearchAction, CreateSavedSearchInput, CreateSavedSearchOutput};
+pub use delete::{DeleteSavedSearchAction, DeleteSavedSearchInput, DeleteSavedSearchOutput};
+pub use list::{ListSavedSearchesOutput, ListSavedSearchesQuery, SavedSearchInfo};
+pub use membership::{MembershipChange, MembershipTracker};
+pub use run::{RunSavedSearchInput, RunSavedSearchQuery};
@@ -0,0 +1,15 @@
+//! Input for running a saved search
+
+use crate::ops::search::input::PaginationOptions;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RunSavedSearchInput {
+	pub saved_search_id: Uuid,
+
+	/// Page to load instead of the saved one (None = the saved search's own page)
+	#[serde(default)]
+	pub pagination: Option<PaginationOptions>,
+}
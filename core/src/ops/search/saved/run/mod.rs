@@ -0,0 +1,7 @@
+//! Run a saved search
+
+pub mod input;
+pub mod query;
+
+pub use input::RunSavedSearchInput;
+pub use query::RunSavedSearchQuery;
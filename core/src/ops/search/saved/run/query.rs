@@ -0,0 +1,64 @@
+//! Run saved search query
+
+use super::input::RunSavedSearchInput;
+use crate::{
+	context::CoreContext,
+	infra::{
+		db::entities::saved_search,
+		query::{LibraryQuery, QueryError, QueryResult},
+	},
+	ops::search::{
+		output::FileSearchOutput, query::FileSearchQuery, saved::membership::search_input,
+	},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+/// Runs a saved search exactly as `search.files` would run its input
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RunSavedSearchQuery {
+	pub input: RunSavedSearchInput,
+}
+
+impl LibraryQuery for RunSavedSearchQuery {
+	type Input = RunSavedSearchInput;
+	type Output = FileSearchOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library in session".to_string()))?;
+		let library = context
+			.get_library(library_id)
+			.await
+			.ok_or(QueryError::LibraryNotFound(library_id))?;
+
+		let model = saved_search::Entity::find()
+			.filter(saved_search::Column::Uuid.eq(self.input.saved_search_id))
+			.one(library.db().conn())
+			.await?
+			.ok_or_else(|| QueryError::Validation {
+				field: "saved_search_id".to_string(),
+				message: format!("Saved search {} not found", self.input.saved_search_id),
+			})?;
+
+		let mut search = search_input(&model)?;
+		if let Some(pagination) = self.input.pagination {
+			search.pagination = pagination;
+		}
+
+		FileSearchQuery::new(search).execute(context, session).await
+	}
+}
+
+crate::register_library_query!(RunSavedSearchQuery, "search.saved.run");
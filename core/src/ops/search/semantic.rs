@@ -0,0 +1,106 @@
+//! Vector similarity for hybrid search
+//!
+//! Embeds the query with the same model as the library's vector index and blends the
+//! nearest files into FTS5 results by reciprocal rank fusion, which only needs each
+//! ranking's order and so sidesteps the different scales of BM25 and cosine scores.
+
+use crate::{library::Library, ops::media::embedding::VectorHit};
+use std::collections::HashMap;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Cosine similarity below which files matching only by meaning are left out
+pub const MIN_SEMANTIC_SIMILARITY: f32 = 0.35;
+
+/// Reciprocal rank fusion constant; larger values flatten the gap between ranks
+const RRF_K: f32 = 60.0;
+
+/// Embed the query text with the model of the library's vector index
+///
+/// Returns `None` when semantic search is unavailable: the `embeddings` feature is
+/// off, the model isn't downloaded, or nothing in the library is embedded yet.
+#[cfg(feature = "embeddings")]
+pub async fn embed_query(library: &Library, query: &str) -> Option<Vec<f32>> {
+	use crate::ops::{
+		media::embedding::TextEncoder,
+		models::{EmbeddingModel, EmbeddingModelManager},
+	};
+
+	// Don't load the model for a library with nothing to compare against
+	let index = library.embedding_index().await.ok()?;
+	if index.read().await.is_empty() {
+		return None;
+	}
+
+	// Searches never trigger the download, the embedding job does
+	let model = EmbeddingModel::default();
+	let data_dir = crate::config::default_data_dir().ok()?;
+	if !EmbeddingModelManager::new(&data_dir)
+		.is_downloaded(&model)
+		.await
+	{
+		return None;
+	}
+
+	let query = query.to_string();
+	let embedded = tokio::task::spawn_blocking(move || {
+		let encoder = TextEncoder::shared(model, &data_dir)?;
+		let mut encoder = encoder.lock().unwrap_or_else(|e| e.into_inner());
+		encoder.embed(&[query])
+	})
+	.await;
+
+	match embedded {
+		Ok(Ok(mut vectors)) => vectors.pop(),
+		Ok(Err(e)) => {
+			warn!("Failed to embed search query: {}", e);
+			None
+		}
+		Err(e) => {
+			warn!("Query embedding panicked: {}", e);
+			None
+		}
+	}
+}
+
+#[cfg(not(feature = "embeddings"))]
+pub async fn embed_query(_library: &Library, _query: &str) -> Option<Vec<f32>> {
+	None
+}
+
+/// Content identities nearest to a query vector
+///
+/// Returns `None` when the library's vector index can't be opened or is empty.
+pub async fn nearest_contents(
+	library: &Library,
+	vector: &[f32],
+	limit: usize,
+) -> Option<Vec<VectorHit>> {
+	let index = match library.embedding_index().await {
+		Ok(index) => index,
+		Err(e) => {
+			warn!("Failed to open vector index: {}", e);
+			return None;
+		}
+	};
+
+	let index = index.read().await;
+	if index.is_empty() {
+		return None;
+	}
+	Some(index.search(vector, limit))
+}
+
+/// Fuse rankings of the same items, best first, into one score per item
+///
+/// Each ranking contributes `1 / (RRF_K + rank)`, so items near the top of
+/// several rankings score highest.
+pub fn reciprocal_rank_fusion(rankings: &[&[Uuid]]) -> HashMap<Uuid, f32> {
+	let mut scores = HashMap::new();
+	for ranking in rankings {
+		for (rank, id) in ranking.iter().enumerate() {
+			*scores.entry(*id).or_insert(0.0) += 1.0 / (RRF_K + rank as f32 + 1.0);
+		}
+	}
+	scores
+}
@@ -127,4 +127,51 @@ mod tests {
 		assert_eq!(highlights[1].start, 0);
 		assert_eq!(highlights[1].end, 4); // "test" extension
 	}
+
+	#[test]
+	fn test_content_highlight_extraction() {
+		use crate::ops::search::query::FileSearchQuery;
+
+		let (text, highlights) = FileSearchQuery::extract_content_highlights(
+			"…the \u{2}quarterly\u{3} budget\nfor \u{2}quarterly\u{3} review…",
+		);
+
+		assert_eq!(text, "…the quarterly budget for quarterly review…");
+		assert_eq!(highlights.len(), 2);
+		assert!(highlights.iter().all(|h| h.field == "content"));
+		assert_eq!(&text[highlights[0].start..highlights[0].end], "quarterly");
+		assert_eq!(&text[highlights[1].start..highlights[1].end], "quarterly");
+	}
+
+	#[test]
+	fn test_path_prefix_pattern_escapes_wildcards() {
+		use crate::ops::search::query::FileSearchQuery;
+		use std::path::Path;
+
+		assert_eq!(
+			FileSearchQuery::path_prefix_pattern(Path::new("/photos/100%_done")),
+			"/photos/100\\%\\_done%"
+		);
+		assert_eq!(
+			FileSearchQuery::path_prefix_pattern(Path::new("/it's/a\\b")),
+			"/it's/a\\\\b%"
+		);
+	}
+
+	#[test]
+	fn test_reciprocal_rank_fusion() {
+		use crate::ops::search::semantic::reciprocal_rank_fusion;
+		use uuid::Uuid;
+
+		let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+		let keyword = [a, b];
+		let semantic = [b, c];
+
+		let scores = reciprocal_rank_fusion(&[&keyword, &semantic]);
+
+		// Ranked by both beats first place in only one
+		assert!(scores[&b] > scores[&a]);
+		assert!(scores[&a] > scores[&c]);
+		assert_eq!(scores.len(), 3);
+	}
 }
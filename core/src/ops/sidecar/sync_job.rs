@@ -136,7 +136,7 @@ impl JobHandler for SidecarSyncJob {
 		ctx.progress(Progress::count(0, plans.len()));
 
 		use crate::ops::files::copy::strategy::RemoteTransferStrategy;
-		let strategy = RemoteTransferStrategy;
+		let strategy = RemoteTransferStrategy::default();
 
 		let mut transferred = 0;
 		let mut failed = 0;
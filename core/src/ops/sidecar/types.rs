@@ -12,6 +12,8 @@ pub enum SidecarKind {
 	Ocr,
 	Transcript,
 	GaussianSplat,
+	PerceptualHash,
+	Metadata,
 }
 
 impl SidecarKind {
@@ -24,6 +26,8 @@ impl SidecarKind {
 			Self::Ocr => "ocr",
 			Self::Transcript => "transcript",
 			Self::GaussianSplat => "gaussian_splat",
+			Self::PerceptualHash => "perceptual_hash",
+			Self::Metadata => "metadata",
 		}
 	}
 
@@ -36,6 +40,8 @@ impl SidecarKind {
 			Self::Ocr => "ocr",
 			Self::Transcript => "transcript",
 			Self::GaussianSplat => "gaussian_splats",
+			Self::PerceptualHash => "perceptual_hashes",
+			Self::Metadata => "metadata",
 		}
 	}
 }
@@ -58,6 +64,8 @@ impl TryFrom<&str> for SidecarKind {
 			"ocr" => Ok(Self::Ocr),
 			"transcript" => Ok(Self::Transcript),
 			"gaussian_splat" => Ok(Self::GaussianSplat),
+			"perceptual_hash" => Ok(Self::PerceptualHash),
+			"metadata" => Ok(Self::Metadata),
 			_ => Err(format!("Invalid sidecar kind: {}", value)),
 		}
 	}
@@ -0,0 +1,109 @@
+//! List sync conflicts query
+
+use crate::context::CoreContext;
+use crate::infra::query::{LibraryQuery, QueryError, QueryResult};
+use crate::infra::sync::{FieldConflict, SyncEventQuery, SyncEventType};
+use std::sync::Arc;
+use tracing::warn;
+
+use super::{ListSyncConflictsInput, ListSyncConflictsOutput, SyncConflictRecord};
+
+/// List same-field conflicts between concurrent edits of shared resources
+///
+/// Conflicts are resolved by HLC when they are received; this lists them from the
+/// sync event log so the losing values can be reviewed.
+pub struct ListSyncConflicts {
+	pub input: ListSyncConflictsInput,
+}
+
+impl LibraryQuery for ListSyncConflicts {
+	type Input = ListSyncConflictsInput;
+	type Output = ListSyncConflictsOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library in session".to_string()))?;
+
+		let library = context
+			.libraries()
+			.await
+			.get_library(library_id)
+			.await
+			.ok_or_else(|| QueryError::LibraryNotFound(library_id))?;
+
+		let sync_service = library
+			.sync_service()
+			.ok_or_else(|| QueryError::Internal("Sync service not available".to_string()))?;
+
+		let mut query =
+			SyncEventQuery::new(library_id).with_event_types(vec![SyncEventType::FieldConflict]);
+
+		if let Some(model_type) = self.input.model_type {
+			query = query.with_model_type(model_type);
+		}
+
+		if let Some(peer_id) = self.input.peer_id {
+			query = query.with_peer(peer_id);
+		}
+
+		// The record lives in the event details, so that filter is applied after the query
+		if self.input.record_uuid.is_some() {
+			query.limit = None;
+		} else {
+			if let Some(limit) = self.input.limit {
+				query = query.with_limit(limit);
+			}
+			if let Some(offset) = self.input.offset {
+				query = query.with_offset(offset);
+			}
+		}
+
+		let events = sync_service
+			.event_logger()
+			.query(query)
+			.await
+			.map_err(|e| QueryError::Internal(format!("Failed to query events: {}", e)))?;
+
+		let mut conflicts: Vec<SyncConflictRecord> = events
+			.into_iter()
+			.filter_map(|event| {
+				let details = event.details?;
+				match serde_json::from_value::<FieldConflict>(details) {
+					Ok(conflict) => Some(SyncConflictRecord {
+						id: event.id,
+						timestamp: event.timestamp,
+						peer_device_id: event.peer_device_id,
+						conflict,
+					}),
+					Err(e) => {
+						warn!("Skipping unreadable conflict event {:?}: {}", event.id, e);
+						None
+					}
+				}
+			})
+			.collect();
+
+		if let Some(record_uuid) = self.input.record_uuid {
+			conflicts = conflicts
+				.into_iter()
+				.filter(|record| record.conflict.record_uuid == record_uuid)
+				.skip(self.input.offset.unwrap_or(0) as usize)
+				.take(self.input.limit.map_or(usize::MAX, |limit| limit as usize))
+				.collect();
+		}
+
+		Ok(ListSyncConflictsOutput { conflicts })
+	}
+}
+
+// Register the query
+crate::register_library_query!(ListSyncConflicts, "sync.conflicts.list");
@@ -0,0 +1,28 @@
+//! Input types for list_sync_conflicts query
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListSyncConflictsInput {
+	/// Filter by model type (e.g., "tag", "space", "user_metadata")
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub model_type: Option<String>,
+
+	/// Filter by record
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub record_uuid: Option<Uuid>,
+
+	/// Filter by the peer whose edit conflicted with ours
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub peer_id: Option<Uuid>,
+
+	/// Maximum number of results
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub limit: Option<u32>,
+
+	/// Offset for pagination
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub offset: Option<u32>,
+}
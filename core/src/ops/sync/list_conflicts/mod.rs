@@ -0,0 +1,9 @@
+//! List sync conflicts operation
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::ListSyncConflicts;
+pub use input::ListSyncConflictsInput;
+pub use output::{ListSyncConflictsOutput, SyncConflictRecord};
@@ -0,0 +1,25 @@
+//! Output types for list_sync_conflicts query
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+use crate::infra::sync::FieldConflict;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListSyncConflictsOutput {
+	pub conflicts: Vec<SyncConflictRecord>,
+}
+
+/// A same-field conflict recorded in the sync event log
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SyncConflictRecord {
+	/// Sync event log ID
+	pub id: Option<i64>,
+	/// When the conflicting change was received
+	pub timestamp: DateTime<Utc>,
+	/// Device whose edit conflicted with ours
+	pub peer_device_id: Option<Uuid>,
+	pub conflict: FieldConflict,
+}
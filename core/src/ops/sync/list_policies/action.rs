@@ -0,0 +1,64 @@
+//! List sync policies query
+
+use crate::context::CoreContext;
+use crate::infra::db::entities::device;
+use crate::infra::query::{LibraryQuery, QueryError, QueryResult};
+use sea_orm::{EntityTrait, QueryOrder};
+use std::sync::Arc;
+
+use super::{ListSyncPoliciesInput, ListSyncPoliciesOutput};
+
+/// List what replicates to each device of the library
+pub struct ListSyncPolicies {
+	pub input: ListSyncPoliciesInput,
+}
+
+impl LibraryQuery for ListSyncPolicies {
+	type Input = ListSyncPoliciesInput;
+	type Output = ListSyncPoliciesOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library in session".to_string()))?;
+
+		let library = context
+			.libraries()
+			.await
+			.get_library(library_id)
+			.await
+			.ok_or_else(|| QueryError::LibraryNotFound(library_id))?;
+
+		let sync_service = library
+			.sync_service()
+			.ok_or_else(|| QueryError::Internal("Sync service not available".to_string()))?;
+		let policies = sync_service.peer_sync().policies();
+
+		let devices = device::Entity::find()
+			.order_by_asc(device::Column::Name)
+			.all(library.db().conn())
+			.await?;
+
+		let mut output = Vec::with_capacity(devices.len());
+		for device in devices {
+			output.push(
+				policies.for_device(device.uuid).await.map_err(|e| {
+					QueryError::Internal(format!("Failed to load sync policy: {}", e))
+				})?,
+			);
+		}
+
+		Ok(ListSyncPoliciesOutput { policies: output })
+	}
+}
+
+// Register the query
+crate::register_library_query!(ListSyncPolicies, "sync.policies.list");
@@ -0,0 +1,7 @@
+//! Input types for list_sync_policies query
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct ListSyncPoliciesInput {}
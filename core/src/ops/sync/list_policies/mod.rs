@@ -0,0 +1,9 @@
+//! List sync policies operation
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::ListSyncPolicies;
+pub use input::ListSyncPoliciesInput;
+pub use output::ListSyncPoliciesOutput;
@@ -0,0 +1,11 @@
+//! Output types for list_sync_policies query
+
+use crate::service::sync::SyncPolicy;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListSyncPoliciesOutput {
+	/// Policy of every device in the library, the full tier where none was set
+	pub policies: Vec<SyncPolicy>,
+}
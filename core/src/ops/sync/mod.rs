@@ -4,3 +4,6 @@ pub mod get_activity;
 pub mod get_event_log;
 pub mod get_metrics;
 pub mod get_sync_partners;
+pub mod list_conflicts;
+pub mod list_policies;
+pub mod set_policy;
@@ -0,0 +1,143 @@
+//! Set sync policy action
+
+use super::{input::SetSyncPolicyInput, output::SetSyncPolicyOutput};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::{error::ActionError, LibraryAction},
+		db::entities::{device, sync_policy},
+		sync::{get_table_name, ChangeType},
+	},
+	library::Library,
+	service::sync::SyncPolicy,
+};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, NotSet, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Set what other devices replicate to a device
+///
+/// The policy syncs to every device, and the devices holding the data filter by it.
+/// Narrowing a policy stops future replication but keeps what the device already has;
+/// widening it makes the device re-sync to fetch what it was missing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetSyncPolicyAction {
+	input: SetSyncPolicyInput,
+}
+
+impl SetSyncPolicyAction {
+	pub fn new(input: SetSyncPolicyInput) -> Self {
+		Self { input }
+	}
+}
+
+impl LibraryAction for SetSyncPolicyAction {
+	type Input = SetSyncPolicyInput;
+	type Output = SetSyncPolicyOutput;
+
+	fn from_input(input: SetSyncPolicyInput) -> Result<Self, String> {
+		input.validate()?;
+		Ok(SetSyncPolicyAction::new(input))
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let db = library.db().conn();
+		let sync_service = library
+			.sync_service()
+			.ok_or_else(|| ActionError::Internal("Sync service not available".to_string()))?;
+		let peer_sync = sync_service.peer_sync();
+
+		let device_id = self
+			.input
+			.device_id
+			.unwrap_or_else(|| peer_sync.device_id());
+		if device::Entity::find()
+			.filter(device::Column::Uuid.eq(device_id))
+			.one(db)
+			.await?
+			.is_none()
+		{
+			return Err(ActionError::DeviceNotFound(device_id));
+		}
+
+		for model_type in &self.input.model_types {
+			if get_table_name(model_type).await.is_none() {
+				return Err(ActionError::Validation {
+					field: "model_types".to_string(),
+					message: format!("Unknown model type: {}", model_type),
+				});
+			}
+		}
+
+		let existing = sync_policy::Entity::find()
+			.filter(sync_policy::Column::DeviceUuid.eq(device_id))
+			.one(db)
+			.await?;
+
+		let now = Utc::now();
+		let tier = self.input.tier.as_str().to_string();
+		let locations = serde_json::to_value(&self.input.locations)?;
+		let volumes = serde_json::to_value(&self.input.volumes)?;
+		let tags = serde_json::to_value(&self.input.tags)?;
+		let model_types = serde_json::to_value(&self.input.model_types)?;
+
+		let (model, change_type) = match existing {
+			Some(existing) => {
+				let mut active: sync_policy::ActiveModel = existing.into();
+				active.tier = Set(tier);
+				active.locations = Set(locations);
+				active.volumes = Set(volumes);
+				active.tags = Set(tags);
+				active.model_types = Set(model_types);
+				active.updated_at = Set(now);
+				(active.update(db).await?, ChangeType::Update)
+			}
+			None => {
+				let model = sync_policy::ActiveModel {
+					id: NotSet,
+					device_uuid: Set(device_id),
+					tier: Set(tier),
+					locations: Set(locations),
+					volumes: Set(volumes),
+					tags: Set(tags),
+					model_types: Set(model_types),
+					created_at: Set(now),
+					updated_at: Set(now),
+				}
+				.insert(db)
+				.await?;
+				(model, ChangeType::Insert)
+			}
+		};
+
+		library
+			.sync_model(&model, change_type)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to sync policy: {}", e)))?;
+
+		if device_id == peer_sync.device_id() {
+			// Best-effort: the policy is saved and peers filter by it either way
+			if let Err(e) = peer_sync.resync_after_policy_change().await {
+				warn!("Failed to re-sync after sync policy change: {}", e);
+			}
+		} else {
+			peer_sync.policies().invalidate().await;
+		}
+
+		Ok(SetSyncPolicyOutput {
+			policy: SyncPolicy::from_model(&model),
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"sync.policy.set"
+	}
+}
+
+crate::register_library_action!(SetSyncPolicyAction, "sync.policy.set");
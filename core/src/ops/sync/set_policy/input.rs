@@ -0,0 +1,49 @@
+//! Input types for set_sync_policy action
+
+use crate::service::sync::SyncTier;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SetSyncPolicyInput {
+	/// Device the policy applies to (defaults to this device)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub device_id: Option<Uuid>,
+
+	pub tier: SyncTier,
+
+	/// Locations whose entries replicate (selective tier)
+	#[serde(default)]
+	pub locations: Vec<Uuid>,
+
+	/// Volumes whose entries replicate (selective tier)
+	#[serde(default)]
+	pub volumes: Vec<Uuid>,
+
+	/// Tags whose tagged entries replicate (selective tier)
+	#[serde(default)]
+	pub tags: Vec<Uuid>,
+
+	/// Model types that replicate, all when empty (selective tier, e.g. "tag", "entry")
+	#[serde(default)]
+	pub model_types: Vec<String>,
+}
+
+impl SetSyncPolicyInput {
+	/// Validate the input
+	pub fn validate(&self) -> Result<(), String> {
+		let has_filters = !(self.locations.is_empty()
+			&& self.volumes.is_empty()
+			&& self.tags.is_empty()
+			&& self.model_types.is_empty());
+
+		if has_filters && self.tier != SyncTier::Selective {
+			return Err(
+				"locations, volumes, tags and model types require the selective tier".to_string(),
+			);
+		}
+
+		Ok(())
+	}
+}
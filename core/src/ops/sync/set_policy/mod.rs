@@ -0,0 +1,9 @@
+//! Set sync policy operation
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::SetSyncPolicyAction;
+pub use input::SetSyncPolicyInput;
+pub use output::SetSyncPolicyOutput;
@@ -0,0 +1,10 @@
+//! Output types for set_sync_policy action
+
+use crate::service::sync::SyncPolicy;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SetSyncPolicyOutput {
+	pub policy: SyncPolicy,
+}
@@ -9,7 +9,7 @@ use crate::{
 	domain::tag::{TagApplication, TagSource},
 	infra::action::{error::ActionError, LibraryAction},
 	library::Library,
-	ops::metadata::manager::UserMetadataManager,
+	ops::{action::UndoOperation, metadata::manager::UserMetadataManager},
 };
 use chrono::Utc;
 use sea_orm::{DatabaseConnection, EntityTrait};
@@ -38,6 +38,15 @@ impl LibraryAction for ApplyTagsAction {
 		Ok(ApplyTagsAction::new(input))
 	}
 
+	async fn undo_operation(
+		&self,
+		library: &Arc<Library>,
+	) -> Result<Option<UndoOperation>, ActionError> {
+		UndoOperation::capture_tags(library.db().conn(), &self.input)
+			.await
+			.map(Some)
+	}
+
 	async fn execute(
 		self,
 		library: Arc<Library>,
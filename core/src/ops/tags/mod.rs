@@ -1,12 +1,13 @@
 //! Tag operations module
 //!
 //! This module contains business logic for managing semantic tags,
-//! including creation, application, search, and hierarchy management.
+//! including creation, application, search, hierarchy management and auto-tag rules.
 
 pub mod apply;
 pub mod create;
 pub mod facade;
 pub mod manager;
+pub mod rules;
 pub mod search;
 pub mod validation;
 
@@ -0,0 +1,80 @@
+//! Apply auto-tag rules action
+
+use super::{input::ApplyAutoTagRulesInput, output::ApplyAutoTagRulesOutput};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::{error::ActionError, LibraryAction},
+		db::entities::{auto_tag_rule, location},
+	},
+	library::Library,
+	ops::tags::rules::job::{AutoTagJob, AutoTagJobConfig},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyAutoTagRulesAction {
+	input: ApplyAutoTagRulesInput,
+}
+
+impl LibraryAction for ApplyAutoTagRulesAction {
+	type Input = ApplyAutoTagRulesInput;
+	type Output = ApplyAutoTagRulesOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let db = library.db().conn();
+
+		if let Some(rule_id) = self.input.rule_id {
+			if auto_tag_rule::Entity::find()
+				.filter(auto_tag_rule::Column::Uuid.eq(rule_id))
+				.one(db)
+				.await?
+				.is_none()
+			{
+				return Err(ActionError::Validation {
+					field: "rule_id".to_string(),
+					message: format!("Auto-tag rule {} not found", rule_id),
+				});
+			}
+		}
+
+		if let Some(location_id) = self.input.location_id {
+			if location::Entity::find()
+				.filter(location::Column::Uuid.eq(location_id))
+				.one(db)
+				.await?
+				.is_none()
+			{
+				return Err(ActionError::LocationNotFound(location_id));
+			}
+		}
+
+		let job = AutoTagJob::new(AutoTagJobConfig {
+			rule_id: self.input.rule_id,
+			location_id: self.input.location_id,
+		});
+		let handle = library.jobs().dispatch(job).await.map_err(|e| {
+			ActionError::Internal(format!("Failed to dispatch auto-tag job: {}", e))
+		})?;
+
+		Ok(ApplyAutoTagRulesOutput {
+			job_id: handle.id().into(),
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"tags.rules.apply"
+	}
+}
+
+crate::register_library_action!(ApplyAutoTagRulesAction, "tags.rules.apply");
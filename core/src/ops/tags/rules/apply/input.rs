@@ -0,0 +1,14 @@
+//! Input for applying auto-tag rules
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct ApplyAutoTagRulesInput {
+	/// Only apply this rule (None = every enabled rule)
+	pub rule_id: Option<Uuid>,
+
+	/// Only tag files in this location (None = the whole library)
+	pub location_id: Option<Uuid>,
+}
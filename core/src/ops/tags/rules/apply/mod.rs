@@ -0,0 +1,9 @@
+//! Apply auto-tag rules to already indexed files
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::ApplyAutoTagRulesAction;
+pub use input::ApplyAutoTagRulesInput;
+pub use output::ApplyAutoTagRulesOutput;
@@ -0,0 +1,10 @@
+//! Output for applying auto-tag rules
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ApplyAutoTagRulesOutput {
+	pub job_id: Uuid,
+}
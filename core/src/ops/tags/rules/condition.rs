@@ -0,0 +1,363 @@
+//! Auto-tag rule conditions and matching
+//!
+//! Conditions are stored as JSON on the rule and compiled once per run, so glob
+//! patterns aren't re-parsed for every file.
+
+use crate::{domain::ContentKind, infra::db::entities::image_media_data};
+use chrono::{DateTime, Utc};
+use globset::{Glob, GlobMatcher};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+
+/// A single condition a file must satisfy for a rule to match
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AutoTagCondition {
+	/// Full path matches a glob pattern, e.g. `**/Screenshots/**`
+	PathGlob { pattern: String },
+	/// Extension is one of these (case-insensitive, without the dot)
+	Extension { extensions: Vec<String> },
+	/// Identified content kind is one of these
+	ContentKind { kinds: Vec<ContentKind> },
+	/// An EXIF field from the file's image media data
+	Exif {
+		field: ExifField,
+		matches: ExifMatch,
+	},
+	/// Size in bytes is within the range (inclusive)
+	Size { min: Option<u64>, max: Option<u64> },
+	/// A date is within the range (inclusive)
+	Date {
+		field: RuleDateField,
+		start: Option<DateTime<Utc>>,
+		end: Option<DateTime<Utc>>,
+	},
+}
+
+/// EXIF fields rules can match on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ExifField {
+	CameraMake,
+	CameraModel,
+	LensModel,
+	FocalLength,
+	Aperture,
+	ShutterSpeed,
+	Iso,
+	Artist,
+	Copyright,
+	Description,
+}
+
+/// How an EXIF field is compared (case-insensitive)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Type)]
+#[serde(tag = "op", content = "value", rename_all = "snake_case")]
+pub enum ExifMatch {
+	/// The field has any value
+	Present,
+	Equals(String),
+	Contains(String),
+}
+
+/// Dates rules can match on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleDateField {
+	CreatedAt,
+	ModifiedAt,
+	/// EXIF capture date
+	DateTaken,
+}
+
+/// Everything a rule can look at for one file
+#[derive(Debug, Clone)]
+pub struct RuleSubject {
+	pub path: PathBuf,
+	pub extension: Option<String>,
+	pub size: u64,
+	pub created_at: DateTime<Utc>,
+	pub modified_at: DateTime<Utc>,
+	/// None until content identification has run
+	pub kind: Option<ContentKind>,
+	/// None until media metadata has been extracted
+	pub image: Option<image_media_data::Model>,
+}
+
+/// Conditions compiled for matching
+#[derive(Debug, Clone)]
+pub struct RuleMatcher {
+	match_all: bool,
+	conditions: Vec<CompiledCondition>,
+}
+
+#[derive(Debug, Clone)]
+enum CompiledCondition {
+	PathGlob(GlobMatcher),
+	Other(AutoTagCondition),
+}
+
+impl RuleMatcher {
+	/// Compile conditions, failing on invalid glob patterns
+	pub fn compile(conditions: &[AutoTagCondition], match_all: bool) -> Result<Self, String> {
+		let conditions = conditions
+			.iter()
+			.map(|condition| match condition {
+				AutoTagCondition::PathGlob { pattern } => Glob::new(pattern)
+					.map(|glob| CompiledCondition::PathGlob(glob.compile_matcher()))
+					.map_err(|e| format!("Invalid glob pattern '{}': {}", pattern, e)),
+				other => Ok(CompiledCondition::Other(other.clone())),
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(Self {
+			match_all,
+			conditions,
+		})
+	}
+
+	/// Whether the subject satisfies the rule; a rule without conditions matches nothing
+	pub fn matches(&self, subject: &RuleSubject) -> bool {
+		if self.conditions.is_empty() {
+			return false;
+		}
+
+		let mut results = self.conditions.iter().map(|c| c.matches(subject));
+		if self.match_all {
+			results.all(|matched| matched)
+		} else {
+			results.any(|matched| matched)
+		}
+	}
+
+	/// Whether any condition depends on content identification or media metadata
+	pub fn needs_content(&self) -> bool {
+		self.conditions.iter().any(|c| {
+			matches!(
+				c,
+				CompiledCondition::Other(AutoTagCondition::ContentKind { .. })
+					| CompiledCondition::Other(AutoTagCondition::Exif { .. })
+					| CompiledCondition::Other(AutoTagCondition::Date {
+						field: RuleDateField::DateTaken,
+						..
+					})
+			)
+		})
+	}
+}
+
+impl CompiledCondition {
+	fn matches(&self, subject: &RuleSubject) -> bool {
+		let condition = match self {
+			CompiledCondition::PathGlob(matcher) => return matcher.is_match(&subject.path),
+			CompiledCondition::Other(condition) => condition,
+		};
+
+		match condition {
+			AutoTagCondition::PathGlob { .. } => unreachable!("globs are compiled"),
+			AutoTagCondition::Extension { extensions } => {
+				subject.extension.as_deref().map_or(false, |ext| {
+					extensions
+						.iter()
+						.any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(ext))
+				})
+			}
+			AutoTagCondition::ContentKind { kinds } => {
+				subject.kind.map_or(false, |kind| kinds.contains(&kind))
+			}
+			AutoTagCondition::Exif { field, matches } => {
+				let value = subject
+					.image
+					.as_ref()
+					.and_then(|image| exif_value(image, *field));
+				match (matches, value) {
+					(_, None) => false,
+					(ExifMatch::Present, Some(_)) => true,
+					(ExifMatch::Equals(expected), Some(value)) => {
+						value.eq_ignore_ascii_case(expected)
+					}
+					(ExifMatch::Contains(needle), Some(value)) => {
+						value.to_lowercase().contains(&needle.to_lowercase())
+					}
+				}
+			}
+			AutoTagCondition::Size { min, max } => {
+				min.map_or(true, |min| subject.size >= min)
+					&& max.map_or(true, |max| subject.size <= max)
+			}
+			AutoTagCondition::Date { field, start, end } => {
+				let date = match field {
+					RuleDateField::CreatedAt => Some(subject.created_at),
+					RuleDateField::ModifiedAt => Some(subject.modified_at),
+					RuleDateField::DateTaken => subject.image.as_ref().and_then(|i| i.date_taken),
+				};
+				date.map_or(false, |date| {
+					start.map_or(true, |start| date >= start) && end.map_or(true, |end| date <= end)
+				})
+			}
+		}
+	}
+}
+
+fn exif_value(image: &image_media_data::Model, field: ExifField) -> Option<String> {
+	match field {
+		ExifField::CameraMake => image.camera_make.clone(),
+		ExifField::CameraModel => image.camera_model.clone(),
+		ExifField::LensModel => image.lens_model.clone(),
+		ExifField::FocalLength => image.focal_length.clone(),
+		ExifField::Aperture => image.aperture.clone(),
+		ExifField::ShutterSpeed => image.shutter_speed.clone(),
+		ExifField::Iso => image.iso.map(|iso| iso.to_string()),
+		ExifField::Artist => image.artist.clone(),
+		ExifField::Copyright => image.copyright.clone(),
+		ExifField::Description => image.description.clone(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn subject(path: &str) -> RuleSubject {
+		let path = PathBuf::from(path);
+		RuleSubject {
+			extension: path.extension().map(|e| e.to_string_lossy().to_lowercase()),
+			path,
+			size: 2_000_000,
+			created_at: "2024-06-01T12:00:00Z".parse().unwrap(),
+			modified_at: "2024-06-02T12:00:00Z".parse().unwrap(),
+			kind: Some(ContentKind::Image),
+			image: None,
+		}
+	}
+
+	fn image(camera_model: &str) -> image_media_data::Model {
+		image_media_data::Model {
+			id: 1,
+			uuid: uuid::Uuid::new_v4(),
+			width: 4000,
+			height: 3000,
+			blurhash: None,
+			date_taken: Some("2023-12-25T09:30:00Z".parse().unwrap()),
+			latitude: None,
+			longitude: None,
+			camera_make: Some("FUJIFILM".to_string()),
+			camera_model: Some(camera_model.to_string()),
+			lens_model: None,
+			focal_length: None,
+			aperture: None,
+			shutter_speed: None,
+			iso: Some(400),
+			orientation: None,
+			color_space: None,
+			color_profile: None,
+			bit_depth: None,
+			artist: None,
+			copyright: None,
+			description: None,
+			created_at: Utc::now(),
+			updated_at: Utc::now(),
+		}
+	}
+
+	#[test]
+	fn test_match_all_and_any() {
+		let conditions = vec![
+			AutoTagCondition::PathGlob {
+				pattern: "**/Screenshots/**".to_string(),
+			},
+			AutoTagCondition::Extension {
+				extensions: vec![".PNG".to_string()],
+			},
+		];
+
+		let all = RuleMatcher::compile(&conditions, true).unwrap();
+		let any = RuleMatcher::compile(&conditions, false).unwrap();
+
+		assert!(all.matches(&subject("/home/me/Screenshots/2024/shot.png")));
+		assert!(!all.matches(&subject("/home/me/Pictures/shot.png")));
+		assert!(any.matches(&subject("/home/me/Pictures/shot.png")));
+		assert!(!any.matches(&subject("/home/me/Pictures/shot.jpg")));
+	}
+
+	#[test]
+	fn test_content_size_and_date() {
+		let matcher = RuleMatcher::compile(
+			&[
+				AutoTagCondition::ContentKind {
+					kinds: vec![ContentKind::Image, ContentKind::Video],
+				},
+				AutoTagCondition::Size {
+					min: Some(1_000_000),
+					max: None,
+				},
+				AutoTagCondition::Date {
+					field: RuleDateField::CreatedAt,
+					start: Some("2024-01-01T00:00:00Z".parse().unwrap()),
+					end: Some("2024-12-31T23:59:59Z".parse().unwrap()),
+				},
+			],
+			true,
+		)
+		.unwrap();
+		assert!(matcher.needs_content());
+
+		let mut file = subject("/photos/a.jpg");
+		assert!(matcher.matches(&file));
+
+		file.size = 10;
+		assert!(!matcher.matches(&file));
+
+		// Content kind is unknown until content identification has run
+		file.size = 2_000_000;
+		file.kind = None;
+		assert!(!matcher.matches(&file));
+	}
+
+	#[test]
+	fn test_exif() {
+		let matcher = RuleMatcher::compile(
+			&[
+				AutoTagCondition::Exif {
+					field: ExifField::CameraModel,
+					matches: ExifMatch::Contains("x100".to_string()),
+				},
+				AutoTagCondition::Exif {
+					field: ExifField::Iso,
+					matches: ExifMatch::Equals("400".to_string()),
+				},
+				AutoTagCondition::Date {
+					field: RuleDateField::DateTaken,
+					start: None,
+					end: Some("2024-01-01T00:00:00Z".parse().unwrap()),
+				},
+			],
+			true,
+		)
+		.unwrap();
+
+		let mut file = subject("/photos/a.jpg");
+		assert!(!matcher.matches(&file));
+
+		file.image = Some(image("X100V"));
+		assert!(matcher.matches(&file));
+
+		file.image = Some(image("GFX100S"));
+		assert!(!matcher.matches(&file));
+	}
+
+	#[test]
+	fn test_invalid_and_empty_rules() {
+		assert!(RuleMatcher::compile(
+			&[AutoTagCondition::PathGlob {
+				pattern: "[".to_string()
+			}],
+			true
+		)
+		.is_err());
+
+		let empty = RuleMatcher::compile(&[], true).unwrap();
+		assert!(!empty.matches(&subject("/photos/a.jpg")));
+	}
+}
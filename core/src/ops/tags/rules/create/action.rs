@@ -0,0 +1,103 @@
+//! Create auto-tag rule action
+
+use super::{input::CreateAutoTagRuleInput, output::CreateAutoTagRuleOutput};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::{error::ActionError, LibraryAction},
+		db::entities::{auto_tag_rule, tag},
+		sync::ChangeType,
+	},
+	library::Library,
+	ops::tags::rules::job::{AutoTagJob, AutoTagJobConfig},
+};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, NotSet, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAutoTagRuleAction {
+	input: CreateAutoTagRuleInput,
+}
+
+impl CreateAutoTagRuleAction {
+	pub fn new(input: CreateAutoTagRuleInput) -> Self {
+		Self { input }
+	}
+}
+
+impl LibraryAction for CreateAutoTagRuleAction {
+	type Input = CreateAutoTagRuleInput;
+	type Output = CreateAutoTagRuleOutput;
+
+	fn from_input(input: CreateAutoTagRuleInput) -> Result<Self, String> {
+		input.validate()?;
+		Ok(CreateAutoTagRuleAction::new(input))
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let db = library.db().conn();
+
+		if tag::Entity::find()
+			.filter(tag::Column::Uuid.eq(self.input.tag_id))
+			.one(db)
+			.await?
+			.is_none()
+		{
+			return Err(ActionError::Validation {
+				field: "tag_id".to_string(),
+				message: format!("Tag {} not found", self.input.tag_id),
+			});
+		}
+
+		let now = Utc::now();
+		let rule = auto_tag_rule::ActiveModel {
+			id: NotSet,
+			uuid: Set(Uuid::new_v4()),
+			name: Set(self.input.name.trim().to_string()),
+			tag_uuid: Set(self.input.tag_id),
+			conditions: Set(serde_json::to_value(&self.input.conditions)?),
+			match_all: Set(self.input.match_all),
+			enabled: Set(true),
+			created_at: Set(now),
+			updated_at: Set(now),
+		}
+		.insert(db)
+		.await?;
+
+		library
+			.sync_model(&rule, ChangeType::Insert)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to sync auto-tag rule: {}", e)))?;
+
+		let job_id = if self.input.apply_to_existing {
+			let job = AutoTagJob::new(AutoTagJobConfig {
+				rule_id: Some(rule.uuid),
+				location_id: None,
+			});
+			let handle = library.jobs().dispatch(job).await.map_err(|e| {
+				ActionError::Internal(format!("Failed to dispatch auto-tag job: {}", e))
+			})?;
+			Some(handle.id().into())
+		} else {
+			None
+		};
+
+		Ok(CreateAutoTagRuleOutput {
+			rule_id: rule.uuid,
+			job_id,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"tags.rules.create"
+	}
+}
+
+crate::register_library_action!(CreateAutoTagRuleAction, "tags.rules.create");
@@ -0,0 +1,47 @@
+//! Input for creating an auto-tag rule
+
+use crate::ops::tags::rules::condition::{AutoTagCondition, RuleMatcher};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CreateAutoTagRuleInput {
+	/// Display name for the rule
+	pub name: String,
+
+	/// Tag applied to matching files
+	pub tag_id: Uuid,
+
+	/// Conditions a file is checked against
+	pub conditions: Vec<AutoTagCondition>,
+
+	/// Whether every condition must match (default), or any one of them
+	#[serde(default = "default_true")]
+	pub match_all: bool,
+
+	/// Tag files that are already indexed, not just ones indexed from now on
+	#[serde(default)]
+	pub apply_to_existing: bool,
+}
+
+fn default_true() -> bool {
+	true
+}
+
+impl CreateAutoTagRuleInput {
+	/// Validate the input
+	pub fn validate(&self) -> Result<(), String> {
+		if self.name.trim().is_empty() {
+			return Err("name cannot be empty".to_string());
+		}
+
+		if self.conditions.is_empty() {
+			return Err("a rule needs at least one condition".to_string());
+		}
+
+		RuleMatcher::compile(&self.conditions, self.match_all)?;
+
+		Ok(())
+	}
+}
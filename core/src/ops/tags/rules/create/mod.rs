@@ -0,0 +1,9 @@
+//! Create an auto-tag rule
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::CreateAutoTagRuleAction;
+pub use input::CreateAutoTagRuleInput;
+pub use output::CreateAutoTagRuleOutput;
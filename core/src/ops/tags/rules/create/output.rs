@@ -0,0 +1,14 @@
+//! Output for creating an auto-tag rule
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CreateAutoTagRuleOutput {
+	/// The created rule's UUID
+	pub rule_id: Uuid,
+
+	/// Job applying the rule to already indexed files, if requested
+	pub job_id: Option<Uuid>,
+}
@@ -0,0 +1,69 @@
+//! Delete auto-tag rule action
+
+use super::{input::DeleteAutoTagRuleInput, output::DeleteAutoTagRuleOutput};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::{error::ActionError, LibraryAction},
+		db::entities::auto_tag_rule,
+		sync::ChangeType,
+	},
+	library::Library,
+	ops::tags::rules::engine::remove_rule_tags,
+};
+use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteAutoTagRuleAction {
+	input: DeleteAutoTagRuleInput,
+}
+
+impl LibraryAction for DeleteAutoTagRuleAction {
+	type Input = DeleteAutoTagRuleInput;
+	type Output = DeleteAutoTagRuleOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let db = library.db().conn();
+		let rule = auto_tag_rule::Entity::find()
+			.filter(auto_tag_rule::Column::Uuid.eq(self.input.rule_id))
+			.one(db)
+			.await?
+			.ok_or_else(|| ActionError::Validation {
+				field: "rule_id".to_string(),
+				message: format!("Auto-tag rule {} not found", self.input.rule_id),
+			})?;
+
+		// Delete the rule first so an indexer running alongside stops applying it
+		rule.clone().delete(db).await?;
+		library
+			.sync_model(&rule, ChangeType::Delete)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to sync auto-tag rule: {}", e)))?;
+
+		let tags_removed = if self.input.remove_applied_tags {
+			remove_rule_tags(&library, rule.uuid)
+				.await
+				.map_err(|e| ActionError::Internal(format!("Failed to remove rule tags: {}", e)))?
+		} else {
+			0
+		};
+
+		Ok(DeleteAutoTagRuleOutput { tags_removed })
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"tags.rules.delete"
+	}
+}
+
+crate::register_library_action!(DeleteAutoTagRuleAction, "tags.rules.delete");
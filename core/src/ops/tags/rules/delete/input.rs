@@ -0,0 +1,18 @@
+//! Input for deleting an auto-tag rule
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DeleteAutoTagRuleInput {
+	pub rule_id: Uuid,
+
+	/// Also remove every tag the rule applied (default)
+	#[serde(default = "default_true")]
+	pub remove_applied_tags: bool,
+}
+
+fn default_true() -> bool {
+	true
+}
@@ -0,0 +1,9 @@
+//! Delete an auto-tag rule
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::DeleteAutoTagRuleAction;
+pub use input::DeleteAutoTagRuleInput;
+pub use output::DeleteAutoTagRuleOutput;
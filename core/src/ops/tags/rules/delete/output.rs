@@ -0,0 +1,10 @@
+//! Output for deleting an auto-tag rule
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DeleteAutoTagRuleOutput {
+	/// Number of rule-applied tags removed from files
+	pub tags_removed: usize,
+}
@@ -0,0 +1,324 @@
+//! Evaluate auto-tag rules against indexed files
+
+use super::condition::{AutoTagCondition, RuleMatcher, RuleSubject};
+use crate::{
+	domain::{
+		tag::{TagApplication, TagError, TagSource},
+		ContentKind,
+	},
+	infra::{
+		db::entities::{
+			auto_tag_rule, content_identity, directory_paths, entry, image_media_data, tag,
+			user_metadata, user_metadata_tag,
+		},
+		sync::ChangeType,
+	},
+	library::Library,
+	ops::metadata::manager::UserMetadataManager,
+};
+use chrono::Utc;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait, QueryFilter};
+use std::{
+	collections::{HashMap, HashSet},
+	path::PathBuf,
+	sync::Arc,
+};
+use thiserror::Error;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// SQLite's bound parameter limit leaves room for ~900 ids per IN clause
+const QUERY_CHUNK_SIZE: usize = 900;
+
+#[derive(Debug, Error)]
+pub enum AutoTagError {
+	#[error("Database error: {0}")]
+	Database(#[from] DbErr),
+
+	#[error("Tag error: {0}")]
+	Tag(#[from] TagError),
+
+	#[error("Sync error: {0}")]
+	Sync(String),
+}
+
+/// A rule ready to be evaluated
+#[derive(Debug, Clone)]
+struct LoadedRule {
+	uuid: Uuid,
+	tag_uuid: Uuid,
+	tag_db_id: i32,
+	matcher: RuleMatcher,
+}
+
+/// Counts from one pass of the engine
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AutoTagStats {
+	pub entries_checked: usize,
+	pub tags_applied: usize,
+}
+
+/// Applies enabled auto-tag rules to entries
+///
+/// Rule-applied tags use [`TagSource::Rule`] with the rule's UUID as their applied context,
+/// which is how [`remove_rule_tags`] finds them again. A tag the entry already has,
+/// however it was applied, is left alone so deleting a rule never removes a user's tag.
+pub struct AutoTagEngine {
+	rules: Vec<LoadedRule>,
+}
+
+impl AutoTagEngine {
+	/// Load enabled rules, or only `rule` if given
+	///
+	/// Rules whose tag hasn't synced to this device yet are skipped until it has.
+	pub async fn load(db: &DatabaseConnection, rule: Option<Uuid>) -> Result<Self, DbErr> {
+		let mut query =
+			auto_tag_rule::Entity::find().filter(auto_tag_rule::Column::Enabled.eq(true));
+		if let Some(rule) = rule {
+			query = query.filter(auto_tag_rule::Column::Uuid.eq(rule));
+		}
+		let models = query.all(db).await?;
+
+		let tag_uuids: Vec<Uuid> = models.iter().map(|m| m.tag_uuid).collect();
+		let tag_ids: HashMap<Uuid, i32> = tag::Entity::find()
+			.filter(tag::Column::Uuid.is_in(tag_uuids))
+			.all(db)
+			.await?
+			.into_iter()
+			.map(|t| (t.uuid, t.id))
+			.collect();
+
+		let mut rules = Vec::with_capacity(models.len());
+		for model in models {
+			let Some(&tag_db_id) = tag_ids.get(&model.tag_uuid) else {
+				debug!(rule = %model.uuid, tag = %model.tag_uuid, "Auto-tag rule's tag not found, skipping");
+				continue;
+			};
+
+			let matcher = serde_json::from_value::<Vec<AutoTagCondition>>(model.conditions.clone())
+				.map_err(|e| e.to_string())
+				.and_then(|conditions| RuleMatcher::compile(&conditions, model.match_all));
+			match matcher {
+				Ok(matcher) => rules.push(LoadedRule {
+					uuid: model.uuid,
+					tag_uuid: model.tag_uuid,
+					tag_db_id,
+					matcher,
+				}),
+				Err(e) => warn!(rule = %model.uuid, "Skipping invalid auto-tag rule: {}", e),
+			}
+		}
+
+		Ok(Self { rules })
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.rules.is_empty()
+	}
+
+	/// Evaluate every rule against the given entries, tagging the files that match
+	///
+	/// Directories are ignored. Returns the UUIDs of entries that gained tags alongside the
+	/// counts, for resource events.
+	pub async fn apply_to_entries(
+		&self,
+		library: &Library,
+		entry_ids: &[i32],
+	) -> Result<(AutoTagStats, Vec<Uuid>), AutoTagError> {
+		let mut stats = AutoTagStats::default();
+		let mut tagged_entries = Vec::new();
+		if self.rules.is_empty() || entry_ids.is_empty() {
+			return Ok((stats, tagged_entries));
+		}
+
+		let db = library.db().conn();
+		let metadata_manager = UserMetadataManager::new(Arc::new(db.clone()));
+		let device_uuid = crate::device::get_current_device_id();
+		let needs_content = self.rules.iter().any(|r| r.matcher.needs_content());
+
+		for chunk in entry_ids.chunks(QUERY_CHUNK_SIZE) {
+			let entries = entry::Entity::find()
+				.filter(entry::Column::Id.is_in(chunk.to_vec()))
+				.filter(entry::Column::Kind.eq(0))
+				.all(db)
+				.await?;
+			let paths = file_paths(db, &entries).await?;
+			let content = if needs_content {
+				content_facts(db, &entries).await?
+			} else {
+				HashMap::new()
+			};
+			let mut existing = existing_applications(db, &entries).await?;
+
+			for entry in entries {
+				let Some(entry_uuid) = entry.uuid else {
+					continue;
+				};
+				stats.entries_checked += 1;
+
+				let (kind, image) = entry
+					.content_id
+					.and_then(|id| content.get(&id).cloned())
+					.unwrap_or((None, None));
+				let subject = RuleSubject {
+					path: paths
+						.get(&entry.id)
+						.cloned()
+						.unwrap_or_else(|| PathBuf::from(&entry.name)),
+					extension: entry.extension.clone(),
+					size: entry.size.max(0) as u64,
+					created_at: entry.created_at,
+					modified_at: entry.modified_at,
+					kind,
+					image,
+				};
+
+				let mut applications = Vec::new();
+				for rule in &self.rules {
+					// Skip tags the entry already has, including ones an earlier rule just added
+					if !rule.matcher.matches(&subject)
+						|| !existing.insert((entry_uuid, rule.tag_db_id))
+					{
+						continue;
+					}
+					applications.push(TagApplication {
+						tag_id: rule.tag_uuid,
+						applied_context: Some(rule.uuid.to_string()),
+						applied_variant: None,
+						confidence: 1.0,
+						source: TagSource::Rule,
+						instance_attributes: HashMap::new(),
+						created_at: Utc::now(),
+						device_uuid,
+					});
+				}
+
+				if applications.is_empty() {
+					continue;
+				}
+
+				let models = metadata_manager
+					.apply_semantic_tags_to_entry(entry_uuid, applications, device_uuid)
+					.await?;
+				for model in &models {
+					library
+						.sync_model(model, ChangeType::Insert)
+						.await
+						.map_err(|e| AutoTagError::Sync(e.to_string()))?;
+				}
+				stats.tags_applied += models.len();
+				tagged_entries.push(entry_uuid);
+			}
+		}
+
+		Ok((stats, tagged_entries))
+	}
+}
+
+/// Remove every tag a rule applied, returning how many were removed
+pub async fn remove_rule_tags(library: &Library, rule: Uuid) -> Result<usize, AutoTagError> {
+	let db = library.db().conn();
+	let applications = user_metadata_tag::Entity::find()
+		.filter(user_metadata_tag::Column::Source.eq(TagSource::Rule.as_str()))
+		.filter(user_metadata_tag::Column::AppliedContext.eq(rule.to_string()))
+		.all(db)
+		.await?;
+
+	let removed = applications.len();
+	for application in applications {
+		application.clone().delete(db).await?;
+		library
+			.sync_model(&application, ChangeType::Delete)
+			.await
+			.map_err(|e| AutoTagError::Sync(e.to_string()))?;
+	}
+
+	Ok(removed)
+}
+
+/// Full paths of file entries, keyed by entry ID
+async fn file_paths(
+	db: &DatabaseConnection,
+	entries: &[entry::Model],
+) -> Result<HashMap<i32, PathBuf>, DbErr> {
+	let parent_ids: Vec<i32> = entries.iter().filter_map(|e| e.parent_id).collect();
+	let parents: HashMap<i32, String> = directory_paths::Entity::find()
+		.filter(directory_paths::Column::EntryId.is_in(parent_ids))
+		.all(db)
+		.await?
+		.into_iter()
+		.map(|dp| (dp.entry_id, dp.path))
+		.collect();
+
+	Ok(entries
+		.iter()
+		.filter_map(|e| {
+			let parent = parents.get(&e.parent_id?)?;
+			let file_name = match &e.extension {
+				Some(ext) => format!("{}.{}", e.name, ext),
+				None => e.name.clone(),
+			};
+			Some((e.id, PathBuf::from(parent).join(file_name)))
+		})
+		.collect())
+}
+
+/// Content kind and image metadata, keyed by content identity ID
+async fn content_facts(
+	db: &DatabaseConnection,
+	entries: &[entry::Model],
+) -> Result<HashMap<i32, (Option<ContentKind>, Option<image_media_data::Model>)>, DbErr> {
+	let content_ids: HashSet<i32> = entries.iter().filter_map(|e| e.content_id).collect();
+	let identities = content_identity::Entity::find()
+		.filter(content_identity::Column::Id.is_in(content_ids))
+		.all(db)
+		.await?;
+
+	let image_ids: Vec<i32> = identities
+		.iter()
+		.filter_map(|ci| ci.image_media_data_id)
+		.collect();
+	let images: HashMap<i32, image_media_data::Model> = image_media_data::Entity::find()
+		.filter(image_media_data::Column::Id.is_in(image_ids))
+		.all(db)
+		.await?
+		.into_iter()
+		.map(|image| (image.id, image))
+		.collect();
+
+	Ok(identities
+		.into_iter()
+		.map(|ci| {
+			let image = ci
+				.image_media_data_id
+				.and_then(|id| images.get(&id).cloned());
+			(ci.id, (Some(ContentKind::from_id(ci.kind_id)), image))
+		})
+		.collect())
+}
+
+/// Tags the entries already carry, as (entry UUID, tag ID) pairs
+async fn existing_applications(
+	db: &DatabaseConnection,
+	entries: &[entry::Model],
+) -> Result<HashSet<(Uuid, i32)>, DbErr> {
+	let entry_uuids: Vec<Uuid> = entries.iter().filter_map(|e| e.uuid).collect();
+	let metadata: HashMap<i32, Uuid> = user_metadata::Entity::find()
+		.filter(user_metadata::Column::EntryUuid.is_in(entry_uuids))
+		.all(db)
+		.await?
+		.into_iter()
+		.filter_map(|m| Some((m.id, m.entry_uuid?)))
+		.collect();
+	if metadata.is_empty() {
+		return Ok(HashSet::new());
+	}
+
+	Ok(user_metadata_tag::Entity::find()
+		.filter(user_metadata_tag::Column::UserMetadataId.is_in(metadata.keys().copied()))
+		.all(db)
+		.await?
+		.into_iter()
+		.filter_map(|t| Some((*metadata.get(&t.user_metadata_id)?, t.tag_id)))
+		.collect())
+}
@@ -0,0 +1,188 @@
+//! Retroactive auto-tagging job
+//!
+//! The indexer applies rules to files as it identifies them; this job applies them to
+//! files indexed before a rule existed, or whose media metadata has been extracted since.
+
+use super::engine::AutoTagEngine;
+use crate::infra::{
+	db::entities::{entry, location},
+	job::prelude::*,
+};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QuerySelect, Statement};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+/// Entries evaluated between checkpoints
+const BATCH_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct AutoTagJobConfig {
+	/// Only apply this rule (None = every enabled rule)
+	pub rule_id: Option<Uuid>,
+	/// Only tag files in this location (None = the whole library)
+	pub location_id: Option<Uuid>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AutoTagJobState {
+	discovered: bool,
+	entry_ids: Vec<i32>,
+	processed: usize,
+	entries_checked: usize,
+	tags_applied: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Job)]
+pub struct AutoTagJob {
+	config: AutoTagJobConfig,
+	state: AutoTagJobState,
+}
+
+impl AutoTagJob {
+	pub fn new(config: AutoTagJobConfig) -> Self {
+		Self {
+			config,
+			state: AutoTagJobState::default(),
+		}
+	}
+}
+
+impl Job for AutoTagJob {
+	const NAME: &'static str = "auto_tag";
+	const RESUMABLE: bool = true;
+	const DESCRIPTION: Option<&'static str> = Some("Apply auto-tag rules to indexed files");
+}
+
+impl crate::infra::job::traits::DynJob for AutoTagJob {
+	fn job_name(&self) -> &'static str {
+		Self::NAME
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AutoTagJobOutput {
+	pub entries_checked: usize,
+	pub tags_applied: usize,
+}
+
+impl From<AutoTagJobOutput> for JobOutput {
+	fn from(output: AutoTagJobOutput) -> Self {
+		JobOutput::AutoTag {
+			entries_checked: output.entries_checked,
+			tags_applied: output.tags_applied,
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl JobHandler for AutoTagJob {
+	type Output = AutoTagJobOutput;
+
+	async fn run(&mut self, ctx: JobContext<'_>) -> JobResult<Self::Output> {
+		let engine = AutoTagEngine::load(ctx.library_db(), self.config.rule_id).await?;
+		if engine.is_empty() {
+			ctx.log("No enabled auto-tag rules to apply");
+			return Ok(self.output());
+		}
+
+		if !self.state.discovered {
+			ctx.progress(Progress::indeterminate("Finding files to tag"));
+			self.state.entry_ids = self.discover(&ctx).await?;
+			self.state.discovered = true;
+			ctx.checkpoint().await?;
+		}
+
+		let total = self.state.entry_ids.len();
+		ctx.log(format!("Applying auto-tag rules to {} files", total));
+
+		while self.state.processed < total {
+			ctx.check_interrupt().await?;
+
+			let end = (self.state.processed + BATCH_SIZE).min(total);
+			let batch = &self.state.entry_ids[self.state.processed..end];
+
+			let (stats, tagged_entries) = engine
+				.apply_to_entries(ctx.library(), batch)
+				.await
+				.map_err(|e| JobError::execution(format!("Auto-tagging failed: {}", e)))?;
+			self.state.entries_checked += stats.entries_checked;
+			self.state.tags_applied += stats.tags_applied;
+			self.state.processed = end;
+
+			if !tagged_entries.is_empty() {
+				let resource_manager = crate::domain::ResourceManager::new(
+					std::sync::Arc::new(ctx.library_db().clone()),
+					ctx.library().event_bus().clone(),
+				);
+				if let Err(e) = resource_manager
+					.emit_resource_events("file", tagged_entries)
+					.await
+				{
+					tracing::warn!(
+						"Failed to emit file resource events after auto-tagging: {}",
+						e
+					);
+				}
+			}
+
+			ctx.progress(Progress::count(self.state.processed, total));
+			ctx.checkpoint().await?;
+		}
+
+		ctx.log(format!(
+			"Auto-tagging complete: {} tags applied across {} files",
+			self.state.tags_applied, self.state.entries_checked
+		));
+
+		Ok(self.output())
+	}
+}
+
+impl AutoTagJob {
+	fn output(&self) -> AutoTagJobOutput {
+		AutoTagJobOutput {
+			entries_checked: self.state.entries_checked,
+			tags_applied: self.state.tags_applied,
+		}
+	}
+
+	async fn discover(&self, ctx: &JobContext<'_>) -> JobResult<Vec<i32>> {
+		let db = ctx.library_db();
+
+		let Some(location_id) = self.config.location_id else {
+			return Ok(entry::Entity::find()
+				.select_only()
+				.column(entry::Column::Id)
+				.filter(entry::Column::Kind.eq(0))
+				.into_tuple::<i32>()
+				.all(db)
+				.await?);
+		};
+
+		let root_entry_id = location::Entity::find()
+			.filter(location::Column::Uuid.eq(location_id))
+			.one(db)
+			.await?
+			.and_then(|loc| loc.entry_id)
+			.ok_or_else(|| JobError::execution(format!("Location {} not found", location_id)))?;
+
+		let rows = db
+			.query_all(Statement::from_sql_and_values(
+				db.get_database_backend(),
+				r#"
+					SELECT e.id AS entry_id
+					FROM entry_closure ec
+					JOIN entries e ON e.id = ec.descendant_id
+					WHERE ec.ancestor_id = ? AND e.kind = 0
+				"#,
+				[root_entry_id.into()],
+			))
+			.await?;
+
+		Ok(rows
+			.iter()
+			.map(|row| row.try_get::<i32>("", "entry_id"))
+			.collect::<Result<_, _>>()?)
+	}
+}
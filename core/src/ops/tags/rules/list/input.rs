@@ -0,0 +1,7 @@
+//! Input for listing auto-tag rules
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct ListAutoTagRulesInput {}
@@ -0,0 +1,9 @@
+//! List auto-tag rules
+
+pub mod input;
+pub mod output;
+pub mod query;
+
+pub use input::ListAutoTagRulesInput;
+pub use output::{AutoTagRuleInfo, ListAutoTagRulesOutput};
+pub use query::ListAutoTagRulesQuery;
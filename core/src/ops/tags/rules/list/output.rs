@@ -0,0 +1,26 @@
+//! Output for listing auto-tag rules
+
+use crate::ops::tags::rules::condition::AutoTagCondition;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListAutoTagRulesOutput {
+	pub rules: Vec<AutoTagRuleInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AutoTagRuleInfo {
+	pub id: Uuid,
+	pub name: String,
+	pub tag_id: Uuid,
+	pub conditions: Vec<AutoTagCondition>,
+	pub match_all: bool,
+	pub enabled: bool,
+	/// Number of files currently tagged by this rule
+	pub applied_count: u64,
+	pub created_at: DateTime<Utc>,
+	pub updated_at: DateTime<Utc>,
+}
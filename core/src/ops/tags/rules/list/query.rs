@@ -0,0 +1,80 @@
+//! List auto-tag rules query
+
+use super::{
+	input::ListAutoTagRulesInput,
+	output::{AutoTagRuleInfo, ListAutoTagRulesOutput},
+};
+use crate::{
+	context::CoreContext,
+	domain::tag::TagSource,
+	infra::{
+		db::entities::{auto_tag_rule, user_metadata_tag},
+		query::{LibraryQuery, QueryError, QueryResult},
+	},
+};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ListAutoTagRulesQuery {
+	pub input: ListAutoTagRulesInput,
+}
+
+impl LibraryQuery for ListAutoTagRulesQuery {
+	type Input = ListAutoTagRulesInput;
+	type Output = ListAutoTagRulesOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library in session".to_string()))?;
+		let library = context
+			.get_library(library_id)
+			.await
+			.ok_or(QueryError::LibraryNotFound(library_id))?;
+		let db = library.db().conn();
+
+		let models = auto_tag_rule::Entity::find()
+			.order_by_asc(auto_tag_rule::Column::CreatedAt)
+			.all(db)
+			.await?;
+
+		let mut rules = Vec::with_capacity(models.len());
+		for model in models {
+			let conditions = serde_json::from_value(model.conditions.clone()).map_err(|e| {
+				QueryError::Internal(format!("Invalid conditions on rule {}: {}", model.uuid, e))
+			})?;
+			let applied_count = user_metadata_tag::Entity::find()
+				.filter(user_metadata_tag::Column::Source.eq(TagSource::Rule.as_str()))
+				.filter(user_metadata_tag::Column::AppliedContext.eq(model.uuid.to_string()))
+				.count(db)
+				.await?;
+
+			rules.push(AutoTagRuleInfo {
+				id: model.uuid,
+				name: model.name,
+				tag_id: model.tag_uuid,
+				conditions,
+				match_all: model.match_all,
+				enabled: model.enabled,
+				applied_count,
+				created_at: model.created_at,
+				updated_at: model.updated_at,
+			});
+		}
+
+		Ok(ListAutoTagRulesOutput { rules })
+	}
+}
+
+crate::register_library_query!(ListAutoTagRulesQuery, "tags.rules.list");
@@ -0,0 +1,23 @@
+//! Auto-tag rules
+//!
+//! Declarative rules, stored per library and synced, that tag files by path glob,
+//! extension, content kind, EXIF fields, size or date. The indexer applies them after
+//! content identification and `AutoTagJob` applies them retroactively. Tags a rule applies
+//! carry [`TagSource::Rule`](crate::domain::tag::TagSource) and the rule's UUID as their
+//! applied context, so they can be removed together when the rule is deleted.
+
+pub mod apply;
+pub mod condition;
+pub mod create;
+pub mod delete;
+pub mod engine;
+pub mod job;
+pub mod list;
+
+pub use apply::{ApplyAutoTagRulesAction, ApplyAutoTagRulesInput, ApplyAutoTagRulesOutput};
+pub use condition::{AutoTagCondition, ExifField, ExifMatch, RuleDateField, RuleMatcher};
+pub use create::{CreateAutoTagRuleAction, CreateAutoTagRuleInput, CreateAutoTagRuleOutput};
+pub use delete::{DeleteAutoTagRuleAction, DeleteAutoTagRuleInput, DeleteAutoTagRuleOutput};
+pub use engine::{remove_rule_tags, AutoTagEngine, AutoTagError, AutoTagStats};
+pub use job::{AutoTagJob, AutoTagJobConfig};
+pub use list::{AutoTagRuleInfo, ListAutoTagRulesOutput, ListAutoTagRulesQuery};
@@ -0,0 +1,173 @@
+//! Vault add files action handler
+
+use super::{input::VaultAddFilesInput, output::VaultAddFilesOutput};
+use crate::{
+	context::CoreContext,
+	crypto::vault::{VaultEntry, VaultKey, VaultStore},
+	domain::ContentKind,
+	infra::action::{error::ActionError, journal::SessionKey, LibraryAction},
+	library::Library,
+	ops::vaults::{action_session, open_vault},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{path::Path, sync::Arc};
+use tokio::fs::OpenOptions;
+use uuid::Uuid;
+
+/// Overwrite passes used when erasing plaintext originals
+const ERASE_PASSES: usize = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultAddFilesAction {
+	input: VaultAddFilesInput,
+	/// Session whose vault unlock state is used, bound by the ActionManager
+	#[serde(skip)]
+	session: Option<SessionKey>,
+}
+
+impl LibraryAction for VaultAddFilesAction {
+	type Input = VaultAddFilesInput;
+	type Output = VaultAddFilesOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self {
+			input,
+			session: None,
+		})
+	}
+
+	fn bind_session(&mut self, session: &SessionKey) {
+		self.session = Some(session.clone());
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let vault = open_vault(library.db().conn(), self.input.location_id)
+			.await?
+			.ok_or(ActionError::LocationNotFound(self.input.location_id))?;
+		let session = action_session(self.session.as_ref())?;
+		let key = context.vault_manager.key(library.id(), session).await?;
+		let mut manifest = vault.store.load_manifest(&key).await?;
+
+		let mut added = Vec::with_capacity(self.input.paths.len());
+		let mut result = Ok(());
+
+		for sd_path in &self.input.paths {
+			let Some(source) = sd_path.as_local_path() else {
+				result = Err(ActionError::Validation {
+					field: "paths".to_string(),
+					message: format!("{} is not on this device", sd_path),
+				});
+				break;
+			};
+
+			match add_file(&vault.store, &key, source, &context).await {
+				Ok(entry) => {
+					manifest.entries.insert(entry.id, entry.clone());
+					added.push((entry, source));
+				}
+				Err(e) => {
+					result = Err(e);
+					break;
+				}
+			}
+		}
+
+		// Record whatever was encrypted, even if a later file failed
+		if !added.is_empty() {
+			vault.store.save_manifest(&key, &manifest).await?;
+		}
+		result?;
+
+		if self.input.delete_source {
+			for (_, source) in &added {
+				erase_file(source)
+					.await
+					.map_err(|e| ActionError::io_error(source.display().to_string(), e))?;
+			}
+		}
+
+		Ok(VaultAddFilesOutput {
+			added: added.into_iter().map(|(entry, _)| entry).collect(),
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"vaults.files.add"
+	}
+
+	async fn validate(
+		&self,
+		_library: &Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<crate::infra::action::ValidationResult, ActionError> {
+		for sd_path in &self.input.paths {
+			if let Some(path) = sd_path.as_local_path() {
+				if !path.is_file() {
+					return Err(ActionError::Validation {
+						field: "paths".to_string(),
+						message: format!("{} is not a file", path.display()),
+					});
+				}
+			}
+		}
+
+		Ok(crate::infra::action::ValidationResult::Success { metadata: None })
+	}
+}
+
+async fn add_file(
+	store: &VaultStore,
+	key: &VaultKey,
+	source: &Path,
+	context: &CoreContext,
+) -> Result<VaultEntry, ActionError> {
+	let metadata = tokio::fs::metadata(source)
+		.await
+		.map_err(|e| ActionError::io_error(source.display().to_string(), e))?;
+
+	let (kind, mime_type) = match context.file_type_registry.identify(source).await {
+		Ok(result) => (
+			result.file_type.category,
+			result.file_type.primary_mime_type().map(str::to_string),
+		),
+		Err(_) => (ContentKind::Unknown, None),
+	};
+
+	let id = Uuid::new_v4();
+	let size = store.write_object(key, id, source).await?;
+
+	Ok(VaultEntry {
+		id,
+		name: source
+			.file_name()
+			.map(|name| name.to_string_lossy().to_string())
+			.unwrap_or_else(|| id.to_string()),
+		size,
+		kind,
+		mime_type,
+		modified_at: metadata.modified().ok().map(DateTime::<Utc>::from),
+		added_at: Utc::now(),
+	})
+}
+
+/// Overwrite a file with random data before removing it
+async fn erase_file(path: &Path) -> std::io::Result<()> {
+	let mut file = OpenOptions::new().read(true).write(true).open(path).await?;
+	let size = file.metadata().await?.len() as usize;
+
+	sd_crypto::erase::erase(&mut file, size, ERASE_PASSES)
+		.await
+		.map_err(std::io::Error::other)?;
+	file.set_len(0).await?;
+	file.sync_all().await?;
+	drop(file);
+
+	tokio::fs::remove_file(path).await
+}
+
+crate::register_library_action!(VaultAddFilesAction, "vaults.files.add");
@@ -0,0 +1,17 @@
+//! Input types for adding files to a vault
+
+use crate::domain::addressing::SdPath;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultAddFilesInput {
+	/// Vault location to add the files to
+	pub location_id: Uuid,
+	/// Local files to encrypt into the vault
+	pub paths: Vec<SdPath>,
+	/// Securely erase the plaintext originals once they are encrypted
+	#[serde(default)]
+	pub delete_source: bool,
+}
@@ -0,0 +1,9 @@
+//! Encrypt files into a vault
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::VaultAddFilesAction;
+pub use input::VaultAddFilesInput;
+pub use output::VaultAddFilesOutput;
@@ -0,0 +1,10 @@
+//! Output types for adding files to a vault
+
+use crate::crypto::vault::VaultEntry;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultAddFilesOutput {
+	pub added: Vec<VaultEntry>,
+}
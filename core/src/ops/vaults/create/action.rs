@@ -0,0 +1,180 @@
+//! Vault create action handler
+
+use super::{input::VaultCreateInput, output::VaultCreateOutput};
+use crate::{
+	context::CoreContext,
+	crypto::vault::VaultStore,
+	infra::{
+		action::{
+			context::ActionContextProvider, error::ActionError, journal::SessionKey, LibraryAction,
+		},
+		db::entities,
+	},
+	library::Library,
+	location::{manager::LocationManager, IndexMode},
+	ops::vaults::action_session,
+};
+use chrono::Utc;
+use sd_crypto::Protected;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultCreateAction {
+	input: VaultCreateInput,
+	/// Session whose vault unlock state is used, bound by the ActionManager
+	#[serde(skip)]
+	session: Option<SessionKey>,
+}
+
+impl LibraryAction for VaultCreateAction {
+	type Input = VaultCreateInput;
+	type Output = VaultCreateOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self {
+			input,
+			session: None,
+		})
+	}
+
+	fn bind_session(&mut self, session: &SessionKey) {
+		self.session = Some(session.clone());
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let path = self
+			.input
+			.path
+			.as_local_path()
+			.ok_or_else(|| ActionError::Validation {
+				field: "path".to_string(),
+				message: "Vaults must be created on this device".to_string(),
+			})?
+			.to_path_buf();
+
+		// Check the password before touching the filesystem
+		let session = action_session(self.session.as_ref())?;
+		let password = Protected::new(self.input.password.clone());
+		let key = context
+			.vault_manager
+			.unlock_or_initialize(library.id(), session, &password)
+			.await?;
+
+		tokio::fs::create_dir_all(&path)
+			.await
+			.map_err(|e| ActionError::io_error(path.display().to_string(), e))?;
+
+		let device_uuid = context
+			.device_manager
+			.device_id()
+			.map_err(ActionError::device_manager_error)?;
+		let db = library.db().conn();
+		let device_record = entities::device::Entity::find()
+			.filter(entities::device::Column::Uuid.eq(device_uuid))
+			.one(db)
+			.await?
+			.ok_or(ActionError::DeviceNotFound(device_uuid))?;
+
+		// Indexing stays off: the library must never see the vault's encrypted objects
+		let action_context = self.create_action_context();
+		let (location_id, _) = LocationManager::new(context.events.as_ref().clone())
+			.add_location(
+				library.clone(),
+				self.input.path.clone(),
+				self.input.name.clone(),
+				device_record.id,
+				IndexMode::None,
+				Some(action_context),
+				None,
+				&context.volume_manager,
+			)
+			.await
+			.map_err(|e| ActionError::Internal(e.to_string()))?;
+
+		let location = entities::location::Entity::find()
+			.filter(entities::location::Column::Uuid.eq(location_id))
+			.one(db)
+			.await?
+			.ok_or(ActionError::LocationNotFound(location_id))?;
+
+		entities::vault_location::ActiveModel {
+			location_id: Set(location.id),
+			created_at: Set(Utc::now()),
+			..Default::default()
+		}
+		.insert(db)
+		.await?;
+
+		VaultStore::new(&path, location_id).initialize(&key).await?;
+
+		Ok(VaultCreateOutput {
+			location_id,
+			name: location.name.unwrap_or_default(),
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"vaults.create"
+	}
+
+	async fn validate(
+		&self,
+		_library: &Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<crate::infra::action::ValidationResult, ActionError> {
+		if self.input.password.is_empty() {
+			return Err(ActionError::Validation {
+				field: "password".to_string(),
+				message: "Password cannot be empty".to_string(),
+			});
+		}
+
+		if let Some(path) = self.input.path.as_local_path() {
+			if let Ok(mut entries) = tokio::fs::read_dir(path).await {
+				if entries.next_entry().await?.is_some() {
+					return Err(ActionError::Validation {
+						field: "path".to_string(),
+						message: "Vault directory must be empty".to_string(),
+					});
+				}
+			}
+		}
+
+		Ok(crate::infra::action::ValidationResult::Success { metadata: None })
+	}
+}
+
+impl ActionContextProvider for VaultCreateAction {
+	fn create_action_context(&self) -> crate::infra::action::context::ActionContext {
+		use crate::infra::action::context::ActionContext;
+
+		// The input carries the password, so only the path and name are recorded
+		ActionContext::new(
+			Self::action_type_name(),
+			json!({
+				"path": self.input.path.to_string(),
+				"name": self.input.name,
+			}),
+			json!({
+				"operation": "create_vault",
+				"trigger": "user_action",
+			}),
+		)
+	}
+
+	fn action_type_name() -> &'static str
+	where
+		Self: Sized,
+	{
+		"vaults.create"
+	}
+}
+
+crate::register_library_action!(VaultCreateAction, "vaults.create");
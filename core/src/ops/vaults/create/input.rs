@@ -0,0 +1,15 @@
+//! Input types for creating a vault
+
+use crate::domain::addressing::SdPath;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultCreateInput {
+	/// Local directory to hold the vault. Created if missing, and must be empty otherwise.
+	pub path: SdPath,
+	pub name: Option<String>,
+	/// Vault password of the library. Becomes the password for all of the library's
+	/// vaults when this is the first one, and must match it otherwise.
+	pub password: String,
+}
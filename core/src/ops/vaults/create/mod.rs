@@ -0,0 +1,9 @@
+//! Create an encrypted vault location
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::VaultCreateAction;
+pub use input::VaultCreateInput;
+pub use output::VaultCreateOutput;
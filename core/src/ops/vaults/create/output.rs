@@ -0,0 +1,11 @@
+//! Output types for creating a vault
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultCreateOutput {
+	pub location_id: Uuid,
+	pub name: String,
+}
@@ -0,0 +1,133 @@
+//! Vault export files action handler
+
+use super::{input::VaultExportFilesInput, output::VaultExportFilesOutput};
+use crate::{
+	context::CoreContext,
+	infra::action::{error::ActionError, journal::SessionKey, LibraryAction},
+	library::Library,
+	ops::vaults::{action_session, open_vault},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultExportFilesAction {
+	input: VaultExportFilesInput,
+	/// Session whose vault unlock state is used, bound by the ActionManager
+	#[serde(skip)]
+	session: Option<SessionKey>,
+}
+
+impl LibraryAction for VaultExportFilesAction {
+	type Input = VaultExportFilesInput;
+	type Output = VaultExportFilesOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self {
+			input,
+			session: None,
+		})
+	}
+
+	fn bind_session(&mut self, session: &SessionKey) {
+		self.session = Some(session.clone());
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let destination =
+			self.input
+				.destination
+				.as_local_path()
+				.ok_or_else(|| ActionError::Validation {
+					field: "destination".to_string(),
+					message: "Files can only be exported to this device".to_string(),
+				})?;
+
+		let vault = open_vault(library.db().conn(), self.input.location_id)
+			.await?
+			.ok_or(ActionError::LocationNotFound(self.input.location_id))?;
+		let session = action_session(self.session.as_ref())?;
+		let key = context.vault_manager.key(library.id(), session).await?;
+		let mut manifest = vault.store.load_manifest(&key).await?;
+
+		let mut exported = Vec::with_capacity(self.input.entry_ids.len());
+		for id in &self.input.entry_ids {
+			let entry = manifest
+				.entries
+				.get(id)
+				.ok_or_else(|| ActionError::Validation {
+					field: "entry_ids".to_string(),
+					message: format!("Vault entry {} not found", id),
+				})?;
+
+			let target = unique_destination(destination, &entry.name).await?;
+			vault.store.read_object_to(&key, *id, &target).await?;
+			exported.push(target);
+		}
+
+		if self.input.remove_from_vault {
+			for id in &self.input.entry_ids {
+				manifest.entries.remove(id);
+			}
+			vault.store.save_manifest(&key, &manifest).await?;
+			for id in &self.input.entry_ids {
+				vault.store.remove_object(*id).await?;
+			}
+		}
+
+		Ok(VaultExportFilesOutput { exported })
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"vaults.files.export"
+	}
+
+	async fn validate(
+		&self,
+		_library: &Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<crate::infra::action::ValidationResult, ActionError> {
+		if let Some(path) = self.input.destination.as_local_path() {
+			if !path.is_dir() {
+				return Err(ActionError::Validation {
+					field: "destination".to_string(),
+					message: "Destination must be an existing directory".to_string(),
+				});
+			}
+		}
+
+		Ok(crate::infra::action::ValidationResult::Success { metadata: None })
+	}
+}
+
+/// Pick a path in `dir` for `name` that doesn't clobber an existing file
+async fn unique_destination(dir: &Path, name: &str) -> Result<PathBuf, ActionError> {
+	let name = Path::new(name);
+	let stem = name
+		.file_stem()
+		.map(|s| s.to_string_lossy().to_string())
+		.unwrap_or_default();
+	let extension = name.extension().map(|e| e.to_string_lossy().to_string());
+
+	let mut candidate = dir.join(name);
+	let mut counter = 1;
+	while tokio::fs::try_exists(&candidate).await? {
+		let file_name = match &extension {
+			Some(extension) => format!("{} ({}).{}", stem, counter, extension),
+			None => format!("{} ({})", stem, counter),
+		};
+		candidate = dir.join(file_name);
+		counter += 1;
+	}
+
+	Ok(candidate)
+}
+
+crate::register_library_action!(VaultExportFilesAction, "vaults.files.export");
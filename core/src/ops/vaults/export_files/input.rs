@@ -0,0 +1,18 @@
+//! Input types for exporting files from a vault
+
+use crate::domain::addressing::SdPath;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultExportFilesInput {
+	pub location_id: Uuid,
+	/// Vault entries to decrypt
+	pub entry_ids: Vec<Uuid>,
+	/// Local directory the decrypted files are written to
+	pub destination: SdPath,
+	/// Remove the entries from the vault once they are decrypted
+	#[serde(default)]
+	pub remove_from_vault: bool,
+}
@@ -0,0 +1,9 @@
+//! Decrypt files out of a vault
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::VaultExportFilesAction;
+pub use input::VaultExportFilesInput;
+pub use output::VaultExportFilesOutput;
@@ -0,0 +1,11 @@
+//! Output types for exporting files from a vault
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultExportFilesOutput {
+	/// Paths of the decrypted files, in the order they were requested
+	pub exported: Vec<PathBuf>,
+}
@@ -0,0 +1,7 @@
+//! Input types for listing vaults
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct VaultsListInput {}
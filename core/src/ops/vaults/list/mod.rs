@@ -0,0 +1,9 @@
+//! List the vaults of a library
+
+pub mod input;
+pub mod output;
+pub mod query;
+
+pub use input::VaultsListInput;
+pub use output::{VaultInfo, VaultsListOutput};
+pub use query::VaultsListQuery;
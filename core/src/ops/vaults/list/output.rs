@@ -0,0 +1,26 @@
+//! Output types for listing vaults
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultsListOutput {
+	/// Whether a vault password has been set for the library
+	pub configured: bool,
+	/// Whether the library's vaults are unlocked in this session
+	pub unlocked: bool,
+	pub vaults: Vec<VaultInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultInfo {
+	pub location_id: Uuid,
+	pub name: Option<String>,
+	pub path: PathBuf,
+	/// Number of files in the vault, only known while unlocked
+	pub file_count: Option<u64>,
+	/// Plaintext size of the vault's files, only known while unlocked
+	pub total_size: Option<u64>,
+}
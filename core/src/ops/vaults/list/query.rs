@@ -0,0 +1,95 @@
+//! List vaults query
+
+use super::{
+	input::VaultsListInput,
+	output::{VaultInfo, VaultsListOutput},
+};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::journal::SessionKey,
+		db::entities::vault_location,
+		query::{LibraryQuery, QueryError, QueryResult},
+	},
+	ops::vaults::open_vault,
+};
+use sea_orm::{EntityTrait, QueryOrder};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultsListQuery {
+	pub input: VaultsListInput,
+}
+
+impl LibraryQuery for VaultsListQuery {
+	type Input = VaultsListInput;
+	type Output = VaultsListOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library in session".to_string()))?;
+		let library = context
+			.get_library(library_id)
+			.await
+			.ok_or(QueryError::LibraryNotFound(library_id))?;
+		let db = library.db().conn();
+
+		let configured = context.vault_manager.is_configured(library_id).await?;
+		let key = context
+			.vault_manager
+			.key(library_id, &SessionKey::from(&session))
+			.await
+			.ok();
+
+		let rows = vault_location::Entity::find()
+			.find_also_related(crate::infra::db::entities::location::Entity)
+			.order_by_asc(vault_location::Column::CreatedAt)
+			.all(db)
+			.await?;
+
+		let mut vaults = Vec::with_capacity(rows.len());
+		for (_, location) in rows {
+			let Some(location) = location else {
+				continue;
+			};
+			let Some(vault) = open_vault(db, location.uuid).await? else {
+				continue;
+			};
+
+			// A vault whose manifest can't be read is still listed, just without totals
+			let manifest = match &key {
+				Some(key) => vault.store.load_manifest(key).await.ok(),
+				None => None,
+			};
+
+			vaults.push(VaultInfo {
+				location_id: location.uuid,
+				name: location.name,
+				path: vault.store.root().to_path_buf(),
+				file_count: manifest.as_ref().map(|m| m.entries.len() as u64),
+				total_size: manifest
+					.as_ref()
+					.map(|m| m.entries.values().map(|e| e.size).sum()),
+			});
+		}
+
+		Ok(VaultsListOutput {
+			configured,
+			unlocked: key.is_some(),
+			vaults,
+		})
+	}
+}
+
+crate::register_library_query!(VaultsListQuery, "vaults.list");
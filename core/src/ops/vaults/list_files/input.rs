@@ -0,0 +1,12 @@
+//! Input types for listing vault files
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultFilesListInput {
+	pub location_id: Uuid,
+	/// Only return files whose name contains this text, ignoring case
+	pub search: Option<String>,
+}
@@ -0,0 +1,9 @@
+//! List the files in a vault
+
+pub mod input;
+pub mod output;
+pub mod query;
+
+pub use input::VaultFilesListInput;
+pub use output::VaultFilesListOutput;
+pub use query::VaultFilesListQuery;
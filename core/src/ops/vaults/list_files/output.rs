@@ -0,0 +1,11 @@
+//! Output types for listing vault files
+
+use crate::crypto::vault::VaultEntry;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultFilesListOutput {
+	/// Matching files, sorted by name
+	pub files: Vec<VaultEntry>,
+}
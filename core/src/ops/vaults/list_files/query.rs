@@ -0,0 +1,61 @@
+//! List vault files query
+
+use super::{input::VaultFilesListInput, output::VaultFilesListOutput};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::journal::SessionKey,
+		query::{LibraryQuery, QueryError, QueryResult},
+	},
+	ops::vaults::open_vault,
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultFilesListQuery {
+	pub input: VaultFilesListInput,
+}
+
+impl LibraryQuery for VaultFilesListQuery {
+	type Input = VaultFilesListInput;
+	type Output = VaultFilesListOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library in session".to_string()))?;
+		let library = context
+			.get_library(library_id)
+			.await
+			.ok_or(QueryError::LibraryNotFound(library_id))?;
+
+		let vault = open_vault(library.db().conn(), self.input.location_id)
+			.await?
+			.ok_or(QueryError::LocationNotFound(self.input.location_id))?;
+		let key = context
+			.vault_manager
+			.key(library_id, &SessionKey::from(&session))
+			.await?;
+		let manifest = vault.store.load_manifest(&key).await?;
+
+		let files = manifest
+			.search(self.input.search.as_deref())
+			.into_iter()
+			.cloned()
+			.collect();
+
+		Ok(VaultFilesListOutput { files })
+	}
+}
+
+crate::register_library_query!(VaultFilesListQuery, "vaults.files.list");
@@ -0,0 +1,51 @@
+//! Vault lock action handler
+
+use super::{input::VaultLockInput, output::VaultLockOutput};
+use crate::{
+	context::CoreContext,
+	infra::action::{error::ActionError, journal::SessionKey, LibraryAction},
+	library::Library,
+	ops::vaults::action_session,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultLockAction {
+	input: VaultLockInput,
+	/// Session whose vault unlock state is used, bound by the ActionManager
+	#[serde(skip)]
+	session: Option<SessionKey>,
+}
+
+impl LibraryAction for VaultLockAction {
+	type Input = VaultLockInput;
+	type Output = VaultLockOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self {
+			input,
+			session: None,
+		})
+	}
+
+	fn bind_session(&mut self, session: &SessionKey) {
+		self.session = Some(session.clone());
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let session = action_session(self.session.as_ref())?;
+		let was_unlocked = context.vault_manager.lock(library.id(), session).await;
+		Ok(VaultLockOutput { was_unlocked })
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"vaults.lock"
+	}
+}
+
+crate::register_library_action!(VaultLockAction, "vaults.lock");
@@ -0,0 +1,9 @@
+//! Lock a library's vaults
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::VaultLockAction;
+pub use input::VaultLockInput;
+pub use output::VaultLockOutput;
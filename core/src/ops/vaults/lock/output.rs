@@ -0,0 +1,10 @@
+//! Output types for locking vaults
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultLockOutput {
+	/// Whether the vaults were unlocked before this call
+	pub was_unlocked: bool,
+}
@@ -0,0 +1,85 @@
+//! Vault operations
+//!
+//! Vaults are locations whose contents are encrypted at rest. They are added with
+//! indexing disabled, so the library database only knows that the location exists; the
+//! names and metadata of the files inside live in the vault's encrypted manifest.
+//!
+//! Unlock state belongs to the API session that entered the password, so every vault
+//! action and query works with the key of the session it runs in.
+
+pub mod add_files;
+pub mod create;
+pub mod export_files;
+pub mod list;
+pub mod list_files;
+pub mod lock;
+pub mod thumbnail;
+pub mod unlock;
+
+pub use add_files::{VaultAddFilesAction, VaultAddFilesInput, VaultAddFilesOutput};
+pub use create::{VaultCreateAction, VaultCreateInput, VaultCreateOutput};
+pub use export_files::{VaultExportFilesAction, VaultExportFilesInput, VaultExportFilesOutput};
+pub use list::{VaultInfo, VaultsListInput, VaultsListOutput, VaultsListQuery};
+pub use list_files::{VaultFilesListInput, VaultFilesListOutput, VaultFilesListQuery};
+pub use lock::{VaultLockAction, VaultLockInput, VaultLockOutput};
+pub use thumbnail::{VaultThumbnailInput, VaultThumbnailOutput, VaultThumbnailQuery};
+pub use unlock::{VaultUnlockAction, VaultUnlockInput, VaultUnlockOutput};
+
+use crate::{
+	crypto::vault::VaultStore,
+	infra::{
+		action::{error::ActionError, journal::SessionKey},
+		db::entities::{directory_paths, location, vault_location},
+	},
+};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+/// A vault location and the store at its root
+pub struct OpenVault {
+	pub location: location::Model,
+	pub store: VaultStore,
+}
+
+/// The session a vault action was bound to by the ActionManager
+fn action_session(session: Option<&SessionKey>) -> Result<&SessionKey, ActionError> {
+	session.ok_or_else(|| {
+		ActionError::Internal("Vault actions must be dispatched within a session".to_string())
+	})
+}
+
+/// Look up the vault at `location_id`, returning `None` if the location is not a vault
+pub async fn open_vault(
+	db: &impl ConnectionTrait,
+	location_id: Uuid,
+) -> Result<Option<OpenVault>, DbErr> {
+	let Some(location) = location::Entity::find()
+		.filter(location::Column::Uuid.eq(location_id))
+		.one(db)
+		.await?
+	else {
+		return Ok(None);
+	};
+
+	let is_vault = vault_location::Entity::find()
+		.filter(vault_location::Column::LocationId.eq(location.id))
+		.one(db)
+		.await?
+		.is_some();
+	if !is_vault {
+		return Ok(None);
+	}
+
+	let Some(entry_id) = location.entry_id else {
+		return Ok(None);
+	};
+	let Some(root) = directory_paths::Entity::find_by_id(entry_id)
+		.one(db)
+		.await?
+	else {
+		return Ok(None);
+	};
+
+	let store = VaultStore::new(root.path, location.uuid);
+	Ok(Some(OpenVault { location, store }))
+}
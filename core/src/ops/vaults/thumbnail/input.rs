@@ -0,0 +1,11 @@
+//! Input types for vault thumbnails
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultThumbnailInput {
+	pub location_id: Uuid,
+	pub entry_id: Uuid,
+}
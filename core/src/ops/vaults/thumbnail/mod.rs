@@ -0,0 +1,9 @@
+//! Thumbnails of vault files
+
+pub mod input;
+pub mod output;
+pub mod query;
+
+pub use input::VaultThumbnailInput;
+pub use output::VaultThumbnailOutput;
+pub use query::VaultThumbnailQuery;
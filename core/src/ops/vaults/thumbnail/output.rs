@@ -0,0 +1,10 @@
+//! Output types for vault thumbnails
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultThumbnailOutput {
+	/// WebP thumbnail, or `None` if the file's type has no thumbnail
+	pub webp: Option<Vec<u8>>,
+}
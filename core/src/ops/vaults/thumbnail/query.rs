@@ -0,0 +1,115 @@
+//! Vault thumbnail query
+//!
+//! Thumbnails are generated on first request. The file is decrypted into a private
+//! temporary directory for the thumbnailer, the resulting WebP is sealed into the vault,
+//! and the plaintext is removed again; thumbnails never reach the sidecar store.
+
+use super::{input::VaultThumbnailInput, output::VaultThumbnailOutput};
+use crate::{
+	context::CoreContext,
+	crypto::vault::{VaultEntry, VaultKey, VaultStore},
+	infra::{
+		action::journal::SessionKey,
+		query::{LibraryQuery, QueryError, QueryResult},
+	},
+	ops::{
+		media::thumbnail::{ThumbnailGenerator, ThumbnailVariants},
+		vaults::open_vault,
+	},
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+use tracing::debug;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultThumbnailQuery {
+	pub input: VaultThumbnailInput,
+}
+
+impl LibraryQuery for VaultThumbnailQuery {
+	type Input = VaultThumbnailInput;
+	type Output = VaultThumbnailOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library in session".to_string()))?;
+		let library = context
+			.get_library(library_id)
+			.await
+			.ok_or(QueryError::LibraryNotFound(library_id))?;
+
+		let vault = open_vault(library.db().conn(), self.input.location_id)
+			.await?
+			.ok_or(QueryError::LocationNotFound(self.input.location_id))?;
+		let key = context
+			.vault_manager
+			.key(library_id, &SessionKey::from(&session))
+			.await?;
+
+		if let Some(webp) = vault
+			.store
+			.read_thumbnail(&key, self.input.entry_id)
+			.await?
+		{
+			return Ok(VaultThumbnailOutput { webp: Some(webp) });
+		}
+
+		let manifest = vault.store.load_manifest(&key).await?;
+		let entry = manifest.entries.get(&self.input.entry_id).ok_or_else(|| {
+			QueryError::InvalidInput(format!("Vault entry {} not found", self.input.entry_id))
+		})?;
+
+		let webp = generate_thumbnail(&vault.store, &key, entry).await?;
+		Ok(VaultThumbnailOutput { webp })
+	}
+}
+
+async fn generate_thumbnail(
+	store: &VaultStore,
+	key: &VaultKey,
+	entry: &VaultEntry,
+) -> QueryResult<Option<Vec<u8>>> {
+	let Some(generator) = entry
+		.mime_type
+		.as_deref()
+		.and_then(|mime| ThumbnailGenerator::for_mime_type(mime).ok())
+	else {
+		return Ok(None);
+	};
+
+	// Dropping the directory removes the decrypted copy, including on early returns
+	let scratch = tempfile::tempdir()?;
+	let extension = std::path::Path::new(&entry.name)
+		.extension()
+		.map(|e| format!(".{}", e.to_string_lossy()))
+		.unwrap_or_default();
+	let plaintext = scratch.path().join(format!("source{}", extension));
+	let output = scratch.path().join("thumbnail.webp");
+
+	store.read_object_to(key, entry.id, &plaintext).await?;
+
+	let variant = ThumbnailVariants::grid_2x();
+	if let Err(e) = generator
+		.generate(&plaintext, &output, variant.size, variant.quality)
+		.await
+	{
+		debug!(entry_id = %entry.id, "Vault thumbnail generation failed: {}", e);
+		return Ok(None);
+	}
+
+	let webp = tokio::fs::read(&output).await?;
+	store.write_thumbnail(key, entry.id, webp.clone()).await?;
+	Ok(Some(webp))
+}
+
+crate::register_library_query!(VaultThumbnailQuery, "vaults.files.thumbnail");
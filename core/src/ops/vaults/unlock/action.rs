@@ -0,0 +1,59 @@
+//! Vault unlock action handler
+
+use super::{input::VaultUnlockInput, output::VaultUnlockOutput};
+use crate::{
+	context::CoreContext,
+	infra::action::{error::ActionError, journal::SessionKey, LibraryAction},
+	library::Library,
+	ops::vaults::action_session,
+};
+use sd_crypto::Protected;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultUnlockAction {
+	input: VaultUnlockInput,
+	/// Session whose vault unlock state is used, bound by the ActionManager
+	#[serde(skip)]
+	session: Option<SessionKey>,
+}
+
+impl LibraryAction for VaultUnlockAction {
+	type Input = VaultUnlockInput;
+	type Output = VaultUnlockOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self {
+			input,
+			session: None,
+		})
+	}
+
+	fn bind_session(&mut self, session: &SessionKey) {
+		self.session = Some(session.clone());
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let session = action_session(self.session.as_ref())?;
+		let vaults = &context.vault_manager;
+		let was_unlocked = vaults.is_unlocked(library.id(), session).await;
+
+		// Always check the password, even when already unlocked
+		vaults
+			.unlock(library.id(), session, &Protected::new(self.input.password))
+			.await?;
+
+		Ok(VaultUnlockOutput { was_unlocked })
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"vaults.unlock"
+	}
+}
+
+crate::register_library_action!(VaultUnlockAction, "vaults.unlock");
@@ -0,0 +1,9 @@
+//! Input types for unlocking vaults
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultUnlockInput {
+	pub password: String,
+}
@@ -0,0 +1,9 @@
+//! Unlock a library's vaults for this session
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::VaultUnlockAction;
+pub use input::VaultUnlockInput;
+pub use output::VaultUnlockOutput;
@@ -0,0 +1,10 @@
+//! Output types for unlocking vaults
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VaultUnlockOutput {
+	/// Whether the vaults were already unlocked
+	pub was_unlocked: bool,
+}
@@ -1,6 +1,6 @@
 //! Add cloud volume action
 //!
-//! This action adds a cloud storage volume (S3, Google Drive, etc.) to a library,
+//! This action adds a cloud storage volume (S3, Google Drive, SFTP, WebDAV, etc.) to a library,
 //! storing encrypted credentials and creating a virtual volume for indexing.
 
 use super::output::VolumeAddCloudOutput;
@@ -71,6 +71,23 @@ pub enum CloudStorageConfig {
 		endpoint: Option<String>,
 		credential: String, // Service account JSON
 	},
+	/// SFTP server (NAS, seedbox, any host running sshd).
+	/// Uses the ssh-agent and default identities when no private key is given.
+	Sftp {
+		host: String,
+		port: Option<u16>,
+		username: String,
+		private_key_path: Option<String>,
+		root: Option<String>,
+	},
+	/// WebDAV server (Nextcloud, ownCloud, Synology, etc.).
+	/// Username and password may be omitted for anonymous shares.
+	WebDav {
+		endpoint: String,
+		root: Option<String>,
+		username: Option<String>,
+		password: Option<String>,
+	},
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -403,6 +420,107 @@ impl LibraryAction for VolumeAddCloudAction {
 					"endpoint": endpoint,
 				});
 
+				(backend, credential, cloud_identifier, mount_point, config)
+			}
+			CloudStorageConfig::Sftp {
+				host,
+				port,
+				username,
+				private_key_path,
+				root,
+			} => {
+				if host.trim().is_empty() {
+					return Err(ActionError::InvalidInput(
+						"SFTP requires a valid host".to_string(),
+					));
+				}
+				if username.trim().is_empty() {
+					return Err(ActionError::InvalidInput(
+						"SFTP requires a valid username".to_string(),
+					));
+				}
+
+				// host[:port] doubles as the cloud identifier so sftp:// URIs resolve
+				let cloud_identifier = match port {
+					Some(port) => format!("{}:{}", host, port),
+					None => host.clone(),
+				};
+
+				let backend = CloudBackend::new_sftp(
+					&cloud_identifier,
+					username,
+					private_key_path.clone(),
+					root.clone(),
+				)
+				.await
+				.map_err(|e| {
+					ActionError::InvalidInput(format!("Failed to create SFTP backend: {}", e))
+				})?;
+
+				let credential = CloudCredential::new_ssh_key(
+					CloudServiceType::Sftp,
+					username.clone(),
+					private_key_path.clone(),
+				);
+
+				let desired_mount_point = format!("sftp://{}", cloud_identifier);
+				let mount_point = context
+					.volume_manager
+					.ensure_unique_mount_point(&desired_mount_point)
+					.await;
+
+				let config = serde_json::json!({
+					"root": root,
+				});
+
+				(backend, credential, cloud_identifier, mount_point, config)
+			}
+			CloudStorageConfig::WebDav {
+				endpoint,
+				root,
+				username,
+				password,
+			} => {
+				let Some((_, address)) = endpoint.split_once("://") else {
+					return Err(ActionError::InvalidInput(
+						"WebDAV endpoint must be an http:// or https:// URL".to_string(),
+					));
+				};
+				let cloud_identifier = address.split('/').next().unwrap_or_default().to_string();
+				if cloud_identifier.is_empty() {
+					return Err(ActionError::InvalidInput(
+						"WebDAV endpoint is missing a host".to_string(),
+					));
+				}
+
+				let backend = CloudBackend::new_webdav(
+					endpoint,
+					username.clone(),
+					password.clone(),
+					root.clone(),
+				)
+				.await
+				.map_err(|e| {
+					ActionError::InvalidInput(format!("Failed to create WebDAV backend: {}", e))
+				})?;
+
+				let credential = CloudCredential::new_basic(
+					CloudServiceType::WebDav,
+					username.clone().unwrap_or_default(),
+					password.clone().unwrap_or_default(),
+				);
+
+				let desired_mount_point = format!("webdav://{}", cloud_identifier);
+				let mount_point = context
+					.volume_manager
+					.ensure_unique_mount_point(&desired_mount_point)
+					.await;
+
+				let config = serde_json::json!({
+					"endpoint": endpoint,
+					"root": root,
+				});
+
 				(backend, credential, cloud_identifier, mount_point, config)
 			}
 		};
@@ -0,0 +1,300 @@
+//! Automation service
+//!
+//! Listens to the event bus and fires the automation rules of open libraries whose
+//! event type and filters match. Rules are cached per library and reloaded when a
+//! library opens or a rule is created or deleted.
+//!
+//! A rule never fires on events its own action caused: events emitted while the action
+//! runs or within `SELF_TRIGGER_WINDOW` of it finishing, and events about jobs the rule
+//! dispatched, are ignored for that rule. Events caused further downstream, such as
+//! those of a dispatched job's own work, are left to the rule's cooldown.
+
+use crate::{
+	context::CoreContext,
+	infra::{db::entities::automation_rule, event::Event},
+	ops::automation::{
+		engine::{attach_volume, event_context, fire, load_rules, matches_filters},
+		rule::EventFieldFilter,
+		AUTOMATION_RULE_RESOURCE,
+	},
+	service::Service,
+};
+use anyhow::Result;
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Mutex, RwLock,
+	},
+	time::{Duration, Instant},
+};
+use tokio::{sync::broadcast::error::RecvError, task::JoinHandle};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// How long after a rule's action finishes the events it emitted are still
+/// attributed to it
+const SELF_TRIGGER_WINDOW: Duration = Duration::from_secs(2);
+
+/// Jobs remembered per rule for matching job events back to it
+const TRACKED_JOBS: usize = 16;
+
+/// What a rule's own actions are doing, so the events they cause don't fire it again
+#[derive(Debug, Default)]
+struct RuleActivity {
+	/// Actions currently running
+	running: usize,
+	finished_at: Option<Instant>,
+	/// Most recent jobs the rule dispatched
+	jobs: VecDeque<Uuid>,
+}
+
+impl RuleActivity {
+	fn caused(&self, event_context: &serde_json::Value) -> bool {
+		if self.running > 0
+			|| self
+				.finished_at
+				.is_some_and(|finished| finished.elapsed() < SELF_TRIGGER_WINDOW)
+		{
+			return true;
+		}
+		event_context["data"]["job_id"]
+			.as_str()
+			.and_then(|id| Uuid::parse_str(id).ok())
+			.is_some_and(|job_id| self.jobs.contains(&job_id))
+	}
+}
+
+type Activity = Arc<Mutex<HashMap<Uuid, RuleActivity>>>;
+
+/// Service that runs automation rules for all open libraries
+pub struct AutomationService {
+	context: Arc<CoreContext>,
+	running: AtomicBool,
+	handle: RwLock<Option<JoinHandle<()>>>,
+}
+
+impl AutomationService {
+	pub fn new(context: Arc<CoreContext>) -> Self {
+		Self {
+			context,
+			running: AtomicBool::new(false),
+			handle: RwLock::new(None),
+		}
+	}
+
+	async fn listen_loop(context: Arc<CoreContext>) {
+		info!("Automation service started");
+
+		let mut subscriber = context.events.subscribe();
+		let mut rules: HashMap<Uuid, Vec<automation_rule::Model>> = HashMap::new();
+		let mut last_fired: HashMap<Uuid, Instant> = HashMap::new();
+		let activity: Activity = Default::default();
+
+		for library in context.libraries().await.get_open_libraries().await {
+			Self::reload_library(&context, &mut rules, library.id()).await;
+		}
+
+		loop {
+			let event = match subscriber.recv().await {
+				Ok(event) => event,
+				Err(RecvError::Lagged(skipped)) => {
+					warn!(
+						skipped,
+						"Automation service lagged, some events were not matched"
+					);
+					continue;
+				}
+				Err(RecvError::Closed) => {
+					info!("Event bus closed, automation service shutting down");
+					break;
+				}
+			};
+
+			match &event {
+				Event::LibraryOpened { id, .. } => {
+					Self::reload_library(&context, &mut rules, *id).await;
+				}
+				Event::LibraryClosed { id, .. } => {
+					rules.remove(id);
+				}
+				Event::ResourceChanged { resource_type, .. }
+				| Event::ResourceDeleted { resource_type, .. }
+					if resource_type == AUTOMATION_RULE_RESOURCE =>
+				{
+					// Resource events don't say which library the rule belongs to
+					let library_ids: Vec<Uuid> = context
+						.libraries()
+						.await
+						.get_open_libraries()
+						.await
+						.iter()
+						.map(|library| library.id())
+						.collect();
+					for library_id in library_ids {
+						Self::reload_library(&context, &mut rules, library_id).await;
+					}
+					continue;
+				}
+				_ => {}
+			}
+
+			Self::handle_event(&context, &rules, &mut last_fired, &activity, &event).await;
+		}
+	}
+
+	async fn reload_library(
+		context: &Arc<CoreContext>,
+		rules: &mut HashMap<Uuid, Vec<automation_rule::Model>>,
+		library_id: Uuid,
+	) {
+		let Some(library) = context.get_library(library_id).await else {
+			rules.remove(&library_id);
+			return;
+		};
+
+		match load_rules(library.db().conn()).await {
+			Ok(loaded) => {
+				debug!(library_id = %library_id, rules = loaded.len(), "Loaded automation rules");
+				rules.insert(library_id, loaded);
+			}
+			Err(e) => warn!(library_id = %library_id, "Failed to load automation rules: {}", e),
+		}
+	}
+
+	/// Fire every rule whose event type, filters and cooldown match the event, unless
+	/// the rule's own action caused it
+	async fn handle_event(
+		context: &Arc<CoreContext>,
+		rules: &HashMap<Uuid, Vec<automation_rule::Model>>,
+		last_fired: &mut HashMap<Uuid, Instant>,
+		activity: &Activity,
+		event: &Event,
+	) {
+		let event_type = event.variant_name();
+		if !rules
+			.values()
+			.flatten()
+			.any(|rule| rule.event_type == event_type)
+		{
+			return;
+		}
+
+		let mut event_context = event_context(event);
+		attach_volume(&mut event_context, &context.volume_manager).await;
+
+		// Events that name a library only fire that library's rules
+		let scoped_library = event_context["library_id"]
+			.as_str()
+			.and_then(|id| Uuid::parse_str(id).ok());
+
+		for (library_id, library_rules) in rules {
+			if scoped_library.is_some_and(|scoped| scoped != *library_id) {
+				continue;
+			}
+
+			for rule in library_rules.iter().filter(|r| r.event_type == event_type) {
+				let filters: Vec<EventFieldFilter> =
+					match serde_json::from_value(rule.filters.clone()) {
+						Ok(filters) => filters,
+						Err(e) => {
+							warn!(rule = %rule.uuid, "Invalid automation rule filters: {}", e);
+							continue;
+						}
+					};
+				if !matches_filters(&filters, &event_context) {
+					continue;
+				}
+
+				if activity
+					.lock()
+					.unwrap()
+					.get(&rule.uuid)
+					.is_some_and(|rule_activity| rule_activity.caused(&event_context))
+				{
+					debug!(rule = %rule.uuid, "Ignoring event caused by the rule's own action");
+					continue;
+				}
+
+				let cooldown = Duration::from_secs(rule.cooldown_secs.max(0) as u64);
+				if last_fired
+					.get(&rule.uuid)
+					.is_some_and(|fired| fired.elapsed() < cooldown)
+				{
+					debug!(rule = %rule.uuid, "Automation rule cooling down");
+					continue;
+				}
+				last_fired.insert(rule.uuid, Instant::now());
+
+				let Some(library) = context.get_library(*library_id).await else {
+					continue;
+				};
+				// Actions can be slow (webhook retries), so don't hold up the event loop
+				activity
+					.lock()
+					.unwrap()
+					.entry(rule.uuid)
+					.or_default()
+					.running += 1;
+				let activity = activity.clone();
+				let rule_id = rule.uuid;
+				let fired = fire(
+					context.clone(),
+					library,
+					rule.clone(),
+					event_context.clone(),
+				);
+				tokio::spawn(async move {
+					let job_id = fired.await.ok().flatten();
+
+					let mut activity = activity.lock().unwrap();
+					let rule_activity = activity.entry(rule_id).or_default();
+					rule_activity.running = rule_activity.running.saturating_sub(1);
+					rule_activity.finished_at = Some(Instant::now());
+					if let Some(job_id) = job_id {
+						if rule_activity.jobs.len() == TRACKED_JOBS {
+							rule_activity.jobs.pop_front();
+						}
+						rule_activity.jobs.push_back(job_id.0);
+					}
+				});
+			}
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl Service for AutomationService {
+	async fn start(&self) -> Result<()> {
+		if self.running.swap(true, Ordering::SeqCst) {
+			return Ok(());
+		}
+
+		let handle = tokio::spawn(Self::listen_loop(self.context.clone()));
+		*self.handle.write().unwrap() = Some(handle);
+
+		info!("Automation service started");
+		Ok(())
+	}
+
+	async fn stop(&self) -> Result<()> {
+		if !self.running.swap(false, Ordering::SeqCst) {
+			return Ok(());
+		}
+
+		if let Some(handle) = self.handle.write().unwrap().take() {
+			handle.abort();
+		}
+
+		info!("Automation service stopped");
+		Ok(())
+	}
+
+	fn is_running(&self) -> bool {
+		self.running.load(Ordering::SeqCst)
+	}
+
+	fn name(&self) -> &'static str {
+		"automation"
+	}
+}
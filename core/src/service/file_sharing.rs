@@ -224,6 +224,7 @@ impl FileSharingService {
 			move_mode: None,
 			copy_method: crate::ops::files::copy::input::CopyMethod::Auto,
 			conflict_resolution: None,
+			bandwidth_limit_mbps: None,
 		});
 
 		// Submit job to job system
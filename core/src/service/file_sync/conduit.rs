@@ -1,3 +1,4 @@
+use super::versions::RetentionPolicy;
 use crate::infra::db::entities::{entry, sync_conduit, sync_generation};
 use anyhow::Result;
 use chrono::Utc;
@@ -40,6 +41,8 @@ impl ConduitManager {
 			));
 		}
 
+		sync_conduit::parse_schedule(&schedule).map_err(|e| anyhow::anyhow!(e))?;
+
 		// Check for duplicate conduits
 		let existing = sync_conduit::Entity::find()
 			.filter(sync_conduit::Column::SourceEntryId.eq(source_entry_id))
@@ -66,6 +69,7 @@ impl ConduitManager {
 			index_mode_override: Set(None),
 			parallel_transfers: Set(3),
 			bandwidth_limit_mbps: Set(None),
+			retention_policy: Set(None),
 			last_sync_completed_at: Set(None),
 			sync_generation: Set(0),
 			last_sync_error: Set(None),
@@ -125,6 +129,45 @@ impl ConduitManager {
 		Ok(())
 	}
 
+	/// Set the version retention rules of a backup conduit, None for the defaults
+	pub async fn set_retention_policy(
+		&self,
+		conduit_id: i32,
+		policy: Option<RetentionPolicy>,
+	) -> Result<()> {
+		if let Some(policy) = &policy {
+			policy.validate().map_err(|e| anyhow::anyhow!(e))?;
+		}
+
+		let conduit = self.get_conduit(conduit_id).await?;
+
+		let mut active: sync_conduit::ActiveModel = conduit.into();
+		active.retention_policy = Set(policy.map(serde_json::to_value).transpose()?);
+		active.updated_at = Set(Utc::now());
+
+		active.update(&*self.db).await?;
+
+		Ok(())
+	}
+
+	/// Cap the bandwidth of this conduit's transfers between devices, None for no cap
+	pub async fn set_bandwidth_limit(
+		&self,
+		conduit_id: i32,
+		limit_mbps: Option<u32>,
+	) -> Result<()> {
+		let conduit = self.get_conduit(conduit_id).await?;
+
+		let mut active: sync_conduit::ActiveModel = conduit.into();
+		active.bandwidth_limit_mbps =
+			Set(limit_mbps.map(|limit| i32::try_from(limit).unwrap_or(i32::MAX)));
+		active.updated_at = Set(Utc::now());
+
+		active.update(&*self.db).await?;
+
+		Ok(())
+	}
+
 	/// Update conduit after successful sync
 	pub async fn update_after_sync(&self, conduit_id: i32) -> Result<()> {
 		let conduit = self.get_conduit(conduit_id).await?;
@@ -170,6 +213,7 @@ impl ConduitManager {
 			conflicts_resolved: Set(0),
 			bytes_transferred: Set(0),
 			errors_encountered: Set(0),
+			files_versioned: Set(0),
 			verified_at: Set(None),
 			verification_status: Set("unverified".to_string()),
 			..Default::default()
@@ -193,6 +237,21 @@ impl ConduitManager {
 		Ok(())
 	}
 
+	/// Record how many prior file versions a generation kept
+	pub async fn record_versions_kept(&self, generation_id: i32, count: i32) -> Result<()> {
+		let gen = sync_generation::Entity::find_by_id(generation_id)
+			.one(&*self.db)
+			.await?
+			.ok_or_else(|| anyhow::anyhow!("Generation not found"))?;
+
+		let mut active: sync_generation::ActiveModel = gen.into();
+		active.files_versioned = Set(count);
+
+		active.update(&*self.db).await?;
+
+		Ok(())
+	}
+
 	/// Update generation verification status
 	pub async fn update_verification_status(&self, generation_id: i32, status: &str) -> Result<()> {
 		let gen = sync_generation::Entity::find_by_id(generation_id)
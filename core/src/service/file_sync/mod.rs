@@ -21,15 +21,18 @@ use uuid::Uuid;
 pub mod conduit;
 pub mod conflict;
 pub mod resolver;
+pub mod versions;
 
 use conduit::ConduitManager;
 use resolver::{DirectionalOps, SyncResolver};
+use versions::VersionStore;
 
 /// File sync orchestration service
 pub struct FileSyncService {
 	library: Arc<Library>,
 	conduit_manager: Arc<ConduitManager>,
 	resolver: Arc<SyncResolver>,
+	versions: Arc<VersionStore>,
 
 	/// Active sync operations (conduit_id -> sync operation)
 	active_syncs: Arc<RwLock<HashMap<i32, SyncOperation>>>,
@@ -62,11 +65,13 @@ impl FileSyncService {
 		let db = Arc::new(library.db().conn().clone());
 		let conduit_manager = Arc::new(ConduitManager::new(db.clone()));
 		let resolver = Arc::new(SyncResolver::new(db.clone()));
+		let versions = Arc::new(VersionStore::new(db.clone()));
 
 		Self {
 			library,
 			conduit_manager,
 			resolver,
+			versions,
 			active_syncs: Arc::new(RwLock::new(HashMap::new())),
 		}
 	}
@@ -87,7 +92,7 @@ impl FileSyncService {
 
 		// Calculate sync operations
 		info!("Calculating sync operations for conduit {}", conduit_id);
-		let operations = self.resolver.calculate_operations(&conduit).await?;
+		let mut operations = self.resolver.calculate_operations(&conduit).await?;
 
 		let mode = sync_conduit::SyncMode::from_str(&conduit.sync_mode)
 			.ok_or_else(|| anyhow::anyhow!("Invalid sync mode"))?;
@@ -131,6 +136,17 @@ impl FileSyncService {
 			.create_generation(conduit_id, conduit.sync_generation + 1)
 			.await?;
 
+		// Backup mode moves files about to be overwritten or deleted into the versions area
+		if mode == sync_conduit::SyncMode::Backup {
+			let kept = self
+				.versions
+				.keep_superseded(&conduit, &generation, &mut operations.source_to_target)
+				.await?;
+			self.conduit_manager
+				.record_versions_kept(generation.id, kept as i32)
+				.await?;
+		}
+
 		// Dispatch source → target jobs
 		let source_to_target = self
 			.dispatch_job_batch(&conduit, &operations.source_to_target, "source → target")
@@ -177,7 +193,7 @@ impl FileSyncService {
 	/// Dispatch copy and delete jobs for a single direction
 	async fn dispatch_job_batch(
 		&self,
-		_conduit: &sync_conduit::Model,
+		conduit: &sync_conduit::Model,
 		operations: &DirectionalOps,
 		direction: &str,
 	) -> Result<JobBatch> {
@@ -212,6 +228,9 @@ impl FileSyncService {
 			let mut job = FileCopyJob::new(SdPathBatch::new(source_paths), destination);
 			job = job.with_options(CopyOptions {
 				overwrite: true, // File sync should overwrite
+				bandwidth_limit_mbps: conduit
+					.bandwidth_limit_mbps
+					.and_then(|limit| u32::try_from(limit).ok()),
 				..Default::default()
 			});
 
@@ -290,6 +309,16 @@ impl FileSyncService {
 			.await?;
 		self.conduit_manager.update_after_sync(conduit_id).await?;
 
+		let conduit = self.conduit_manager.get_conduit(conduit_id).await?;
+		if conduit.sync_mode == sync_conduit::SyncMode::Backup.as_str() {
+			if let Err(e) = self.versions.apply_retention(&conduit).await {
+				warn!(
+					"Failed to apply version retention for conduit {}: {}",
+					conduit_id, e
+				);
+			}
+		}
+
 		info!(
 			"Sync operations completed for conduit {}, starting verification",
 			conduit_id
@@ -320,6 +349,11 @@ impl FileSyncService {
 	pub fn conduit_manager(&self) -> &Arc<ConduitManager> {
 		&self.conduit_manager
 	}
+
+	/// Get the store of prior file versions kept by backup conduits
+	pub fn versions(&self) -> &Arc<VersionStore> {
+		&self.versions
+	}
 }
 
 impl Clone for FileSyncService {
@@ -328,6 +362,7 @@ impl Clone for FileSyncService {
 			library: self.library.clone(),
 			conduit_manager: self.conduit_manager.clone(),
 			resolver: self.resolver.clone(),
+			versions: self.versions.clone(),
 			active_syncs: self.active_syncs.clone(),
 		}
 	}
@@ -1,3 +1,4 @@
+use super::versions::VERSIONS_DIR;
 use crate::{
 	domain::addressing::SdPath,
 	infra::db::entities::{entry, sync_conduit, sync_generation},
@@ -106,6 +107,8 @@ impl SyncResolver {
 					.await
 			}
 			sync_conduit::SyncMode::Selective => Ok(self.resolve_mirror(&source_map, &target_map)),
+			// Prior versions are kept aside by the service before the mirror runs
+			sync_conduit::SyncMode::Backup => Ok(self.resolve_mirror(&source_map, &target_map)),
 		}
 	}
 
@@ -125,9 +128,8 @@ impl SyncResolver {
 
 		// For MVP, we'll use a simple relative path construction
 		// In production, this should walk parent links to build full paths
-		for entry in entries {
-			let relative_path = PathBuf::from(&entry.name);
-			let full_path = PathBuf::from(&entry.name); // Simplified
+		for (entry, relative_path) in entries {
+			let full_path = relative_path.clone(); // Simplified
 
 			results.push(EntryWithPath {
 				entry,
@@ -139,20 +141,36 @@ impl SyncResolver {
 		Ok(results)
 	}
 
-	/// Find all children of an entry recursively using parent_id relationship
-	async fn find_children_recursive(&self, parent_id: i32) -> Result<Vec<entry::Model>> {
+	/// Find all children of an entry recursively using parent_id relationship,
+	/// with their paths relative to that entry
+	///
+	/// The versions area of backup targets is skipped so kept versions never sync.
+	async fn find_children_recursive(
+		&self,
+		parent_id: i32,
+	) -> Result<Vec<(entry::Model, PathBuf)>> {
 		let mut all_children = Vec::new();
-		let mut to_process = vec![parent_id];
+		let mut to_process = vec![(parent_id, PathBuf::new())];
 
-		while let Some(current_parent) = to_process.pop() {
+		while let Some((current_parent, parent_path)) = to_process.pop() {
 			let children = entry::Entity::find()
 				.filter(entry::Column::ParentId.eq(current_parent))
 				.all(&*self.db)
 				.await?;
 
 			for child in children {
-				to_process.push(child.id);
-				all_children.push(child);
+				if parent_id == current_parent && child.name == VERSIONS_DIR {
+					continue;
+				}
+
+				let file_name = match child.extension.as_deref().filter(|ext| !ext.is_empty()) {
+					Some(ext) => format!("{}.{}", child.name, ext),
+					None => child.name.clone(),
+				};
+				let relative_path = parent_path.join(file_name);
+
+				to_process.push((child.id, relative_path.clone()));
+				all_children.push((child, relative_path));
 			}
 		}
 
@@ -0,0 +1,515 @@
+//! Prior file versions kept by backup conduits
+//!
+//! Before a backup sync overwrites or deletes a file on the target, the old copy is moved
+//! into `<target>/.sdversions/<generation>/<relative path>` and recorded in `file_version`.
+//! Retention rules then thin the kept versions of each file over time, in the style of
+//! "keep hourly for 24h, daily for 30d, monthly for 1y".
+
+use super::resolver::{DirectionalOps, EntryWithPath};
+use crate::{
+	infra::{
+		db::entities::{
+			file_version::{self, VersionReason},
+			sync_conduit, sync_generation,
+		},
+		job::schedule::parse_interval,
+	},
+	ops::indexing::PathResolver,
+};
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+use sea_orm::{prelude::*, ActiveValue::Set, DatabaseConnection, QueryOrder};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Directory under a backup target holding prior versions
+pub const VERSIONS_DIR: &str = ".sdversions";
+
+/// Granularity at which a retention rule keeps versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionPeriod {
+	Hourly,
+	Daily,
+	Weekly,
+	Monthly,
+	Yearly,
+}
+
+impl RetentionPeriod {
+	/// Key shared by every time in the same period
+	fn bucket(&self, time: DateTime<Utc>) -> i64 {
+		let (year, month, day) = (time.year() as i64, time.month() as i64, time.day() as i64);
+		match self {
+			Self::Hourly => ((year * 100 + month) * 100 + day) * 100 + time.hour() as i64,
+			Self::Daily => (year * 100 + month) * 100 + day,
+			Self::Weekly => {
+				let week = time.iso_week();
+				week.year() as i64 * 100 + week.week() as i64
+			}
+			Self::Monthly => year * 100 + month,
+			Self::Yearly => year,
+		}
+	}
+}
+
+/// Keep the newest version in each period for a while
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct RetentionRule {
+	pub every: RetentionPeriod,
+	/// How long the rule applies, e.g. "24h", "30d", "2w" or "1y"
+	pub keep_for: String,
+}
+
+impl RetentionRule {
+	pub fn new(every: RetentionPeriod, keep_for: &str) -> Self {
+		Self {
+			every,
+			keep_for: keep_for.to_string(),
+		}
+	}
+
+	pub fn keep_for(&self) -> Result<Duration, String> {
+		parse_keep_for(&self.keep_for)
+	}
+}
+
+/// Version retention rules of a backup conduit
+///
+/// A version survives if any rule keeps it; versions older than every rule's window
+/// are pruned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct RetentionPolicy {
+	pub rules: Vec<RetentionRule>,
+}
+
+impl Default for RetentionPolicy {
+	/// Hourly for a day, daily for a month, monthly for a year
+	fn default() -> Self {
+		Self {
+			rules: vec![
+				RetentionRule::new(RetentionPeriod::Hourly, "24h"),
+				RetentionRule::new(RetentionPeriod::Daily, "30d"),
+				RetentionRule::new(RetentionPeriod::Monthly, "1y"),
+			],
+		}
+	}
+}
+
+impl RetentionPolicy {
+	/// The conduit's policy, the defaults if it has none or it can't be read
+	pub fn for_conduit(conduit: &sync_conduit::Model) -> Self {
+		conduit
+			.retention_policy
+			.clone()
+			.and_then(|json| serde_json::from_value(json).ok())
+			.unwrap_or_default()
+	}
+
+	pub fn validate(&self) -> Result<(), String> {
+		if self.rules.is_empty() {
+			return Err("Retention policy needs at least one rule".to_string());
+		}
+		for rule in &self.rules {
+			rule.keep_for()?;
+		}
+		Ok(())
+	}
+
+	/// IDs of the versions of one file to keep
+	pub fn versions_to_keep(
+		&self,
+		versions: &[(i32, DateTime<Utc>)],
+		now: DateTime<Utc>,
+	) -> HashSet<i32> {
+		let mut newest_first = versions.to_vec();
+		newest_first.sort_by(|a, b| b.1.cmp(&a.1));
+
+		let mut keep = HashSet::new();
+		for rule in &self.rules {
+			let Ok(keep_for) = rule.keep_for() else {
+				continue;
+			};
+			let cutoff = now - keep_for;
+
+			let mut buckets = HashSet::new();
+			for (id, archived_at) in &newest_first {
+				if *archived_at >= cutoff && buckets.insert(rule.every.bucket(*archived_at)) {
+					keep.insert(*id);
+				}
+			}
+		}
+		keep
+	}
+}
+
+/// Parse a retention window such as "24h", "30d", "2w" or "1y"
+fn parse_keep_for(value: &str) -> Result<Duration, String> {
+	let value = value.trim();
+	let days_per_unit = if let Some(amount) = value.strip_suffix('y') {
+		Some((amount, 365))
+	} else {
+		value.strip_suffix('w').map(|amount| (amount, 7))
+	};
+
+	let duration = match days_per_unit {
+		Some((amount, days)) => {
+			let amount: i64 = amount
+				.trim()
+				.parse()
+				.map_err(|_| format!("Invalid retention window '{}'", value))?;
+			if amount <= 0 {
+				return Err(format!("Invalid retention window '{}'", value));
+			}
+			Duration::days(amount * days)
+		}
+		None => Duration::from_std(parse_interval(value)?)
+			.map_err(|_| format!("Retention window '{}' is too long", value))?,
+	};
+	Ok(duration)
+}
+
+/// Keeps, lists, restores and prunes prior versions of backed up files
+pub struct VersionStore {
+	db: Arc<DatabaseConnection>,
+}
+
+impl VersionStore {
+	pub fn new(db: Arc<DatabaseConnection>) -> Self {
+		Self { db }
+	}
+
+	/// Move target files a backup sync is about to overwrite or delete into the
+	/// versions area, returning how many were kept
+	///
+	/// Files that can't be moved aside are dropped from the operations so the sync
+	/// never destroys a version it failed to keep; kept deletions are dropped too, as
+	/// the file is already gone from the target.
+	pub async fn keep_superseded(
+		&self,
+		conduit: &sync_conduit::Model,
+		generation: &sync_generation::Model,
+		operations: &mut DirectionalOps,
+	) -> Result<usize> {
+		let target_root = PathResolver::get_full_path(&*self.db, conduit.target_entry_id).await?;
+		let mut kept = 0;
+
+		let mut to_copy = Vec::with_capacity(operations.to_copy.len());
+		for source in operations.to_copy.drain(..) {
+			let target_file = target_root.join(&source.relative_path);
+			if !is_file(&target_file).await {
+				to_copy.push(source);
+				continue;
+			}
+
+			match self
+				.keep_version(
+					conduit,
+					generation,
+					&target_root,
+					&source.relative_path,
+					VersionReason::Modified,
+				)
+				.await
+			{
+				Ok(_) => {
+					kept += 1;
+					to_copy.push(source);
+				}
+				Err(e) => warn!(
+					"Not overwriting {}, failed to keep its prior version: {}",
+					target_file.display(),
+					e
+				),
+			}
+		}
+		operations.to_copy = to_copy;
+
+		let mut to_delete: Vec<EntryWithPath> = Vec::with_capacity(operations.to_delete.len());
+		for target in operations.to_delete.drain(..) {
+			// Directories are removed as usual once the files inside are kept
+			if !is_file(&target_root.join(&target.relative_path)).await {
+				to_delete.push(target);
+				continue;
+			}
+
+			match self
+				.keep_version(
+					conduit,
+					generation,
+					&target_root,
+					&target.relative_path,
+					VersionReason::Deleted,
+				)
+				.await
+			{
+				Ok(_) => kept += 1,
+				Err(e) => warn!(
+					"Not deleting {}, failed to keep its prior version: {}",
+					target.relative_path.display(),
+					e
+				),
+			}
+		}
+		operations.to_delete = to_delete;
+
+		if kept > 0 {
+			info!(
+				"Kept {} prior versions for conduit {} (generation {})",
+				kept, conduit.id, generation.generation
+			);
+		}
+
+		Ok(kept)
+	}
+
+	/// Move one target file into the versions area and record it
+	async fn keep_version(
+		&self,
+		conduit: &sync_conduit::Model,
+		generation: &sync_generation::Model,
+		target_root: &Path,
+		relative_path: &Path,
+		reason: VersionReason,
+	) -> Result<file_version::Model> {
+		let current = target_root.join(relative_path);
+		let version_path = PathBuf::from(VERSIONS_DIR)
+			.join(generation.generation.to_string())
+			.join(relative_path);
+		let destination = target_root.join(&version_path);
+
+		let metadata = tokio::fs::metadata(&current).await?;
+		if let Some(parent) = destination.parent() {
+			tokio::fs::create_dir_all(parent).await?;
+		}
+		// The versions area lives on the target itself, so this is a rename
+		tokio::fs::rename(&current, &destination).await?;
+
+		let version = file_version::ActiveModel {
+			uuid: Set(Uuid::new_v4()),
+			conduit_id: Set(conduit.id),
+			generation_id: Set(generation.id),
+			relative_path: Set(relative_path.to_string_lossy().to_string()),
+			version_path: Set(version_path.to_string_lossy().to_string()),
+			reason: Set(reason.as_str().to_string()),
+			size: Set(metadata.len() as i64),
+			modified_at: Set(metadata.modified().ok().map(DateTime::<Utc>::from)),
+			archived_at: Set(Utc::now()),
+			..Default::default()
+		}
+		.insert(&*self.db)
+		.await;
+
+		match version {
+			Ok(version) => Ok(version),
+			Err(e) => {
+				// Put the file back rather than leave an untracked copy behind
+				if let Err(restore_err) = tokio::fs::rename(&destination, &current).await {
+					warn!(
+						"Failed to move {} back after recording its version failed: {}",
+						current.display(),
+						restore_err
+					);
+				}
+				Err(e.into())
+			}
+		}
+	}
+
+	/// Versions of a conduit's files, newest first, optionally for one file
+	pub async fn list(
+		&self,
+		conduit_id: i32,
+		relative_path: Option<&str>,
+	) -> Result<Vec<(file_version::Model, Option<sync_generation::Model>)>> {
+		let mut query =
+			file_version::Entity::find().filter(file_version::Column::ConduitId.eq(conduit_id));
+		if let Some(relative_path) = relative_path {
+			query = query.filter(file_version::Column::RelativePath.eq(relative_path));
+		}
+
+		Ok(query
+			.order_by_desc(file_version::Column::ArchivedAt)
+			.find_also_related(sync_generation::Entity)
+			.all(&*self.db)
+			.await?)
+	}
+
+	/// Copy a kept version back, by default to where the file lives in the source
+	///
+	/// The version stays in the versions area so it can be restored again.
+	pub async fn restore(
+		&self,
+		version: &file_version::Model,
+		destination: Option<PathBuf>,
+		overwrite: bool,
+	) -> Result<PathBuf> {
+		let conduit = sync_conduit::Entity::find_by_id(version.conduit_id)
+			.one(&*self.db)
+			.await?
+			.ok_or_else(|| anyhow::anyhow!("Conduit not found"))?;
+
+		let target_root = PathResolver::get_full_path(&*self.db, conduit.target_entry_id).await?;
+		let kept_copy = target_root.join(&version.version_path);
+
+		let destination = match destination {
+			Some(destination) => destination,
+			None => PathResolver::get_full_path(&*self.db, conduit.source_entry_id)
+				.await?
+				.join(&version.relative_path),
+		};
+
+		if !overwrite && tokio::fs::try_exists(&destination).await? {
+			return Err(anyhow::anyhow!("{} already exists", destination.display()));
+		}
+
+		if let Some(parent) = destination.parent() {
+			tokio::fs::create_dir_all(parent).await?;
+		}
+		tokio::fs::copy(&kept_copy, &destination).await?;
+
+		info!(
+			"Restored version {} of {} to {}",
+			version.uuid,
+			version.relative_path,
+			destination.display()
+		);
+
+		Ok(destination)
+	}
+
+	/// Prune versions the conduit's retention policy no longer keeps, returning how
+	/// many were removed
+	pub async fn apply_retention(&self, conduit: &sync_conduit::Model) -> Result<usize> {
+		let policy = RetentionPolicy::for_conduit(conduit);
+		let target_root = PathResolver::get_full_path(&*self.db, conduit.target_entry_id).await?;
+
+		let versions = file_version::Entity::find()
+			.filter(file_version::Column::ConduitId.eq(conduit.id))
+			.all(&*self.db)
+			.await?;
+
+		let mut by_file: HashMap<&str, Vec<(i32, DateTime<Utc>)>> = HashMap::new();
+		for version in &versions {
+			by_file
+				.entry(version.relative_path.as_str())
+				.or_default()
+				.push((version.id, version.archived_at));
+		}
+
+		let now = Utc::now();
+		let keep: HashSet<i32> = by_file
+			.values()
+			.flat_map(|versions| policy.versions_to_keep(versions, now))
+			.collect();
+
+		let mut pruned = 0;
+		let mut emptied_dirs = HashSet::new();
+		for version in versions.iter().filter(|v| !keep.contains(&v.id)) {
+			let kept_copy = target_root.join(&version.version_path);
+			match tokio::fs::remove_file(&kept_copy).await {
+				Ok(()) => {}
+				Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+				Err(e) => {
+					warn!("Failed to prune version {}: {}", kept_copy.display(), e);
+					continue;
+				}
+			}
+			file_version::Entity::delete_by_id(version.id)
+				.exec(&*self.db)
+				.await?;
+			if let Some(parent) = kept_copy.parent() {
+				emptied_dirs.insert(parent.to_path_buf());
+			}
+			pruned += 1;
+		}
+
+		// Remove directories the pruning emptied, up to the versions area itself
+		let versions_root = target_root.join(VERSIONS_DIR);
+		for dir in emptied_dirs {
+			let mut dir = dir.as_path();
+			while dir.starts_with(&versions_root) && dir != versions_root {
+				if tokio::fs::remove_dir(dir).await.is_err() {
+					break;
+				}
+				match dir.parent() {
+					Some(parent) => dir = parent,
+					None => break,
+				}
+			}
+		}
+
+		if pruned > 0 {
+			debug!("Pruned {} versions for conduit {}", pruned, conduit.id);
+		}
+
+		Ok(pruned)
+	}
+}
+
+async fn is_file(path: &Path) -> bool {
+	tokio::fs::symlink_metadata(path)
+		.await
+		.map(|metadata| metadata.is_file())
+		.unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::TimeZone;
+
+	fn at(day: u32, hour: u32) -> DateTime<Utc> {
+		Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap()
+	}
+
+	#[test]
+	fn test_parse_keep_for() {
+		assert_eq!(parse_keep_for("24h").unwrap(), Duration::hours(24));
+		assert_eq!(parse_keep_for("30d").unwrap(), Duration::days(30));
+		assert_eq!(parse_keep_for("2w").unwrap(), Duration::days(14));
+		assert_eq!(parse_keep_for("1y").unwrap(), Duration::days(365));
+		assert!(parse_keep_for("0y").is_err());
+		assert!(parse_keep_for("soon").is_err());
+	}
+
+	#[test]
+	fn test_hourly_keeps_newest_per_hour() {
+		let policy = RetentionPolicy {
+			rules: vec![RetentionRule::new(RetentionPeriod::Hourly, "24h")],
+		};
+		let now = at(10, 12);
+		let versions = [
+			(1, at(10, 9) + Duration::minutes(10)),
+			(2, at(10, 9) + Duration::minutes(50)),
+			(3, at(10, 11)),
+			// Outside the window
+			(4, at(8, 11)),
+		];
+
+		let keep = policy.versions_to_keep(&versions, now);
+		assert_eq!(keep, HashSet::from([2, 3]));
+	}
+
+	#[test]
+	fn test_default_policy_thins_older_versions() {
+		let policy = RetentionPolicy::default();
+		let now = at(31, 12);
+		let versions = [
+			// Same day, a week ago: only the newest survives the daily rule
+			(1, at(24, 8)),
+			(2, at(24, 20)),
+			// Two months ago: kept monthly
+			(3, Utc.with_ymd_and_hms(2026, 1, 15, 0, 0, 0).unwrap()),
+			// Two years ago: past every rule
+			(4, Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap()),
+		];
+
+		let keep = policy.versions_to_keep(&versions, now);
+		assert_eq!(keep, HashSet::from([2, 3]));
+	}
+}
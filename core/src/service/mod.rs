@@ -9,10 +9,12 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::info;
 
+pub mod automation;
 pub mod device;
 pub mod file_sharing;
 pub mod file_sync;
 pub mod network;
+pub mod saved_search_listener;
 pub mod session;
 pub mod sidecar_manager;
 pub mod statistics_listener;
@@ -21,9 +23,11 @@ pub mod volume_monitor;
 pub mod watcher;
 // NOTE: watcher_old/ is kept as reference during migration but not compiled
 
+use automation::AutomationService;
 use device::DeviceService;
 use file_sharing::FileSharingService;
 use network::NetworkingService;
+use saved_search_listener::SavedSearchListenerService;
 use sidecar_manager::SidecarManager;
 use statistics_listener::StatisticsListenerService;
 use volume_monitor::{VolumeMonitorConfig, VolumeMonitorService};
@@ -44,6 +48,10 @@ pub struct Services {
 	pub volume_monitor: Option<Arc<VolumeMonitorService>>,
 	/// Statistics listener service - recalculates library statistics
 	pub statistics_listener: Option<Arc<StatisticsListenerService>>,
+	/// Saved search listener - keeps smart collection membership current
+	pub saved_search_listener: Arc<SavedSearchListenerService>,
+	/// Automation service - runs event-driven automation rules
+	pub automation: Arc<AutomationService>,
 	/// Sidecar manager
 	pub sidecar_manager: Arc<SidecarManager>,
 	/// Key manager
@@ -65,6 +73,8 @@ impl Services {
 		let sidecar_manager = Arc::new(SidecarManager::new(context.clone()));
 		let key_manager = context.key_manager.clone();
 		let statistics_listener = Some(Arc::new(StatisticsListenerService::new(context.clone())));
+		let saved_search_listener = Arc::new(SavedSearchListenerService::new(context.clone()));
+		let automation = Arc::new(AutomationService::new(context.clone()));
 		Self {
 			fs_watcher,
 			file_sharing,
@@ -72,6 +82,8 @@ impl Services {
 			networking: None,     // Initialized separately when needed
 			volume_monitor: None, // Initialized after library manager is available
 			statistics_listener,
+			saved_search_listener,
+			automation,
 			sidecar_manager,
 			key_manager,
 			context,
@@ -96,6 +108,9 @@ impl Services {
 			monitor.start().await?;
 		}
 
+		self.saved_search_listener.start().await?;
+		self.automation.start().await?;
+
 		Ok(())
 	}
 
@@ -142,6 +157,9 @@ impl Services {
 			info!("Statistics listener disabled in configuration");
 		}
 
+		self.saved_search_listener.start().await?;
+		self.automation.start().await?;
+
 		Ok(())
 	}
 
@@ -161,6 +179,9 @@ impl Services {
 			stats.stop().await?;
 		}
 
+		self.saved_search_listener.stop().await?;
+		self.automation.stop().await?;
+
 		// Stop networking service if initialized
 		if let Some(networking) = &self.networking {
 			networking
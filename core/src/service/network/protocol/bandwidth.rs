@@ -0,0 +1,413 @@
+//! Token-bucket bandwidth limiting for outgoing file transfers
+//!
+//! Every transfer draws from up to three buckets: one shared by all transfers, one shared
+//! by transfers with the same device and one of its own, which also carries any limit of
+//! the sync conduit the transfer belongs to. Limits come from `BandwidthConfig` and are
+//! re-read as the transfer runs, so a scheduled window such as office hours takes effect
+//! on transfers already in flight.
+
+use crate::config::app_config::{BandwidthConfig, BandwidthDays, BandwidthWindow};
+use chrono::{Datelike, Duration as ChronoDuration, NaiveDateTime, NaiveTime, Weekday};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc, Mutex, RwLock,
+	},
+	time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+/// Bytes per second in one megabit per second
+const BYTES_PER_MBIT: u64 = 125_000;
+
+/// Convert a limit in megabits per second to bytes per second (0 = unlimited)
+pub fn mbps_to_bytes(mbps: u32) -> Option<u64> {
+	(mbps > 0).then(|| mbps as u64 * BYTES_PER_MBIT)
+}
+
+/// Parse a window boundary in "HH:MM" form
+pub fn parse_window_time(time: &str) -> Result<NaiveTime, String> {
+	NaiveTime::parse_from_str(time, "%H:%M")
+		.map_err(|_| format!("Invalid time '{}', expected HH:MM", time))
+}
+
+impl BandwidthConfig {
+	/// Check that every scheduled window can be evaluated
+	pub fn validate(&self) -> Result<(), String> {
+		for window in &self.schedule {
+			parse_window_time(&window.start)?;
+			parse_window_time(&window.end)?;
+		}
+		Ok(())
+	}
+
+	/// The global and per-transfer limits in effect at `now`, in bytes per second
+	pub fn limits_at(&self, now: NaiveDateTime) -> ActiveLimits {
+		let (global_mbps, per_transfer_mbps) = self
+			.schedule
+			.iter()
+			.find(|window| window.is_active(now))
+			.map(|window| (window.global_limit_mbps, window.per_transfer_limit_mbps))
+			.unwrap_or((self.global_limit_mbps, self.per_transfer_limit_mbps));
+
+		ActiveLimits {
+			global: global_mbps.and_then(mbps_to_bytes),
+			per_transfer: per_transfer_mbps.and_then(mbps_to_bytes),
+		}
+	}
+
+	/// The limit shared by transfers with `device_id`, in bytes per second
+	pub fn device_limit(&self, device_id: Uuid) -> Option<u64> {
+		self.device_limits_mbps
+			.get(&device_id)
+			.copied()
+			.and_then(mbps_to_bytes)
+	}
+}
+
+impl BandwidthWindow {
+	/// Whether the window covers `now`; windows that run past midnight belong to the day
+	/// they start on
+	pub fn is_active(&self, now: NaiveDateTime) -> bool {
+		let (Ok(start), Ok(end)) = (parse_window_time(&self.start), parse_window_time(&self.end))
+		else {
+			return false;
+		};
+
+		let time = now.time();
+		let day = if start <= end {
+			if time < start || time >= end {
+				return false;
+			}
+			now.date()
+		} else if time >= start {
+			now.date()
+		} else if time < end {
+			now.date() - ChronoDuration::days(1)
+		} else {
+			return false;
+		};
+
+		self.days.includes(day.weekday())
+	}
+}
+
+impl BandwidthDays {
+	pub fn includes(self, day: Weekday) -> bool {
+		let weekend = matches!(day, Weekday::Sat | Weekday::Sun);
+		match self {
+			Self::Daily => true,
+			Self::Weekdays => !weekend,
+			Self::Weekends => weekend,
+		}
+	}
+}
+
+/// Limits in bytes per second, None when unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActiveLimits {
+	pub global: Option<u64>,
+	pub per_transfer: Option<u64>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+	rate: Option<u64>,
+	tokens: f64,
+	refilled_at: Instant,
+}
+
+/// A token bucket refilled at `rate` bytes per second, holding up to one second of tokens
+///
+/// Sends are never refused: a send larger than the tokens available drives the bucket
+/// negative, and the sender waits until it has refilled to zero.
+#[derive(Debug)]
+pub struct TokenBucket {
+	state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+	pub fn new(rate: Option<u64>) -> Self {
+		Self {
+			state: Mutex::new(BucketState {
+				rate,
+				tokens: rate.unwrap_or_default() as f64,
+				refilled_at: Instant::now(),
+			}),
+		}
+	}
+
+	pub fn rate(&self) -> Option<u64> {
+		self.state.lock().unwrap().rate
+	}
+
+	/// Change the refill rate, keeping any debt already taken on
+	pub fn set_rate(&self, rate: Option<u64>) {
+		let mut state = self.state.lock().unwrap();
+		if state.rate != rate {
+			let now = Instant::now();
+			Self::refill(&mut state, now);
+			state.rate = rate;
+			state.tokens = state.tokens.min(rate.unwrap_or_default() as f64);
+		}
+	}
+
+	/// Take `bytes` from the bucket, returning how long to wait before sending them
+	pub fn reserve(&self, bytes: u64) -> Duration {
+		self.reserve_at(bytes, Instant::now())
+	}
+
+	fn reserve_at(&self, bytes: u64, now: Instant) -> Duration {
+		let mut state = self.state.lock().unwrap();
+		Self::refill(&mut state, now);
+
+		let Some(rate) = state.rate else {
+			return Duration::ZERO;
+		};
+		state.tokens -= bytes as f64;
+		if state.tokens >= 0.0 {
+			Duration::ZERO
+		} else {
+			Duration::from_secs_f64(-state.tokens / rate as f64)
+		}
+	}
+
+	fn refill(state: &mut BucketState, now: Instant) {
+		let elapsed = now.saturating_duration_since(state.refilled_at);
+		state.refilled_at = now;
+		if let Some(rate) = state.rate {
+			state.tokens = (state.tokens + elapsed.as_secs_f64() * rate as f64).min(rate as f64);
+		}
+	}
+}
+
+/// Shared limiter that hands out a `TransferThrottle` per outgoing transfer
+#[derive(Debug)]
+pub struct BandwidthLimiter {
+	config: RwLock<BandwidthConfig>,
+	global: TokenBucket,
+	devices: Mutex<HashMap<Uuid, Arc<TokenBucket>>>,
+}
+
+impl BandwidthLimiter {
+	pub fn new(config: BandwidthConfig) -> Self {
+		let limits = config.limits_at(chrono::Local::now().naive_local());
+		Self {
+			config: RwLock::new(config),
+			global: TokenBucket::new(limits.global),
+			devices: Mutex::new(HashMap::new()),
+		}
+	}
+
+	pub fn set_config(&self, config: BandwidthConfig) {
+		*self.config.write().unwrap() = config;
+	}
+
+	pub fn config(&self) -> BandwidthConfig {
+		self.config.read().unwrap().clone()
+	}
+
+	/// Start throttling a transfer to `device_id`, additionally capped at
+	/// `transfer_limit_mbps` (e.g. a sync conduit's limit)
+	pub fn throttle(
+		self: &Arc<Self>,
+		device_id: Uuid,
+		transfer_limit_mbps: Option<u32>,
+	) -> TransferThrottle {
+		let device = self
+			.devices
+			.lock()
+			.unwrap()
+			.entry(device_id)
+			.or_insert_with(|| Arc::new(TokenBucket::new(None)))
+			.clone();
+
+		let throttle = TransferThrottle {
+			limiter: self.clone(),
+			device_id,
+			device,
+			transfer: TokenBucket::new(None),
+			transfer_limit: transfer_limit_mbps.and_then(mbps_to_bytes),
+			throttled_nanos: AtomicU64::new(0),
+		};
+		throttle.refresh();
+		throttle
+	}
+}
+
+/// Rate limiting for a single transfer
+#[derive(Debug)]
+pub struct TransferThrottle {
+	limiter: Arc<BandwidthLimiter>,
+	device_id: Uuid,
+	device: Arc<TokenBucket>,
+	transfer: TokenBucket,
+	transfer_limit: Option<u64>,
+	throttled_nanos: AtomicU64,
+}
+
+impl TransferThrottle {
+	/// Wait until `bytes` may be sent without exceeding any limit
+	pub async fn consume(&self, bytes: u64) {
+		self.refresh();
+
+		let wait = self
+			.limiter
+			.global
+			.reserve(bytes)
+			.max(self.device.reserve(bytes))
+			.max(self.transfer.reserve(bytes));
+
+		if !wait.is_zero() {
+			self.throttled_nanos
+				.fetch_add(wait.as_nanos() as u64, Ordering::Relaxed);
+			tokio::time::sleep(wait).await;
+		}
+	}
+
+	/// The strictest limit currently applied to this transfer, in bytes per second
+	pub fn limit(&self) -> Option<u64> {
+		[
+			self.limiter.global.rate(),
+			self.device.rate(),
+			self.transfer.rate(),
+		]
+		.into_iter()
+		.flatten()
+		.min()
+	}
+
+	/// Total time spent waiting for the limits so far
+	pub fn throttled_for(&self) -> Duration {
+		Duration::from_nanos(self.throttled_nanos.load(Ordering::Relaxed))
+	}
+
+	/// Apply the limits in effect right now to the buckets
+	fn refresh(&self) {
+		let (limits, device_limit) = {
+			let config = self.limiter.config.read().unwrap();
+			(
+				config.limits_at(chrono::Local::now().naive_local()),
+				config.device_limit(self.device_id),
+			)
+		};
+
+		let transfer_limit = match (limits.per_transfer, self.transfer_limit) {
+			(Some(a), Some(b)) => Some(a.min(b)),
+			(a, b) => a.or(b),
+		};
+
+		self.limiter.global.set_rate(limits.global);
+		self.device.set_rate(device_limit);
+		self.transfer.set_rate(transfer_limit);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::NaiveDate;
+
+	fn at(day: u32, time: &str) -> NaiveDateTime {
+		// 2024-01-01 was a Monday
+		NaiveDate::from_ymd_opt(2024, 1, day)
+			.unwrap()
+			.and_time(parse_window_time(time).unwrap())
+	}
+
+	fn office_hours() -> BandwidthConfig {
+		BandwidthConfig {
+			global_limit_mbps: Some(100),
+			per_transfer_limit_mbps: None,
+			device_limits_mbps: HashMap::new(),
+			schedule: vec![BandwidthWindow {
+				days: BandwidthDays::Weekdays,
+				start: "09:00".to_string(),
+				end: "17:30".to_string(),
+				global_limit_mbps: Some(10),
+				per_transfer_limit_mbps: Some(2),
+			}],
+		}
+	}
+
+	#[test]
+	fn test_bucket_allows_burst_then_paces() {
+		let bucket = TokenBucket::new(Some(1000));
+		let start = Instant::now();
+
+		assert_eq!(bucket.reserve_at(1000, start), Duration::ZERO);
+		assert_eq!(bucket.reserve_at(500, start), Duration::from_millis(500));
+
+		// Half a second later the debt is paid off
+		let later = start + Duration::from_millis(500);
+		assert_eq!(bucket.reserve_at(250, later), Duration::from_millis(250));
+	}
+
+	#[test]
+	fn test_unlimited_bucket_never_waits() {
+		let bucket = TokenBucket::new(None);
+		assert_eq!(bucket.reserve(u64::MAX / 2), Duration::ZERO);
+	}
+
+	#[test]
+	fn test_window_replaces_limits_during_office_hours() {
+		let config = office_hours();
+
+		let tuesday_morning = config.limits_at(at(2, "10:15"));
+		assert_eq!(tuesday_morning.global, Some(10 * BYTES_PER_MBIT));
+		assert_eq!(tuesday_morning.per_transfer, Some(2 * BYTES_PER_MBIT));
+
+		let tuesday_evening = config.limits_at(at(2, "17:30"));
+		assert_eq!(tuesday_evening.global, Some(100 * BYTES_PER_MBIT));
+		assert_eq!(tuesday_evening.per_transfer, None);
+
+		let saturday = config.limits_at(at(6, "10:15"));
+		assert_eq!(saturday.global, Some(100 * BYTES_PER_MBIT));
+	}
+
+	#[test]
+	fn test_overnight_window_belongs_to_its_start_day() {
+		let window = BandwidthWindow {
+			days: BandwidthDays::Weekdays,
+			start: "22:00".to_string(),
+			end: "06:00".to_string(),
+			global_limit_mbps: None,
+			per_transfer_limit_mbps: None,
+		};
+
+		// Friday night into Saturday morning
+		assert!(window.is_active(at(5, "23:00")));
+		assert!(window.is_active(at(6, "05:59")));
+		// Sunday night into Monday morning
+		assert!(!window.is_active(at(7, "23:00")));
+		assert!(!window.is_active(at(1, "05:00")));
+		assert!(!window.is_active(at(2, "12:00")));
+	}
+
+	#[test]
+	fn test_validate_rejects_bad_times() {
+		let mut config = office_hours();
+		assert!(config.validate().is_ok());
+
+		config.schedule[0].end = "5pm".to_string();
+		assert!(config.validate().is_err());
+	}
+
+	#[test]
+	fn test_throttle_takes_strictest_limit() {
+		let device_id = Uuid::new_v4();
+		let mut config = BandwidthConfig {
+			per_transfer_limit_mbps: Some(50),
+			..Default::default()
+		};
+		config.device_limits_mbps.insert(device_id, 20);
+		let limiter = Arc::new(BandwidthLimiter::new(config));
+
+		let throttle = limiter.throttle(device_id, Some(8));
+		assert_eq!(throttle.limit(), Some(8 * BYTES_PER_MBIT));
+
+		let other = limiter.throttle(Uuid::new_v4(), None);
+		assert_eq!(other.limit(), Some(50 * BYTES_PER_MBIT));
+	}
+}
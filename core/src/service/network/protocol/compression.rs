@@ -0,0 +1,113 @@
+//! zstd compression of file transfer chunks
+//!
+//! Compression is negotiated per transfer and decided per file: content that is already
+//! compressed (media, archives, encrypted data) is sent as-is. Each chunk is still only
+//! sent compressed when that makes it smaller, and carries a flag so the receiver knows
+//! which chunks to decompress.
+
+use crate::domain::ContentKind;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+/// Fast level, transfers are usually limited by the network rather than the CPU
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Largest chunk a receiver will decompress, guarding against decompression bombs
+const MAX_DECOMPRESSED_CHUNK: usize = 16 * 1024 * 1024;
+
+/// Encoding of a chunk's data on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChunkCompression {
+	Zstd,
+}
+
+/// Whether content of this kind is already compressed, so zstd would only cost CPU
+pub fn is_precompressed(kind: ContentKind) -> bool {
+	matches!(
+		kind,
+		ContentKind::Image
+			| ContentKind::Video
+			| ContentKind::Audio
+			| ContentKind::Archive
+			| ContentKind::Package
+			| ContentKind::Encrypted
+	)
+}
+
+/// Compress a chunk, or None when compression wouldn't make it smaller
+pub fn compress_chunk(data: &[u8]) -> Option<Vec<u8>> {
+	zstd::bulk::compress(data, COMPRESSION_LEVEL)
+		.ok()
+		.filter(|compressed| compressed.len() < data.len())
+}
+
+/// Recover a chunk's original data
+pub fn decompress_chunk(
+	data: Vec<u8>,
+	compression: Option<ChunkCompression>,
+) -> io::Result<Vec<u8>> {
+	match compression {
+		None => Ok(data),
+		Some(ChunkCompression::Zstd) => zstd::bulk::decompress(&data, MAX_DECOMPRESSED_CHUNK),
+	}
+}
+
+/// Raw and on-the-wire byte counts of a transfer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompressionStats {
+	pub raw_bytes: u64,
+	pub wire_bytes: u64,
+}
+
+impl CompressionStats {
+	/// Encode a chunk for sending, returning its wire data and encoding
+	pub fn encode(&mut self, data: &[u8], compress: bool) -> (Vec<u8>, Option<ChunkCompression>) {
+		let encoded = if compress {
+			compress_chunk(data)
+				.map(|compressed| (compressed, Some(ChunkCompression::Zstd)))
+				.unwrap_or_else(|| (data.to_vec(), None))
+		} else {
+			(data.to_vec(), None)
+		};
+
+		self.raw_bytes += data.len() as u64;
+		self.wire_bytes += encoded.0.len() as u64;
+		encoded
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_compressible_chunk_round_trips() {
+		let data = b"spacedrive ".repeat(4096);
+		let mut stats = CompressionStats::default();
+
+		let (wire, compression) = stats.encode(&data, true);
+		assert_eq!(compression, Some(ChunkCompression::Zstd));
+		assert!(stats.wire_bytes < stats.raw_bytes);
+		assert_eq!(decompress_chunk(wire, compression).unwrap(), data);
+	}
+
+	#[test]
+	fn test_incompressible_chunk_is_sent_raw() {
+		// Already-compressed data doesn't shrink a second time
+		let data = zstd::bulk::compress(&b"spacedrive ".repeat(4096), 19).unwrap();
+		let mut stats = CompressionStats::default();
+
+		let (wire, compression) = stats.encode(&data, true);
+		assert_eq!(compression, None);
+		assert_eq!(wire, data);
+		assert_eq!(stats.wire_bytes, stats.raw_bytes);
+	}
+
+	#[test]
+	fn test_precompressed_kinds() {
+		assert!(is_precompressed(ContentKind::Video));
+		assert!(is_precompressed(ContentKind::Archive));
+		assert!(!is_precompressed(ContentKind::Text));
+		assert!(!is_precompressed(ContentKind::Database));
+	}
+}
@@ -1,15 +1,22 @@
 //! File delete protocol for cross-device deletion operations
 
 use crate::{
-	ops::files::delete::{
-		strategy::{DeleteStrategy, FileDeleteMessage, LocalDeleteStrategy},
-		DeleteMode,
+	library::Library,
+	ops::files::{
+		delete::{
+			strategy::{DeleteStrategy, FileDeleteMessage, LocalDeleteStrategy},
+			DeleteMode,
+		},
+		trash::TrashLedger,
 	},
 	service::network::{NetworkingError, Result},
 };
 use async_trait::async_trait;
 use iroh::EndpointId;
-use std::sync::Arc;
+use std::{
+	path::{Path, PathBuf},
+	sync::Arc,
+};
 use uuid::Uuid;
 
 /// File delete protocol handler
@@ -58,6 +65,7 @@ impl FileDeleteProtocolHandler {
 			paths,
 			mode,
 			request_id,
+			library_id,
 		} = request
 		{
 			// Get context to create a temporary job context
@@ -65,14 +73,21 @@ impl FileDeleteProtocolHandler {
 				NetworkingError::Protocol("Context not available for deletion".to_string())
 			})?;
 
+			// Trashed paths are recorded in the requesting library's ledger, so they can
+			// be listed and restored like a local delete
+			let library = match library_id {
+				Some(library_id) => context.libraries().await.get_library(library_id).await,
+				None => None,
+			};
+
 			// Create local delete strategy
-			let strategy = LocalDeleteStrategy;
+			let strategy = LocalDeleteStrategy::default();
 
 			// Execute deletion using the strategy
 			// Note: We're creating a minimal job context for this operation
 			// In a full implementation, this might integrate with the job system
 			let results = match self
-				.execute_deletion_with_strategy(&strategy, &paths, mode.clone())
+				.execute_deletion_with_strategy(&strategy, library.as_ref(), &paths, mode.clone())
 				.await
 			{
 				Ok(results) => results,
@@ -86,6 +101,7 @@ impl FileDeleteProtocolHandler {
 								success: false,
 								bytes_freed: 0,
 								error: Some(format!("Strategy execution failed: {}", e)),
+								trash_path: None,
 							})
 							.collect(),
 					});
@@ -107,6 +123,7 @@ impl FileDeleteProtocolHandler {
 	async fn execute_deletion_with_strategy(
 		&self,
 		strategy: &LocalDeleteStrategy,
+		library: Option<&Arc<Library>>,
 		paths: &[crate::domain::addressing::SdPath],
 		mode: DeleteMode,
 	) -> anyhow::Result<Vec<crate::ops::files::delete::strategy::DeleteResult>> {
@@ -128,6 +145,7 @@ impl FileDeleteProtocolHandler {
 					success: false,
 					bytes_freed: 0,
 					error: Some("Path not within allowed locations".to_string()),
+					trash_path: None,
 				});
 				continue;
 			}
@@ -146,22 +164,71 @@ impl FileDeleteProtocolHandler {
 
 			// Perform deletion based on mode
 			let result = match mode {
-				DeleteMode::Trash => LocalDeleteStrategy.move_to_trash(local_path).await,
-				DeleteMode::Permanent => LocalDeleteStrategy.permanent_delete(local_path).await,
-				DeleteMode::Secure => LocalDeleteStrategy.secure_delete(local_path).await,
+				DeleteMode::Trash => Self::trash_and_record(strategy, library, path, local_path)
+					.await
+					.map(Some),
+				DeleteMode::Permanent => strategy.permanent_delete(local_path).await.map(|_| None),
+				DeleteMode::Secure => strategy.secure_delete(local_path).await.map(|_| None),
 			};
 
-			results.push(crate::ops::files::delete::strategy::DeleteResult {
-				path: path.clone(),
-				success: result.is_ok(),
-				bytes_freed: if result.is_ok() { size } else { 0 },
-				error: result.err().map(|e| e.to_string()),
+			results.push(match result {
+				Ok(trash_path) => crate::ops::files::delete::strategy::DeleteResult {
+					path: path.clone(),
+					success: true,
+					bytes_freed: size,
+					error: None,
+					trash_path,
+				},
+				Err(e) => crate::ops::files::delete::strategy::DeleteResult {
+					path: path.clone(),
+					success: false,
+					bytes_freed: 0,
+					error: Some(e.to_string()),
+					trash_path: None,
+				},
 			});
 		}
 
 		Ok(results)
 	}
 
+	/// Move a path to the trash, snapshotting its index state first and recording it in
+	/// the library's trash ledger afterwards, the same way `DeleteJob` does for local paths
+	async fn trash_and_record(
+		strategy: &LocalDeleteStrategy,
+		library: Option<&Arc<Library>>,
+		path: &crate::domain::addressing::SdPath,
+		local_path: &Path,
+	) -> std::result::Result<PathBuf, std::io::Error> {
+		let snapshot = match library {
+			Some(library) => TrashLedger::snapshot(library.db().conn(), path)
+				.await
+				.map_err(|e| {
+					tracing::warn!(
+						path = %local_path.display(),
+						"Failed to snapshot remote delete for the trash ledger: {}",
+						e
+					)
+				})
+				.ok(),
+			None => None,
+		};
+
+		let trash_path = strategy.trash(local_path).await?;
+
+		if let (Some(library), Some(snapshot)) = (library, snapshot) {
+			if let Err(e) = TrashLedger::record(library.db().conn(), snapshot, &trash_path).await {
+				tracing::warn!(
+					path = %local_path.display(),
+					"Failed to record remote delete in the trash ledger: {}",
+					e
+				);
+			}
+		}
+
+		Ok(trash_path)
+	}
+
 	/// Check if a path is within allowed locations (registered Locations from all libraries).
 	/// Uses canonicalization to prevent traversal attacks.
 	fn is_path_allowed(&self, path: &std::path::Path) -> bool {
@@ -0,0 +1,703 @@
+//! Rolling-checksum delta encoding for file transfers
+//!
+//! When the receiving side already holds an older copy of a file, it sends a signature of
+//! that copy: a weak rolling checksum and a truncated BLAKE3 hash per fixed-size block. The
+//! sending side slides a window over the new file, confirms weak checksum hits with the
+//! strong hash, and sends references to matching blocks plus the literal bytes in between.
+//! The receiver rebuilds the file next to the old copy and only replaces it once the result
+//! matches the sender's content hash.
+
+use super::bandwidth::TransferThrottle;
+use super::file_transfer::{write_message, FileTransferMessage};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashMap,
+	io::{self, Read},
+	path::{Path, PathBuf},
+};
+use tokio::{
+	fs::File,
+	io::{AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt},
+	sync::mpsc,
+};
+use uuid::Uuid;
+
+/// Files smaller than this are always sent whole
+pub const MIN_DELTA_FILE_SIZE: u64 = 256 * 1024;
+
+const MIN_BLOCK_SIZE: u32 = 2 * 1024;
+const MAX_BLOCK_SIZE: u32 = 128 * 1024;
+
+/// Literal bytes buffered before they're emitted
+const MAX_LITERAL: usize = 64 * 1024;
+
+/// Bytes read from the new file at a time
+const READ_SIZE: usize = 1024 * 1024;
+
+/// Operations sent per `DeltaChunk` message
+const MAX_BATCH_OPS: usize = 1024;
+
+const PARTIAL_SUFFIX: &str = ".sdpartial";
+
+/// Block size for a basis file: roughly its square root, as rsync does
+pub fn block_size_for(file_size: u64) -> u32 {
+	let root = (file_size as f64).sqrt() as u32;
+	root.div_ceil(1024)
+		.saturating_mul(1024)
+		.clamp(MIN_BLOCK_SIZE, MAX_BLOCK_SIZE)
+}
+
+/// Checksums of one block of the basis file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockSignature {
+	pub weak: u32,
+	pub strong: [u8; 16],
+}
+
+/// Checksums of every block of the basis file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileSignature {
+	pub block_size: u32,
+	pub file_size: u64,
+	pub blocks: Vec<BlockSignature>,
+}
+
+impl FileSignature {
+	/// Signature of everything a reader yields
+	pub fn compute<R: Read>(mut reader: R, block_size: u32) -> io::Result<Self> {
+		let mut block = vec![0u8; block_size as usize];
+		let mut blocks = Vec::new();
+		let mut file_size = 0u64;
+
+		loop {
+			let len = read_full(&mut reader, &mut block)?;
+			if len == 0 {
+				break;
+			}
+			let data = &block[..len];
+			blocks.push(BlockSignature {
+				weak: RollingChecksum::new(data).value(),
+				strong: strong_hash(data),
+			});
+			file_size += len as u64;
+			if len < block.len() {
+				break;
+			}
+		}
+
+		Ok(Self {
+			block_size,
+			file_size,
+			blocks,
+		})
+	}
+
+	/// Signature of a file on disk, computed off the async runtime
+	pub async fn of_file(path: &Path) -> io::Result<Self> {
+		let path = path.to_path_buf();
+		tokio::task::spawn_blocking(move || {
+			let file = std::fs::File::open(&path)?;
+			let block_size = block_size_for(file.metadata()?.len());
+			Self::compute(io::BufReader::new(file), block_size)
+		})
+		.await
+		.map_err(io::Error::other)?
+	}
+
+	/// Whether a signature received from a peer is one `compute` could have produced:
+	/// a block size `block_size_for` can pick, and one block per started block of the file
+	pub fn is_valid(&self) -> bool {
+		(MIN_BLOCK_SIZE..=MAX_BLOCK_SIZE).contains(&self.block_size)
+			&& self.block_size % 1024 == 0
+			&& self.file_size.div_ceil(self.block_size as u64) == self.blocks.len() as u64
+	}
+
+	/// Length of a block, shorter for the last one and zero past the end
+	fn block_len(&self, index: u32) -> u64 {
+		let start = index as u64 * self.block_size as u64;
+		self.file_size
+			.checked_sub(start)
+			.map_or(0, |rest| rest.min(self.block_size as u64))
+	}
+}
+
+/// How to rebuild part of the new file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeltaOp {
+	/// Copy consecutive blocks of the basis file
+	Copy { block: u32, count: u32 },
+	/// Bytes not found in the basis file
+	Literal(Vec<u8>),
+}
+
+/// Operations for the next stretch of the new file
+#[derive(Debug, Default)]
+pub struct DeltaBatch {
+	pub ops: Vec<DeltaOp>,
+	/// Bytes of the new file the operations cover
+	pub bytes: u64,
+	/// Of which sent as literals
+	pub literal_bytes: u64,
+}
+
+/// rsync's weak checksum, which rolls one byte at a time in constant time
+#[derive(Debug, Clone, Copy)]
+struct RollingChecksum {
+	a: u32,
+	b: u32,
+	len: u32,
+}
+
+impl RollingChecksum {
+	fn new(data: &[u8]) -> Self {
+		let len = data.len() as u32;
+		let (mut a, mut b) = (0u32, 0u32);
+		for (i, &byte) in data.iter().enumerate() {
+			a = a.wrapping_add(byte as u32);
+			b = b.wrapping_add((len - i as u32).wrapping_mul(byte as u32));
+		}
+		Self { a, b, len }
+	}
+
+	fn roll(&mut self, out: u8, into: u8) {
+		self.a = self.a.wrapping_sub(out as u32).wrapping_add(into as u32);
+		self.b = self
+			.b
+			.wrapping_sub(self.len.wrapping_mul(out as u32))
+			.wrapping_add(self.a);
+	}
+
+	fn value(&self) -> u32 {
+		(self.a & 0xffff) | (self.b << 16)
+	}
+}
+
+fn strong_hash(data: &[u8]) -> [u8; 16] {
+	let mut strong = [0u8; 16];
+	strong.copy_from_slice(&blake3::hash(data).as_bytes()[..16]);
+	strong
+}
+
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+	let mut filled = 0;
+	while filled < buf.len() {
+		match reader.read(&mut buf[filled..]) {
+			Ok(0) => break,
+			Ok(n) => filled += n,
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+			Err(e) => return Err(e),
+		}
+	}
+	Ok(filled)
+}
+
+/// Finds the basis blocks reused by a new version of a file
+pub struct DeltaEncoder<'a> {
+	signature: &'a FileSignature,
+	/// Weak checksum -> indexes of the full-size blocks with it
+	index: HashMap<u32, Vec<u32>>,
+}
+
+impl<'a> DeltaEncoder<'a> {
+	pub fn new(signature: &'a FileSignature) -> Self {
+		let mut index: HashMap<u32, Vec<u32>> = HashMap::new();
+		for (i, block) in signature.blocks.iter().enumerate() {
+			if signature.block_len(i as u32) == signature.block_size as u64 {
+				index.entry(block.weak).or_default().push(i as u32);
+			}
+		}
+		Self { signature, index }
+	}
+
+	/// Encode everything a reader yields, handing batches of operations to `emit`
+	pub fn encode<R: Read>(
+		&self,
+		mut reader: R,
+		mut emit: impl FnMut(DeltaBatch) -> io::Result<()>,
+	) -> io::Result<()> {
+		let block_size = self.signature.block_size as usize;
+		let mut batch = DeltaBatch::default();
+		let mut buf = Vec::new();
+		let mut eof = false;
+		let mut pos = 0;
+		let mut literal_start = 0;
+		let mut rolling: Option<RollingChecksum> = None;
+
+		loop {
+			// Drop bytes already encoded
+			if literal_start >= READ_SIZE {
+				buf.drain(..literal_start);
+				pos -= literal_start;
+				literal_start = 0;
+			}
+			fill(&mut reader, &mut buf, pos + block_size, &mut eof)?;
+			if pos >= buf.len() {
+				break;
+			}
+
+			let window = &buf[pos..(pos + block_size).min(buf.len())];
+			if window.len() < block_size {
+				// The tail can only match the basis file's last block
+				if let Some(block) = self.match_tail(window) {
+					self.push_literal(&mut batch, &buf[literal_start..pos], &mut emit)?;
+					self.push_copy(&mut batch, block, window.len() as u64, &mut emit)?;
+					pos = buf.len();
+					literal_start = pos;
+				}
+				break;
+			}
+
+			let checksum = rolling.get_or_insert_with(|| RollingChecksum::new(window));
+			if let Some(block) = self.match_block(checksum.value(), window) {
+				self.push_literal(&mut batch, &buf[literal_start..pos], &mut emit)?;
+				self.push_copy(&mut batch, block, block_size as u64, &mut emit)?;
+				pos += block_size;
+				literal_start = pos;
+				rolling = None;
+				continue;
+			}
+
+			fill(&mut reader, &mut buf, pos + block_size + 1, &mut eof)?;
+			match buf.get(pos + block_size) {
+				Some(&into) => checksum.roll(buf[pos], into),
+				None => rolling = None,
+			}
+			pos += 1;
+
+			if pos - literal_start >= MAX_LITERAL {
+				self.push_literal(&mut batch, &buf[literal_start..pos], &mut emit)?;
+				literal_start = pos;
+			}
+		}
+
+		for literal in buf[literal_start..].chunks(MAX_LITERAL) {
+			self.push_literal(&mut batch, literal, &mut emit)?;
+		}
+		if !batch.ops.is_empty() {
+			emit(batch)?;
+		}
+		Ok(())
+	}
+
+	fn match_block(&self, weak: u32, window: &[u8]) -> Option<u32> {
+		let candidates = self.index.get(&weak)?;
+		let strong = strong_hash(window);
+		candidates
+			.iter()
+			.copied()
+			.find(|&block| self.signature.blocks[block as usize].strong == strong)
+	}
+
+	fn match_tail(&self, window: &[u8]) -> Option<u32> {
+		let last = self.signature.blocks.len().checked_sub(1)? as u32;
+		let block = &self.signature.blocks[last as usize];
+		(self.signature.block_len(last) == window.len() as u64
+			&& block.weak == RollingChecksum::new(window).value()
+			&& block.strong == strong_hash(window))
+		.then_some(last)
+	}
+
+	fn push_literal(
+		&self,
+		batch: &mut DeltaBatch,
+		data: &[u8],
+		emit: &mut impl FnMut(DeltaBatch) -> io::Result<()>,
+	) -> io::Result<()> {
+		if data.is_empty() {
+			return Ok(());
+		}
+		batch.ops.push(DeltaOp::Literal(data.to_vec()));
+		batch.bytes += data.len() as u64;
+		batch.literal_bytes += data.len() as u64;
+		// Literals dominate message size, so flush on those as well as op count
+		if batch.literal_bytes >= MAX_LITERAL as u64 || batch.ops.len() >= MAX_BATCH_OPS {
+			emit(std::mem::take(batch))?;
+		}
+		Ok(())
+	}
+
+	fn push_copy(
+		&self,
+		batch: &mut DeltaBatch,
+		block: u32,
+		len: u64,
+		emit: &mut impl FnMut(DeltaBatch) -> io::Result<()>,
+	) -> io::Result<()> {
+		batch.bytes += len;
+		if let Some(DeltaOp::Copy {
+			block: first,
+			count,
+		}) = batch.ops.last_mut()
+		{
+			if *first + *count == block {
+				*count += 1;
+				return Ok(());
+			}
+		}
+		batch.ops.push(DeltaOp::Copy { block, count: 1 });
+		if batch.ops.len() >= MAX_BATCH_OPS {
+			emit(std::mem::take(batch))?;
+		}
+		Ok(())
+	}
+}
+
+/// Read until `buf` holds at least `want` bytes or the reader runs out
+fn fill<R: Read>(reader: &mut R, buf: &mut Vec<u8>, want: usize, eof: &mut bool) -> io::Result<()> {
+	while !*eof && buf.len() < want {
+		let old_len = buf.len();
+		buf.resize(old_len + READ_SIZE, 0);
+		let read = read_full(reader, &mut buf[old_len..])?;
+		buf.truncate(old_len + read);
+		if read == 0 {
+			*eof = true;
+		}
+	}
+	Ok(())
+}
+
+/// Stream a file as `DeltaChunk` messages against the receiver's signature
+///
+/// Encoding runs on a blocking thread while batches are sent as they're ready.
+/// Literal bytes are paced by `throttle`, block references are too small to count.
+/// `on_progress` gets the bytes of the new file covered so far. Returns the bytes
+/// covered and the literal bytes sent.
+pub async fn send_delta<W>(
+	send: &mut W,
+	transfer_id: Uuid,
+	path: &Path,
+	signature: FileSignature,
+	throttle: Option<&TransferThrottle>,
+	mut on_progress: impl FnMut(u64),
+) -> io::Result<(u64, u64)>
+where
+	W: AsyncWrite + Unpin + ?Sized,
+{
+	let (tx, mut rx) = mpsc::channel::<DeltaBatch>(4);
+	let path = path.to_path_buf();
+	let encoder = tokio::task::spawn_blocking(move || {
+		let file = std::fs::File::open(&path)?;
+		DeltaEncoder::new(&signature).encode(file, |batch| {
+			tx.blocking_send(batch)
+				.map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "delta receiver closed"))
+		})
+	});
+
+	let (mut covered, mut literal) = (0u64, 0u64);
+	let mut chunk_index = 0u32;
+	while let Some(batch) = rx.recv().await {
+		covered += batch.bytes;
+		literal += batch.literal_bytes;
+		if let Some(throttle) = throttle {
+			throttle.consume(batch.literal_bytes).await;
+		}
+
+		let message = FileTransferMessage::DeltaChunk {
+			transfer_id,
+			chunk_index,
+			ops: batch.ops,
+		};
+		write_message(send, &message).await?;
+
+		chunk_index += 1;
+		on_progress(covered);
+	}
+
+	encoder.await.map_err(io::Error::other)??;
+	Ok((covered, literal))
+}
+
+/// Rebuilds a file from delta operations into a partial file next to its basis
+pub struct DeltaWriter {
+	destination: PathBuf,
+	partial: PathBuf,
+	basis: Option<File>,
+	output: File,
+	block_size: u64,
+	basis_size: u64,
+	written: u64,
+}
+
+impl DeltaWriter {
+	/// Start rebuilding `destination`, whose current content the signature describes
+	pub async fn create(destination: &Path, signature: &FileSignature) -> io::Result<Self> {
+		let basis = if signature.blocks.is_empty() {
+			None
+		} else {
+			Some(File::open(destination).await?)
+		};
+		let partial = partial_path(destination);
+		let output = File::create(&partial).await?;
+
+		Ok(Self {
+			destination: destination.to_path_buf(),
+			partial,
+			basis,
+			output,
+			block_size: signature.block_size as u64,
+			basis_size: signature.file_size,
+			written: 0,
+		})
+	}
+
+	/// Path the file is rebuilt at until it's verified
+	pub fn partial_path(&self) -> &Path {
+		&self.partial
+	}
+
+	/// Bytes of the new file written so far
+	pub fn written(&self) -> u64 {
+		self.written
+	}
+
+	pub async fn apply(&mut self, ops: Vec<DeltaOp>) -> io::Result<()> {
+		for op in ops {
+			match op {
+				DeltaOp::Literal(data) => {
+					self.output.write_all(&data).await?;
+					self.written += data.len() as u64;
+				}
+				DeltaOp::Copy { block, count } => {
+					let start = block as u64 * self.block_size;
+					let end = (start + count as u64 * self.block_size).min(self.basis_size);
+					let basis = self.basis.as_mut().filter(|_| start < end).ok_or_else(|| {
+						io::Error::new(
+							io::ErrorKind::InvalidData,
+							format!("delta references missing blocks {}+{}", block, count),
+						)
+					})?;
+
+					basis.seek(io::SeekFrom::Start(start)).await?;
+					let copied =
+						tokio::io::copy(&mut basis.take(end - start), &mut self.output).await?;
+					if copied != end - start {
+						return Err(io::Error::new(
+							io::ErrorKind::UnexpectedEof,
+							"basis file changed during transfer",
+						));
+					}
+					self.written += copied;
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Flush the rebuilt file, returning its partial path for verification
+	pub async fn finish(mut self) -> io::Result<FinishedDelta> {
+		self.output.flush().await?;
+		self.output.sync_all().await?;
+		Ok(FinishedDelta {
+			destination: self.destination,
+			partial: self.partial,
+		})
+	}
+
+	/// Give up and remove the partial file
+	pub async fn abort(self) {
+		let partial = self.partial.clone();
+		drop(self);
+		let _ = tokio::fs::remove_file(partial).await;
+	}
+}
+
+/// A fully rebuilt file waiting for verification
+pub struct FinishedDelta {
+	destination: PathBuf,
+	partial: PathBuf,
+}
+
+impl FinishedDelta {
+	pub fn partial_path(&self) -> &Path {
+		&self.partial
+	}
+
+	/// Replace the basis with the rebuilt file
+	pub async fn commit(self) -> io::Result<PathBuf> {
+		tokio::fs::rename(&self.partial, &self.destination).await?;
+		Ok(self.destination)
+	}
+
+	/// Discard the rebuilt file, keeping the basis
+	pub async fn discard(self) {
+		let _ = tokio::fs::remove_file(&self.partial).await;
+	}
+}
+
+fn partial_path(destination: &Path) -> PathBuf {
+	let mut name = destination.file_name().unwrap_or_default().to_os_string();
+	name.push(PARTIAL_SUFFIX);
+	destination.with_file_name(name)
+}
+
+/// Whether an existing file is worth receiving a delta against
+///
+/// False when the file is missing, not a regular file, or too small to gain from a delta.
+pub async fn is_delta_basis(path: &Path) -> bool {
+	tokio::fs::symlink_metadata(path)
+		.await
+		.map(|metadata| metadata.is_file() && metadata.len() >= MIN_DELTA_FILE_SIZE)
+		.unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Deterministic bytes that don't repeat within a block
+	fn data(len: usize, seed: u64) -> Vec<u8> {
+		let mut state = seed;
+		(0..len)
+			.map(|_| {
+				state = state
+					.wrapping_mul(6364136223846793005)
+					.wrapping_add(1442695040888963407);
+				(state >> 33) as u8
+			})
+			.collect()
+	}
+
+	fn encode(basis: &[u8], new: &[u8], block_size: u32) -> (Vec<DeltaOp>, u64) {
+		let signature = FileSignature::compute(basis, block_size).unwrap();
+		let mut ops = Vec::new();
+		let mut literal = 0;
+		DeltaEncoder::new(&signature)
+			.encode(new, |batch| {
+				literal += batch.literal_bytes;
+				ops.extend(batch.ops);
+				Ok(())
+			})
+			.unwrap();
+		(ops, literal)
+	}
+
+	fn rebuild(basis: &[u8], ops: &[DeltaOp], block_size: usize) -> Vec<u8> {
+		let mut out = Vec::new();
+		for op in ops {
+			match op {
+				DeltaOp::Literal(data) => out.extend_from_slice(data),
+				DeltaOp::Copy { block, count } => {
+					let start = *block as usize * block_size;
+					let end = (start + *count as usize * block_size).min(basis.len());
+					out.extend_from_slice(&basis[start..end]);
+				}
+			}
+		}
+		out
+	}
+
+	#[test]
+	fn test_rolling_checksum_matches_fresh() {
+		let bytes = data(4096, 1);
+		let mut rolling = RollingChecksum::new(&bytes[..1024]);
+		for i in 0..1000 {
+			rolling.roll(bytes[i], bytes[i + 1024]);
+			assert_eq!(
+				rolling.value(),
+				RollingChecksum::new(&bytes[i + 1..i + 1025]).value()
+			);
+		}
+	}
+
+	#[test]
+	fn test_unchanged_file_is_all_copies() {
+		let basis = data(10_000, 2);
+		let (ops, literal) = encode(&basis, &basis, 2048);
+
+		assert_eq!(literal, 0);
+		assert_eq!(ops, vec![DeltaOp::Copy { block: 0, count: 5 }]);
+		assert_eq!(rebuild(&basis, &ops, 2048), basis);
+	}
+
+	#[test]
+	fn test_insertion_sends_only_new_bytes() {
+		let basis = data(64 * 1024, 3);
+		let mut new = basis[..20_000].to_vec();
+		new.extend_from_slice(b"inserted in the middle");
+		new.extend_from_slice(&basis[20_000..]);
+
+		let (ops, literal) = encode(&basis, &new, 2048);
+
+		assert_eq!(rebuild(&basis, &ops, 2048), new);
+		// The insertion plus the block it landed in
+		assert!(literal < 2048 + 64, "sent {} literal bytes", literal);
+	}
+
+	#[test]
+	fn test_unrelated_file_is_all_literal() {
+		let basis = data(20_000, 4);
+		let new = data(30_000, 5);
+		let (ops, literal) = encode(&basis, &new, 2048);
+
+		assert_eq!(literal, new.len() as u64);
+		assert_eq!(rebuild(&basis, &ops, 2048), new);
+	}
+
+	#[test]
+	fn test_empty_basis() {
+		let new = data(5_000, 6);
+		let (ops, literal) = encode(&[], &new, 2048);
+
+		assert_eq!(literal, new.len() as u64);
+		assert_eq!(rebuild(&[], &ops, 2048), new);
+	}
+
+	#[test]
+	fn test_block_size_bounds() {
+		assert_eq!(block_size_for(0), MIN_BLOCK_SIZE);
+		assert_eq!(block_size_for(1 << 30), 32 * 1024);
+		assert_eq!(block_size_for(1 << 50), MAX_BLOCK_SIZE);
+	}
+
+	#[test]
+	fn test_signature_validation() {
+		let signature = FileSignature::compute(&data(10_000, 9)[..], 2048).unwrap();
+		assert!(signature.is_valid());
+		assert!(FileSignature::compute(&[][..], 2048).unwrap().is_valid());
+
+		// Block sizes `block_size_for` never picks
+		for block_size in [0, 1000, MIN_BLOCK_SIZE - 1024, MAX_BLOCK_SIZE + 1024] {
+			let invalid = FileSignature {
+				block_size,
+				..signature.clone()
+			};
+			assert!(!invalid.is_valid());
+		}
+
+		// Too few or too many blocks for the claimed size
+		let mut short = signature.clone();
+		short.blocks.pop();
+		assert!(!short.is_valid());
+		let oversized = FileSignature {
+			file_size: u64::MAX,
+			..signature.clone()
+		};
+		assert!(!oversized.is_valid());
+		assert_eq!(oversized.block_len(0), 2048);
+		assert_eq!(signature.block_len(u32::MAX), 0);
+	}
+
+	#[tokio::test]
+	async fn test_writer_rebuilds_file() {
+		let dir = tempfile::tempdir().unwrap();
+		let destination = dir.path().join("image.bin");
+		let basis = data(300_000, 7);
+		tokio::fs::write(&destination, &basis).await.unwrap();
+
+		let mut new = basis.clone();
+		new[150_000..150_100].copy_from_slice(&data(100, 8));
+
+		let signature = FileSignature::of_file(&destination).await.unwrap();
+		let (ops, _) = encode(&basis, &new, signature.block_size);
+
+		let mut writer = DeltaWriter::create(&destination, &signature).await.unwrap();
+		writer.apply(ops).await.unwrap();
+		assert_eq!(writer.written(), new.len() as u64);
+
+		let finished = writer.finish().await.unwrap();
+		// The basis stays untouched until the result is verified
+		assert_eq!(tokio::fs::read(&destination).await.unwrap(), basis);
+		finished.commit().await.unwrap();
+		assert_eq!(tokio::fs::read(&destination).await.unwrap(), new);
+	}
+}
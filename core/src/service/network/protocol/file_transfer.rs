@@ -1,17 +1,27 @@
 //! File transfer protocol for cross-device file operations
 
+use super::bandwidth::{BandwidthLimiter, TransferThrottle};
+use super::compression::{decompress_chunk, is_precompressed, ChunkCompression, CompressionStats};
+use super::file_delta::{block_size_for, is_delta_basis, DeltaOp, DeltaWriter, FileSignature};
+use crate::config::app_config::FileTransferConfig;
 use crate::service::network::utils::logging::NetworkLogger;
 use crate::service::network::{NetworkingError, Result};
 use async_trait::async_trait;
 use iroh::EndpointId;
 use serde::{Deserialize, Serialize};
 use std::{
-	collections::HashMap,
+	collections::{HashMap, HashSet},
 	path::PathBuf,
-	sync::{Arc, RwLock},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, RwLock,
+	},
 	time::{Duration, SystemTime},
 };
-use tokio::{fs::File, io::AsyncReadExt};
+use tokio::{
+	fs::File,
+	io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+};
 use uuid::Uuid;
 
 // Encryption imports
@@ -45,6 +55,13 @@ pub struct FileTransferProtocolHandler {
 	allowed_paths: Arc<RwLock<Vec<PathBuf>>>,
 	/// Core context for dynamic location lookup (if available).
 	core_context: Option<std::sync::Arc<crate::context::CoreContext>>,
+	/// Rate limits for outgoing transfers
+	bandwidth: Arc<BandwidthLimiter>,
+	/// Whether chunks are compressed when the other side supports it
+	compression: AtomicBool,
+	/// Connected devices that didn't answer capability negotiation, so PUSHes to them
+	/// skip it until they reconnect
+	legacy_peers: RwLock<HashSet<Uuid>>,
 }
 
 /// Configuration for file transfers
@@ -58,6 +75,8 @@ pub struct TransferConfig {
 	pub transfer_timeout: Duration,
 	/// Enable integrity verification
 	pub verify_checksums: bool,
+	/// Accept delta transfers against existing copies of incoming files
+	pub delta_transfers: bool,
 }
 
 impl Default for TransferConfig {
@@ -67,6 +86,7 @@ impl Default for TransferConfig {
 			max_concurrent_transfers: 10,
 			transfer_timeout: Duration::from_secs(300), // 5 minutes
 			verify_checksums: true,
+			delta_transfers: true,
 		}
 	}
 }
@@ -124,6 +144,19 @@ pub struct FileMetadata {
 	pub mime_type: Option<String>,
 }
 
+/// Optional protocol features
+///
+/// The initiating side offers what it supports and the other side answers with what it
+/// enabled for the transfer. Peers that predate negotiation send none, and get no answer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferCapabilities {
+	/// Send only what changed against the receiver's existing copy (see `file_delta`)
+	pub delta: bool,
+	/// Compress file chunks with zstd (see `compression`)
+	#[serde(default)]
+	pub compression: bool,
+}
+
 /// Universal message types for file operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FileTransferMessage {
@@ -135,6 +168,9 @@ pub enum FileTransferMessage {
 		chunk_size: u32,
 		total_chunks: u32,
 		destination_path: String,
+		/// Features the sender supports; a response is only sent when present
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		capabilities: Option<TransferCapabilities>,
 	},
 
 	/// Response to transfer request
@@ -143,6 +179,9 @@ pub enum FileTransferMessage {
 		accepted: bool,
 		reason: Option<String>,
 		supported_resume: bool,
+		/// Features the receiver enabled for this transfer
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		capabilities: Option<TransferCapabilities>,
 	},
 
 	/// File data chunk
@@ -152,6 +191,9 @@ pub enum FileTransferMessage {
 		data: Vec<u8>,            // Encrypted data
 		nonce: [u8; 12],          // ChaCha20-Poly1305 nonce
 		chunk_checksum: [u8; 32], // Checksum of original (unencrypted) data
+		/// Encoding of `data`, when it was compressed for the wire
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		compression: Option<ChunkCompression>,
 	},
 
 	/// Acknowledge received chunk
@@ -187,6 +229,15 @@ pub enum FileTransferMessage {
 		source_path: PathBuf,
 		/// The device ID making the request
 		requested_by: Uuid,
+		/// Features the requester supports
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		capabilities: Option<TransferCapabilities>,
+		/// Signature of the requester's existing copy, when offering a delta
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		basis: Option<FileSignature>,
+		/// Cap on the source's sending rate for this transfer, in megabits per second
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		rate_limit_mbps: Option<u32>,
 	},
 
 	/// Response to a pull request
@@ -198,6 +249,22 @@ pub enum FileTransferMessage {
 		accepted: bool,
 		/// Error message if rejected
 		error: Option<String>,
+		/// Features the source enabled for this transfer
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		capabilities: Option<TransferCapabilities>,
+	},
+
+	/// Signature of the receiver's existing copy, sent after accepting a delta transfer
+	BlockSignatures {
+		transfer_id: Uuid,
+		signature: FileSignature,
+	},
+
+	/// Part of a delta transfer, sent instead of `FileChunk`s
+	DeltaChunk {
+		transfer_id: Uuid,
+		chunk_index: u32,
+		ops: Vec<DeltaOp>,
 	},
 }
 
@@ -235,6 +302,9 @@ impl FileTransferProtocolHandler {
 			logger,
 			allowed_paths: Arc::new(RwLock::new(Vec::new())),
 			core_context: None,
+			bandwidth: Arc::new(BandwidthLimiter::new(Default::default())),
+			compression: AtomicBool::new(true),
+			legacy_peers: RwLock::new(HashSet::new()),
 		}
 	}
 
@@ -248,11 +318,12 @@ impl FileTransferProtocolHandler {
 				chunk_size,
 				total_chunks,
 				destination_path,
+				capabilities,
 			} => {
-				format!("TransferRequest {{ transfer_id: {}, file_metadata: FileMetadata {{ name: \"{}\", size: {}, is_directory: {}, checksum: {:?}, .. }}, transfer_mode: {:?}, chunk_size: {}, total_chunks: {}, destination_path: \"{}\" }}",
+				format!("TransferRequest {{ transfer_id: {}, file_metadata: FileMetadata {{ name: \"{}\", size: {}, is_directory: {}, checksum: {:?}, .. }}, transfer_mode: {:?}, chunk_size: {}, total_chunks: {}, destination_path: \"{}\", capabilities: {:?} }}",
 					transfer_id, file_metadata.name, file_metadata.size, file_metadata.is_directory,
 					file_metadata.checksum.as_ref().map(|c| &c[..16]).unwrap_or("None"),
-					transfer_mode, chunk_size, total_chunks, destination_path)
+					transfer_mode, chunk_size, total_chunks, destination_path, capabilities)
 			}
 			FileTransferMessage::FileChunk {
 				transfer_id,
@@ -260,9 +331,10 @@ impl FileTransferProtocolHandler {
 				data,
 				nonce,
 				chunk_checksum,
+				compression,
 			} => {
-				format!("FileChunk {{ transfer_id: {}, chunk_index: {}, data: [{} bytes], nonce: [{} bytes], chunk_checksum: [{} bytes] }}",
-					transfer_id, chunk_index, data.len(), nonce.len(), chunk_checksum.len())
+				format!("FileChunk {{ transfer_id: {}, chunk_index: {}, data: [{} bytes], nonce: [{} bytes], chunk_checksum: [{} bytes], compression: {:?} }}",
+					transfer_id, chunk_index, data.len(), nonce.len(), chunk_checksum.len(), compression)
 			}
 			FileTransferMessage::TransferComplete {
 				transfer_id,
@@ -279,9 +351,10 @@ impl FileTransferProtocolHandler {
 				accepted,
 				reason,
 				supported_resume,
+				capabilities,
 			} => {
-				format!("TransferResponse {{ transfer_id: {}, accepted: {}, reason: {:?}, supported_resume: {} }}",
-					transfer_id, accepted, reason, supported_resume)
+				format!("TransferResponse {{ transfer_id: {}, accepted: {}, reason: {:?}, supported_resume: {}, capabilities: {:?} }}",
+					transfer_id, accepted, reason, supported_resume, capabilities)
 			}
 			FileTransferMessage::ChunkAck {
 				transfer_id,
@@ -309,12 +382,18 @@ impl FileTransferProtocolHandler {
 				transfer_id,
 				source_path,
 				requested_by,
+				capabilities,
+				basis,
+				rate_limit_mbps,
 			} => {
 				format!(
-					"PullRequest {{ transfer_id: {}, source_path: \"{}\", requested_by: {} }}",
+					"PullRequest {{ transfer_id: {}, source_path: \"{}\", requested_by: {}, capabilities: {:?}, basis: {:?}, rate_limit_mbps: {:?} }}",
 					transfer_id,
 					source_path.display(),
-					requested_by
+					requested_by,
+					capabilities,
+					basis.as_ref().map(|b| format!("[{} blocks]", b.blocks.len())),
+					rate_limit_mbps
 				)
 			}
 			FileTransferMessage::PullResponse {
@@ -322,13 +401,38 @@ impl FileTransferProtocolHandler {
 				file_metadata,
 				accepted,
 				error,
+				capabilities,
 			} => {
 				format!(
-					"PullResponse {{ transfer_id: {}, file_metadata: {:?}, accepted: {}, error: {:?} }}",
+					"PullResponse {{ transfer_id: {}, file_metadata: {:?}, accepted: {}, error: {:?}, capabilities: {:?} }}",
 					transfer_id,
 					file_metadata.as_ref().map(|m| &m.name),
 					accepted,
-					error
+					error,
+					capabilities
+				)
+			}
+			FileTransferMessage::BlockSignatures {
+				transfer_id,
+				signature,
+			} => {
+				format!(
+					"BlockSignatures {{ transfer_id: {}, block_size: {}, blocks: [{} blocks] }}",
+					transfer_id,
+					signature.block_size,
+					signature.blocks.len()
+				)
+			}
+			FileTransferMessage::DeltaChunk {
+				transfer_id,
+				chunk_index,
+				ops,
+			} => {
+				format!(
+					"DeltaChunk {{ transfer_id: {}, chunk_index: {}, ops: [{} ops] }}",
+					transfer_id,
+					chunk_index,
+					ops.len()
 				)
 			}
 		}
@@ -364,6 +468,44 @@ impl FileTransferProtocolHandler {
 		self.core_context = Some(context);
 	}
 
+	/// Apply compression and bandwidth settings from the app config.
+	/// Transfers already in flight pick up new bandwidth limits on their next chunk.
+	pub fn set_transfer_config(&self, config: FileTransferConfig) {
+		self.compression
+			.store(config.compression, Ordering::Relaxed);
+		self.bandwidth.set_config(config.bandwidth);
+	}
+
+	/// Whether chunks are compressed when the other side supports it
+	pub fn compression_enabled(&self) -> bool {
+		self.compression.load(Ordering::Relaxed)
+	}
+
+	/// Start rate limiting an outgoing transfer to `device_id`, optionally capped further
+	/// (e.g. by the sync conduit it belongs to)
+	pub fn throttle(&self, device_id: Uuid, limit_mbps: Option<u32>) -> TransferThrottle {
+		self.bandwidth.throttle(device_id, limit_mbps)
+	}
+
+	/// Whether to offer capabilities to a device, false once it has shown it predates them
+	pub fn negotiates_with(&self, device_id: Uuid) -> bool {
+		!self.legacy_peers.read().unwrap().contains(&device_id)
+	}
+
+	/// Remember that a device didn't answer capability negotiation
+	pub fn mark_legacy_peer(&self, device_id: Uuid) {
+		self.legacy_peers.write().unwrap().insert(device_id);
+	}
+
+	/// Whether a file's contents are worth compressing, judged by its extension
+	pub fn should_compress(&self, path: &std::path::Path) -> bool {
+		let kind = match &self.core_context {
+			Some(context) => context.file_type_registry().identify_by_extension(path),
+			None => return true,
+		};
+		!is_precompressed(kind)
+	}
+
 	/// Get all allowed paths by combining static allowed_paths with dynamic locations.
 	/// This queries all libraries for their registered locations asynchronously.
 	async fn get_all_allowed_paths(&self) -> Vec<PathBuf> {
@@ -689,6 +831,7 @@ impl FileTransferProtocolHandler {
 					accepted: false,
 					reason: Some("Destination path not within allowed locations".to_string()),
 					supported_resume: false,
+					capabilities: None,
 				});
 			}
 
@@ -730,6 +873,7 @@ impl FileTransferProtocolHandler {
 					Some("User declined".to_string())
 				},
 				supported_resume: true,
+				capabilities: None,
 			})
 		} else {
 			Err(NetworkingError::Protocol(
@@ -750,6 +894,7 @@ impl FileTransferProtocolHandler {
 			data,
 			nonce,
 			chunk_checksum,
+			compression,
 		} = chunk
 		{
 			// Get session keys for decryption
@@ -763,6 +908,7 @@ impl FileTransferProtocolHandler {
 				&data,
 				&nonce,
 			)?;
+			let decrypted_data = decompress_chunk(decrypted_data, compression)?;
 
 			// Verify chunk checksum (of decrypted data)
 			if self.config.verify_checksums {
@@ -1047,6 +1193,7 @@ impl FileTransferProtocolHandler {
 		encrypted_data: Vec<u8>,
 		nonce: [u8; 12],
 		chunk_checksum: [u8; 32],
+		compression: Option<ChunkCompression>,
 	) -> Result<()> {
 		self.logger
 			.debug(&format!(
@@ -1070,7 +1217,9 @@ impl FileTransferProtocolHandler {
 		};
 
 		// Skip decryption - Iroh already provides E2E encryption for the connection
-		let chunk_data = encrypted_data;
+		let chunk_data = decompress_chunk(encrypted_data, compression).map_err(|e| {
+			NetworkingError::Protocol(format!("Failed to decompress chunk {}: {}", chunk_index, e))
+		})?;
 
 		self.logger
 			.debug(&format!(
@@ -1148,6 +1297,16 @@ impl FileTransferProtocolHandler {
 			))
 			.await;
 
+		// A transfer that failed midway must not be acknowledged as complete
+		if let Some(TransferState::Failed(reason)) =
+			self.get_session(&transfer_id).map(|session| session.state)
+		{
+			return Err(NetworkingError::Protocol(format!(
+				"Transfer {} failed: {}",
+				transfer_id, reason
+			)));
+		}
+
 		// Mark transfer as completed
 		self.update_session_state(&transfer_id, TransferState::Completed)?;
 
@@ -1158,6 +1317,173 @@ impl FileTransferProtocolHandler {
 		Ok(())
 	}
 
+	/// Answer a sender that negotiates capabilities, preparing a delta transfer if both
+	/// sides want one
+	async fn respond_to_transfer_request(
+		&self,
+		send: &mut (dyn AsyncWrite + Send + Unpin),
+		transfer_id: Uuid,
+		accepted: std::result::Result<(), String>,
+		offered: TransferCapabilities,
+		destination_path: &str,
+		deltas: &mut HashMap<Uuid, DeltaWriter>,
+	) -> Result<()> {
+		let destination = PathBuf::from(destination_path);
+		let delta = accepted.is_ok()
+			&& offered.delta
+			&& self.config.delta_transfers
+			&& is_delta_basis(&destination).await;
+
+		let compression = accepted.is_ok() && offered.compression && self.compression_enabled();
+
+		let response = FileTransferMessage::TransferResponse {
+			transfer_id,
+			accepted: accepted.is_ok(),
+			reason: accepted.err(),
+			supported_resume: false,
+			capabilities: Some(TransferCapabilities { delta, compression }),
+		};
+		write_message(send, &response).await?;
+
+		if !delta {
+			return Ok(());
+		}
+
+		// An unreadable basis degrades to an empty signature, so everything is sent as literals
+		let signature = match FileSignature::of_file(&destination).await {
+			Ok(signature) => signature,
+			Err(e) => {
+				self.logger
+					.warn(&format!(
+						"Failed to read {} for delta transfer {}: {}",
+						destination.display(),
+						transfer_id,
+						e
+					))
+					.await;
+				FileSignature {
+					block_size: block_size_for(0),
+					..Default::default()
+				}
+			}
+		};
+
+		match DeltaWriter::create(&destination, &signature).await {
+			Ok(writer) => {
+				deltas.insert(transfer_id, writer);
+				self.logger
+					.info(&format!(
+						"Delta transfer {} against {} ({} blocks)",
+						transfer_id,
+						destination.display(),
+						signature.blocks.len()
+					))
+					.await;
+				write_message(
+					send,
+					&FileTransferMessage::BlockSignatures {
+						transfer_id,
+						signature,
+					},
+				)
+				.await?;
+			}
+			Err(e) => {
+				let message = format!("Failed to prepare delta transfer: {}", e);
+				self.update_session_state(&transfer_id, TransferState::Failed(message.clone()))?;
+				write_message(
+					send,
+					&FileTransferMessage::TransferError {
+						transfer_id,
+						error_type: TransferErrorType::FileSystemError,
+						message,
+						recoverable: true,
+					},
+				)
+				.await?;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Apply a received `DeltaChunk`, failing the transfer if it can't be applied
+	async fn handle_incoming_delta_chunk(
+		&self,
+		transfer_id: Uuid,
+		ops: Vec<DeltaOp>,
+		deltas: &mut HashMap<Uuid, DeltaWriter>,
+	) -> Result<()> {
+		let Some(writer) = deltas.get_mut(&transfer_id) else {
+			return Err(NetworkingError::Protocol(format!(
+				"No delta transfer in progress for {}",
+				transfer_id
+			)));
+		};
+
+		match writer.apply(ops).await {
+			Ok(()) => {
+				let written = writer.written();
+				let mut sessions = self.sessions.write().unwrap();
+				if let Some(session) = sessions.get_mut(&transfer_id) {
+					session.bytes_transferred = written;
+				}
+				Ok(())
+			}
+			Err(e) => {
+				if let Some(writer) = deltas.remove(&transfer_id) {
+					writer.abort().await;
+				}
+				let message = format!("Failed to apply delta: {}", e);
+				self.update_session_state(&transfer_id, TransferState::Failed(message.clone()))?;
+				Err(NetworkingError::Protocol(message))
+			}
+		}
+	}
+
+	/// Verify a file rebuilt from a delta against the sender's content hash and put it
+	/// in place of the old copy
+	async fn finish_delta_transfer(
+		&self,
+		transfer_id: Uuid,
+		writer: DeltaWriter,
+		final_checksum: &str,
+	) -> Result<()> {
+		let finished = writer.finish().await?;
+		let checksum = match self
+			.calculate_file_checksum(&finished.partial_path().to_path_buf())
+			.await
+		{
+			Ok(checksum) => checksum,
+			Err(e) => {
+				finished.discard().await;
+				return Err(e);
+			}
+		};
+
+		if checksum != final_checksum {
+			finished.discard().await;
+			let message = format!(
+				"Delta checksum mismatch: expected {}, got {}",
+				final_checksum, checksum
+			);
+			self.update_session_state(&transfer_id, TransferState::Failed(message.clone()))?;
+			return Err(NetworkingError::Protocol(message));
+		}
+
+		let destination = finished.commit().await?;
+		self.update_session_state(&transfer_id, TransferState::Completed)?;
+		self.logger
+			.info(&format!(
+				"Delta transfer {} completed and verified: {}",
+				transfer_id,
+				destination.display()
+			))
+			.await;
+
+		Ok(())
+	}
+
 	/// Validate that a path is safe to access for PULL requests.
 	/// Prevents directory traversal attacks and enforces access boundaries.
 	/// SECURITY: Only allows access to files within registered locations.
@@ -1193,10 +1519,11 @@ impl FileTransferProtocolHandler {
 		transfer_id: Uuid,
 		source_path: PathBuf,
 		requested_by: Uuid,
+		capabilities: Option<TransferCapabilities>,
+		basis: Option<FileSignature>,
+		rate_limit_mbps: Option<u32>,
 		send: &mut (dyn tokio::io::AsyncWrite + Send + Unpin),
 	) -> Result<()> {
-		use tokio::io::AsyncWriteExt;
-
 		self.logger
 			.info(&format!(
 				"Handling PULL request {} for path: {} from device {}",
@@ -1221,9 +1548,10 @@ impl FileTransferProtocolHandler {
 				file_metadata: None,
 				accepted: false,
 				error: Some("Access denied".to_string()),
+				capabilities: None,
 			};
 
-			let response_data = rmp_serde::to_vec(&response)
+			let response_data = encode_message(&response)
 				.map_err(|e| NetworkingError::Protocol(format!("Serialization failed: {}", e)))?;
 
 			send.write_u8(0).await.map_err(|e| {
@@ -1267,15 +1595,41 @@ impl FileTransferProtocolHandler {
 			mime_type: None,
 		};
 
+		// Delta against the requester's copy when it offered one
+		// A malformed basis signature is ignored and the whole file sent
+		let basis = match basis {
+			Some(signature) if !signature.is_valid() => {
+				self.logger
+					.warn(&format!(
+						"PULL request {} carried an invalid basis signature, sending the whole file",
+						transfer_id
+					))
+					.await;
+				None
+			}
+			basis => basis,
+		};
+		let delta = basis.filter(|_| {
+			self.config.delta_transfers && capabilities.is_some_and(|offered| offered.delta)
+		});
+		let compression = delta.is_none()
+			&& capabilities.is_some_and(|offered| offered.compression)
+			&& self.compression_enabled()
+			&& self.should_compress(&source_path);
+
 		// Send acceptance response
 		let response = FileTransferMessage::PullResponse {
 			transfer_id,
 			file_metadata: Some(file_metadata.clone()),
 			accepted: true,
 			error: None,
+			capabilities: capabilities.map(|_| TransferCapabilities {
+				delta: delta.is_some(),
+				compression,
+			}),
 		};
 
-		let response_data = rmp_serde::to_vec(&response)
+		let response_data = encode_message(&response)
 			.map_err(|e| NetworkingError::Protocol(format!("Serialization failed: {}", e)))?;
 
 		send.write_u8(0).await.map_err(|e| {
@@ -1300,9 +1654,71 @@ impl FileTransferProtocolHandler {
 			))
 			.await;
 
+		let throttle = self.throttle(requested_by, rate_limit_mbps);
+
 		// Stream file chunks to requester
-		self.stream_file_for_pull(transfer_id, &source_path, file_size, checksum, send)
-			.await?;
+		match delta {
+			Some(signature) => {
+				self.stream_delta_for_pull(
+					transfer_id,
+					&source_path,
+					checksum,
+					signature,
+					&throttle,
+					send,
+				)
+				.await?
+			}
+			None => {
+				self.stream_file_for_pull(
+					transfer_id,
+					&source_path,
+					file_size,
+					checksum,
+					compression,
+					&throttle,
+					send,
+				)
+				.await?
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Stream only what changed against the PULL requester's copy
+	async fn stream_delta_for_pull(
+		&self,
+		transfer_id: Uuid,
+		source_path: &PathBuf,
+		final_checksum: Option<String>,
+		signature: FileSignature,
+		throttle: &TransferThrottle,
+		send: &mut (dyn tokio::io::AsyncWrite + Send + Unpin),
+	) -> Result<()> {
+		let (total_bytes, literal_bytes) = super::file_delta::send_delta(
+			send,
+			transfer_id,
+			source_path,
+			signature,
+			Some(throttle),
+			|_| {},
+		)
+		.await?;
+
+		let completion_message = FileTransferMessage::TransferComplete {
+			transfer_id,
+			final_checksum: final_checksum.unwrap_or_default(),
+			total_bytes,
+		};
+		write_message(send, &completion_message).await?;
+
+		self.logger
+			.info(&format!(
+				"PULL delta transfer {} completed: {} bytes, {} sent as literals",
+				transfer_id, total_bytes, literal_bytes
+			))
+			.await;
 
 		Ok(())
 	}
@@ -1314,6 +1730,8 @@ impl FileTransferProtocolHandler {
 		source_path: &PathBuf,
 		file_size: u64,
 		final_checksum: Option<String>,
+		compress: bool,
+		throttle: &TransferThrottle,
 		send: &mut (dyn tokio::io::AsyncWrite + Send + Unpin),
 	) -> Result<()> {
 		use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -1326,6 +1744,7 @@ impl FileTransferProtocolHandler {
 		let mut buffer = vec![0u8; chunk_size];
 		let mut chunk_index = 0u32;
 		let mut bytes_sent = 0u64;
+		let mut stats = CompressionStats::default();
 
 		loop {
 			let bytes_read = file.read(&mut buffer).await.map_err(|e| {
@@ -1338,17 +1757,20 @@ impl FileTransferProtocolHandler {
 
 			let chunk_data = &buffer[..bytes_read];
 			let chunk_checksum = blake3::hash(chunk_data);
+			let (data, compression) = stats.encode(chunk_data, compress);
+			throttle.consume(data.len() as u64).await;
 
 			// Skip encryption - Iroh provides E2E encryption
 			let chunk_message = FileTransferMessage::FileChunk {
 				transfer_id,
 				chunk_index,
-				data: chunk_data.to_vec(),
+				data,
 				nonce: [0u8; 12], // Dummy nonce since we're not encrypting
 				chunk_checksum: *chunk_checksum.as_bytes(),
+				compression,
 			};
 
-			let message_data = rmp_serde::to_vec(&chunk_message)
+			let message_data = encode_message(&chunk_message)
 				.map_err(|e| NetworkingError::Protocol(format!("Serialization failed: {}", e)))?;
 
 			send.write_u8(0).await.map_err(|e| {
@@ -1386,7 +1808,7 @@ impl FileTransferProtocolHandler {
 			total_bytes: bytes_sent,
 		};
 
-		let completion_data = rmp_serde::to_vec(&completion_message)
+		let completion_data = encode_message(&completion_message)
 			.map_err(|e| NetworkingError::Protocol(format!("Serialization failed: {}", e)))?;
 
 		send.write_u8(0).await.map_err(|e| {
@@ -1406,8 +1828,12 @@ impl FileTransferProtocolHandler {
 
 		self.logger
 			.info(&format!(
-				"PULL transfer {} completed: {} chunks, {} bytes",
-				transfer_id, chunk_index, bytes_sent
+				"PULL transfer {} completed: {} chunks, {} bytes ({} on the wire, throttled for {:?})",
+				transfer_id,
+				chunk_index,
+				bytes_sent,
+				stats.wire_bytes,
+				throttle.throttled_for()
 			))
 			.await;
 
@@ -1462,6 +1888,8 @@ impl super::ProtocolHandler for FileTransferProtocolHandler {
 				// Keep reading messages until stream closes or TransferComplete received
 				// Note: The first type byte (0) was already read above
 				let mut first_message = true;
+				// Files being rebuilt from delta transfers on this stream
+				let mut deltas: HashMap<Uuid, DeltaWriter> = HashMap::new();
 
 				loop {
 					// For messages after the first, read the type byte
@@ -1512,165 +1940,238 @@ impl super::ProtocolHandler for FileTransferProtocolHandler {
 					}
 
 					// Deserialize and handle
-					if let Ok(message) = rmp_serde::from_slice::<FileTransferMessage>(&msg_buf) {
-						self.logger
-							.debug(&format!(
-								"Received file transfer message: {}",
-								Self::truncate_message_for_logging(&message)
-							))
-							.await;
+					let message = match rmp_serde::from_slice::<FileTransferMessage>(&msg_buf) {
+						Ok(message) => message,
+						Err(e) => {
+							self.logger
+								.error(&format!(
+									"Failed to decode file transfer message ({} bytes): {}",
+									msg_len, e
+								))
+								.await;
+							continue;
+						}
+					};
+					self.logger
+						.debug(&format!(
+							"Received file transfer message: {}",
+							Self::truncate_message_for_logging(&message)
+						))
+						.await;
 
-						// Get device ID from node ID using device registry
-						let device_id = if let Some(device_registry) = &self.device_registry {
-							let registry = device_registry.read().await;
-							registry
-								.get_device_by_node(remote_node_id)
-								.unwrap_or_else(|| {
-									// Note: Can't use await in closure, this should be refactored
-									tracing::warn!(
-										"Could not find device ID for node {}, using random ID",
-										remote_node_id
-									);
-									uuid::Uuid::new_v4()
-								})
-						} else {
-							// Note: Need to await this call properly
-							tracing::warn!("Device registry not available, using random device ID");
-							uuid::Uuid::new_v4()
-						};
-
-						// Process the message based on type
-						match message {
-							FileTransferMessage::TransferRequest {
-								transfer_id,
-								file_metadata,
-								destination_path,
-								..
-							} => {
-								// Handle transfer request
-								if let Err(e) = self
-									.handle_incoming_transfer_request(
-										device_id,
-										transfer_id,
-										file_metadata,
-										destination_path,
-									)
-									.await
-								{
-									self.logger
-										.error(&format!("Failed to handle transfer request: {}", e))
-										.await;
-								}
+					// Get device ID from node ID using device registry
+					let device_id = if let Some(device_registry) = &self.device_registry {
+						let registry = device_registry.read().await;
+						registry
+							.get_device_by_node(remote_node_id)
+							.unwrap_or_else(|| {
+								// Note: Can't use await in closure, this should be refactored
+								tracing::warn!(
+									"Could not find device ID for node {}, using random ID",
+									remote_node_id
+								);
+								uuid::Uuid::new_v4()
+							})
+					} else {
+						// Note: Need to await this call properly
+						tracing::warn!("Device registry not available, using random device ID");
+						uuid::Uuid::new_v4()
+					};
+
+					// Process the message based on type
+					match message {
+						FileTransferMessage::TransferRequest {
+							transfer_id,
+							file_metadata,
+							destination_path,
+							capabilities,
+							..
+						} => {
+							// Handle transfer request
+							let accepted = self
+								.handle_incoming_transfer_request(
+									device_id,
+									transfer_id,
+									file_metadata,
+									destination_path.clone(),
+								)
+								.await
+								.map_err(|e| e.to_string());
+							if let Err(e) = &accepted {
+								self.logger
+									.error(&format!("Failed to handle transfer request: {}", e))
+									.await;
 							}
-							FileTransferMessage::FileChunk {
-								transfer_id,
-								chunk_index,
-								data,
-								nonce,
-								chunk_checksum,
-							} => {
-								// Handle file chunk
+
+							// Senders that predate capabilities don't expect a response
+							if let Some(offered) = capabilities {
 								if let Err(e) = self
-									.handle_incoming_file_chunk(
+									.respond_to_transfer_request(
+										&mut *send,
 										transfer_id,
-										chunk_index,
-										data,
-										nonce,
-										chunk_checksum,
+										accepted,
+										offered,
+										&destination_path,
+										&mut deltas,
 									)
 									.await
 								{
 									self.logger
-										.error(&format!("Failed to handle file chunk: {}", e))
+										.error(&format!(
+											"Failed to respond to transfer request: {}",
+											e
+										))
 										.await;
 								}
 							}
-							FileTransferMessage::TransferComplete {
-								transfer_id,
-								final_checksum,
-								total_bytes,
-							} => {
-								// Handle transfer completion
-								if let Err(e) = self
-									.handle_incoming_transfer_complete(
+						}
+						FileTransferMessage::DeltaChunk {
+							transfer_id, ops, ..
+						} => {
+							if let Err(e) = self
+								.handle_incoming_delta_chunk(transfer_id, ops, &mut deltas)
+								.await
+							{
+								self.logger
+									.error(&format!("Failed to handle delta chunk: {}", e))
+									.await;
+							}
+						}
+						FileTransferMessage::FileChunk {
+							transfer_id,
+							chunk_index,
+							data,
+							nonce,
+							chunk_checksum,
+							compression,
+						} => {
+							// Handle file chunk
+							if let Err(e) = self
+								.handle_incoming_file_chunk(
+									transfer_id,
+									chunk_index,
+									data,
+									nonce,
+									chunk_checksum,
+									compression,
+								)
+								.await
+							{
+								self.logger
+									.error(&format!("Failed to handle file chunk: {}", e))
+									.await;
+							}
+						}
+						FileTransferMessage::TransferComplete {
+							transfer_id,
+							final_checksum,
+							total_bytes,
+						} => {
+							// Handle transfer completion
+							let completed = match deltas.remove(&transfer_id) {
+								Some(writer) => {
+									self.finish_delta_transfer(transfer_id, writer, &final_checksum)
+										.await
+								}
+								None => {
+									self.handle_incoming_transfer_complete(
 										transfer_id,
 										final_checksum.clone(),
 										total_bytes,
 									)
 									.await
-								{
-									self.logger
-										.error(&format!(
-											"Failed to handle transfer completion: {}",
-											e
-										))
-										.await;
-								} else {
-									// Send TransferFinalAck response back to sender
-									self.logger
-										.info(&format!(
-											"Sending TransferFinalAck for transfer {}",
-											transfer_id
-										))
-										.await;
-
-									let ack_message =
-										FileTransferMessage::TransferFinalAck { transfer_id };
-									if let Ok(ack_data) = rmp_serde::to_vec(&ack_message) {
-										// Send type (0) + length + data
-										let _ = send.write_u8(0).await;
-										let _ = send
-											.write_all(&(ack_data.len() as u32).to_be_bytes())
-											.await;
-										let _ = send.write_all(&ack_data).await;
-										let _ = send.flush().await;
-
-										self.logger
-											.info(&format!(
-												"TransferFinalAck sent for transfer {}",
-												transfer_id
-											))
-											.await;
-									}
 								}
-							}
-							FileTransferMessage::PullRequest {
-								transfer_id,
-								source_path,
-								requested_by,
-							} => {
-								// Handle PULL request - stream file back to requester
+							};
+
+							if let Err(e) = completed {
+								self.logger
+									.error(&format!("Failed to handle transfer completion: {}", e))
+									.await;
+
+								// Tell the sender instead of leaving it waiting for an ack
+								let error_message = FileTransferMessage::TransferError {
+									transfer_id,
+									error_type: TransferErrorType::ChecksumMismatch,
+									message: e.to_string(),
+									recoverable: false,
+								};
+								let _ = write_message(&mut *send, &error_message).await;
+							} else {
+								// Send TransferFinalAck response back to sender
 								self.logger
 									.info(&format!(
-										"Received PullRequest {} for path: {} from device {}",
-										transfer_id,
-										source_path.display(),
-										requested_by
+										"Sending TransferFinalAck for transfer {}",
+										transfer_id
 									))
 									.await;
 
-								if let Err(e) = self
-									.handle_pull_request(
-										transfer_id,
-										source_path,
-										requested_by,
-										&mut *send,
-									)
-									.await
-								{
+								let ack_message =
+									FileTransferMessage::TransferFinalAck { transfer_id };
+								if let Ok(ack_data) = encode_message(&ack_message) {
+									// Send type (0) + length + data
+									let _ = send.write_u8(0).await;
+									let _ = send
+										.write_all(&(ack_data.len() as u32).to_be_bytes())
+										.await;
+									let _ = send.write_all(&ack_data).await;
+									let _ = send.flush().await;
+
 									self.logger
-										.error(&format!("Failed to handle PULL request: {}", e))
+										.info(&format!(
+											"TransferFinalAck sent for transfer {}",
+											transfer_id
+										))
 										.await;
 								}
 							}
-							_ => {
+						}
+						FileTransferMessage::PullRequest {
+							transfer_id,
+							source_path,
+							requested_by,
+							capabilities,
+							basis,
+							rate_limit_mbps,
+						} => {
+							// Handle PULL request - stream file back to requester
+							self.logger
+								.info(&format!(
+									"Received PullRequest {} for path: {} from device {}",
+									transfer_id,
+									source_path.display(),
+									requested_by
+								))
+								.await;
+
+							if let Err(e) = self
+								.handle_pull_request(
+									transfer_id,
+									source_path,
+									requested_by,
+									capabilities,
+									basis,
+									rate_limit_mbps,
+									&mut *send,
+								)
+								.await
+							{
 								self.logger
-									.warn("Received unexpected file transfer message type")
+									.error(&format!("Failed to handle PULL request: {}", e))
 									.await;
 							}
 						}
-					} // Close the if let Ok(message)
+						_ => {
+							self.logger
+								.warn("Received unexpected file transfer message type")
+								.await;
+						}
+					}
 				} // Close the loop
+
+				// Drop files left half-rebuilt by an interrupted stream
+				for (_, writer) in deltas.drain() {
+					writer.abort().await;
+				}
 			}
 			1 => {
 				// File data stream
@@ -1716,7 +2217,7 @@ impl super::ProtocolHandler for FileTransferProtocolHandler {
 		};
 
 		// Serialize the response
-		rmp_serde::to_vec(&response)
+		encode_message(&response)
 			.map_err(|e| NetworkingError::Protocol(format!("Failed to serialize response: {}", e)))
 	}
 
@@ -1820,6 +2321,8 @@ impl super::ProtocolHandler for FileTransferProtocolHandler {
 						device_id
 					))
 					.await;
+				// It may come back upgraded, so negotiate with it again
+				self.legacy_peers.write().unwrap().remove(&device_id);
 				// TODO: Pause transfers to this device
 			}
 			super::ProtocolEvent::ConnectionFailed { device_id, reason } => {
@@ -1837,6 +2340,49 @@ impl super::ProtocolHandler for FileTransferProtocolHandler {
 	}
 }
 
+/// Encode a message for the wire
+///
+/// Fields are encoded by name, so peers on older versions skip fields they don't know and
+/// optional fields can be left out without shifting the ones after them.
+pub fn encode_message(
+	message: &FileTransferMessage,
+) -> std::result::Result<Vec<u8>, rmp_serde::encode::Error> {
+	rmp_serde::to_vec_named(message)
+}
+
+/// Write a message framed as the file transfer stream expects: type byte, length, payload
+pub async fn write_message<W>(send: &mut W, message: &FileTransferMessage) -> std::io::Result<()>
+where
+	W: AsyncWrite + Unpin + ?Sized,
+{
+	let data = encode_message(message)
+		.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+	send.write_u8(0).await?;
+	send.write_all(&(data.len() as u32).to_be_bytes()).await?;
+	send.write_all(&data).await?;
+	send.flush().await
+}
+
+/// Read one framed message from a file transfer stream
+pub async fn read_message<R>(recv: &mut R) -> std::io::Result<FileTransferMessage>
+where
+	R: AsyncRead + Unpin + ?Sized,
+{
+	let msg_type = recv.read_u8().await?;
+	if msg_type != 0 {
+		return Err(std::io::Error::new(
+			std::io::ErrorKind::InvalidData,
+			format!("Unexpected message type: {}", msg_type),
+		));
+	}
+
+	let len = recv.read_u32().await? as usize;
+	let mut data = vec![0u8; len];
+	recv.read_exact(&mut data).await?;
+	rmp_serde::from_slice(&data)
+		.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
 /// Error extensions for file transfer
 impl NetworkingError {
 	pub fn transfer_not_found(transfer_id: Uuid) -> Self {
@@ -2004,4 +2550,317 @@ mod tests {
 		// Clean up
 		std::fs::remove_dir_all(&temp_dir).ok();
 	}
+
+	// Capability negotiation and delta transfer tests
+
+	/// Deterministic bytes that don't repeat within a delta block
+	fn test_data(len: usize, seed: u64) -> Vec<u8> {
+		let mut state = seed;
+		(0..len)
+			.map(|_| {
+				state = state
+					.wrapping_mul(6364136223846793005)
+					.wrapping_add(1442695040888963407);
+				(state >> 33) as u8
+			})
+			.collect()
+	}
+
+	fn test_metadata(name: &str, size: u64) -> FileMetadata {
+		FileMetadata {
+			name: name.to_string(),
+			size,
+			modified: None,
+			is_directory: false,
+			checksum: None,
+			mime_type: None,
+		}
+	}
+
+	/// Every framed message written to a buffer
+	async fn read_all_messages(mut written: &[u8]) -> Vec<FileTransferMessage> {
+		let mut messages = Vec::new();
+		while !written.is_empty() {
+			messages.push(read_message(&mut written).await.unwrap());
+		}
+		messages
+	}
+
+	/// `TransferResponse` as peers encoded it before capabilities existed
+	#[derive(Serialize, Deserialize)]
+	enum LegacyMessage {
+		TransferResponse {
+			transfer_id: Uuid,
+			accepted: bool,
+			reason: Option<String>,
+			supported_resume: bool,
+		},
+	}
+
+	#[test]
+	fn test_messages_decode_across_versions() {
+		let transfer_id = Uuid::new_v4();
+
+		// A response from an older peer decodes without capabilities
+		let legacy = rmp_serde::to_vec(&LegacyMessage::TransferResponse {
+			transfer_id,
+			accepted: true,
+			reason: None,
+			supported_resume: true,
+		})
+		.unwrap();
+		match rmp_serde::from_slice(&legacy).unwrap() {
+			FileTransferMessage::TransferResponse { capabilities, .. } => {
+				assert!(capabilities.is_none())
+			}
+			other => panic!("Unexpected message: {:?}", other),
+		}
+
+		// An older peer skips the fields it doesn't know
+		let current = encode_message(&FileTransferMessage::TransferResponse {
+			transfer_id,
+			accepted: true,
+			reason: None,
+			supported_resume: false,
+			capabilities: Some(TransferCapabilities {
+				delta: true,
+				compression: true,
+			}),
+		})
+		.unwrap();
+		let LegacyMessage::TransferResponse {
+			transfer_id: decoded_id,
+			accepted,
+			..
+		} = rmp_serde::from_slice(&current).unwrap();
+		assert_eq!(decoded_id, transfer_id);
+		assert!(accepted);
+	}
+
+	#[tokio::test]
+	async fn test_pull_request_round_trips_without_basis() {
+		let transfer_id = Uuid::new_v4();
+		let requested_by = Uuid::new_v4();
+		let request = FileTransferMessage::PullRequest {
+			transfer_id,
+			source_path: PathBuf::from("/photos/beach.jpg"),
+			requested_by,
+			capabilities: Some(TransferCapabilities {
+				delta: false,
+				compression: true,
+			}),
+			basis: None,
+			rate_limit_mbps: Some(50),
+		};
+
+		let mut written = Vec::new();
+		write_message(&mut written, &request).await.unwrap();
+
+		// The omitted basis must not shift the rate limit into its place
+		match read_message(&mut written.as_slice()).await.unwrap() {
+			FileTransferMessage::PullRequest {
+				transfer_id: decoded_id,
+				source_path,
+				requested_by: decoded_by,
+				capabilities,
+				basis,
+				rate_limit_mbps,
+			} => {
+				assert_eq!(decoded_id, transfer_id);
+				assert_eq!(source_path, PathBuf::from("/photos/beach.jpg"));
+				assert_eq!(decoded_by, requested_by);
+				assert_eq!(
+					capabilities,
+					Some(TransferCapabilities {
+						delta: false,
+						compression: true,
+					})
+				);
+				assert!(basis.is_none());
+				assert_eq!(rate_limit_mbps, Some(50));
+			}
+			other => panic!("Unexpected message: {:?}", other),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_negotiation_without_delta_sends_no_signatures() {
+		let dir = tempfile::tempdir().unwrap();
+		let handler = FileTransferProtocolHandler::new_default(Arc::new(SilentLogger));
+		handler.set_allowed_paths(vec![dir.path().to_path_buf()]);
+		let transfer_id = Uuid::new_v4();
+		let destination = dir.path().join("new.bin");
+
+		handler
+			.handle_incoming_transfer_request(
+				Uuid::new_v4(),
+				transfer_id,
+				test_metadata("new.bin", 1024),
+				destination.display().to_string(),
+			)
+			.await
+			.unwrap();
+
+		// Nothing to delta against, so the receiver turns it down and only compresses
+		let mut written = Vec::new();
+		let mut deltas = HashMap::new();
+		handler
+			.respond_to_transfer_request(
+				&mut written,
+				transfer_id,
+				Ok(()),
+				TransferCapabilities {
+					delta: true,
+					compression: true,
+				},
+				&destination.display().to_string(),
+				&mut deltas,
+			)
+			.await
+			.unwrap();
+
+		let messages = read_all_messages(&written).await;
+		assert_eq!(messages.len(), 1);
+		match &messages[0] {
+			FileTransferMessage::TransferResponse {
+				accepted,
+				capabilities,
+				..
+			} => {
+				assert!(accepted);
+				assert_eq!(
+					*capabilities,
+					Some(TransferCapabilities {
+						delta: false,
+						compression: true,
+					})
+				);
+			}
+			other => panic!("Unexpected message: {:?}", other),
+		}
+		assert!(deltas.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_delta_transfer_rebuilds_existing_copy() {
+		let dir = tempfile::tempdir().unwrap();
+		let handler = FileTransferProtocolHandler::new_default(Arc::new(SilentLogger));
+		handler.set_allowed_paths(vec![dir.path().to_path_buf()]);
+		let transfer_id = Uuid::new_v4();
+
+		let destination = dir.path().join("report.bin");
+		let basis = test_data(600_000, 1);
+		tokio::fs::write(&destination, &basis).await.unwrap();
+
+		// The sender's copy has a changed range and a few appended bytes
+		let source = dir.path().join("source.bin");
+		let mut new = basis.clone();
+		new[200_000..200_500].copy_from_slice(&test_data(500, 2));
+		new.extend(test_data(1_000, 3));
+		tokio::fs::write(&source, &new).await.unwrap();
+
+		handler
+			.handle_incoming_transfer_request(
+				Uuid::new_v4(),
+				transfer_id,
+				test_metadata("report.bin", new.len() as u64),
+				destination.display().to_string(),
+			)
+			.await
+			.unwrap();
+
+		let mut written = Vec::new();
+		let mut deltas = HashMap::new();
+		handler
+			.respond_to_transfer_request(
+				&mut written,
+				transfer_id,
+				Ok(()),
+				TransferCapabilities {
+					delta: true,
+					compression: false,
+				},
+				&destination.display().to_string(),
+				&mut deltas,
+			)
+			.await
+			.unwrap();
+
+		let mut messages = read_all_messages(&written).await.into_iter();
+		match messages.next() {
+			Some(FileTransferMessage::TransferResponse { capabilities, .. }) => {
+				assert!(capabilities.is_some_and(|enabled| enabled.delta))
+			}
+			other => panic!("Unexpected message: {:?}", other),
+		}
+		let signature = match messages.next() {
+			Some(FileTransferMessage::BlockSignatures { signature, .. }) => signature,
+			other => panic!("Expected block signatures, got {:?}", other),
+		};
+		assert!(deltas.contains_key(&transfer_id));
+
+		// The sender encodes against the signature, and the receiver applies the chunks
+		let mut delta_stream = Vec::new();
+		let (covered, literal) = crate::service::network::protocol::file_delta::send_delta(
+			&mut delta_stream,
+			transfer_id,
+			&source,
+			signature,
+			None,
+			|_| {},
+		)
+		.await
+		.unwrap();
+		assert_eq!(covered, new.len() as u64);
+		assert!(
+			literal < new.len() as u64 / 10,
+			"Unchanged blocks are copied"
+		);
+
+		for message in read_all_messages(&delta_stream).await {
+			let FileTransferMessage::DeltaChunk {
+				transfer_id, ops, ..
+			} = message
+			else {
+				panic!("Expected delta chunks");
+			};
+			handler
+				.handle_incoming_delta_chunk(transfer_id, ops, &mut deltas)
+				.await
+				.unwrap();
+		}
+
+		// The rebuilt file replaces the copy once it matches the sender's hash
+		let writer = deltas.remove(&transfer_id).unwrap();
+		let checksum = handler.calculate_file_checksum(&source).await.unwrap();
+		handler
+			.finish_delta_transfer(transfer_id, writer, &checksum)
+			.await
+			.unwrap();
+
+		assert_eq!(tokio::fs::read(&destination).await.unwrap(), new);
+		assert_eq!(
+			handler.get_session(&transfer_id).unwrap().state,
+			TransferState::Completed
+		);
+	}
+
+	#[tokio::test]
+	async fn test_legacy_peers_are_remembered_until_they_disconnect() {
+		let handler = FileTransferProtocolHandler::new_default(Arc::new(SilentLogger));
+		let device_id = Uuid::new_v4();
+		assert!(handler.negotiates_with(device_id));
+
+		handler.mark_legacy_peer(device_id);
+		assert!(!handler.negotiates_with(device_id));
+		assert!(handler.negotiates_with(Uuid::new_v4()));
+
+		handler
+			.handle_event(
+				crate::service::network::protocol::ProtocolEvent::DeviceDisconnected { device_id },
+			)
+			.await
+			.unwrap();
+		assert!(handler.negotiates_with(device_id));
+	}
 }
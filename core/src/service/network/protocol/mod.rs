@@ -1,6 +1,9 @@
 //! Protocol handling system for different message types
 
+pub mod bandwidth;
+pub mod compression;
 pub mod file_delete;
+pub mod file_delta;
 pub mod file_transfer;
 pub mod job_activity;
 pub mod library_messages;
@@ -17,8 +20,8 @@ use uuid::Uuid;
 
 pub use file_delete::FileDeleteProtocolHandler;
 pub use file_transfer::{
-	FileMetadata, FileTransferMessage, FileTransferProtocolHandler, TransferDirection,
-	TransferMode, TransferSession,
+	FileMetadata, FileTransferMessage, FileTransferProtocolHandler, TransferCapabilities,
+	TransferDirection, TransferMode, TransferSession,
 };
 pub use job_activity::{JobActivityMessage, JobActivityProtocolHandler, RemoteJobEvent};
 pub use library_messages::{LibraryDiscoveryInfo, LibraryMessage};
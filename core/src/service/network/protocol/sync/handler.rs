@@ -260,6 +260,22 @@ impl SyncProtocolHandler {
 					None
 				};
 
+				// Filter by the requester's sync policy only after paging is decided, so a
+				// page emptied by the filter still advances the checkpoint
+				let policies = peer_sync.policies();
+				let records = match policies.for_device(from_device).await {
+					Ok(policy) => policies
+						.filter_state_records(&policy, &model_type, records)
+						.await
+						.map_err(|e| {
+							NetworkingError::Protocol(format!("Failed to apply sync policy: {}", e))
+						})?,
+					Err(e) => {
+						warn!(error = %e, "Failed to load sync policy, sending unfiltered state");
+						records
+					}
+				};
+
 				// Record metrics for backfill response
 				if let Some(metrics) = &self.metrics {
 					// Estimate response size (records + tombstones)
@@ -301,7 +317,7 @@ impl SyncProtocolHandler {
 			}
 
 			SyncMessage::SharedChangeRequest {
-				library_id,
+				library_id: _,
 				since_hlc,
 				limit,
 			} => {
@@ -311,29 +327,21 @@ impl SyncProtocolHandler {
 					"Processing SharedChangeRequest"
 				);
 
-				// Query peer log
-				let (entries, has_more) = peer_sync
-					.get_shared_changes(since_hlc, limit)
+				// Query peer log, filtered by the requester's sync policy
+				let response = peer_sync
+					.shared_change_response(from_device, since_hlc, limit)
 					.await
 					.map_err(|e| {
 						NetworkingError::Protocol(format!("Failed to query shared changes: {}", e))
 					})?;
-
-				// If initial backfill (since_hlc = None), include full current state
-				let current_state = if since_hlc.is_none() {
-					debug!("Initial backfill requested - querying full shared resource state");
-					match peer_sync.get_full_shared_state().await {
-						Ok(state) => {
-							info!("Including full state snapshot for initial backfill");
-							Some(state)
-						}
-						Err(e) => {
-							warn!("Failed to query full shared state: {}", e);
-							None
-						}
-					}
-				} else {
-					None
+				let SyncMessage::SharedChangeResponse {
+					entries,
+					current_state,
+					has_more,
+					..
+				} = &response
+				else {
+					return Ok(Some(response));
 				};
 
 				debug!(
@@ -359,12 +367,7 @@ impl SyncProtocolHandler {
 						.await;
 				}
 
-				Ok(Some(SyncMessage::SharedChangeResponse {
-					library_id,
-					entries,
-					current_state,
-					has_more,
-				}))
+				Ok(Some(response))
 			}
 
 			SyncMessage::SharedChangeResponse { .. } => {
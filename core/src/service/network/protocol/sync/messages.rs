@@ -80,6 +80,10 @@ pub enum SyncMessage {
 		entries: Vec<SharedChangeEntry>,
 		current_state: Option<serde_json::Value>, // Fallback if logs pruned
 		has_more: bool,
+		/// Last change scanned for this page; entries filtered out by the requester's
+		/// sync policy may leave it past the last entry returned
+		#[serde(default)]
+		cursor: Option<HLC>,
 	},
 
 	/// Acknowledge shared changes (for pruning)
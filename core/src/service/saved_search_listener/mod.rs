@@ -0,0 +1,325 @@
+//! Saved search listener service
+//!
+//! Re-evaluates saved searches (smart collections) as entries change and emits
+//! `SavedSearchMembershipChanged` when a search gains or loses members
+//!
+//! Only the library an event belongs to is re-evaluated. Resource events don't carry a
+//! library, so their resources are looked up in each library that has saved searches,
+//! and resource types no search depends on are ignored.
+
+use crate::{
+	context::CoreContext,
+	infra::{db::entities::location, event::Event},
+	ops::search::saved::MembershipTracker,
+	service::Service,
+};
+use anyhow::Result;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, RwLock,
+	},
+	time::Duration,
+};
+use tokio::{
+	sync::broadcast::error::RecvError,
+	task::JoinHandle,
+	time::{sleep_until, Instant},
+};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Delay between the first change and re-evaluation, so bursts (e.g. indexing) are
+/// evaluated once rather than per event
+const REFRESH_DELAY: Duration = Duration::from_secs(2);
+
+/// Which libraries an event may have changed the search results of
+enum Affected {
+	Nothing,
+	Library(Uuid),
+	/// Whichever libraries hold these resources or have searches filtering on them
+	Resources {
+		resource_type: String,
+		ids: Vec<Uuid>,
+	},
+	/// Whichever library holds this location
+	Location(Uuid),
+	AllLibraries,
+}
+
+/// Service that keeps smart collection membership current for all open libraries
+pub struct SavedSearchListenerService {
+	context: Arc<CoreContext>,
+	running: AtomicBool,
+	handle: RwLock<Option<JoinHandle<()>>>,
+}
+
+impl SavedSearchListenerService {
+	pub fn new(context: Arc<CoreContext>) -> Self {
+		Self {
+			context,
+			running: AtomicBool::new(false),
+			handle: RwLock::new(None),
+		}
+	}
+
+	async fn listen_loop(context: Arc<CoreContext>) {
+		info!("Saved search listener started");
+
+		let mut subscriber = context.events.subscribe();
+		let mut trackers: HashMap<Uuid, MembershipTracker> = HashMap::new();
+		let mut pending: HashSet<Uuid> = HashSet::new();
+
+		// Record current members so the first change is reported against them
+		for library in context.libraries().await.get_open_libraries().await {
+			pending.insert(library.id());
+		}
+		let mut refresh_at = Some(Instant::now());
+
+		loop {
+			let received = match refresh_at {
+				Some(deadline) => tokio::select! {
+					received = subscriber.recv() => Some(received),
+					_ = sleep_until(deadline) => None,
+				},
+				None => Some(subscriber.recv().await),
+			};
+
+			let affected = match received {
+				None => {
+					for library_id in pending.drain() {
+						Self::refresh_library(&context, &mut trackers, library_id).await;
+					}
+					refresh_at = None;
+					continue;
+				}
+				Some(Ok(Event::LibraryClosed { id, .. })) => {
+					trackers.remove(&id);
+					pending.remove(&id);
+					continue;
+				}
+				Some(Ok(event)) => affected_by(&event),
+				Some(Err(RecvError::Lagged(skipped))) => {
+					warn!(
+						skipped,
+						"Saved search listener lagged, re-evaluating all libraries"
+					);
+					Affected::AllLibraries
+				}
+				Some(Err(RecvError::Closed)) => {
+					info!("Event bus closed, saved search listener shutting down");
+					break;
+				}
+			};
+
+			match affected {
+				Affected::Nothing => continue,
+				Affected::Library(library_id) => {
+					pending.insert(library_id);
+				}
+				Affected::Resources { resource_type, ids } => {
+					Self::collect_holders(&context, &trackers, &resource_type, &ids, &mut pending)
+						.await;
+				}
+				Affected::Location(location_id) => {
+					Self::collect_location_holder(&context, location_id, &mut pending).await;
+				}
+				Affected::AllLibraries => {
+					for library in context.libraries().await.get_open_libraries().await {
+						pending.insert(library.id());
+					}
+				}
+			}
+			if !pending.is_empty() {
+				refresh_at.get_or_insert_with(|| Instant::now() + REFRESH_DELAY);
+			}
+		}
+	}
+
+	/// Queue the libraries whose saved searches a resource change can affect
+	async fn collect_holders(
+		context: &Arc<CoreContext>,
+		trackers: &HashMap<Uuid, MembershipTracker>,
+		resource_type: &str,
+		ids: &[Uuid],
+		pending: &mut HashSet<Uuid>,
+	) {
+		for library in context.libraries().await.get_open_libraries().await {
+			let library_id = library.id();
+			// Libraries without a tracker are still waiting for their first evaluation
+			let Some(tracker) = trackers.get(&library_id) else {
+				continue;
+			};
+			if pending.contains(&library_id) {
+				continue;
+			}
+
+			match tracker
+				.is_affected_by(library.db().conn(), resource_type, ids)
+				.await
+			{
+				Ok(true) => {
+					pending.insert(library_id);
+				}
+				Ok(false) => {}
+				Err(e) => {
+					warn!(
+						library_id = %library_id,
+						"Failed to match {} change to library: {}",
+						resource_type,
+						e
+					);
+					pending.insert(library_id);
+				}
+			}
+		}
+	}
+
+	/// Queue the library a location belongs to
+	async fn collect_location_holder(
+		context: &Arc<CoreContext>,
+		location_id: Uuid,
+		pending: &mut HashSet<Uuid>,
+	) {
+		for library in context.libraries().await.get_open_libraries().await {
+			let holds_location = location::Entity::find()
+				.filter(location::Column::Uuid.eq(location_id))
+				.count(library.db().conn())
+				.await
+				.map(|count| count > 0)
+				.unwrap_or(true);
+			if holds_location {
+				pending.insert(library.id());
+			}
+		}
+	}
+
+	/// Re-evaluate a library's saved searches and emit an event for each that changed
+	async fn refresh_library(
+		context: &Arc<CoreContext>,
+		trackers: &mut HashMap<Uuid, MembershipTracker>,
+		library_id: Uuid,
+	) {
+		let Some(library) = context.get_library(library_id).await else {
+			trackers.remove(&library_id);
+			return;
+		};
+
+		let tracker = trackers.entry(library_id).or_default();
+		match tracker
+			.refresh(library.db().conn(), context.file_type_registry())
+			.await
+		{
+			Ok(changes) => {
+				for change in changes {
+					debug!(
+						library_id = %library_id,
+						saved_search = %change.saved_search_id,
+						added = change.added.len(),
+						removed = change.removed.len(),
+						"Saved search membership changed"
+					);
+					context.events.emit(Event::SavedSearchMembershipChanged {
+						library_id,
+						saved_search_id: change.saved_search_id,
+						added: change.added,
+						removed: change.removed,
+					});
+				}
+			}
+			Err(e) => {
+				warn!(library_id = %library_id, "Failed to re-evaluate saved searches: {}", e);
+			}
+		}
+	}
+}
+
+/// Resource types whose changes can move entries in or out of a saved search
+fn is_search_resource(resource_type: &str) -> bool {
+	matches!(resource_type, "file" | "tag" | "location")
+}
+
+/// IDs of the resources a `ResourceChanged`/`ResourceChangedBatch` payload carries
+fn resource_ids<'a>(resources: impl IntoIterator<Item = &'a serde_json::Value>) -> Vec<Uuid> {
+	resources
+		.into_iter()
+		.filter_map(|resource| resource.get("id")?.as_str()?.parse().ok())
+		.collect()
+}
+
+fn affected_by(event: &Event) -> Affected {
+	match event {
+		Event::LibraryOpened { id, .. } => Affected::Library(*id),
+		Event::EntryCreated { library_id, .. }
+		| Event::EntryModified { library_id, .. }
+		| Event::EntryDeleted { library_id, .. }
+		| Event::EntryMoved { library_id, .. }
+		| Event::FilesIndexed { library_id, .. }
+		| Event::FilesModified { library_id, .. }
+		| Event::FileOperationCompleted { library_id, .. } => Affected::Library(*library_id),
+		// Resource events don't say which library they belong to
+		Event::ResourceChanged {
+			resource_type,
+			resource,
+			..
+		} if is_search_resource(resource_type) => Affected::Resources {
+			resource_type: resource_type.clone(),
+			ids: resource_ids([resource]),
+		},
+		Event::ResourceChangedBatch {
+			resource_type,
+			resources,
+			..
+		} if is_search_resource(resource_type) => Affected::Resources {
+			resource_type: resource_type.clone(),
+			ids: resource_ids(resources.as_array().into_iter().flatten()),
+		},
+		Event::ResourceDeleted {
+			resource_type,
+			resource_id,
+		} if is_search_resource(resource_type) => Affected::Resources {
+			resource_type: resource_type.clone(),
+			ids: vec![*resource_id],
+		},
+		Event::IndexingCompleted { location_id, .. } => Affected::Location(*location_id),
+		_ => Affected::Nothing,
+	}
+}
+
+#[async_trait::async_trait]
+impl Service for SavedSearchListenerService {
+	async fn start(&self) -> Result<()> {
+		if self.running.swap(true, Ordering::SeqCst) {
+			return Ok(());
+		}
+
+		let handle = tokio::spawn(Self::listen_loop(self.context.clone()));
+		*self.handle.write().unwrap() = Some(handle);
+
+		info!("Saved search listener service started");
+		Ok(())
+	}
+
+	async fn stop(&self) -> Result<()> {
+		if !self.running.swap(false, Ordering::SeqCst) {
+			return Ok(());
+		}
+
+		if let Some(handle) = self.handle.write().unwrap().take() {
+			handle.abort();
+		}
+
+		info!("Saved search listener service stopped");
+		Ok(())
+	}
+
+	fn is_running(&self) -> bool {
+		self.running.load(Ordering::SeqCst)
+	}
+
+	fn name(&self) -> &'static str {
+		"saved_search_listener"
+	}
+}
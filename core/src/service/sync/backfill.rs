@@ -358,6 +358,21 @@ impl BackfillManager {
 		Ok(())
 	}
 
+	/// This device's sync policy, the full tier if it can't be loaded
+	///
+	/// Peers filter what they send by it; checking it here as well keeps backfill from
+	/// requesting, or applying from peers that don't filter, what this device doesn't want.
+	async fn own_sync_policy(&self) -> super::policy::SyncPolicy {
+		self.peer_sync
+			.policies()
+			.for_device(self.device_id)
+			.await
+			.unwrap_or_else(|e| {
+				warn!(error = %e, "Failed to load own sync policy, backfilling everything");
+				super::policy::SyncPolicy::full(self.device_id)
+			})
+	}
+
 	/// Backfill device-owned state from all peers in dependency order
 	///
 	/// Uses per-resource watermarks for each model type to enable independent sync progress.
@@ -379,10 +394,12 @@ impl BackfillManager {
 			"Computed dependency-ordered sync sequence"
 		);
 
-		// Filter to only device-owned models
+		// Filter to only device-owned models our sync policy subscribes to
+		let policy = self.own_sync_policy().await;
 		let mut model_types = Vec::new();
 		for model in sync_order {
-			if crate::infra::sync::is_device_owned(&model).await {
+			if crate::infra::sync::is_device_owned(&model).await && policy.replicates_model(&model)
+			{
 				model_types.push(model);
 			}
 		}
@@ -806,6 +823,8 @@ impl BackfillManager {
 			info!("Backfilling all shared resources");
 		}
 
+		let policy = self.own_sync_policy().await;
+
 		// Request shared changes from peer in batches (can be 100k+ records)
 		let mut last_hlc = since_hlc;
 		let mut total_applied = 0;
@@ -821,13 +840,15 @@ impl BackfillManager {
 				entries,
 				current_state,
 				has_more,
+				cursor,
 				..
 			} = response
 			{
 				let batch_size = entries.len();
 
 				// Track max HLC for ACK (critical for pruning)
-				let max_hlc_in_batch = entries.last().map(|e| e.hlc);
+				// The peer's cursor also covers changes our sync policy filtered out of the page
+				let max_hlc_in_batch = cursor.or_else(|| entries.last().map(|e| e.hlc));
 
 				// IMPORTANT: Apply current_state snapshot FIRST (before HLC entries)
 				// The snapshot contains base data that peer log entries may depend on
@@ -850,10 +871,11 @@ impl BackfillManager {
 
 						// Apply snapshot records in dependency order
 						for model_type in sync_order {
-							// Skip if model not in snapshot
+							// Skip if model not in snapshot, or (from peers that don't filter)
+							// excluded by our sync policy
 							let records_value = match state_map.get(&model_type) {
-								Some(val) => val,
-								None => continue,
+								Some(val) if policy.replicates_model(&model_type) => val,
+								_ => continue,
 							};
 
 							if let Some(records_array) = records_value.as_array() {
@@ -890,6 +912,7 @@ impl BackfillManager {
 														record_uuid,
 														change_type: crate::infra::sync::ChangeType::Insert,
 														data: data.clone(),
+														field_clocks: None,
 													};
 
 													let db = self.peer_sync.db().clone();
@@ -1046,6 +1069,10 @@ impl BackfillManager {
 
 				// Now apply HLC-ordered peer log entries (after snapshot dependencies are available)
 				for entry in &entries {
+					if !policy.replicates_model(&entry.model_type) {
+						continue;
+					}
+
 					// Attempt to apply the shared change
 					// If it fails due to missing FK dependency, buffer it for retry
 					match self.log_handler.handle_shared_change(entry.clone()).await {
@@ -1261,12 +1288,13 @@ impl BackfillManager {
 				}
 
 				// Update cursor to last HLC for next batch
-				if let Some(last_entry) = entries.last() {
-					last_hlc = Some(last_entry.hlc);
+				if max_hlc_in_batch.is_some() {
+					last_hlc = max_hlc_in_batch;
 				}
 
-				// Continue if there are more entries
-				if !has_more || batch_size == 0 {
+				// Continue if there are more entries (a page may be empty after filtering,
+				// so stop only when the cursor didn't move)
+				if !has_more || max_hlc_in_batch.is_none() {
 					break;
 				}
 			} else {
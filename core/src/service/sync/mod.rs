@@ -9,6 +9,7 @@ pub mod backfill;
 pub mod dependency;
 pub mod metrics;
 pub mod peer;
+pub mod policy;
 pub mod protocol_handler;
 pub mod retry_queue;
 pub mod state;
@@ -33,6 +34,7 @@ use crate::service::network::protocol::SyncProtocolHandler;
 
 pub use activity::SyncActivityAggregator;
 pub use peer::PeerSync;
+pub use policy::{SyncPolicies, SyncPolicy, SyncTier};
 pub use state::{
 	select_backfill_peer, BackfillCheckpoint, BufferQueue, BufferedUpdate, DeviceSyncState,
 	PeerInfo, StateChangeMessage,
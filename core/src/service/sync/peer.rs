@@ -31,6 +31,8 @@
 //! - `broadcast_shared_change()`: Sends shared resource changes with HLC for ordering
 //! - Broadcasts in parallel to all connected sync partners (30s timeout per peer)
 //! - Failed sends are queued for retry with exponential backoff
+//! - Each partner receives the change filtered by its sync policy (see `policy`), with
+//!   references to rows it doesn't receive detached
 //!
 //! ### 4. Change Application
 //! - `on_state_change_received()`: Applies incoming device state changes
@@ -72,17 +74,21 @@
 //!
 //! ## Conflict Resolution
 //!
-//! For shared resources, conflicts are resolved using HLC timestamps:
-//! - Incoming change with older/equal HLC → ignored
-//! - Incoming change with newer HLC → applied (last-write-wins)
+//! For shared resources, conflicts are resolved per field using HLC timestamps:
+//! - Each field keeps the HLC of its last write (see `infra::sync::field_clock`)
+//! - Incoming fields with older/equal HLC → local value kept
+//! - Incoming fields with newer HLC → applied (last-write-wins per field)
+//! - Concurrent edits to different fields of a record merge; concurrent edits to the
+//!   same field are resolved by HLC and recorded as `FieldConflict` sync events
 //! - HLC ensures causal consistency across all peers
 
 use crate::{
 	infra::{
 		event::{Event, EventBus},
 		sync::{
-			HLCGenerator, NetworkTransport, PeerLog, PeerLogError, ResourceWatermarkStore,
-			SharedChangeEntry, SystemTimeSource, HLC,
+			field_clock::merge_fields, ApplyError, ChangeType, FieldConflict, HLCGenerator,
+			NetworkTransport, PeerLog, PeerLogError, ResourceWatermarkStore, SharedChangeEntry,
+			SyncEventLog, SyncEventType, SystemTimeSource, HLC,
 		},
 	},
 	library::Library,
@@ -100,6 +106,7 @@ use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use super::{
+	policy::SyncPolicies,
 	retry_queue::RetryQueue,
 	state::{BufferQueue, DeviceSyncState, StateChangeMessage},
 };
@@ -154,6 +161,9 @@ pub struct PeerSync {
 	/// Retry queue for failed messages
 	retry_queue: Arc<RetryQueue>,
 
+	/// Per-device selective sync policies, evaluated before sending
+	policies: Arc<SyncPolicies>,
+
 	/// Dependency tracker for event-driven retry (replaces O(n²) buffer retry)
 	dependency_tracker: Arc<super::dependency::DependencyTracker>,
 
@@ -192,10 +202,13 @@ impl PeerSync {
 		// Create watermark store for per-resource tracking
 		let watermark_store = ResourceWatermarkStore::new(device_id);
 
+		let db = Arc::new(library.db().conn().clone());
+
 		Ok(Self {
 			library_id,
 			device_id,
-			db: Arc::new(library.db().conn().clone()),
+			policies: Arc::new(SyncPolicies::new(db.clone())),
+			db,
 			network,
 			state: Arc::new(RwLock::new(DeviceSyncState::Uninitialized)),
 			buffer: Arc::new(BufferQueue::new()),
@@ -239,6 +252,11 @@ impl PeerSync {
 		&self.db
 	}
 
+	/// Get selective sync policies
+	pub fn policies(&self) -> &Arc<SyncPolicies> {
+		&self.policies
+	}
+
 	/// Get network transport
 	pub fn network(&self) -> &Arc<dyn NetworkTransport> {
 		&self.network
@@ -327,6 +345,42 @@ impl PeerSync {
 			.map_err(|e| anyhow::anyhow!("Failed to get peer watermarks: {}", e))
 	}
 
+	/// Re-sync with connected peers after this device's sync policy changed
+	///
+	/// Peers only sent what the old policy allowed, so our watermarks don't cover
+	/// what a wider policy now includes. Clearing them makes the next watermark
+	/// exchange fetch everything again, filtered by the new policy.
+	pub async fn resync_after_policy_change(&self) -> Result<()> {
+		self.policies.invalidate().await;
+
+		let conn = self.peer_log.conn();
+		self.watermark_store
+			.clear(conn)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to clear resource watermarks: {}", e))?;
+		crate::infra::sync::PeerWatermarkStore::new(self.device_id)
+			.clear(conn)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to clear peer watermarks: {}", e))?;
+
+		info!(
+			library_id = %self.library_id,
+			"Sync policy changed, cleared watermarks for re-sync"
+		);
+
+		let partners = self
+			.network
+			.get_connected_sync_partners(self.library_id, &self.db)
+			.await?;
+		for peer in partners {
+			if let Err(e) = self.exchange_watermarks_and_catchup(peer).await {
+				warn!(peer = %peer, error = %e, "Failed to start re-sync after policy change");
+			}
+		}
+
+		Ok(())
+	}
+
 	/// Query watermarks from sync.db (per-resource aggregation)
 	///
 	/// For state watermark: Returns the maximum (most recent) timestamp across all resources
@@ -789,6 +843,7 @@ impl PeerSync {
 		let db = self.db.clone();
 		let event_bus_for_emit = self.event_bus.clone();
 		let retry_queue = self.retry_queue.clone();
+		let policies = self.policies.clone();
 		let mut subscriber = self.sync_events.subscribe();
 		let is_running = self.is_running.clone();
 		let config = self.config.clone();
@@ -853,6 +908,7 @@ impl PeerSync {
 													&state,
 													&buffer,
 													&retry_queue,
+													&policies,
 													&db,
 													&config,
 													&last_realtime_activity,
@@ -886,6 +942,7 @@ impl PeerSync {
 										&state,
 										&buffer,
 										&retry_queue,
+										&policies,
 										&db,
 										&config,
 										&metrics,
@@ -941,6 +998,7 @@ impl PeerSync {
 									&state,
 									&buffer,
 									&retry_queue,
+									&policies,
 									&db,
 									&config,
 									&last_realtime_activity,
@@ -964,6 +1022,7 @@ impl PeerSync {
 					&state,
 					&buffer,
 					&retry_queue,
+					&policies,
 					&db,
 					&config,
 					&last_realtime_activity,
@@ -1198,6 +1257,7 @@ impl PeerSync {
 		state: &Arc<RwLock<DeviceSyncState>>,
 		buffer: &Arc<BufferQueue>,
 		retry_queue: &Arc<RetryQueue>,
+		policies: &Arc<SyncPolicies>,
 		db: &Arc<sea_orm::DatabaseConnection>,
 		config: &Arc<crate::infra::sync::SyncConfig>,
 		last_realtime_activity: &Arc<RwLock<Option<chrono::DateTime<chrono::Utc>>>>,
@@ -1320,10 +1380,12 @@ impl PeerSync {
 				};
 
 				// Queue for all devices except self
-				for target_device_id in library_devices {
-					if target_device_id != device_id {
-						retry_queue.enqueue(target_device_id, message.clone()).await;
-					}
+				let targets: Vec<Uuid> = library_devices
+					.into_iter()
+					.filter(|id| *id != device_id)
+					.collect();
+				for (target_device_id, message) in policies.route(&targets, &message).await {
+					retry_queue.enqueue(target_device_id, message).await;
 				}
 
 				continue;
@@ -1333,11 +1395,14 @@ impl PeerSync {
 			use futures::future::join_all;
 
 			let timeout_secs = config.network.message_timeout_secs;
-			let send_futures: Vec<_> = connected_partners
+			// Each partner gets the change filtered by its sync policy
+			let routed = policies.route(&connected_partners, &message).await;
+			let send_futures: Vec<_> = routed
 				.iter()
-				.map(|&partner| {
+				.map(|(partner, msg)| {
+					let partner = *partner;
 					let network = network.clone();
-					let msg = message.clone();
+					let msg = msg.clone();
 					async move {
 						match tokio::time::timeout(
 							std::time::Duration::from_secs(timeout_secs),
@@ -1362,7 +1427,7 @@ impl PeerSync {
 			let mut success_count = 0;
 			let mut error_count = 0;
 
-			for (partner_uuid, result) in results {
+			for ((partner_uuid, result), (_, routed_message)) in results.into_iter().zip(&routed) {
 				match result {
 					Ok(()) => {
 						success_count += 1;
@@ -1375,7 +1440,9 @@ impl PeerSync {
 							error = %e,
 							"Failed to send StateBatch to partner, enqueuing for retry"
 						);
-						retry_queue.enqueue(partner_uuid, message.clone()).await;
+						retry_queue
+							.enqueue(partner_uuid, routed_message.clone())
+							.await;
 					}
 				}
 			}
@@ -1416,6 +1483,7 @@ impl PeerSync {
 		state: &Arc<RwLock<DeviceSyncState>>,
 		buffer: &Arc<BufferQueue>,
 		retry_queue: &Arc<RetryQueue>,
+		policies: &Arc<SyncPolicies>,
 		db: &Arc<sea_orm::DatabaseConnection>,
 		config: &Arc<crate::infra::sync::SyncConfig>,
 		last_realtime_activity: &Arc<RwLock<Option<chrono::DateTime<chrono::Utc>>>>,
@@ -1506,10 +1574,12 @@ impl PeerSync {
 			let library_devices = Self::get_library_devices_static(db).await?;
 
 			// Queue for all devices except self
-			for device_id in library_devices {
-				if device_id != change.device_id {
-					retry_queue.enqueue(device_id, message.clone()).await;
-				}
+			let targets: Vec<Uuid> = library_devices
+				.into_iter()
+				.filter(|id| *id != change.device_id)
+				.collect();
+			for (device_id, message) in policies.route(&targets, &message).await {
+				retry_queue.enqueue(device_id, message).await;
 			}
 
 			return Ok(());
@@ -1529,11 +1599,14 @@ impl PeerSync {
 		use futures::future::join_all;
 
 		let timeout_secs = config.network.message_timeout_secs;
-		let send_futures: Vec<_> = connected_partners
+		// Each partner gets the change filtered by its sync policy
+		let routed = policies.route(&connected_partners, &message).await;
+		let send_futures: Vec<_> = routed
 			.iter()
-			.map(|&partner| {
+			.map(|(partner, msg)| {
+				let partner = *partner;
 				let network = network.clone();
-				let msg = message.clone();
+				let msg = msg.clone();
 				async move {
 					match tokio::time::timeout(
 						std::time::Duration::from_secs(timeout_secs),
@@ -1558,7 +1631,7 @@ impl PeerSync {
 		let mut success_count = 0;
 		let mut error_count = 0;
 
-		for (partner_uuid, result) in results {
+		for ((partner_uuid, result), (_, routed_message)) in results.into_iter().zip(&routed) {
 			match result {
 				Ok(()) => {
 					success_count += 1;
@@ -1572,7 +1645,9 @@ impl PeerSync {
 						"Failed to send state change to partner, enqueuing for retry"
 					);
 					// Enqueue for retry
-					retry_queue.enqueue(partner_uuid, message.clone()).await;
+					retry_queue
+						.enqueue(partner_uuid, routed_message.clone())
+						.await;
 				}
 			}
 		}
@@ -1615,6 +1690,7 @@ impl PeerSync {
 		state: &Arc<RwLock<DeviceSyncState>>,
 		buffer: &Arc<BufferQueue>,
 		retry_queue: &Arc<RetryQueue>,
+		policies: &Arc<SyncPolicies>,
 		db: &Arc<sea_orm::DatabaseConnection>,
 		config: &Arc<crate::infra::sync::SyncConfig>,
 		metrics: &Arc<super::metrics::SyncMetricsCollector>,
@@ -1663,10 +1739,12 @@ impl PeerSync {
 			let library_devices = Self::get_library_devices_static(db).await?;
 
 			// Queue for all devices except self
-			for device_id in library_devices {
-				if device_id != entry.hlc.device_id {
-					retry_queue.enqueue(device_id, message.clone()).await;
-				}
+			let targets: Vec<Uuid> = library_devices
+				.into_iter()
+				.filter(|id| *id != entry.hlc.device_id)
+				.collect();
+			for (device_id, message) in policies.route(&targets, &message).await {
+				retry_queue.enqueue(device_id, message).await;
 			}
 
 			return Ok(());
@@ -1686,11 +1764,14 @@ impl PeerSync {
 		use futures::future::join_all;
 
 		let timeout_secs = config.network.message_timeout_secs;
-		let send_futures: Vec<_> = connected_partners
+		// Each partner gets the change filtered by its sync policy
+		let routed = policies.route(&connected_partners, &message).await;
+		let send_futures: Vec<_> = routed
 			.iter()
-			.map(|&partner| {
+			.map(|(partner, msg)| {
+				let partner = *partner;
 				let network = network.clone();
-				let msg = message.clone();
+				let msg = msg.clone();
 				async move {
 					match tokio::time::timeout(
 						std::time::Duration::from_secs(timeout_secs),
@@ -1715,7 +1796,7 @@ impl PeerSync {
 		let mut success_count = 0;
 		let mut error_count = 0;
 
-		for (partner_uuid, result) in results {
+		for ((partner_uuid, result), (_, routed_message)) in results.into_iter().zip(&routed) {
 			match result {
 				Ok(()) => {
 					success_count += 1;
@@ -1729,7 +1810,9 @@ impl PeerSync {
 						"Failed to send shared change to partner, enqueuing for retry"
 					);
 					// Enqueue for retry
-					retry_queue.enqueue(partner_uuid, message.clone()).await;
+					retry_queue
+						.enqueue(partner_uuid, routed_message.clone())
+						.await;
 				}
 			}
 		}
@@ -1818,10 +1901,12 @@ impl PeerSync {
 			let library_devices = self.get_library_devices().await?;
 
 			// Queue for all devices except self
-			for device_id in library_devices {
-				if device_id != self.device_id {
-					self.retry_queue.enqueue(device_id, message.clone()).await;
-				}
+			let targets: Vec<Uuid> = library_devices
+				.into_iter()
+				.filter(|id| *id != self.device_id)
+				.collect();
+			for (device_id, message) in self.policies.route(&targets, &message).await {
+				self.retry_queue.enqueue(device_id, message).await;
 			}
 
 			return Ok(());
@@ -1840,11 +1925,14 @@ impl PeerSync {
 		// Broadcast to all partners in parallel using futures::join_all
 		use futures::future::join_all;
 
-		let send_futures: Vec<_> = connected_partners
+		// Each partner gets the change filtered by its sync policy
+		let routed = self.policies.route(&connected_partners, &message).await;
+		let send_futures: Vec<_> = routed
 			.iter()
-			.map(|&partner| {
+			.map(|(partner, msg)| {
+				let partner = *partner;
 				let network = self.network.clone();
-				let msg = message.clone();
+				let msg = msg.clone();
 				async move {
 					// Add timeout to prevent hanging indefinitely
 					match tokio::time::timeout(
@@ -1867,7 +1955,7 @@ impl PeerSync {
 		let mut success_count = 0;
 		let mut error_count = 0;
 
-		for (partner_uuid, result) in results {
+		for ((partner_uuid, result), (_, routed_message)) in results.into_iter().zip(&routed) {
 			match result {
 				Ok(()) => {
 					success_count += 1;
@@ -1882,7 +1970,7 @@ impl PeerSync {
 					);
 					// Enqueue for retry
 					self.retry_queue
-						.enqueue(partner_uuid, message.clone())
+						.enqueue(partner_uuid, routed_message.clone())
 						.await;
 				}
 			}
@@ -1922,14 +2010,21 @@ impl PeerSync {
 		let hlc = self.hlc_generator.lock().await.next();
 
 		// Create entry
-		let entry = SharedChangeEntry {
+		let mut entry = SharedChangeEntry {
 			hlc,
 			model_type: model_type.clone(),
 			record_uuid,
 			change_type,
 			data,
+			field_clocks: None,
 		};
 
+		// Stamp changed fields so concurrent edits to other fields merge
+		self.peer_log
+			.stamp_field_clocks(&mut entry)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to stamp field clocks: {}", e))?;
+
 		// Write to our peer log
 		self.peer_log
 			.append(entry.clone())
@@ -1969,10 +2064,12 @@ impl PeerSync {
 			let library_devices = self.get_library_devices().await?;
 
 			// Queue for all devices except self
-			for device_id in library_devices {
-				if device_id != self.device_id {
-					self.retry_queue.enqueue(device_id, message.clone()).await;
-				}
+			let targets: Vec<Uuid> = library_devices
+				.into_iter()
+				.filter(|id| *id != self.device_id)
+				.collect();
+			for (device_id, message) in self.policies.route(&targets, &message).await {
+				self.retry_queue.enqueue(device_id, message).await;
 			}
 
 			return Ok(());
@@ -1992,11 +2089,14 @@ impl PeerSync {
 		// Broadcast to all partners in parallel using futures::join_all
 		use futures::future::join_all;
 
-		let send_futures: Vec<_> = connected_partners
+		// Each partner gets the change filtered by its sync policy
+		let routed = self.policies.route(&connected_partners, &message).await;
+		let send_futures: Vec<_> = routed
 			.iter()
-			.map(|&partner| {
+			.map(|(partner, msg)| {
+				let partner = *partner;
 				let network = self.network.clone();
-				let msg = message.clone();
+				let msg = msg.clone();
 				async move {
 					// Add timeout to prevent hanging indefinitely
 					match tokio::time::timeout(
@@ -2019,7 +2119,7 @@ impl PeerSync {
 		let mut success_count = 0;
 		let mut error_count = 0;
 
-		for (partner_uuid, result) in results {
+		for ((partner_uuid, result), (_, routed_message)) in results.into_iter().zip(&routed) {
 			match result {
 				Ok(()) => {
 					success_count += 1;
@@ -2034,7 +2134,7 @@ impl PeerSync {
 					);
 					// Enqueue for retry
 					self.retry_queue
-						.enqueue(partner_uuid, message.clone())
+						.enqueue(partner_uuid, routed_message.clone())
 						.await;
 				}
 			}
@@ -2339,41 +2439,31 @@ impl PeerSync {
 			"Applying shared change"
 		);
 
-		// HLC conflict resolution: check if we already have a more recent change for this record
-		if let Ok(Some(existing_hlc)) = self
-			.peer_log
-			.get_latest_hlc_for_record(entry.record_uuid)
-			.await
-		{
-			if entry.hlc <= existing_hlc {
-				debug!(
-					incoming_hlc = %entry.hlc,
-					existing_hlc = %existing_hlc,
-					record_uuid = %entry.record_uuid,
-					"Ignoring incoming change with older or equal HLC"
-				);
-				return Ok(());
-			}
-		}
+		// Field-level merge against what we already have for this record
+		let applied = self.apply_merged_shared_change(&entry).await.map_err(|e| {
+			// Record error metrics (spawn async task)
+			let metrics = self.metrics.clone();
+			let model_type = entry.model_type.clone();
+			let error_msg = format!("Failed to apply shared change: {}", e);
+			tokio::spawn(async move {
+				let _ = metrics
+					.record_error(
+						super::metrics::ErrorEvent::new("apply".to_string(), error_msg)
+							.with_model_type(model_type),
+					)
+					.await;
+			});
+			anyhow::anyhow!("Failed to apply shared change: {}", e)
+		})?;
 
-		// Use the registry to route to the appropriate apply function
-		crate::infra::sync::apply_shared_change(entry.clone(), self.db.clone())
-			.await
-			.map_err(|e| {
-				// Record error metrics (spawn async task)
-				let metrics = self.metrics.clone();
-				let model_type = entry.model_type.clone();
-				let error_msg = format!("Failed to apply shared change: {}", e);
-				tokio::spawn(async move {
-					let _ = metrics
-						.record_error(
-							super::metrics::ErrorEvent::new("apply".to_string(), error_msg)
-								.with_model_type(model_type),
-						)
-						.await;
-				});
-				anyhow::anyhow!("Failed to apply shared change: {}", e)
-			})?;
+		if !applied {
+			debug!(
+				incoming_hlc = %entry.hlc,
+				record_uuid = %entry.record_uuid,
+				"Ignoring incoming change already superseded locally"
+			);
+			return Ok(());
+		}
 
 		// Record this change in our peer log (track what we've applied)
 		self.peer_log
@@ -2484,7 +2574,6 @@ impl PeerSync {
 
 		// Emit resource event for UI reactivity using ResourceManager
 		// This ensures proper resource format (Location, etc.) instead of raw DB model
-		use crate::infra::sync::peer_log::ChangeType;
 		match entry.change_type {
 			ChangeType::Delete => {
 				self.event_bus.emit(Event::ResourceDeleted {
@@ -2514,6 +2603,126 @@ impl PeerSync {
 		Ok(())
 	}
 
+	/// Merge an incoming shared change into local state field by field, then apply it
+	///
+	/// Fields whose local clock is newer keep their local value, so concurrent edits to
+	/// different fields of a record both survive. Deletes, and records we have no field
+	/// clocks for yet, fall back to whole-record HLC comparison. Returns `false` when
+	/// nothing in the change is newer than what we have.
+	pub(crate) async fn apply_merged_shared_change(
+		&self,
+		entry: &SharedChangeEntry,
+	) -> std::result::Result<bool, ApplyError> {
+		let log_err = |e: PeerLogError| ApplyError::DatabaseError(e.to_string());
+
+		// Redelivered change (or one of ours coming back through a peer's log)
+		if self.peer_log.contains(entry.hlc).await.map_err(log_err)? {
+			return Ok(false);
+		}
+
+		let local = self
+			.peer_log
+			.field_states(entry.record_uuid)
+			.await
+			.map_err(log_err)?;
+
+		if entry.change_type == ChangeType::Delete || local.is_empty() {
+			if let Ok(Some(existing_hlc)) = self
+				.peer_log
+				.get_latest_hlc_for_record(entry.record_uuid)
+				.await
+			{
+				if entry.hlc <= existing_hlc {
+					return Ok(false);
+				}
+			}
+		}
+
+		if entry.change_type == ChangeType::Delete {
+			crate::infra::sync::apply_shared_change(entry.clone(), self.db.clone()).await?;
+			self.peer_log
+				.clear_field_states(entry.record_uuid)
+				.await
+				.map_err(log_err)?;
+			self.on_shared_change_applied(entry).await;
+			return Ok(true);
+		}
+
+		let merge = merge_fields(&local, entry);
+		for conflict in &merge.conflicts {
+			self.record_field_conflict(entry.hlc.device_id, conflict)
+				.await;
+		}
+
+		if merge.accepted.is_empty() {
+			// Remember the change so a redelivery doesn't report its conflicts again
+			if !merge.conflicts.is_empty() {
+				self.peer_log.append(entry.clone()).await.map_err(log_err)?;
+			}
+			return Ok(false);
+		}
+
+		let mut merged = entry.clone();
+		merged.data = merge.data;
+		crate::infra::sync::apply_shared_change(merged, self.db.clone()).await?;
+
+		self.peer_log
+			.save_field_states(&entry.model_type, entry.record_uuid, &merge.accepted)
+			.await
+			.map_err(log_err)?;
+		self.on_shared_change_applied(entry).await;
+
+		Ok(true)
+	}
+
+	/// Follow up on shared changes that affect sync itself
+	async fn on_shared_change_applied(&self, entry: &SharedChangeEntry) {
+		if entry.model_type != "sync_policy" {
+			return;
+		}
+
+		// Policies are keyed by their device, so this is our own policy changing
+		if entry.record_uuid == self.device_id {
+			if let Err(e) = self.resync_after_policy_change().await {
+				warn!(error = %e, "Failed to re-sync after sync policy change");
+			}
+		} else {
+			self.policies.invalidate().await;
+		}
+	}
+
+	/// Record a same-field conflict in metrics and the sync event log
+	async fn record_field_conflict(&self, peer_device_id: Uuid, conflict: &FieldConflict) {
+		self.metrics.record_conflict_detected();
+		self.metrics.record_conflict_resolved_by_hlc();
+
+		warn!(
+			model_type = %conflict.model_type,
+			record_uuid = %conflict.record_uuid,
+			field = %conflict.field,
+			peer = %peer_device_id,
+			resolution = ?conflict.resolution,
+			"Concurrent edits to the same field, resolved by HLC"
+		);
+
+		if let Some(event_logger) = self.metrics.event_logger().read().await.as_ref() {
+			let event = SyncEventLog::new(
+				self.device_id,
+				SyncEventType::FieldConflict,
+				format!(
+					"Conflicting edits to {}.{} resolved by HLC",
+					conflict.model_type, conflict.field
+				),
+			)
+			.with_peer(peer_device_id)
+			.with_model_types(vec![conflict.model_type.clone()])
+			.with_record_count(1)
+			.with_details(serde_json::to_value(conflict).unwrap_or_default());
+
+			let _ = event_logger.log(event).await;
+		}
+	}
+
 	/// Record ACK from peer and prune
 	pub async fn on_ack_received(&self, peer_id: Uuid, up_to_hlc: HLC) -> Result<()> {
 		// Don't record ACKs from ourselves (defense in depth)
@@ -2783,6 +2992,66 @@ impl PeerSync {
 		Ok(serde_json::Value::Object(response))
 	}
 
+	/// Answer a peer's SharedChangeRequest, filtered by the peer's sync policy
+	///
+	/// The initial request (no `since_hlc`) also carries the full shared state. The
+	/// response's cursor is the last change scanned, which the filter may have left out
+	/// of the page, so the peer resumes past it instead of asking for it again.
+	pub async fn shared_change_response(
+		&self,
+		peer: Uuid,
+		since_hlc: Option<HLC>,
+		limit: usize,
+	) -> Result<SyncMessage> {
+		let (entries, has_more) = self.get_shared_changes(since_hlc, limit).await?;
+
+		let current_state = if since_hlc.is_none() {
+			debug!("Initial backfill requested - querying full shared resource state");
+			match self.get_full_shared_state().await {
+				Ok(state) => {
+					info!("Including full state snapshot for initial backfill");
+					Some(state)
+				}
+				Err(e) => {
+					warn!("Failed to query full shared state: {}", e);
+					None
+				}
+			}
+		} else {
+			None
+		};
+
+		let cursor = entries.last().map(|entry| entry.hlc);
+
+		let (entries, current_state) = match self.policies.for_device(peer).await {
+			Ok(policy) => {
+				let entries = self
+					.policies
+					.filter_shared_entries(&policy, entries)
+					.await?;
+				let current_state = match current_state {
+					Some(state) => {
+						Some(self.policies.filter_shared_snapshot(&policy, state).await?)
+					}
+					None => None,
+				};
+				(entries, current_state)
+			}
+			Err(e) => {
+				warn!(error = %e, "Failed to load sync policy, sending unfiltered changes");
+				(entries, current_state)
+			}
+		};
+
+		Ok(SyncMessage::SharedChangeResponse {
+			library_id: self.library_id,
+			entries,
+			current_state,
+			has_more,
+			cursor,
+		})
+	}
+
 	/// Transition to ready state (after backfill)
 	pub async fn transition_to_ready(&self) -> Result<()> {
 		let current_state = self.state().await;
@@ -2926,6 +3195,7 @@ impl PeerSync {
 				&self.state,
 				&self.buffer,
 				&self.retry_queue,
+				&self.policies,
 				&self.db,
 				&self.config,
 				&self.last_realtime_activity,
@@ -2952,6 +3222,7 @@ impl PeerSync {
 				&self.state,
 				&self.buffer,
 				&self.retry_queue,
+				&self.policies,
 				&self.db,
 				&self.config,
 				&self.metrics,
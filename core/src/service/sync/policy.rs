@@ -0,0 +1,640 @@
+//! Selective sync policies
+//!
+//! Each device may subscribe to a subset of the library: a tier, and for the selective
+//! tier, the locations, volumes, tags and model types it wants. Policies are shared
+//! resources (`sync_policy`), so the device holding the data evaluates the receiver's
+//! policy before anything is sent, in broadcasts and when serving backfill.
+//!
+//! A record referencing a row the receiver won't get is held back rather than sent with
+//! the reference dropped, so the receiver never waits on a dependency that can't arrive;
+//! widening the policy re-syncs it. Optional references into a model the receiver takes
+//! none of, such as a location's root entry under the metadata-only tier, are sent empty
+//! instead.
+//!
+//! Narrowing a policy only stops future replication; rows already on the device stay.
+
+use crate::{
+	infra::{
+		db::entities::sync_policy,
+		sync::{
+			get_fk_mappings, references_replicated, registry::get_model_type_by_table, ChangeType,
+			FKMapping, SharedChangeEntry,
+		},
+	},
+	service::network::protocol::sync::messages::{StateRecord, SyncMessage},
+};
+use anyhow::Result;
+use sea_orm::{ConnectionTrait, DatabaseConnection, EntityTrait, Statement};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+	time::{Duration, Instant},
+};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Models every device receives, whatever its policy
+const ALWAYS_REPLICATED: &[&str] = &["device", "sync_policy"];
+
+/// Models describing files and their contents, left out by the metadata-only tier
+const FILE_MODELS: &[&str] = &[
+	"entry",
+	"content_identity",
+	"image_media_data",
+	"video_media_data",
+	"audio_media_data",
+	"sidecar",
+];
+
+/// References that may be sent empty when the receiver takes none of their target model,
+/// as (model type, foreign key field)
+const OPTIONAL_REFERENCES: &[(&str, &str)] = &[("location", "entry_id")];
+
+/// How long loaded policies are trusted before being re-read
+///
+/// Policies change rarely and arrive through several sync paths, so a short-lived cache
+/// is simpler than invalidating on each of them.
+const CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Entry UUIDs resolved against the scope per query
+const SCOPE_QUERY_CHUNK: usize = 500;
+
+/// How much of the library replicates to a device
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncTier {
+	/// Everything
+	#[default]
+	Full,
+	/// Locations, volumes, tags, spaces and other shared organization, but no entries
+	/// or content
+	MetadataOnly,
+	/// Only the locations, volumes, tags and model types listed in the policy
+	Selective,
+}
+
+impl SyncTier {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Full => "full",
+			Self::MetadataOnly => "metadata_only",
+			Self::Selective => "selective",
+		}
+	}
+
+	pub fn from_str(s: &str) -> Option<Self> {
+		match s {
+			"full" => Some(Self::Full),
+			"metadata_only" => Some(Self::MetadataOnly),
+			"selective" => Some(Self::Selective),
+			_ => None,
+		}
+	}
+}
+
+/// What other devices replicate to one device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct SyncPolicy {
+	/// The subscribing device
+	pub device_id: Uuid,
+	pub tier: SyncTier,
+	/// Entries under these locations replicate (selective tier)
+	#[serde(default)]
+	pub locations: Vec<Uuid>,
+	/// Entries on these volumes, and the directories above them, replicate (selective tier)
+	#[serde(default)]
+	pub volumes: Vec<Uuid>,
+	/// Entries tagged, directly or through their content, with these tags, and the
+	/// directories above them, replicate (selective tier)
+	#[serde(default)]
+	pub tags: Vec<Uuid>,
+	/// Model types that replicate, all when empty (selective tier)
+	#[serde(default)]
+	pub model_types: Vec<String>,
+}
+
+impl SyncPolicy {
+	/// The default policy: everything replicates
+	pub fn full(device_id: Uuid) -> Self {
+		Self {
+			device_id,
+			tier: SyncTier::Full,
+			locations: Vec::new(),
+			volumes: Vec::new(),
+			tags: Vec::new(),
+			model_types: Vec::new(),
+		}
+	}
+
+	pub fn from_model(model: &sync_policy::Model) -> Self {
+		let uuids = |json: &Value| serde_json::from_value(json.clone()).unwrap_or_default();
+		Self {
+			device_id: model.device_uuid,
+			tier: SyncTier::from_str(&model.tier).unwrap_or_default(),
+			locations: uuids(&model.locations),
+			volumes: uuids(&model.volumes),
+			tags: uuids(&model.tags),
+			model_types: serde_json::from_value(model.model_types.clone()).unwrap_or_default(),
+		}
+	}
+
+	/// Whether records of a model type replicate to the device at all
+	pub fn replicates_model(&self, model_type: &str) -> bool {
+		if ALWAYS_REPLICATED.contains(&model_type) {
+			return true;
+		}
+		match self.tier {
+			SyncTier::Full => true,
+			SyncTier::MetadataOnly => !FILE_MODELS.contains(&model_type),
+			SyncTier::Selective => {
+				self.model_types.is_empty() || self.model_types.iter().any(|m| m == model_type)
+			}
+		}
+	}
+
+	/// Whether only some entries replicate, decided per entry
+	pub fn scopes_entries(&self) -> bool {
+		self.tier == SyncTier::Selective
+			&& !(self.locations.is_empty() && self.volumes.is_empty() && self.tags.is_empty())
+	}
+}
+
+/// Loads sync policies and filters outgoing sync data by the receiver's policy
+pub struct SyncPolicies {
+	db: Arc<DatabaseConnection>,
+	cache: RwLock<Option<(Instant, Arc<HashMap<Uuid, SyncPolicy>>)>>,
+}
+
+impl SyncPolicies {
+	pub fn new(db: Arc<DatabaseConnection>) -> Self {
+		Self {
+			db,
+			cache: RwLock::new(None),
+		}
+	}
+
+	/// Drop cached policies so the next lookup re-reads them
+	pub async fn invalidate(&self) {
+		*self.cache.write().await = None;
+	}
+
+	/// Policy of a device, the full tier if it has none
+	pub async fn for_device(&self, device_id: Uuid) -> Result<SyncPolicy> {
+		Ok(self
+			.load()
+			.await?
+			.get(&device_id)
+			.cloned()
+			.unwrap_or_else(|| SyncPolicy::full(device_id)))
+	}
+
+	async fn load(&self) -> Result<Arc<HashMap<Uuid, SyncPolicy>>> {
+		if let Some((loaded_at, policies)) = self.cache.read().await.as_ref() {
+			if loaded_at.elapsed() < CACHE_TTL {
+				return Ok(policies.clone());
+			}
+		}
+
+		let policies: HashMap<Uuid, SyncPolicy> = sync_policy::Entity::find()
+			.all(self.db.as_ref())
+			.await?
+			.iter()
+			.map(|model| (model.device_uuid, SyncPolicy::from_model(model)))
+			.collect();
+		let policies = Arc::new(policies);
+
+		*self.cache.write().await = Some((Instant::now(), policies.clone()));
+		Ok(policies)
+	}
+
+	/// The message each target should receive, leaving out targets it's filtered away for
+	///
+	/// If a policy can't be evaluated the message is sent unfiltered; the receiver's own
+	/// backfill still skips model types it doesn't subscribe to.
+	pub async fn route(&self, targets: &[Uuid], message: &SyncMessage) -> Vec<(Uuid, SyncMessage)> {
+		let mut routed = Vec::with_capacity(targets.len());
+
+		for &target in targets {
+			let filtered = match self.for_device(target).await {
+				Ok(policy) => self.filter_message(&policy, message).await,
+				Err(e) => Err(e),
+			};
+
+			match filtered {
+				Ok(Some(message)) => routed.push((target, message)),
+				Ok(None) => {
+					debug!(target = %target, "Change excluded by sync policy, not sending");
+				}
+				Err(e) => {
+					warn!(
+						target = %target,
+						error = %e,
+						"Failed to evaluate sync policy, sending unfiltered"
+					);
+					routed.push((target, message.clone()));
+				}
+			}
+		}
+
+		routed
+	}
+
+	/// Filter a broadcast message, `None` if nothing in it replicates
+	pub async fn filter_message(
+		&self,
+		policy: &SyncPolicy,
+		message: &SyncMessage,
+	) -> Result<Option<SyncMessage>> {
+		if policy.tier == SyncTier::Full {
+			return Ok(Some(message.clone()));
+		}
+
+		let filtered = match message {
+			SyncMessage::StateChange {
+				library_id,
+				model_type,
+				record_uuid,
+				device_id,
+				data,
+				timestamp,
+			} => self
+				.filter_data(policy, model_type, vec![data.clone()])
+				.await?
+				.pop()
+				.flatten()
+				.map(|data| SyncMessage::StateChange {
+					library_id: *library_id,
+					model_type: model_type.clone(),
+					record_uuid: *record_uuid,
+					device_id: *device_id,
+					data,
+					timestamp: *timestamp,
+				}),
+			SyncMessage::StateBatch {
+				library_id,
+				model_type,
+				device_id,
+				records,
+			} => {
+				let records = self
+					.filter_state_records(policy, model_type, records.clone())
+					.await?;
+				(!records.is_empty()).then(|| SyncMessage::StateBatch {
+					library_id: *library_id,
+					model_type: model_type.clone(),
+					device_id: *device_id,
+					records,
+				})
+			}
+			SyncMessage::SharedChange { library_id, entry } => self
+				.filter_shared_entries(policy, vec![entry.clone()])
+				.await?
+				.pop()
+				.map(|entry| SyncMessage::SharedChange {
+					library_id: *library_id,
+					entry,
+				}),
+			SyncMessage::SharedChangeBatch {
+				library_id,
+				entries,
+			} => {
+				let entries = self.filter_shared_entries(policy, entries.clone()).await?;
+				(!entries.is_empty()).then(|| SyncMessage::SharedChangeBatch {
+					library_id: *library_id,
+					entries,
+				})
+			}
+			other => Some(other.clone()),
+		};
+
+		Ok(filtered)
+	}
+
+	/// Filter device-owned records of one model type
+	pub async fn filter_state_records(
+		&self,
+		policy: &SyncPolicy,
+		model_type: &str,
+		records: Vec<StateRecord>,
+	) -> Result<Vec<StateRecord>> {
+		if policy.tier == SyncTier::Full {
+			return Ok(records);
+		}
+
+		let data = records.iter().map(|record| record.data.clone()).collect();
+		let filtered = self.filter_data(policy, model_type, data).await?;
+
+		Ok(records
+			.into_iter()
+			.zip(filtered)
+			.filter_map(|(record, data)| data.map(|data| StateRecord { data, ..record }))
+			.collect())
+	}
+
+	/// Filter shared changes, which may mix model types
+	///
+	/// Deletions pass whenever their model type replicates; they carry no references.
+	pub async fn filter_shared_entries(
+		&self,
+		policy: &SyncPolicy,
+		mut entries: Vec<SharedChangeEntry>,
+	) -> Result<Vec<SharedChangeEntry>> {
+		if policy.tier == SyncTier::Full {
+			return Ok(entries);
+		}
+
+		entries.retain(|entry| policy.replicates_model(&entry.model_type));
+
+		let mut by_model: HashMap<String, Vec<usize>> = HashMap::new();
+		for (index, entry) in entries.iter().enumerate() {
+			if entry.change_type != ChangeType::Delete {
+				by_model
+					.entry(entry.model_type.clone())
+					.or_default()
+					.push(index);
+			}
+		}
+
+		let mut keep = vec![true; entries.len()];
+		for (model_type, indices) in by_model {
+			let data = indices.iter().map(|&i| entries[i].data.clone()).collect();
+			let filtered = self.filter_data(policy, &model_type, data).await?;
+			for (index, data) in indices.into_iter().zip(filtered) {
+				match data {
+					Some(data) => entries[index].data = data,
+					None => keep[index] = false,
+				}
+			}
+		}
+
+		Ok(entries
+			.into_iter()
+			.zip(keep)
+			.filter_map(|(entry, keep)| keep.then_some(entry))
+			.collect())
+	}
+
+	/// Filter a full shared state snapshot (`{ model_type: [{ uuid, data }] }`)
+	pub async fn filter_shared_snapshot(
+		&self,
+		policy: &SyncPolicy,
+		snapshot: Value,
+	) -> Result<Value> {
+		if policy.tier == SyncTier::Full {
+			return Ok(snapshot);
+		}
+
+		let Value::Object(models) = snapshot else {
+			return Ok(snapshot);
+		};
+
+		let mut filtered_models = serde_json::Map::new();
+		for (model_type, records) in models {
+			let Value::Array(records) = records else {
+				continue;
+			};
+
+			let data = records
+				.iter()
+				.map(|record| record.get("data").cloned().unwrap_or(Value::Null))
+				.collect();
+			let filtered = self.filter_data(policy, &model_type, data).await?;
+
+			let records: Vec<Value> = records
+				.into_iter()
+				.zip(filtered)
+				.filter_map(|(mut record, data)| {
+					record["data"] = data?;
+					Some(record)
+				})
+				.collect();
+
+			if !records.is_empty() {
+				filtered_models.insert(model_type, Value::Array(records));
+			}
+		}
+
+		Ok(Value::Object(filtered_models))
+	}
+
+	/// Filter records of one model type, keeping their positions
+	async fn filter_data(
+		&self,
+		policy: &SyncPolicy,
+		model_type: &str,
+		records: Vec<Value>,
+	) -> Result<Vec<Option<Value>>> {
+		if !policy.replicates_model(model_type) {
+			return Ok(vec![None; records.len()]);
+		}
+
+		let mappings = get_fk_mappings(model_type).unwrap_or_default();
+		let targets_entries =
+			|fk: &FKMapping| get_model_type_by_table(fk.target_table) == Some("entry");
+
+		// Resolve every entry these records are or point at against the scope at once
+		let in_scope = if policy.scopes_entries() {
+			let mut uuids = HashSet::new();
+			for record in &records {
+				if model_type == "entry" {
+					uuids.extend(uuid_field(record, "uuid"));
+				}
+				for fk in mappings.iter().filter(|fk| targets_entries(fk)) {
+					uuids.extend(uuid_field(record, &fk.uuid_field_name()));
+				}
+			}
+			Some(self.entries_in_scope(policy, uuids).await?)
+		} else {
+			None
+		};
+
+		let is_replicated =
+			|fk: &FKMapping, uuid: Uuid| match get_model_type_by_table(fk.target_table) {
+				Some("entry") => {
+					policy.replicates_model("entry")
+						&& in_scope
+							.as_ref()
+							.map_or(true, |scope| scope.contains(&uuid))
+				}
+				Some(target) => policy.replicates_model(target),
+				None => true,
+			};
+
+		let detached: Vec<String> = mappings
+			.iter()
+			.filter(|fk| {
+				OPTIONAL_REFERENCES
+					.iter()
+					.any(|&(model, field)| model == model_type && field == fk.local_field)
+					&& get_model_type_by_table(fk.target_table)
+						.is_some_and(|target| !policy.replicates_model(target))
+			})
+			.map(FKMapping::uuid_field_name)
+			.collect();
+
+		Ok(records
+			.into_iter()
+			.map(|mut record| {
+				for field in &detached {
+					if let Some(value) = record.get_mut(field) {
+						*value = Value::Null;
+					}
+				}
+				if model_type == "entry" {
+					if let Some(scope) = &in_scope {
+						if !uuid_field(&record, "uuid").is_some_and(|uuid| scope.contains(&uuid)) {
+							return None;
+						}
+					}
+				}
+				references_replicated(&record, &mappings, is_replicated).then_some(record)
+			})
+			.collect())
+	}
+
+	/// The entries, out of `uuids`, that a selective policy's locations, volumes or tags cover
+	///
+	/// Volumes and tags also cover the directories above the entries they match, so each
+	/// replicated entry's parent replicates with it. Locations cover whole subtrees already.
+	async fn entries_in_scope(
+		&self,
+		policy: &SyncPolicy,
+		uuids: HashSet<Uuid>,
+	) -> Result<HashSet<Uuid>> {
+		let db = self.db.as_ref();
+		let uuids: Vec<Uuid> = uuids.into_iter().collect();
+		let mut in_scope = HashSet::new();
+
+		for chunk in uuids.chunks(SCOPE_QUERY_CHUNK) {
+			let mut selects = Vec::new();
+			let mut values: Vec<sea_orm::Value> = Vec::new();
+
+			let mut add_select = |sql: &str, filter: &[Uuid]| {
+				if filter.is_empty() {
+					return;
+				}
+				selects.push(
+					sql.replace("{filter}", &placeholders(filter.len()))
+						.replace("{entries}", &placeholders(chunk.len())),
+				);
+				values.extend(filter.iter().map(|uuid| (*uuid).into()));
+				values.extend(chunk.iter().map(|uuid| (*uuid).into()));
+			};
+
+			add_select(
+				r#"
+				SELECT e.uuid AS uuid FROM entries e
+				JOIN entry_closure ec ON ec.descendant_id = e.id
+				JOIN locations l ON l.entry_id = ec.ancestor_id
+				WHERE l.uuid IN ({filter}) AND e.uuid IN ({entries})
+				"#,
+				&policy.locations,
+			);
+			add_select(
+				r#"
+				SELECT e.uuid AS uuid FROM entries e
+				JOIN entry_closure ec ON ec.ancestor_id = e.id
+				JOIN entries d ON d.id = ec.descendant_id
+				JOIN volumes v ON v.id = d.volume_id
+				WHERE v.uuid IN ({filter}) AND e.uuid IN ({entries})
+				"#,
+				&policy.volumes,
+			);
+			add_select(
+				r#"
+				SELECT e.uuid AS uuid FROM entries e
+				JOIN entry_closure ec ON ec.ancestor_id = e.id
+				JOIN entries d ON d.id = ec.descendant_id
+				JOIN user_metadata um ON um.entry_uuid = d.uuid
+					OR um.content_identity_uuid =
+						(SELECT ci.uuid FROM content_identities ci WHERE ci.id = d.content_id)
+				JOIN user_metadata_tag umt ON umt.user_metadata_id = um.id
+				JOIN tag t ON t.id = umt.tag_id
+				WHERE t.uuid IN ({filter}) AND e.uuid IN ({entries})
+				"#,
+				&policy.tags,
+			);
+
+			if selects.is_empty() {
+				break;
+			}
+
+			let rows = db
+				.query_all(Statement::from_sql_and_values(
+					db.get_database_backend(),
+					selects.join(" UNION "),
+					values,
+				))
+				.await?;
+			for row in rows {
+				if let Some(uuid) = row.try_get::<Option<Uuid>>("", "uuid")? {
+					in_scope.insert(uuid);
+				}
+			}
+		}
+
+		Ok(in_scope)
+	}
+}
+
+fn uuid_field(record: &Value, field: &str) -> Option<Uuid> {
+	record
+		.get(field)
+		.and_then(|v| v.as_str())
+		.and_then(|s| Uuid::parse_str(s).ok())
+}
+
+fn placeholders(count: usize) -> String {
+	vec!["?"; count].join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_metadata_only_skips_files() {
+		let policy = SyncPolicy {
+			tier: SyncTier::MetadataOnly,
+			..SyncPolicy::full(Uuid::new_v4())
+		};
+
+		assert!(policy.replicates_model("location"));
+		assert!(policy.replicates_model("tag"));
+		assert!(policy.replicates_model("space"));
+		assert!(!policy.replicates_model("entry"));
+		assert!(!policy.replicates_model("content_identity"));
+		assert!(!policy.scopes_entries());
+	}
+
+	#[test]
+	fn test_selective_model_types() {
+		let policy = SyncPolicy {
+			tier: SyncTier::Selective,
+			model_types: vec!["tag".to_string()],
+			..SyncPolicy::full(Uuid::new_v4())
+		};
+
+		assert!(policy.replicates_model("tag"));
+		assert!(!policy.replicates_model("location"));
+		// Devices and policies always replicate so every peer can evaluate the others
+		assert!(policy.replicates_model("device"));
+		assert!(policy.replicates_model("sync_policy"));
+	}
+
+	#[test]
+	fn test_selective_scopes_entries() {
+		let policy = SyncPolicy {
+			tier: SyncTier::Selective,
+			locations: vec![Uuid::new_v4()],
+			..SyncPolicy::full(Uuid::new_v4())
+		};
+
+		assert!(policy.replicates_model("entry"));
+		assert!(policy.scopes_entries());
+	}
+}
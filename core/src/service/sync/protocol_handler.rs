@@ -54,23 +54,24 @@ impl LogSyncHandler {
 			"Handling shared change"
 		);
 
-		// Use registry to apply with conflict resolution (shared models)
+		// Merge field by field against local state, then apply via the registry
 		// Models implement their own merge strategies (union, LWW, etc.)
+		let applied = self
+			.peer_sync
+			.apply_merged_shared_change(&entry)
+			.await
+			.map_err(|e| anyhow::anyhow!("{}", e))?;
 
-		// Extract info before moving entry
 		let hlc_device_id = entry.hlc.device_id;
 		let hlc = entry.hlc;
-		let model_type = entry.model_type.clone();
+		let model_type = entry.model_type;
 		let record_uuid = entry.record_uuid;
 		let change_type = entry.change_type;
 
 		let db = Arc::new(self.peer_sync.db().as_ref().clone());
-		crate::infra::sync::registry::apply_shared_change(entry, db.clone())
-			.await
-			.map_err(|e| anyhow::anyhow!("{}", e))?;
 
 		// Emit resource event for UI reactivity (for insert/update changes)
-		if matches!(change_type, ChangeType::Insert | ChangeType::Update) {
+		if applied && matches!(change_type, ChangeType::Insert | ChangeType::Update) {
 			let resource_manager =
 				crate::domain::ResourceManager::new(db.clone(), self.peer_sync.event_bus().clone());
 
@@ -134,6 +135,7 @@ impl LogSyncHandler {
 			entries: limited,
 			current_state,
 			has_more,
+			cursor: None,
 		})
 	}
 
@@ -10,7 +10,7 @@ use crate::ops::indexing::handlers::{EphemeralEventHandler, LocationMeta, Persis
 use crate::ops::indexing::rules::RuleToggles;
 use crate::service::Service;
 use anyhow::Result;
-use sd_fs_watcher::{FsEvent, FsWatcher, WatchConfig, WatcherConfig};
+use sd_fs_watcher::{FsEvent, FsWatcher, PollingConfig, WatchBackend, WatchConfig, WatcherConfig};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -27,6 +27,8 @@ pub struct FsWatcherServiceConfig {
 	pub tick_interval: Duration,
 	/// Enable debug logging
 	pub debug_mode: bool,
+	/// Polling cadence for paths on network shares and FUSE mounts
+	pub polling: PollingConfig,
 }
 
 impl Default for FsWatcherServiceConfig {
@@ -35,6 +37,7 @@ impl Default for FsWatcherServiceConfig {
 			event_buffer_size: 100_000,
 			tick_interval: Duration::from_millis(100),
 			debug_mode: false,
+			polling: PollingConfig::default(),
 		}
 	}
 }
@@ -45,6 +48,7 @@ impl From<FsWatcherServiceConfig> for WatcherConfig {
 			.with_buffer_size(config.event_buffer_size)
 			.with_tick_interval(config.tick_interval)
 			.with_debug(config.debug_mode)
+			.with_polling(config.polling)
 	}
 }
 
@@ -124,13 +128,35 @@ impl FsWatcherService {
 	///
 	/// For persistent locations, use `WatchConfig::recursive()`.
 	/// For ephemeral browsing, use `WatchConfig::shallow()`.
+	///
+	/// Paths on volumes that don't deliver change notifications are polled instead.
 	pub async fn watch_path(&self, path: impl Into<PathBuf>, config: WatchConfig) -> Result<()> {
 		let path = path.into();
 		debug!("Watching path: {}", path.display());
+		let config = self.select_backend(&path, config).await;
 		self.watcher.watch_path(&path, config).await?;
 		Ok(())
 	}
 
+	/// Switch a watch to polling if its path is on a network share or FUSE mount
+	async fn select_backend(&self, path: &Path, config: WatchConfig) -> WatchConfig {
+		let Some(volume) = self.context.volume_manager.volume_for_path(path).await else {
+			return config;
+		};
+
+		if volume.requires_polling_watcher() {
+			info!(
+				"Polling {} for changes ({} volume '{}')",
+				path.display(),
+				volume.file_system,
+				volume.name
+			);
+			config.with_backend(WatchBackend::Polling)
+		} else {
+			config
+		}
+	}
+
 	/// Stop watching a path
 	pub async fn unwatch_path(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
 		let path = path.as_ref();
@@ -298,9 +324,8 @@ impl FsWatcherService {
 			.register_for_watching(path.clone());
 
 		// Start OS-level watching (shallow = immediate children only)
-		self.watcher
-			.watch_path(&path, WatchConfig::shallow())
-			.await?;
+		let config = self.select_backend(&path, WatchConfig::shallow()).await;
+		self.watcher.watch_path(&path, config).await?;
 
 		Ok(())
 	}
@@ -223,6 +223,7 @@ impl TestConfigBuilder {
 			},
 			logging: crate::config::app_config::LoggingConfig::default(),
 			proxy_pairing: crate::config::app_config::ProxyPairingConfig::default(),
+			file_transfer: crate::config::app_config::FileTransferConfig::default(),
 		}
 	}
 
@@ -1,7 +1,7 @@
 //! Cloud storage backend implementation using OpenDAL
 //!
 //! This module provides cloud storage support for S3, Google Drive, Dropbox,
-//! OneDrive, and 40+ other services via Apache OpenDAL.
+//! OneDrive, SFTP and WebDAV servers, and 40+ other services via Apache OpenDAL.
 
 use async_trait::async_trait;
 use bytes::Bytes;
@@ -227,6 +227,90 @@ impl CloudBackend {
 		})
 	}
 
+	/// Create a new backend for an SFTP server
+	///
+	/// `endpoint` is `host` or `host:port`. Authentication uses the given private
+	/// key, or the ssh-agent and default identities when `private_key` is None.
+	/// The host key must already be in `known_hosts`.
+	///
+	/// SFTP is only available on Unix, where OpenDAL can drive the system `ssh`.
+	pub async fn new_sftp(
+		endpoint: impl AsRef<str>,
+		user: impl AsRef<str>,
+		private_key: Option<String>,
+		root: Option<String>,
+	) -> Result<Self, VolumeError> {
+		#[cfg(unix)]
+		{
+			let mut builder = opendal::services::Sftp::default()
+				.endpoint(&format!("ssh://{}", endpoint.as_ref()))
+				.user(user.as_ref());
+
+			if let Some(key) = &private_key {
+				builder = builder.key(key);
+			}
+
+			if let Some(r) = &root {
+				builder = builder.root(r);
+			}
+
+			let operator = opendal::Operator::new(builder)
+				.map_err(|e| {
+					VolumeError::Platform(format!("Failed to create SFTP operator: {}", e))
+				})?
+				.finish();
+
+			Ok(Self {
+				operator,
+				service_type: CloudServiceType::Sftp,
+				root: PathBuf::from(root.unwrap_or_else(|| "/".to_string())),
+			})
+		}
+
+		#[cfg(not(unix))]
+		{
+			let _ = (endpoint, user, private_key, root);
+			Err(VolumeError::Platform(
+				"SFTP volumes are not supported on this platform".to_string(),
+			))
+		}
+	}
+
+	/// Create a new backend for a WebDAV server
+	///
+	/// `endpoint` is the server URL (e.g. `https://nas.local/remote.php/dav/files/me`).
+	/// Credentials are optional for servers that allow anonymous access.
+	pub async fn new_webdav(
+		endpoint: impl AsRef<str>,
+		username: Option<String>,
+		password: Option<String>,
+		root: Option<String>,
+	) -> Result<Self, VolumeError> {
+		let mut builder = opendal::services::Webdav::default().endpoint(endpoint.as_ref());
+
+		if let Some(u) = &username {
+			builder = builder.username(u);
+		}
+
+		if let Some(p) = &password {
+			builder = builder.password(p);
+		}
+
+		if let Some(r) = &root {
+			builder = builder.root(r);
+		}
+
+		let operator = opendal::Operator::new(builder)
+			.map_err(|e| VolumeError::Platform(format!("Failed to create WebDAV operator: {}", e)))?
+			.finish();
+
+		Ok(Self {
+			operator,
+			service_type: CloudServiceType::WebDav,
+			root: PathBuf::from(root.unwrap_or_else(|| "/".to_string())),
+		})
+	}
+
 	/// Create a cloud backend from a pre-configured OpenDAL operator
 	pub fn from_operator(operator: opendal::Operator, service_type: CloudServiceType) -> Self {
 		Self {
@@ -482,4 +566,75 @@ mod tests {
 		// Test exists
 		assert!(backend.exists(Path::new("test.txt")).await.unwrap());
 	}
+
+	#[tokio::test]
+	#[ignore]
+	async fn test_cloud_backend_webdav() {
+		// Requires a WebDAV server, e.g. `docker run -p 8080:80 bytemark/webdav`
+		// Set WEBDAV_ENDPOINT (and optionally WEBDAV_USERNAME, WEBDAV_PASSWORD) to run
+
+		let endpoint = std::env::var("WEBDAV_ENDPOINT").unwrap();
+		let username = std::env::var("WEBDAV_USERNAME").ok();
+		let password = std::env::var("WEBDAV_PASSWORD").ok();
+
+		let backend = CloudBackend::new_webdav(&endpoint, username, password, None)
+			.await
+			.unwrap();
+		assert_eq!(
+			backend.backend_type(),
+			BackendType::Cloud(CloudServiceType::WebDav)
+		);
+
+		let test_data = Bytes::from("Hello, WebDAV!");
+		backend
+			.write(Path::new("test.txt"), test_data.clone())
+			.await
+			.unwrap();
+
+		// Ranged reads are what content hashing relies on
+		let range = backend
+			.read_range(Path::new("test.txt"), 7..13)
+			.await
+			.unwrap();
+		assert_eq!(&range[..], b"WebDAV");
+
+		let entries = backend.read_dir(Path::new("/")).await.unwrap();
+		assert!(entries.iter().any(|e| e.name == "test.txt"));
+
+		backend.delete(Path::new("test.txt")).await.unwrap();
+		assert!(!backend.exists(Path::new("test.txt")).await.unwrap());
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	#[ignore]
+	async fn test_cloud_backend_sftp() {
+		// Requires an sshd with SFTP enabled whose host key is in known_hosts
+		// Set SFTP_ENDPOINT (host:port), SFTP_USER and optionally SFTP_KEY to run
+
+		let endpoint = std::env::var("SFTP_ENDPOINT").unwrap();
+		let user = std::env::var("SFTP_USER").unwrap();
+		let key = std::env::var("SFTP_KEY").ok();
+
+		let backend = CloudBackend::new_sftp(&endpoint, &user, key, Some("/tmp".to_string()))
+			.await
+			.unwrap();
+
+		let test_data = Bytes::from("Hello, SFTP!");
+		backend
+			.write(Path::new("sd-test.txt"), test_data.clone())
+			.await
+			.unwrap();
+
+		let range = backend
+			.read_range(Path::new("sd-test.txt"), 7..11)
+			.await
+			.unwrap();
+		assert_eq!(&range[..], b"SFTP");
+
+		let metadata = backend.metadata(Path::new("sd-test.txt")).await.unwrap();
+		assert_eq!(metadata.size, test_data.len() as u64);
+
+		backend.delete(Path::new("sd-test.txt")).await.unwrap();
+	}
 }
@@ -86,6 +86,10 @@ pub enum CloudServiceType {
 	Wasabi,
 	#[serde(rename = "spaces")]
 	DigitalOceanSpaces,
+	#[serde(rename = "sftp")]
+	Sftp,
+	#[serde(rename = "webdav")]
+	WebDav,
 	#[serde(rename = "cloud")]
 	Other,
 }
@@ -104,6 +108,8 @@ impl CloudServiceType {
 			Self::BackblazeB2 => "b2",
 			Self::Wasabi => "wasabi",
 			Self::DigitalOceanSpaces => "spaces",
+			Self::Sftp => "sftp",
+			Self::WebDav => "webdav",
 			Self::Other => "cloud",
 		}
 	}
@@ -121,6 +127,8 @@ impl CloudServiceType {
 			"b2" => Some(Self::BackblazeB2),
 			"wasabi" => Some(Self::Wasabi),
 			"spaces" => Some(Self::DigitalOceanSpaces),
+			"sftp" => Some(Self::Sftp),
+			"webdav" => Some(Self::WebDav),
 			_ => None,
 		}
 	}
@@ -332,6 +332,62 @@ impl VolumeManager {
 									continue;
 								}
 							}
+							crate::volume::CloudServiceType::Sftp => {
+								if let crate::crypto::cloud_credentials::CredentialData::SshKey {
+									username,
+									private_key_path,
+								} = &credential.data
+								{
+									let root = cloud_config
+										.as_ref()
+										.and_then(|c| c.get("root"))
+										.and_then(|r| r.as_str())
+										.map(String::from);
+
+									crate::volume::CloudBackend::new_sftp(
+										cloud_identifier,
+										username,
+										private_key_path.clone(),
+										root,
+									).await
+								} else {
+									warn!("Invalid credential type for SFTP volume {}", fingerprint.0);
+									continue;
+								}
+							}
+							crate::volume::CloudServiceType::WebDav => {
+								if let crate::crypto::cloud_credentials::CredentialData::Basic {
+									username,
+									password,
+								} = &credential.data
+								{
+									// The full endpoint URL lives in cloud_config; the identifier is only host[:port]
+									let Some(endpoint) = cloud_config
+										.as_ref()
+										.and_then(|c| c.get("endpoint"))
+										.and_then(|e| e.as_str())
+									else {
+										warn!("No endpoint for WebDAV volume {}", fingerprint.0);
+										continue;
+									};
+
+									let root = cloud_config
+										.as_ref()
+										.and_then(|c| c.get("root"))
+										.and_then(|r| r.as_str())
+										.map(String::from);
+
+									crate::volume::CloudBackend::new_webdav(
+										endpoint,
+										Some(username.clone()).filter(|u| !u.is_empty()),
+										Some(password.clone()).filter(|p| !p.is_empty()),
+										root,
+									).await
+								} else {
+									warn!("Invalid credential type for WebDAV volume {}", fingerprint.0);
+									continue;
+								}
+							}
 							_ => {
 								warn!("Unsupported cloud service type {:?} for volume {}", credential.service, fingerprint.0);
 								continue;
@@ -0,0 +1,147 @@
+//! Integration tests for the undo journal
+//!
+//! Verifies that reversible actions dispatched through the API are recorded per
+//! session, can be undone and redone, and that an undo refuses to touch the
+//! filesystem once it has drifted from what the action left behind.
+
+mod helpers;
+
+use helpers::*;
+use sd_core::{
+	domain::addressing::SdPath,
+	infra::{
+		action::journal::SessionKey,
+		api::{ApiDispatcher, SessionContext},
+	},
+	ops::{
+		action::{ActionRedoAction, ActionRedoInput, ActionUndoAction, ActionUndoInput},
+		files::{CreateFolderAction, CreateFolderInput, FileRenameAction, FileRenameInput},
+	},
+};
+use std::time::Duration;
+
+fn session(harness: &IndexingHarness) -> SessionContext {
+	let device_id = sd_core::device::get_current_device_id();
+	let device_name = sd_core::device::get_current_device_slug();
+	SessionContext::device_session(device_id, device_name).with_library(harness.library.id())
+}
+
+/// Wait until a session's undo stack reaches `depth`, which for job-backed actions only
+/// happens after the job has completed
+async fn wait_for_undo_depth(harness: &IndexingHarness, depth: usize) -> anyhow::Result<()> {
+	let key = SessionKey::from(&session(harness));
+	let journal = &harness.core.context.undo_journal;
+	for _ in 0..100 {
+		if journal.depth(harness.library.id(), &key).0 == depth {
+			return Ok(());
+		}
+		tokio::time::sleep(Duration::from_millis(50)).await;
+	}
+	anyhow::bail!("Undo stack never reached depth {}", depth)
+}
+
+#[tokio::test]
+async fn test_undo_redo_create_folder() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("action_undo_create_folder")
+		.disable_watcher()
+		.build()
+		.await?;
+	let test_location = harness.create_test_location("undo").await?;
+	let dispatcher = ApiDispatcher::permissive(harness.core.context.clone());
+	let folder = test_location.path().join("Projects");
+
+	dispatcher
+		.execute_library_action::<CreateFolderAction>(
+			CreateFolderInput::new(SdPath::local(test_location.path()), "Projects"),
+			session(&harness),
+		)
+		.await?;
+	assert!(folder.is_dir(), "Folder should be created");
+
+	let undone = dispatcher
+		.execute_library_action::<ActionUndoAction>(ActionUndoInput::default(), session(&harness))
+		.await?;
+	assert_eq!(undone.action_kind, "files.createFolder");
+	assert_eq!((undone.undo_depth, undone.redo_depth), (0, 1));
+	assert!(!folder.exists(), "Undo should remove the created folder");
+
+	let redone = dispatcher
+		.execute_library_action::<ActionRedoAction>(
+			ActionRedoInput {
+				action_id: Some(undone.action_id),
+			},
+			session(&harness),
+		)
+		.await?;
+	assert_eq!((redone.undo_depth, redone.redo_depth), (1, 0));
+	assert!(folder.is_dir(), "Redo should recreate the folder");
+
+	// The folder gained a file since the action ran, so undoing must not remove it
+	let note = folder.join("notes.txt");
+	std::fs::write(&note, "keep me")?;
+	let refused = dispatcher
+		.execute_library_action::<ActionUndoAction>(ActionUndoInput::default(), session(&harness))
+		.await;
+	assert!(
+		refused.is_err(),
+		"Undo should refuse after the folder changed"
+	);
+	assert!(
+		note.exists(),
+		"A refused undo must leave the filesystem alone"
+	);
+
+	// A refused undo stays on the stack and succeeds once the drift is gone
+	std::fs::remove_file(&note)?;
+	dispatcher
+		.execute_library_action::<ActionUndoAction>(ActionUndoInput::default(), session(&harness))
+		.await?;
+	assert!(!folder.exists());
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_undo_redo_rename() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("action_undo_rename")
+		.disable_watcher()
+		.build()
+		.await?;
+	let test_location = harness.create_test_location("undo").await?;
+	test_location.write_file("draft.txt", "first draft").await?;
+	let dispatcher = ApiDispatcher::permissive(harness.core.context.clone());
+	let original = test_location.path().join("draft.txt");
+	let renamed = test_location.path().join("final.txt");
+
+	let receipt = dispatcher
+		.execute_library_action::<FileRenameAction>(
+			FileRenameInput::new(SdPath::local(&original), "final.txt"),
+			session(&harness),
+		)
+		.await?;
+	if let Some(handle) = harness.library.jobs().get_job(receipt.id).await {
+		handle.wait().await?;
+	}
+	assert!(renamed.is_file(), "File should be renamed");
+
+	// The rename is only undoable once its job has completed
+	wait_for_undo_depth(&harness, 1).await?;
+
+	let undone = dispatcher
+		.execute_library_action::<ActionUndoAction>(ActionUndoInput::default(), session(&harness))
+		.await?;
+	assert_eq!(undone.action_kind, "files.rename");
+	assert!(original.is_file(), "Undo should restore the original name");
+	assert!(!renamed.exists());
+	assert_eq!(std::fs::read_to_string(&original)?, "first draft");
+
+	dispatcher
+		.execute_library_action::<ActionRedoAction>(ActionRedoInput::default(), session(&harness))
+		.await?;
+	assert!(renamed.is_file(), "Redo should rename the file again");
+	assert!(!original.exists());
+
+	harness.shutdown().await?;
+	Ok(())
+}
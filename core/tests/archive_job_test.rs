@@ -0,0 +1,381 @@
+//! Integration tests for the compress and extract jobs
+//!
+//! Verifies that the jobs hand what they create to the location responder, resolve
+//! conflicts with existing files and folders, and pick up from their checkpoints after
+//! an interruption without redoing finished work.
+
+mod helpers;
+
+use helpers::*;
+use sd_core::{
+	domain::addressing::{SdPath, SdPathBatch},
+	infra::job::output::JobOutput,
+	location::IndexMode,
+	ops::files::{
+		archive::{append_source, load_index, CompressFormat},
+		compress::FileCompressJob,
+		copy::action::FileConflictResolution,
+		extract::FileExtractJob,
+	},
+};
+use std::{
+	io::Write,
+	path::{Path, PathBuf},
+};
+
+async fn write(path: &Path, contents: &str) -> anyhow::Result<()> {
+	if let Some(parent) = path.parent() {
+		tokio::fs::create_dir_all(parent).await?;
+	}
+	tokio::fs::write(path, contents).await?;
+	Ok(())
+}
+
+/// A folder and a file to compress, under `root`
+async fn write_sources(root: &Path) -> anyhow::Result<(PathBuf, PathBuf)> {
+	let folder = root.join("photos");
+	write(&folder.join("2024/beach.jpg"), "sand").await?;
+	write(&folder.join("cover.png"), "cover").await?;
+	let file = root.join("notes.txt");
+	write(&file, "hello").await?;
+	Ok((folder, file))
+}
+
+/// Build a job as the job system would restore it from a checkpoint
+fn restore<T: serde::Serialize + serde::de::DeserializeOwned>(
+	job: T,
+	state: serde_json::Value,
+) -> anyhow::Result<T> {
+	let mut value = serde_json::to_value(job)?;
+	for (key, field) in state.as_object().expect("state is an object") {
+		value[key] = field.clone();
+	}
+	Ok(serde_json::from_value(value)?)
+}
+
+#[tokio::test]
+async fn test_created_archives_and_folders_are_indexed() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("archive_job_indexing")
+		.build()
+		.await?;
+	let test_location = harness.create_test_location("archives").await?;
+	let (folder, file) = write_sources(test_location.path()).await?;
+	let location = test_location
+		.index("Archive Jobs", IndexMode::Shallow)
+		.await?;
+
+	let archive = test_location.path().join("backup.tar.zst");
+	harness
+		.library
+		.jobs()
+		.dispatch(FileCompressJob::new(
+			SdPathBatch::new(vec![SdPath::local(&folder), SdPath::local(&file)]),
+			SdPath::local(&archive),
+			CompressFormat::TarZst,
+		))
+		.await?
+		.wait()
+		.await?;
+	assert!(!test_location.path().join("backup.tar.zst.partial").exists());
+
+	// Indexed by the job itself, before the watcher has caught up
+	let entries = location.get_all_entries().await?;
+	assert!(entries
+		.iter()
+		.any(|entry| entry.kind == 0 && entry.name.starts_with("backup")));
+
+	let extracted = test_location.path().join("restored");
+	tokio::fs::create_dir_all(&extracted).await?;
+	harness
+		.library
+		.jobs()
+		.dispatch(FileExtractJob::new(
+			SdPathBatch::new(vec![SdPath::local(&archive)]),
+			SdPath::local(&extracted),
+		))
+		.await?
+		.wait()
+		.await?;
+	assert_eq!(
+		tokio::fs::read_to_string(extracted.join("backup/photos/2024/beach.jpg")).await?,
+		"sand"
+	);
+
+	let entries = location.get_all_entries().await?;
+	assert!(entries
+		.iter()
+		.any(|entry| entry.kind == 1 && entry.name == "backup"));
+	assert!(entries
+		.iter()
+		.any(|entry| entry.kind == 0 && entry.name == "beach"));
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_extract_conflicts() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("archive_job_conflicts")
+		.disable_watcher()
+		.build()
+		.await?;
+	let root = harness.temp_path().to_path_buf();
+	let (folder, file) = write_sources(&root.join("sources")).await?;
+	let archive = root.join("photos.zip");
+	let len = append_source(&archive, CompressFormat::Zip, 0, &folder, "photos", |_| {})?;
+	append_source(
+		&archive,
+		CompressFormat::Zip,
+		len,
+		&file,
+		"notes.txt",
+		|_| {},
+	)?;
+
+	// A folder with the archive's name is already in the destination
+	let destination = root.join("out");
+	let existing = destination.join("photos");
+	write(&existing.join("stray.txt"), "keep?").await?;
+
+	let extract = |resolution| {
+		FileExtractJob::new(
+			SdPathBatch::new(vec![SdPath::local(&archive)]),
+			SdPath::local(&destination),
+		)
+		.with_conflict_resolution(resolution)
+	};
+
+	// Without a resolution the job fails and leaves the folder alone
+	let result = harness
+		.library
+		.jobs()
+		.dispatch(extract(None))
+		.await?
+		.wait()
+		.await;
+	assert!(result.is_err());
+	assert!(existing.join("stray.txt").exists());
+	assert!(!existing.join("notes.txt").exists());
+
+	let skipped = harness
+		.library
+		.jobs()
+		.dispatch(extract(Some(FileConflictResolution::Skip)))
+		.await?
+		.wait()
+		.await?;
+	assert!(matches!(
+		skipped,
+		JobOutput::FileExtract {
+			extracted_count: 0,
+			skipped_count: 1,
+			..
+		}
+	));
+	assert!(!existing.join("notes.txt").exists());
+
+	harness
+		.library
+		.jobs()
+		.dispatch(extract(Some(FileConflictResolution::AutoModifyName)))
+		.await?
+		.wait()
+		.await?;
+	assert_eq!(
+		tokio::fs::read_to_string(destination.join("photos (1)/notes.txt")).await?,
+		"hello"
+	);
+	assert!(!existing.join("notes.txt").exists());
+
+	// Overwriting replaces the folder instead of merging into it
+	harness
+		.library
+		.jobs()
+		.dispatch(extract(Some(FileConflictResolution::Overwrite)))
+		.await?
+		.wait()
+		.await?;
+	assert!(!existing.join("stray.txt").exists());
+	assert_eq!(
+		tokio::fs::read_to_string(existing.join("photos/cover.png")).await?,
+		"cover"
+	);
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_compress_conflicts() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("archive_job_compress_conflicts")
+		.disable_watcher()
+		.build()
+		.await?;
+	let root = harness.temp_path().to_path_buf();
+	let (_, file) = write_sources(&root).await?;
+	let archive = root.join("notes.zip");
+	write(&archive, "not an archive yet").await?;
+
+	let compress = |resolution| {
+		FileCompressJob::new(
+			SdPathBatch::new(vec![SdPath::local(&file)]),
+			SdPath::local(&archive),
+			CompressFormat::Zip,
+		)
+		.with_conflict_resolution(resolution)
+	};
+
+	let skipped = harness
+		.library
+		.jobs()
+		.dispatch(compress(Some(FileConflictResolution::Skip)))
+		.await?
+		.wait()
+		.await?;
+	assert!(matches!(
+		skipped,
+		JobOutput::FileCompress {
+			source_count: 0,
+			..
+		}
+	));
+	assert_eq!(
+		tokio::fs::read_to_string(&archive).await?,
+		"not an archive yet"
+	);
+
+	harness
+		.library
+		.jobs()
+		.dispatch(compress(Some(FileConflictResolution::AutoModifyName)))
+		.await?
+		.wait()
+		.await?;
+	assert!(load_index(&root.join("notes (1).zip"))?
+		.get("notes.txt")
+		.is_some());
+
+	harness
+		.library
+		.jobs()
+		.dispatch(compress(Some(FileConflictResolution::Overwrite)))
+		.await?
+		.wait()
+		.await?;
+	assert!(load_index(&archive)?.get("notes.txt").is_some());
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_compress_resumes_after_the_last_checkpoint() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("archive_job_compress_resume")
+		.disable_watcher()
+		.build()
+		.await?;
+	let root = harness.temp_path().to_path_buf();
+	let (folder, file) = write_sources(&root).await?;
+	let archive = root.join("backup.tar.zst");
+	let partial = root.join("backup.tar.zst.partial");
+
+	// The first source was checkpointed, then the job stopped partway through the second
+	let written_len = append_source(
+		&partial,
+		CompressFormat::TarZst,
+		0,
+		&file,
+		"notes.txt",
+		|_| {},
+	)?;
+	let mut torn = std::fs::OpenOptions::new().append(true).open(&partial)?;
+	torn.write_all(&[0xAB; 300])?;
+	drop(torn);
+
+	let job = restore(
+		FileCompressJob::new(
+			SdPathBatch::new(vec![SdPath::local(&file), SdPath::local(&folder)]),
+			SdPath::local(&archive),
+			CompressFormat::TarZst,
+		),
+		serde_json::json!({
+			"completed_indices": [0],
+			"target": archive,
+			"written_len": written_len,
+			"bytes_compressed": 5,
+		}),
+	)?;
+	let output = harness.library.jobs().dispatch(job).await?.wait().await?;
+	assert!(matches!(
+		output,
+		JobOutput::FileCompress {
+			source_count: 2,
+			total_bytes: 14,
+			..
+		}
+	));
+
+	assert!(!partial.exists());
+	let index = load_index(&archive)?;
+	assert_eq!(index.get("notes.txt").unwrap().size, 5);
+	assert_eq!(index.get("photos/2024/beach.jpg").unwrap().size, 4);
+	assert_eq!(index.size_of(""), 14);
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_extract_resumes_after_the_last_checkpoint() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("archive_job_extract_resume")
+		.disable_watcher()
+		.build()
+		.await?;
+	let root = harness.temp_path().to_path_buf();
+	let (folder, file) = write_sources(&root.join("sources")).await?;
+	let first = root.join("first.zip");
+	append_source(&first, CompressFormat::Zip, 0, &file, "notes.txt", |_| {})?;
+	let second = root.join("second.zip");
+	append_source(&second, CompressFormat::Zip, 0, &folder, "photos", |_| {})?;
+
+	// The first archive was checkpointed, then the second was cut off partway through
+	let destination = root.join("out");
+	let target = destination.join("second");
+	write(&target.join("photos/cover.png"), "co").await?;
+
+	let job = restore(
+		FileExtractJob::new(
+			SdPathBatch::new(vec![SdPath::local(&first), SdPath::local(&second)]),
+			SdPath::local(&destination),
+		),
+		serde_json::json!({
+			"completed_indices": [0],
+			"current_target": target,
+		}),
+	)?;
+	let output = harness.library.jobs().dispatch(job).await?.wait().await?;
+	assert!(matches!(
+		output,
+		JobOutput::FileExtract {
+			extracted_count: 1,
+			failed_count: 0,
+			..
+		}
+	));
+
+	// The finished archive isn't extracted again, and the cut off one goes into the same
+	// folder rather than a renamed one
+	assert!(!destination.join("first").exists());
+	assert!(!destination.join("second (1)").exists());
+	assert_eq!(
+		tokio::fs::read_to_string(target.join("photos/cover.png")).await?,
+		"cover"
+	);
+	assert_eq!(
+		tokio::fs::read_to_string(target.join("photos/2024/beach.jpg")).await?,
+		"sand"
+	);
+
+	harness.shutdown().await?;
+	Ok(())
+}
@@ -0,0 +1,152 @@
+//! Integration tests for browsing archives in place
+//!
+//! Verifies that a zip archive can be listed folder by folder through the directory
+//! listing query, and that `FileCopyJob` extracts files and folders out of it.
+
+mod helpers;
+
+use helpers::*;
+use sd_core::{
+	device::{get_current_device_id, get_current_device_slug},
+	domain::{
+		addressing::{SdPath, SdPathBatch},
+		file::EntryKind,
+	},
+	infra::{api::SessionContext, query::LibraryQuery},
+	ops::files::{
+		copy::job::FileCopyJob,
+		query::directory_listing::{DirectoryListingInput, DirectoryListingQuery, DirectorySortBy},
+	},
+};
+use std::{io::Write, path::Path};
+
+fn write_zip(path: &Path, files: &[(&str, &[u8])]) -> anyhow::Result<()> {
+	let mut zip = zip::ZipWriter::new(std::fs::File::create(path)?);
+	for (name, data) in files {
+		zip.start_file(*name, zip::write::SimpleFileOptions::default())?;
+		zip.write_all(data)?;
+	}
+	zip.finish()?;
+	Ok(())
+}
+
+async fn list(
+	harness: &IndexingHarness,
+	path: SdPath,
+) -> anyhow::Result<Vec<(String, EntryKind, u64)>> {
+	let session =
+		SessionContext::device_session(get_current_device_id(), get_current_device_slug())
+			.with_library(harness.library.id());
+	let output = DirectoryListingQuery::from_input(DirectoryListingInput {
+		path,
+		folders_first: Some(true),
+		limit: None,
+		include_hidden: Some(false),
+		sort_by: DirectorySortBy::Name,
+	})?
+	.execute(harness.core.context.clone(), session)
+	.await?;
+
+	Ok(output
+		.files
+		.into_iter()
+		.map(|file| {
+			(
+				file.sd_path.file_name().unwrap_or_default().to_string(),
+				file.kind,
+				file.size,
+			)
+		})
+		.collect())
+}
+
+#[tokio::test]
+async fn test_archive_directory_listing() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("archive_listing")
+		.disable_watcher()
+		.build()
+		.await?;
+	let archive = harness.temp_path().join("photos.zip");
+	write_zip(
+		&archive,
+		&[
+			("2024/summer/beach.jpg", b"beach"),
+			("2024/notes.txt", b"hello"),
+			("readme.md", b"readme"),
+			(".hidden", b"secret"),
+		],
+	)?;
+
+	let root = SdPath::archive(get_current_device_slug(), &archive, "");
+	assert_eq!(
+		list(&harness, root.clone()).await?,
+		vec![
+			("2024".to_string(), EntryKind::Directory, 0),
+			("readme.md".to_string(), EntryKind::File, 6),
+		]
+	);
+
+	let year = list(&harness, root.join("2024")).await?;
+	assert_eq!(
+		year,
+		vec![
+			("summer".to_string(), EntryKind::Directory, 0),
+			("notes.txt".to_string(), EntryKind::File, 5),
+		]
+	);
+
+	// The frontend's "local" placeholder browses the same archive
+	let placeholder = SdPath::archive("local".to_string(), &archive, "2024");
+	assert!(placeholder.is_local());
+	assert_eq!(list(&harness, placeholder).await?, year);
+
+	// Listing a file or a folder the archive doesn't have fails
+	assert!(list(&harness, root.join("readme.md")).await.is_err());
+	assert!(list(&harness, root.join("2023")).await.is_err());
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_copy_out_of_archive() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("archive_copy_out")
+		.disable_watcher()
+		.build()
+		.await?;
+	let archive = harness.temp_path().join("photos.zip");
+	write_zip(
+		&archive,
+		&[
+			("2024/summer/beach.jpg", b"beach"),
+			("2024/summer/dunes/sand.jpg", b"sand"),
+			("2024/notes.txt", b"hello"),
+		],
+	)?;
+	let dest = harness.temp_path().join("extracted");
+	tokio::fs::create_dir_all(&dest).await?;
+
+	let root = SdPath::archive(get_current_device_slug(), &archive, "");
+	let job = FileCopyJob::new(
+		SdPathBatch::new(vec![root.join("2024/notes.txt"), root.join("2024/summer")]),
+		SdPath::local(&dest),
+	);
+	harness.library.jobs().dispatch(job).await?.wait().await?;
+
+	assert_eq!(tokio::fs::read(dest.join("notes.txt")).await?, b"hello");
+	assert_eq!(
+		tokio::fs::read(dest.join("summer/beach.jpg")).await?,
+		b"beach"
+	);
+	assert_eq!(
+		tokio::fs::read(dest.join("summer/dunes/sand.jpg")).await?,
+		b"sand"
+	);
+
+	// The archive itself is left as it was
+	let listed = list(&harness, root.join("2024")).await?;
+	assert_eq!(listed.len(), 2);
+
+	harness.shutdown().await?;
+	Ok(())
+}
@@ -0,0 +1,193 @@
+//! Auto-tag rules integration test
+//!
+//! Verifies that rules tag matching files during indexing, that they can be applied
+//! retroactively by job, and that deleting a rule removes only the tags it applied.
+
+mod helpers;
+
+use helpers::*;
+use sd_core::{
+	infra::{
+		action::LibraryAction,
+		db::entities::{entry, tag, user_metadata, user_metadata_tag},
+		job::types::JobId,
+	},
+	location::IndexMode,
+	ops::tags::{
+		apply::{action::ApplyTagsAction, input::ApplyTagsInput},
+		create::{action::CreateTagAction, input::CreateTagInput},
+		rules::{
+			AutoTagCondition, CreateAutoTagRuleAction, CreateAutoTagRuleInput,
+			DeleteAutoTagRuleAction, DeleteAutoTagRuleInput,
+		},
+	},
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use tokio::time::{timeout, Duration};
+use uuid::Uuid;
+
+/// Names of the files carrying `tag_uuid`, with the source each was applied by
+async fn tagged_files(
+	db: &DatabaseConnection,
+	tag_uuid: Uuid,
+) -> anyhow::Result<Vec<(String, String)>> {
+	let tag = tag::Entity::find()
+		.filter(tag::Column::Uuid.eq(tag_uuid))
+		.one(db)
+		.await?
+		.expect("tag should exist");
+
+	let mut files = Vec::new();
+	let applications = user_metadata_tag::Entity::find()
+		.filter(user_metadata_tag::Column::TagId.eq(tag.id))
+		.find_also_related(user_metadata::Entity)
+		.all(db)
+		.await?;
+	for (application, metadata) in applications {
+		let Some(entry_uuid) = metadata.and_then(|m| m.entry_uuid) else {
+			continue;
+		};
+		let entry = entry::Entity::find()
+			.filter(entry::Column::Uuid.eq(entry_uuid))
+			.one(db)
+			.await?
+			.expect("tagged entry should exist");
+		files.push((entry.name, application.source));
+	}
+	files.sort();
+	Ok(files)
+}
+
+#[tokio::test]
+async fn test_auto_tag_rules() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("auto_tag_rules")
+		.build()
+		.await?;
+	let db = harness.library.db().conn();
+	let action_manager = harness.core.context.get_action_manager().await.unwrap();
+	let library_id = harness.library.id();
+
+	let screenshots = action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateTagAction::from_input(CreateTagInput::simple("Screenshots".to_string())).unwrap(),
+		)
+		.await?
+		.tag_id;
+	let notes = action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateTagAction::from_input(CreateTagInput::simple("Notes".to_string())).unwrap(),
+		)
+		.await?
+		.tag_id;
+
+	// A rule that exists before indexing is applied by the indexer
+	action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateAutoTagRuleAction::from_input(CreateAutoTagRuleInput {
+				name: "Screenshots".to_string(),
+				tag_id: screenshots,
+				conditions: vec![
+					AutoTagCondition::PathGlob {
+						pattern: "**/Screenshots/**".to_string(),
+					},
+					AutoTagCondition::Extension {
+						extensions: vec!["png".to_string()],
+					},
+				],
+				match_all: true,
+				apply_to_existing: false,
+			})
+			.unwrap(),
+		)
+		.await?;
+
+	let test_location = harness.create_test_location("auto_tag").await?;
+	test_location
+		.write_file("Screenshots/shot1.png", "png one")
+		.await?;
+	test_location
+		.write_file("Screenshots/readme.txt", "not an image")
+		.await?;
+	test_location
+		.write_file("Pictures/photo.png", "png two")
+		.await?;
+	test_location.write_file("notes/todo.txt", "todo").await?;
+	test_location
+		.index("Auto Tag Location", IndexMode::Deep)
+		.await?;
+
+	assert_eq!(
+		tagged_files(db, screenshots).await?,
+		vec![("shot1".to_string(), "rule".to_string())]
+	);
+
+	// The user has already tagged one of the files the next rule matches
+	let todo_entry = entry::Entity::find()
+		.filter(entry::Column::Name.eq("todo"))
+		.one(db)
+		.await?
+		.expect("todo.txt should be indexed");
+	action_manager
+		.dispatch_library(
+			Some(library_id),
+			ApplyTagsAction::from_input(ApplyTagsInput::user_tags_entry(
+				vec![todo_entry.id],
+				vec![notes],
+			))
+			.unwrap(),
+		)
+		.await?;
+
+	// A rule created afterwards is applied retroactively by job
+	let created = action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateAutoTagRuleAction::from_input(CreateAutoTagRuleInput {
+				name: "Text files".to_string(),
+				tag_id: notes,
+				conditions: vec![AutoTagCondition::Extension {
+					extensions: vec!["txt".to_string()],
+				}],
+				match_all: true,
+				apply_to_existing: true,
+			})
+			.unwrap(),
+		)
+		.await?;
+
+	if let Some(handle) = harness
+		.library
+		.jobs()
+		.get_job(JobId(created.job_id.expect("job should be dispatched")))
+		.await
+	{
+		timeout(Duration::from_secs(30), handle.wait()).await??;
+	}
+
+	let notes_files = tagged_files(db, notes).await?;
+	assert!(notes_files.contains(&("readme".to_string(), "rule".to_string())));
+	assert!(notes_files.contains(&("todo".to_string(), "user".to_string())));
+
+	// Deleting the rule removes what it applied and leaves the user's tag
+	let deleted = action_manager
+		.dispatch_library(
+			Some(library_id),
+			DeleteAutoTagRuleAction::from_input(DeleteAutoTagRuleInput {
+				rule_id: created.rule_id,
+				remove_applied_tags: true,
+			})
+			.unwrap(),
+		)
+		.await?;
+	assert_eq!(deleted.tags_removed, 1);
+	assert_eq!(
+		tagged_files(db, notes).await?,
+		vec![("todo".to_string(), "user".to_string())]
+	);
+
+	harness.shutdown().await?;
+	Ok(())
+}
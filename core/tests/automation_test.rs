@@ -0,0 +1,299 @@
+//! Event automation integration test
+//!
+//! Verifies that automation rules are validated, that a matching event POSTs to a
+//! webhook with templates rendered and its headers redacted from listings, that
+//! undeliverable webhooks reach the dead-letter log, and that a rule isn't fired again
+//! by the events its own action causes.
+
+mod helpers;
+
+use helpers::*;
+use sd_core::{
+	infra::{action::LibraryAction, api::SessionContext, event::Event, query::LibraryQuery},
+	ops::{
+		automation::{
+			rule::REDACTED, AutomationAction, CreateAutomationRuleAction,
+			CreateAutomationRuleInput, DeleteAutomationRuleAction, DeleteAutomationRuleInput,
+			EventFieldFilter, ListAutomationDeadLettersInput, ListAutomationDeadLettersQuery,
+			ListAutomationRulesQuery,
+		},
+		tags::rules::{AutoTagJob, AutoTagJobConfig},
+	},
+};
+use serde_json::json;
+use std::collections::HashMap;
+use tokio::{
+	io::{AsyncReadExt, AsyncWriteExt},
+	net::TcpListener,
+	sync::mpsc,
+	time::{sleep, timeout, Duration},
+};
+
+fn session(harness: &IndexingHarness) -> SessionContext {
+	let device_id = sd_core::device::get_current_device_id();
+	let device_name = sd_core::device::get_current_device_slug();
+	SessionContext::device_session(device_id, device_name).with_library(harness.library.id())
+}
+
+fn webhook_rule(name: &str, event_type: &str, url: String) -> CreateAutomationRuleInput {
+	CreateAutomationRuleInput {
+		name: name.to_string(),
+		event_type: "Custom".to_string(),
+		filters: vec![EventFieldFilter::Equals {
+			field: "data.event_type".to_string(),
+			value: json!(event_type),
+		}],
+		action: AutomationAction::Webhook {
+			url,
+			headers: HashMap::from([("Authorization".to_string(), "Bearer secret".to_string())]),
+			max_attempts: 1,
+			allow_remote: false,
+		},
+		cooldown_seconds: 0,
+	}
+}
+
+/// Accepts HTTP requests and forwards each request's path and JSON body
+async fn webhook_receiver(
+) -> anyhow::Result<(u16, mpsc::UnboundedReceiver<(String, serde_json::Value)>)> {
+	let listener = TcpListener::bind("127.0.0.1:0").await?;
+	let port = listener.local_addr()?.port();
+	let (tx, rx) = mpsc::unbounded_channel();
+
+	tokio::spawn(async move {
+		while let Ok((mut stream, _)) = listener.accept().await {
+			let mut request = Vec::new();
+			let mut buf = [0u8; 4096];
+			loop {
+				let Ok(n) = stream.read(&mut buf).await else {
+					break;
+				};
+				if n == 0 {
+					break;
+				}
+				request.extend_from_slice(&buf[..n]);
+
+				let text = String::from_utf8_lossy(&request);
+				let Some(header_end) = text.find("\r\n\r\n") else {
+					continue;
+				};
+				let content_length = text[..header_end]
+					.lines()
+					.find_map(|line| {
+						let (name, value) = line.split_once(':')?;
+						name.eq_ignore_ascii_case("content-length")
+							.then(|| value.trim().parse::<usize>().ok())
+							.flatten()
+					})
+					.unwrap_or(0);
+				if request.len() < header_end + 4 + content_length {
+					continue;
+				}
+
+				let path = text
+					.split_whitespace()
+					.nth(1)
+					.unwrap_or_default()
+					.to_string();
+				let body = serde_json::from_slice(&request[header_end + 4..]).unwrap_or_default();
+				let _ = tx.send((path, body));
+				let _ = stream
+					.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+					.await;
+				break;
+			}
+		}
+	});
+
+	Ok((port, rx))
+}
+
+#[tokio::test]
+async fn test_automation_webhooks() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("automation_webhooks")
+		.disable_watcher()
+		.build()
+		.await?;
+	let action_manager = harness.core.context.get_action_manager().await.unwrap();
+	let library_id = harness.library.id();
+	let (port, mut deliveries) = webhook_receiver().await?;
+
+	// Progress events fire too often to be triggers, and unknown ones never fire
+	let mut noisy = webhook_rule("noisy", "x", format!("http://127.0.0.1:{}/", port));
+	noisy.event_type = "JobProgress".to_string();
+	assert!(CreateAutomationRuleAction::from_input(noisy).is_err());
+	let mut unknown = webhook_rule("unknown", "x", format!("http://127.0.0.1:{}/", port));
+	unknown.event_type = "NoSuchEvent".to_string();
+	assert!(CreateAutomationRuleAction::from_input(unknown).is_err());
+
+	// Webhooks stay on this machine unless the rule opts in to remote hosts
+	let mut remote = webhook_rule("remote", "x", "https://example.com/hook".to_string());
+	assert!(CreateAutomationRuleAction::from_input(remote.clone()).is_err());
+	if let AutomationAction::Webhook { allow_remote, .. } = &mut remote.action {
+		*allow_remote = true;
+	}
+	assert!(CreateAutomationRuleAction::from_input(remote).is_ok());
+
+	// Rules created without a cooldown get a non-zero one
+	let defaulted: CreateAutomationRuleInput = serde_json::from_value(json!({
+		"name": "defaulted",
+		"event_type": "Custom",
+		"action": { "type": "webhook", "url": format!("http://localhost:{}/", port) },
+	}))?;
+	assert!(defaulted.cooldown_seconds > 0);
+
+	let delivered = action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateAutomationRuleAction::from_input(webhook_rule(
+				"Notify on import",
+				"photo_import",
+				format!("http://127.0.0.1:{}/hooks/{{{{data.event_type}}}}", port),
+			))
+			.unwrap(),
+		)
+		.await?;
+	let failing = action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateAutomationRuleAction::from_input(webhook_rule(
+				"Unreachable",
+				"job_alert",
+				"http://127.0.0.1:1/unreachable".to_string(),
+			))
+			.unwrap(),
+		)
+		.await?;
+
+	let listed = ListAutomationRulesQuery::from_input(Default::default())?
+		.execute(harness.core.context.clone(), session(&harness))
+		.await?;
+	assert_eq!(listed.rules.len(), 2);
+	for rule in &listed.rules {
+		let AutomationAction::Webhook { headers, .. } = &rule.action else {
+			panic!("expected a webhook rule");
+		};
+		assert_eq!(headers["Authorization"], REDACTED);
+	}
+
+	// Give the automation service a moment to load the new rules
+	sleep(Duration::from_millis(500)).await;
+
+	let events = harness.library.event_bus();
+	events.emit(Event::Custom {
+		event_type: "unrelated".to_string(),
+		data: json!({}),
+	});
+	events.emit(Event::Custom {
+		event_type: "photo_import".to_string(),
+		data: json!({ "card": "EOS_DIGITAL" }),
+	});
+
+	let (path, body) = timeout(Duration::from_secs(10), deliveries.recv())
+		.await?
+		.expect("webhook should be delivered");
+	assert_eq!(path, "/hooks/photo_import");
+	assert_eq!(body["rule_id"], json!(delivered.rule_id));
+	assert_eq!(body["event"]["type"], "Custom");
+	assert_eq!(body["event"]["data"]["data"]["card"], "EOS_DIGITAL");
+
+	// The undeliverable webhook ends up in the dead-letter log
+	events.emit(Event::Custom {
+		event_type: "job_alert".to_string(),
+		data: json!({}),
+	});
+	let mut dead_letters = Vec::new();
+	for _ in 0..50 {
+		dead_letters =
+			ListAutomationDeadLettersQuery::from_input(ListAutomationDeadLettersInput {
+				rule_id: Some(failing.rule_id),
+				limit: None,
+			})?
+			.execute(harness.core.context.clone(), session(&harness))
+			.await?
+			.dead_letters;
+		if !dead_letters.is_empty() {
+			break;
+		}
+		sleep(Duration::from_millis(200)).await;
+	}
+	assert_eq!(dead_letters.len(), 1);
+	assert_eq!(dead_letters[0].attempts, 1);
+	assert_eq!(dead_letters[0].rule_name, "Unreachable");
+	assert_eq!(
+		dead_letters[0].action["headers"]["Authorization"],
+		json!(REDACTED)
+	);
+	assert!(deliveries.try_recv().is_err());
+
+	action_manager
+		.dispatch_library(
+			Some(library_id),
+			DeleteAutomationRuleAction::from_input(DeleteAutomationRuleInput {
+				rule_id: delivered.rule_id,
+			})
+			.unwrap(),
+		)
+		.await?;
+	let listed = ListAutomationRulesQuery::from_input(Default::default())?
+		.execute(harness.core.context.clone(), session(&harness))
+		.await?;
+	assert_eq!(listed.rules.len(), 1);
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_automation_ignores_its_own_events() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("automation_own_events")
+		.disable_watcher()
+		.build()
+		.await?;
+	let action_manager = harness.core.context.get_action_manager().await.unwrap();
+	let args = serde_json::to_value(AutoTagJob::new(AutoTagJobConfig::default()))?;
+
+	// Without self-trigger suppression this rule would keep re-running itself
+	action_manager
+		.dispatch_library(
+			Some(harness.library.id()),
+			CreateAutomationRuleAction::from_input(CreateAutomationRuleInput {
+				name: "Re-tag after tagging".to_string(),
+				event_type: "JobCompleted".to_string(),
+				filters: vec![EventFieldFilter::Equals {
+					field: "data.job_type".to_string(),
+					value: json!("auto_tag"),
+				}],
+				action: AutomationAction::Job {
+					job_name: "auto_tag".to_string(),
+					args: args.clone(),
+				},
+				cooldown_seconds: 0,
+			})
+			.unwrap(),
+		)
+		.await?;
+	sleep(Duration::from_millis(500)).await;
+
+	let jobs = harness.library.jobs();
+	jobs.dispatch(AutoTagJob::new(AutoTagJobConfig::default()))
+		.await?
+		.wait()
+		.await?;
+
+	// The first completion fires the rule once; the job it dispatched doesn't fire it again
+	let mut auto_tag_runs = 0;
+	for _ in 0..25 {
+		sleep(Duration::from_millis(200)).await;
+		auto_tag_runs = jobs
+			.list_jobs(None)
+			.await?
+			.iter()
+			.filter(|job| job.name == "auto_tag")
+			.count();
+	}
+	assert_eq!(auto_tag_runs, 2);
+
+	harness.shutdown().await?;
+	Ok(())
+}
@@ -78,6 +78,7 @@ async fn test_copy_with_persistent_index() -> anyhow::Result<()> {
 		preserve_timestamps: true,
 		delete_after_copy: false,
 		move_mode: None,
+		bandwidth_limit_mbps: None,
 	});
 
 	tracing::info!("Dispatching copy job");
@@ -193,6 +194,7 @@ async fn test_copy_with_ephemeral_index() -> anyhow::Result<()> {
 		preserve_timestamps: true,
 		delete_after_copy: false,
 		move_mode: None,
+		bandwidth_limit_mbps: None,
 	});
 
 	tracing::info!("Dispatching ephemeral copy job");
@@ -266,6 +268,7 @@ async fn test_copy_action_construction() {
 		preserve_timestamps: true,
 		delete_after_copy: false,
 		move_mode: None,
+		bandwidth_limit_mbps: None,
 	});
 
 	assert_eq!(copy_job.sources.paths.len(), 2);
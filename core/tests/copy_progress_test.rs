@@ -171,6 +171,7 @@ async fn test_copy_progress_with_metadata_tracking() {
 			preserve_timestamps: true,
 			delete_after_copy: false,
 			move_mode: None,
+			bandwidth_limit_mbps: None,
 			copy_method: CopyMethod::Streaming,
 		},
 		on_conflict: None,
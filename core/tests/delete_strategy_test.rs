@@ -55,7 +55,7 @@ async fn test_local_delete_strategy_permanent() {
 	assert!(test_file2.exists());
 
 	// Execute deletion using LocalDeleteStrategy
-	let strategy = LocalDeleteStrategy;
+	let strategy = LocalDeleteStrategy::default();
 	let result1 = strategy.permanent_delete(&test_file1).await;
 	let result2 = strategy.permanent_delete(&test_file2).await;
 
@@ -83,7 +83,7 @@ async fn test_local_delete_strategy_trash() {
 	assert!(test_file.exists());
 
 	// Execute deletion using trash mode
-	let strategy = LocalDeleteStrategy;
+	let strategy = LocalDeleteStrategy::default();
 	let result = strategy.move_to_trash(&test_file).await;
 
 	// Verify result - print error if it fails
@@ -117,7 +117,7 @@ async fn test_local_delete_strategy_directory() {
 	assert!(test_dir.join("subdir").join("file3.txt").exists());
 
 	// Execute deletion
-	let strategy = LocalDeleteStrategy;
+	let strategy = LocalDeleteStrategy::default();
 	let result = strategy.permanent_delete(&test_dir).await;
 
 	// Verify result
@@ -171,7 +171,7 @@ async fn test_delete_modes_all_types() {
 	let temp_dir = TempDir::new().unwrap();
 	let test_root = temp_dir.path();
 
-	let strategy = LocalDeleteStrategy;
+	let strategy = LocalDeleteStrategy::default();
 
 	// Test 1: Permanent delete
 	let perm_file = test_root.join("permanent.txt");
@@ -202,10 +202,38 @@ async fn test_delete_modes_all_types() {
 	println!("test_delete_modes_all_types passed!");
 }
 
+#[cfg(all(unix, not(target_os = "macos")))]
+#[tokio::test]
+async fn test_local_delete_strategy_trash_follows_freedesktop_spec() {
+	use sd_core::ops::files::trash::freedesktop::FreedesktopTrash;
+
+	let temp_dir = TempDir::new().unwrap();
+	let trash_root = temp_dir.path().join("xdg-data").join("Trash");
+
+	let test_file = temp_dir.path().join("docs").join("notes.md");
+	create_test_file(&test_file, "# Notes").await.unwrap();
+
+	let strategy = LocalDeleteStrategy::with_trash_root(&trash_root);
+	let trash_path = strategy.trash(&test_file).await.unwrap();
+	assert!(!test_file.exists());
+	assert_eq!(trash_path, trash_root.join("files/notes.md"));
+
+	// The .trashinfo records the original location
+	let trash = FreedesktopTrash::at(&trash_root);
+	let trash_name = trash_path.file_name().unwrap();
+	let info = trash.read_info(trash_name).await.unwrap();
+	assert_eq!(info.original_path, test_file);
+
+	// Restoring moves the file back and drops the .trashinfo
+	trash.restore(trash_name, &test_file).await.unwrap();
+	assert_eq!(fs::read_to_string(&test_file).await.unwrap(), "# Notes");
+	assert!(!trash_root.join("info/notes.md.trashinfo").exists());
+}
+
 #[tokio::test]
 async fn test_strategy_error_handling() {
 	// Test that strategies properly handle and report errors
-	let strategy = LocalDeleteStrategy;
+	let strategy = LocalDeleteStrategy::default();
 
 	// Try to delete a non-existent file
 	let nonexistent = PathBuf::from("/tmp/definitely_does_not_exist_12345.txt");
@@ -15,10 +15,10 @@
 //! ```
 
 use sd_core::{
-	infra::db::entities::{entry, sync_conduit},
+	infra::db::entities::{directory_paths, entry, sync_conduit, sync_generation},
 	Core,
 };
-use sea_orm::{ActiveModelTrait, Set};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
 use std::sync::Arc;
 use tempfile::TempDir;
 use tokio::fs;
@@ -65,6 +65,7 @@ impl FileSyncTestSetup {
 			},
 			logging: sd_core::config::LoggingConfig::default(),
 			proxy_pairing: sd_core::config::app_config::ProxyPairingConfig::default(),
+			file_transfer: sd_core::config::app_config::FileTransferConfig::default(),
 		};
 		config.save()?;
 
@@ -535,3 +536,118 @@ async fn test_generation_tracking() {
 	println!("✓ Generation tracking working correctly");
 	println!("  Generation increments on each sync: 0 -> 1 -> 2");
 }
+
+#[tokio::test]
+async fn test_backup_keeps_prior_versions() {
+	let setup = FileSyncTestSetup::new().await.unwrap();
+	let data_dir = TempDir::new().unwrap();
+	let source_dir = data_dir.path().join("source");
+	let target_dir = data_dir.path().join("target");
+
+	// Source has a newer notes.txt, target still has the old one and a file since removed
+	let source = setup.create_entry("source", 1, None, 0).await.unwrap();
+	setup
+		.create_entry("notes.txt", 0, Some(source.id), 3)
+		.await
+		.unwrap();
+	let target = setup.create_entry("target", 1, None, 0).await.unwrap();
+	setup
+		.create_entry("notes.txt", 0, Some(target.id), 9)
+		.await
+		.unwrap();
+	setup
+		.create_entry("removed.txt", 0, Some(target.id), 7)
+		.await
+		.unwrap();
+
+	create_test_file(&source_dir.join("notes.txt"), "new")
+		.await
+		.unwrap();
+	create_test_file(&target_dir.join("notes.txt"), "old notes")
+		.await
+		.unwrap();
+	create_test_file(&target_dir.join("removed.txt"), "removed")
+		.await
+		.unwrap();
+
+	for (entry, path) in [(&source, &source_dir), (&target, &target_dir)] {
+		directory_paths::ActiveModel {
+			entry_id: Set(entry.id),
+			path: Set(path.to_string_lossy().to_string()),
+		}
+		.insert(setup.library.db().conn())
+		.await
+		.unwrap();
+	}
+
+	let file_sync = setup.library.file_sync_service().unwrap();
+	let conduit_manager = file_sync.conduit_manager();
+
+	let conduit = conduit_manager
+		.create_conduit(
+			source.id,
+			target.id,
+			sync_conduit::SyncMode::Backup,
+			"manual".to_string(),
+		)
+		.await
+		.unwrap();
+
+	let handle = file_sync.sync_now(conduit.id).await.unwrap();
+
+	// The kept deletion no longer needs a delete job
+	assert!(handle.source_to_target.copy_job_id.is_some());
+	assert!(handle.source_to_target.delete_job_id.is_none());
+
+	// Both prior files moved into the versions area of this generation
+	let kept = target_dir
+		.join(".sdversions")
+		.join(handle.generation.to_string());
+	assert_eq!(
+		fs::read_to_string(kept.join("notes.txt")).await.unwrap(),
+		"old notes"
+	);
+	assert_eq!(
+		fs::read_to_string(kept.join("removed.txt")).await.unwrap(),
+		"removed"
+	);
+	assert!(!target_dir.join("removed.txt").exists());
+
+	let versions = file_sync.versions().list(conduit.id, None).await.unwrap();
+	assert_eq!(versions.len(), 2);
+
+	let generation = sync_generation::Entity::find()
+		.filter(sync_generation::Column::ConduitId.eq(conduit.id))
+		.one(setup.library.db().conn())
+		.await
+		.unwrap()
+		.unwrap();
+	assert_eq!(generation.files_versioned, 2);
+
+	// Restore the old notes next to the new ones
+	let notes = file_sync
+		.versions()
+		.list(conduit.id, Some("notes.txt"))
+		.await
+		.unwrap()
+		.remove(0)
+		.0;
+	assert_eq!(notes.reason, "modified");
+
+	let restored = data_dir.path().join("restored").join("notes.txt");
+	file_sync
+		.versions()
+		.restore(&notes, Some(restored.clone()), false)
+		.await
+		.unwrap();
+	assert_eq!(fs::read_to_string(&restored).await.unwrap(), "old notes");
+
+	// Restoring over an existing file needs overwrite
+	assert!(file_sync
+		.versions()
+		.restore(&notes, Some(restored.clone()), false)
+		.await
+		.is_err());
+
+	println!("✓ Backup conduit kept prior versions");
+}
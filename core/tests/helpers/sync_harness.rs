@@ -72,6 +72,7 @@ impl TestConfigBuilder {
 				statistics_listener_enabled: false,
 			},
 			proxy_pairing: sd_core::config::app_config::ProxyPairingConfig::default(),
+			file_transfer: sd_core::config::app_config::FileTransferConfig::default(),
 		};
 
 		config.save()?;
@@ -176,26 +176,14 @@ impl MockTransport {
 						.await?;
 				}
 				SyncMessage::SharedChangeRequest {
-					library_id,
+					library_id: _,
 					since_hlc,
 					limit,
 				} => {
-					let (entries, has_more) = sync_service
+					let response = sync_service
 						.peer_sync()
-						.get_shared_changes(since_hlc, limit)
+						.shared_change_response(sender, since_hlc, limit)
 						.await?;
-					let current_state = if since_hlc.is_none() {
-						Some(sync_service.peer_sync().get_full_shared_state().await?)
-					} else {
-						None
-					};
-
-					let response = SyncMessage::SharedChangeResponse {
-						library_id,
-						entries,
-						current_state,
-						has_more,
-					};
 
 					self.send_sync_message(sender, response).await?;
 				}
@@ -250,6 +238,7 @@ impl MockTransport {
 												record_uuid: uuid,
 												change_type: ChangeType::Insert,
 												data,
+												field_clocks: None,
 											},
 											sync_service.peer_sync().db().clone(),
 										)
@@ -731,6 +720,14 @@ impl NetworkTransport for MockTransport {
 					None
 				};
 
+				// Filter by our sync policy after paging, like the protocol handler
+				let model_type = model_types.first().cloned().unwrap_or_default();
+				let policies = sync_service.peer_sync().policies();
+				let policy = policies.for_device(self.my_device_id).await?;
+				let records = policies
+					.filter_state_records(&policy, &model_type, records)
+					.await?;
+
 				SyncMessage::StateResponse {
 					library_id: request.library_id(),
 					model_type: model_types.first().cloned().unwrap_or_default(),
@@ -744,25 +741,11 @@ impl NetworkTransport for MockTransport {
 			SyncMessage::SharedChangeRequest {
 				since_hlc, limit, ..
 			} => {
-				// Query actual shared changes from target device
-				let (entries, has_more) = sync_service
+				// Query actual shared changes from target device, filtered by our sync policy
+				sync_service
 					.peer_sync()
-					.get_shared_changes(*since_hlc, *limit)
-					.await?;
-
-				// Include current state snapshot if initial backfill
-				let current_state = if since_hlc.is_none() {
-					Some(sync_service.peer_sync().get_full_shared_state().await?)
-				} else {
-					None
-				};
-
-				SyncMessage::SharedChangeResponse {
-					library_id: request.library_id(),
-					entries,
-					current_state,
-					has_more,
-				}
+					.shared_change_response(self.my_device_id, *since_hlc, *limit)
+					.await?
 			}
 			_ => {
 				return Err(anyhow::anyhow!(
@@ -0,0 +1,264 @@
+//! Job schedule integration test
+//!
+//! Verifies creating, listing and deleting schedules through the ops layer, that a
+//! due schedule dispatches its job and moves on to the next run, and that runs missed
+//! while the daemon was stopped follow each schedule's missed run policy. A schedule
+//! whose stored trigger can't be read is disabled without holding up the others.
+
+mod helpers;
+
+use chrono::Utc;
+use helpers::*;
+use sd_core::{
+	infra::{
+		action::LibraryAction,
+		api::SessionContext,
+		job::{
+			database::schedules,
+			schedule::{MissedRunPolicy, ScheduleTrigger},
+		},
+		query::LibraryQuery,
+	},
+	ops::{
+		jobs::schedule::{
+			CreateJobScheduleAction, CreateJobScheduleInput, DeleteJobScheduleAction,
+			DeleteJobScheduleInput, ListJobSchedulesQuery,
+		},
+		tags::rules::{AutoTagJob, AutoTagJobConfig},
+	},
+};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityTrait};
+use uuid::Uuid;
+
+fn session(harness: &IndexingHarness) -> SessionContext {
+	let device_id = sd_core::device::get_current_device_id();
+	let device_name = sd_core::device::get_current_device_slug();
+	SessionContext::device_session(device_id, device_name).with_library(harness.library.id())
+}
+
+fn schedule_input(job_name: &str, args: serde_json::Value) -> CreateJobScheduleInput {
+	CreateJobScheduleInput {
+		name: "Nightly auto-tag".to_string(),
+		job_name: job_name.to_string(),
+		args,
+		trigger: ScheduleTrigger::parse("interval:1h").unwrap(),
+		conditions: Vec::new(),
+		jitter_seconds: 0,
+		missed_runs: Default::default(),
+	}
+}
+
+#[tokio::test]
+async fn test_job_schedule_lifecycle() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("job_schedule_lifecycle")
+		.disable_watcher()
+		.build()
+		.await?;
+	let action_manager = harness.core.context.get_action_manager().await.unwrap();
+	let library_id = harness.library.id();
+	let args = serde_json::to_value(AutoTagJob::new(AutoTagJobConfig::default()))?;
+
+	// Unknown jobs and bad args are rejected up front
+	assert!(action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateJobScheduleAction::from_input(schedule_input("no_such_job", args.clone()))
+				.unwrap(),
+		)
+		.await
+		.is_err());
+	assert!(action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateJobScheduleAction::from_input(schedule_input(
+				"auto_tag",
+				serde_json::json!("not a job")
+			))
+			.unwrap(),
+		)
+		.await
+		.is_err());
+
+	let created = action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateJobScheduleAction::from_input(schedule_input("auto_tag", args)).unwrap(),
+		)
+		.await?;
+	assert!(created.next_run_at > Utc::now());
+
+	let listed = ListJobSchedulesQuery::from_input(Default::default())?
+		.execute(harness.core.context.clone(), session(&harness))
+		.await?;
+	assert_eq!(listed.schedules.len(), 1);
+	assert_eq!(listed.schedules[0].id, created.schedule_id);
+	assert_eq!(listed.schedules[0].job_name, "auto_tag");
+
+	// Nothing is due yet
+	let jobs = harness.library.jobs();
+	assert!(jobs.run_due_schedules().await?.is_empty());
+
+	// Pretend the daemon was stopped past several runs
+	make_overdue(&harness, created.schedule_id).await?;
+
+	// Missed runs collapse into a single dispatch
+	let dispatched = jobs.run_due_schedules().await?;
+	assert_eq!(dispatched.len(), 1);
+	assert!(jobs.run_due_schedules().await?.is_empty());
+
+	let schedule = schedules::Entity::find_by_id(created.schedule_id.to_string())
+		.one(jobs.database().conn())
+		.await?
+		.expect("schedule should exist");
+	assert!(schedule.next_run_at > Utc::now());
+	assert_eq!(schedule.last_job_id, Some(dispatched[0].to_string()));
+	assert!(schedule.last_error.is_none());
+
+	let deleted = action_manager
+		.dispatch_library(
+			Some(library_id),
+			DeleteJobScheduleAction::from_input(DeleteJobScheduleInput {
+				schedule_id: created.schedule_id,
+			})
+			.unwrap(),
+		)
+		.await?;
+	assert!(deleted.deleted);
+
+	let listed = ListJobSchedulesQuery::from_input(Default::default())?
+		.execute(harness.core.context.clone(), session(&harness))
+		.await?;
+	assert!(listed.schedules.is_empty());
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
+/// Move a schedule's next run into the past, as if the daemon had been stopped
+async fn make_overdue(harness: &IndexingHarness, schedule_id: Uuid) -> anyhow::Result<()> {
+	schedules::ActiveModel {
+		id: Set(schedule_id.to_string()),
+		next_run_at: Set(Utc::now() - chrono::Duration::hours(5)),
+		..Default::default()
+	}
+	.update(harness.library.jobs().database().conn())
+	.await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_job_schedule_missed_runs() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("job_schedule_missed_runs")
+		.disable_watcher()
+		.build()
+		.await?;
+	let action_manager = harness.core.context.get_action_manager().await.unwrap();
+	let library_id = harness.library.id();
+	let jobs = harness.library.jobs();
+	let args = serde_json::to_value(AutoTagJob::new(AutoTagJobConfig::default()))?;
+
+	let mut skip_input = schedule_input("auto_tag", args.clone());
+	skip_input.missed_runs = MissedRunPolicy::Skip;
+	let skipped = action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateJobScheduleAction::from_input(skip_input).unwrap(),
+		)
+		.await?;
+	let run_once = action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateJobScheduleAction::from_input(schedule_input("auto_tag", args)).unwrap(),
+		)
+		.await?;
+	make_overdue(&harness, skipped.schedule_id).await?;
+	make_overdue(&harness, run_once.schedule_id).await?;
+
+	// Catching up pushes Skip schedules to their next run and leaves RunOnce ones due
+	jobs.catch_up_missed_runs().await?;
+	let skip_schedule = schedules::Entity::find_by_id(skipped.schedule_id.to_string())
+		.one(jobs.database().conn())
+		.await?
+		.expect("schedule should exist");
+	assert!(skip_schedule.next_run_at > Utc::now());
+	assert!(skip_schedule.last_run_at.is_none());
+
+	let dispatched = jobs.run_due_schedules().await?;
+	assert_eq!(dispatched.len(), 1);
+	let run_once_schedule = schedules::Entity::find_by_id(run_once.schedule_id.to_string())
+		.one(jobs.database().conn())
+		.await?
+		.expect("schedule should exist");
+	assert_eq!(
+		run_once_schedule.last_job_id,
+		Some(dispatched[0].to_string())
+	);
+	assert!(run_once_schedule.next_run_at > Utc::now());
+
+	// Catching up again has nothing left to do
+	jobs.catch_up_missed_runs().await?;
+	assert!(jobs.run_due_schedules().await?.is_empty());
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_job_schedule_broken_row_is_disabled() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("job_schedule_broken_row")
+		.disable_watcher()
+		.build()
+		.await?;
+	let action_manager = harness.core.context.get_action_manager().await.unwrap();
+	let library_id = harness.library.id();
+	let jobs = harness.library.jobs();
+	let args = serde_json::to_value(AutoTagJob::new(AutoTagJobConfig::default()))?;
+
+	let broken = action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateJobScheduleAction::from_input(schedule_input("auto_tag", args.clone())).unwrap(),
+		)
+		.await?;
+	let healthy = action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateJobScheduleAction::from_input(schedule_input("auto_tag", args)).unwrap(),
+		)
+		.await?;
+	make_overdue(&harness, broken.schedule_id).await?;
+	make_overdue(&harness, healthy.schedule_id).await?;
+
+	// Corrupt the first schedule's trigger, as an older or damaged row would be
+	schedules::ActiveModel {
+		id: Set(broken.schedule_id.to_string()),
+		trigger: Set(serde_json::json!("not a trigger")),
+		..Default::default()
+	}
+	.update(jobs.database().conn())
+	.await?;
+
+	// Neither pass fails, and the healthy schedule still runs
+	jobs.catch_up_missed_runs().await?;
+	let dispatched = jobs.run_due_schedules().await?;
+	assert_eq!(dispatched.len(), 1);
+
+	let broken_schedule = schedules::Entity::find_by_id(broken.schedule_id.to_string())
+		.one(jobs.database().conn())
+		.await?
+		.expect("schedule should exist");
+	assert!(!broken_schedule.enabled);
+	assert!(broken_schedule.last_error.is_some());
+
+	let healthy_schedule = schedules::Entity::find_by_id(healthy.schedule_id.to_string())
+		.one(jobs.database().conn())
+		.await?
+		.expect("schedule should exist");
+	assert_eq!(
+		healthy_schedule.last_job_id,
+		Some(dispatched[0].to_string())
+	);
+
+	harness.shutdown().await?;
+	Ok(())
+}
@@ -0,0 +1,203 @@
+//! Library Export/Import Integration Test
+//!
+//! Exports a library from one core and imports it into another, verifying that the
+//! imported library is registered, its locations are handed to the importing device,
+//! and library ID conflicts are handled.
+
+use sd_core::{
+	domain::addressing::SdPath,
+	infra::{
+		action::{CoreAction, LibraryAction},
+		db::entities,
+	},
+	ops::{
+		indexing::IndexMode,
+		libraries::{
+			export::{action::LibraryExportAction, input::LibraryExportInput},
+			import::{LibraryImportAction, LibraryImportConflict, LibraryImportInput},
+		},
+		locations::add::action::{LocationAddAction, LocationAddInput},
+	},
+	Core,
+};
+use sea_orm::EntityTrait;
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_library_export_import() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+	let temp_dir = TempDir::new()?;
+	let location_dir = temp_dir.path().join("photos");
+	let export_dir = temp_dir.path().join("export");
+	tokio::fs::create_dir_all(&location_dir).await?;
+
+	// Source core: a library with one unindexed location
+	let source = Core::new(temp_dir.path().join("source")).await?;
+	let library = source
+		.libraries
+		.create_library("Travel", None, source.context.clone())
+		.await?;
+
+	let source_actions = source.context.action_manager.read().await.clone().unwrap();
+	source_actions
+		.dispatch_library(
+			Some(library.id()),
+			LocationAddAction::from_input(LocationAddInput {
+				path: SdPath::local(location_dir.clone()),
+				name: Some("Photos".to_string()),
+				mode: IndexMode::None,
+				job_policies: None,
+			})?,
+		)
+		.await?;
+
+	source_actions
+		.dispatch_library(
+			Some(library.id()),
+			LibraryExportAction::from_input(LibraryExportInput {
+				library_id: library.id(),
+				export_path: export_dir.clone(),
+				include_thumbnails: true,
+				include_previews: false,
+			})?,
+		)
+		.await?;
+	assert!(export_dir.join("export.json").exists());
+	assert!(export_dir.join("library.db").exists());
+
+	// Target core: a different device importing the export
+	let target = Core::new(temp_dir.path().join("target")).await?;
+	let target_device = target.context.device_manager.device_id()?;
+	let target_actions = target.context.action_manager.read().await.clone().unwrap();
+
+	let imported = target_actions
+		.dispatch_core(LibraryImportAction::from_input(LibraryImportInput {
+			archive_path: export_dir.clone(),
+			name: None,
+			path: None,
+			on_conflict: LibraryImportConflict::Fail,
+		})?)
+		.await?;
+
+	assert_eq!(imported.library_id, library.id());
+	assert_eq!(imported.name, "Travel");
+	assert_eq!(imported.remapped_locations, 1);
+
+	let imported_library = target
+		.libraries
+		.get_library(imported.library_id)
+		.await
+		.expect("imported library should be open");
+	let (location, device) = entities::location::Entity::find()
+		.find_also_related(entities::device::Entity)
+		.one(imported_library.db().conn())
+		.await?
+		.expect("location should be imported");
+	assert_eq!(location.name.as_deref(), Some("Photos"));
+	assert_eq!(device.map(|d| d.uuid), Some(target_device));
+
+	// Importing the same library again conflicts unless asked for a new ID
+	let conflicting = target_actions
+		.dispatch_core(LibraryImportAction::from_input(LibraryImportInput {
+			archive_path: export_dir.clone(),
+			name: None,
+			path: None,
+			on_conflict: LibraryImportConflict::Fail,
+		})?)
+		.await;
+	assert!(conflicting.is_err());
+
+	let copy = target_actions
+		.dispatch_core(LibraryImportAction::from_input(LibraryImportInput {
+			archive_path: export_dir,
+			name: Some("Travel Copy".to_string()),
+			path: None,
+			on_conflict: LibraryImportConflict::NewId,
+		})?)
+		.await?;
+	assert_ne!(copy.library_id, library.id());
+	assert_eq!(copy.source_library_id, library.id());
+	assert!(target
+		.libraries
+		.get_library(copy.library_id)
+		.await
+		.is_some());
+
+	source.shutdown().await?;
+	target.shutdown().await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_library_import_replace() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+	let temp_dir = TempDir::new()?;
+	let export_dir = temp_dir.path().join("export");
+	let extra_location_dir = temp_dir.path().join("documents");
+	tokio::fs::create_dir_all(&extra_location_dir).await?;
+
+	let core = Core::new(temp_dir.path().join("core")).await?;
+	let actions = core.context.action_manager.read().await.clone().unwrap();
+	let library = core
+		.libraries
+		.create_library("Archive", None, core.context.clone())
+		.await?;
+	let library_id = library.id();
+	let library_path = library.path().to_path_buf();
+
+	actions
+		.dispatch_library(
+			Some(library_id),
+			LibraryExportAction::from_input(LibraryExportInput {
+				library_id,
+				export_path: export_dir.clone(),
+				include_thumbnails: false,
+				include_previews: false,
+			})?,
+		)
+		.await?;
+
+	// Change the library after exporting it, so the replacement is observable
+	actions
+		.dispatch_library(
+			Some(library_id),
+			LocationAddAction::from_input(LocationAddInput {
+				path: SdPath::local(extra_location_dir),
+				name: Some("Documents".to_string()),
+				mode: IndexMode::None,
+				job_policies: None,
+			})?,
+		)
+		.await?;
+	drop(library);
+
+	let replaced = actions
+		.dispatch_core(LibraryImportAction::from_input(LibraryImportInput {
+			archive_path: export_dir,
+			name: None,
+			path: None,
+			on_conflict: LibraryImportConflict::Replace,
+		})?)
+		.await?;
+	assert!(replaced.replaced_existing);
+	assert_eq!(replaced.library_id, library_id);
+	assert_eq!(replaced.path, library_path);
+
+	// The open library is the imported copy, without the location added after export
+	let reopened = core
+		.libraries
+		.get_library(library_id)
+		.await
+		.expect("replacement should be open");
+	let locations = entities::location::Entity::find()
+		.all(reopened.db().conn())
+		.await?;
+	assert!(locations
+		.iter()
+		.all(|location| location.name.as_deref() != Some("Documents")));
+
+	// Nothing is left behind from the swap
+	assert!(!library_path.with_extension("sdreplaced").exists());
+	assert!(!library_path.with_extension("sdimport").exists());
+
+	core.shutdown().await?;
+	Ok(())
+}
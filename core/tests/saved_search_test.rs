@@ -0,0 +1,200 @@
+//! Saved search integration test
+//!
+//! Verifies creating, listing, running and deleting saved searches, and that smart
+//! collection membership is re-evaluated as entries are indexed.
+
+mod helpers;
+
+use helpers::*;
+use sd_core::{
+	infra::{
+		action::LibraryAction,
+		api::SessionContext,
+		query::{LibraryQuery, QueryError},
+	},
+	location::IndexMode,
+	ops::search::{
+		input::FileSearchInput,
+		saved::{
+			CreateSavedSearchAction, CreateSavedSearchInput, DeleteSavedSearchAction,
+			DeleteSavedSearchInput, ListSavedSearchesQuery, MembershipTracker, RunSavedSearchInput,
+			RunSavedSearchQuery,
+		},
+	},
+};
+
+fn session(harness: &IndexingHarness) -> SessionContext {
+	let device_id = sd_core::device::get_current_device_id();
+	let device_name = sd_core::device::get_current_device_slug();
+	SessionContext::device_session(device_id, device_name).with_library(harness.library.id())
+}
+
+#[tokio::test]
+async fn test_saved_search_lifecycle() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("saved_search_lifecycle")
+		.disable_watcher()
+		.build()
+		.await?;
+	let db = harness.library.db().conn();
+	let action_manager = harness.core.context.get_action_manager().await.unwrap();
+	let library_id = harness.library.id();
+	let registry = harness.core.context.file_type_registry();
+
+	assert!(CreateSavedSearchAction::from_input(CreateSavedSearchInput {
+		name: "  ".to_string(),
+		description: None,
+		search: FileSearchInput::fast("report".to_string()),
+	})
+	.is_err());
+
+	let created = action_manager
+		.dispatch_library(
+			Some(library_id),
+			CreateSavedSearchAction::from_input(CreateSavedSearchInput {
+				name: "Reports".to_string(),
+				description: Some("Every report".to_string()),
+				search: FileSearchInput::fast("report".to_string()),
+			})
+			.unwrap(),
+		)
+		.await?;
+
+	let listed = ListSavedSearchesQuery::from_input(Default::default())?
+		.execute(harness.core.context.clone(), session(&harness))
+		.await?;
+	assert_eq!(listed.saved_searches.len(), 1);
+	assert_eq!(listed.saved_searches[0].id, created.saved_search_id);
+	assert_eq!(listed.saved_searches[0].search.query, "report");
+
+	// Nothing is indexed yet, so the collection starts empty
+	let mut tracker = MembershipTracker::default();
+	assert!(tracker.refresh(db, registry).await?.is_empty());
+
+	let test_location = harness.create_test_location("saved_search").await?;
+	test_location.write_file("report.txt", "q1").await?;
+	test_location.write_file("reports.md", "q2").await?;
+	test_location.write_file("notes.txt", "unrelated").await?;
+	test_location
+		.index("Saved Search Location", IndexMode::Shallow)
+		.await?;
+
+	let changes = tracker.refresh(db, registry).await?;
+	assert_eq!(changes.len(), 1);
+	assert_eq!(changes[0].saved_search_id, created.saved_search_id);
+	assert_eq!(changes[0].added.len(), 2);
+	assert!(changes[0].removed.is_empty());
+
+	// Unchanged results report nothing
+	assert!(tracker.refresh(db, registry).await?.is_empty());
+
+	let results = RunSavedSearchQuery::from_input(RunSavedSearchInput {
+		saved_search_id: created.saved_search_id,
+		pagination: None,
+	})?
+	.execute(harness.core.context.clone(), session(&harness))
+	.await?;
+	let mut names: Vec<String> = results
+		.results
+		.iter()
+		.map(|r| r.file.name.clone())
+		.collect();
+	names.sort();
+	assert_eq!(names, vec!["report", "reports"]);
+
+	action_manager
+		.dispatch_library(
+			Some(library_id),
+			DeleteSavedSearchAction::from_input(DeleteSavedSearchInput {
+				saved_search_id: created.saved_search_id,
+			})
+			.unwrap(),
+		)
+		.await?;
+
+	let listed = ListSavedSearchesQuery::from_input(Default::default())?
+		.execute(harness.core.context.clone(), session(&harness))
+		.await?;
+	assert!(listed.saved_searches.is_empty());
+	assert!(tracker.refresh(db, registry).await?.is_empty());
+
+	let missing = RunSavedSearchQuery::from_input(RunSavedSearchInput {
+		saved_search_id: created.saved_search_id,
+		pagination: None,
+	})?
+	.execute(harness.core.context.clone(), session(&harness))
+	.await;
+	assert!(matches!(missing, Err(QueryError::Validation { .. })));
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_saved_search_membership_scope() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("saved_search_membership_scope")
+		.disable_watcher()
+		.build()
+		.await?;
+	let db = harness.library.db().conn();
+	let registry = harness.core.context.file_type_registry();
+	let action_manager = harness.core.context.get_action_manager().await.unwrap();
+
+	let test_location = harness.create_test_location("membership").await?;
+	for name in [
+		"invoice-1.txt",
+		"invoice-2.txt",
+		"invoice-3.txt",
+		"receipt.txt",
+	] {
+		test_location.write_file(name, "paid").await?;
+	}
+	test_location
+		.index("Membership Location", IndexMode::Shallow)
+		.await?;
+
+	// A page size of one must not cap the collection at one member
+	let mut search = FileSearchInput::fast("invoice".to_string());
+	search.pagination.limit = 1;
+	action_manager
+		.dispatch_library(
+			Some(harness.library.id()),
+			CreateSavedSearchAction::from_input(CreateSavedSearchInput {
+				name: "Invoices".to_string(),
+				description: None,
+				search,
+			})
+			.unwrap(),
+		)
+		.await?;
+
+	let mut tracker = MembershipTracker::default();
+	let changes = tracker.refresh(db, registry).await?;
+	assert_eq!(changes.len(), 1);
+	assert_eq!(changes[0].added.len(), 3);
+
+	test_location.write_file("invoice-4.txt", "due").await?;
+	test_location
+		.index("Membership Location", IndexMode::Shallow)
+		.await?;
+	let changes = tracker.refresh(db, registry).await?;
+	assert_eq!(changes.len(), 1);
+	assert_eq!(changes[0].added.len(), 1);
+
+	// Only changes to this library's files, or to what a search filters on, matter
+	let member = changes[0].added[0];
+	assert!(tracker.is_affected_by(db, "file", &[member]).await?);
+	assert!(
+		!tracker
+			.is_affected_by(db, "file", &[uuid::Uuid::new_v4()])
+			.await?
+	);
+	assert!(
+		!tracker
+			.is_affected_by(db, "tag", &[uuid::Uuid::new_v4()])
+			.await?
+	);
+	assert!(!tracker.is_affected_by(db, "album", &[member]).await?);
+
+	harness.shutdown().await?;
+	Ok(())
+}
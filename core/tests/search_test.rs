@@ -9,16 +9,23 @@
 //! - Filter application (file types, size, date, content types)
 //! - Index type detection and routing
 //! - Result accuracy and relevance scoring
+//! - Blending vector similarity into Normal search
 
 mod helpers;
 
 use helpers::*;
 use sd_core::{
 	domain::{addressing::SdPath, ContentKind},
-	infra::{api::SessionContext, query::LibraryQuery},
+	infra::{
+		api::SessionContext,
+		db::entities::{content_identity, entry},
+		query::LibraryQuery,
+	},
 	location::IndexMode,
 	ops::{
 		indexing::{IndexScope, IndexerJob, IndexerJobConfig},
+		media::text_extraction::TextExtractionJob,
+		models::EmbeddingModel,
 		search::{
 			input::{
 				DateField, DateRangeFilter, FileSearchInput, PaginationOptions, SearchFilters,
@@ -29,6 +36,7 @@ use sd_core::{
 		},
 	},
 };
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use std::path::PathBuf;
 use tokio::time::Duration;
 
@@ -360,6 +368,261 @@ async fn test_persistent_search_by_path() -> anyhow::Result<()> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn test_persistent_search_full_matches_file_contents() -> anyhow::Result<()> {
+	// Tests that full search finds files by their extracted text
+	let harness = IndexingHarnessBuilder::new("persistent_search_contents")
+		.disable_watcher()
+		.build()
+		.await?;
+
+	let test_location = harness.create_test_location("test_search").await?;
+
+	test_location
+		.write_file(
+			"notes.md",
+			"# Travel\n\nPacking list for the zanzibar trip: sunscreen and snorkel.",
+		)
+		.await?;
+	test_location
+		.write_file("main.rs", "fn main() { println!(\"hello\"); }")
+		.await?;
+
+	let location = test_location
+		.index("Test Location", IndexMode::Content)
+		.await?;
+
+	let extraction = harness
+		.library
+		.jobs()
+		.dispatch(TextExtractionJob::from_location(location.uuid))
+		.await?;
+	extraction.wait().await?;
+
+	let content_search = FileSearchInput {
+		query: "zanzibar".to_string(),
+		scope: SearchScope::Location {
+			location_id: location.uuid,
+		},
+		mode: SearchMode::Full,
+		filters: SearchFilters::default(),
+		sort: SortOptions {
+			field: SortField::Relevance,
+			direction: SortDirection::Desc,
+		},
+		pagination: PaginationOptions {
+			limit: 50,
+			offset: 0,
+		},
+	};
+
+	let results = execute_search(&harness, content_search.clone()).await?;
+
+	// The name doesn't match, so notes.md can only be found through its contents
+	let notes = results
+		.results
+		.iter()
+		.find(|r| r.file.name == "notes")
+		.expect("Should find notes.md by its contents");
+
+	let highlight = notes
+		.highlights
+		.iter()
+		.find(|h| h.field == "content")
+		.expect("Should carry a content highlight");
+	assert_eq!(&highlight.text[highlight.start..highlight.end], "zanzibar");
+	assert!(notes
+		.matched_content
+		.as_deref()
+		.is_some_and(|snippet| snippet.contains("zanzibar trip")));
+
+	// Normal search only looks at names
+	let name_search = FileSearchInput {
+		mode: SearchMode::Normal,
+		..content_search
+	};
+	let results = execute_search(&harness, name_search).await?;
+	assert!(results.results.iter().all(|r| r.file.name != "notes"));
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_persistent_search_full_respects_scope_and_pagination() -> anyhow::Result<()> {
+	// Tests that name and content matches are scoped, filtered and paginated together
+	let harness = IndexingHarnessBuilder::new("persistent_search_full_scoped")
+		.disable_watcher()
+		.build()
+		.await?;
+
+	let first_location = harness.create_test_location("first").await?;
+	first_location
+		.write_file("zanzibar.txt", "Ferry timetable")
+		.await?;
+	first_location
+		.write_file("notes.md", "Packing list for the zanzibar trip.")
+		.await?;
+	let first = first_location.index("First", IndexMode::Content).await?;
+
+	let second_location = harness.create_test_location("second").await?;
+	second_location
+		.write_file("journal.md", "Day three in zanzibar: spice farm.")
+		.await?;
+	let second = second_location.index("Second", IndexMode::Content).await?;
+
+	for location_id in [first.uuid, second.uuid] {
+		harness
+			.library
+			.jobs()
+			.dispatch(TextExtractionJob::from_location(location_id))
+			.await?
+			.wait()
+			.await?;
+	}
+
+	let scoped = FileSearchInput {
+		query: "zanzibar".to_string(),
+		scope: SearchScope::Location {
+			location_id: first.uuid,
+		},
+		mode: SearchMode::Full,
+		filters: SearchFilters::default(),
+		sort: SortOptions {
+			field: SortField::Relevance,
+			direction: SortDirection::Desc,
+		},
+		pagination: PaginationOptions {
+			limit: 50,
+			offset: 0,
+		},
+	};
+
+	// Only the first location's name and content matches come back
+	let results = execute_search(&harness, scoped.clone()).await?;
+	let mut names: Vec<&str> = results
+		.results
+		.iter()
+		.map(|r| r.file.name.as_str())
+		.collect();
+	names.sort();
+	assert_eq!(names, ["notes", "zanzibar"]);
+
+	// Filters apply to content matches too
+	let filtered = FileSearchInput {
+		filters: SearchFilters {
+			file_types: Some(vec!["md".to_string()]),
+			..Default::default()
+		},
+		..scoped.clone()
+	};
+	let results = execute_search(&harness, filtered).await?;
+	assert_eq!(results.results.len(), 1);
+	assert_eq!(results.results[0].file.name, "notes");
+
+	// One page never holds more than the limit, and pages don't overlap
+	let page = |offset| FileSearchInput {
+		pagination: PaginationOptions { limit: 1, offset },
+		..scoped.clone()
+	};
+	let first_page = execute_search(&harness, page(0)).await?;
+	let second_page = execute_search(&harness, page(1)).await?;
+	assert_eq!(first_page.results.len(), 1);
+	assert_eq!(second_page.results.len(), 1);
+	assert_ne!(
+		first_page.results[0].file.id,
+		second_page.results[0].file.id
+	);
+
+	// Library-wide search sees both locations
+	let library_wide = FileSearchInput {
+		scope: SearchScope::Library,
+		..scoped
+	};
+	let results = execute_search(&harness, library_wide).await?;
+	assert!(results.results.iter().any(|r| r.file.name == "journal"));
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_persistent_search_blends_semantic_matches() -> anyhow::Result<()> {
+	// Tests that a file matching only by meaning joins Normal search results
+	let harness = IndexingHarnessBuilder::new("persistent_search_semantic")
+		.disable_watcher()
+		.build()
+		.await?;
+
+	let test_location = harness.create_test_location("photos").await?;
+	test_location
+		.write_file("beach-trip.txt", "Waves and sand")
+		.await?;
+	test_location
+		.write_file("invoice.txt", "Amount due")
+		.await?;
+	let location = test_location.index("Photos", IndexMode::Content).await?;
+
+	// Seed the vector index with one unit vector per file, orthogonal to each other
+	let dimensions = EmbeddingModel::default().dimensions();
+	let unit = |axis: usize| {
+		let mut vector = vec![0.0; dimensions];
+		vector[axis] = 1.0;
+		vector
+	};
+	let index = harness.library.embedding_index().await?;
+	for (axis, name) in ["beach-trip", "invoice"].into_iter().enumerate() {
+		let file = entry::Entity::find()
+			.filter(entry::Column::Name.eq(name))
+			.one(harness.library.db().conn())
+			.await?
+			.expect("file should be indexed");
+		let content = content_identity::Entity::find_by_id(file.content_id.unwrap())
+			.one(harness.library.db().conn())
+			.await?
+			.expect("file should have a content identity");
+		index
+			.write()
+			.await
+			.upsert(content.id, &content.content_hash, vec![unit(axis)])?;
+	}
+
+	// No name or content matches "sunset", so only the vector ranking can find the file
+	let input = FileSearchInput {
+		query: "sunset".to_string(),
+		scope: SearchScope::Location {
+			location_id: location.uuid,
+		},
+		mode: SearchMode::Normal,
+		filters: SearchFilters::default(),
+		sort: SortOptions {
+			field: SortField::Relevance,
+			direction: SortDirection::Desc,
+		},
+		pagination: PaginationOptions {
+			limit: 50,
+			offset: 0,
+		},
+	};
+	let query = FileSearchQuery::new(input).with_query_vector(unit(0));
+	let device_id = sd_core::device::get_current_device_id();
+	let device_name = sd_core::device::get_current_device_slug();
+	let session =
+		SessionContext::device_session(device_id, device_name).with_library(harness.library.id());
+	let results = query.execute(harness.core.context.clone(), session).await?;
+
+	let names: Vec<&str> = results
+		.results
+		.iter()
+		.map(|r| r.file.name.as_str())
+		.collect();
+	assert_eq!(names, ["beach-trip"], "Dissimilar files are left out");
+	assert!(results.results[0].score_breakdown.semantic_score.unwrap() > 0.99);
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
 // ============================================================================
 // EPHEMERAL SEARCH TESTS (Non-Indexed Directories)
 // ============================================================================
@@ -0,0 +1,231 @@
+//! Sync conflict integration test
+//!
+//! Two devices edit the same shared tag while partitioned. Verifies that merging each
+//! side's change field by field keeps edits to different fields, resolves the field
+//! both edited by HLC on both devices, and that `sync.conflicts.list` reports it.
+
+mod helpers;
+
+use helpers::TwoDeviceHarnessBuilder;
+use sd_core::{
+	infra::{
+		api::SessionContext,
+		db::entities::tag,
+		query::LibraryQuery,
+		sync::{ChangeType, ConflictResolution, SharedChangeEntry},
+	},
+	library::Library,
+	ops::sync::list_conflicts::{ListSyncConflicts, ListSyncConflictsInput},
+	Core,
+};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use std::sync::Arc;
+use tokio::time::Duration;
+use uuid::Uuid;
+
+async fn find_tag(library: &Library, uuid: Uuid) -> anyhow::Result<Option<tag::Model>> {
+	Ok(tag::Entity::find()
+		.filter(tag::Column::Uuid.eq(uuid))
+		.one(library.db().conn())
+		.await?)
+}
+
+/// Change a tag locally and commit it to the library's sync log
+async fn edit_tag(
+	library: &Library,
+	uuid: Uuid,
+	edit: impl FnOnce(&mut tag::ActiveModel),
+) -> anyhow::Result<()> {
+	let existing = find_tag(library, uuid).await?.expect("tag should exist");
+	let mut active: tag::ActiveModel = existing.into();
+	edit(&mut active);
+	active.updated_at = Set(chrono::Utc::now());
+	let updated = active.update(library.db().conn()).await?;
+	library.sync_model(&updated, ChangeType::Update).await?;
+	Ok(())
+}
+
+/// The newest change to a record in a library's shared log
+async fn latest_change(library: &Library, uuid: Uuid) -> anyhow::Result<SharedChangeEntry> {
+	let (entries, _) = library
+		.sync_service()
+		.unwrap()
+		.peer_sync()
+		.get_shared_changes(None, 1000)
+		.await?;
+	entries
+		.into_iter()
+		.filter(|entry| entry.record_uuid == uuid)
+		.max_by_key(|entry| entry.hlc)
+		.ok_or_else(|| anyhow::anyhow!("No shared change for {}", uuid))
+}
+
+async fn list_conflicts(
+	core: &Core,
+	library: &Arc<Library>,
+	input: ListSyncConflictsInput,
+) -> anyhow::Result<Vec<sd_core::ops::sync::list_conflicts::SyncConflictRecord>> {
+	let session =
+		SessionContext::device_session(core.device.device_id()?, "test-device".to_string())
+			.with_library(library.id());
+	Ok(ListSyncConflicts::from_input(input)?
+		.execute(core.context.clone(), session)
+		.await?
+		.conflicts)
+}
+
+fn conflicts_input() -> ListSyncConflictsInput {
+	ListSyncConflictsInput {
+		model_type: None,
+		record_uuid: None,
+		peer_id: None,
+		limit: None,
+		offset: None,
+	}
+}
+
+#[tokio::test]
+async fn test_concurrent_tag_edits_merge_by_field() -> anyhow::Result<()> {
+	let harness = TwoDeviceHarnessBuilder::new("sync_conflict_merge")
+		.await?
+		.build()
+		.await?;
+	let (alice, bob) = (&harness.library_alice, &harness.library_bob);
+
+	// Alice creates a tag, which reaches Bob normally
+	let tag_uuid = Uuid::new_v4();
+	let now = chrono::Utc::now();
+	let created = tag::ActiveModel {
+		uuid: Set(tag_uuid),
+		canonical_name: Set("travel".to_string()),
+		tag_type: Set("standard".to_string()),
+		color: Set(Some("#ff0000".to_string())),
+		description: Set(Some("Trips".to_string())),
+		is_organizational_anchor: Set(false),
+		privacy_level: Set("normal".to_string()),
+		search_weight: Set(100),
+		created_at: Set(now),
+		updated_at: Set(now),
+		created_by_device: Set(Some(harness.device_alice_id)),
+		..Default::default()
+	}
+	.insert(alice.db().conn())
+	.await?;
+	alice.sync_model(&created, ChangeType::Insert).await?;
+
+	let mut delivered = false;
+	for _ in 0..50 {
+		if find_tag(bob, tag_uuid).await?.is_some() {
+			delivered = true;
+			break;
+		}
+		tokio::time::sleep(Duration::from_millis(100)).await;
+	}
+	assert!(delivered, "Bob should receive the new tag");
+
+	// Partition the devices and edit the tag on both sides
+	harness
+		.transport_alice
+		.block_device(harness.device_bob_id)
+		.await;
+	harness
+		.transport_alice
+		.block_device(harness.device_alice_id)
+		.await;
+
+	edit_tag(alice, tag_uuid, |tag| {
+		tag.canonical_name = Set("vacation".to_string());
+		tag.description = Set(Some("Alice's trips".to_string()));
+	})
+	.await?;
+	tokio::time::sleep(Duration::from_millis(20)).await;
+	edit_tag(bob, tag_uuid, |tag| {
+		tag.color = Set(Some("#00ff00".to_string()));
+		tag.description = Set(Some("Bob's trips".to_string()));
+	})
+	.await?;
+
+	// Exchange the concurrent changes
+	let from_alice = latest_change(alice, tag_uuid).await?;
+	let from_bob = latest_change(bob, tag_uuid).await?;
+	assert!(from_bob.hlc > from_alice.hlc);
+
+	let bob_sync = bob.sync_service().unwrap().peer_sync().clone();
+	let alice_sync = alice.sync_service().unwrap().peer_sync().clone();
+	bob_sync
+		.on_shared_change_received(from_alice.clone())
+		.await?;
+	alice_sync
+		.on_shared_change_received(from_bob.clone())
+		.await?;
+
+	// Disjoint fields merge and the shared field goes to the newer write on both sides
+	for library in [alice, bob] {
+		let merged = find_tag(library, tag_uuid)
+			.await?
+			.expect("tag should exist");
+		assert_eq!(merged.canonical_name, "vacation");
+		assert_eq!(merged.color.as_deref(), Some("#00ff00"));
+		assert_eq!(merged.description.as_deref(), Some("Bob's trips"));
+	}
+
+	// Redelivering a change is a no-op
+	bob_sync.on_shared_change_received(from_alice).await?;
+	let unchanged = find_tag(bob, tag_uuid).await?.expect("tag should exist");
+	assert_eq!(unchanged.description.as_deref(), Some("Bob's trips"));
+
+	// Each side reports the one real conflict once, from its own point of view
+	let bob_conflicts = list_conflicts(&harness.core_bob, bob, conflicts_input()).await?;
+	assert_eq!(bob_conflicts.len(), 1);
+	let conflict = &bob_conflicts[0].conflict;
+	assert_eq!(conflict.model_type, "tag");
+	assert_eq!(conflict.record_uuid, tag_uuid);
+	assert_eq!(conflict.field, "description");
+	assert_eq!(conflict.resolution, ConflictResolution::KeptLocal);
+	assert_eq!(conflict.remote_value, serde_json::json!("Alice's trips"));
+	assert_eq!(
+		bob_conflicts[0].peer_device_id,
+		Some(harness.device_alice_id)
+	);
+
+	let alice_conflicts = list_conflicts(&harness.core_alice, alice, conflicts_input()).await?;
+	assert_eq!(alice_conflicts.len(), 1);
+	assert_eq!(
+		alice_conflicts[0].conflict.resolution,
+		ConflictResolution::TookRemote
+	);
+
+	// Filters narrow the list
+	let by_record = list_conflicts(
+		&harness.core_bob,
+		bob,
+		ListSyncConflictsInput {
+			record_uuid: Some(tag_uuid),
+			..conflicts_input()
+		},
+	)
+	.await?;
+	assert_eq!(by_record.len(), 1);
+	let other_model = list_conflicts(
+		&harness.core_bob,
+		bob,
+		ListSyncConflictsInput {
+			model_type: Some("space".to_string()),
+			..conflicts_input()
+		},
+	)
+	.await?;
+	assert!(other_model.is_empty());
+	let other_record = list_conflicts(
+		&harness.core_bob,
+		bob,
+		ListSyncConflictsInput {
+			record_uuid: Some(Uuid::new_v4()),
+			..conflicts_input()
+		},
+	)
+	.await?;
+	assert!(other_record.is_empty());
+
+	Ok(())
+}
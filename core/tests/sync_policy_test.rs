@@ -0,0 +1,433 @@
+//! Selective sync policy integration test
+//!
+//! Bob subscribes to tags only. Verifies that Alice's broadcasts leave out what Bob's
+//! policy excludes, that the shared backfill she serves him is filtered the same way,
+//! and that a page the filter empties still moves Bob's cursor past what was scanned.
+//! Also checks that the metadata-only tier still sends locations, without their root
+//! entry, and that a tag scope sends the directories above the tagged files.
+
+mod helpers;
+
+use helpers::{TwoDeviceHarness, TwoDeviceHarnessBuilder};
+use sd_core::{
+	infra::{
+		action::LibraryAction,
+		db::entities::{collection, entry, location, tag, user_metadata, user_metadata_tag},
+		sync::{ChangeType, NetworkTransport, SharedChangeEntry},
+	},
+	ops::sync::set_policy::{SetSyncPolicyAction, SetSyncPolicyInput},
+	service::{
+		network::protocol::sync::messages::{StateRecord, SyncMessage},
+		sync::{SyncPolicy, SyncTier},
+	},
+};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use std::{collections::HashSet, path::Path};
+use tokio::time::Duration;
+use uuid::Uuid;
+
+/// The change a library logged for a record
+fn logged_change(log: &[SharedChangeEntry], uuid: Uuid) -> anyhow::Result<&SharedChangeEntry> {
+	log.iter()
+		.find(|entry| entry.record_uuid == uuid)
+		.ok_or_else(|| anyhow::anyhow!("No shared change for {}", uuid))
+}
+
+fn shared_records(messages: &[SyncMessage]) -> Vec<Uuid> {
+	messages
+		.iter()
+		.flat_map(|message| match message {
+			SyncMessage::SharedChange { entry, .. } => vec![entry.record_uuid],
+			SyncMessage::SharedChangeBatch { entries, .. } => {
+				entries.iter().map(|entry| entry.record_uuid).collect()
+			}
+			_ => Vec::new(),
+		})
+		.collect()
+}
+
+#[tokio::test]
+async fn test_selective_policy_filters_broadcast_and_backfill() -> anyhow::Result<()> {
+	let harness = TwoDeviceHarnessBuilder::new("sync_policy_filter")
+		.await?
+		.build()
+		.await?;
+	let (alice, bob) = (&harness.library_alice, &harness.library_bob);
+
+	// Drop Bob's ACKs so Alice keeps her log entries for the backfill requests below
+	harness
+		.transport_bob
+		.block_device(harness.device_alice_id)
+		.await;
+
+	// Alice learns that Bob only wants tags
+	harness
+		.core_alice
+		.context
+		.get_action_manager()
+		.await
+		.unwrap()
+		.dispatch_library(
+			Some(alice.id()),
+			SetSyncPolicyAction::from_input(SetSyncPolicyInput {
+				device_id: Some(harness.device_bob_id),
+				tier: SyncTier::Selective,
+				locations: Vec::new(),
+				volumes: Vec::new(),
+				tags: Vec::new(),
+				model_types: vec!["tag".to_string()],
+			})
+			.unwrap(),
+		)
+		.await?;
+
+	// Alice creates a collection, which Bob doesn't subscribe to, then a tag
+	let now = chrono::Utc::now();
+	let collection_uuid = Uuid::new_v4();
+	let created_collection = collection::ActiveModel {
+		uuid: Set(collection_uuid),
+		name: Set("Receipts".to_string()),
+		description: Set(None),
+		created_at: Set(now),
+		updated_at: Set(now),
+		..Default::default()
+	}
+	.insert(alice.db().conn())
+	.await?;
+	alice
+		.sync_model(&created_collection, ChangeType::Insert)
+		.await?;
+
+	let tag_uuid = Uuid::new_v4();
+	let created_tag = tag::ActiveModel {
+		uuid: Set(tag_uuid),
+		canonical_name: Set("travel".to_string()),
+		tag_type: Set("standard".to_string()),
+		is_organizational_anchor: Set(false),
+		privacy_level: Set("normal".to_string()),
+		search_weight: Set(100),
+		created_at: Set(now),
+		updated_at: Set(now),
+		created_by_device: Set(Some(harness.device_alice_id)),
+		..Default::default()
+	}
+	.insert(alice.db().conn())
+	.await?;
+	alice.sync_model(&created_tag, ChangeType::Insert).await?;
+
+	let mut delivered = false;
+	for _ in 0..50 {
+		if tag::Entity::find()
+			.filter(tag::Column::Uuid.eq(tag_uuid))
+			.one(bob.db().conn())
+			.await?
+			.is_some()
+		{
+			delivered = true;
+			break;
+		}
+		tokio::time::sleep(Duration::from_millis(100)).await;
+	}
+	assert!(delivered, "Bob should receive the tag");
+
+	// Broadcasts are routed through Bob's policy
+	let sent_to_bob = shared_records(
+		&harness
+			.transport_alice
+			.get_messages_between(harness.device_alice_id, harness.device_bob_id)
+			.await,
+	);
+	assert!(sent_to_bob.contains(&tag_uuid));
+	assert!(!sent_to_bob.contains(&collection_uuid));
+	assert!(collection::Entity::find()
+		.filter(collection::Column::Uuid.eq(collection_uuid))
+		.one(bob.db().conn())
+		.await?
+		.is_none());
+
+	// The initial backfill Alice serves Bob leaves the collection out of both the log
+	// and the state snapshot
+	let response = harness
+		.transport_bob
+		.send_sync_request(
+			harness.device_alice_id,
+			SyncMessage::SharedChangeRequest {
+				library_id: alice.id(),
+				since_hlc: None,
+				limit: 1000,
+			},
+		)
+		.await?;
+	let SyncMessage::SharedChangeResponse {
+		entries,
+		current_state,
+		..
+	} = response
+	else {
+		panic!("Expected a SharedChangeResponse");
+	};
+	let records: Vec<Uuid> = entries.iter().map(|entry| entry.record_uuid).collect();
+	assert!(records.contains(&tag_uuid));
+	assert!(!records.contains(&collection_uuid));
+	let current_state = current_state.expect("initial backfill carries the shared state");
+	assert!(current_state.get("tag").is_some());
+	assert!(current_state.get("collection").is_none());
+
+	// A page holding only the collection comes back empty, with the cursor past it
+	let (log, _) = alice
+		.sync_service()
+		.unwrap()
+		.peer_sync()
+		.get_shared_changes(None, 1000)
+		.await?;
+	let collection_change = logged_change(&log, collection_uuid)?;
+	let tag_change = logged_change(&log, tag_uuid)?;
+	let since_hlc = log
+		.iter()
+		.map(|entry| entry.hlc)
+		.filter(|hlc| *hlc < collection_change.hlc)
+		.max();
+
+	let response = harness
+		.transport_bob
+		.send_sync_request(
+			harness.device_alice_id,
+			SyncMessage::SharedChangeRequest {
+				library_id: alice.id(),
+				since_hlc,
+				limit: 1,
+			},
+		)
+		.await?;
+	let SyncMessage::SharedChangeResponse {
+		entries,
+		has_more,
+		cursor,
+		..
+	} = response
+	else {
+		panic!("Expected a SharedChangeResponse");
+	};
+	assert!(entries.is_empty(), "The collection is filtered out");
+	assert!(has_more, "The tag is still to come");
+	assert_eq!(cursor, Some(collection_change.hlc));
+
+	// Resuming from the cursor picks up the tag
+	let response = harness
+		.transport_bob
+		.send_sync_request(
+			harness.device_alice_id,
+			SyncMessage::SharedChangeRequest {
+				library_id: alice.id(),
+				since_hlc: cursor,
+				limit: 1,
+			},
+		)
+		.await?;
+	let SyncMessage::SharedChangeResponse {
+		entries, cursor, ..
+	} = response
+	else {
+		panic!("Expected a SharedChangeResponse");
+	};
+	assert_eq!(entries.len(), 1);
+	assert_eq!(entries[0].record_uuid, tag_uuid);
+	assert_eq!(cursor, Some(tag_change.hlc));
+
+	Ok(())
+}
+
+/// Index `trips/2024/beach.jpg`, `trips/2024/notes.txt` and `other.txt` under `dir` as a
+/// location on Alice
+async fn index_photos(harness: &TwoDeviceHarness, dir: &Path) -> anyhow::Result<Uuid> {
+	tokio::fs::create_dir_all(dir.join("trips/2024")).await?;
+	tokio::fs::write(dir.join("trips/2024/beach.jpg"), "fake jpg data").await?;
+	tokio::fs::write(dir.join("trips/2024/notes.txt"), "Sunscreen").await?;
+	tokio::fs::write(dir.join("other.txt"), "Unrelated").await?;
+	harness
+		.add_and_index_location_alice(dir.to_str().unwrap(), "photos")
+		.await
+}
+
+/// Alice's records of one model type, before any policy applies
+async fn alice_state(
+	harness: &TwoDeviceHarness,
+	model_type: &str,
+) -> anyhow::Result<Vec<StateRecord>> {
+	harness
+		.library_alice
+		.sync_service()
+		.unwrap()
+		.peer_sync()
+		.get_device_state(
+			vec![model_type.to_string()],
+			Some(harness.device_alice_id),
+			None,
+			None,
+			10_000,
+		)
+		.await
+}
+
+async fn entry_uuid(harness: &TwoDeviceHarness, name: &str) -> anyhow::Result<Uuid> {
+	entry::Entity::find()
+		.filter(entry::Column::Name.eq(name))
+		.one(harness.library_alice.db().conn())
+		.await?
+		.and_then(|entry| entry.uuid)
+		.ok_or_else(|| anyhow::anyhow!("No entry named {}", name))
+}
+
+#[tokio::test]
+async fn test_metadata_only_policy_sends_locations_without_entries() -> anyhow::Result<()> {
+	let harness = TwoDeviceHarnessBuilder::new("sync_policy_metadata_only")
+		.await?
+		.build()
+		.await?;
+	let photos = tempfile::tempdir()?;
+	let location_uuid = index_photos(&harness, photos.path()).await?;
+
+	let policies = harness
+		.library_alice
+		.sync_service()
+		.unwrap()
+		.peer_sync()
+		.policies()
+		.clone();
+	let policy = SyncPolicy {
+		tier: SyncTier::MetadataOnly,
+		..SyncPolicy::full(harness.device_bob_id)
+	};
+
+	// The location is sent, with its root entry reference emptied
+	let locations = alice_state(&harness, "location").await?;
+	let unfiltered = locations
+		.iter()
+		.find(|record| record.uuid == location_uuid)
+		.expect("Alice has the location");
+	assert!(unfiltered.data["entry_uuid"].is_string());
+	let locations = policies
+		.filter_state_records(&policy, "location", locations)
+		.await?;
+	let sent = locations
+		.iter()
+		.find(|record| record.uuid == location_uuid)
+		.expect("The location is sent");
+	assert!(sent.data["entry_uuid"].is_null());
+
+	// None of the entries are
+	let entries = alice_state(&harness, "entry").await?;
+	assert!(!entries.is_empty());
+	assert!(policies
+		.filter_state_records(&policy, "entry", entries)
+		.await?
+		.is_empty());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_tag_policy_sends_directories_above_tagged_entries() -> anyhow::Result<()> {
+	let harness = TwoDeviceHarnessBuilder::new("sync_policy_tag_ancestors")
+		.await?
+		.build()
+		.await?;
+	let alice = &harness.library_alice;
+	let photos = tempfile::tempdir()?;
+	let location_uuid = index_photos(&harness, photos.path()).await?;
+
+	// Alice tags the photo, three levels below the location root
+	let now = chrono::Utc::now();
+	let tag_uuid = Uuid::new_v4();
+	let created_tag = tag::ActiveModel {
+		uuid: Set(tag_uuid),
+		canonical_name: Set("beach".to_string()),
+		tag_type: Set("standard".to_string()),
+		is_organizational_anchor: Set(false),
+		privacy_level: Set("normal".to_string()),
+		search_weight: Set(100),
+		created_at: Set(now),
+		updated_at: Set(now),
+		created_by_device: Set(Some(harness.device_alice_id)),
+		..Default::default()
+	}
+	.insert(alice.db().conn())
+	.await?;
+	let metadata = user_metadata::ActiveModel {
+		uuid: Set(Uuid::new_v4()),
+		entry_uuid: Set(Some(entry_uuid(&harness, "beach").await?)),
+		content_identity_uuid: Set(None),
+		notes: Set(None),
+		favorite: Set(false),
+		hidden: Set(false),
+		custom_data: Set(serde_json::json!({})),
+		created_at: Set(now),
+		updated_at: Set(now),
+		..Default::default()
+	}
+	.insert(alice.db().conn())
+	.await?;
+	user_metadata_tag::ActiveModel {
+		user_metadata_id: Set(metadata.id),
+		tag_id: Set(created_tag.id),
+		applied_context: Set(None),
+		applied_variant: Set(None),
+		confidence: Set(1.0),
+		source: Set("user".to_string()),
+		instance_attributes: Set(None),
+		created_at: Set(now),
+		updated_at: Set(now),
+		device_uuid: Set(harness.device_alice_id),
+		uuid: Set(Uuid::new_v4()),
+		version: Set(1),
+		..Default::default()
+	}
+	.insert(alice.db().conn())
+	.await?;
+
+	let policies = alice.sync_service().unwrap().peer_sync().policies().clone();
+	let policy = SyncPolicy {
+		tier: SyncTier::Selective,
+		tags: vec![tag_uuid],
+		..SyncPolicy::full(harness.device_bob_id)
+	};
+
+	// The photo is sent with every directory above it, so its parent chain resolves
+	let entries = alice_state(&harness, "entry").await?;
+	let sent: HashSet<Uuid> = policies
+		.filter_state_records(&policy, "entry", entries)
+		.await?
+		.iter()
+		.map(|record| record.uuid)
+		.collect();
+	let root_id = location::Entity::find()
+		.filter(location::Column::Uuid.eq(location_uuid))
+		.one(alice.db().conn())
+		.await?
+		.and_then(|location| location.entry_id)
+		.expect("The location has a root entry");
+	let root_uuid = entry::Entity::find_by_id(root_id)
+		.one(alice.db().conn())
+		.await?
+		.and_then(|entry| entry.uuid)
+		.expect("The root entry has a UUID");
+	assert!(sent.contains(&root_uuid));
+	for name in ["trips", "2024", "beach"] {
+		assert!(
+			sent.contains(&entry_uuid(&harness, name).await?),
+			"{} is sent",
+			name
+		);
+	}
+
+	// Siblings that aren't tagged stay behind
+	for name in ["notes", "other"] {
+		assert!(
+			!sent.contains(&entry_uuid(&harness, name).await?),
+			"{} is held back",
+			name
+		);
+	}
+
+	Ok(())
+}
@@ -0,0 +1,138 @@
+//! Integration test for encrypted vaults
+//!
+//! Creates a vault through the API, adds a file to it and checks that nothing readable
+//! is left on disk, then locks and unlocks the library's vaults and exports the file
+//! again, byte for byte.
+
+mod helpers;
+
+use helpers::*;
+use sd_core::{
+	domain::addressing::SdPath,
+	infra::api::{ApiDispatcher, SessionContext},
+	ops::vaults::{
+		VaultAddFilesAction, VaultAddFilesInput, VaultCreateAction, VaultCreateInput,
+		VaultExportFilesAction, VaultExportFilesInput, VaultLockAction, VaultLockInput,
+		VaultUnlockAction, VaultUnlockInput,
+	},
+};
+use std::path::Path;
+
+fn session(harness: &IndexingHarness) -> SessionContext {
+	let device_id = sd_core::device::get_current_device_id();
+	let device_name = sd_core::device::get_current_device_slug();
+	SessionContext::device_session(device_id, device_name).with_library(harness.library.id())
+}
+
+fn files_under(dir: &Path) -> Vec<std::path::PathBuf> {
+	let mut files = Vec::new();
+	let mut stack = vec![dir.to_path_buf()];
+	while let Some(dir) = stack.pop() {
+		for entry in std::fs::read_dir(dir).unwrap() {
+			let path = entry.unwrap().path();
+			if path.is_dir() {
+				stack.push(path);
+			} else {
+				files.push(path);
+			}
+		}
+	}
+	files
+}
+
+#[tokio::test]
+async fn test_vault_round_trip() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("vault_round_trip")
+		.disable_watcher()
+		.build()
+		.await?;
+	let dispatcher = ApiDispatcher::permissive(harness.core.context.clone());
+	let root = harness.temp_path().to_path_buf();
+
+	let vault_dir = root.join("vault");
+	let created = dispatcher
+		.execute_library_action::<VaultCreateAction>(
+			VaultCreateInput {
+				path: SdPath::local(&vault_dir),
+				name: Some("Secrets".to_string()),
+				password: "correct horse".to_string(),
+			},
+			session(&harness),
+		)
+		.await?;
+
+	let contents: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+	let source = root.join("tax-return.pdf");
+	tokio::fs::write(&source, &contents).await?;
+
+	let added = dispatcher
+		.execute_library_action::<VaultAddFilesAction>(
+			VaultAddFilesInput {
+				location_id: created.location_id,
+				paths: vec![SdPath::local(&source)],
+				delete_source: true,
+			},
+			session(&harness),
+		)
+		.await?;
+	assert_eq!(added.added.len(), 1);
+	let entry = &added.added[0];
+	assert_eq!(entry.name, "tax-return.pdf");
+	assert_eq!(entry.size, contents.len() as u64);
+	assert!(!source.exists(), "The plaintext original is erased");
+
+	// Neither the name nor the contents are readable in the vault
+	for file in files_under(&vault_dir) {
+		let bytes = std::fs::read(&file)?;
+		assert!(!bytes.windows(14).any(|w| w == b"tax-return.pdf"));
+		assert!(!bytes.windows(64).any(|w| w == &contents[..64]));
+	}
+
+	let locked = dispatcher
+		.execute_library_action::<VaultLockAction>(VaultLockInput {}, session(&harness))
+		.await?;
+	assert!(locked.was_unlocked);
+
+	let destination = root.join("exported");
+	tokio::fs::create_dir_all(&destination).await?;
+	let export = || VaultExportFilesInput {
+		location_id: created.location_id,
+		entry_ids: vec![entry.id],
+		destination: SdPath::local(&destination),
+		remove_from_vault: false,
+	};
+
+	// Locked vaults can't be read, and a wrong password doesn't unlock them
+	assert!(dispatcher
+		.execute_library_action::<VaultExportFilesAction>(export(), session(&harness))
+		.await
+		.is_err());
+	assert!(dispatcher
+		.execute_library_action::<VaultUnlockAction>(
+			VaultUnlockInput {
+				password: "wrong".to_string(),
+			},
+			session(&harness),
+		)
+		.await
+		.is_err());
+
+	let unlocked = dispatcher
+		.execute_library_action::<VaultUnlockAction>(
+			VaultUnlockInput {
+				password: "correct horse".to_string(),
+			},
+			session(&harness),
+		)
+		.await?;
+	assert!(!unlocked.was_unlocked);
+
+	let exported = dispatcher
+		.execute_library_action::<VaultExportFilesAction>(export(), session(&harness))
+		.await?;
+	assert_eq!(exported.exported, vec![destination.join("tax-return.pdf")]);
+	assert_eq!(tokio::fs::read(&exported.exported[0]).await?, contents);
+
+	harness.shutdown().await?;
+	Ok(())
+}
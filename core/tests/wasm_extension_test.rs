@@ -1,10 +1,31 @@
 //! WASM Extension System Integration Test
 //!
-//! Tests that we can actually load and run WASM extensions.
+//! Tests that we can actually load and run WASM extensions, and that event handlers,
+//! schedules, job dispatch, notifications and media handlers work and respect extension
+//! permissions, and that startup handlers can call into the host.
+//! Hook tests use small WAT modules written into the extensions directory, and drive
+//! schedules and idle dispatch through a manual clock.
 
-use sd_core::Core;
-use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use sd_core::{
+	infra::{
+		action::CoreAction,
+		event::Event,
+		extension::{HookClock, JOB_DISPATCH_WHEN_IDLE},
+	},
+	ops::extensions::{ExtensionResumeAction, ExtensionResumeInput},
+	Core,
+};
+use serde_json::json;
+use std::{
+	path::{Path, PathBuf},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Mutex,
+	},
+};
 use tempfile::TempDir;
+use tokio::time::{timeout, Duration};
 
 #[tokio::test]
 async fn test_load_wasm_extension() {
@@ -82,3 +103,915 @@ async fn test_load_wasm_extension() {
 	tracing::info!("All checks passed!");
 	tracing::info!("WASM extension system works!");
 }
+
+/// Strings laid out in a WAT module's memory
+#[derive(Default)]
+struct WatStrings {
+	segments: String,
+	next: usize,
+}
+
+impl WatStrings {
+	/// Add a string, returning the `(ptr, len)` arguments that pass it to a host function
+	fn add(&mut self, value: &str) -> String {
+		self.add_bytes(value.as_bytes())
+	}
+
+	/// Add raw bytes, returning their `(ptr, len)` arguments
+	fn add_bytes(&mut self, value: &[u8]) -> String {
+		let offset = self.next;
+		let escaped: String = value.iter().map(|b| format!("\\{:02x}", b)).collect();
+		self.segments
+			.push_str(&format!("(data (i32.const {}) \"{}\")\n", offset, escaped));
+		self.next += value.len() + 1;
+		format!("(i32.const {}) (i32.const {})", offset, value.len())
+	}
+}
+
+/// A module importing the hook host functions, with a bump allocator for payloads
+fn hook_module(strings: &WatStrings, init: &str, exports: &str) -> String {
+	format!(
+		r#"(module
+	(import "spacedrive" "spacedrive_call" (func $spacedrive_call (param i32 i32 i32 i32 i32) (result i32)))
+	(import "spacedrive" "register_job" (func $register_job (param i32 i32 i32 i32 i32) (result i32)))
+	(import "spacedrive" "register_event_handler" (func $register_event_handler (param i32 i32 i32 i32 i32 i32) (result i32)))
+	(import "spacedrive" "register_schedule" (func $register_schedule (param i32 i32 i32 i32) (result i32)))
+	(import "spacedrive" "register_startup_handler" (func $register_startup_handler (param i32 i32) (result i32)))
+	(import "spacedrive" "job_dispatch" (func $job_dispatch (param i32 i32 i32 i32 i32 i32 i32) (result i32)))
+	(import "spacedrive" "notify" (func $notify (param i32 i32 i32 i32) (result i32)))
+	(import "spacedrive" "register_media_handler" (func $register_media_handler (param i32 i32 i32 i32 i32) (result i32)))
+	(import "spacedrive" "media_read" (func $media_read (param i64 i32 i32) (result i64)))
+	(import "spacedrive" "media_output" (func $media_output (param i32 i32) (result i32)))
+	(memory (export "memory") 4)
+	(global $heap (mut i32) (i32.const 65536))
+	{}
+	(func (export "wasm_alloc") (param $size i32) (result i32)
+		(local $ptr i32)
+		(local.set $ptr (global.get $heap))
+		(global.set $heap (i32.add (global.get $heap) (local.get $size)))
+		(local.get $ptr))
+	(func (export "plugin_init") (result i32)
+		{}
+		(i32.const 0))
+	{}
+)"#,
+		strings.segments, init, exports
+	)
+}
+
+fn write_extension(extensions_dir: &Path, id: &str, permissions: serde_json::Value, wat: &str) {
+	let dir = extensions_dir.join(id);
+	std::fs::create_dir_all(&dir).unwrap();
+
+	let manifest = json!({
+		"id": id,
+		"name": id,
+		"version": "0.1.0",
+		"description": "Hook test extension",
+		"author": "Spacedrive Team",
+		"homepage": null,
+		"wasm_file": "extension.wat",
+		"permissions": permissions,
+	});
+	std::fs::write(
+		dir.join("manifest.json"),
+		serde_json::to_string_pretty(&manifest).unwrap(),
+	)
+	.unwrap();
+	std::fs::write(dir.join("extension.wat"), wat).unwrap();
+}
+
+/// Wall clock and idleness the tests move by hand
+struct ManualClock {
+	now: Mutex<DateTime<Utc>>,
+	idle: AtomicBool,
+}
+
+impl ManualClock {
+	fn new() -> Arc<Self> {
+		Arc::new(Self {
+			now: Mutex::new(Utc::now()),
+			idle: AtomicBool::new(false),
+		})
+	}
+
+	fn advance(&self, by: chrono::Duration) {
+		*self.now.lock().unwrap() += by;
+	}
+
+	fn set_idle(&self, idle: bool) {
+		self.idle.store(idle, Ordering::SeqCst);
+	}
+}
+
+impl HookClock for ManualClock {
+	fn now(&self) -> DateTime<Utc> {
+		*self.now.lock().unwrap()
+	}
+
+	fn system_idle(&self) -> bool {
+		self.idle.load(Ordering::SeqCst)
+	}
+}
+
+/// Init and exports that notify "marker" for custom events of type "marker"
+fn marker_handler(strings: &mut WatStrings) -> (String, String) {
+	let custom = strings.add("Custom");
+	let on_marker = strings.add("on_marker");
+	let filters = strings.add(
+		&json!([{ "op": "equals", "field": "data.event_type", "value": "marker" }]).to_string(),
+	);
+	let marker_title = strings.add("marker");
+	(
+		format!(
+			"(if (call $register_event_handler {custom} {on_marker} {filters}) (then unreachable))"
+		),
+		format!(
+			r#"(func (export "on_marker") (param $ptr i32) (param $len i32) (result i32)
+		(call $notify {marker_title} (local.get $ptr) (local.get $len)))"#
+		),
+	)
+}
+
+/// Notifications received up to and including one titled `title`, as
+/// `(extension_id, title, message)`
+async fn notifications_until(
+	events: &mut sd_core::infra::event::EventSubscriber,
+	title: &str,
+) -> Vec<(String, Option<String>, String)> {
+	let mut notifications = Vec::new();
+	let received = timeout(Duration::from_secs(10), async {
+		loop {
+			match events.recv().await {
+				Ok(Event::ExtensionNotification {
+					extension_id,
+					title: notification_title,
+					message,
+				}) => {
+					let done = notification_title.as_deref() == Some(title);
+					notifications.push((extension_id, notification_title, message));
+					if done {
+						break;
+					}
+				}
+				Ok(_) => {}
+				Err(e) => panic!("Event bus closed: {}", e),
+			}
+		}
+	})
+	.await;
+	assert!(received.is_ok(), "no {} notification arrived", title);
+	notifications
+}
+
+/// Notifications for everything emitted so far, which the event loop handles in order
+async fn notifications_so_far(
+	core: &Core,
+	events: &mut sd_core::infra::event::EventSubscriber,
+) -> Vec<(String, Option<String>, String)> {
+	core.events.emit(Event::Custom {
+		event_type: "marker".to_string(),
+		data: json!({}),
+	});
+	let mut notifications = notifications_until(events, "marker").await;
+	notifications.pop();
+	notifications
+}
+
+#[tokio::test]
+async fn test_extension_hooks() {
+	let temp_dir = TempDir::new().unwrap();
+	let core = Core::new(temp_dir.path().to_path_buf()).await.unwrap();
+	let extensions_dir = temp_dir.path().join("extensions");
+
+	let pm = core.plugin_manager.as_ref().unwrap();
+	let hooks = pm.read().await.hooks();
+	let clock = ManualClock::new();
+	hooks.set_clock(clock.clone());
+
+	// Allowed: subscribes to "ping" custom events, ticks every minute and greets on startup
+	let mut strings = WatStrings::default();
+	let custom = strings.add("Custom");
+	let on_custom = strings.add("on_custom");
+	let filters = strings
+		.add(&json!([{ "op": "equals", "field": "data.event_type", "value": "ping" }]).to_string());
+	let interval = strings.add("interval:1m");
+	let on_tick = strings.add("on_tick");
+	let on_start = strings.add("on_start");
+	let startup_title = strings.add("startup");
+	let event_title = strings.add("event");
+	let tick_title = strings.add("tick");
+	let hello = strings.add("hello");
+	let init = format!(
+		"(if (call $register_event_handler {custom} {on_custom} {filters}) (then unreachable))
+		(if (call $register_schedule {interval} {on_tick}) (then unreachable))
+		(if (call $register_startup_handler {on_start}) (then unreachable))"
+	);
+	let exports = format!(
+		r#"(func (export "on_start") (param $ptr i32) (param $len i32) (result i32)
+		(call $notify {startup_title} {hello}))
+	(func (export "on_custom") (param $ptr i32) (param $len i32) (result i32)
+		(call $notify {event_title} (local.get $ptr) (local.get $len)))
+	(func (export "on_tick") (param $ptr i32) (param $len i32) (result i32)
+		(call $notify {tick_title} (local.get $ptr) (local.get $len)))"#
+	);
+	write_extension(
+		&extensions_dir,
+		"hooks-extension",
+		json!({
+			"methods": [],
+			"events": ["Custom"],
+			"schedules": true,
+			"notifications": true,
+		}),
+		&hook_module(&strings, &init, &exports),
+	);
+
+	// Denied: asks for the same hooks without permissions, so every registration must fail
+	let mut strings = WatStrings::default();
+	let custom = strings.add("Custom");
+	let on_custom = strings.add("on_custom");
+	let interval = strings.add("interval:1m");
+	let on_start = strings.add("on_start");
+	let hello = strings.add("hello");
+	let init = format!(
+		"(if (i32.eqz (call $register_event_handler {custom} {on_custom} (i32.const 0) (i32.const 0))) (then unreachable))
+		(if (i32.eqz (call $register_schedule {interval} {on_custom})) (then unreachable))
+		(if (call $register_startup_handler {on_start}) (then unreachable))"
+	);
+	let exports = format!(
+		r#"(func (export "on_start") (param $ptr i32) (param $len i32) (result i32)
+		(call $notify (i32.const 0) (i32.const 0) {hello}))
+	(func (export "on_custom") (param $ptr i32) (param $len i32) (result i32)
+		(call $notify (i32.const 0) (i32.const 0) {hello}))"#
+	);
+	write_extension(
+		&extensions_dir,
+		"denied-extension",
+		json!({ "methods": [] }),
+		&hook_module(&strings, &init, &exports),
+	);
+
+	// Marks the point every earlier event has been handled by
+	let mut strings = WatStrings::default();
+	let (init, exports) = marker_handler(&mut strings);
+	write_extension(
+		&extensions_dir,
+		"marker-extension",
+		json!({
+			"methods": [],
+			"events": ["Custom"],
+			"notifications": true,
+		}),
+		&hook_module(&strings, &init, &exports),
+	);
+
+	let mut events = core.events.subscribe();
+
+	for id in ["hooks-extension", "denied-extension", "marker-extension"] {
+		pm.write()
+			.await
+			.load_plugin(id)
+			.await
+			.unwrap_or_else(|e| panic!("Should load {}: {}", id, e));
+	}
+
+	core.events.emit(Event::Custom {
+		event_type: "ignored".to_string(),
+		data: json!({}),
+	});
+	core.events.emit(Event::Custom {
+		event_type: "ping".to_string(),
+		data: json!({ "n": 1 }),
+	});
+
+	let notifications = notifications_so_far(&core, &mut events).await;
+
+	// The denied extension's startup handler ran but couldn't notify
+	assert!(
+		notifications
+			.iter()
+			.all(|(extension_id, _, _)| extension_id == "hooks-extension"),
+		"denied extension should not notify"
+	);
+
+	let titled = |title: &str| {
+		notifications
+			.iter()
+			.filter(|(_, t, _)| t.as_deref() == Some(title))
+			.map(|(_, _, message)| message.clone())
+			.collect::<Vec<_>>()
+	};
+
+	assert_eq!(titled("startup"), vec!["hello".to_string()]);
+
+	// The handler gets the event context, and the filter drops other custom events
+	let delivered = titled("event");
+	assert_eq!(
+		delivered.len(),
+		1,
+		"only the ping event should be delivered"
+	);
+	let context: serde_json::Value = serde_json::from_str(&delivered[0]).unwrap();
+	assert_eq!(context["type"], "Custom");
+	assert_eq!(context["data"]["event_type"], "ping");
+	assert_eq!(context["data"]["data"]["n"], 1);
+
+	// The schedule fires once its interval has passed on the hooks' clock
+	assert!(titled("tick").is_empty(), "the schedule isn't due yet");
+	clock.advance(chrono::Duration::minutes(1));
+	hooks.tick(&core.context).await;
+	let ticks = notifications_until(&mut events, "tick").await;
+	assert_eq!(ticks.len(), 1);
+	let tick: serde_json::Value = serde_json::from_str(&ticks[0].2).unwrap();
+	assert_eq!(tick["trigger"]["type"], "interval");
+
+	// Unloading removes the extension's hooks
+	pm.write()
+		.await
+		.unload_plugin("hooks-extension")
+		.await
+		.unwrap();
+	core.events.emit(Event::Custom {
+		event_type: "ping".to_string(),
+		data: json!({}),
+	});
+	clock.advance(chrono::Duration::minutes(5));
+	hooks.tick(&core.context).await;
+	let after_unload = notifications_so_far(&core, &mut events).await;
+	assert!(after_unload.is_empty(), "unloaded extension should not run");
+}
+
+#[tokio::test]
+async fn test_extension_startup_host_call() {
+	let temp_dir = TempDir::new().unwrap();
+	let core = Core::new(temp_dir.path().to_path_buf()).await.unwrap();
+	let extensions_dir = temp_dir.path().join("extensions");
+
+	// Lists libraries through the host on startup, then reports back
+	let mut strings = WatStrings::default();
+	let on_start = strings.add("on_start");
+	let method = strings.add("query:libraries.list");
+	let payload = strings.add(&json!({ "include_stats": false }).to_string());
+	let title = strings.add("startup");
+	let called = strings.add("called");
+	let init = format!("(if (call $register_startup_handler {on_start}) (then unreachable))");
+	let exports = format!(
+		r#"(func (export "on_start") (param $ptr i32) (param $len i32) (result i32)
+		(if (i32.eqz (call $spacedrive_call {method} (i32.const 0) {payload}))
+			(then (return (i32.const 1))))
+		(call $notify {title} {called}))"#
+	);
+	write_extension(
+		&extensions_dir,
+		"startup-call-extension",
+		json!({ "methods": ["query:libraries."], "notifications": true }),
+		&hook_module(&strings, &init, &exports),
+	);
+
+	let mut events = core.events.subscribe();
+	let pm = core.plugin_manager.as_ref().unwrap();
+	pm.write()
+		.await
+		.load_plugin("startup-call-extension")
+		.await
+		.expect("Should load startup-call-extension");
+
+	let notifications = notifications_until(&mut events, "startup").await;
+	assert_eq!(
+		notifications.last().unwrap(),
+		&(
+			"startup-call-extension".to_string(),
+			Some("startup".to_string()),
+			"called".to_string()
+		)
+	);
+}
+
+/// A module that dispatches its counter job into each library that is created, with
+/// `flags` passed to `job_dispatch`
+fn dispatch_module(flags: u32) -> String {
+	let mut strings = WatStrings::default();
+	let counter = strings.add("counter");
+	let execute_counter = strings.add("execute_counter");
+	let library_created = strings.add("LibraryCreated");
+	let on_library = strings.add("on_library");
+	let args = strings.add(&json!({ "current": 0, "target": 3 }).to_string());
+	let (marker_init, marker_exports) = marker_handler(&mut strings);
+	let init = format!(
+		"(if (call $register_job {counter} {execute_counter} (i32.const 1)) (then unreachable))
+		(if (call $register_event_handler {library_created} {on_library} (i32.const 0) (i32.const 0)) (then unreachable))
+		{marker_init}"
+	);
+	let exports = format!(
+		r#"(func (export "on_library") (param $ptr i32) (param $len i32) (result i32)
+		(call $job_dispatch {counter} (i32.const 0) {args} (i32.const 0) (i32.const {flags})))
+	(func (export "execute_counter") (param i32 i32 i32 i32) (result i32)
+		(i32.const 0))
+	{marker_exports}"#
+	);
+	hook_module(&strings, &init, &exports)
+}
+
+async fn load_dispatch_extension(core: &Core, temp_dir: &TempDir, flags: u32) {
+	write_extension(
+		&temp_dir.path().join("extensions"),
+		"dispatch-extension",
+		json!({
+			"methods": [],
+			"events": ["LibraryCreated", "Custom"],
+			"dispatch_jobs": true,
+			"notifications": true,
+		}),
+		&dispatch_module(flags),
+	);
+
+	core.plugin_manager
+		.as_ref()
+		.unwrap()
+		.write()
+		.await
+		.load_plugin("dispatch-extension")
+		.await
+		.expect("Should load dispatch-extension");
+}
+
+/// Wait for the counter job to start or finish
+async fn counter_dispatched(events: &mut sd_core::infra::event::EventSubscriber) -> bool {
+	timeout(Duration::from_secs(10), async {
+		loop {
+			match events.recv().await {
+				Ok(Event::JobStarted { job_type, .. } | Event::JobCompleted { job_type, .. })
+					if job_type == "dispatch-extension:counter" =>
+				{
+					break
+				}
+				Ok(_) => {}
+				Err(e) => panic!("Event bus closed: {}", e),
+			}
+		}
+	})
+	.await
+	.is_ok()
+}
+
+#[tokio::test]
+async fn test_extension_job_dispatch() {
+	let temp_dir = TempDir::new().unwrap();
+	let core = Core::new(temp_dir.path().to_path_buf()).await.unwrap();
+	load_dispatch_extension(&core, &temp_dir, 0).await;
+
+	let mut events = core.events.subscribe();
+	core.libraries
+		.create_library("Extension Jobs", None, core.context.clone())
+		.await
+		.unwrap();
+
+	assert!(
+		counter_dispatched(&mut events).await,
+		"extension job should have been dispatched"
+	);
+}
+
+#[tokio::test]
+async fn test_extension_idle_job_dispatch() {
+	let temp_dir = TempDir::new().unwrap();
+	let core = Core::new(temp_dir.path().to_path_buf()).await.unwrap();
+	let hooks = core.plugin_manager.as_ref().unwrap().read().await.hooks();
+	let clock = ManualClock::new();
+	hooks.set_clock(clock.clone());
+	load_dispatch_extension(&core, &temp_dir, JOB_DISPATCH_WHEN_IDLE).await;
+
+	let mut events = core.events.subscribe();
+	let library = core
+		.libraries
+		.create_library("Idle Jobs", None, core.context.clone())
+		.await
+		.unwrap();
+	let created_again = Event::LibraryCreated {
+		id: library.id(),
+		name: library.name().await,
+		path: library.path().to_path_buf(),
+		source: Default::default(),
+	};
+
+	// The same job asked for twice waits once
+	core.events.emit(created_again.clone());
+	notifications_so_far(&core, &mut events).await;
+	let waiting = hooks.idle_dispatches();
+	assert_eq!(waiting.len(), 1);
+	assert_eq!(waiting[0].library_id, library.id());
+	assert_eq!(waiting[0].job_name, "dispatch-extension:counter");
+
+	// Nothing runs while the system is busy
+	clock.advance(chrono::Duration::minutes(1));
+	hooks.tick(&core.context).await;
+	assert_eq!(hooks.idle_dispatches().len(), 1);
+
+	clock.set_idle(true);
+	clock.advance(chrono::Duration::minutes(1));
+	hooks.tick(&core.context).await;
+	assert!(
+		counter_dispatched(&mut events).await,
+		"extension job should have been dispatched once idle"
+	);
+	assert!(hooks.idle_dispatches().is_empty());
+
+	// Closing a library drops the jobs waiting to run in it
+	clock.set_idle(false);
+	let other = core
+		.libraries
+		.create_library("Closed Before Idle", None, core.context.clone())
+		.await
+		.unwrap();
+	notifications_so_far(&core, &mut events).await;
+	let waiting = hooks.idle_dispatches();
+	assert_eq!(waiting.len(), 1);
+	assert_eq!(waiting[0].library_id, other.id());
+	core.libraries.close_library(other.id()).await.unwrap();
+	notifications_so_far(&core, &mut events).await;
+	assert!(hooks.idle_dispatches().is_empty());
+
+	// Unloading the extension drops its waiting jobs
+	core.events.emit(created_again);
+	notifications_so_far(&core, &mut events).await;
+	assert_eq!(hooks.idle_dispatches().len(), 1);
+	core.plugin_manager
+		.as_ref()
+		.unwrap()
+		.write()
+		.await
+		.unload_plugin("dispatch-extension")
+		.await
+		.unwrap();
+	assert!(hooks.idle_dispatches().is_empty());
+}
+
+#[tokio::test]
+async fn test_extension_resource_limits() {
+	let temp_dir = TempDir::new().unwrap();
+	let core = Core::new(temp_dir.path().to_path_buf()).await.unwrap();
+	let extensions_dir = temp_dir.path().join("extensions");
+
+	// Spins forever on startup, so it runs out of fuel
+	let mut strings = WatStrings::default();
+	let on_start = strings.add("on_start");
+	let init = format!("(if (call $register_startup_handler {on_start}) (then unreachable))");
+	let exports = r#"(func (export "on_start") (param $ptr i32) (param $len i32) (result i32)
+		(loop $spin (br $spin))
+		(i32.const 0))
+	(func (export "ping") (param $ptr i32) (param $len i32) (result i32)
+		(i32.const 7))"#;
+	write_extension(
+		&extensions_dir,
+		"spinning-extension",
+		json!({ "methods": [], "max_fuel_per_call": 1_000_000 }),
+		&hook_module(&strings, &init, exports),
+	);
+
+	// Tries to grow its memory past a 1 MB cap
+	let exports = r#"(func (export "grow") (param $ptr i32) (param $len i32) (result i32)
+		(memory.grow (i32.const 100)))"#;
+	write_extension(
+		&extensions_dir,
+		"capped-extension",
+		json!({ "methods": [], "max_memory_mb": 1 }),
+		&hook_module(&WatStrings::default(), "", exports),
+	);
+
+	// Needs more memory than its cap to start at all
+	write_extension(
+		&extensions_dir,
+		"oversized-extension",
+		json!({ "methods": [], "max_memory_mb": 1 }),
+		r#"(module (memory (export "memory") 32))"#,
+	);
+
+	let pm = core.plugin_manager.as_ref().unwrap();
+	let mut events = core.events.subscribe();
+
+	pm.write()
+		.await
+		.load_plugin("spinning-extension")
+		.await
+		.expect("Should load spinning-extension");
+
+	let suspended = timeout(Duration::from_secs(5), async {
+		loop {
+			match events.recv().await {
+				Ok(Event::ExtensionSuspended {
+					extension_id,
+					reason,
+				}) if extension_id == "spinning-extension" => break reason,
+				Ok(_) => {}
+				Err(e) => panic!("Event bus closed: {}", e),
+			}
+		}
+	})
+	.await
+	.expect("spinning extension should have been suspended");
+	assert!(
+		suspended.contains("fuel"),
+		"unexpected reason: {}",
+		suspended
+	);
+
+	let usage = pm.read().await.resource_usage();
+	let spinning = usage
+		.iter()
+		.find(|usage| usage.extension_id == "spinning-extension")
+		.unwrap();
+	assert_eq!(spinning.last_call_fuel, 1_000_000);
+	assert_eq!(
+		spinning.suspended_reason.as_deref(),
+		Some(suspended.as_str())
+	);
+
+	// Suspended extensions aren't called until they're resumed
+	let result = pm
+		.write()
+		.await
+		.call_handler("spinning-extension", "ping", &json!({}), None);
+	assert!(matches!(
+		result,
+		Err(sd_core::infra::extension::PluginError::Suspended(..))
+	));
+	let action_manager = core.context.get_action_manager().await.unwrap();
+	let resume = || {
+		ExtensionResumeAction::from_input(ExtensionResumeInput {
+			extension_id: "spinning-extension".to_string(),
+		})
+		.unwrap()
+	};
+	let resumed = action_manager.dispatch_core(resume()).await.unwrap();
+	assert!(resumed.resumed);
+	let resumed_again = action_manager.dispatch_core(resume()).await.unwrap();
+	assert!(
+		!resumed_again.resumed,
+		"the extension is no longer suspended"
+	);
+	let pinged = pm
+		.write()
+		.await
+		.call_handler("spinning-extension", "ping", &json!({}), None)
+		.expect("resumed extension should run");
+	assert_eq!(pinged, 7);
+	let usage = pm.read().await.resource_usage();
+	assert!(usage
+		.iter()
+		.find(|usage| usage.extension_id == "spinning-extension")
+		.unwrap()
+		.suspended_reason
+		.is_none());
+
+	// Memory can't grow past the cap
+	pm.write()
+		.await
+		.load_plugin("capped-extension")
+		.await
+		.expect("Should load capped-extension");
+	let grown = pm
+		.write()
+		.await
+		.call_handler("capped-extension", "grow", &json!({}), None)
+		.unwrap();
+	assert_eq!(grown, -1, "memory.grow should fail at the cap");
+
+	let usage = pm.read().await.resource_usage();
+	let capped = usage
+		.iter()
+		.find(|usage| usage.extension_id == "capped-extension")
+		.unwrap();
+	assert_eq!(capped.memory_bytes, 4 * 64 * 1024);
+	assert_eq!(capped.max_memory_bytes, 1024 * 1024);
+	assert!(capped.suspended_reason.is_none());
+
+	assert!(pm
+		.write()
+		.await
+		.load_plugin("oversized-extension")
+		.await
+		.is_err());
+}
+
+#[tokio::test]
+async fn test_extension_media_handlers() {
+	use sd_core::{
+		infra::{
+			db::entities::content_identity,
+			extension::{MediaHandlerKind, PluginError},
+		},
+		ops::{
+			media::{extract_extension_metadata, thumbnail::ThumbnailGenerator},
+			sidecar::types::{SidecarFormat, SidecarKind, SidecarVariant},
+		},
+	};
+	use sea_orm::{ActiveModelTrait, Set};
+
+	let temp_dir = TempDir::new().unwrap();
+	let core = Core::new(temp_dir.path().to_path_buf()).await.unwrap();
+	let extensions_dir = temp_dir.path().join("extensions");
+
+	let mut png = std::io::Cursor::new(Vec::new());
+	image::DynamicImage::new_rgb8(64, 32)
+		.write_to(&mut png, image::ImageFormat::Png)
+		.unwrap();
+	let png = png.into_inner();
+
+	// Renders .cad files by reading them with media_read (they're PNGs in disguise), renders
+	// every sketch as the same PNG and extracts constant metadata from .cad files
+	let mut strings = WatStrings::default();
+	let thumbnail_patterns = strings.add(r#"[".cad"]"#);
+	let thumbnailer_cad = strings.add("thumbnailer_cad");
+	let sketch_patterns = strings.add(r#"["image/x-sketch"]"#);
+	let thumbnailer_sketch = strings.add("thumbnailer_sketch");
+	let sketch_render = strings.add_bytes(&png);
+	let metadata_patterns = strings.add(r#"["model/x-cad", ".cad"]"#);
+	let metadata_cad = strings.add("metadata_cad");
+	let metadata = strings.add(&json!({ "format": "cad", "layers": 3 }).to_string());
+	let init = format!(
+		"(if (call $register_media_handler (i32.const 0) {thumbnail_patterns} {thumbnailer_cad}) (then unreachable))
+		(if (call $register_media_handler (i32.const 0) {sketch_patterns} {thumbnailer_sketch}) (then unreachable))
+		(if (call $register_media_handler (i32.const 1) {metadata_patterns} {metadata_cad}) (then unreachable))"
+	);
+	let exports = format!(
+		r#"(func (export "thumbnailer_cad") (param i32 i32 i32 i32) (result i32)
+		(local $read i64)
+		(local.set $read (call $media_read (i64.const 0) (i32.const 131072) (i32.const 65536)))
+		(if (i64.lt_s (local.get $read) (i64.const 1)) (then (return (i32.const 1))))
+		(call $media_output (i32.const 131072) (i32.wrap_i64 (local.get $read))))
+	(func (export "thumbnailer_sketch") (param i32 i32 i32 i32) (result i32)
+		(call $media_output {sketch_render}))
+	(func (export "metadata_cad") (param i32 i32 i32 i32) (result i32)
+		(call $media_output {metadata}))
+	(func (export "broken_cad") (param i32 i32 i32 i32) (result i32)
+		(i32.const 0))"#
+	);
+	write_extension(
+		&extensions_dir,
+		"cad-extension",
+		json!({ "methods": [], "media_handlers": true }),
+		&hook_module(&strings, &init, &exports),
+	);
+
+	// Not allowed to register media handlers
+	let mut strings = WatStrings::default();
+	let patterns = strings.add(r#"[".dwg"]"#);
+	let thumbnailer_dwg = strings.add("thumbnailer_dwg");
+	let init = format!(
+		"(if (i32.eqz (call $register_media_handler (i32.const 0) {patterns} {thumbnailer_dwg})) (then unreachable))"
+	);
+	write_extension(
+		&extensions_dir,
+		"denied-extension",
+		json!({ "methods": [] }),
+		&hook_module(&strings, &init, ""),
+	);
+
+	let pm = core.plugin_manager.as_ref().unwrap();
+	for id in ["cad-extension", "denied-extension"] {
+		pm.write()
+			.await
+			.load_plugin(id)
+			.await
+			.unwrap_or_else(|e| panic!("Should load {}: {}", id, e));
+	}
+
+	let handlers = pm.read().await.media_handlers();
+	let thumbnailer = handlers
+		.find(MediaHandlerKind::Thumbnailer, None, Some("cad"))
+		.expect("cad thumbnailer should be registered");
+	assert_eq!(thumbnailer.extension_id, "cad-extension");
+	assert!(handlers
+		.find(MediaHandlerKind::Thumbnailer, None, Some("dwg"))
+		.is_none());
+
+	let library = core
+		.libraries
+		.create_library("Extension Media", None, core.context.clone())
+		.await
+		.unwrap();
+
+	let source_path = temp_dir.path().join("part.cad");
+	std::fs::write(&source_path, &png).unwrap();
+
+	// Extension thumbnailers are picked for files no built-in generator handles
+	let generator = ThumbnailGenerator::for_file(&library, None, Some("cad"))
+		.await
+		.unwrap();
+	assert!(matches!(generator, ThumbnailGenerator::Extension(_)));
+	let output_path = temp_dir.path().join("thumbs").join("part.webp");
+	let info = generator
+		.generate(&source_path, &output_path, 32, 80)
+		.await
+		.expect("extension thumbnail should be generated");
+	assert_eq!(info.dimensions, (32, 16));
+	assert_eq!(info.format, "webp");
+	assert!(output_path.exists());
+
+	assert!(ThumbnailGenerator::for_file(&library, None, Some("dwg"))
+		.await
+		.is_err());
+
+	// Images go to the built-in generator first, and the extension renders the ones it
+	// can't decode
+	let generator = ThumbnailGenerator::for_file(&library, Some("image/x-sketch"), Some("sketch"))
+		.await
+		.unwrap();
+	assert!(matches!(
+		&generator,
+		ThumbnailGenerator::Fallback(first, second)
+			if matches!(**first, ThumbnailGenerator::Image(_))
+				&& matches!(**second, ThumbnailGenerator::Extension(_))
+	));
+	let sketch_path = temp_dir.path().join("drawing.sketch");
+	std::fs::write(&sketch_path, b"not an image").unwrap();
+	let info = generator
+		.generate(
+			&sketch_path,
+			&temp_dir.path().join("thumbs").join("drawing.webp"),
+			32,
+			80,
+		)
+		.await
+		.expect("the extension should render what the built-in generator can't");
+	assert_eq!(info.dimensions, (32, 16));
+
+	// Metadata is stored as a sidecar named after the extension, once
+	let content_uuid = uuid::Uuid::new_v4();
+	content_identity::ActiveModel {
+		uuid: Set(Some(content_uuid)),
+		content_hash: Set("extension-media".to_string()),
+		kind_id: Set(0),
+		total_size: Set(0),
+		entry_count: Set(1),
+		first_seen_at: Set(chrono::Utc::now()),
+		last_verified_at: Set(chrono::Utc::now()),
+		..Default::default()
+	}
+	.insert(library.db().conn())
+	.await
+	.unwrap();
+
+	let extracted = extract_extension_metadata(
+		&library,
+		content_uuid,
+		&source_path,
+		Some("model/x-cad"),
+		Some("cad"),
+	)
+	.await
+	.unwrap();
+	assert_eq!(extracted.as_deref(), Some("cad-extension"));
+
+	let sidecar_path = core
+		.context
+		.get_sidecar_manager()
+		.await
+		.unwrap()
+		.compute_path(
+			&library.id(),
+			&content_uuid,
+			&SidecarKind::Metadata,
+			&SidecarVariant::new("cad-extension"),
+			&SidecarFormat::Json,
+		)
+		.await
+		.unwrap();
+	let stored: serde_json::Value =
+		serde_json::from_slice(&std::fs::read(&sidecar_path.absolute_path).unwrap()).unwrap();
+	assert_eq!(stored, json!({ "format": "cad", "layers": 3 }));
+
+	let extracted = extract_extension_metadata(
+		&library,
+		content_uuid,
+		&source_path,
+		Some("model/x-cad"),
+		Some("cad"),
+	)
+	.await
+	.unwrap();
+	assert!(
+		extracted.is_none(),
+		"existing metadata shouldn't be extracted again"
+	);
+
+	// A handler that returns without handing anything back fails
+	let mut broken = thumbnailer.clone();
+	broken.export_fn = "broken_cad".to_string();
+	let result =
+		pm.write()
+			.await
+			.call_media_handler(&broken, &source_path, &json!({}), Some(library.id()));
+	assert!(matches!(result, Err(PluginError::CallFailed(..))));
+
+	// Unloading removes the extension's handlers
+	pm.write()
+		.await
+		.unload_plugin("cad-extension")
+		.await
+		.unwrap();
+	assert!(handlers
+		.find(MediaHandlerKind::Thumbnailer, None, Some("cad"))
+		.is_none());
+}
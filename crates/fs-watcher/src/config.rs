@@ -12,6 +12,9 @@ pub struct WatchConfig {
 	pub recursive: bool,
 	/// Rules for filtering events
 	pub filters: EventFilters,
+	/// How changes under the path are detected
+	#[serde(default)]
+	pub backend: WatchBackend,
 }
 
 impl Default for WatchConfig {
@@ -19,6 +22,7 @@ impl Default for WatchConfig {
 		Self {
 			recursive: true,
 			filters: EventFilters::default(),
+			backend: WatchBackend::Native,
 		}
 	}
 }
@@ -29,6 +33,7 @@ impl WatchConfig {
 		Self {
 			recursive: true,
 			filters: EventFilters::default(),
+			backend: WatchBackend::Native,
 		}
 	}
 
@@ -37,6 +42,7 @@ impl WatchConfig {
 		Self {
 			recursive: false,
 			filters: EventFilters::default(),
+			backend: WatchBackend::Native,
 		}
 	}
 
@@ -51,6 +57,23 @@ impl WatchConfig {
 		self.filters = filters;
 		self
 	}
+
+	/// Set how changes are detected
+	pub fn with_backend(mut self, backend: WatchBackend) -> Self {
+		self.backend = backend;
+		self
+	}
+}
+
+/// How changes under a watched path are detected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WatchBackend {
+	/// OS change notifications (inotify, FSEvents, ReadDirectoryChangesW)
+	#[default]
+	Native,
+	/// Periodic directory scans, for network shares and FUSE mounts where the OS
+	/// doesn't report changes made by other machines
+	Polling,
 }
 
 /// Filters for which events to emit
@@ -165,6 +188,8 @@ pub struct WatcherConfig {
 	pub debounce_duration: Duration,
 	/// Enable detailed debug logging
 	pub debug_mode: bool,
+	/// Cadence for paths watched with `WatchBackend::Polling`
+	pub polling: PollingConfig,
 }
 
 impl Default for WatcherConfig {
@@ -174,6 +199,7 @@ impl Default for WatcherConfig {
 			tick_interval: Duration::from_millis(100),
 			debounce_duration: Duration::from_millis(100),
 			debug_mode: false,
+			polling: PollingConfig::default(),
 		}
 	}
 }
@@ -207,6 +233,60 @@ impl WatcherConfig {
 		self.debug_mode = debug;
 		self
 	}
+
+	/// Set the polling cadence
+	pub fn with_polling(mut self, polling: PollingConfig) -> Self {
+		self.polling = polling;
+		self
+	}
+}
+
+/// Cadence for polled watches
+///
+/// Each directory is polled on its own schedule: one that just changed is polled again
+/// after `min_interval`, and each quiet poll doubles its interval up to `max_interval`.
+#[derive(Debug, Clone)]
+pub struct PollingConfig {
+	/// Interval a directory is first polled at
+	pub interval: Duration,
+	/// Interval for directories that just changed
+	pub min_interval: Duration,
+	/// Interval quiet directories back off to
+	pub max_interval: Duration,
+}
+
+impl Default for PollingConfig {
+	fn default() -> Self {
+		Self {
+			interval: Duration::from_secs(5),
+			min_interval: Duration::from_secs(1),
+			max_interval: Duration::from_secs(60),
+		}
+	}
+}
+
+impl PollingConfig {
+	/// Set the interval directories are first polled at
+	pub fn with_interval(mut self, interval: Duration) -> Self {
+		self.interval = interval;
+		self
+	}
+
+	/// Set the fastest and slowest polling intervals
+	pub fn with_bounds(mut self, min_interval: Duration, max_interval: Duration) -> Self {
+		self.min_interval = min_interval;
+		self.max_interval = max_interval;
+		self
+	}
+
+	/// Interval for a directory after a poll, given whether the poll found changes
+	pub(crate) fn next_interval(&self, current: Duration, changed: bool) -> Duration {
+		if changed {
+			self.min_interval
+		} else {
+			(current * 2).min(self.max_interval).max(self.min_interval)
+		}
+	}
 }
 
 #[cfg(test)]
@@ -254,5 +334,25 @@ mod tests {
 
 		let config = WatchConfig::shallow();
 		assert!(!config.recursive);
+		assert_eq!(config.backend, WatchBackend::Native);
+
+		let config = WatchConfig::recursive().with_backend(WatchBackend::Polling);
+		assert_eq!(config.backend, WatchBackend::Polling);
+	}
+
+	#[test]
+	fn test_polling_backoff() {
+		let config =
+			PollingConfig::default().with_bounds(Duration::from_secs(1), Duration::from_secs(8));
+
+		// Quiet directories back off up to the maximum
+		let mut interval = Duration::from_secs(1);
+		for expected in [2, 4, 8, 8] {
+			interval = config.next_interval(interval, false);
+			assert_eq!(interval, Duration::from_secs(expected));
+		}
+
+		// A change makes the directory hot again
+		assert_eq!(config.next_interval(interval, true), Duration::from_secs(1));
 	}
 }
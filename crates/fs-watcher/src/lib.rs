@@ -10,6 +10,8 @@
 //!
 //! - **FsWatcher**: Main interface for watching paths and receiving events
 //! - **PlatformHandler**: Platform-specific event processing (rename detection, buffering)
+//! - **PollingHandler**: Directory snapshot diffing for paths the OS doesn't report changes
+//!   for, such as network shares and FUSE mounts
 //! - **FsEvent/FsEventKind**: Normalized, storage-agnostic event types
 //!
 //! # Key Features
@@ -17,6 +19,7 @@
 //! - **Storage Agnostic**: No knowledge of databases, libraries, or UUIDs
 //! - **Rename Detection**: Handles macOS FSEvents rename quirks via inode tracking
 //! - **Event Filtering**: Built-in filtering for temp files, hidden files, etc.
+//! - **Polling Backend**: `WatchBackend::Polling` with adaptive per-directory intervals
 //! - **Reference Counting**: Multiple watchers on the same path share resources
 //! - **Broadcast Events**: Multiple subscribers can receive events concurrently
 //!
@@ -59,10 +62,10 @@ mod event;
 mod platform;
 mod watcher;
 
-pub use config::{EventFilters, WatchConfig, WatcherConfig};
+pub use config::{EventFilters, PollingConfig, WatchBackend, WatchConfig, WatcherConfig};
 pub use error::{Result, WatcherError};
 pub use event::{FsEvent, FsEventKind, RawEventKind, RawNotifyEvent};
-pub use platform::{EventHandler, PlatformHandler};
+pub use platform::{EventHandler, PlatformHandler, PollingHandler};
 pub use watcher::{FsWatcher, WatchHandle};
 
 // Re-export notify types that users might need
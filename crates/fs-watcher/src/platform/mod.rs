@@ -8,6 +8,10 @@
 //! - Event buffering and debouncing
 //! - Platform-specific quirk handling
 //!
+//! `PollingHandler` is the exception: it serves paths watched with `WatchBackend::Polling`
+//! on every platform, producing events by diffing directory snapshots instead of
+//! translating OS events.
+//!
 //! Platform handlers are storage-agnostic - they return raw events without
 //! any knowledge of locations, libraries, or databases.
 
@@ -18,6 +22,7 @@ use crate::Result;
 mod linux;
 #[cfg(target_os = "macos")]
 mod macos;
+mod polling;
 #[cfg(target_os = "windows")]
 mod windows;
 
@@ -25,6 +30,7 @@ mod windows;
 pub use linux::LinuxHandler;
 #[cfg(target_os = "macos")]
 pub use macos::MacOsHandler;
+pub use polling::PollingHandler;
 #[cfg(target_os = "windows")]
 pub use windows::WindowsHandler;
 
@@ -0,0 +1,471 @@
+//! Polling event handler
+//!
+//! OS change notifications only cover changes made through the local kernel, so edits
+//! made by other machines on NFS/SMB shares, and by most FUSE filesystems, never arrive.
+//! For paths watched with `WatchBackend::Polling` this handler keeps a snapshot of every
+//! directory and diffs it against the disk on each tick, emitting the same events the
+//! native handlers do.
+//!
+//! A directory is only listed again when its mtime moves (an entry was added, removed or
+//! renamed); otherwise its files are re-stat'ed to catch in-place edits. Renames can't be
+//! told apart from a remove and a create, so they're reported that way.
+
+use crate::config::{EventFilters, PollingConfig};
+use crate::event::{FsEvent, FsEventKind, RawNotifyEvent};
+use crate::platform::EventHandler;
+use crate::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{debug, trace};
+
+/// Directories modified this close to their last listing are listed again on the next
+/// poll, as coarse mtimes (2s on FAT, 1s on some NFS servers) can hide a second change
+const MTIME_GRANULARITY: Duration = Duration::from_secs(2);
+
+/// What a poll remembers about a directory entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EntrySnapshot {
+	is_dir: bool,
+	size: u64,
+	modified: Option<SystemTime>,
+}
+
+impl EntrySnapshot {
+	fn from_metadata(metadata: &fs::Metadata) -> Self {
+		Self {
+			is_dir: metadata.is_dir(),
+			size: metadata.len(),
+			modified: metadata.modified().ok(),
+		}
+	}
+}
+
+/// A polled directory and its schedule
+struct PolledDir {
+	modified: Option<SystemTime>,
+	listed_at: SystemTime,
+	entries: HashMap<PathBuf, EntrySnapshot>,
+	interval: Duration,
+	next_poll: Instant,
+}
+
+/// A watched path and the directories polled under it
+struct PolledRoot {
+	path: PathBuf,
+	recursive: bool,
+	filters: EventFilters,
+	/// Empty until the first tick takes the baseline snapshot
+	dirs: HashMap<PathBuf, PolledDir>,
+}
+
+impl PolledRoot {
+	/// Poll every directory that's due, pushing the changes found onto `events`
+	fn poll(&mut self, config: &PollingConfig, now: Instant, events: &mut Vec<FsEvent>) {
+		if self.dirs.is_empty() {
+			self.snapshot_tree(self.path.clone(), config, now);
+			return;
+		}
+
+		let due: Vec<PathBuf> = self
+			.dirs
+			.iter()
+			.filter(|(_, dir)| dir.next_poll <= now)
+			.map(|(path, _)| path.clone())
+			.collect();
+
+		for path in due {
+			self.poll_dir(&path, config, now, events);
+		}
+	}
+
+	fn poll_dir(
+		&mut self,
+		path: &Path,
+		config: &PollingConfig,
+		now: Instant,
+		events: &mut Vec<FsEvent>,
+	) {
+		// Already dropped if an earlier directory in this pass found it removed
+		let Some(dir) = self.dirs.get_mut(path) else {
+			return;
+		};
+
+		let modified = match fs::metadata(path) {
+			Ok(metadata) => metadata.modified().ok(),
+			Err(e) => {
+				// A removed directory is reported by its parent; an unreachable share just waits
+				trace!("Skipping poll of {}: {}", path.display(), e);
+				dir.interval = config.next_interval(dir.interval, false);
+				dir.next_poll = now + dir.interval;
+				return;
+			}
+		};
+
+		let relist = match (modified, dir.modified) {
+			(Some(modified), Some(previous)) => {
+				modified != previous || modified + MTIME_GRANULARITY >= dir.listed_at
+			}
+			_ => true,
+		};
+
+		let entries = if relist {
+			match list_dir(path, &self.filters) {
+				Ok(entries) => entries,
+				Err(e) => {
+					trace!("Failed to list {}: {}", path.display(), e);
+					dir.interval = config.next_interval(dir.interval, false);
+					dir.next_poll = now + dir.interval;
+					return;
+				}
+			}
+		} else {
+			// Same entries as before, so only files need a fresh stat
+			dir.entries
+				.iter()
+				.filter_map(|(child, previous)| {
+					if previous.is_dir {
+						return Some((child.clone(), *previous));
+					}
+					fs::symlink_metadata(child)
+						.ok()
+						.map(|metadata| (child.clone(), EntrySnapshot::from_metadata(&metadata)))
+				})
+				.collect()
+		};
+
+		let reported = events.len();
+		let (added_dirs, removed_dirs) = diff_entries(&dir.entries, &entries, events);
+		let changed = events.len() > reported;
+
+		dir.entries = entries;
+		dir.modified = modified;
+		if relist {
+			dir.listed_at = SystemTime::now();
+		}
+		dir.interval = config.next_interval(dir.interval, changed);
+		dir.next_poll = now + dir.interval;
+
+		for removed in removed_dirs {
+			self.dirs.retain(|path, _| !path.starts_with(&removed));
+		}
+
+		// New folders are indexed as a whole when their create event is handled, so their
+		// contents are snapshotted without emitting events of their own
+		if self.recursive {
+			for added in added_dirs {
+				self.snapshot_tree(added, config, now);
+			}
+		}
+	}
+
+	/// Take a snapshot of a directory, and its subdirectories for recursive watches
+	fn snapshot_tree(&mut self, path: PathBuf, config: &PollingConfig, now: Instant) {
+		let mut pending = vec![path];
+
+		while let Some(path) = pending.pop() {
+			let modified = fs::metadata(&path).ok().and_then(|m| m.modified().ok());
+			let entries = match list_dir(&path, &self.filters) {
+				Ok(entries) => entries,
+				Err(e) => {
+					debug!("Failed to snapshot {}: {}", path.display(), e);
+					continue;
+				}
+			};
+
+			if self.recursive {
+				pending.extend(
+					entries
+						.iter()
+						.filter(|(_, entry)| entry.is_dir)
+						.map(|(child, _)| child.clone()),
+				);
+			}
+
+			self.dirs.insert(
+				path,
+				PolledDir {
+					modified,
+					listed_at: SystemTime::now(),
+					entries,
+					interval: config.interval,
+					next_poll: now + config.interval,
+				},
+			);
+		}
+	}
+}
+
+/// List a directory's entries, leaving out filtered paths so their subtrees aren't polled
+fn list_dir(path: &Path, filters: &EventFilters) -> io::Result<HashMap<PathBuf, EntrySnapshot>> {
+	let mut entries = HashMap::new();
+
+	for entry in fs::read_dir(path)? {
+		let entry = entry?;
+		let child = entry.path();
+		if filters.should_skip(&child) {
+			continue;
+		}
+		// Symlinks are recorded as files rather than followed
+		match fs::symlink_metadata(&child) {
+			Ok(metadata) => {
+				entries.insert(child, EntrySnapshot::from_metadata(&metadata));
+			}
+			// Removed between the listing and the stat
+			Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+			Err(e) => return Err(e),
+		}
+	}
+
+	Ok(entries)
+}
+
+/// Compare two listings of a directory, returning the subdirectories added and removed
+fn diff_entries(
+	previous: &HashMap<PathBuf, EntrySnapshot>,
+	current: &HashMap<PathBuf, EntrySnapshot>,
+	events: &mut Vec<FsEvent>,
+) -> (Vec<PathBuf>, Vec<PathBuf>) {
+	let mut added_dirs = Vec::new();
+	let mut removed_dirs = Vec::new();
+
+	for (path, previous) in previous {
+		let replaced = current
+			.get(path)
+			.is_some_and(|entry| entry.is_dir != previous.is_dir);
+		if !current.contains_key(path) || replaced {
+			events.push(FsEvent::new_with_dir_flag(
+				path.clone(),
+				FsEventKind::Remove,
+				previous.is_dir,
+			));
+			if previous.is_dir {
+				removed_dirs.push(path.clone());
+			}
+		}
+	}
+
+	for (path, entry) in current {
+		match previous.get(path) {
+			Some(previous) if previous.is_dir == entry.is_dir => {
+				let edited = previous.size != entry.size || previous.modified != entry.modified;
+				if edited && !entry.is_dir {
+					events.push(FsEvent::modify_file(path.clone()));
+				}
+			}
+			_ => {
+				if entry.is_dir {
+					events.push(FsEvent::create_dir(path.clone()));
+					added_dirs.push(path.clone());
+				} else {
+					events.push(FsEvent::create_file(path.clone()));
+				}
+			}
+		}
+	}
+
+	(added_dirs, removed_dirs)
+}
+
+/// Event handler for polled watches
+///
+/// Polled paths aren't registered with notify, so `process` never sees their events;
+/// all of the work happens in `tick`, which polls the directories that are due.
+pub struct PollingHandler {
+	config: PollingConfig,
+	roots: Mutex<HashMap<PathBuf, Arc<Mutex<PolledRoot>>>>,
+}
+
+impl PollingHandler {
+	/// Create a new polling handler
+	pub fn new(config: PollingConfig) -> Self {
+		Self {
+			config,
+			roots: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Start polling a path
+	///
+	/// The baseline snapshot is taken on the next tick rather than here, so a large
+	/// share doesn't hold up the caller.
+	pub fn add_root(&self, path: PathBuf, recursive: bool, filters: EventFilters) {
+		let root = PolledRoot {
+			path: path.clone(),
+			recursive,
+			filters,
+			dirs: HashMap::new(),
+		};
+		self.roots
+			.lock()
+			.unwrap()
+			.insert(path, Arc::new(Mutex::new(root)));
+	}
+
+	/// Stop polling a path
+	pub fn remove_root(&self, path: &Path) {
+		self.roots.lock().unwrap().remove(path);
+	}
+
+	/// Paths currently being polled
+	pub fn roots(&self) -> Vec<PathBuf> {
+		self.roots.lock().unwrap().keys().cloned().collect()
+	}
+}
+
+#[async_trait::async_trait]
+impl EventHandler for PollingHandler {
+	async fn process(&self, _event: RawNotifyEvent) -> Result<Vec<FsEvent>> {
+		Ok(vec![])
+	}
+
+	async fn tick(&self) -> Result<Vec<FsEvent>> {
+		let roots: Vec<_> = self.roots.lock().unwrap().values().cloned().collect();
+		if roots.is_empty() {
+			return Ok(vec![]);
+		}
+
+		let config = self.config.clone();
+		let events = tokio::task::spawn_blocking(move || {
+			let now = Instant::now();
+			let mut events = Vec::new();
+			for root in roots {
+				root.lock().unwrap().poll(&config, now, &mut events);
+			}
+			events
+		})
+		.await
+		.map_err(io::Error::other)?;
+
+		for event in &events {
+			trace!("Polled change: {:?} {}", event.kind, event.path.display());
+		}
+
+		Ok(events)
+	}
+
+	async fn reset(&self) {
+		// Snapshots are retaken on the next tick, so changes made while stopped aren't
+		// reported as a burst of events
+		for root in self.roots.lock().unwrap().values() {
+			root.lock().unwrap().dirs.clear();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::TempDir;
+
+	/// Temp dirs are named `.tmpXXXX`, which the default filters skip
+	fn temp_dir() -> TempDir {
+		tempfile::Builder::new()
+			.prefix("sd-poll")
+			.tempdir()
+			.unwrap()
+	}
+
+	fn config() -> PollingConfig {
+		PollingConfig::default()
+			.with_interval(Duration::ZERO)
+			.with_bounds(Duration::ZERO, Duration::ZERO)
+	}
+
+	fn root(path: &Path) -> PolledRoot {
+		PolledRoot {
+			path: path.to_path_buf(),
+			recursive: true,
+			filters: EventFilters::default(),
+			dirs: HashMap::new(),
+		}
+	}
+
+	fn poll(root: &mut PolledRoot) -> Vec<FsEvent> {
+		let mut events = Vec::new();
+		root.poll(&config(), Instant::now(), &mut events);
+		events
+	}
+
+	fn find<'a>(events: &'a [FsEvent], path: &Path) -> &'a FsEvent {
+		events
+			.iter()
+			.find(|event| event.path == path)
+			.unwrap_or_else(|| panic!("no event for {}: {:?}", path.display(), events))
+	}
+
+	#[test]
+	fn test_baseline_emits_nothing() {
+		let temp = temp_dir();
+		fs::create_dir(temp.path().join("photos")).unwrap();
+		fs::write(temp.path().join("photos/beach.jpg"), b"sand").unwrap();
+
+		let mut root = root(temp.path());
+		assert!(poll(&mut root).is_empty());
+		assert!(root.dirs.contains_key(&temp.path().join("photos")));
+		assert!(poll(&mut root).is_empty());
+	}
+
+	#[test]
+	fn test_detects_changes() {
+		let temp = temp_dir();
+		let photos = temp.path().join("photos");
+		fs::create_dir(&photos).unwrap();
+		fs::write(photos.join("beach.jpg"), b"sand").unwrap();
+		fs::write(temp.path().join("notes.txt"), b"hello").unwrap();
+
+		let mut root = root(temp.path());
+		poll(&mut root);
+
+		fs::write(temp.path().join("notes.txt"), b"hello, world").unwrap();
+		fs::write(photos.join("cover.png"), b"cover").unwrap();
+		fs::create_dir(temp.path().join("music")).unwrap();
+		fs::write(temp.path().join(".hidden"), b"").unwrap();
+
+		let events = poll(&mut root);
+		assert!(find(&events, &temp.path().join("notes.txt"))
+			.kind
+			.is_modify());
+		assert!(find(&events, &photos.join("cover.png")).kind.is_create());
+		let music = find(&events, &temp.path().join("music"));
+		assert!(music.kind.is_create());
+		assert_eq!(music.is_dir(), Some(true));
+		assert!(!events
+			.iter()
+			.any(|event| event.path == temp.path().join(".hidden")));
+		assert_eq!(events.len(), 3);
+
+		fs::remove_dir_all(&photos).unwrap();
+
+		let events = poll(&mut root);
+		let removed = find(&events, &photos);
+		assert!(removed.kind.is_remove());
+		assert_eq!(removed.is_dir(), Some(true));
+		assert_eq!(events.len(), 1);
+		assert!(!root.dirs.contains_key(&photos));
+	}
+
+	#[tokio::test]
+	async fn test_handler_reset_retakes_baseline() {
+		let temp = temp_dir();
+		let handler = PollingHandler::new(config());
+		handler.add_root(temp.path().to_path_buf(), true, EventFilters::default());
+
+		assert!(handler.tick().await.unwrap().is_empty());
+
+		fs::write(temp.path().join("notes.txt"), b"hello").unwrap();
+		let events = handler.tick().await.unwrap();
+		assert_eq!(events.len(), 1);
+		assert!(events[0].kind.is_create());
+
+		handler.reset().await;
+		fs::write(temp.path().join("todo.txt"), b"milk").unwrap();
+		assert!(handler.tick().await.unwrap().is_empty());
+
+		handler.remove_root(temp.path());
+		assert!(handler.roots().is_empty());
+	}
+}
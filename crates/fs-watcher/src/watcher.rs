@@ -4,10 +4,10 @@
 //! It's storage-agnostic - it only knows about paths and events, not
 //! about locations, libraries, or databases.
 
-use crate::config::{WatchConfig, WatcherConfig};
+use crate::config::{WatchBackend, WatchConfig, WatcherConfig};
 use crate::error::{Result, WatcherError};
 use crate::event::{FsEvent, RawNotifyEvent};
-use crate::platform::PlatformHandler;
+use crate::platform::{EventHandler, PlatformHandler, PollingHandler};
 use notify::{RecommendedWatcher, RecursiveMode, Watcher as NotifyWatcher};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -63,6 +63,8 @@ struct FsWatcherInner {
 	notify_watcher: RwLock<Option<RecommendedWatcher>>,
 	/// Platform-specific event handler
 	platform_handler: PlatformHandler,
+	/// Handler for paths watched with `WatchBackend::Polling`
+	polling_handler: PollingHandler,
 	/// Whether the watcher is running
 	is_running: AtomicBool,
 	/// Event sender for broadcasts
@@ -93,8 +95,12 @@ impl FsWatcherInner {
 			return Err(WatcherError::PathNotFound(path));
 		}
 
-		// Register with notify if we're running
-		if self.is_running.load(Ordering::SeqCst) {
+		if config.backend == WatchBackend::Polling {
+			// Polled paths are never registered with notify, and are only polled while running
+			self.polling_handler
+				.add_root(path.clone(), config.recursive, config.filters.clone());
+		} else if self.is_running.load(Ordering::SeqCst) {
+			// Register with notify if we're running
 			if let Some(watcher) = self.notify_watcher.write().await.as_mut() {
 				let mode = if config.recursive {
 					RecursiveMode::Recursive
@@ -140,10 +146,12 @@ impl FsWatcherInner {
 		};
 
 		if should_unwatch {
-			watched.remove(path);
+			let state = watched.remove(path);
 
-			// Unregister from notify if we're running
-			if self.is_running.load(Ordering::SeqCst) {
+			if state.is_some_and(|state| state.config.backend == WatchBackend::Polling) {
+				self.polling_handler.remove_root(path);
+			} else if self.is_running.load(Ordering::SeqCst) {
+				// Unregister from notify if we're running
 				if let Some(watcher) = self.notify_watcher.write().await.as_mut() {
 					if let Err(e) = watcher.unwatch(path) {
 						warn!("Failed to unwatch {}: {}", path.display(), e);
@@ -190,6 +198,7 @@ impl FsWatcher {
 	/// Create a new filesystem watcher
 	pub fn new(config: WatcherConfig) -> Self {
 		let (event_tx, _) = broadcast::channel(config.event_buffer_size);
+		let polling_handler = PollingHandler::new(config.polling.clone());
 
 		Self {
 			inner: Arc::new(FsWatcherInner {
@@ -197,6 +206,7 @@ impl FsWatcher {
 				watched_paths: RwLock::new(HashMap::new()),
 				notify_watcher: RwLock::new(None),
 				platform_handler: PlatformHandler::new(),
+				polling_handler,
 				is_running: AtomicBool::new(false),
 				event_tx,
 				events_received: AtomicU64::new(0),
@@ -245,6 +255,9 @@ impl FsWatcher {
 		// Start the event processing loop
 		self.start_event_loop(raw_rx).await;
 
+		// Start polling paths that don't get OS events
+		self.start_poll_loop();
+
 		info!("Filesystem watcher started");
 		Ok(())
 	}
@@ -262,6 +275,7 @@ impl FsWatcher {
 
 		// Reset platform handler state
 		self.inner.platform_handler.reset().await;
+		self.inner.polling_handler.reset().await;
 
 		info!("Filesystem watcher stopped");
 		Ok(())
@@ -331,6 +345,10 @@ impl FsWatcher {
 
 		if let Some(watcher) = watcher_guard.as_mut() {
 			for (path, state) in watched.iter() {
+				if state.config.backend == WatchBackend::Polling {
+					continue;
+				}
+
 				let mode = if state.config.recursive {
 					RecursiveMode::Recursive
 				} else {
@@ -425,6 +443,50 @@ impl FsWatcher {
 			info!("Event processing loop stopped");
 		});
 	}
+
+	/// Start the loop that polls paths watched with `WatchBackend::Polling`
+	///
+	/// Each directory keeps its own schedule, so the loop just wakes at the fastest
+	/// polling interval and lets the handler poll whatever is due.
+	fn start_poll_loop(&self) {
+		let inner = self.inner.clone();
+		let period = self
+			.inner
+			.config
+			.polling
+			.min_interval
+			.max(self.inner.config.tick_interval);
+
+		tokio::spawn(async move {
+			debug!("Poll loop started");
+
+			let mut poll_timer = tokio::time::interval(period);
+			poll_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+			loop {
+				poll_timer.tick().await;
+				if !inner.is_running.load(Ordering::SeqCst) {
+					break;
+				}
+
+				match inner.polling_handler.tick().await {
+					Ok(events) => {
+						for event in events {
+							inner.events_emitted.fetch_add(1, Ordering::Relaxed);
+							if let Err(e) = inner.event_tx.send(event) {
+								trace!("No event subscribers: {}", e);
+							}
+						}
+					}
+					Err(e) => {
+						error!("Error polling watched paths: {}", e);
+					}
+				}
+			}
+
+			debug!("Poll loop stopped");
+		});
+	}
 }
 
 impl Default for FsWatcher {
@@ -539,6 +601,57 @@ mod tests {
 		watcher.stop().await.unwrap();
 	}
 
+	#[tokio::test]
+	async fn test_polling_backend() {
+		let config = WatcherConfig::default().with_polling(
+			crate::config::PollingConfig::default()
+				.with_interval(Duration::from_millis(50))
+				.with_bounds(Duration::from_millis(50), Duration::from_millis(200)),
+		);
+		let watcher = FsWatcher::new(config);
+		watcher.start().await.unwrap();
+
+		// The default filters skip paths containing `.tmp`, as TempDir names do
+		let temp_dir = tempfile::Builder::new()
+			.prefix("sd-poll")
+			.tempdir()
+			.unwrap();
+		let mut rx = watcher.subscribe();
+
+		watcher
+			.watch_path(
+				temp_dir.path(),
+				WatchConfig::recursive().with_backend(WatchBackend::Polling),
+			)
+			.await
+			.unwrap();
+		assert_eq!(watcher.inner.polling_handler.roots().len(), 1);
+
+		// Let the first poll take its baseline
+		tokio::time::sleep(Duration::from_millis(200)).await;
+
+		let test_file = temp_dir.path().join("polled.txt");
+		std::fs::write(&test_file, "hello").unwrap();
+
+		let event = tokio::time::timeout(Duration::from_secs(3), async {
+			loop {
+				if let Ok(event) = rx.recv().await {
+					if event.path == test_file {
+						return event;
+					}
+				}
+			}
+		})
+		.await
+		.expect("Timeout waiting for polled create event");
+		assert!(event.kind.is_create());
+
+		watcher.unwatch(temp_dir.path()).await.unwrap();
+		assert!(watcher.inner.polling_handler.roots().is_empty());
+
+		watcher.stop().await.unwrap();
+	}
+
 	#[tokio::test]
 	async fn test_file_deletion_events() {
 		let _ = tracing_subscriber::fmt()
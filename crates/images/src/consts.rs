@@ -17,6 +17,7 @@ pub const GENERIC_EXTENSIONS: [&str; 17] = [
 ];
 pub const SVG_EXTENSIONS: [&str; 2] = ["svg", "svgz"];
 pub const PDF_EXTENSIONS: [&str; 1] = ["pdf"];
+pub const RAW_EXTENSIONS: [&str; 7] = ["arw", "cr2", "cr3", "dng", "nef", "orf", "raf"];
 #[cfg(feature = "heif")]
 pub const HEIF_EXTENSIONS: [&str; 8] = [
 	"hif", "heif", "heifs", "heic", "heics", "avif", "avci", "avcs",
@@ -39,6 +40,12 @@ pub const SVG_TARGET_PX: f32 = 262_144f32;
 pub const PDF_PORTRAIT_RENDER_WIDTH: pdfium_render::prelude::Pixels = 794;
 pub const PDF_LANDSCAPE_RENDER_WIDTH: pdfium_render::prelude::Pixels = 1123;
 
+/// The smallest (on its long edge) that a JPEG preview embedded in a RAW file can be and
+/// still be used instead of developing the sensor data.
+///
+/// This matches the largest default thumbnail size, so those are never upscaled.
+pub const RAW_MINIMUM_PREVIEW_SIZE: u32 = 1024;
+
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 #[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
@@ -72,6 +79,13 @@ pub enum ConvertibleExtension {
 	Svgz,
 	Pdf,
 	Webp,
+	Arw,
+	Cr2,
+	Cr3,
+	Dng,
+	Nef,
+	Orf,
+	Raf,
 }
 
 impl ConvertibleExtension {
@@ -86,6 +100,14 @@ impl ConvertibleExtension {
 				| Self::Avcs
 		)
 	}
+
+	#[must_use]
+	pub const fn is_raw(self) -> bool {
+		matches!(
+			self,
+			Self::Arw | Self::Cr2 | Self::Cr3 | Self::Dng | Self::Nef | Self::Orf | Self::Raf
+		)
+	}
 }
 
 impl Display for ConvertibleExtension {
@@ -128,6 +150,13 @@ impl TryFrom<String> for ConvertibleExtension {
 			"svgz" => Ok(Self::Svgz),
 			"pdf" => Ok(Self::Pdf),
 			"webp" => Ok(Self::Webp),
+			"arw" => Ok(Self::Arw),
+			"cr2" => Ok(Self::Cr2),
+			"cr3" => Ok(Self::Cr3),
+			"dng" => Ok(Self::Dng),
+			"nef" => Ok(Self::Nef),
+			"orf" => Ok(Self::Orf),
+			"raf" => Ok(Self::Raf),
 			_ => Err(crate::Error::Unsupported),
 		}
 	}
@@ -193,6 +222,7 @@ pub fn all_compatible_extensions() -> Vec<String> {
 		.chain(HEIF_EXTENSIONS)
 		.chain(SVG_EXTENSIONS)
 		.chain(PDF_EXTENSIONS)
+		.chain(RAW_EXTENSIONS)
 		.map(String::from)
 		.collect();
 
@@ -201,6 +231,7 @@ pub fn all_compatible_extensions() -> Vec<String> {
 		.into_iter()
 		.chain(SVG_EXTENSIONS)
 		.chain(PDF_EXTENSIONS)
+		.chain(RAW_EXTENSIONS)
 		.map(String::from)
 		.collect();
 
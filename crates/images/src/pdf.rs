@@ -73,14 +73,20 @@ static LANDSCAPE_CONFIG: LazyLock<PdfRenderConfig> = LazyLock::new(|| {
 	thumbnail_config(PdfRenderConfig::new().set_target_width(PDF_LANDSCAPE_RENDER_WIDTH))
 });
 
+fn load_pdfium() -> Result<Pdfium> {
+	Ok(Pdfium::new(
+		Pdfium::bind_to_library(PDFIUM_LIB.as_str()).or_else(|err| {
+			error!("{err:#?}");
+			Pdfium::bind_to_system_library()
+		})?,
+	))
+}
+
 pub struct PdfHandler {}
 
 impl ImageHandler for PdfHandler {
 	fn handle_image(&self, path: &Path) -> Result<DynamicImage> {
-		let pdfium = Pdfium::new(Pdfium::bind_to_library(PDFIUM_LIB.as_str()).or_else(|err| {
-			error!("{err:#?}");
-			Pdfium::bind_to_system_library()
-		})?);
+		let pdfium = load_pdfium()?;
 
 		let pdf = pdfium.load_pdf_from_file(path, None)?;
 		let first_page = pdf.pages().first()?;
@@ -96,3 +102,16 @@ impl ImageHandler for PdfHandler {
 		Ok(image)
 	}
 }
+
+/// Extract the text layer of every page of a PDF, one string per page
+///
+/// Scanned documents without a text layer produce empty pages; those need OCR instead.
+pub fn extract_pdf_text(path: impl AsRef<Path>) -> Result<Vec<String>> {
+	let pdfium = load_pdfium()?;
+	let pdf = pdfium.load_pdf_from_file(path.as_ref(), None)?;
+
+	pdf.pages()
+		.iter()
+		.map(|page| -> Result<String> { Ok(page.text()?.all()) })
+		.collect()
+}
@@ -0,0 +1,1073 @@
+//! Camera RAW images (CR2, CR3, NEF, ARW, RAF, DNG and ORF)
+//!
+//! Cameras embed a JPEG preview in their RAW files, often at full size, which is far
+//! cheaper to decode than the sensor data. It's used when it's large enough, otherwise the
+//! sensor data decoded by `rawloader` is demosaiced. Neither is rotated, so like other
+//! images the EXIF orientation is applied by the caller. [`RawFile::metadata`] finds
+//! that EXIF data in the containers `kamadak-exif` can't read by itself, from the same
+//! read of the file as the image.
+
+use std::{borrow::Cow, cmp::Reverse, io::Cursor, ops::Range, path::Path};
+
+use image::{DynamicImage, ImageFormat, RgbImage};
+use rawloader::{RawImage, RawImageData};
+use tracing::debug;
+
+use crate::{consts::RAW_MINIMUM_PREVIEW_SIZE, Error, ImageHandler, Result};
+
+const RAF_MAGIC: &[u8] = b"FUJIFILMCCD-RAW";
+
+/// The CR3 box holding the `PRVW` preview
+const CR3_PREVIEW_UUID: [u8; 16] = [
+	0xea, 0xf4, 0x2b, 0x5e, 0x1c, 0x98, 0x4b, 0x88, 0xb9, 0xfb, 0xb7, 0xdc, 0x40, 0x6e, 0x4d, 0x16,
+];
+/// The CR3 box (inside `moov`) holding the `THMB` thumbnail and the `CMT*` TIFF structures
+const CR3_METADATA_UUID: [u8; 16] = [
+	0x85, 0xc0, 0xb6, 0x87, 0x82, 0x0f, 0x11, 0xe0, 0x81, 0x11, 0xf4, 0xce, 0x46, 0x2b, 0x6a, 0x48,
+];
+
+// TIFF tags
+const IMAGE_WIDTH: u16 = 0x0100;
+const IMAGE_LENGTH: u16 = 0x0101;
+const COMPRESSION: u16 = 0x0103;
+const STRIP_OFFSETS: u16 = 0x0111;
+const STRIP_BYTE_COUNTS: u16 = 0x0117;
+const SUB_IFDS: u16 = 0x014a;
+const JPEG_OFFSET: u16 = 0x0201;
+const JPEG_LENGTH: u16 = 0x0202;
+const NEW_SUBFILE_TYPE: u16 = 0x00fe;
+const EXIF_IFD: u16 = 0x8769;
+const GPS_IFD: u16 = 0x8825;
+const INTEROP_IFD: u16 = 0xa005;
+const PIXEL_X_DIMENSION: u16 = 0xa002;
+const PIXEL_Y_DIMENSION: u16 = 0xa003;
+
+/// sRGB (D65) primaries in XYZ
+const SRGB_TO_XYZ: [[f32; 3]; 3] = [
+	[0.412_453, 0.357_580, 0.180_423],
+	[0.212_671, 0.715_160, 0.072_169],
+	[0.019_334, 0.119_193, 0.950_227],
+];
+const IDENTITY: [[f32; 3]; 3] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+/// Entries in the lookup table used to gamma-encode developed pixels
+const GAMMA_STEPS: usize = 4096;
+
+pub struct RawHandler {}
+
+impl ImageHandler for RawHandler {
+	fn handle_image(&self, path: &Path) -> Result<DynamicImage> {
+		RawFile::open(path)?.decode()
+	}
+}
+
+/// A RAW file read into memory, so its image and metadata come from a single read
+pub struct RawFile {
+	data: Vec<u8>,
+}
+
+impl RawFile {
+	pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+		RawHandler {}.get_data(path.as_ref()).map(Self::from_bytes)
+	}
+
+	#[must_use]
+	pub const fn from_bytes(data: Vec<u8>) -> Self {
+		Self { data }
+	}
+
+	/// The largest embedded preview that's big enough, otherwise the demosaiced sensor data
+	pub fn decode(&self) -> Result<DynamicImage> {
+		let mut previews = Container::detect(&self.data)
+			.map(|container| container.previews())
+			.unwrap_or_default();
+		previews.sort_by_key(|preview| Reverse(preview.width * preview.height));
+
+		let (large, small): (Vec<_>, Vec<_>) = previews
+			.into_iter()
+			.partition(|preview| preview.width.max(preview.height) >= RAW_MINIMUM_PREVIEW_SIZE);
+
+		if let Some(img) = decode_first(&large) {
+			return Ok(img);
+		}
+
+		// A small preview still beats no thumbnail if the sensor data can't be decoded
+		demosaic(&self.data).or_else(|e| decode_first(&small).ok_or(e))
+	}
+
+	pub fn metadata(&self) -> Result<RawMetadata> {
+		let Some(container) = Container::detect(&self.data) else {
+			return Err(Error::Unsupported);
+		};
+
+		Ok(RawMetadata {
+			dimensions: container.dimensions(),
+			exif: container.exif().map(Cow::into_owned),
+		})
+	}
+}
+
+/// Metadata read from a RAW file's container, without decoding the image
+#[derive(Debug, Clone, Default)]
+pub struct RawMetadata {
+	/// EXIF data in a form `kamadak-exif` can read (a TIFF structure or a JPEG)
+	pub exif: Option<Vec<u8>>,
+	/// Width and height of the full-size image, where the container records them
+	pub dimensions: Option<(u32, u32)>,
+}
+
+pub fn extract_raw_metadata(path: impl AsRef<Path>) -> Result<RawMetadata> {
+	RawFile::open(path)?.metadata()
+}
+
+fn decode_first(previews: &[Preview<'_>]) -> Option<DynamicImage> {
+	previews.iter().find_map(|preview| {
+		image::load_from_memory_with_format(preview.data, ImageFormat::Jpeg)
+			.map_err(|e| debug!("Skipping undecodable RAW preview: {e}"))
+			.ok()
+	})
+}
+
+/// A JPEG embedded in a RAW file that the `image` crate can decode
+struct Preview<'a> {
+	data: &'a [u8],
+	width: u32,
+	height: u32,
+}
+
+impl<'a> Preview<'a> {
+	fn new(data: &'a [u8]) -> Option<Self> {
+		let (width, height) = jpeg_dimensions(data)?;
+		Some(Self {
+			data,
+			width,
+			height,
+		})
+	}
+}
+
+/// The dimensions of a baseline or progressive JPEG
+///
+/// Lossless, hierarchical and arithmetic-coded JPEGs (which is how several formats store
+/// the sensor data itself) aren't supported by the `image` crate, so they return `None`.
+fn jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+	if !data.starts_with(&[0xff, 0xd8]) {
+		return None;
+	}
+
+	let mut pos = 2;
+	loop {
+		// Markers may be preceded by any number of fill bytes
+		while *data.get(pos + 1)? == 0xff {
+			pos += 1;
+		}
+		if data[pos] != 0xff {
+			return None;
+		}
+		let marker = data[pos + 1];
+		pos += 2;
+
+		match marker {
+			0x01 | 0xd0..=0xd8 => {}
+			0xc0..=0xc2 => {
+				let height = read_u16(data, pos + 3, true)?;
+				let width = read_u16(data, pos + 5, true)?;
+				return (width > 0 && height > 0).then(|| (width.into(), height.into()));
+			}
+			0xc3 | 0xc5..=0xc7 | 0xc9..=0xcb | 0xcd..=0xcf | 0xd9 | 0xda => return None,
+			_ => pos += usize::from(read_u16(data, pos, true)?),
+		}
+	}
+}
+
+enum Container<'a> {
+	/// CR2, NEF, ARW, DNG and ORF are all TIFF structures
+	Tiff(Tiff<'a>),
+	Raf(&'a [u8]),
+	/// CR3 is an ISO base media file
+	Cr3(&'a [u8]),
+}
+
+impl<'a> Container<'a> {
+	fn detect(data: &'a [u8]) -> Option<Self> {
+		if data.starts_with(RAF_MAGIC) {
+			Some(Self::Raf(data))
+		} else if data.get(4..12) == Some(b"ftypcrx ".as_slice()) {
+			Some(Self::Cr3(data))
+		} else {
+			Tiff::new(data).map(Self::Tiff)
+		}
+	}
+
+	fn previews(&self) -> Vec<Preview<'a>> {
+		match self {
+			Self::Tiff(tiff) => tiff
+				.ifds()
+				.iter()
+				.flat_map(|ifd| {
+					let value = |tag| ifd.get(tag).and_then(|entry| tiff.value(entry, 0));
+
+					let interchange = value(JPEG_OFFSET).zip(value(JPEG_LENGTH));
+					// A single strip of (possibly lossless) JPEG data
+					let strip = ifd
+						.get(STRIP_OFFSETS)
+						.filter(|entry| {
+							entry.count == 1 && matches!(value(COMPRESSION), Some(6 | 7 | 34_892))
+						})
+						.and_then(|_| value(STRIP_OFFSETS).zip(value(STRIP_BYTE_COUNTS)));
+
+					[interchange, strip]
+				})
+				.flatten()
+				.filter_map(|(offset, len)| tiff.slice(offset, len).and_then(Preview::new))
+				.collect(),
+
+			Self::Raf(data) => read_u32(data, 84, true)
+				.zip(read_u32(data, 88, true))
+				.and_then(|(offset, len)| slice(data, offset, len))
+				.and_then(Preview::new)
+				.into_iter()
+				.collect(),
+
+			Self::Cr3(data) => {
+				let preview = uuid_box(data, &CR3_PREVIEW_UUID).and_then(|content| {
+					// `PRVW` follows a few bytes of header inside the UUID box
+					let tag = find(content, b"PRVW")?.checked_sub(4)?;
+					let size = read_u32(content, tag, true)?;
+					slice(content, u32::try_from(tag).ok()?, size)
+				});
+				let thumbnail =
+					cr3_metadata(data).and_then(|metadata| child_box(metadata, *b"THMB"));
+
+				[preview, thumbnail]
+					.into_iter()
+					.flatten()
+					.filter_map(|content| {
+						Preview::new(&content[find(content, &[0xff, 0xd8, 0xff])?..])
+					})
+					.collect()
+			}
+		}
+	}
+
+	fn exif(&self) -> Option<Cow<'a, [u8]>> {
+		match self {
+			Self::Tiff(tiff) if tiff.standard_magic => Some(Cow::Borrowed(tiff.data)),
+			// Olympus uses its own magic number for an otherwise standard TIFF structure
+			Self::Tiff(tiff) => {
+				let mut data = tiff.data.to_vec();
+				write_u16(&mut data, 2, 42, tiff.big_endian);
+				Some(Cow::Owned(data))
+			}
+			// The preview JPEG carries the EXIF data
+			Self::Raf(_) => self
+				.previews()
+				.pop()
+				.map(|preview| Cow::Borrowed(preview.data)),
+			Self::Cr3(data) => {
+				let metadata = cr3_metadata(data)?;
+				let ifd0 = child_box(metadata, *b"CMT1")?;
+				let sub_ifds = [(EXIF_IFD, *b"CMT2"), (GPS_IFD, *b"CMT4")]
+					.into_iter()
+					.filter_map(|(tag, kind)| Some((tag, child_box(metadata, kind)?)))
+					.collect::<Vec<_>>();
+
+				merge_tiff(ifd0, &sub_ifds).map(Cow::Owned)
+			}
+		}
+	}
+
+	fn dimensions(&self) -> Option<(u32, u32)> {
+		match self {
+			// The largest full-resolution image in the file
+			Self::Tiff(tiff) => tiff
+				.ifds()
+				.iter()
+				.filter(|ifd| {
+					let kind = ifd
+						.get(NEW_SUBFILE_TYPE)
+						.and_then(|entry| tiff.value(entry, 0));
+					matches!(kind, None | Some(0))
+				})
+				.filter_map(|ifd| {
+					let value = |tag| ifd.get(tag).and_then(|entry| tiff.value(entry, 0));
+					value(IMAGE_WIDTH).zip(value(IMAGE_LENGTH))
+				})
+				.max_by_key(|(width, height)| u64::from(*width) * u64::from(*height)),
+
+			// Fujifilm's own directory, preferring the cropped size over the full sensor
+			Self::Raf(data) => {
+				let directory = read_u32(data, 92, true)? as usize;
+				let count = read_u32(data, directory, true)?;
+				let mut pos = directory + 4;
+				let mut sizes = Vec::new();
+				for _ in 0..count.min(256) {
+					let tag = read_u16(data, pos, true)?;
+					let len = read_u16(data, pos + 2, true)?;
+					if matches!(tag, 0x100 | 0x111 | 0x121) && len >= 4 {
+						let height = read_u16(data, pos + 4, true)?;
+						let width = read_u16(data, pos + 6, true)?;
+						sizes.push((tag, (u32::from(width), u32::from(height))));
+					}
+					pos += 4 + usize::from(len);
+				}
+
+				[0x111, 0x121, 0x100].into_iter().find_map(|preferred| {
+					sizes
+						.iter()
+						.find(|(tag, _)| *tag == preferred)
+						.map(|(_, size)| *size)
+				})
+			}
+
+			Self::Cr3(data) => {
+				let exif = Tiff::new(child_box(cr3_metadata(data)?, *b"CMT2")?)?;
+				let ifd = exif.ifd(exif.first_ifd()?)?;
+				let value = |tag| ifd.get(tag).and_then(|entry| exif.value(entry, 0));
+				value(PIXEL_X_DIMENSION).zip(value(PIXEL_Y_DIMENSION))
+			}
+		}
+	}
+}
+
+/// A TIFF structure, read without copying
+#[derive(Clone, Copy)]
+struct Tiff<'a> {
+	data: &'a [u8],
+	big_endian: bool,
+	/// Whether the header has the usual magic number (42) rather than Olympus' own
+	standard_magic: bool,
+}
+
+struct Ifd {
+	entries: Vec<IfdEntry>,
+}
+
+struct IfdEntry {
+	tag: u16,
+	kind: u16,
+	count: u32,
+	/// Where the 12-byte entry starts
+	pos: usize,
+}
+
+impl Ifd {
+	fn get(&self, tag: u16) -> Option<&IfdEntry> {
+		self.entries.iter().find(|entry| entry.tag == tag)
+	}
+}
+
+impl<'a> Tiff<'a> {
+	/// The most IFDs read from one file, guarding against loops and corrupt files
+	const MAX_IFDS: usize = 32;
+
+	fn new(data: &'a [u8]) -> Option<Self> {
+		let big_endian = match data.get(..2)? {
+			b"II" => false,
+			b"MM" => true,
+			_ => return None,
+		};
+		let standard_magic = match read_u16(data, 2, big_endian)? {
+			42 => true,
+			// `IIRO` and `IIRS`
+			0x4f52 | 0x5352 if !big_endian => false,
+			_ => return None,
+		};
+
+		Some(Self {
+			data,
+			big_endian,
+			standard_magic,
+		})
+	}
+
+	fn first_ifd(&self) -> Option<usize> {
+		self.u32(4).map(|offset| offset as usize)
+	}
+
+	fn u16(&self, pos: usize) -> Option<u16> {
+		read_u16(self.data, pos, self.big_endian)
+	}
+
+	fn u32(&self, pos: usize) -> Option<u32> {
+		read_u32(self.data, pos, self.big_endian)
+	}
+
+	fn slice(&self, offset: u32, len: u32) -> Option<&'a [u8]> {
+		slice(self.data, offset, len)
+	}
+
+	fn ifd(&self, pos: usize) -> Option<Ifd> {
+		let count = usize::from(self.u16(pos)?);
+		let entries = (0..count)
+			.map(|i| {
+				let pos = pos + 2 + i * 12;
+				Some(IfdEntry {
+					tag: self.u16(pos)?,
+					kind: self.u16(pos + 2)?,
+					count: self.u32(pos + 4)?,
+					pos,
+				})
+			})
+			.collect::<Option<_>>()?;
+
+		Some(Ifd { entries })
+	}
+
+	/// The offset of the IFD after the one at `pos`, if there is one
+	fn next_ifd(&self, pos: usize) -> Option<usize> {
+		let count = usize::from(self.u16(pos)?);
+		self.u32(pos + 2 + count * 12)
+			.filter(|offset| *offset != 0)
+			.map(|offset| offset as usize)
+	}
+
+	/// Every image IFD: the main chain and the sub-IFDs hanging off it
+	fn ifds(&self) -> Vec<Ifd> {
+		let mut ifds = Vec::new();
+		let mut visited = Vec::new();
+		let mut pending = self.first_ifd().into_iter().collect::<Vec<_>>();
+
+		while let Some(pos) = pending.pop() {
+			if visited.contains(&pos) || visited.len() >= Self::MAX_IFDS {
+				continue;
+			}
+			visited.push(pos);
+
+			let Some(ifd) = self.ifd(pos) else {
+				continue;
+			};
+			pending.extend(self.next_ifd(pos));
+			if let Some(entry) = ifd.get(SUB_IFDS) {
+				pending.extend(
+					(0..entry.count.min(8))
+						.filter_map(|i| self.value(entry, i))
+						.map(|offset| offset as usize),
+				);
+			}
+			ifds.push(ifd);
+		}
+
+		ifds
+	}
+
+	/// Where an entry's value is, inline or at the offset it holds
+	fn value_pos(&self, entry: &IfdEntry) -> Option<usize> {
+		let len = type_size(entry.kind)?.checked_mul(entry.count as usize)?;
+		if len <= 4 {
+			Some(entry.pos + 8)
+		} else {
+			self.u32(entry.pos + 8).map(|offset| offset as usize)
+		}
+	}
+
+	/// An integer value of an entry
+	fn value(&self, entry: &IfdEntry, index: u32) -> Option<u32> {
+		if index >= entry.count {
+			return None;
+		}
+		let pos = self.value_pos(entry)? + type_size(entry.kind)? * index as usize;
+
+		match entry.kind {
+			1 | 7 => self.data.get(pos).copied().map(u32::from),
+			3 => self.u16(pos).map(u32::from),
+			4 | 13 => self.u32(pos),
+			_ => None,
+		}
+	}
+}
+
+/// The size of one value of a TIFF field type
+const fn type_size(kind: u16) -> Option<usize> {
+	match kind {
+		1 | 2 | 6 | 7 => Some(1),
+		3 | 8 => Some(2),
+		4 | 9 | 11 | 13 => Some(4),
+		5 | 10 | 12 => Some(8),
+		_ => None,
+	}
+}
+
+/// Combine a TIFF structure holding IFD0 with others that each hold just a sub-IFD (as
+/// CR3 stores EXIF data), linking the sub-IFDs from IFD0 with the given tags
+fn merge_tiff(ifd0: &[u8], sub_ifds: &[(u16, &[u8])]) -> Option<Vec<u8>> {
+	let main = Tiff::new(ifd0)?;
+	let big_endian = main.big_endian;
+	let mut out = ifd0.to_vec();
+	let mut links = Vec::new();
+
+	for (tag, data) in sub_ifds {
+		let Some(sub) = Tiff::new(data).filter(|sub| sub.big_endian == big_endian) else {
+			continue;
+		};
+		let Some(ifd) = sub.first_ifd().and_then(|pos| sub.ifd(pos)) else {
+			continue;
+		};
+
+		out.resize(out.len() + out.len() % 2, 0);
+		let base = u32::try_from(out.len()).ok()?;
+		out.extend_from_slice(data);
+
+		// Copy the IFD after the data, moving the offsets it holds along with it. Any
+		// further sub-IFDs are left out rather than relocated.
+		let entries = ifd
+			.entries
+			.iter()
+			.filter(|entry| entry.tag != INTEROP_IFD && type_size(entry.kind).is_some())
+			.map(|entry| {
+				let mut raw: [u8; 12] = data.get(entry.pos..entry.pos + 12)?.try_into().ok()?;
+				if type_size(entry.kind)?.checked_mul(entry.count as usize)? > 4 {
+					let offset = read_u32(&raw, 8, big_endian)?.checked_add(base)?;
+					write_u32(&mut raw, 8, offset, big_endian);
+				}
+				Some(raw)
+			})
+			.collect::<Option<Vec<_>>>()?;
+
+		links.push((*tag, append_ifd(&mut out, entries, big_endian)?));
+	}
+
+	// A new IFD0 with the original entries (whose offsets are unchanged) and the links
+	let mut entries = main
+		.ifd(main.first_ifd()?)?
+		.entries
+		.iter()
+		.filter(|entry| !links.iter().any(|(tag, _)| *tag == entry.tag))
+		.filter_map(|entry| ifd0.get(entry.pos..entry.pos + 12)?.try_into().ok())
+		.collect::<Vec<[u8; 12]>>();
+	for (tag, pos) in links {
+		let mut raw = [0; 12];
+		write_u16(&mut raw, 0, tag, big_endian);
+		write_u16(&mut raw, 2, 4, big_endian);
+		write_u32(&mut raw, 4, 1, big_endian);
+		write_u32(&mut raw, 8, pos, big_endian);
+		entries.push(raw);
+	}
+	entries.sort_by_key(|raw| read_u16(raw, 0, big_endian));
+
+	let pos = append_ifd(&mut out, entries, big_endian)?;
+	write_u32(&mut out, 4, pos, big_endian);
+
+	Some(out)
+}
+
+/// Write an IFD with no successor at the end of `out`, returning its offset
+fn append_ifd(out: &mut Vec<u8>, entries: Vec<[u8; 12]>, big_endian: bool) -> Option<u32> {
+	out.resize(out.len() + out.len() % 2, 0);
+	let pos = u32::try_from(out.len()).ok()?;
+
+	let mut count = [0; 2];
+	write_u16(
+		&mut count,
+		0,
+		u16::try_from(entries.len()).ok()?,
+		big_endian,
+	);
+	out.extend_from_slice(&count);
+	out.extend(entries.into_iter().flatten());
+	out.extend_from_slice(&[0; 4]);
+
+	Some(pos)
+}
+
+/// The content of a top-level `uuid` box with the given ID
+fn uuid_box<'a>(data: &'a [u8], id: &[u8; 16]) -> Option<&'a [u8]> {
+	Boxes(data)
+		.filter(|(kind, _)| kind == b"uuid")
+		.find_map(|(_, payload)| payload.strip_prefix(id))
+}
+
+/// The content of CR3's metadata box
+fn cr3_metadata(data: &[u8]) -> Option<&[u8]> {
+	let moov = child_box(data, *b"moov")?;
+	uuid_box(moov, &CR3_METADATA_UUID)
+}
+
+fn child_box(data: &[u8], kind: [u8; 4]) -> Option<&[u8]> {
+	Boxes(data).find_map(|(child, payload)| (child == kind).then_some(payload))
+}
+
+/// Iterates over the ISO base media boxes in a buffer as their type and payload
+struct Boxes<'a>(&'a [u8]);
+
+impl<'a> Iterator for Boxes<'a> {
+	type Item = ([u8; 4], &'a [u8]);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let data = self.0;
+		let kind = data.get(4..8)?.try_into().ok()?;
+		let (header, size) = match read_u32(data, 0, true)? {
+			0 => (8, data.len()),
+			1 => (16, usize::try_from(read_u64(data, 8)?).ok()?),
+			size => (8, size as usize),
+		};
+
+		let Some(payload) = data.get(header..size) else {
+			self.0 = &[];
+			return None;
+		};
+		self.0 = &data[size..];
+
+		Some((kind, payload))
+	}
+}
+
+/// Develops the sensor data into an sRGB image
+///
+/// Each output pixel averages one block of the colour filter array (2x2 for Bayer sensors,
+/// 3x3 for X-Trans), which halves the resolution but needs no interpolation. That's more
+/// than enough for thumbnails.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn demosaic(data: &[u8]) -> Result<DynamicImage> {
+	let raw =
+		rawloader::decode(&mut Cursor::new(data)).map_err(|e| Error::RawDecode(e.to_string()))?;
+	let sensor = Sensor::new(&raw)?;
+
+	let block_size = match (raw.cpp, raw.cfa.width, raw.cfa.height) {
+		(3, _, _) => 1,
+		(_, 6, 6) => 3,
+		_ => 2,
+	};
+	let [top, right, bottom, left] = raw.crops;
+	let out_width = raw.width.saturating_sub(left + right) / block_size;
+	let out_height = raw.height.saturating_sub(top + bottom) / block_size;
+	if out_width == 0 || out_height == 0 {
+		return Err(Error::InvalidLength);
+	}
+
+	let to_srgb = camera_to_srgb(&raw.xyz_to_cam);
+	let gamma = gamma_table();
+	let last_step = f32::from(u16::try_from(GAMMA_STEPS - 1)?);
+
+	let mut pixels = Vec::with_capacity(out_width * out_height * 3);
+	for y in 0..out_height {
+		let rows = top + y * block_size..top + (y + 1) * block_size;
+		for x in 0..out_width {
+			let cols = left + x * block_size..left + (x + 1) * block_size;
+			let camera = sensor.average(rows.clone(), cols);
+
+			pixels.extend(to_srgb.map(|row| {
+				let linear =
+					row[0].mul_add(camera[0], row[1].mul_add(camera[1], row[2] * camera[2]));
+				gamma[(linear.clamp(0.0, 1.0) * last_step).round() as usize]
+			}));
+		}
+	}
+
+	let width = u32::try_from(out_width)?;
+	let height = u32::try_from(out_height)?;
+	RgbImage::from_raw(width, height, pixels).map_or_else(
+		|| Err(Error::RgbImageConversion),
+		|x| Ok(DynamicImage::ImageRgb8(x)),
+	)
+}
+
+/// Decoded sensor data, read as white-balanced camera RGB in 0..1
+struct Sensor<'a> {
+	raw: &'a RawImage,
+	/// Black level and scale factor of each colour
+	levels: [(f32, f32); 4],
+}
+
+impl<'a> Sensor<'a> {
+	fn new(raw: &'a RawImage) -> Result<Self> {
+		let len = match &raw.data {
+			RawImageData::Integer(data) => data.len(),
+			RawImageData::Float(data) => data.len(),
+		};
+		if !matches!(raw.cpp, 1 | 3) || len < raw.width * raw.height * raw.cpp {
+			return Err(Error::InvalidLength);
+		}
+
+		// White balance is relative to green, and cameras without it get none
+		let wb = raw.wb_coeffs.map(|coeff| {
+			if coeff.is_finite() && coeff > 0.0 {
+				coeff
+			} else {
+				1.0
+			}
+		});
+		let levels = std::array::from_fn(|c| match raw.data {
+			RawImageData::Integer(_) => {
+				let black = f32::from(raw.blacklevels[c]);
+				let range = (f32::from(raw.whitelevels[c]) - black).max(1.0);
+				(black, wb[c] / wb[1] / range)
+			}
+			// Float data is already normalised
+			RawImageData::Float(_) => (0.0, wb[c] / wb[1]),
+		});
+
+		Ok(Self { raw, levels })
+	}
+
+	fn sample(&self, row: usize, col: usize, channel: usize, colour: usize) -> f32 {
+		let i = (row * self.raw.width + col) * self.raw.cpp + channel;
+		let value = match &self.raw.data {
+			RawImageData::Integer(data) => f32::from(data[i]),
+			RawImageData::Float(data) => data[i],
+		};
+		let (black, factor) = self.levels[colour];
+		((value - black) * factor).clamp(0.0, 1.0)
+	}
+
+	/// The filter colour of a photosite, with the fourth colour of RGBE and CYGM sensors
+	/// read as green and monochrome sensors read as all green
+	fn colour_at(&self, row: usize, col: usize) -> usize {
+		if self.raw.cfa.width == 0 {
+			return 1;
+		}
+		match self.raw.cfa.color_at(row, col) {
+			3 => 1,
+			colour => colour.min(2),
+		}
+	}
+
+	/// The average of each colour in a block, borrowing green for any colour it lacks
+	#[allow(clippy::cast_precision_loss)]
+	fn average(&self, rows: Range<usize>, cols: Range<usize>) -> [f32; 3] {
+		let mut sums = [0.0f32; 3];
+		let mut counts = [0u32; 3];
+
+		for row in rows {
+			for col in cols.clone() {
+				if self.raw.cpp == 3 {
+					for colour in 0..3 {
+						sums[colour] += self.sample(row, col, colour, colour);
+						counts[colour] += 1;
+					}
+				} else {
+					let colour = self.colour_at(row, col);
+					sums[colour] += self.sample(row, col, 0, colour);
+					counts[colour] += 1;
+				}
+			}
+		}
+
+		let green = sums[1] / counts[1].max(1) as f32;
+		std::array::from_fn(|c| {
+			if counts[c] == 0 {
+				green
+			} else {
+				sums[c] / counts[c] as f32
+			}
+		})
+	}
+}
+
+/// Lookup table from linear light to 8-bit sRGB
+#[allow(
+	clippy::cast_possible_truncation,
+	clippy::cast_precision_loss,
+	clippy::cast_sign_loss
+)]
+fn gamma_table() -> Vec<u8> {
+	(0..GAMMA_STEPS)
+		.map(|i| {
+			let linear = i as f32 / (GAMMA_STEPS - 1) as f32;
+			let encoded = if linear <= 0.003_130_8 {
+				linear * 12.92
+			} else {
+				1.055f32.mul_add(linear.powf(1.0 / 2.4), -0.055)
+			};
+			(encoded * 255.0).round() as u8
+		})
+		.collect()
+}
+
+/// The matrix converting white-balanced camera RGB to linear sRGB, from the camera's
+/// XYZ to camera matrix
+///
+/// The sRGB to camera matrix's rows are normalised so white stays white, then inverted.
+/// Cameras without a known matrix (all zeroes) get the identity.
+fn camera_to_srgb(xyz_to_cam: &[[f32; 3]; 4]) -> [[f32; 3]; 3] {
+	let srgb_to_cam: [[f32; 3]; 3] = std::array::from_fn(|i| {
+		let row: [f32; 3] =
+			std::array::from_fn(|j| (0..3).map(|k| xyz_to_cam[i][k] * SRGB_TO_XYZ[k][j]).sum());
+		let sum: f32 = row.iter().sum();
+		row.map(|value| value / sum)
+	});
+
+	if srgb_to_cam.iter().flatten().any(|value| !value.is_finite()) {
+		return IDENTITY;
+	}
+	invert(srgb_to_cam).unwrap_or(IDENTITY)
+}
+
+#[allow(clippy::many_single_char_names, clippy::suboptimal_flops)]
+fn invert(m: [[f32; 3]; 3]) -> Option<[[f32; 3]; 3]> {
+	let [[a, b, c], [d, e, f], [g, h, i]] = m;
+	let cofactors = [
+		[e * i - f * h, c * h - b * i, b * f - c * e],
+		[f * g - d * i, a * i - c * g, c * d - a * f],
+		[d * h - e * g, b * g - a * h, a * e - b * d],
+	];
+	let det = a * cofactors[0][0] + b * cofactors[1][0] + c * cofactors[2][0];
+	if det.abs() < f32::EPSILON {
+		return None;
+	}
+
+	Some(cofactors.map(|row| row.map(|value| value / det)))
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+	haystack
+		.windows(needle.len())
+		.position(|window| window == needle)
+}
+
+fn slice(data: &[u8], offset: u32, len: u32) -> Option<&[u8]> {
+	let start = offset as usize;
+	data.get(start..start.checked_add(len as usize)?)
+}
+
+fn read_u16(data: &[u8], pos: usize, big_endian: bool) -> Option<u16> {
+	let bytes = data.get(pos..pos.checked_add(2)?)?.try_into().ok()?;
+	Some(if big_endian {
+		u16::from_be_bytes(bytes)
+	} else {
+		u16::from_le_bytes(bytes)
+	})
+}
+
+fn read_u32(data: &[u8], pos: usize, big_endian: bool) -> Option<u32> {
+	let bytes = data.get(pos..pos.checked_add(4)?)?.try_into().ok()?;
+	Some(if big_endian {
+		u32::from_be_bytes(bytes)
+	} else {
+		u32::from_le_bytes(bytes)
+	})
+}
+
+fn read_u64(data: &[u8], pos: usize) -> Option<u64> {
+	let bytes = data.get(pos..pos.checked_add(8)?)?.try_into().ok()?;
+	Some(u64::from_be_bytes(bytes))
+}
+
+fn write_u16(data: &mut [u8], pos: usize, value: u16, big_endian: bool) {
+	let bytes = if big_endian {
+		value.to_be_bytes()
+	} else {
+		value.to_le_bytes()
+	};
+	data[pos..pos + 2].copy_from_slice(&bytes);
+}
+
+fn write_u32(data: &mut [u8], pos: usize, value: u32, big_endian: bool) {
+	let bytes = if big_endian {
+		value.to_be_bytes()
+	} else {
+		value.to_le_bytes()
+	};
+	data[pos..pos + 4].copy_from_slice(&bytes);
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::cast_possible_truncation)]
+mod tests {
+	use super::*;
+
+	/// Builds a little-endian TIFF structure, with data blobs placed before the IFDs
+	struct TiffBuilder(Vec<u8>);
+
+	impl TiffBuilder {
+		fn new() -> Self {
+			Self(b"II*\0\0\0\0\0".to_vec())
+		}
+
+		fn blob(&mut self, data: &[u8]) -> u32 {
+			let offset = self.0.len() as u32;
+			self.0.extend_from_slice(data);
+			self.0.resize(self.0.len() + self.0.len() % 2, 0);
+			offset
+		}
+
+		/// Appends an IFD of `(tag, type, count, value or offset)` entries
+		fn ifd(&mut self, entries: &[(u16, u16, u32, u32)], next: u32) -> u32 {
+			let offset = self.0.len() as u32;
+			self.0
+				.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+			for (tag, kind, count, value) in entries {
+				self.0.extend_from_slice(&tag.to_le_bytes());
+				self.0.extend_from_slice(&kind.to_le_bytes());
+				self.0.extend_from_slice(&count.to_le_bytes());
+				self.0.extend_from_slice(&value.to_le_bytes());
+			}
+			self.0.extend_from_slice(&next.to_le_bytes());
+			offset
+		}
+
+		fn finish(mut self, first_ifd: u32) -> Vec<u8> {
+			self.0[4..8].copy_from_slice(&first_ifd.to_le_bytes());
+			self.0
+		}
+	}
+
+	fn jpeg(width: u32, height: u32) -> Vec<u8> {
+		let mut data = Cursor::new(Vec::new());
+		DynamicImage::new_rgb8(width, height)
+			.write_to(&mut data, ImageFormat::Jpeg)
+			.unwrap();
+		data.into_inner()
+	}
+
+	fn iso_box(kind: [u8; 4], content: &[u8]) -> Vec<u8> {
+		let mut data = ((content.len() + 8) as u32).to_be_bytes().to_vec();
+		data.extend_from_slice(&kind);
+		data.extend_from_slice(content);
+		data
+	}
+
+	#[test]
+	fn test_tiff_previews_and_dimensions() {
+		let mut tiff = TiffBuilder::new();
+		let full = jpeg(1200, 800);
+		let thumbnail = jpeg(160, 120);
+		let full_offset = tiff.blob(&full);
+		let thumbnail_offset = tiff.blob(&thumbnail);
+		// A lossless JPEG strip, like CR2's sensor data
+		let lossless_offset = tiff.blob(&[0xff, 0xd8, 0xff, 0xc3, 0, 11, 8, 0, 16, 0, 16, 1]);
+
+		let raw_ifd = tiff.ifd(
+			&[
+				(COMPRESSION, 3, 1, 6),
+				(STRIP_OFFSETS, 4, 1, lossless_offset),
+				(STRIP_BYTE_COUNTS, 4, 1, 12),
+			],
+			0,
+		);
+		let thumbnail_ifd = tiff.ifd(
+			&[
+				(JPEG_OFFSET, 4, 1, thumbnail_offset),
+				(JPEG_LENGTH, 4, 1, thumbnail.len() as u32),
+			],
+			raw_ifd,
+		);
+		let ifd0 = tiff.ifd(
+			&[
+				(IMAGE_WIDTH, 3, 1, 1200),
+				(IMAGE_LENGTH, 3, 1, 800),
+				(COMPRESSION, 3, 1, 6),
+				(STRIP_OFFSETS, 4, 1, full_offset),
+				(STRIP_BYTE_COUNTS, 4, 1, full.len() as u32),
+			],
+			thumbnail_ifd,
+		);
+		let mut data = tiff.finish(ifd0);
+
+		let container = Container::detect(&data).unwrap();
+		let mut sizes = container
+			.previews()
+			.iter()
+			.map(|preview| (preview.width, preview.height))
+			.collect::<Vec<_>>();
+		sizes.sort_unstable();
+		assert_eq!(sizes, [(160, 120), (1200, 800)]);
+		assert_eq!(container.dimensions(), Some((1200, 800)));
+		assert!(matches!(container.exif(), Some(Cow::Borrowed(_))));
+
+		// Olympus' magic number is swapped for the standard one
+		data[2..4].copy_from_slice(b"RO");
+		let exif = Container::detect(&data).unwrap().exif().unwrap();
+		assert_eq!(&exif[..4], b"II*\0");
+	}
+
+	#[test]
+	fn test_cr3_metadata() {
+		let mut cmt1 = TiffBuilder::new();
+		let ifd0 = cmt1.ifd(&[(0x0112, 3, 1, 6)], 0);
+		let cmt1 = cmt1.finish(ifd0);
+
+		// ExposureTime is stored out of line, so it has to move with the IFD
+		let mut cmt2 = TiffBuilder::new();
+		let exposure = cmt2.blob(&[1, 0, 0, 0, 250, 0, 0, 0]);
+		let exif_ifd = cmt2.ifd(
+			&[
+				(0x829a, 5, 1, exposure),
+				(PIXEL_X_DIMENSION, 4, 1, 6000),
+				(PIXEL_Y_DIMENSION, 4, 1, 4000),
+			],
+			0,
+		);
+		let cmt2 = cmt2.finish(exif_ifd);
+
+		let mut metadata = CR3_METADATA_UUID.to_vec();
+		metadata.extend(iso_box(*b"CMT1", &cmt1));
+		metadata.extend(iso_box(*b"CMT2", &cmt2));
+		metadata.extend(iso_box(
+			*b"THMB",
+			&[[0; 16].as_slice(), &jpeg(160, 120)].concat(),
+		));
+		let mut preview = CR3_PREVIEW_UUID.to_vec();
+		preview.extend([0; 8]);
+		preview.extend(iso_box(
+			*b"PRVW",
+			&[[0; 16].as_slice(), &jpeg(1620, 1080)].concat(),
+		));
+
+		let data = [
+			iso_box(*b"ftyp", b"crx \0\0\0\x01"),
+			iso_box(*b"moov", &iso_box(*b"uuid", &metadata)),
+			iso_box(*b"uuid", &preview),
+		]
+		.concat();
+
+		let container = Container::detect(&data).unwrap();
+		let mut sizes = container
+			.previews()
+			.iter()
+			.map(|preview| (preview.width, preview.height))
+			.collect::<Vec<_>>();
+		sizes.sort_unstable();
+		assert_eq!(sizes, [(160, 120), (1620, 1080)]);
+		assert_eq!(container.dimensions(), Some((6000, 4000)));
+
+		let exif = container.exif().unwrap();
+		let tiff = Tiff::new(&exif).unwrap();
+		let ifd0 = tiff.ifd(tiff.first_ifd().unwrap()).unwrap();
+		assert_eq!(tiff.value(ifd0.get(0x0112).unwrap(), 0), Some(6));
+
+		let exif_ifd = tiff
+			.ifd(tiff.value(ifd0.get(EXIF_IFD).unwrap(), 0).unwrap() as usize)
+			.unwrap();
+		let exposure = tiff.value_pos(exif_ifd.get(0x829a).unwrap()).unwrap();
+		assert_eq!(tiff.u32(exposure), Some(1));
+		assert_eq!(tiff.u32(exposure + 4), Some(250));
+	}
+
+	#[test]
+	fn test_dng_demosaic() {
+		// A 4x4 RGGB sensor with a saturated left half and a dark right half
+		let mut tiff = TiffBuilder::new();
+		let sensor = (0..16)
+			.flat_map(|i| if i % 4 < 2 { 1000u16 } else { 0 }.to_le_bytes())
+			.collect::<Vec<_>>();
+		let sensor_offset = tiff.blob(&sensor);
+
+		let ifd0 = tiff.ifd(
+			&[
+				(IMAGE_WIDTH, 3, 1, 4),
+				(IMAGE_LENGTH, 3, 1, 4),
+				(0x0102, 3, 1, 16),
+				(COMPRESSION, 3, 1, 1),
+				(0x0106, 3, 1, 32803),
+				(0x010f, 2, 4, u32::from_le_bytes(*b"Foo\0")),
+				(0x0110, 2, 4, u32::from_le_bytes(*b"Bar\0")),
+				(STRIP_OFFSETS, 4, 1, sensor_offset),
+				(0x0115, 3, 1, 1),
+				(STRIP_BYTE_COUNTS, 4, 1, sensor.len() as u32),
+				(0x828e, 1, 4, u32::from_le_bytes([0, 1, 1, 2])),
+				(0xc612, 1, 4, u32::from_le_bytes([1, 4, 0, 0])),
+				(0xc61d, 3, 1, 1000),
+			],
+			0,
+		);
+		let data = tiff.finish(ifd0);
+
+		// There's no preview, so the sensor data is developed in 2x2 blocks
+		let img = RawFile::from_bytes(data).decode().unwrap().into_rgb8();
+		assert_eq!(img.dimensions(), (2, 2));
+		for y in 0..2 {
+			assert_eq!(img.get_pixel(0, y).0, [255, 255, 255]);
+			assert_eq!(img.get_pixel(1, y).0, [0, 0, 0]);
+		}
+	}
+}
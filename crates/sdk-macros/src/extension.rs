@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{
 	parse::{Parse, ParseStream},
-	parse_macro_input, Expr, Ident, ItemStruct, LitStr, Result, Token,
+	parse_macro_input, Expr, Ident, ItemStruct, LitStr, Path, Result, Token,
 };
 
 struct ExtensionArgs {
@@ -12,6 +12,7 @@ struct ExtensionArgs {
 	name: String,
 	version: String,
 	jobs: Vec<Ident>,
+	hooks: Vec<Path>,
 	// We'll ignore other parameters for now (description, permissions, etc.)
 	// They can be used by tooling but don't need codegen
 }
@@ -22,6 +23,7 @@ impl Parse for ExtensionArgs {
 		let mut name = None;
 		let mut version = None;
 		let mut jobs = Vec::new();
+		let mut hooks = Vec::new();
 
 		while !input.is_empty() {
 			let ident: Ident = input.parse()?;
@@ -50,6 +52,16 @@ impl Parse for ExtensionArgs {
 						}
 					}
 				}
+				"hooks" => {
+					let content;
+					syn::bracketed!(content in input);
+					while !content.is_empty() {
+						hooks.push(content.parse()?);
+						if content.peek(Token![,]) {
+							content.parse::<Token![,]>()?;
+						}
+					}
+				}
 				// Ignore other parameters - just skip their values
 				"description" | "min_core_version" => {
 					let _: LitStr = input.parse()?;
@@ -95,6 +107,7 @@ impl Parse for ExtensionArgs {
 			name: name.ok_or_else(|| input.error("missing name parameter"))?,
 			version: version.ok_or_else(|| input.error("missing version parameter"))?,
 			jobs,
+			hooks,
 		})
 	}
 }
@@ -127,6 +140,26 @@ pub fn extension_impl(args: TokenStream, input: TokenStream) -> TokenStream {
 		}
 	});
 
+	// Generate hook registration code. Hooks in impl blocks are named by path, so the
+	// helper is found by replacing the last segment.
+	let hook_registrations = args.hooks.iter().map(|hook_path| {
+		let mut register_fn = hook_path.clone();
+		if let Some(last) = register_fn.segments.last_mut() {
+			last.ident = quote::format_ident!("__register_{}", last.ident);
+		}
+		quote! {
+			{
+				let (kind, spec, export_fn) = #register_fn();
+				::spacedrive_sdk::ffi::log_info(&format!("Registering {} hook: {}", kind, export_fn));
+
+				if let Err(_) = ::spacedrive_sdk::ffi::register_hook_with_host(kind, spec, export_fn) {
+					::spacedrive_sdk::ffi::log_error(&format!("Failed to register hook: {}", export_fn));
+					return 1;
+				}
+			}
+		}
+	});
+
 	let expanded = quote! {
 		#input_struct
 
@@ -142,6 +175,9 @@ pub fn extension_impl(args: TokenStream, input: TokenStream) -> TokenStream {
 			// Register all jobs
 			#(#job_registrations)*
 
+			// Register event, schedule and startup hooks
+			#(#hook_registrations)*
+
 			::spacedrive_sdk::ffi::log_info(&format!(
 				"✓ {} v{} initialized!",
 				#ext_name,
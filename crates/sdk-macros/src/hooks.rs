@@ -0,0 +1,241 @@
+//! Hook macro implementations (on_startup, on_event, scheduled, thumbnailer,
+//! metadata_extractor)
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+	parse::{Parse, ParseStream},
+	parse_macro_input,
+	punctuated::Punctuated,
+	Ident, ItemFn, LitStr, Token,
+};
+
+/// Arguments for `#[scheduled(cron = "...")]` or `#[scheduled(interval = "5m")]`
+struct ScheduleArgs {
+	schedule: String,
+}
+
+impl Parse for ScheduleArgs {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let ident: Ident = input.parse()?;
+		input.parse::<Token![=]>()?;
+		let lit: LitStr = input.parse()?;
+
+		let schedule = match ident.to_string().as_str() {
+			"cron" => format!("cron:{}", lit.value()),
+			"interval" => format!("interval:{}", lit.value()),
+			_ => {
+				return Err(syn::Error::new(
+					ident.span(),
+					"expected `cron` or `interval`",
+				))
+			}
+		};
+
+		Ok(ScheduleArgs { schedule })
+	}
+}
+
+/// Arguments for `#[thumbnailer(mime = [...], extensions = [...])]`, rendered as the JSON
+/// array of patterns the host expects (MIME types as given, extensions with a leading dot)
+struct MediaHandlerArgs {
+	patterns: String,
+}
+
+impl Parse for MediaHandlerArgs {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let mut patterns = Vec::new();
+
+		while !input.is_empty() {
+			let ident: Ident = input.parse()?;
+			input.parse::<Token![=]>()?;
+
+			let content;
+			syn::bracketed!(content in input);
+			let values = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+
+			for lit in values {
+				let value = lit.value();
+				let pattern = match ident.to_string().as_str() {
+					"mime" if value.contains('/') => value,
+					"extensions" if !value.trim_start_matches('.').is_empty() => {
+						format!(".{}", value.trim_start_matches('.'))
+					}
+					"mime" | "extensions" => {
+						return Err(syn::Error::new(lit.span(), "invalid pattern"))
+					}
+					_ => {
+						return Err(syn::Error::new(
+							ident.span(),
+							"expected `mime` or `extensions`",
+						))
+					}
+				};
+				if pattern.contains(['"', '\\']) {
+					return Err(syn::Error::new(lit.span(), "invalid pattern"));
+				}
+				patterns.push(format!("\"{}\"", pattern));
+			}
+
+			if input.peek(Token![,]) {
+				input.parse::<Token![,]>()?;
+			}
+		}
+
+		if patterns.is_empty() {
+			return Err(input.error("expected `mime = [...]` or `extensions = [...]`"));
+		}
+
+		Ok(MediaHandlerArgs {
+			patterns: format!("[{}]", patterns.join(",")),
+		})
+	}
+}
+
+pub fn on_startup_impl(_args: TokenStream, input: TokenStream) -> TokenStream {
+	let input_fn = parse_macro_input!(input as ItemFn);
+	hook(input_fn, "startup", String::new(), "on_startup")
+}
+
+pub fn on_event_impl(args: TokenStream, input: TokenStream) -> TokenStream {
+	let event_type = parse_macro_input!(args as Ident);
+	let input_fn = parse_macro_input!(input as ItemFn);
+	hook(input_fn, "event", event_type.to_string(), "on_event")
+}
+
+pub fn scheduled_impl(args: TokenStream, input: TokenStream) -> TokenStream {
+	let args = parse_macro_input!(args as ScheduleArgs);
+	let input_fn = parse_macro_input!(input as ItemFn);
+	hook(input_fn, "schedule", args.schedule, "scheduled")
+}
+
+pub fn thumbnailer_impl(args: TokenStream, input: TokenStream) -> TokenStream {
+	let args = parse_macro_input!(args as MediaHandlerArgs);
+	let input_fn = parse_macro_input!(input as ItemFn);
+	media_handler(input_fn, "thumbnailer", args.patterns)
+}
+
+pub fn metadata_extractor_impl(args: TokenStream, input: TokenStream) -> TokenStream {
+	let args = parse_macro_input!(args as MediaHandlerArgs);
+	let input_fn = parse_macro_input!(input as ItemFn);
+	media_handler(input_fn, "metadata", args.patterns)
+}
+
+/// Emit the handler with an FFI export the host calls and a registration helper
+/// for `#[extension(hooks = [...])]`
+///
+/// The generated items are associated functions when the handler is in an impl block,
+/// so the hooks list names them by path (e.g. `Photos::on_new_photo`).
+fn hook(input_fn: ItemFn, kind: &str, spec: String, prefix: &str) -> TokenStream {
+	let fn_name = &input_fn.sig.ident;
+	let export_name = format_ident!("{}_{}", prefix, fn_name);
+	let export_name_str = export_name.to_string();
+	let register_fn_name = format_ident!("__register_{}", fn_name);
+
+	let expanded = quote! {
+		// Keep original function for internal use
+		#input_fn
+
+		// Registration helper function
+		// This will be called by plugin_init
+		#[doc(hidden)]
+		pub fn #register_fn_name() -> (&'static str, &'static str, &'static str) {
+			(#kind, #spec, #export_name_str)
+		}
+
+		// Generate FFI export
+		#[no_mangle]
+		pub extern "C" fn #export_name(payload_ptr: u32, payload_len: u32) -> i32 {
+			let payload = unsafe {
+				let slice = ::std::slice::from_raw_parts(
+					payload_ptr as *const u8,
+					payload_len as usize
+				);
+				::std::str::from_utf8(slice).unwrap_or("{}")
+			};
+
+			if let Err(e) = ::serde_json::from_str::<::serde_json::Value>(payload) {
+				::spacedrive_sdk::ffi::log_error(&format!("Invalid hook payload: {}", e));
+				return 1;
+			}
+
+			// STUB: Like #[job], running the async handler needs async support at the
+			// WASM FFI boundary. For now the export only acknowledges the call.
+			::spacedrive_sdk::ffi::log_debug(&format!("Hook {} called", #export_name_str));
+			0
+		}
+	};
+
+	TokenStream::from(expanded)
+}
+
+/// Emit a thumbnailer or metadata extractor with an FFI export that calls it
+///
+/// Unlike other hooks the handler is synchronous, so the export runs it and hands its
+/// result back with `media_output`. The export calls the handler by name, so it must
+/// be a free function rather than an associated one.
+fn media_handler(input_fn: ItemFn, kind: &str, patterns: String) -> TokenStream {
+	let fn_name = &input_fn.sig.ident;
+	let export_name = format_ident!("{}_{}", kind, fn_name);
+	let export_name_str = export_name.to_string();
+	let register_fn_name = format_ident!("__register_{}", fn_name);
+
+	// Thumbnailers return image bytes, metadata extractors anything serializable
+	let output = if kind == "thumbnailer" {
+		quote! { ::std::result::Result::<_, ::spacedrive_sdk::Error>::Ok(output) }
+	} else {
+		quote! {
+			::serde_json::to_vec(&output)
+				.map_err(|e| ::spacedrive_sdk::Error::Serialization(e.to_string()))
+		}
+	};
+
+	let expanded = quote! {
+		#input_fn
+
+		#[doc(hidden)]
+		pub fn #register_fn_name() -> (&'static str, &'static str, &'static str) {
+			(#kind, #patterns, #export_name_str)
+		}
+
+		#[no_mangle]
+		pub extern "C" fn #export_name(
+			request_ptr: u32,
+			request_len: u32,
+			data_ptr: u32,
+			data_len: u32,
+		) -> i32 {
+			let read = |ptr: u32, len: u32| -> &'static [u8] {
+				if len == 0 {
+					return &[];
+				}
+				unsafe { ::std::slice::from_raw_parts(ptr as *const u8, len as usize) }
+			};
+
+			let file = match ::spacedrive_sdk::media::MediaFile::from_raw(
+				read(request_ptr, request_len),
+				read(data_ptr, data_len).to_vec(),
+			) {
+				Ok(file) => file,
+				Err(e) => {
+					::spacedrive_sdk::ffi::log_error(&format!("Invalid media request: {}", e));
+					return 1;
+				}
+			};
+
+			let result = #fn_name(&file).and_then(|output| #output);
+			match result {
+				Ok(bytes) => match ::spacedrive_sdk::ffi::set_media_output(&bytes) {
+					Ok(()) => 0,
+					Err(()) => 1,
+				},
+				Err(e) => {
+					::spacedrive_sdk::ffi::log_warn(&format!("{} failed: {}", #export_name_str, e));
+					1
+				}
+			}
+		}
+	};
+
+	TokenStream::from(expanded)
+}
@@ -8,6 +8,7 @@ use proc_macro::TokenStream;
 mod action;
 mod agent;
 mod extension;
+mod hooks;
 mod job;
 mod model;
 mod query;
@@ -86,21 +87,56 @@ pub fn agent_trail(_args: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 /// On startup handler macro
+///
+/// Generates an `on_startup_<name>` export the host calls once the extension has loaded.
+/// List the handler in `#[extension(hooks = [...])]` to register it.
 #[proc_macro_attribute]
-pub fn on_startup(_args: TokenStream, input: TokenStream) -> TokenStream {
-	input // Pass through for now
+pub fn on_startup(args: TokenStream, input: TokenStream) -> TokenStream {
+	hooks::on_startup_impl(args, input)
 }
 
 /// On event handler macro
+///
+/// Generates an `on_event_<name>` export the host calls with each event of the given
+/// type, e.g. `#[on_event(EntryCreated)]`. List the handler in
+/// `#[extension(hooks = [...])]` to register it.
 #[proc_macro_attribute]
-pub fn on_event(_args: TokenStream, input: TokenStream) -> TokenStream {
-	input // Pass through for now
+pub fn on_event(args: TokenStream, input: TokenStream) -> TokenStream {
+	hooks::on_event_impl(args, input)
 }
 
 /// Scheduled task macro
+///
+/// Generates a `scheduled_<name>` export the host calls on a schedule, given as
+/// `cron = "0 9 * * SUN"` or `interval = "5m"`. List the handler in
+/// `#[extension(hooks = [...])]` to register it.
 #[proc_macro_attribute]
-pub fn scheduled(_args: TokenStream, input: TokenStream) -> TokenStream {
-	input // Pass through for now
+pub fn scheduled(args: TokenStream, input: TokenStream) -> TokenStream {
+	hooks::scheduled_impl(args, input)
+}
+
+/// Thumbnailer macro
+///
+/// Marks a function that renders a preview for the files it matches, e.g.
+/// `#[thumbnailer(mime = ["application/x-blender"], extensions = ["blend"])]`. The
+/// function takes a `&MediaFile` and returns `Result<Vec<u8>>` holding an encoded image
+/// (PNG, JPEG, WebP, ...). Generates a `thumbnailer_<name>` export; list the function in
+/// `#[extension(hooks = [...])]` to register it.
+#[proc_macro_attribute]
+pub fn thumbnailer(args: TokenStream, input: TokenStream) -> TokenStream {
+	hooks::thumbnailer_impl(args, input)
+}
+
+/// Metadata extractor macro
+///
+/// Marks a function that extracts metadata from the files it matches, given as
+/// `mime = [...]` and `extensions = [...]` like `#[thumbnailer]`. The function takes a
+/// `&MediaFile` and returns a `Result` of any serializable value, stored as JSON in the
+/// file's metadata sidecar. Generates a `metadata_<name>` export; list the function in
+/// `#[extension(hooks = [...])]` to register it.
+#[proc_macro_attribute]
+pub fn metadata_extractor(args: TokenStream, input: TokenStream) -> TokenStream {
+	hooks::metadata_extractor_impl(args, input)
 }
 
 /// Filter attribute for event handlers
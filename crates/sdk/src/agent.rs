@@ -57,50 +57,100 @@ impl<M: AgentMemory> AgentContext<M> {
 pub struct JobDispatcher;
 
 impl JobDispatcher {
-	pub fn dispatch<J, A>(&self, _job: J, _args: A) -> JobDispatchBuilder {
-		JobDispatchBuilder::default()
+	/// Dispatch one of this extension's `#[job]` functions with its initial state
+	pub fn dispatch<J, A: Serialize>(&self, _job: J, args: A) -> JobDispatchBuilder {
+		// A function item's type name is its path, ending in the function name
+		let job_name = std::any::type_name::<J>()
+			.rsplit("::")
+			.next()
+			.unwrap_or_default()
+			.to_string();
+
+		JobDispatchBuilder {
+			job_name,
+			args: serde_json::to_string(&args).map_err(|e| Error::Serialization(e.to_string())),
+			priority: Priority::Normal,
+			when_idle: false,
+			library_id: None,
+		}
 	}
 }
 
-#[derive(Default)]
-pub struct JobDispatchBuilder;
+pub struct JobDispatchBuilder {
+	job_name: String,
+	args: Result<String>,
+	priority: Priority,
+	when_idle: bool,
+	library_id: Option<Uuid>,
+}
 
 impl JobDispatchBuilder {
-	pub fn priority(self, _priority: Priority) -> Self {
+	pub fn priority(mut self, priority: Priority) -> Self {
+		self.priority = priority;
 		self
 	}
 
-	pub fn when_idle(self) -> Self {
+	/// Hold the job back until the system is idle
+	pub fn when_idle(mut self) -> Self {
+		self.when_idle = true;
 		self
 	}
 
+	/// Library to run the job in. Defaults to the library of the event being handled.
+	pub fn in_library(mut self, library_id: Uuid) -> Self {
+		self.library_id = Some(library_id);
+		self
+	}
+
+	/// Jobs currently run on this device
 	pub fn on_device_with_capability(self, _cap: Capability) -> Self {
 		self
 	}
 
 	pub async fn execute(self) -> Result<()> {
-		panic!("Dispatch job")
+		let args = self.args?;
+		let priority = match self.priority {
+			Priority::Low => -1,
+			Priority::Normal => 0,
+			Priority::High => 1,
+		};
+
+		crate::ffi::dispatch_job(
+			&self.job_name,
+			self.library_id,
+			&args,
+			priority,
+			self.when_idle,
+		)
+		.map_err(|_| Error::HostCall(format!("Failed to dispatch job {}", self.job_name)))
 	}
 }
 
 #[derive(Default)]
-pub struct NotificationBuilder;
+pub struct NotificationBuilder {
+	title: Option<String>,
+	message: String,
+}
 
 impl NotificationBuilder {
-	pub fn message(self, _msg: impl Into<String>) -> Self {
+	pub fn message(mut self, msg: impl Into<String>) -> Self {
+		self.message = msg.into();
 		self
 	}
 
+	/// Notifications go to every connected client
 	pub fn on_active_device(self) -> Self {
 		self
 	}
 
-	pub fn with_title(self, _title: impl Into<String>) -> Self {
+	pub fn with_title(mut self, title: impl Into<String>) -> Self {
+		self.title = Some(title.into());
 		self
 	}
 
 	pub async fn send(self) -> Result<()> {
-		panic!("Send notification")
+		crate::ffi::send_notification(self.title.as_deref(), &self.message)
+			.map_err(|_| Error::HostCall("Failed to send notification".into()))
 	}
 }
 
@@ -13,8 +13,50 @@ extern "C" {
 		export_fn_len: u32,
 		resumable: u32,
 	) -> i32;
+	fn register_event_handler(
+		event_type_ptr: *const u8,
+		event_type_len: u32,
+		export_fn_ptr: *const u8,
+		export_fn_len: u32,
+		filters_ptr: *const u8,
+		filters_len: u32,
+	) -> i32;
+	fn register_schedule(
+		schedule_ptr: *const u8,
+		schedule_len: u32,
+		export_fn_ptr: *const u8,
+		export_fn_len: u32,
+	) -> i32;
+	fn register_startup_handler(export_fn_ptr: *const u8, export_fn_len: u32) -> i32;
+	fn register_media_handler(
+		kind: u32,
+		patterns_ptr: *const u8,
+		patterns_len: u32,
+		export_fn_ptr: *const u8,
+		export_fn_len: u32,
+	) -> i32;
+	fn media_read(offset: u64, buf_ptr: *mut u8, buf_len: u32) -> i64;
+	fn media_output(output_ptr: *const u8, output_len: u32) -> i32;
+	fn job_dispatch(
+		job_name_ptr: *const u8,
+		job_name_len: u32,
+		library_id_ptr: *const u8,
+		args_ptr: *const u8,
+		args_len: u32,
+		priority: i32,
+		flags: u32,
+	) -> i32;
+	fn notify(
+		title_ptr: *const u8,
+		title_len: u32,
+		message_ptr: *const u8,
+		message_len: u32,
+	) -> i32;
 }
 
+/// `job_dispatch` flag: hold the job back until the system is idle
+const JOB_DISPATCH_WHEN_IDLE: u32 = 1;
+
 /// Log a message (info level)
 pub fn log_info(message: &str) {
 	unsafe {
@@ -80,3 +122,122 @@ pub fn register_job_with_host(job_name: &str, export_fn: &str, resumable: bool)
 		Err(())
 	}
 }
+
+/// Register a hook with the extension system
+///
+/// Called automatically by #[extension] macro during plugin_init() for each entry in
+/// `hooks`. `kind` is "event" (spec = event type), "schedule" (spec = schedule string),
+/// "startup", "thumbnailer" or "metadata" (spec = JSON array of MIME types and dotted
+/// file extensions).
+pub fn register_hook_with_host(kind: &str, spec: &str, export_fn: &str) -> Result<(), ()> {
+	let result = unsafe {
+		match kind {
+			"event" => register_event_handler(
+				spec.as_ptr(),
+				spec.len() as u32,
+				export_fn.as_ptr(),
+				export_fn.len() as u32,
+				std::ptr::null(),
+				0,
+			),
+			"schedule" => register_schedule(
+				spec.as_ptr(),
+				spec.len() as u32,
+				export_fn.as_ptr(),
+				export_fn.len() as u32,
+			),
+			"startup" => register_startup_handler(export_fn.as_ptr(), export_fn.len() as u32),
+			"thumbnailer" | "metadata" => register_media_handler(
+				if kind == "thumbnailer" { 0 } else { 1 },
+				spec.as_ptr(),
+				spec.len() as u32,
+				export_fn.as_ptr(),
+				export_fn.len() as u32,
+			),
+			_ => 1,
+		}
+	};
+
+	if result == 0 {
+		Ok(())
+	} else {
+		Err(())
+	}
+}
+
+/// Dispatch one of this extension's jobs
+///
+/// Without a library ID the job goes to the library of the event being handled.
+pub fn dispatch_job(
+	job_name: &str,
+	library_id: Option<uuid::Uuid>,
+	args_json: &str,
+	priority: i32,
+	when_idle: bool,
+) -> Result<(), ()> {
+	let library_id_ptr = library_id
+		.as_ref()
+		.map_or(std::ptr::null(), |id| id.as_bytes().as_ptr());
+
+	let result = unsafe {
+		job_dispatch(
+			job_name.as_ptr(),
+			job_name.len() as u32,
+			library_id_ptr,
+			args_json.as_ptr(),
+			args_json.len() as u32,
+			priority,
+			if when_idle { JOB_DISPATCH_WHEN_IDLE } else { 0 },
+		)
+	};
+
+	if result == 0 {
+		Ok(())
+	} else {
+		Err(())
+	}
+}
+
+/// Send a notification to connected clients
+pub fn send_notification(title: Option<&str>, message: &str) -> Result<(), ()> {
+	let title = title.unwrap_or("");
+
+	let result = unsafe {
+		notify(
+			title.as_ptr(),
+			title.len() as u32,
+			message.as_ptr(),
+			message.len() as u32,
+		)
+	};
+
+	if result == 0 {
+		Ok(())
+	} else {
+		Err(())
+	}
+}
+
+/// Read part of the file the running media handler was called for
+///
+/// Returns the number of bytes read, 0 at the end of the file.
+pub fn read_media(offset: u64, buf: &mut [u8]) -> Result<usize, ()> {
+	let result = unsafe { media_read(offset, buf.as_mut_ptr(), buf.len() as u32) };
+
+	if result >= 0 {
+		Ok(result as usize)
+	} else {
+		Err(())
+	}
+}
+
+/// Hand the running media handler's result back to the host
+pub fn set_media_output(output: &[u8]) -> Result<(), ()> {
+	let result = unsafe { media_output(output.as_ptr(), output.len() as u32) };
+
+	if result == 0 {
+		Ok(())
+	} else {
+		Err(())
+	}
+}
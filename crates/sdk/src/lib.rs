@@ -35,6 +35,7 @@ pub mod agent;
 pub mod ai;
 pub mod ffi;
 pub mod job_context;
+pub mod media;
 pub mod models;
 pub mod query;
 pub mod tasks;
@@ -50,6 +51,7 @@ pub use agent::{
 };
 pub use ai::*;
 pub use job_context::JobContext as SdkJobContext;
+pub use media::{MediaFile, MediaRequest};
 pub use models::*;
 pub use query::*;
 pub use tasks::*;
@@ -62,6 +64,7 @@ pub mod prelude {
 	pub use crate::agent::*;
 	pub use crate::ai::*;
 	pub use crate::job_context::{JobContext, JobResult};
+	pub use crate::media::{MediaFile, MediaRequest};
 	pub use crate::models::*;
 	pub use crate::query::*;
 	pub use crate::tasks::*;
@@ -73,5 +76,6 @@ pub mod prelude {
 // Re-export macros
 pub use spacedrive_sdk_macros::{
 	action, action_execute, agent, agent_memory, agent_trail, extension, filter, job,
-	memory_config, model, on_event, on_startup, persist_strategy, query, scheduled, setting, task,
+	memory_config, metadata_extractor, model, on_event, on_startup, persist_strategy, query,
+	scheduled, setting, task, thumbnailer,
 };
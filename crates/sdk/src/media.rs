@@ -0,0 +1,111 @@
+//! Thumbnailers and metadata extractors
+//!
+//! Functions marked `#[thumbnailer(...)]` or `#[metadata_extractor(...)]` and listed in
+//! `#[extension(hooks = [...])]` handle the files they match. They receive a `MediaFile`
+//! and return either an encoded image, which Spacedrive resizes into thumbnails, or a
+//! serializable value stored as the file's metadata sidecar.
+//!
+//! ```ignore
+//! #[thumbnailer(mime = ["application/x-blender"], extensions = ["blend"])]
+//! fn blend_preview(file: &MediaFile) -> Result<Vec<u8>> {
+//!     // .blend files embed a preview image near the start
+//!     find_preview(file.head())
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::ffi;
+use crate::types::{Error, Result};
+
+/// What the host asked a media handler for
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MediaRequest {
+	pub mime_type: Option<String>,
+	/// File extension without the dot
+	pub extension: Option<String>,
+	pub file_size: u64,
+	/// Size in pixels of the largest thumbnail that will be made from the image
+	/// (thumbnailers only)
+	pub target_size: Option<u32>,
+}
+
+/// A file handed to a media handler
+#[derive(Debug, Clone)]
+pub struct MediaFile {
+	request: MediaRequest,
+	head: Vec<u8>,
+}
+
+impl MediaFile {
+	/// Build the file from what the host passed to the handler export
+	#[doc(hidden)]
+	pub fn from_raw(request_json: &[u8], head: Vec<u8>) -> Result<Self> {
+		let request = serde_json::from_slice(request_json)
+			.map_err(|e| Error::Deserialization(e.to_string()))?;
+
+		Ok(Self { request, head })
+	}
+
+	pub fn request(&self) -> &MediaRequest {
+		&self.request
+	}
+
+	pub fn mime_type(&self) -> Option<&str> {
+		self.request.mime_type.as_deref()
+	}
+
+	pub fn extension(&self) -> Option<&str> {
+		self.request.extension.as_deref()
+	}
+
+	/// Size of the whole file in bytes
+	pub fn size(&self) -> u64 {
+		self.request.file_size
+	}
+
+	/// Bytes from the start of the file, passed with the call
+	///
+	/// Small files arrive whole; check `is_complete()` before relying on that.
+	pub fn head(&self) -> &[u8] {
+		&self.head
+	}
+
+	/// Whether `head()` holds the whole file
+	pub fn is_complete(&self) -> bool {
+		self.head.len() as u64 >= self.request.file_size
+	}
+
+	/// Read up to `len` bytes at `offset`, fewer at the end of the file
+	pub fn read_at(&self, offset: u64, len: usize) -> Result<Vec<u8>> {
+		let end = offset.saturating_add(len as u64).min(self.size());
+		if offset >= end {
+			return Ok(Vec::new());
+		}
+		if end <= self.head.len() as u64 {
+			return Ok(self.head[offset as usize..end as usize].to_vec());
+		}
+
+		let mut data = vec![0u8; (end - offset) as usize];
+		let mut filled = 0;
+		while filled < data.len() {
+			let read = ffi::read_media(offset + filled as u64, &mut data[filled..])
+				.map_err(|_| Error::HostCall("media_read failed".to_string()))?;
+			if read == 0 {
+				break;
+			}
+			filled += read;
+		}
+		data.truncate(filled);
+
+		Ok(data)
+	}
+
+	/// Read the whole file
+	pub fn read_all(&self) -> Result<Vec<u8>> {
+		if self.is_complete() {
+			return Ok(self.head.clone());
+		}
+		self.read_at(0, self.size() as usize)
+	}
+}
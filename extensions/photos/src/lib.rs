@@ -42,7 +42,12 @@ use spacedrive_sdk::{extension, prelude::*};
         Permission::UseModel(category = "face_detection", preference = ModelPreference::LocalOnly),
         Permission::UseModel(category = "scene_classification", preference = ModelPreference::LocalOnly),
         Permission::DispatchJobs,
-    ]
+    ],
+    hooks = [
+        Photos::initialize,
+        Photos::on_new_photo,
+        Photos::generate_weekly_memories,
+    ],
 )]
 pub struct Photos {
 	config: PhotosConfig,
@@ -75,6 +75,8 @@ export const DEFAULT_EVENT_SUBSCRIPTION: EventVariant[] = [
 	"ResourceChanged",
 	"ResourceChangedBatch",
 	"ResourceDeleted",
+	// Saved search events
+	"SavedSearchMembershipChanged",
 	// Legacy compatibility events
 	"LocationAdded",
 	"LocationRemoved",
@@ -82,6 +84,9 @@ export const DEFAULT_EVENT_SUBSCRIPTION: EventVariant[] = [
 	"ThumbnailsGenerated",
 	"FileOperationCompleted",
 	"FilesModified",
+	// Extension events
+	"ExtensionNotification",
+	"ExtensionSuspended",
 ];
 
 /**
@@ -98,7 +98,11 @@ logging: LoggingConfigOutput;
 /**
  * Proxy pairing configuration
  */
-proxy_pairing: ProxyPairingConfigOutput };
+proxy_pairing: ProxyPairingConfigOutput; 
+/**
+ * Peer file transfer configuration
+ */
+file_transfer: FileTransferConfig };
 
 export type ApplyTagsInput = { 
 /**
@@ -168,12 +172,83 @@ export type ApplyToTargets =
 /**
  * Audio metadata extracted from FFmpeg
  */
+export type ArchiveThumbnailInput = { 
+/**
+ * The file inside an archive to generate thumbnails for
+ */
+path: SdPath; 
+/**
+ * Regenerate thumbnails that already exist
+ */
+force: boolean };
+
+export type ArchiveThumbnailOutput = { 
+/**
+ * ID the thumbnails are stored under, used in place of a content UUID
+ */
+thumbnail_id: string; 
+/**
+ * Variant names that are available
+ */
+variants: string[] };
+
 export type AudioMediaData = { uuid: string; duration_seconds: number | null; bit_rate: number | null; sample_rate: number | null; channels: string | null; codec: string | null; title: string | null; artist: string | null; album: string | null; album_artist: string | null; genre: string | null; year: number | null; track_number: number | null; disc_number: number | null; composer: string | null; publisher: string | null; copyright: string | null };
 
+/**
+ * Bandwidth limits for outgoing transfers, in megabits per second (None = unlimited)
+ */
+export type BandwidthConfig = { 
+/**
+ * Limit shared by all transfers
+ */
+global_limit_mbps?: number | null; 
+/**
+ * Limit for each individual transfer
+ */
+per_transfer_limit_mbps?: number | null; 
+/**
+ * Limits shared by all transfers with a specific device
+ */
+device_limits_mbps?: { [key in string]: number }; 
+/**
+ * Time windows with their own limits, the first active one replaces the limits above
+ */
+schedule?: BandwidthWindow[] };
+
+/**
+ * Days a bandwidth window applies on
+ */
+export type BandwidthDays = "daily" | "weekdays" | "weekends";
+
+/**
+ * Limits that apply during a recurring time window, e.g. office hours
+ */
+export type BandwidthWindow = { 
+/**
+ * Days the window applies on
+ */
+days: BandwidthDays; 
+/**
+ * Local start time, as "HH:MM"
+ */
+start: string; 
+/**
+ * Local end time, as "HH:MM"; windows ending before they start run past midnight
+ */
+end: string; 
+/**
+ * Limit shared by all transfers during the window
+ */
+global_limit_mbps?: number | null; 
+/**
+ * Limit for each individual transfer during the window
+ */
+per_transfer_limit_mbps?: number | null };
+
 /**
  * Cloud service type identifier
  */
-export type CloudServiceType = "s3" | "gdrive" | "dropbox" | "onedrive" | "gcs" | "azblob" | "b2" | "wasabi" | "spaces" | "cloud";
+export type CloudServiceType = "s3" | "gdrive" | "dropbox" | "onedrive" | "gcs" | "azblob" | "b2" | "wasabi" | "spaces" | "sftp" | "webdav" | "cloud";
 
 export type CloudStorageConfig = { type: "S3"; bucket: string; region: string; access_key_id: string; secret_access_key: string; endpoint: string | null } | 
 /**
@@ -191,7 +266,17 @@ export type CloudStorageConfig = { type: "S3"; bucket: string; region: string; a
  * OpenDAL automatically obtains and refreshes access tokens as needed.
  * Only refresh_token is required (not access_token).
  */
-{ type: "Dropbox"; root: string | null; refresh_token: string; client_id: string; client_secret: string } | { type: "AzureBlob"; container: string; endpoint: string | null; account_name: string; account_key: string } | { type: "GoogleCloudStorage"; bucket: string; root: string | null; endpoint: string | null; credential: string };
+{ type: "Dropbox"; root: string | null; refresh_token: string; client_id: string; client_secret: string } | { type: "AzureBlob"; container: string; endpoint: string | null; account_name: string; account_key: string } | { type: "GoogleCloudStorage"; bucket: string; root: string | null; endpoint: string | null; credential: string } | 
+/**
+ * SFTP server (NAS, seedbox, any host running sshd).
+ * Uses the ssh-agent and default identities when no private key is given.
+ */
+{ type: "Sftp"; host: string; port: number | null; username: string; private_key_path: string | null; root: string | null } | 
+/**
+ * WebDAV server (Nextcloud, ownCloud, Synology, etc.).
+ * Username and password may be omitted for anonymous shares.
+ */
+{ type: "WebDav"; endpoint: string; root: string | null; username: string | null; password: string | null };
 
 /**
  * Operators for combining tag attributes
@@ -219,6 +304,11 @@ export type CompositionOperator =
  */
 export type CompositionRule = { operator: CompositionOperator; operands: string[]; result_attribute: string };
 
+/**
+ * Formats archives can be created in
+ */
+export type CompressFormat = "Zip" | "TarZst";
+
 /**
  * Network connection method for a device
  */
@@ -416,7 +506,8 @@ export type CopyMethod =
  */
 export type CopyStrategyMetadata = { 
 /**
- * Internal strategy name (e.g., "LocalMove", "FastCopy", "LocalStream", "RemoteTransfer")
+ * Internal strategy name (e.g., "LocalMove", "FastCopy", "LocalStream", "RemoteTransfer",
+ * "ArchiveExtract")
  */
 strategy_name: string; 
 /**
@@ -854,6 +945,26 @@ export type DownloadWhisperModelOutput = {
  */
 job_id: string };
 
+/**
+ * Text embedding policy
+ * 
+ * Embeds file names, extracted text, OCR output and transcripts so searches
+ * can match files by meaning as well as by keyword.
+ */
+export type EmbeddingPolicy = { 
+/**
+ * Whether to compute embeddings on this location
+ */
+enabled: boolean; 
+/**
+ * Model to use (e.g., "minilm-l6-v2")
+ */
+model: string; 
+/**
+ * Whether to re-embed files that are already indexed
+ */
+reprocess: boolean };
+
 export type EnableIndexingInput = { 
 /**
  * UUID of the location to enable indexing for
@@ -1071,7 +1182,15 @@ resource_type: string;
 /**
  * The deleted resource's ID
  */
-resource_id: string } } | { LocationAdded: { library_id: string; location_id: string; path: string } } | { LocationRemoved: { library_id: string; location_id: string } } | { FilesIndexed: { library_id: string; location_id: string; count: number } } | { ThumbnailsGenerated: { library_id: string; count: number } } | { FileOperationCompleted: { library_id: string; operation: FileOperation; affected_files: number } } | { FilesModified: { library_id: string; paths: string[] } } | { ConfigChanged: { field: string } } | { Custom: { event_type: string } };
+resource_id: string } } | { SavedSearchMembershipChanged: { library_id: string; saved_search_id: string; 
+/**
+ * Entries that now match the search
+ */
+added: string[]; 
+/**
+ * Entries that no longer match the search
+ */
+removed: string[] } } | { LocationAdded: { library_id: string; location_id: string; path: string } } | { LocationRemoved: { library_id: string; location_id: string } } | { FilesIndexed: { library_id: string; location_id: string; count: number } } | { ThumbnailsGenerated: { library_id: string; count: number } } | { FileOperationCompleted: { library_id: string; operation: FileOperation; affected_files: number } } | { FilesModified: { library_id: string; paths: string[] } } | { ConfigChanged: { field: string } } | { ExtensionNotification: { extension_id: string; title: string | null; message: string } } | { ExtensionSuspended: { extension_id: string; reason: string } } | { Custom: { event_type: string } };
 
 /**
  * Event category for grouping related events
@@ -1134,6 +1253,51 @@ export type EventSeverity =
  */
 export type ExportStats = { entries: number; content_identities: number; user_metadata: number; tags: number; media_data: number };
 
+/**
+ * Resource usage and limits of a loaded extension
+ */
+export type ExtensionResourceUsage = { extension_id: string; 
+/**
+ * Calls into the extension, including hooks and job runs
+ */
+calls: number; 
+/**
+ * Fuel used by all calls
+ */
+fuel_consumed: number; 
+/**
+ * Fuel used by the most recent call
+ */
+last_call_fuel: number; max_fuel_per_call: number; max_fuel_per_job: number; 
+/**
+ * Current size of the extension's linear memory
+ */
+memory_bytes: number; max_memory_bytes: number; running_jobs: number; max_concurrent_jobs: number; 
+/**
+ * Host calls abandoned after the timeout
+ */
+host_call_timeouts: number; 
+/**
+ * Why the extension was suspended, if it is
+ */
+suspended_reason: string | null; suspended_at: string | null };
+
+export type ExtensionResumeInput = { extension_id: string };
+
+export type ExtensionResumeOutput = { 
+/**
+ * Whether the extension was suspended
+ */
+resumed: boolean };
+
+export type ExtensionUsageInput = { 
+/**
+ * Only report this extension
+ */
+extension_id?: string | null };
+
+export type ExtensionUsageOutput = { extensions: ExtensionResourceUsage[] };
+
 export type ExtractTextInput = { 
 /**
  * UUID of the entry to extract text from
@@ -1229,6 +1393,27 @@ export type FileByIdQuery = { file_id: string };
  */
 export type FileByPathQuery = { path: string };
 
+/**
+ * Input for compressing files into an archive
+ */
+export type FileCompressInput = { 
+/**
+ * Files or folders to add to the archive
+ */
+sources: SdPathBatch; 
+/**
+ * Path of the archive to create. The format's extension is added if it's missing.
+ */
+destination: SdPath; 
+/**
+ * Format of the archive
+ */
+format: CompressFormat; 
+/**
+ * How to handle an existing file at the destination (set by confirmation)
+ */
+on_conflict: FileConflictResolution | null };
+
 /**
  * Internal enum for file conflict resolution strategies
  */
@@ -1289,6 +1474,23 @@ permanent: boolean;
  */
 recursive: boolean };
 
+/**
+ * Input for extracting archives
+ */
+export type FileExtractInput = { 
+/**
+ * Archives to extract
+ */
+sources: SdPathBatch; 
+/**
+ * Folder to extract into. Each archive gets its own folder inside it.
+ */
+destination: SdPath; 
+/**
+ * How to handle an existing folder with an archive's name (set by confirmation)
+ */
+on_conflict: FileConflictResolution | null };
+
 /**
  * Types of file operations
  */
@@ -1415,6 +1617,19 @@ export type FileSystem =
  */
 { Other: string };
 
+/**
+ * Peer file transfer configuration
+ */
+export type FileTransferConfig = { 
+/**
+ * Compress chunks with zstd when the other device supports it
+ */
+compression?: boolean; 
+/**
+ * Limits on the bandwidth used by outgoing transfers
+ */
+bandwidth?: BandwidthConfig };
+
 /**
  * Indicates which filters are available for a given search type
  */
@@ -2022,6 +2237,10 @@ export type ItemType =
  * Tag filter
  */
 { Tag: { tag_id: string } } | 
+/**
+ * Saved search (smart collection)
+ */
+{ SavedSearch: { saved_search_id: string } } | 
 /**
  * Any arbitrary path (dragged from explorer)
  */
@@ -2095,6 +2314,7 @@ export type JobOutput =
  * OCR text extraction output
  */
 { type: "OcrExtraction"; data: { total_processed: number; success_count: number; error_count: number } } | 
+{ type: "TextExtraction"; data: { total_processed: number; success_count: number; error_count: number } } | 
 /**
  * Speech-to-text transcription output
  */
@@ -2102,7 +2322,23 @@ export type JobOutput =
 /**
  * Gaussian splat generation output
  */
-{ type: "GaussianSplat"; data: { total_processed: number; success_count: number; error_count: number } };
+{ type: "GaussianSplat"; data: { total_processed: number; success_count: number; error_count: number } } | 
+/**
+ * Auto-tag rule application output
+ */
+{ type: "AutoTag"; data: { entries_checked: number; tags_applied: number } } | 
+/**
+ * Semantic search embedding output
+ */
+{ type: "Embedding"; data: { total_processed: number; embedded_count: number; error_count: number } } | 
+/**
+ * Archive creation output
+ */
+{ type: "FileCompress"; data: { source_count: number; total_bytes: number; archive_size: number } } | 
+/**
+ * Archive extraction output
+ */
+{ type: "FileExtract"; data: { extracted_count: number; skipped_count: number; failed_count: number; total_bytes: number } };
 
 export type JobPauseInput = { job_id: string };
 
@@ -2131,10 +2367,18 @@ proxy?: ProxyPolicy;
  * OCR (text extraction) policy
  */
 ocr?: OcrPolicy; 
+/**
+ * Document text extraction policy (full-text content search)
+ */
+text_extraction?: TextExtractionPolicy; 
 /**
  * Speech-to-text transcription policy
  */
 speech_to_text?: SpeechPolicy; 
+/**
+ * Text embedding policy (semantic search)
+ */
+embedding?: EmbeddingPolicy; 
 /**
  * Object detection policy (future)
  */
@@ -2199,7 +2443,7 @@ export type JobStatus =
 /**
  * Type of job to trigger for a location
  */
-export type JobType = "thumbnail" | "thumbstrip" | "ocr" | "speech_to_text" | "object_detection";
+export type JobType = "thumbnail" | "thumbstrip" | "ocr" | "text_extraction" | "speech_to_text" | "embedding" | "object_detection";
 
 export type JsonValue = null | boolean | number | string | JsonValue[] | { [key in string]: JsonValue };
 
@@ -2969,7 +3213,11 @@ export type ModelType =
 /**
  * Tesseract OCR language data
  */
-"Tesseract";
+"Tesseract" | 
+/**
+ * Text embedding model for semantic search
+ */
+"Embedding";
 
 /**
  * Mount type classification
@@ -3408,6 +3656,7 @@ export type ScoreBreakdown = { temporal_score: number; semantic_score: number |
  * - A physical file at a specific path on a specific device
  * - A content-addressed file that can be sourced from any device
  * - A sidecar (derivative data) attached to content
+ * - A file or folder inside an archive (zip, tar, 7z)
  * 
  * This enum-based approach enables resilient file operations by allowing
  * content-based paths to be resolved to optimal physical locations at runtime.
@@ -3469,7 +3718,23 @@ variant: SidecarVariant;
 /**
  * The storage format (webp, json, msgpack, etc.)
  */
-format: SidecarFormat } };
+format: SidecarFormat } } | 
+/**
+ * A file or folder inside an archive, browsed in place without extracting it
+ */
+{ Archive: { 
+/**
+ * The device slug of the device holding the archive
+ */
+device_slug: string; 
+/**
+ * The path of the archive file on that device
+ */
+archive_path: string; 
+/**
+ * The '/'-separated path inside the archive, empty for its root
+ */
+inner_path: string } };
 
 /**
  * A batch of SdPaths, useful for operations on multiple files
@@ -3499,7 +3764,7 @@ export type SearchMode =
  */
 "Normal" | 
 /**
- * Full search with content analysis (<500ms)
+ * Full search that also matches extracted file contents (<500ms)
  */
 "Full";
 
@@ -3607,7 +3872,7 @@ export type Sidecar = { id: number; content_uuid: string; kind: string; variant:
  */
 export type SidecarFormat = "webp" | "mp_4" | "json" | "message_pack" | "text" | "ply";
 
-export type SidecarKind = "thumb" | "thumbstrip" | "proxy" | "embeddings" | "ocr" | "transcript" | "gaussian_splat";
+export type SidecarKind = "thumb" | "thumbstrip" | "proxy" | "embeddings" | "ocr" | "transcript" | "gaussian_splat" | "perceptual_hash" | "metadata";
 
 export type SidecarVariant = string;
 
@@ -3883,7 +4148,11 @@ export type SyncEventType =
 /**
  * Sync error occurred
  */
-"sync_error";
+"sync_error" | 
+/**
+ * Concurrent edits to the same field of a shared record, resolved by HLC
+ */
+"field_conflict";
 
 /**
  * Point-in-time snapshot of all sync metrics
@@ -4014,7 +4283,11 @@ export type TagSource =
 /**
  * Synchronized from another device
  */
-"Sync";
+"Sync" | 
+/**
+ * Applied by an auto-tag rule (the rule's UUID is the applied context)
+ */
+"Rule";
 
 /**
  * Specifies what to tag: content (all instances) or specific entries
@@ -4055,6 +4328,26 @@ export type TagType =
 /**
  * Text highlighting information
  */
+/**
+ * Document text extraction policy
+ * 
+ * Indexes the text of plain text, source code, PDF, DOCX/ODT and EPUB files
+ * so full searches can match on file contents.
+ */
+export type TextExtractionPolicy = { 
+/**
+ * Whether to extract document text on this location
+ */
+enabled: boolean; 
+/**
+ * Skip structured documents larger than this (MB); plain text is truncated instead
+ */
+max_file_size_mb: number; 
+/**
+ * Whether to re-extract text that is already indexed
+ */
+reprocess: boolean };
+
 export type TextHighlight = { field: string; text: string; start: number; end: number };
 
 export type ThumbnailInput = { paths: string[]; size: number; quality: number };
@@ -4244,7 +4537,11 @@ proxy_pairing_vouch_response_timeout?: number | null;
 /**
  * Maximum retries for queued vouches
  */
-proxy_pairing_vouch_queue_retry_limit?: number | null };
+proxy_pairing_vouch_queue_retry_limit?: number | null; 
+/**
+ * Peer file transfer compression and bandwidth limits (replaces the current settings)
+ */
+file_transfer?: FileTransferConfig | null };
 
 /**
  * Output for update app configuration action
@@ -4741,6 +5038,7 @@ export type CoreAction =
   |  { type: 'core.ephemeral_reset'; input: EphemeralCacheResetInput; output: EphemeralCacheResetOutput }
   |  { type: 'core.reset'; input: ResetDataInput; output: ResetDataOutput }
   |  { type: 'device.update'; input: UpdateDeviceInput; output: UpdateDeviceOutput }
+  |  { type: 'extensions.resume'; input: ExtensionResumeInput; output: ExtensionResumeOutput }
   |  { type: 'libraries.create'; input: LibraryCreateInput; output: LibraryCreateOutput }
   |  { type: 'libraries.delete'; input: LibraryDeleteInput; output: LibraryDeleteOutput }
   |  { type: 'libraries.open'; input: LibraryOpenInput; output: LibraryOpenOutput }
@@ -4760,9 +5058,12 @@ export type CoreAction =
 
 export type LibraryAction =
      { type: 'config.library.update'; input: UpdateLibraryConfigInput; output: UpdateLibraryConfigOutput }
+  |  { type: 'files.archive.thumbnail'; input: ArchiveThumbnailInput; output: ArchiveThumbnailOutput }
+  |  { type: 'files.compress'; input: FileCompressInput; output: JobReceipt }
   |  { type: 'files.copy'; input: FileCopyInput; output: JobReceipt }
   |  { type: 'files.createFolder'; input: CreateFolderInput; output: CreateFolderOutput }
   |  { type: 'files.delete'; input: FileDeleteInput; output: JobReceipt }
+  |  { type: 'files.extract'; input: FileExtractInput; output: JobReceipt }
   |  { type: 'files.rename'; input: FileRenameInput; output: JobReceipt }
   |  { type: 'indexing.start'; input: IndexInput; output: JobReceipt }
   |  { type: 'indexing.verify'; input: IndexVerifyInput; output: IndexVerifyOutput }
@@ -4813,6 +5114,7 @@ export type CoreQuery =
   |  { type: 'core.ephemeral_status'; input: EphemeralCacheStatusInput; output: EphemeralCacheStatus }
   |  { type: 'core.events.list'; input: ListEventsInput; output: ListEventsOutput }
   |  { type: 'core.status'; input: Empty; output: CoreStatus }
+  |  { type: 'extensions.usage'; input: ExtensionUsageInput; output: ExtensionUsageOutput }
   |  { type: 'jobs.remote.all_devices'; input: RemoteJobsAllDevicesInput; output: RemoteJobsAllDevicesOutput }
   |  { type: 'jobs.remote.for_device'; input: RemoteJobsForDeviceInput; output: RemoteJobsForDeviceOutput }
   |  { type: 'libraries.list'; input: ListLibrariesInput; output: [LibraryInfo] }
@@ -4863,6 +5165,7 @@ export const WIRE_METHODS = {
     'core.ephemeral_reset': 'action:core.ephemeral_reset.input',
     'core.reset': 'action:core.reset.input',
     'device.update': 'action:device.update.input',
+    'extensions.resume': 'action:extensions.resume.input',
     'libraries.create': 'action:libraries.create.input',
     'libraries.delete': 'action:libraries.delete.input',
     'libraries.open': 'action:libraries.open.input',
@@ -4882,9 +5185,12 @@ export const WIRE_METHODS = {
 
   libraryActions: {
     'config.library.update': 'action:config.library.update.input',
+    'files.archive.thumbnail': 'action:files.archive.thumbnail.input',
+    'files.compress': 'action:files.compress.input',
     'files.copy': 'action:files.copy.input',
     'files.createFolder': 'action:files.createFolder.input',
     'files.delete': 'action:files.delete.input',
+    'files.extract': 'action:files.extract.input',
     'files.rename': 'action:files.rename.input',
     'indexing.start': 'action:indexing.start.input',
     'indexing.verify': 'action:indexing.verify.input',
@@ -4935,6 +5241,7 @@ export const WIRE_METHODS = {
     'core.ephemeral_status': 'query:core.ephemeral_status',
     'core.events.list': 'query:core.events.list',
     'core.status': 'query:core.status',
+    'extensions.usage': 'query:extensions.usage',
     'jobs.remote.all_devices': 'query:jobs.remote.all_devices',
     'jobs.remote.for_device': 'query:jobs.remote.for_device',
     'libraries.list': 'query:libraries.list',